@@ -208,7 +208,10 @@ mod tests {
                     content: vec![ContentBlock::Text("ok".to_string())],
                 },
                 stop_reason: CoreStopReason::EndTurn,
+                stop_sequence: None,
                 usage: None,
+                service_tier: None,
+                model: None,
             })
         }
     }
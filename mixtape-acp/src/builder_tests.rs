@@ -37,7 +37,10 @@ impl ModelProvider for MockProvider {
                 content: vec![ContentBlock::Text("ok".to_string())],
             },
             stop_reason: StopReason::EndTurn,
+            stop_sequence: None,
             usage: None,
+            service_tier: None,
+            model: None,
         })
     }
 }
@@ -53,12 +53,20 @@ pub(crate) fn agent_event_to_session_update(event: &AgentEvent) -> Option<Sessio
             Some(tool_call_update(tool_use_id, fields))
         }
 
+        AgentEvent::ToolSkippedDryRun { tool_use_id, .. } => {
+            let fields = ToolCallUpdateFields::new()
+                .status(ToolCallStatus::Completed)
+                .raw_output(Value::String("Skipped (dry run)".to_string()));
+            Some(tool_call_update(tool_use_id, fields))
+        }
+
         // Lifecycle events don't map to session updates
         AgentEvent::RunStarted { .. }
         | AgentEvent::RunCompleted { .. }
         | AgentEvent::RunFailed { .. }
         | AgentEvent::ModelCallStarted { .. }
         | AgentEvent::ModelCallCompleted { .. }
+        | AgentEvent::TurnCompleted { .. }
         | AgentEvent::PermissionRequired { .. }
         | AgentEvent::PermissionGranted { .. }
         | AgentEvent::PermissionDenied { .. } => None,
@@ -86,7 +94,9 @@ pub(crate) fn agent_error_to_stop_reason(
         mixtape_core::AgentError::MaxTokensExceeded => {
             Ok(agent_client_protocol::StopReason::MaxTokens)
         }
-        mixtape_core::AgentError::ContentFiltered => Ok(agent_client_protocol::StopReason::Refusal),
+        mixtape_core::AgentError::ContentFiltered | mixtape_core::AgentError::Refused(_) => {
+            Ok(agent_client_protocol::StopReason::Refusal)
+        }
         other => Err(agent_client_protocol::Error::internal_error().data(other.to_string())),
     }
 }
@@ -45,6 +45,7 @@ fn test_tool_completed_converts_to_tool_call_update_completed() {
         tool_use_id: "tool-123".to_string(),
         name: "read_file".to_string(),
         output: ToolResult::text("file contents here"),
+        model_output: None,
         duration: Duration::from_millis(100),
     };
     let update = agent_event_to_session_update(&event);
@@ -65,14 +66,27 @@ fn test_tool_failed_converts_to_tool_call_update_failed() {
     assert!(matches!(update.unwrap(), SessionUpdate::ToolCallUpdate(_)));
 }
 
+#[test]
+fn test_tool_skipped_dry_run_converts_to_tool_call_update_completed() {
+    let event = AgentEvent::ToolSkippedDryRun {
+        tool_use_id: "tool-123".to_string(),
+        name: "write_file".to_string(),
+        input: serde_json::json!({"path": "/tmp/out"}),
+    };
+    let update = agent_event_to_session_update(&event);
+    assert!(matches!(update, Some(SessionUpdate::ToolCallUpdate(_))));
+}
+
 #[test]
 fn test_lifecycle_events_return_none() {
     let lifecycle_events = vec![
         AgentEvent::RunStarted {
+            agent_name: None,
             input: "hello".to_string(),
             timestamp: Instant::now(),
         },
         AgentEvent::RunCompleted {
+            agent_name: None,
             output: "world".to_string(),
             duration: Duration::from_secs(1),
         },
@@ -228,7 +242,10 @@ fn tool_failed_embeds_error_message_in_output() {
 fn agent_error_to_stop_reason_embeds_error_detail_on_failure() {
     // When the error maps to Err, the protocol error's data should include the
     // agent error's display text so operators can diagnose failures.
-    let err = mixtape_core::AgentError::ToolNotFound("calculator".to_string());
+    let err = mixtape_core::AgentError::ToolNotFound {
+        name: "calculator".to_string(),
+        available: Vec::new(),
+    };
     let proto_err = agent_error_to_stop_reason(&err).unwrap_err();
     let proto_str = proto_err.to_string();
     assert!(
@@ -250,6 +267,10 @@ fn all_mappable_agent_errors_covered() {
             mixtape_core::AgentError::ContentFiltered,
             agent_client_protocol::StopReason::Refusal,
         ),
+        (
+            mixtape_core::AgentError::Refused("I can't help with that.".to_string()),
+            agent_client_protocol::StopReason::Refusal,
+        ),
     ];
 
     for (err, expected_stop_reason) in cases {
@@ -299,6 +320,7 @@ fn tool_completed_json_result_serializes_to_json_string() {
         tool_use_id: "json-tool-1".to_string(),
         name: "calculate".to_string(),
         output: ToolResult::Json(serde_json::json!({"answer": 42})),
+        model_output: None,
         duration: Duration::from_millis(5),
     };
     let update = agent_event_to_session_update(&event).expect("should produce Some");
@@ -328,6 +350,7 @@ fn tool_completed_preserves_tool_use_id() {
         tool_use_id: "completed-id-7".to_string(),
         name: "read_file".to_string(),
         output: ToolResult::text("contents"),
+        model_output: None,
         duration: Duration::from_millis(1),
     };
     let update = agent_event_to_session_update(&event).expect("should produce Some");
@@ -357,7 +380,10 @@ fn non_mappable_agent_errors_all_produce_protocol_error() {
         AgentError::NoResponse,
         AgentError::EmptyResponse,
         AgentError::ToolDenied("denied".to_string()),
-        AgentError::ToolNotFound("missing".to_string()),
+        AgentError::ToolNotFound {
+            name: "missing".to_string(),
+            available: Vec::new(),
+        },
         AgentError::InvalidToolInput("bad input".to_string()),
         AgentError::PermissionFailed("no perm".to_string()),
         AgentError::UnexpectedStopReason("weird".to_string()),
@@ -2,12 +2,12 @@
 
 use crate::batch::{BatchCreateParams, BatchListResponse, BatchResult, MessageBatch};
 use crate::error::{AnthropicError, ApiErrorResponse, RetryConfig};
+use crate::jsonl::jsonl_stream;
 use crate::messages::{Message, MessageCreateParams};
 use crate::streaming::MessageStream;
 use crate::tokens::{CountTokensParams, CountTokensResponse};
 use futures::stream::BoxStream;
-use futures::StreamExt;
-use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE, USER_AGENT};
 use std::collections::HashMap;
 use std::time::Duration;
 
@@ -168,7 +168,17 @@ pub struct Anthropic {
     api_key: String,
     api_base: String,
     api_version: String,
+    user_agent: Option<String>,
     retry_config: RetryConfig,
+    /// Per-request timeout, set only when the HTTP client was supplied by
+    /// the caller and so could not be built with the configured timeout
+    request_timeout: Option<Duration>,
+    /// Per-request timeout for non-streaming message calls
+    message_timeout: Option<Duration>,
+    /// Per-request timeout for streaming message calls
+    stream_timeout: Option<Duration>,
+    /// Per-request timeout for token counting calls
+    count_tokens_timeout: Option<Duration>,
 }
 
 impl std::fmt::Debug for Anthropic {
@@ -177,7 +187,12 @@ impl std::fmt::Debug for Anthropic {
             .field("api_base", &self.api_base)
             .field("api_version", &self.api_version)
             .field("api_key", &"[REDACTED]")
+            .field("user_agent", &self.user_agent)
             .field("retry_config", &self.retry_config)
+            .field("request_timeout", &self.request_timeout)
+            .field("message_timeout", &self.message_timeout)
+            .field("stream_timeout", &self.stream_timeout)
+            .field("count_tokens_timeout", &self.count_tokens_timeout)
             .finish()
     }
 }
@@ -203,6 +218,24 @@ impl Anthropic {
         AnthropicBuilder::new()
     }
 
+    /// Return a copy of this client that sends the given `User-Agent` header
+    ///
+    /// Equivalent to [`AnthropicBuilder::user_agent`], for clients that have
+    /// already been built.
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Return a copy of this client that retries according to `config`
+    ///
+    /// Equivalent to [`AnthropicBuilder::retry_config`], for clients that have
+    /// already been built.
+    pub fn with_retry_config(mut self, config: RetryConfig) -> Self {
+        self.retry_config = config;
+        self
+    }
+
     /// Get a handle to the messages API
     pub fn messages(&self) -> Messages<'_> {
         Messages { client: self }
@@ -213,18 +246,28 @@ impl Anthropic {
         Batches { client: self }
     }
 
+    /// Timeout for one request, preferring the operation's own timeout
+    ///
+    /// `None` leaves the HTTP client's timeout in effect.
+    fn timeout_for(&self, operation_timeout: Option<Duration>) -> Option<Duration> {
+        operation_timeout.or(self.request_timeout)
+    }
+
     /// Execute a request with automatic retry
     ///
     /// This is a shared helper that handles:
     /// - Exponential backoff with jitter
     /// - Retry-After header parsing
     /// - Retryable error detection (429, 5xx, network errors)
+    ///
+    /// `timeout` applies to each attempt separately.
     async fn execute_with_retry<T, B>(
         &self,
         url: &str,
         body: Option<&B>,
         method: reqwest::Method,
         headers: HeaderMap,
+        timeout: Option<Duration>,
     ) -> Result<Response<T>, AnthropicError>
     where
         T: serde::de::DeserializeOwned,
@@ -238,6 +281,10 @@ impl Anthropic {
                 .request(method.clone(), url)
                 .headers(headers.clone());
 
+            if let Some(timeout) = timeout {
+                request = request.timeout(timeout);
+            }
+
             if let Some(b) = body {
                 request = request.json(b);
             }
@@ -303,8 +350,13 @@ pub struct AnthropicBuilder {
     api_key: Option<String>,
     api_base: Option<String>,
     api_version: Option<String>,
+    user_agent: Option<String>,
     timeout: Option<Duration>,
     retry_config: Option<RetryConfig>,
+    http_client: Option<reqwest::Client>,
+    message_timeout: Option<Duration>,
+    stream_timeout: Option<Duration>,
+    count_tokens_timeout: Option<Duration>,
 }
 
 impl AnthropicBuilder {
@@ -314,8 +366,13 @@ impl AnthropicBuilder {
             api_key: None,
             api_base: None,
             api_version: None,
+            user_agent: None,
             timeout: None,
             retry_config: None,
+            http_client: None,
+            message_timeout: None,
+            stream_timeout: None,
+            count_tokens_timeout: None,
         }
     }
 
@@ -337,12 +394,65 @@ impl AnthropicBuilder {
         self
     }
 
+    /// Set the `User-Agent` header sent with every request
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
     /// Set the request timeout
+    ///
+    /// Applies to every request without an operation-specific timeout
+    /// below. Defaults to 600 seconds.
     pub fn timeout(mut self, timeout: Duration) -> Self {
         self.timeout = Some(timeout);
         self
     }
 
+    /// Set the timeout for non-streaming message calls
+    ///
+    /// Overrides [`Self::timeout`] for [`Messages::create`] and
+    /// [`Messages::create_with_metadata`]. Each retry attempt gets the full
+    /// timeout.
+    pub fn message_timeout(mut self, timeout: Duration) -> Self {
+        self.message_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the timeout for streaming message calls
+    ///
+    /// Overrides [`Self::timeout`] for [`Messages::stream`]. The timeout
+    /// covers the whole stream, not just the time to the first event.
+    pub fn stream_timeout(mut self, timeout: Duration) -> Self {
+        self.stream_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the timeout for token counting calls
+    ///
+    /// Overrides [`Self::timeout`] for [`Messages::count_tokens`] and
+    /// [`Messages::count_tokens_with_metadata`], which are normally fast
+    /// and shouldn't wait as long as a message call.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use mixtape_anthropic_sdk::Anthropic;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = Anthropic::builder()
+    ///     .api_key("your-api-key")
+    ///     .count_tokens_timeout(Duration::from_secs(10))
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn count_tokens_timeout(mut self, timeout: Duration) -> Self {
+        self.count_tokens_timeout = Some(timeout);
+        self
+    }
+
     /// Set the maximum number of retries (default: 2)
     ///
     /// Set to 0 to disable retries.
@@ -359,20 +469,56 @@ impl AnthropicBuilder {
         self
     }
 
+    /// Use a pre-built HTTP client
+    ///
+    /// Use this to configure proxies, custom root certificates, or
+    /// connection pooling. Retry settings still apply. A timeout set with
+    /// [`Self::timeout`] is applied to each request; otherwise the client's
+    /// own timeout (if any) is used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use mixtape_anthropic_sdk::Anthropic;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let http_client = reqwest::Client::builder()
+    ///     .proxy(reqwest::Proxy::https("http://proxy.internal:3128")?)
+    ///     .build()?;
+    ///
+    /// let client = Anthropic::builder()
+    ///     .api_key("your-api-key")
+    ///     .http_client(http_client)
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
     /// Build the client
     pub fn build(self) -> Result<Anthropic, AnthropicError> {
         let api_key = self
             .api_key
             .ok_or_else(|| AnthropicError::Configuration("API key is required".to_string()))?;
 
-        let timeout = self.timeout.unwrap_or(DEFAULT_TIMEOUT);
-
-        let client = reqwest::Client::builder()
-            .timeout(timeout)
-            .build()
-            .map_err(|e| {
-                AnthropicError::Configuration(format!("Failed to create HTTP client: {}", e))
-            })?;
+        let (client, request_timeout) = match self.http_client {
+            Some(client) => (client, self.timeout),
+            None => {
+                let client = reqwest::Client::builder()
+                    .timeout(self.timeout.unwrap_or(DEFAULT_TIMEOUT))
+                    .build()
+                    .map_err(|e| {
+                        AnthropicError::Configuration(format!(
+                            "Failed to create HTTP client: {}",
+                            e
+                        ))
+                    })?;
+                (client, None)
+            }
+        };
 
         Ok(Anthropic {
             client,
@@ -383,7 +529,12 @@ impl AnthropicBuilder {
             api_version: self
                 .api_version
                 .unwrap_or_else(|| DEFAULT_API_VERSION.to_string()),
+            user_agent: self.user_agent,
             retry_config: self.retry_config.unwrap_or_default(),
+            request_timeout,
+            message_timeout: self.message_timeout,
+            stream_timeout: self.stream_timeout,
+            count_tokens_timeout: self.count_tokens_timeout,
         })
     }
 }
@@ -437,9 +588,10 @@ impl<'a> Messages<'a> {
             .as_ref()
             .map(|b| b.iter().map(|f| f.to_string()).collect());
         let headers = self.build_headers(beta_strings.as_deref())?;
+        let timeout = self.client.timeout_for(self.client.message_timeout);
 
         self.client
-            .execute_with_retry(&url, Some(&params), reqwest::Method::POST, headers)
+            .execute_with_retry(&url, Some(&params), reqwest::Method::POST, headers, timeout)
             .await
     }
 
@@ -472,7 +624,14 @@ impl<'a> Messages<'a> {
             .map(|b| b.iter().map(|f| f.to_string()).collect());
         let headers = self.build_headers(beta_strings.as_deref())?;
 
-        MessageStream::new(&self.client.client, &url, headers, params).await
+        MessageStream::new(
+            &self.client.client,
+            &url,
+            headers,
+            self.client.timeout_for(self.client.stream_timeout),
+            params,
+        )
+        .await
     }
 
     /// Count tokens for a message
@@ -498,14 +657,15 @@ impl<'a> Messages<'a> {
     ) -> Result<Response<CountTokensResponse>, AnthropicError> {
         let url = format!("{}/v1/messages/count_tokens", self.client.api_base);
         let headers = self.build_headers(None)?;
+        let timeout = self.client.timeout_for(self.client.count_tokens_timeout);
 
         self.client
-            .execute_with_retry(&url, Some(&params), reqwest::Method::POST, headers)
+            .execute_with_retry(&url, Some(&params), reqwest::Method::POST, headers, timeout)
             .await
     }
 
     fn build_headers(&self, betas: Option<&[String]>) -> Result<HeaderMap, AnthropicError> {
-        build_headers(&self.client.api_key, &self.client.api_version, betas)
+        build_headers(self.client, betas)
     }
 }
 
@@ -554,7 +714,13 @@ impl<'a> Batches<'a> {
         let url = format!("{}/v1/messages/batches", self.client.api_base);
         let headers = self.build_headers()?;
         self.client
-            .execute_with_retry(&url, Some(&params), reqwest::Method::POST, headers)
+            .execute_with_retry(
+                &url,
+                Some(&params),
+                reqwest::Method::POST,
+                headers,
+                self.client.request_timeout,
+            )
             .await
     }
 
@@ -575,7 +741,13 @@ impl<'a> Batches<'a> {
         let url = format!("{}/v1/messages/batches/{}", self.client.api_base, batch_id);
         let headers = self.build_headers()?;
         self.client
-            .execute_with_retry::<MessageBatch, ()>(&url, None, reqwest::Method::GET, headers)
+            .execute_with_retry::<MessageBatch, ()>(
+                &url,
+                None,
+                reqwest::Method::GET,
+                headers,
+                self.client.request_timeout,
+            )
             .await
     }
 
@@ -618,7 +790,13 @@ impl<'a> Batches<'a> {
 
         let headers = self.build_headers()?;
         self.client
-            .execute_with_retry::<BatchListResponse, ()>(&url, None, reqwest::Method::GET, headers)
+            .execute_with_retry::<BatchListResponse, ()>(
+                &url,
+                None,
+                reqwest::Method::GET,
+                headers,
+                self.client.request_timeout,
+            )
             .await
     }
 
@@ -645,7 +823,13 @@ impl<'a> Batches<'a> {
         );
         let headers = self.build_headers()?;
         self.client
-            .execute_with_retry::<MessageBatch, ()>(&url, None, reqwest::Method::POST, headers)
+            .execute_with_retry::<MessageBatch, ()>(
+                &url,
+                None,
+                reqwest::Method::POST,
+                headers,
+                self.client.request_timeout,
+            )
             .await
     }
 
@@ -680,68 +864,11 @@ impl<'a> Batches<'a> {
         }
 
         // Stream JSONL response
-        let byte_stream = response.bytes_stream();
-
-        let result_stream = async_stream::stream! {
-            let mut buffer = String::new();
-            let mut byte_stream = byte_stream;
-
-            while let Some(chunk_result) = byte_stream.next().await {
-                match chunk_result {
-                    Ok(chunk) => {
-                        // Append chunk to buffer
-                        match std::str::from_utf8(&chunk) {
-                            Ok(s) => buffer.push_str(s),
-                            Err(e) => {
-                                yield Err(AnthropicError::Other(format!("Invalid UTF-8: {}", e)));
-                                return;
-                            }
-                        }
-
-                        // Process complete lines
-                        while let Some(newline_pos) = buffer.find('\n') {
-                            let line = buffer[..newline_pos].trim();
-                            if !line.is_empty() {
-                                match serde_json::from_str::<BatchResult>(line) {
-                                    Ok(result) => yield Ok(result),
-                                    Err(e) => {
-                                        yield Err(AnthropicError::Other(format!(
-                                            "Failed to parse batch result: {} (line: {})",
-                                            e, line
-                                        )));
-                                    }
-                                }
-                            }
-                            buffer = buffer[newline_pos + 1..].to_string();
-                        }
-                    }
-                    Err(e) => {
-                        yield Err(AnthropicError::Network(format!("Stream error: {}", e)));
-                        return;
-                    }
-                }
-            }
-
-            // Process any remaining content in buffer
-            let remaining = buffer.trim();
-            if !remaining.is_empty() {
-                match serde_json::from_str::<BatchResult>(remaining) {
-                    Ok(result) => yield Ok(result),
-                    Err(e) => {
-                        yield Err(AnthropicError::Other(format!(
-                            "Failed to parse final batch result: {} (line: {})",
-                            e, remaining
-                        )));
-                    }
-                }
-            }
-        };
-
-        Ok(Box::pin(result_stream))
+        Ok(Box::pin(jsonl_stream(response.bytes_stream())))
     }
 
     fn build_headers(&self) -> Result<HeaderMap, AnthropicError> {
-        build_headers(&self.client.api_key, &self.client.api_version, None)
+        build_headers(self.client, None)
     }
 }
 
@@ -750,24 +877,31 @@ impl<'a> Batches<'a> {
 // ============================================================================
 
 fn build_headers(
-    api_key: &str,
-    api_version: &str,
+    client: &Anthropic,
     betas: Option<&[String]>,
 ) -> Result<HeaderMap, AnthropicError> {
     let mut headers = HeaderMap::new();
 
     headers.insert(
         "x-api-key",
-        HeaderValue::from_str(api_key)
+        HeaderValue::from_str(&client.api_key)
             .map_err(|e| AnthropicError::Configuration(format!("Invalid API key: {}", e)))?,
     );
 
     headers.insert(
         "anthropic-version",
-        HeaderValue::from_str(api_version)
+        HeaderValue::from_str(&client.api_version)
             .map_err(|e| AnthropicError::Configuration(format!("Invalid API version: {}", e)))?,
     );
 
+    if let Some(user_agent) = &client.user_agent {
+        headers.insert(
+            USER_AGENT,
+            HeaderValue::from_str(user_agent)
+                .map_err(|e| AnthropicError::Configuration(format!("Invalid user agent: {}", e)))?,
+        );
+    }
+
     headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
 
     // Add beta features header if any betas are specified
@@ -852,6 +986,31 @@ mod tests {
         assert_eq!(client.api_base, "https://custom.api.com");
     }
 
+    #[test]
+    fn test_builder_user_agent() {
+        let client = Anthropic::builder()
+            .api_key("test-key")
+            .user_agent("my-app/1.0")
+            .build()
+            .unwrap();
+        assert_eq!(client.user_agent.as_deref(), Some("my-app/1.0"));
+
+        let headers = build_headers(&client, None).unwrap();
+        assert_eq!(headers[USER_AGENT], "my-app/1.0");
+    }
+
+    #[test]
+    fn test_with_user_agent_overrides_built_client() {
+        let client = Anthropic::new("test-key").unwrap();
+        assert!(!build_headers(&client, None)
+            .unwrap()
+            .contains_key(USER_AGENT));
+
+        let client = client.with_user_agent("other-app/2.0");
+        let headers = build_headers(&client, None).unwrap();
+        assert_eq!(headers[USER_AGENT], "other-app/2.0");
+    }
+
     #[test]
     fn test_builder_with_max_retries() {
         let client = Anthropic::builder()
@@ -1188,6 +1347,48 @@ mod tests {
         assert!(client.is_ok());
     }
 
+    #[test]
+    fn test_builder_http_client() {
+        let http_client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()
+            .unwrap();
+
+        let client = Anthropic::builder()
+            .api_key("test-key")
+            .http_client(http_client)
+            .build()
+            .unwrap();
+        assert_eq!(client.request_timeout, None);
+
+        let client = Anthropic::builder()
+            .api_key("test-key")
+            .http_client(reqwest::Client::new())
+            .timeout(Duration::from_secs(30))
+            .build()
+            .unwrap();
+        assert_eq!(client.request_timeout, Some(Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn test_timeout_for_prefers_operation_timeout() {
+        let client = Anthropic::builder()
+            .api_key("test-key")
+            .http_client(reqwest::Client::new())
+            .timeout(Duration::from_secs(30))
+            .count_tokens_timeout(Duration::from_secs(5))
+            .build()
+            .unwrap();
+        assert_eq!(
+            client.timeout_for(client.count_tokens_timeout),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(
+            client.timeout_for(client.message_timeout),
+            Some(Duration::from_secs(30))
+        );
+    }
+
     #[test]
     fn test_client_new() {
         let client = Anthropic::new("test-key");
@@ -1265,6 +1466,106 @@ mod wiremock_tests {
         })
     }
 
+    #[tokio::test]
+    async fn test_injected_http_client_is_used() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(message_response_json())
+                    .set_delay(Duration::from_secs(2)),
+            )
+            .mount(&mock_server)
+            .await;
+
+        // The injected client's short timeout applies, not the SDK default
+        let http_client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(50))
+            .build()
+            .unwrap();
+        let client = Anthropic::builder()
+            .api_key("test-key")
+            .api_base(mock_server.uri())
+            .http_client(http_client)
+            .max_retries(0)
+            .build()
+            .unwrap();
+
+        let result = client
+            .messages()
+            .create(
+                MessageCreateParams::builder("claude-sonnet-4-20250514", 1024)
+                    .user("Hi")
+                    .build(),
+            )
+            .await;
+
+        match result {
+            Err(AnthropicError::Network(msg)) => assert!(msg.contains("timed out"), "{}", msg),
+            other => panic!("Expected timeout, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_per_operation_timeouts() {
+        let mock_server = MockServer::start().await;
+
+        // Both endpoints answer after the same delay
+        Mock::given(method("POST"))
+            .and(path("/v1/messages/count_tokens"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"input_tokens": 42}))
+                    .set_delay(Duration::from_millis(300)),
+            )
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(message_response_json())
+                    .set_delay(Duration::from_millis(300)),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let client = Anthropic::builder()
+            .api_key("test-key")
+            .api_base(mock_server.uri())
+            .count_tokens_timeout(Duration::from_millis(50))
+            .message_timeout(Duration::from_secs(10))
+            .max_retries(0)
+            .build()
+            .unwrap();
+
+        let result = client
+            .messages()
+            .count_tokens(
+                CountTokensParams::builder("claude-sonnet-4-20250514")
+                    .user("Hi")
+                    .build(),
+            )
+            .await;
+        match result {
+            Err(AnthropicError::Network(msg)) => assert!(msg.contains("timed out"), "{}", msg),
+            other => panic!("Expected timeout, got {:?}", other),
+        }
+
+        let message = client
+            .messages()
+            .create(
+                MessageCreateParams::builder("claude-sonnet-4-20250514", 1024)
+                    .user("Hi")
+                    .build(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(message.id, "msg_test123");
+    }
+
     #[tokio::test]
     async fn test_successful_message_create() {
         let mock_server = MockServer::start().await;
@@ -2007,4 +2308,42 @@ mod wiremock_tests {
         assert!(matches!(result, Err(AnthropicError::InvalidResponse(_))));
         // Mock expectation of 1 call verifies no retries occurred
     }
+
+    #[tokio::test]
+    async fn test_stream_does_not_reconnect_after_it_ends() {
+        use futures::StreamExt;
+
+        let mock_server = MockServer::start().await;
+
+        // The body ends without message_stop, as when the connection drops
+        let body = "event: ping\ndata: {\"type\": \"ping\"}\n\n";
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = Anthropic::builder()
+            .api_key("test-key")
+            .api_base(mock_server.uri())
+            .build()
+            .unwrap();
+        let mut stream = client
+            .messages()
+            .stream(
+                MessageCreateParams::builder("claude-sonnet-4-20250514", 1024)
+                    .user("Hi")
+                    .build(),
+            )
+            .await
+            .unwrap();
+
+        while stream.next().await.is_some() {}
+
+        // Polling past the end must not send the request again
+        let next = tokio::time::timeout(Duration::from_secs(2), stream.next()).await;
+        assert!(matches!(next, Ok(None)));
+        assert_eq!(mock_server.received_requests().await.unwrap().len(), 1);
+    }
 }
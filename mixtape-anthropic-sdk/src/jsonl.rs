@@ -0,0 +1,221 @@
+//! Line-delimited JSON (JSONL) response parsing
+//!
+//! Endpoints such as batch results return one JSON object per line. The
+//! response body arrives in arbitrarily sized chunks, so a chunk may end in
+//! the middle of a line or even in the middle of a multi-byte character.
+//! [`jsonl_stream`] buffers raw bytes and only decodes complete lines.
+//!
+//! # Example
+//!
+//! ```
+//! use futures::{stream, StreamExt};
+//! use mixtape_anthropic_sdk::jsonl::jsonl_stream;
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let chunks = stream::iter(vec![
+//!     Ok::<_, std::io::Error>(b"{\"n\": 1}\n{\"n\"".to_vec()),
+//!     Ok(b": 2}".to_vec()),
+//! ]);
+//!
+//! let values: Vec<serde_json::Value> = jsonl_stream(chunks)
+//!     .map(|item| item.unwrap())
+//!     .collect()
+//!     .await;
+//! assert_eq!(values.len(), 2);
+//! # }
+//! ```
+
+use crate::error::AnthropicError;
+use futures::stream::Stream;
+use futures::StreamExt;
+use serde::de::DeserializeOwned;
+
+/// Parse a stream of byte chunks as JSONL, yielding one item per non-empty line
+///
+/// A line that is not valid UTF-8 or does not deserialize as `T` yields an
+/// error and parsing continues with the next line. A final line without a
+/// trailing newline is still parsed. An error from the underlying byte
+/// stream yields [`AnthropicError::Network`] and ends the stream.
+pub fn jsonl_stream<T, S, B, E>(byte_stream: S) -> impl Stream<Item = Result<T, AnthropicError>>
+where
+    T: DeserializeOwned,
+    S: Stream<Item = Result<B, E>> + Unpin,
+    B: AsRef<[u8]>,
+    E: std::fmt::Display,
+{
+    async_stream::stream! {
+        let mut buffer: Vec<u8> = Vec::new();
+        let mut byte_stream = byte_stream;
+
+        while let Some(chunk_result) = byte_stream.next().await {
+            match chunk_result {
+                Ok(chunk) => {
+                    buffer.extend_from_slice(chunk.as_ref());
+
+                    // Process complete lines, keeping any partial line buffered
+                    let mut start = 0;
+                    while let Some(offset) = buffer[start..].iter().position(|&b| b == b'\n') {
+                        let end = start + offset;
+                        if let Some(item) = parse_line(&buffer[start..end]) {
+                            yield item;
+                        }
+                        start = end + 1;
+                    }
+                    buffer.drain(..start);
+                }
+                Err(e) => {
+                    yield Err(AnthropicError::Network(format!("Stream error: {}", e)));
+                    return;
+                }
+            }
+        }
+
+        // Trailing content without a final newline
+        if let Some(item) = parse_line(&buffer) {
+            yield item;
+        }
+    }
+}
+
+/// Decode and deserialize one line, returning `None` for blank lines
+fn parse_line<T: DeserializeOwned>(bytes: &[u8]) -> Option<Result<T, AnthropicError>> {
+    let line = match std::str::from_utf8(bytes) {
+        Ok(line) => line.trim(),
+        Err(e) => {
+            return Some(Err(AnthropicError::Other(format!(
+                "Invalid UTF-8 in JSONL line: {}",
+                e
+            ))))
+        }
+    };
+    if line.is_empty() {
+        return None;
+    }
+    Some(serde_json::from_str(line).map_err(|e| {
+        AnthropicError::Other(format!(
+            "Failed to parse JSONL line: {} (line: {})",
+            e, line
+        ))
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::stream;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Item {
+        text: String,
+    }
+
+    fn item(text: &str) -> Item {
+        Item {
+            text: text.to_string(),
+        }
+    }
+
+    async fn parse(chunks: Vec<&[u8]>) -> Vec<Result<Item, AnthropicError>> {
+        let chunks = chunks
+            .into_iter()
+            .map(|c| Ok::<_, std::io::Error>(c.to_vec()))
+            .collect::<Vec<_>>();
+        jsonl_stream(stream::iter(chunks)).collect().await
+    }
+
+    async fn parse_ok(chunks: Vec<&[u8]>) -> Vec<Item> {
+        parse(chunks)
+            .await
+            .into_iter()
+            .map(|r| r.expect("line should parse"))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_lines_split_across_chunks() {
+        let items = parse_ok(vec![
+            b"{\"text\": \"a\"}\n{\"te",
+            b"xt\": \"b\"",
+            b"}\n{\"text\": \"c\"}\n",
+        ])
+        .await;
+        assert_eq!(items, vec![item("a"), item("b"), item("c")]);
+    }
+
+    #[tokio::test]
+    async fn test_every_split_point() {
+        let body = "{\"text\": \"héllo\"}\n{\"text\": \"wörld\"}\n".as_bytes();
+        for split in 0..=body.len() {
+            let items = parse_ok(vec![&body[..split], &body[split..]]).await;
+            assert_eq!(
+                items,
+                vec![item("héllo"), item("wörld")],
+                "split at byte {}",
+                split
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_split_mid_multibyte_char() {
+        // "€" is three bytes; split it across three chunks
+        let euro = "€".as_bytes();
+        let items = parse_ok(vec![
+            b"{\"text\": \"",
+            &euro[..1],
+            &euro[1..2],
+            &euro[2..],
+            b"\"}\n",
+        ])
+        .await;
+        assert_eq!(items, vec![item("€")]);
+    }
+
+    #[tokio::test]
+    async fn test_trailing_line_without_newline() {
+        let items = parse_ok(vec![b"{\"text\": \"a\"}\n{\"text\": \"b\"}"]).await;
+        assert_eq!(items, vec![item("a"), item("b")]);
+    }
+
+    #[tokio::test]
+    async fn test_blank_lines_and_crlf_skipped() {
+        let items = parse_ok(vec![
+            b"\r\n{\"text\": \"a\"}\r\n\n  \n{\"text\": \"b\"}\r\n",
+        ])
+        .await;
+        assert_eq!(items, vec![item("a"), item("b")]);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_utf8_line_errors_and_continues() {
+        let results = parse(vec![b"{\"text\": \"\xff\"}\n{\"text\": \"ok\"}\n"]).await;
+        assert_eq!(results.len(), 2);
+        let err = results[0].as_ref().unwrap_err();
+        assert!(err.to_string().contains("Invalid UTF-8"));
+        assert_eq!(results[1].as_ref().unwrap(), &item("ok"));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_json_line_errors_and_continues() {
+        let results = parse(vec![b"not json\n{\"text\": \"ok\"}"]).await;
+        assert_eq!(results.len(), 2);
+        let err = results[0].as_ref().unwrap_err();
+        assert!(err.to_string().contains("line: not json"));
+        assert_eq!(results[1].as_ref().unwrap(), &item("ok"));
+    }
+
+    #[tokio::test]
+    async fn test_stream_error_ends_stream() {
+        let chunks = stream::iter(vec![
+            Ok(b"{\"text\": \"a\"}\n".to_vec()),
+            Err(std::io::Error::other("connection reset")),
+            Ok(b"{\"text\": \"b\"}\n".to_vec()),
+        ]);
+        let results: Vec<Result<Item, _>> = jsonl_stream(chunks).collect().await;
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].as_ref().unwrap(), &item("a"));
+        assert!(matches!(results[1], Err(AnthropicError::Network(_))));
+    }
+}
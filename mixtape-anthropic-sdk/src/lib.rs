@@ -163,6 +163,7 @@
 pub mod batch;
 mod client;
 mod error;
+pub mod jsonl;
 pub mod messages;
 pub mod streaming;
 pub mod tokens;
@@ -186,12 +187,13 @@ pub use streaming::{
 pub use messages::{
     BetaFeature, CacheControl, CacheTtl, CitationsConfig, ContentBlockParam, DocumentSource,
     ImageSource, MessageContent, MessageCreateParams, MessageCreateParamsBuilder, MessageParam,
-    Metadata, Role, ServiceTier, ThinkingConfig, ToolResultContent, ToolResultContentBlock,
-    WebSearchErrorCode, WebSearchResult, WebSearchToolResultContent, WebSearchToolResultError,
+    Metadata, Role, ServiceTier, SystemPrompt, ThinkingConfig, ToolResultContent,
+    ToolResultContentBlock, WebSearchErrorCode, WebSearchResult, WebSearchToolResultContent,
+    WebSearchToolResultError,
 };
 
 // Messages - response types
-pub use messages::{ContentBlock, Message, StopReason, Usage};
+pub use messages::{ContentBlock, Message, StopReason, TextCitation, Usage};
 
 // Tools
 pub use tools::{Tool, ToolChoice, ToolInputSchema};
@@ -54,6 +54,18 @@ pub enum BetaFeature {
     /// **Pricing**: ~2x input, ~1.5x output when prompts exceed 200K tokens.
     Context1M,
 
+    /// Files API
+    ///
+    /// Required to reference previously uploaded files by ID, for example in
+    /// [`DocumentSource::File`].
+    FilesApi,
+
+    /// Interleaved thinking
+    ///
+    /// Lets the model think between tool calls when extended thinking is
+    /// enabled, instead of only before its first response.
+    InterleavedThinking,
+
     /// A custom beta feature identifier for forward compatibility
     ///
     /// Use this for beta features not yet added to this enum.
@@ -65,6 +77,8 @@ impl BetaFeature {
     pub fn as_str(&self) -> &str {
         match self {
             BetaFeature::Context1M => "context-1m-2025-08-07",
+            BetaFeature::FilesApi => "files-api-2025-04-14",
+            BetaFeature::InterleavedThinking => "interleaved-thinking-2025-05-14",
             BetaFeature::Custom(s) => s,
         }
     }
@@ -94,7 +108,7 @@ pub struct MessageCreateParams {
 
     /// System prompt (optional)
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub system: Option<String>,
+    pub system: Option<SystemPrompt>,
 
     /// Sampling temperature (0.0 to 1.0)
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -200,7 +214,7 @@ pub struct MessageCreateParamsBuilder {
     model: String,
     max_tokens: u32,
     messages: Vec<MessageParam>,
-    system: Option<String>,
+    system: Option<SystemPrompt>,
     temperature: Option<f32>,
     top_p: Option<f32>,
     top_k: Option<u32>,
@@ -294,8 +308,8 @@ impl MessageCreateParamsBuilder {
         self
     }
 
-    /// Set the system prompt
-    pub fn system(mut self, system: impl Into<String>) -> Self {
+    /// Set the system prompt, as text or as blocks with cache control
+    pub fn system(mut self, system: impl Into<SystemPrompt>) -> Self {
         self.system = Some(system.into());
         self
     }
@@ -590,6 +604,64 @@ impl From<Vec<ContentBlockParam>> for MessageContent {
     }
 }
 
+/// System prompt - can be simple text or text blocks
+///
+/// Use blocks to place a cache breakpoint on the system prompt.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum SystemPrompt {
+    /// Simple text prompt
+    Text(String),
+
+    /// Text blocks (only `ContentBlockParam::Text` is accepted by the API)
+    Blocks(Vec<ContentBlockParam>),
+}
+
+impl SystemPrompt {
+    /// Create a single-block prompt with a cache breakpoint after it
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use mixtape_anthropic_sdk::{CacheControl, SystemPrompt};
+    ///
+    /// let system = SystemPrompt::cached("You are a helpful assistant.", CacheControl::ephemeral());
+    /// ```
+    pub fn cached(text: impl Into<String>, cache_control: CacheControl) -> Self {
+        SystemPrompt::Blocks(vec![ContentBlockParam::Text {
+            text: text.into(),
+            cache_control: Some(cache_control),
+        }])
+    }
+
+    /// The prompt's text, with blocks joined by blank lines
+    pub fn text(&self) -> String {
+        match self {
+            SystemPrompt::Text(text) => text.clone(),
+            SystemPrompt::Blocks(blocks) => blocks
+                .iter()
+                .filter_map(|block| match block {
+                    ContentBlockParam::Text { text, .. } => Some(text.as_str()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+        }
+    }
+}
+
+impl From<&str> for SystemPrompt {
+    fn from(s: &str) -> Self {
+        SystemPrompt::Text(s.to_string())
+    }
+}
+
+impl From<String> for SystemPrompt {
+    fn from(s: String) -> Self {
+        SystemPrompt::Text(s)
+    }
+}
+
 // ============================================================================
 // Content Blocks (Request)
 // ============================================================================
@@ -715,6 +787,10 @@ pub enum DocumentSource {
     Url { url: String },
     /// Content blocks as document
     Content { content: Vec<ContentBlockParam> },
+    /// Reference to a file uploaded through the Files API
+    ///
+    /// Requests using this source must enable [`BetaFeature::FilesApi`].
+    File { file_id: String },
 }
 
 /// Web search tool result content
@@ -805,7 +881,13 @@ pub struct Message {
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ContentBlock {
     /// Text content
-    Text { text: String },
+    Text {
+        text: String,
+        /// Sources for this text, when citations are enabled on a document
+        /// or the text draws on web search results
+        #[serde(default)]
+        citations: Option<Vec<TextCitation>>,
+    },
 
     /// Tool use request
     ToolUse {
@@ -834,6 +916,54 @@ pub enum ContentBlock {
     },
 }
 
+/// A source cited by a text block
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TextCitation {
+    /// Character range of a plain text document
+    CharLocation {
+        cited_text: String,
+        document_index: u32,
+        document_title: Option<String>,
+        start_char_index: u32,
+        /// Exclusive
+        end_char_index: u32,
+    },
+
+    /// Page range of a PDF document
+    PageLocation {
+        cited_text: String,
+        document_index: u32,
+        document_title: Option<String>,
+        /// 1-indexed
+        start_page_number: u32,
+        /// Exclusive
+        end_page_number: u32,
+    },
+
+    /// Block range of a custom content document
+    ContentBlockLocation {
+        cited_text: String,
+        document_index: u32,
+        document_title: Option<String>,
+        start_block_index: u32,
+        /// Exclusive
+        end_block_index: u32,
+    },
+
+    /// A web search result
+    WebSearchResultLocation {
+        cited_text: String,
+        url: String,
+        title: Option<String>,
+        encrypted_index: String,
+    },
+
+    /// A citation type this SDK does not know
+    #[serde(other)]
+    Unknown,
+}
+
 /// Reason the model stopped generating
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
@@ -873,6 +1003,10 @@ pub struct Usage {
     /// Tokens read from cache
     #[serde(default)]
     pub cache_read_input_tokens: u32,
+
+    /// Service tier that handled the request (`standard`, `priority`, or `batch`)
+    #[serde(default)]
+    pub service_tier: Option<String>,
 }
 
 // ============================================================================
@@ -998,6 +1132,36 @@ mod tests {
         assert!(json.contains("\"text\":\"Hello\""));
     }
 
+    #[test]
+    fn test_document_file_source_serialization() {
+        let block = ContentBlockParam::Document {
+            source: DocumentSource::File {
+                file_id: "file_abc".to_string(),
+            },
+            cache_control: None,
+            title: None,
+            context: None,
+            citations: None,
+        };
+        let json = serde_json::to_value(&block).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "type": "document",
+                "source": {"type": "file", "file_id": "file_abc"}
+            })
+        );
+        assert_eq!(BetaFeature::FilesApi.as_str(), "files-api-2025-04-14");
+    }
+
+    #[test]
+    fn test_interleaved_thinking_beta_identifier() {
+        assert_eq!(
+            BetaFeature::InterleavedThinking.to_string(),
+            "interleaved-thinking-2025-05-14"
+        );
+    }
+
     #[test]
     fn test_message_content_from_str() {
         let content: MessageContent = "Hello".into();
@@ -1031,7 +1195,7 @@ mod tests {
         assert_eq!(params.model, "claude-sonnet-4-20250514");
         assert_eq!(params.max_tokens, 1024);
         assert_eq!(params.messages.len(), 1);
-        assert_eq!(params.system, Some("Be helpful".to_string()));
+        assert_eq!(params.system.unwrap().text(), "Be helpful");
         assert_eq!(params.temperature, Some(0.7));
     }
 
@@ -1048,6 +1212,26 @@ mod tests {
         assert_eq!(CacheControl::ephemeral_1h().ttl, Some(CacheTtl::OneHour));
     }
 
+    #[test]
+    fn test_system_prompt_serialization() {
+        let text = serde_json::to_value(SystemPrompt::from("Be helpful")).unwrap();
+        assert_eq!(text, serde_json::json!("Be helpful"));
+
+        let cached = serde_json::to_value(SystemPrompt::cached(
+            "Be helpful",
+            CacheControl::ephemeral(),
+        ))
+        .unwrap();
+        assert_eq!(
+            cached,
+            serde_json::json!([{
+                "type": "text",
+                "text": "Be helpful",
+                "cache_control": {"type": "ephemeral"}
+            }])
+        );
+    }
+
     #[test]
     fn test_thinking_config() {
         let enabled = ThinkingConfig::enabled(4096);
@@ -1165,7 +1349,7 @@ mod tests {
         assert_eq!(params.model, "test-model");
         assert_eq!(params.max_tokens, 2048);
         assert_eq!(params.messages.len(), 1);
-        assert_eq!(params.system, Some("test system".to_string()));
+        assert_eq!(params.system.unwrap().text(), "test system");
         assert_eq!(params.temperature, Some(0.8));
         assert_eq!(params.top_p, Some(0.9));
         assert_eq!(params.top_k, Some(40));
@@ -23,7 +23,9 @@
 //! ```
 
 use crate::error::{AnthropicError, ApiError};
-use crate::messages::{ContentBlock, Message, MessageCreateParams, StopReason, Usage};
+use crate::messages::{
+    ContentBlock, Message, MessageCreateParams, StopReason, TextCitation, Usage,
+};
 use futures::stream::Stream;
 use futures::StreamExt;
 use reqwest::header::HeaderMap;
@@ -31,6 +33,7 @@ use reqwest_eventsource::{Event, EventSource, RequestBuilderExt};
 use serde::Deserialize;
 use std::pin::Pin;
 use std::task::{Context, Poll};
+use std::time::Duration;
 
 // ============================================================================
 // Streaming Event Types
@@ -89,6 +92,9 @@ pub enum ContentBlockDelta {
 
     /// Signature delta (for thinking blocks)
     SignatureDelta { signature: String },
+
+    /// A citation for the text block
+    CitationsDelta { citation: TextCitation },
 }
 
 /// Delta update for the message
@@ -122,13 +128,19 @@ impl MessageStream {
         client: &reqwest::Client,
         url: &str,
         headers: HeaderMap,
+        timeout: Option<Duration>,
         params: MessageCreateParams,
     ) -> Result<Self, AnthropicError> {
-        let request = client.post(url).headers(headers).json(&params);
+        let mut request = client.post(url).headers(headers).json(&params);
+        if let Some(timeout) = timeout {
+            request = request.timeout(timeout);
+        }
 
-        let event_source = request
+        let mut event_source = request
             .eventsource()
             .map_err(|e| AnthropicError::Stream(format!("Failed to create event source: {}", e)))?;
+        // A reconnect would send (and bill) the whole request again
+        event_source.set_retry_policy(Box::new(reqwest_eventsource::retry::Never));
 
         Ok(Self {
             inner: event_source,
@@ -361,6 +373,7 @@ pub(crate) struct ContentBlockBuilder {
     tool_input_json: String,
     thinking: String,
     thinking_signature: String,
+    citations: Vec<TextCitation>,
 }
 
 #[derive(Debug, Clone)]
@@ -383,14 +396,16 @@ impl ContentBlockBuilder {
             tool_input_json: String::new(),
             thinking: String::new(),
             thinking_signature: String::new(),
+            citations: Vec::new(),
         }
     }
 
     fn set_initial(&mut self, block: ContentBlock) {
         match block {
-            ContentBlock::Text { text } => {
+            ContentBlock::Text { text, citations } => {
                 self.block_type = Some(ContentBlockType::Text);
                 self.text = text;
+                self.citations = citations.unwrap_or_default();
             }
             ContentBlock::ToolUse { id, name, input } => {
                 self.block_type = Some(ContentBlockType::ToolUse);
@@ -448,12 +463,18 @@ impl ContentBlockBuilder {
             ContentBlockDelta::SignatureDelta { signature } => {
                 self.thinking_signature.push_str(&signature);
             }
+            ContentBlockDelta::CitationsDelta { citation } => {
+                self.citations.push(citation);
+            }
         }
     }
 
     fn build(self) -> Option<ContentBlock> {
         match self.block_type? {
-            ContentBlockType::Text => Some(ContentBlock::Text { text: self.text }),
+            ContentBlockType::Text => Some(ContentBlock::Text {
+                text: self.text,
+                citations: (!self.citations.is_empty()).then_some(self.citations),
+            }),
             ContentBlockType::ToolUse => {
                 let input = serde_json::from_str(&self.tool_input_json)
                     .unwrap_or(serde_json::Value::Object(Default::default()));
@@ -697,6 +718,7 @@ mod tests {
         let mut builder = ContentBlockBuilder::new();
         builder.set_initial(ContentBlock::Text {
             text: "Hello".to_string(),
+            citations: None,
         });
         builder.apply_delta(ContentBlockDelta::TextDelta {
             text: " World".to_string(),
@@ -704,7 +726,10 @@ mod tests {
         let block = builder.build();
         assert!(block.is_some());
         match block.unwrap() {
-            ContentBlock::Text { text } => assert_eq!(text, "Hello World"),
+            ContentBlock::Text { text, citations } => {
+                assert_eq!(text, "Hello World");
+                assert!(citations.is_none());
+            }
             _ => panic!("Expected Text block"),
         }
     }
@@ -786,6 +811,7 @@ mod tests {
         let mut builder = ContentBlockBuilder::new();
         builder.set_initial(ContentBlock::Text {
             text: "".to_string(),
+            citations: None,
         });
         builder.apply_delta(ContentBlockDelta::TextDelta {
             text: "One ".to_string(),
@@ -798,7 +824,49 @@ mod tests {
         });
         let block = builder.build();
         match block.unwrap() {
-            ContentBlock::Text { text } => assert_eq!(text, "One Two Three"),
+            ContentBlock::Text { text, .. } => assert_eq!(text, "One Two Three"),
+            _ => panic!("Expected Text block"),
+        }
+    }
+
+    #[test]
+    fn test_content_block_builder_collects_citations() {
+        let mut builder = ContentBlockBuilder::new();
+        builder.set_initial(ContentBlock::Text {
+            text: String::new(),
+            citations: None,
+        });
+        let delta: ContentBlockDelta = serde_json::from_value(serde_json::json!({
+            "type": "citations_delta",
+            "citation": {
+                "type": "char_location",
+                "cited_text": "The grass is green.",
+                "document_index": 0,
+                "document_title": "Facts",
+                "start_char_index": 0,
+                "end_char_index": 19
+            }
+        }))
+        .unwrap();
+        builder.apply_delta(delta);
+        builder.apply_delta(ContentBlockDelta::TextDelta {
+            text: "the grass is green".to_string(),
+        });
+
+        match builder.build().unwrap() {
+            ContentBlock::Text { text, citations } => {
+                assert_eq!(text, "the grass is green");
+                assert_eq!(
+                    citations.unwrap(),
+                    vec![TextCitation::CharLocation {
+                        cited_text: "The grass is green.".to_string(),
+                        document_index: 0,
+                        document_title: Some("Facts".to_string()),
+                        start_char_index: 0,
+                        end_char_index: 19,
+                    }]
+                );
+            }
             _ => panic!("Expected Text block"),
         }
     }
@@ -13,6 +13,10 @@ pub enum CliError {
     #[error("Session error: {0}")]
     Session(#[from] mixtape_core::SessionError),
 
+    /// Grant store error
+    #[error("Grant store error: {0}")]
+    Grants(#[from] mixtape_core::GrantStoreError),
+
     /// Readline/input error
     #[error("Input error: {0}")]
     Readline(#[from] rustyline::error::ReadlineError),
@@ -15,4 +15,4 @@ pub use repl::{
     read_input, run_cli, ApprovalPrompter, DefaultPrompter, EventPresenter, PermissionRequest,
     PresentationHook, SimplePrompter, Verbosity,
 };
-pub use session::SqliteStore;
+pub use session::{SerializationFormat, SqliteStore, StorageStats};
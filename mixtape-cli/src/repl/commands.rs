@@ -1,5 +1,6 @@
 use crate::error::CliError;
-use mixtape_core::Agent;
+use mixtape_core::{Agent, Grant, SessionSummary};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
@@ -73,6 +74,8 @@ pub async fn handle_special_command(
     input: &str,
     agent: &Agent,
     verbosity: &Arc<Mutex<Verbosity>>,
+    stream_text: &Arc<AtomicBool>,
+    color: &Arc<AtomicBool>,
 ) -> Result<Option<SpecialCommandResult>, CliError> {
     match CommandType::parse(input) {
         CommandType::Shell(shell_cmd) => {
@@ -103,10 +106,30 @@ pub async fn handle_special_command(
                     update_verbosity(verbosity, args);
                     Ok(Some(SpecialCommandResult::Continue))
                 }
+                "/stream" => {
+                    update_switch(stream_text, "Streaming", args);
+                    Ok(Some(SpecialCommandResult::Continue))
+                }
+                "/color" => {
+                    update_switch(color, "Color", args);
+                    Ok(Some(SpecialCommandResult::Continue))
+                }
                 "/session" => {
                     show_session_info(agent).await?;
                     Ok(Some(SpecialCommandResult::Continue))
                 }
+                "/search" => {
+                    search_sessions(agent, args).await?;
+                    Ok(Some(SpecialCommandResult::Continue))
+                }
+                "/grants" => {
+                    show_grants(agent).await?;
+                    Ok(Some(SpecialCommandResult::Continue))
+                }
+                "/revoke" => {
+                    revoke_grant(agent, args).await?;
+                    Ok(Some(SpecialCommandResult::Continue))
+                }
                 _ => {
                     eprintln!(
                         "Unknown command: {}. Type /help for available commands.",
@@ -162,6 +185,7 @@ async fn execute_shell_command(cmd: &str) -> Result<(), CliError> {
 
 async fn clear_session(agent: &Agent) -> Result<(), CliError> {
     agent.clear_session().await?;
+    agent.clear_history();
     println!("Session cleared.");
     Ok(())
 }
@@ -186,19 +210,29 @@ Navigation:
   /history [n]      Show last n messages (default: 10)
   /clear            Clear current session history
   /verbosity [level]  Set output verbosity (quiet|normal|verbose)
+  /stream [on|off]  Print model text as it streams
+  /color [on|off]   Render responses as highlighted Markdown
 ";
 
     /// Session management section
     pub const SESSION: &str = "\
 Session Management:
   /session          Show current session info
+  /search <query>   Search past sessions for matching messages
+";
+
+    /// Permission commands section
+    pub const PERMISSIONS: &str = "\
+Permissions:
+  /grants           List tools you have granted permission to
+  /revoke <tool> [hash]  Revoke a tool-wide grant, or one exact-call grant
 ";
 
     /// Exit commands section
     pub const EXIT: &str = "\
 Exit:
   /exit, /quit      Exit and save session
-  Ctrl+C            Interrupt current operation
+  Ctrl+C            Abort running tools, or interrupt current operation
   Ctrl+D            Exit
 ";
 
@@ -214,8 +248,8 @@ Keyboard Shortcuts:
     /// Get the complete help text
     pub fn full_text() -> String {
         format!(
-            "{}{}\n{}\n{}\n{}\n{}",
-            HEADER, SHELL_COMMANDS, NAVIGATION, SESSION, EXIT, KEYBOARD
+            "{}{}\n{}\n{}\n{}\n{}\n{}",
+            HEADER, SHELL_COMMANDS, NAVIGATION, SESSION, PERMISSIONS, EXIT, KEYBOARD
         )
     }
 }
@@ -279,6 +313,25 @@ fn update_verbosity(verbosity: &Arc<Mutex<Verbosity>>, args: &[&str]) {
     }
 }
 
+/// Show or set an on/off setting such as `/stream`
+fn update_switch(flag: &Arc<AtomicBool>, label: &str, args: &[&str]) {
+    let enabled = match args.first() {
+        None => {
+            let current = flag.load(Ordering::Relaxed);
+            println!("{}: {}", label, if current { "on" } else { "off" });
+            return;
+        }
+        Some(&"on") => true,
+        Some(&"off") => false,
+        Some(other) => {
+            println!("Unknown {} mode: {} (on|off)", label.to_lowercase(), other);
+            return;
+        }
+    };
+    flag.store(enabled, Ordering::Relaxed);
+    println!("{} {}", label, if enabled { "on" } else { "off" });
+}
+
 async fn show_history(agent: &Agent, args: &[&str]) -> Result<(), CliError> {
     let limit: usize = args.first().and_then(|s| s.parse().ok()).unwrap_or(10);
 
@@ -291,7 +344,7 @@ async fn show_history(agent: &Agent, args: &[&str]) -> Result<(), CliError> {
         for (idx, msg) in history.iter().enumerate() {
             let role = match msg.role {
                 mixtape_core::MessageRole::User => "User",
-                mixtape_core::MessageRole::Assistant => "Assistant",
+                mixtape_core::MessageRole::Assistant => agent.name().unwrap_or("Assistant"),
                 mixtape_core::MessageRole::System => "System",
             };
 
@@ -351,6 +404,94 @@ async fn show_session_info(agent: &Agent) -> Result<(), CliError> {
     Ok(())
 }
 
+async fn show_grants(agent: &Agent) -> Result<(), CliError> {
+    let grants = agent.list_grants().await?;
+    print!("{}", format_grant_list(&grants));
+    Ok(())
+}
+
+async fn revoke_grant(agent: &Agent, args: &[&str]) -> Result<(), CliError> {
+    let Some(tool) = args.first() else {
+        println!("Usage: /revoke <tool> [hash]");
+        return Ok(());
+    };
+    let params_hash = args.get(1).copied();
+
+    if agent.revoke_grant(tool, params_hash).await? {
+        println!("Revoked grant for {}.", tool);
+    } else {
+        println!(
+            "No matching grant for {}. Type /grants to list grants.",
+            tool
+        );
+    }
+    Ok(())
+}
+
+/// Format stored grants for display
+pub fn format_grant_list(grants: &[Grant]) -> String {
+    let mut output = String::from("\n🔐 Grants:\n\n");
+
+    if grants.is_empty() {
+        output.push_str("  No grants\n");
+    } else {
+        for grant in grants {
+            let target = match &grant.params_hash {
+                None => "all calls".to_string(),
+                Some(hash) => format!("exact call {}", hash),
+            };
+            output.push_str(&format!(
+                "  {} - {} ({}, {})\n",
+                grant.tool,
+                target,
+                grant.scope,
+                grant.created_at.format("%Y-%m-%d %H:%M")
+            ));
+        }
+    }
+
+    output
+}
+
+/// Maximum number of sessions shown by `/search`
+const SEARCH_RESULT_LIMIT: usize = 10;
+
+async fn search_sessions(agent: &Agent, args: &[&str]) -> Result<(), CliError> {
+    let query = args.join(" ");
+    if query.trim().is_empty() {
+        println!("Usage: /search <query>");
+        return Ok(());
+    }
+
+    let results = agent.search_sessions(&query, SEARCH_RESULT_LIMIT).await?;
+    print!("{}", format_search_results(&query, &results));
+    Ok(())
+}
+
+/// Format session search results for display
+pub fn format_search_results(query: &str, results: &[SessionSummary]) -> String {
+    if results.is_empty() {
+        return format!("\nNo sessions match \"{}\".\n\n", query);
+    }
+
+    let mut output = format!("\n🔎 Sessions matching \"{}\":\n\n", query);
+    for summary in results {
+        let short_id = &summary.id[..8.min(summary.id.len())];
+        output.push_str(&format!(
+            "  {}  {}  {} ({} messages)\n",
+            short_id,
+            summary.updated_at.format("%Y-%m-%d %H:%M"),
+            summary.directory,
+            summary.message_count
+        ));
+        if let Some(snippet) = &summary.snippet {
+            output.push_str(&format!("    \x1b[2m{}\x1b[0m\n", snippet));
+        }
+    }
+    output.push('\n');
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -498,11 +639,20 @@ mod tests {
             assert!(help::NAVIGATION.contains("/history"));
             assert!(help::NAVIGATION.contains("/clear"));
             assert!(help::NAVIGATION.contains("/verbosity"));
+            assert!(help::NAVIGATION.contains("/stream"));
+            assert!(help::NAVIGATION.contains("/color"));
         }
 
         #[test]
         fn session_documents_session_command() {
             assert!(help::SESSION.contains("/session"));
+            assert!(help::SESSION.contains("/search"));
+        }
+
+        #[test]
+        fn permissions_documents_grant_commands() {
+            assert!(help::PERMISSIONS.contains("/grants"));
+            assert!(help::PERMISSIONS.contains("/revoke"));
         }
 
         #[test]
@@ -526,6 +676,7 @@ mod tests {
             assert!(full.contains("!<command>"));
             assert!(full.contains("/help"));
             assert!(full.contains("/session"));
+            assert!(full.contains("/grants"));
             assert!(full.contains("/exit"));
             assert!(full.contains("Up/Down"));
         }
@@ -584,4 +735,66 @@ mod tests {
             assert!(output.contains("  test"));
         }
     }
+
+    mod format_search_results_tests {
+        use super::*;
+        use chrono::{TimeZone, Utc};
+
+        fn summary(id: &str, snippet: Option<&str>) -> SessionSummary {
+            SessionSummary {
+                id: id.to_string(),
+                directory: "/proj/api".to_string(),
+                message_count: 4,
+                created_at: Utc.with_ymd_and_hms(2025, 1, 2, 3, 4, 5).unwrap(),
+                updated_at: Utc.with_ymd_and_hms(2025, 1, 2, 3, 4, 5).unwrap(),
+                snippet: snippet.map(str::to_string),
+            }
+        }
+
+        #[test]
+        fn no_results_message() {
+            let output = format_search_results("migration", &[]);
+            assert!(output.contains("No sessions match \"migration\""));
+        }
+
+        #[test]
+        fn results_show_session_and_snippet() {
+            let output = format_search_results(
+                "migration",
+                &[summary("0123456789abcdef", Some("…rerun the migration…"))],
+            );
+            assert!(output.contains("Sessions matching \"migration\""));
+            assert!(output.contains("01234567  2025-01-02 03:04  /proj/api (4 messages)"));
+            assert!(!output.contains("0123456789"));
+            assert!(output.contains("…rerun the migration…"));
+        }
+
+        #[test]
+        fn missing_snippet_omits_line() {
+            let output = format_search_results("x", &[summary("abc", None)]);
+            assert_eq!(output.lines().filter(|l| !l.is_empty()).count(), 2);
+        }
+    }
+
+    mod format_grant_list_tests {
+        use super::*;
+        use mixtape_core::Scope;
+
+        #[test]
+        fn empty_list_shows_no_grants_message() {
+            let output = format_grant_list(&[]);
+            assert!(output.contains("No grants"));
+        }
+
+        #[test]
+        fn tool_wide_and_exact_grants_formatted() {
+            let grants = vec![
+                Grant::tool("read_file").with_scope(Scope::Persistent),
+                Grant::exact("bash", "abc123"),
+            ];
+            let output = format_grant_list(&grants);
+            assert!(output.contains("  read_file - all calls (Persistent, "));
+            assert!(output.contains("  bash - exact call abc123 (Session, "));
+        }
+    }
 }
@@ -0,0 +1,338 @@
+//! Terminal rendering of Markdown model text
+//!
+//! Fenced code blocks are syntax highlighted with syntect. Other lines get
+//! light formatting: bold headings, bullet glyphs, dimmed quotes and rules,
+//! and inline bold, italic, and code spans. [`MarkdownStream`] renders
+//! streamed text a line at a time and holds back each code block until its
+//! closing fence arrives, so a half-written block is never highlighted.
+
+use std::sync::OnceLock;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::parsing::SyntaxSet;
+use syntect::util::{as_24_bit_terminal_escaped, LinesWithEndings};
+
+/// syntect theme used for code blocks
+const THEME: &str = "base16-ocean.dark";
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const ITALIC: &str = "\x1b[3m";
+const UNDERLINE: &str = "\x1b[4m";
+const INLINE_CODE: &str = "\x1b[36m";
+
+/// Width of a rendered horizontal rule
+const RULE_WIDTH: usize = 40;
+
+fn syntaxes() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn theme() -> &'static Theme {
+    static THEME_CELL: OnceLock<Theme> = OnceLock::new();
+    THEME_CELL.get_or_init(|| {
+        ThemeSet::load_defaults()
+            .themes
+            .remove(THEME)
+            .expect("syntect ships the base16-ocean.dark theme")
+    })
+}
+
+/// Render a complete Markdown text for the terminal
+pub fn render_markdown(text: &str) -> String {
+    let mut stream = MarkdownStream::default();
+    let mut rendered = stream.push(text);
+    rendered.push_str(&stream.finish());
+    rendered
+}
+
+/// Incremental renderer for streamed Markdown
+#[derive(Debug, Default)]
+pub struct MarkdownStream {
+    /// Text after the last newline, not yet rendered
+    partial: String,
+    /// Code block waiting for its closing fence
+    block: Option<CodeBlock>,
+}
+
+impl MarkdownStream {
+    /// Add streamed text, returning the rendered lines it completes
+    pub fn push(&mut self, delta: &str) -> String {
+        self.partial.push_str(delta);
+        let mut rendered = String::new();
+        while let Some(end) = self.partial.find('\n') {
+            let line: String = self.partial.drain(..=end).collect();
+            self.render_line(line.trim_end_matches(['\n', '\r']), &mut rendered);
+        }
+        rendered
+    }
+
+    /// Render everything still buffered
+    ///
+    /// A code block that never closed is returned as plain text, and a
+    /// trailing partial line is returned without a newline.
+    pub fn finish(&mut self) -> String {
+        let mut rendered = String::new();
+        if let Some(block) = self.block.take() {
+            rendered.push_str(&block.opening);
+            rendered.push('\n');
+            rendered.push_str(&block.code);
+        }
+        let partial = std::mem::take(&mut self.partial);
+        if !partial.is_empty() {
+            rendered.push_str(&render_prose(&partial));
+        }
+        rendered
+    }
+
+    fn render_line(&mut self, line: &str, rendered: &mut String) {
+        if let Some(block) = &mut self.block {
+            if !block.fence.closed_by(line) {
+                block.code.push_str(line);
+                block.code.push('\n');
+                return;
+            }
+            let block = self.block.take().expect("checked above");
+            rendered.push_str(&dim(&block.opening));
+            rendered.push('\n');
+            rendered.push_str(&highlight(&block.code, &block.language));
+            rendered.push_str(&dim(line));
+            rendered.push('\n');
+            return;
+        }
+
+        if let Some((fence, language)) = Fence::open(line) {
+            self.block = Some(CodeBlock {
+                fence,
+                language,
+                opening: line.to_string(),
+                code: String::new(),
+            });
+            return;
+        }
+
+        rendered.push_str(&render_prose(line));
+        rendered.push('\n');
+    }
+}
+
+#[derive(Debug)]
+struct CodeBlock {
+    fence: Fence,
+    /// First word of the fence's info string, such as `rust`
+    language: String,
+    /// The opening fence line as written
+    opening: String,
+    code: String,
+}
+
+/// A code fence: three or more backticks or tildes
+#[derive(Debug)]
+struct Fence {
+    marker: char,
+    len: usize,
+}
+
+impl Fence {
+    /// Parse an opening fence line into the fence and its language
+    fn open(line: &str) -> Option<(Self, String)> {
+        let trimmed = line.trim_start();
+        let marker = trimmed.chars().next().filter(|c| *c == '`' || *c == '~')?;
+        let len = trimmed.chars().take_while(|c| *c == marker).count();
+        if len < 3 {
+            return None;
+        }
+        let info = trimmed[len..].trim();
+        if marker == '`' && info.contains('`') {
+            return None;
+        }
+        let language = info.split_whitespace().next().unwrap_or_default();
+        Some((Self { marker, len }, language.to_string()))
+    }
+
+    fn closed_by(&self, line: &str) -> bool {
+        let trimmed = line.trim();
+        trimmed.chars().count() >= self.len && trimmed.chars().all(|c| c == self.marker)
+    }
+}
+
+/// Highlight code for a 24-bit color terminal, one line at a time
+fn highlight(code: &str, language: &str) -> String {
+    let syntaxes = syntaxes();
+    let syntax = syntaxes
+        .find_syntax_by_token(language)
+        .unwrap_or_else(|| syntaxes.find_syntax_plain_text());
+    let mut highlighter = HighlightLines::new(syntax, theme());
+
+    let mut rendered = String::new();
+    for line in LinesWithEndings::from(code) {
+        match highlighter.highlight_line(line, syntaxes) {
+            Ok(ranges) => {
+                let escaped = as_24_bit_terminal_escaped(&ranges, false);
+                rendered.push_str(escaped.trim_end_matches('\n'));
+                rendered.push_str(RESET);
+                rendered.push('\n');
+            }
+            Err(_) => rendered.push_str(line),
+        }
+    }
+    rendered
+}
+
+/// Format one line of text outside a code block
+fn render_prose(line: &str) -> String {
+    let trimmed = line.trim_start();
+    let indent = &line[..line.len() - trimmed.len()];
+
+    let hashes = trimmed.chars().take_while(|c| *c == '#').count();
+    if (1..=6).contains(&hashes) {
+        let rest = &trimmed[hashes..];
+        if rest.is_empty() || rest.starts_with(' ') {
+            let style = if hashes == 1 {
+                format!("{}{}", BOLD, UNDERLINE)
+            } else {
+                BOLD.to_string()
+            };
+            return format!("{}{}{}{}", indent, style, rest.trim(), RESET);
+        }
+    }
+
+    if is_rule(trimmed) {
+        return dim(&"─".repeat(RULE_WIDTH));
+    }
+
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(rest) = trimmed.strip_prefix(bullet) {
+            return format!("{}• {}", indent, render_inline(rest));
+        }
+    }
+
+    if let Some(rest) = trimmed.strip_prefix('>') {
+        return format!(
+            "{}{}{}",
+            indent,
+            dim("│ "),
+            render_inline(rest.trim_start())
+        );
+    }
+
+    render_inline(line)
+}
+
+/// Whether a line is a thematic break such as `---` or `* * *`
+fn is_rule(trimmed: &str) -> bool {
+    let marks: Vec<char> = trimmed.chars().filter(|c| !c.is_whitespace()).collect();
+    marks.len() >= 3 && matches!(marks[0], '-' | '*' | '_') && marks.iter().all(|c| *c == marks[0])
+}
+
+/// Style inline code spans, `**bold**`, and `*italic*` text
+fn render_inline(text: &str) -> String {
+    let mut rendered = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(['`', '*']) {
+        rendered.push_str(&rest[..start]);
+        let tail = &rest[start..];
+
+        if let Some((span, after)) = delimited(tail, "`") {
+            rendered.push_str(&styled(INLINE_CODE, span));
+            rest = after;
+        } else if let Some((span, after)) = delimited(tail, "**") {
+            rendered.push_str(&styled(BOLD, span));
+            rest = after;
+        } else if let Some((span, after)) =
+            delimited(tail, "*").filter(|(span, _)| !span.starts_with(' ') && !span.ends_with(' '))
+        {
+            rendered.push_str(&styled(ITALIC, span));
+            rest = after;
+        } else {
+            rendered.push_str(&tail[..1]);
+            rest = &tail[1..];
+        }
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+/// Split `text` that starts with `delimiter` into the non-empty span up to
+/// the next `delimiter` and the text after it
+fn delimited<'a>(text: &'a str, delimiter: &str) -> Option<(&'a str, &'a str)> {
+    let inner = text.strip_prefix(delimiter)?;
+    let end = inner.find(delimiter).filter(|end| *end > 0)?;
+    Some((&inner[..end], &inner[end + delimiter.len()..]))
+}
+
+fn styled(style: &str, text: &str) -> String {
+    format!("{}{}{}", style, text, RESET)
+}
+
+fn dim(text: &str) -> String {
+    styled(DIM, text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fenced_block_is_detected_and_highlighted() {
+        let rendered = render_markdown("Here:\n```rust\nfn main() {}\n```\nDone.\n");
+
+        assert!(rendered.starts_with("Here:\n\x1b[2m```rust\x1b[0m\n"));
+        assert!(rendered.contains("\x1b[38;2;"), "code should be colored");
+        assert!(rendered.contains("fn"));
+        assert!(rendered.contains("main"));
+        assert!(rendered.ends_with("\x1b[2m```\x1b[0m\nDone.\n"));
+    }
+
+    #[test]
+    fn partial_block_held_until_fence_closes() {
+        let mut stream = MarkdownStream::default();
+
+        assert_eq!(stream.push("Intro\n```py"), "Intro\n");
+        assert_eq!(stream.push("thon\nprint(1)\n"), "");
+
+        let rendered = stream.push("```\n");
+        assert!(rendered.starts_with("\x1b[2m```python\x1b[0m\n"));
+        assert!(rendered.contains("print"));
+        assert!(rendered.contains("\x1b[38;2;"));
+        assert_eq!(stream.finish(), "");
+    }
+
+    #[test]
+    fn unclosed_block_finishes_as_plain_text() {
+        let mut stream = MarkdownStream::default();
+
+        assert_eq!(stream.push("~~~\nlet x = 1;\nlet y"), "");
+        assert_eq!(stream.finish(), "~~~\nlet x = 1;\nlet y");
+    }
+
+    #[test]
+    fn longer_fence_closes_only_on_matching_fence() {
+        let rendered = render_markdown("````\n```\nnested\n```\n````\n");
+        assert!(rendered.contains("nested"));
+        assert!(rendered.ends_with("\x1b[2m````\x1b[0m\n"));
+    }
+
+    #[test]
+    fn prose_lines_formatted() {
+        assert_eq!(render_prose("# Title"), "\x1b[1m\x1b[4mTitle\x1b[0m");
+        assert_eq!(render_prose("## Section"), "\x1b[1mSection\x1b[0m");
+        assert_eq!(render_prose("#hashtag"), "#hashtag");
+        assert_eq!(render_prose("  - item"), "  • item");
+        assert_eq!(render_prose("> quoted"), "\x1b[2m│ \x1b[0mquoted");
+        assert_eq!(render_prose("---"), dim(&"─".repeat(RULE_WIDTH)));
+    }
+
+    #[test]
+    fn inline_spans_styled() {
+        assert_eq!(
+            render_inline("run `cargo test` **now**"),
+            "run \x1b[36mcargo test\x1b[0m \x1b[1mnow\x1b[0m"
+        );
+        assert_eq!(render_inline("an *emphasis*"), "an \x1b[3memphasis\x1b[0m");
+        assert_eq!(render_inline("2 * 3 * 4"), "2 * 3 * 4");
+        assert_eq!(render_inline("unclosed `tick"), "unclosed `tick");
+    }
+}
@@ -5,6 +5,7 @@ mod commands;
 mod core;
 mod formatter;
 mod input;
+mod markdown;
 mod presentation;
 mod spinner;
 mod status;
@@ -21,12 +22,18 @@ use status::{clear_status_line, update_status_line};
 
 use mixtape_core::{Agent, AgentError, AgentEvent, AgentResponse, AuthorizationResponse};
 use serde_json::Value;
+use std::sync::atomic::AtomicBool;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tokio::sync::mpsc;
+use tokio::time::MissedTickBehavior;
 
 /// Permission request data: (proposal_id, tool_name, params_hash, params)
 type PermissionData = (String, String, String, Value);
 
+/// How often queued output is printed while text streaming is on
+const STREAM_FLUSH_INTERVAL: Duration = Duration::from_millis(20);
+
 pub use approval::{
     print_confirmation, prompt_for_approval, read_input, ApprovalPrompter, DefaultPrompter,
     PermissionRequest, SimplePrompter,
@@ -46,6 +53,8 @@ pub use presentation::{
 /// - Special commands (!shell, /help, etc)
 /// - Automatic session management
 /// - Rich tool presentation with CLIPresenter formatting
+/// - Markdown responses with syntax-highlighted code blocks, unless the
+///   `NO_COLOR` environment variable is set or `/color off` is used
 /// - Tool approval prompts (when using Registry approval mode)
 ///
 /// # Errors
@@ -79,11 +88,15 @@ pub async fn run_cli(agent: Agent) -> Result<(), CliError> {
 
     // Presenter for formatting and printing queued events
     let verbosity = Arc::new(Mutex::new(Verbosity::Normal));
+    let stream_text = Arc::new(AtomicBool::new(false));
+    let color = Arc::new(AtomicBool::new(color_by_default()));
     let presenter = EventPresenter::new(
         Arc::clone(&agent),
         Arc::clone(&verbosity),
         Arc::clone(&event_queue),
-    );
+    )
+    .with_stream_text(Arc::clone(&stream_text))
+    .with_color(Arc::clone(&color));
 
     // Set up permission handling channel (once, for entire session)
     let (perm_tx, perm_rx) = mpsc::unbounded_channel::<PermissionData>();
@@ -144,7 +157,9 @@ pub async fn run_cli(agent: Agent) -> Result<(), CliError> {
                 rl.add_history_entry(line)?;
 
                 // Handle special commands
-                if let Some(result) = handle_special_command(line, &agent, &verbosity).await? {
+                if let Some(result) =
+                    handle_special_command(line, &agent, &verbosity, &stream_text, &color).await?
+                {
                     match result {
                         SpecialCommandResult::Exit => break,
                         SpecialCommandResult::Continue => continue,
@@ -165,9 +180,19 @@ pub async fn run_cli(agent: Agent) -> Result<(), CliError> {
                 )
                 .await;
 
+                // Streamed text has already shown the response
+                let streamed = presenter.end_stream();
                 match result {
                     Ok(response) => {
-                        println!("\n{}\n", response);
+                        if streamed {
+                            println!();
+                        } else {
+                            println!("\n{}\n", presenter.render_response(&response.to_string()));
+                        }
+                        update_status_line(&agent);
+                    }
+                    Err(AgentError::Interrupted) => {
+                        println!("\n⏹  Interrupted\n");
                         update_status_line(&agent);
                     }
                     Err(e) => {
@@ -208,6 +233,14 @@ pub async fn run_cli(agent: Agent) -> Result<(), CliError> {
     Ok(())
 }
 
+/// Whether output starts in color, following the `NO_COLOR` convention
+fn color_by_default() -> bool {
+    match std::env::var_os("NO_COLOR") {
+        Some(value) => value.is_empty(),
+        None => true,
+    }
+}
+
 /// Run agent with interactive permission handling
 async fn run_with_permissions<F: formatter::ToolFormatter>(
     agent: Arc<Agent>,
@@ -218,7 +251,15 @@ async fn run_with_permissions<F: formatter::ToolFormatter>(
 ) -> Result<AgentResponse, AgentError> {
     // Spawn agent run in background
     let agent_clone = Arc::clone(&agent);
-    let mut handle = tokio::spawn(async move { agent_clone.run(&input).await });
+    let (interrupt, interrupted) = tokio::sync::oneshot::channel::<()>();
+    let mut interrupt = Some(interrupt);
+    let mut handle = tokio::spawn(async move {
+        agent_clone
+            .run_until(&input, async {
+                interrupted.await.ok();
+            })
+            .await
+    });
 
     // Lock the receiver for this run
     let mut rx = perm_rx.lock().await;
@@ -226,6 +267,9 @@ async fn run_with_permissions<F: formatter::ToolFormatter>(
     // Track if spinner is still active
     let mut spinner = Some(spinner);
 
+    let mut stream_ticker = tokio::time::interval(STREAM_FLUSH_INTERVAL);
+    stream_ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
     // Wait for permission requests or agent completion
     loop {
         tokio::select! {
@@ -276,6 +320,39 @@ async fn run_with_permissions<F: formatter::ToolFormatter>(
                 spinner = Some(Spinner::new("thinking"));
             }
 
+            // Ctrl+C aborts running tools; with none running it interrupts the run
+            _ = tokio::signal::ctrl_c() => {
+                if let Some(s) = spinner.take() {
+                    s.stop().await;
+                }
+                presenter.flush();
+
+                let running = agent.running_tool_ids();
+                if running.is_empty() {
+                    // The run stops and reports itself as interrupted
+                    if let Some(interrupt) = interrupt.take() {
+                        interrupt.send(()).ok();
+                    }
+                    continue;
+                }
+                for tool_use_id in &running {
+                    agent.abort_tool(tool_use_id);
+                }
+                println!("\n⏹  Aborted {} running tool(s)", running.len());
+                spinner = Some(Spinner::new("thinking"));
+            }
+
+            // With text streaming on, print output as it arrives. The
+            // spinner would overwrite the streamed line, so it stops for good.
+            _ = stream_ticker.tick(), if presenter.streams_text() => {
+                if presenter.has_pending() {
+                    if let Some(s) = spinner.take() {
+                        s.stop().await;
+                    }
+                    presenter.flush();
+                }
+            }
+
             // Agent finished
             result = &mut handle => {
                 // Stop spinner if still running
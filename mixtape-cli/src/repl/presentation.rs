@@ -2,12 +2,18 @@
 
 use super::commands::Verbosity;
 use super::formatter::ToolFormatter;
-use mixtape_core::{Agent, AgentEvent, AgentHook, Display};
+use super::markdown::{render_markdown, MarkdownStream};
+use mixtape_core::{Agent, AgentEvent, AgentHook, Display, EMIT_PROGRESS_TOOL};
 use std::collections::VecDeque;
+use std::io::{self, stdout, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 
 const BOX_WIDTH: usize = 80;
 
+/// Maximum bytes of tool input shown at [`Verbosity::Normal`]
+const MAX_INPUT_DISPLAY_BYTES: usize = 2048;
+
 /// Queue for tool events that need to be printed
 pub type EventQueue = Arc<Mutex<VecDeque<AgentEvent>>>;
 
@@ -16,7 +22,7 @@ pub fn new_event_queue() -> EventQueue {
     Arc::new(Mutex::new(VecDeque::new()))
 }
 
-/// Hook that queues tool events for later presentation
+/// Hook that queues tool and streaming text events for later presentation
 ///
 /// Events are queued rather than printed immediately, allowing the caller
 /// to control when output appears (e.g., not during permission prompts).
@@ -32,12 +38,21 @@ impl PresentationHook {
 
 impl AgentHook for PresentationHook {
     fn on_event(&self, event: &AgentEvent) {
-        // Only queue tool-related events
+        // Only queue events the presenter knows how to print. Progress
+        // tool calls are shown by their Progress event, not as tool boxes.
         match event {
-            AgentEvent::ToolRequested { .. }
+            AgentEvent::ToolRequested { name, .. }
+            | AgentEvent::ToolExecuting { name, .. }
+            | AgentEvent::ToolCompleted { name, .. }
+            | AgentEvent::ToolFailed { name, .. }
+                if name == EMIT_PROGRESS_TOOL => {}
+            AgentEvent::ModelCallStreaming { .. }
+            | AgentEvent::Progress { .. }
+            | AgentEvent::ToolRequested { .. }
             | AgentEvent::ToolExecuting { .. }
             | AgentEvent::ToolCompleted { .. }
-            | AgentEvent::ToolFailed { .. } => {
+            | AgentEvent::ToolFailed { .. }
+            | AgentEvent::ToolSkippedDryRun { .. } => {
                 self.queue.lock().unwrap().push_back(event.clone());
             }
             _ => {}
@@ -45,11 +60,33 @@ impl AgentHook for PresentationHook {
     }
 }
 
+/// Where the presenter is within streamed model text
+#[derive(Debug, Default)]
+struct StreamState {
+    /// Text has been written without a trailing newline
+    line_open: bool,
+    /// Text has been streamed since the last [`EventPresenter::end_stream`]
+    streamed: bool,
+    /// Markdown rendering of streamed text, used when color is on
+    markdown: MarkdownStream,
+}
+
 /// Presenter that formats and prints queued events
+///
+/// Model text deltas are only printed when text streaming is enabled (see
+/// [`EventPresenter::with_stream_text`]); otherwise the caller prints the
+/// final response once the run completes.
+///
+/// With color on (see [`EventPresenter::with_color`]), model text is
+/// rendered as Markdown with highlighted code blocks. Streamed text is then
+/// printed a line at a time, and each code block once it is complete.
 pub struct EventPresenter<F: ToolFormatter = Agent> {
     formatter: Arc<F>,
     verbosity: Arc<Mutex<Verbosity>>,
+    stream_text: Arc<AtomicBool>,
+    color: Arc<AtomicBool>,
     queue: EventQueue,
+    stream_state: Mutex<StreamState>,
 }
 
 impl<F: ToolFormatter> EventPresenter<F> {
@@ -57,19 +94,102 @@ impl<F: ToolFormatter> EventPresenter<F> {
         Self {
             formatter,
             verbosity,
+            stream_text: Arc::new(AtomicBool::new(false)),
+            color: Arc::new(AtomicBool::new(false)),
             queue,
+            stream_state: Mutex::new(StreamState::default()),
+        }
+    }
+
+    /// Share a flag that turns live printing of model text on and off
+    pub fn with_stream_text(mut self, stream_text: Arc<AtomicBool>) -> Self {
+        self.stream_text = stream_text;
+        self
+    }
+
+    /// Whether model text is printed as it streams
+    pub fn streams_text(&self) -> bool {
+        self.stream_text.load(Ordering::Relaxed)
+    }
+
+    /// Share a flag that turns Markdown and syntax highlighting on and off
+    pub fn with_color(mut self, color: Arc<AtomicBool>) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Whether model text is rendered with color
+    pub fn colors_text(&self) -> bool {
+        self.color.load(Ordering::Relaxed)
+    }
+
+    /// Format a final response that was not streamed
+    pub fn render_response(&self, text: &str) -> String {
+        if self.colors_text() {
+            render_markdown(text)
+        } else {
+            text.to_string()
         }
     }
 
+    /// Whether any events are waiting to be printed
+    pub fn has_pending(&self) -> bool {
+        !self.queue.lock().unwrap().is_empty()
+    }
+
     /// Drain and print all queued events
     pub fn flush(&self) {
+        self.flush_to(&mut stdout());
+    }
+
+    /// Drain all queued events into `out`
+    pub fn flush_to<W: Write>(&self, out: &mut W) {
         let mut queue = self.queue.lock().unwrap();
         while let Some(event) = queue.pop_front() {
-            self.print_event(&event);
+            let _ = self.write_event(out, &event);
         }
     }
 
-    fn print_event(&self, event: &AgentEvent) {
+    /// Finish streamed text, ending its line
+    ///
+    /// Returns whether any text was streamed since the last call, in which
+    /// case the final response has already been shown.
+    pub fn end_stream(&self) -> bool {
+        self.end_stream_to(&mut stdout())
+    }
+
+    fn end_stream_to<W: Write>(&self, out: &mut W) -> bool {
+        let mut state = self.stream_state.lock().unwrap();
+        let _ = Self::close_line(out, &mut state);
+        std::mem::take(&mut state.streamed)
+    }
+
+    /// Write any buffered Markdown and end the current line of streamed text
+    fn close_line<W: Write>(out: &mut W, state: &mut StreamState) -> io::Result<()> {
+        let rest = state.markdown.finish();
+        if !rest.is_empty() {
+            write!(out, "{}", rest)?;
+            state.line_open = !rest.ends_with('\n');
+        }
+        if state.line_open {
+            writeln!(out)?;
+            state.line_open = false;
+        }
+        Ok(())
+    }
+
+    fn write_event<W: Write>(&self, out: &mut W, event: &AgentEvent) -> io::Result<()> {
+        if let AgentEvent::ModelCallStreaming { delta, .. } = event {
+            return self.write_delta(out, delta);
+        }
+        if matches!(event, AgentEvent::ToolExecuting { .. }) {
+            // Optional: could show spinner for long-running tools
+            return Ok(());
+        }
+
+        // Tool boxes start on their own line, after any streamed text
+        Self::close_line(out, &mut self.stream_state.lock().unwrap())?;
+
         match event {
             AgentEvent::ToolRequested { name, input, .. } => {
                 let verbosity = *self.verbosity.lock().unwrap();
@@ -78,25 +198,21 @@ impl<F: ToolFormatter> EventPresenter<F> {
                     .format_tool_input(name, input, Display::Cli)
                     .and_then(|formatted| format_tool_input(name, &formatted, verbosity));
 
-                print_tool_header(name);
+                write_tool_header(out, name)?;
                 if let Some(output) = formatted {
                     for line in output.lines() {
-                        println!("│  {}", line);
+                        writeln!(out, "│  {}", line)?;
                     }
                 }
             }
-            AgentEvent::ToolExecuting { .. } => {
-                // Optional: could show spinner for long-running tools
-            }
             AgentEvent::ToolCompleted { name, output, .. } => {
                 let verbosity = *self.verbosity.lock().unwrap();
                 if verbosity == Verbosity::Quiet {
-                    print_result_separator();
-                    println!("│  \x1b[32m✓\x1b[0m");
-                    print_tool_footer(name);
-                    return;
+                    write_result_separator(out)?;
+                    writeln!(out, "│  \x1b[32m✓\x1b[0m")?;
+                    return write_tool_footer(out, name);
                 }
-                print_result_separator();
+                write_result_separator(out)?;
 
                 if let Some(formatted) =
                     self.formatter
@@ -104,23 +220,57 @@ impl<F: ToolFormatter> EventPresenter<F> {
                 {
                     if let Some(output) = format_tool_output(name, &formatted, verbosity) {
                         for line in output.lines() {
-                            println!("│  {}", line);
+                            writeln!(out, "│  {}", line)?;
                         }
                     } else {
-                        println!("│  \x1b[2m(no output)\x1b[0m");
+                        writeln!(out, "│  \x1b[2m(no output)\x1b[0m")?;
                     }
                 } else {
-                    println!("│  \x1b[2m(no output)\x1b[0m");
+                    writeln!(out, "│  \x1b[2m(no output)\x1b[0m")?;
                 }
-                print_tool_footer(name);
+                write_tool_footer(out, name)?;
             }
             AgentEvent::ToolFailed { name, error, .. } => {
-                print_result_separator();
-                println!("│  \x1b[31m{}\x1b[0m", error);
-                print_tool_footer(name);
+                write_result_separator(out)?;
+                writeln!(out, "│  \x1b[31m{}\x1b[0m", error)?;
+                write_tool_footer(out, name)?;
+            }
+            AgentEvent::ToolSkippedDryRun { name, .. } => {
+                write_result_separator(out)?;
+                writeln!(out, "│  \x1b[2mskipped (dry run)\x1b[0m")?;
+                write_tool_footer(out, name)?;
+            }
+            AgentEvent::Progress { message, fraction } => {
+                writeln!(out, "{}", format_progress(message, *fraction))?;
             }
             _ => {}
         }
+        Ok(())
+    }
+
+    /// Write one model text delta and flush it so it appears immediately
+    fn write_delta<W: Write>(&self, out: &mut W, delta: &str) -> io::Result<()> {
+        if !self.streams_text() || delta.is_empty() {
+            return Ok(());
+        }
+        let mut state = self.stream_state.lock().unwrap();
+        if !state.streamed {
+            // Match the blank line printed before a non-streamed response
+            writeln!(out)?;
+        }
+        let text = if self.colors_text() {
+            state.markdown.push(delta)
+        } else {
+            // Color may have just been turned off with text still buffered
+            state.markdown.finish() + delta
+        };
+        write!(out, "{}", text)?;
+        out.flush()?;
+        state.streamed = true;
+        if !text.is_empty() {
+            state.line_open = !text.ends_with('\n');
+        }
+        Ok(())
     }
 }
 
@@ -134,7 +284,23 @@ fn format_tool_input(tool_name: &str, formatted: &str, verbosity: Verbosity) ->
     if tool_is_noisy(tool_name) {
         return None;
     }
-    Some(formatted.to_string())
+    Some(truncate_for_display(formatted, MAX_INPUT_DISPLAY_BYTES))
+}
+
+/// Cut text to at most `max_bytes` (on a char boundary) with a `[+N more bytes]` note
+fn truncate_for_display(text: &str, max_bytes: usize) -> String {
+    if text.len() <= max_bytes {
+        return text.to_string();
+    }
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!(
+        "{}\n\x1b[2m[+{} more bytes]\x1b[0m",
+        &text[..end],
+        text.len() - end
+    )
 }
 
 fn format_tool_output(tool_name: &str, formatted: &str, verbosity: Verbosity) -> Option<String> {
@@ -169,32 +335,56 @@ fn tool_is_noisy(tool_name: &str) -> bool {
     )
 }
 
+/// One dimmed status line for a progress report, with a percentage if known
+fn format_progress(message: &str, fraction: Option<f64>) -> String {
+    match fraction {
+        Some(fraction) => dim_text(&format!(
+            "▸ {} ({:.0}%)",
+            message,
+            (fraction * 100.0).clamp(0.0, 100.0)
+        )),
+        None => dim_text(&format!("▸ {}", message)),
+    }
+}
+
 fn dim_text(text: &str) -> String {
     format!("\x1b[2m{}\x1b[0m", text)
 }
 
 /// Print tool header: ┌─ 🛠️  name ───...───┐
 pub fn print_tool_header(name: &str) {
+    let _ = write_tool_header(&mut stdout(), name);
+}
+
+fn write_tool_header<W: Write>(out: &mut W, name: &str) -> io::Result<()> {
     let prefix = format!("┌─ 🛠️  {} ", name);
     let prefix_display_len = 6 + name.len() + 1; // ┌─ + space + emoji(2) + 2 spaces + name + space
     let fill = BOX_WIDTH.saturating_sub(prefix_display_len + 1);
-    println!("\n{}{}┐", prefix, "─".repeat(fill));
-    println!("│");
+    writeln!(out, "\n{}{}┐", prefix, "─".repeat(fill))?;
+    writeln!(out, "│")
 }
 
 /// Print tool footer: └───...─── name ─┘
 pub fn print_tool_footer(name: &str) {
-    println!("│");
+    let _ = write_tool_footer(&mut stdout(), name);
+}
+
+fn write_tool_footer<W: Write>(out: &mut W, name: &str) -> io::Result<()> {
+    writeln!(out, "│")?;
     let suffix = format!(" {} ─┘", name);
     let fill = BOX_WIDTH.saturating_sub(suffix.len() + 1);
-    println!("└{}{}", "─".repeat(fill), suffix);
+    writeln!(out, "└{}{}", "─".repeat(fill), suffix)
 }
 
 /// Print result separator with blank lines
 pub fn print_result_separator() {
-    println!("│");
-    println!("├─ Result");
-    println!("│");
+    let _ = write_result_separator(&mut stdout());
+}
+
+fn write_result_separator<W: Write>(out: &mut W) -> io::Result<()> {
+    writeln!(out, "│")?;
+    writeln!(out, "├─ Result")?;
+    writeln!(out, "│")
 }
 
 pub fn indent_lines(text: &str) -> String {
@@ -331,6 +521,35 @@ mod tests {
                 Some("content".to_string())
             );
         }
+
+        #[test]
+        fn normal_truncates_large_input() {
+            let large = "x".repeat(MAX_INPUT_DISPLAY_BYTES + 500);
+            let output = format_tool_input("bulk_insert", &large, Verbosity::Normal).unwrap();
+
+            assert!(output.starts_with(&"x".repeat(MAX_INPUT_DISPLAY_BYTES)));
+            assert!(output.ends_with("\x1b[2m[+500 more bytes]\x1b[0m"));
+            assert!(output.len() < large.len());
+        }
+
+        #[test]
+        fn verbose_shows_full_large_input() {
+            let large = "x".repeat(MAX_INPUT_DISPLAY_BYTES + 500);
+            assert_eq!(
+                format_tool_input("bulk_insert", &large, Verbosity::Verbose),
+                Some(large)
+            );
+        }
+
+        #[test]
+        fn truncation_respects_char_boundaries() {
+            // 'é' is two bytes, so byte 3 falls inside the second character
+            assert_eq!(
+                truncate_for_display("éé", 3),
+                "é\n\x1b[2m[+2 more bytes]\x1b[0m"
+            );
+            assert_eq!(truncate_for_display("short", 10), "short");
+        }
     }
 
     mod format_tool_output_tests {
@@ -396,6 +615,7 @@ mod tests {
                 tool_use_id: "test-id".to_string(),
                 name: name.to_string(),
                 output: ToolResult::Text("result".to_string()),
+                model_output: None,
                 duration: std::time::Duration::from_millis(100),
             }
         }
@@ -417,6 +637,7 @@ mod tests {
             let hook = PresentationHook::new(Arc::clone(&queue));
 
             hook.on_event(&AgentEvent::RunStarted {
+                agent_name: None,
                 input: "test".to_string(),
                 timestamp: Instant::now(),
             });
@@ -431,4 +652,156 @@ mod tests {
             let _: &dyn AgentHook = &hook;
         }
     }
+
+    mod streaming_tests {
+        use super::*;
+        use crate::repl::formatter::ToolFormatter;
+        use mixtape_core::ToolResult;
+        use serde_json::{json, Value};
+
+        struct PlainFormatter;
+
+        impl ToolFormatter for PlainFormatter {
+            fn format_tool_input(&self, _: &str, input: &Value, _: Display) -> Option<String> {
+                Some(input.to_string())
+            }
+
+            fn format_tool_output(&self, _: &str, _: &ToolResult, _: Display) -> Option<String> {
+                None
+            }
+        }
+
+        fn presenter(stream: bool) -> (EventPresenter<PlainFormatter>, PresentationHook) {
+            let queue = new_event_queue();
+            let presenter = EventPresenter::new(
+                Arc::new(PlainFormatter),
+                Arc::new(Mutex::new(Verbosity::Normal)),
+                Arc::clone(&queue),
+            )
+            .with_stream_text(Arc::new(AtomicBool::new(stream)));
+            (presenter, PresentationHook::new(queue))
+        }
+
+        fn delta(text: &str) -> AgentEvent {
+            AgentEvent::ModelCallStreaming {
+                delta: text.to_string(),
+                accumulated_length: 0,
+            }
+        }
+
+        #[test]
+        fn deltas_written_incrementally_when_streaming() {
+            let (presenter, hook) = presenter(true);
+            let mut out = Vec::new();
+
+            hook.on_event(&delta("Hel"));
+            presenter.flush_to(&mut out);
+            assert_eq!(String::from_utf8_lossy(&out), "\nHel");
+
+            hook.on_event(&delta("lo"));
+            presenter.flush_to(&mut out);
+            assert_eq!(String::from_utf8_lossy(&out), "\nHello");
+
+            assert!(presenter.end_stream_to(&mut out));
+            assert_eq!(String::from_utf8_lossy(&out), "\nHello\n");
+            assert!(!presenter.end_stream_to(&mut out));
+        }
+
+        #[test]
+        fn tool_header_starts_on_new_line_after_streamed_text() {
+            let (presenter, hook) = presenter(true);
+            let mut out = Vec::new();
+
+            hook.on_event(&delta("Let me check."));
+            hook.on_event(&AgentEvent::ToolRequested {
+                tool_use_id: "test-id".to_string(),
+                name: "read_file".to_string(),
+                input: json!({"path": "a.txt"}),
+            });
+            presenter.flush_to(&mut out);
+
+            let output = String::from_utf8_lossy(&out);
+            assert!(output.starts_with("\nLet me check.\n\n┌─ 🛠️  read_file "));
+            assert!(output.contains("│  {\"path\":\"a.txt\"}"));
+
+            // The tool box closed the line, so nothing is left to end
+            let mut tail = Vec::new();
+            assert!(presenter.end_stream_to(&mut tail));
+            assert!(tail.is_empty());
+        }
+
+        #[test]
+        fn progress_shown_as_status_line_instead_of_tool_box() {
+            let (presenter, hook) = presenter(true);
+            let mut out = Vec::new();
+
+            hook.on_event(&delta("Working"));
+            hook.on_event(&AgentEvent::ToolRequested {
+                tool_use_id: "test-id".to_string(),
+                name: EMIT_PROGRESS_TOOL.to_string(),
+                input: json!({"message": "Step 3 of 5", "fraction": 0.6}),
+            });
+            hook.on_event(&AgentEvent::Progress {
+                message: "Step 3 of 5".to_string(),
+                fraction: Some(0.6),
+            });
+            hook.on_event(&AgentEvent::ToolCompleted {
+                tool_use_id: "test-id".to_string(),
+                name: EMIT_PROGRESS_TOOL.to_string(),
+                output: ToolResult::Text(String::new()),
+                model_output: None,
+                duration: std::time::Duration::ZERO,
+            });
+            presenter.flush_to(&mut out);
+
+            assert_eq!(
+                String::from_utf8_lossy(&out),
+                "\nWorking\n\x1b[2m▸ Step 3 of 5 (60%)\x1b[0m\n"
+            );
+        }
+
+        #[test]
+        fn streamed_code_block_highlighted_once_closed() {
+            let (presenter, hook) = presenter(true);
+            let presenter = presenter.with_color(Arc::new(AtomicBool::new(true)));
+            let mut out = Vec::new();
+
+            hook.on_event(&delta("Try **this**:\n```rust\nfn main"));
+            presenter.flush_to(&mut out);
+            assert_eq!(String::from_utf8_lossy(&out), "\nTry \x1b[1mthis\x1b[0m:\n");
+
+            hook.on_event(&delta("() {}\n```\nDone"));
+            presenter.flush_to(&mut out);
+            let output = String::from_utf8_lossy(&out).into_owned();
+            assert!(output.contains("\x1b[2m```rust\x1b[0m\n"));
+            assert!(output.contains("\x1b[38;2;"));
+            assert!(output.ends_with("\x1b[2m```\x1b[0m\n"));
+
+            assert!(presenter.end_stream_to(&mut out));
+            assert!(String::from_utf8_lossy(&out).ends_with("```\x1b[0m\nDone\n"));
+        }
+
+        #[test]
+        fn response_rendered_only_with_color() {
+            let (presenter, _) = presenter(false);
+            assert_eq!(presenter.render_response("**hi**"), "**hi**");
+
+            let presenter = presenter.with_color(Arc::new(AtomicBool::new(true)));
+            assert_eq!(presenter.render_response("**hi**"), "\x1b[1mhi\x1b[0m");
+        }
+
+        #[test]
+        fn deltas_dropped_when_streaming_off() {
+            let (presenter, hook) = presenter(false);
+            let mut out = Vec::new();
+
+            hook.on_event(&delta("Hello"));
+            assert!(presenter.has_pending());
+            presenter.flush_to(&mut out);
+
+            assert!(out.is_empty());
+            assert!(!presenter.has_pending());
+            assert!(!presenter.end_stream_to(&mut out));
+        }
+    }
 }
@@ -0,0 +1,134 @@
+//! Encoding of the serialized message columns
+//!
+//! `tool_calls` and `tool_results` are stored in one of two ways:
+//!
+//! - As TEXT holding JSON, exactly as before other formats existed.
+//! - As a BLOB whose first byte is the format, followed by the payload.
+//!
+//! The format byte records both the serialization and whether the payload
+//! is zstd-compressed, so rows written with any settings stay readable.
+//! Message `content` is always plain TEXT because the search index reads
+//! it directly.
+
+use mixtape_core::session::SessionError;
+use rusqlite::types::Value;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use super::SerializationFormat;
+
+/// Format byte for zstd-compressed JSON
+const FORMAT_ZSTD: u8 = 1;
+
+/// Format byte for MessagePack
+const FORMAT_MSGPACK: u8 = 2;
+
+/// Format byte for zstd-compressed MessagePack
+const FORMAT_ZSTD_MSGPACK: u8 = 3;
+
+/// zstd's default level: fast, with most of the size benefit
+const ZSTD_LEVEL: i32 = 3;
+
+/// Serialize a value for storage
+///
+/// Compression is skipped when it would not make the value smaller, so
+/// short JSON lists such as `[]` stay readable TEXT.
+pub(super) fn encode<T: Serialize>(
+    value: &T,
+    format: SerializationFormat,
+    compress: bool,
+) -> Result<Value, SessionError> {
+    let (payload, plain) = match format {
+        SerializationFormat::Json => (
+            serde_json::to_vec(value).map_err(SessionError::Serialization)?,
+            None,
+        ),
+        SerializationFormat::MessagePack => (
+            rmp_serde::to_vec_named(value).map_err(|e| {
+                SessionError::Storage(format!("Failed to encode MessagePack: {}", e))
+            })?,
+            Some(FORMAT_MSGPACK),
+        ),
+    };
+
+    if compress {
+        let compressed = zstd::encode_all(payload.as_slice(), ZSTD_LEVEL)
+            .map_err(|e| SessionError::Storage(format!("Failed to compress message: {}", e)))?;
+        if compressed.len() + 1 < payload.len() {
+            let tag = match format {
+                SerializationFormat::Json => FORMAT_ZSTD,
+                SerializationFormat::MessagePack => FORMAT_ZSTD_MSGPACK,
+            };
+            return Ok(tagged(tag, compressed));
+        }
+    }
+
+    Ok(match plain {
+        Some(tag) => tagged(tag, payload),
+        None => Value::Text(String::from_utf8(payload).expect("serde_json writes UTF-8")),
+    })
+}
+
+/// Deserialize a stored value
+///
+/// Empty or unparseable payloads yield `None`, leaving the caller to pick a
+/// default; unknown formats and corrupt compressed data are errors.
+pub(super) fn decode<T: DeserializeOwned>(value: Value) -> Result<Option<T>, SessionError> {
+    let (format, payload) = match payload(value)? {
+        Some(decoded) => decoded,
+        None => return Ok(None),
+    };
+    Ok(match format {
+        SerializationFormat::Json => serde_json::from_slice(&payload).ok(),
+        SerializationFormat::MessagePack => rmp_serde::from_slice(&payload).ok(),
+    })
+}
+
+/// Number of bytes a stored value occupies
+pub(super) fn stored_len(value: &Value) -> usize {
+    match value {
+        Value::Text(text) => text.len(),
+        Value::Blob(blob) => blob.len(),
+        _ => 0,
+    }
+}
+
+/// Number of bytes of a stored value's serialized payload, before compression
+pub(super) fn logical_len(value: Value) -> Result<usize, SessionError> {
+    Ok(payload(value)?.map_or(0, |(_, payload)| payload.len()))
+}
+
+fn tagged(tag: u8, payload: Vec<u8>) -> Value {
+    let mut blob = Vec::with_capacity(payload.len() + 1);
+    blob.push(tag);
+    blob.extend(payload);
+    Value::Blob(blob)
+}
+
+/// The serialization format and decompressed payload of a stored value
+fn payload(value: Value) -> Result<Option<(SerializationFormat, Vec<u8>)>, SessionError> {
+    let blob = match value {
+        Value::Text(json) => return Ok(Some((SerializationFormat::Json, json.into_bytes()))),
+        Value::Blob(blob) => blob,
+        _ => return Ok(None),
+    };
+    let Some((&tag, rest)) = blob.split_first() else {
+        return Ok(None);
+    };
+    let decompress = |data: &[u8]| {
+        zstd::decode_all(data)
+            .map_err(|e| SessionError::Storage(format!("Failed to decompress message: {}", e)))
+    };
+    let decoded = match tag {
+        FORMAT_ZSTD => (SerializationFormat::Json, decompress(rest)?),
+        FORMAT_MSGPACK => (SerializationFormat::MessagePack, rest.to_vec()),
+        FORMAT_ZSTD_MSGPACK => (SerializationFormat::MessagePack, decompress(rest)?),
+        format => {
+            return Err(SessionError::Storage(format!(
+                "Unknown message encoding: {}",
+                format
+            )))
+        }
+    };
+    Ok(Some(decoded))
+}
@@ -1,3 +1,4 @@
+mod codec;
 mod store;
 
-pub use store::SqliteStore;
+pub use store::{SerializationFormat, SqliteStore, StorageStats};
@@ -1,17 +1,26 @@
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use mixtape_core::session::{
-    MessageRole, Session, SessionError, SessionMessage, SessionStore, SessionSummary, ToolCall,
-    ToolResult,
+    search_snippet, MessageRole, Session, SessionError, SessionMessage, SessionStore,
+    SessionSummary, ToolCall, ToolResult,
 };
 use rusqlite::{params, Connection, OptionalExtension};
 use std::path::PathBuf;
+
+use super::codec;
 use std::sync::{Arc, Mutex};
 
 /// SQLite-based session storage
 ///
 /// Sessions are stored in a local SQLite database, scoped to the
-/// current working directory.
+/// current working directory. Message content is indexed with FTS5 for
+/// [`search`](SessionStore::search) when the SQLite build supports it;
+/// otherwise search falls back to a `LIKE` scan.
+///
+/// Tool calls and tool results are stored as JSON by default. They can be
+/// stored as MessagePack instead, see
+/// [`with_serialization_format`](Self::with_serialization_format), and
+/// compressed with zstd, see [`with_compression`](Self::with_compression).
 ///
 /// # Example
 /// ```no_run
@@ -26,6 +35,33 @@ use std::sync::{Arc, Mutex};
 /// ```
 pub struct SqliteStore {
     conn: Arc<Mutex<Connection>>,
+    /// Whether the FTS5 message index is available
+    fts: bool,
+    /// Whether to compress tool calls and tool results on save
+    compress: bool,
+    /// Serialization used for tool calls and tool results on save
+    format: SerializationFormat,
+}
+
+/// Serialization used for the tool calls and tool results a [`SqliteStore`] saves
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SerializationFormat {
+    /// JSON, readable with any SQLite client
+    #[default]
+    Json,
+    /// MessagePack, smaller and faster to parse than JSON
+    MessagePack,
+}
+
+/// Size of the message data held by a [`SqliteStore`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StorageStats {
+    /// Number of stored messages
+    pub messages: usize,
+    /// Bytes of message data as stored, after compression
+    pub stored_bytes: usize,
+    /// Bytes of message data before compression, in its stored format
+    pub logical_bytes: usize,
 }
 
 impl SqliteStore {
@@ -48,18 +84,193 @@ impl SqliteStore {
         // Initialize schema
         conn.execute_batch(include_str!("schema.sql"))
             .map_err(|e| SessionError::Storage(format!("Failed to initialize schema: {}", e)))?;
+        let fts = init_fts(&conn)?;
 
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
+            fts,
+            compress: false,
+            format: SerializationFormat::default(),
         })
     }
 
+    /// Compress tool calls and tool results with zstd when saving
+    ///
+    /// Rows are tagged with their format, so a database can mix compressed
+    /// and uncompressed rows and is readable whether or not compression is
+    /// enabled. Existing rows are rewritten in the new format the next time
+    /// their session is saved. Message text is never compressed, so search
+    /// is unaffected.
+    pub fn with_compression(mut self, enabled: bool) -> Self {
+        self.compress = enabled;
+        self
+    }
+
+    /// Serialize tool calls and tool results with `format` when saving
+    ///
+    /// Like compression, the format is recorded per row: existing JSON rows
+    /// stay readable after switching to MessagePack and back, and are
+    /// rewritten the next time their session is saved. The two settings
+    /// are independent, so either format can be compressed.
+    pub fn with_serialization_format(mut self, format: SerializationFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Measure the stored and logical size of all message data
+    pub fn storage_stats(&self) -> Result<StorageStats, SessionError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT content, tool_calls, tool_results FROM messages")
+            .map_err(|e| SessionError::Storage(e.to_string()))?;
+        let rows = stmt
+            .query_map(params![], |row| {
+                Ok((row.get::<_, String>(0)?, row.get(1)?, row.get(2)?))
+            })
+            .map_err(|e| SessionError::Storage(e.to_string()))?;
+
+        let mut stats = StorageStats::default();
+        for row in rows {
+            let (content, tool_calls, tool_results) =
+                row.map_err(|e| SessionError::Storage(e.to_string()))?;
+            stats.messages += 1;
+            stats.stored_bytes +=
+                content.len() + codec::stored_len(&tool_calls) + codec::stored_len(&tool_results);
+            stats.logical_bytes +=
+                content.len() + codec::logical_len(tool_calls)? + codec::logical_len(tool_results)?;
+        }
+        Ok(stats)
+    }
+
     /// Use default location (.mixtape/sessions.db in current directory)
     pub fn default_location() -> Result<Self, SessionError> {
         Self::new(".mixtape/sessions.db")
     }
 }
 
+/// Create the FTS5 message index, returning false if FTS5 is unavailable
+///
+/// A database created before the index existed is backfilled once.
+fn init_fts(conn: &Connection) -> Result<bool, SessionError> {
+    let existed: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE name = 'messages_fts')",
+            params![],
+            |row| row.get(0),
+        )
+        .map_err(|e| SessionError::Storage(e.to_string()))?;
+
+    if conn.execute_batch(include_str!("fts.sql")).is_err() {
+        return Ok(false);
+    }
+    if !existed {
+        conn.execute(
+            "INSERT INTO messages_fts(messages_fts) VALUES ('rebuild')",
+            params![],
+        )
+        .map_err(|e| SessionError::Storage(format!("Failed to build search index: {}", e)))?;
+    }
+    Ok(true)
+}
+
+/// Convert free text into an FTS5 query matching every term as a prefix
+///
+/// Terms are quoted so punctuation and FTS5 operators in the input are
+/// treated as literal text.
+fn fts_query(query: &str) -> String {
+    query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"*", term.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Escape `LIKE` wildcards so the query matches literally
+fn like_pattern(query: &str) -> String {
+    let escaped = query
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+type SearchRow = (String, String, i64, i64, i64, String);
+
+/// Rank sessions by their best-matching message (BM25), then recency
+fn search_fts(
+    conn: &Connection,
+    query: &str,
+    limit: usize,
+) -> Result<Vec<SearchRow>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "WITH hits AS (
+             SELECT m.session_id, bm25(messages_fts) AS score,
+                    snippet(messages_fts, 0, '', '', '…', 16) AS snippet
+             FROM messages_fts JOIN messages m ON m.id = messages_fts.rowid
+             WHERE messages_fts MATCH ?1
+         ), best AS (
+             SELECT session_id, score, snippet,
+                    ROW_NUMBER() OVER (PARTITION BY session_id ORDER BY score) AS rn
+             FROM hits
+         )
+         SELECT s.id, s.directory, s.created_at, s.updated_at,
+                (SELECT COUNT(*) FROM messages c WHERE c.session_id = s.id),
+                best.snippet
+         FROM best JOIN sessions s ON s.id = best.session_id
+         WHERE best.rn = 1
+         ORDER BY best.score, s.updated_at DESC
+         LIMIT ?2",
+    )?;
+    let rows = stmt.query_map(params![fts_query(query), limit as i64], search_row)?;
+    rows.collect()
+}
+
+/// Rank sessions by number of matching messages, then recency
+fn search_like(
+    conn: &Connection,
+    query: &str,
+    limit: usize,
+) -> Result<Vec<SearchRow>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT s.id, s.directory, s.created_at, s.updated_at,
+                (SELECT COUNT(*) FROM messages c WHERE c.session_id = s.id),
+                (SELECT x.content FROM messages x
+                 WHERE x.session_id = s.id AND x.content LIKE ?1 ESCAPE '\\'
+                 ORDER BY x.idx LIMIT 1)
+         FROM messages m JOIN sessions s ON s.id = m.session_id
+         WHERE m.content LIKE ?1 ESCAPE '\\'
+         GROUP BY s.id
+         ORDER BY COUNT(*) DESC, s.updated_at DESC
+         LIMIT ?2",
+    )?;
+    let rows = stmt
+        .query_map(params![like_pattern(query), limit as i64], search_row)?
+        .map(|row| {
+            row.map(|(id, dir, created, updated, count, content)| {
+                (
+                    id,
+                    dir,
+                    created,
+                    updated,
+                    count,
+                    search_snippet(&content, query),
+                )
+            })
+        });
+    rows.collect()
+}
+
+fn search_row(row: &rusqlite::Row<'_>) -> Result<SearchRow, rusqlite::Error> {
+    Ok((
+        row.get(0)?,
+        row.get(1)?,
+        row.get(2)?,
+        row.get(3)?,
+        row.get(4)?,
+        row.get(5)?,
+    ))
+}
+
 #[async_trait]
 impl SessionStore for SqliteStore {
     async fn get_or_create_session(&self) -> Result<Session, SessionError> {
@@ -147,8 +358,8 @@ impl SessionStore for SqliteStore {
                 Ok((
                     row.get::<_, String>(0)?,
                     row.get::<_, String>(1)?,
-                    row.get::<_, String>(2)?,
-                    row.get::<_, String>(3)?,
+                    row.get(2)?,
+                    row.get(3)?,
                     row.get::<_, i64>(4)?,
                 ))
             })
@@ -156,30 +367,26 @@ impl SessionStore for SqliteStore {
             .collect::<Result<Vec<_>, _>>()
             .map_err(|e| SessionError::Storage(e.to_string()))?
             .into_iter()
-            .map(
-                |(role, content, tool_calls_json, tool_results_json, timestamp)| {
-                    let role = match role.as_str() {
-                        "User" => MessageRole::User,
-                        "Assistant" => MessageRole::Assistant,
-                        "System" => MessageRole::System,
-                        _ => MessageRole::User,
-                    };
-
-                    let tool_calls: Vec<ToolCall> =
-                        serde_json::from_str(&tool_calls_json).unwrap_or_default();
-                    let tool_results: Vec<ToolResult> =
-                        serde_json::from_str(&tool_results_json).unwrap_or_default();
-
-                    SessionMessage {
-                        role,
-                        content,
-                        tool_calls,
-                        tool_results,
-                        timestamp: DateTime::from_timestamp(timestamp, 0).unwrap_or(Utc::now()),
-                    }
-                },
-            )
-            .collect();
+            .map(|(role, content, tool_calls, tool_results, timestamp)| {
+                let tool_calls: Vec<ToolCall> = codec::decode(tool_calls)?.unwrap_or_default();
+                let tool_results: Vec<ToolResult> =
+                    codec::decode(tool_results)?.unwrap_or_default();
+                let role = match role.as_str() {
+                    "User" => MessageRole::User,
+                    "Assistant" => MessageRole::Assistant,
+                    "System" => MessageRole::System,
+                    _ => MessageRole::User,
+                };
+
+                Ok(SessionMessage {
+                    role,
+                    content,
+                    tool_calls,
+                    tool_results,
+                    timestamp: DateTime::from_timestamp(timestamp, 0).unwrap_or(Utc::now()),
+                })
+            })
+            .collect::<Result<_, SessionError>>()?;
 
         Ok(Some(Session {
             id,
@@ -222,10 +429,8 @@ impl SessionStore for SqliteStore {
 
         // Insert new messages
         for (idx, msg) in session.messages.iter().enumerate() {
-            let tool_calls_json =
-                serde_json::to_string(&msg.tool_calls).map_err(SessionError::Serialization)?;
-            let tool_results_json =
-                serde_json::to_string(&msg.tool_results).map_err(SessionError::Serialization)?;
+            let tool_calls = codec::encode(&msg.tool_calls, self.format, self.compress)?;
+            let tool_results = codec::encode(&msg.tool_results, self.format, self.compress)?;
 
             tx.execute(
                 "INSERT INTO messages (session_id, idx, role, content, tool_calls, tool_results, timestamp)
@@ -235,8 +440,8 @@ impl SessionStore for SqliteStore {
                     idx as i64,
                     format!("{:?}", msg.role),
                     msg.content,
-                    tool_calls_json,
-                    tool_results_json,
+                    tool_calls,
+                    tool_results,
                     msg.timestamp.timestamp(),
                 ],
             )
@@ -284,6 +489,7 @@ impl SessionStore for SqliteStore {
                     message_count,
                     created_at: DateTime::from_timestamp(created_at, 0).unwrap_or(Utc::now()),
                     updated_at: DateTime::from_timestamp(updated_at, 0).unwrap_or(Utc::now()),
+                    snippet: None,
                 },
             )
             .collect();
@@ -291,6 +497,35 @@ impl SessionStore for SqliteStore {
         Ok(sessions)
     }
 
+    async fn search(&self, query: &str, limit: usize) -> Result<Vec<SessionSummary>, SessionError> {
+        let query = query.trim();
+        if query.is_empty() || limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let conn = self.conn.lock().unwrap();
+        let rows = if self.fts {
+            search_fts(&conn, query, limit)
+        } else {
+            search_like(&conn, query, limit)
+        }
+        .map_err(|e| SessionError::Storage(format!("Search failed: {}", e)))?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(id, directory, created_at, updated_at, message_count, snippet)| SessionSummary {
+                    id,
+                    directory,
+                    message_count: message_count as usize,
+                    created_at: DateTime::from_timestamp(created_at, 0).unwrap_or(Utc::now()),
+                    updated_at: DateTime::from_timestamp(updated_at, 0).unwrap_or(Utc::now()),
+                    snippet: Some(snippet),
+                },
+            )
+            .collect())
+    }
+
     async fn delete_session(&self, id: &str) -> Result<(), SessionError> {
         let conn = self.conn.lock().unwrap();
 
@@ -806,4 +1041,347 @@ mod tests {
         assert_eq!(loaded.messages[0].tool_calls[0].name, "工具");
         assert_eq!(loaded.messages[0].tool_results[0].content, "Ελληνικά");
     }
+
+    /// Insert a session in `directory` holding user messages with `contents`
+    async fn insert_session(store: &SqliteStore, directory: &str, contents: &[&str]) -> String {
+        let id = uuid::Uuid::new_v4().to_string();
+        {
+            let conn = store.conn.lock().unwrap();
+            conn.execute(
+                "INSERT INTO sessions (id, directory, created_at, updated_at) VALUES (?, ?, 0, 0)",
+                params![id, directory],
+            )
+            .unwrap();
+        }
+        let session = Session {
+            id: id.clone(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            directory: directory.to_string(),
+            messages: contents
+                .iter()
+                .map(|content| SessionMessage {
+                    role: MessageRole::User,
+                    content: content.to_string(),
+                    tool_calls: vec![],
+                    tool_results: vec![],
+                    timestamp: Utc::now(),
+                })
+                .collect(),
+        };
+        store.save_session(&session).await.unwrap();
+        id
+    }
+
+    async fn search_store() -> (TempDir, SqliteStore, String, String) {
+        let temp_dir = TempDir::new().unwrap();
+        let store = SqliteStore::new(temp_dir.path().join("test.db")).unwrap();
+        let migration = insert_session(
+            &store,
+            "/proj/api",
+            &[
+                "The users migration fails with a duplicate column error",
+                "Try dropping the index before rerunning the migration",
+            ],
+        )
+        .await;
+        insert_session(&store, "/proj/web", &["Center the login form"]).await;
+        let mentions = insert_session(
+            &store,
+            "/proj/docs",
+            &["Write release notes mentioning the migration guide"],
+        )
+        .await;
+        (temp_dir, store, migration, mentions)
+    }
+
+    #[tokio::test]
+    async fn test_search_uses_fts_index() {
+        let (_temp_dir, store, migration, mentions) = search_store().await;
+        assert!(store.fts, "bundled SQLite should support FTS5");
+
+        let results = store.search("migration", 10).await.unwrap();
+        let ids: Vec<&str> = results.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids.len(), 2);
+        assert!(ids.contains(&migration.as_str()));
+        assert!(ids.contains(&mentions.as_str()));
+
+        let api = results.iter().find(|s| s.id == migration).unwrap();
+        assert_eq!(api.directory, "/proj/api");
+        assert_eq!(api.message_count, 2);
+        assert!(api.snippet.as_deref().unwrap().contains("migration"));
+
+        // All terms must match, as prefixes, in the same message
+        let results = store.search("duplicate colum", 10).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, migration);
+
+        assert_eq!(store.search("migration", 1).await.unwrap().len(), 1);
+        assert!(store.search("kubernetes", 10).await.unwrap().is_empty());
+        assert!(store.search("  ", 10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_treats_operators_as_text() {
+        let (_temp_dir, store, _, _) = search_store().await;
+
+        for query in ["\"unbalanced", "NOT AND OR", "column:value", "a*b (c)"] {
+            assert!(store.search(query, 10).await.is_ok(), "query {:?}", query);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_index_tracks_saves_and_deletes() {
+        let (_temp_dir, store, migration, _) = search_store().await;
+
+        let mut session = store.get_session(&migration).await.unwrap().unwrap();
+        session.messages.truncate(1);
+        session.messages[0].content = "Fixed the flaky websocket test".to_string();
+        store.save_session(&session).await.unwrap();
+
+        let results = store.search("migration", 10).await.unwrap();
+        assert!(results.iter().all(|s| s.id != migration));
+        assert_eq!(
+            store.search("websocket", 10).await.unwrap()[0].id,
+            migration
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_backfills_existing_database() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let id = {
+            let store = SqliteStore::new(&db_path).unwrap();
+            let id = insert_session(&store, "/proj", &["remember the backfill"]).await;
+            // Simulate a database created before the index existed
+            let conn = store.conn.lock().unwrap();
+            conn.execute_batch(
+                "DROP TRIGGER messages_fts_insert;
+                 DROP TRIGGER messages_fts_delete;
+                 DROP TRIGGER messages_fts_update;
+                 DROP TABLE messages_fts;",
+            )
+            .unwrap();
+            id
+        };
+
+        let store = SqliteStore::new(&db_path).unwrap();
+        let results = store.search("backfill", 10).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, id);
+    }
+
+    #[tokio::test]
+    async fn test_search_like_fallback() {
+        let (_temp_dir, store, migration, mentions) = search_store().await;
+        let conn = store.conn.lock().unwrap();
+
+        let rows = search_like(&conn, "MIGRATION", 10).unwrap();
+        let ids: Vec<&str> = rows.iter().map(|r| r.0.as_str()).collect();
+        // The session with two matching messages ranks first
+        assert_eq!(ids, vec![migration.as_str(), mentions.as_str()]);
+        assert!(rows[0].5.contains("migration fails"));
+
+        assert!(search_like(&conn, "100%", 10).unwrap().is_empty());
+        assert_eq!(search_like(&conn, "migration", 1).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_fts_query_quotes_terms() {
+        assert_eq!(fts_query("debug  migration"), "\"debug\"* \"migration\"*");
+        assert_eq!(fts_query("say \"hi\""), "\"say\"* \"\"\"hi\"\"\"*");
+    }
+
+    #[test]
+    fn test_like_pattern_escapes_wildcards() {
+        assert_eq!(like_pattern("50%_off"), "%50\\%\\_off%");
+    }
+
+    #[tokio::test]
+    async fn test_compressed_large_message_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = SqliteStore::new(temp_dir.path().join("test.db"))
+            .unwrap()
+            .with_compression(true);
+
+        let mut session = store.get_or_create_session().await.unwrap();
+        let output = "line of tool output\n".repeat(10_000);
+        session.messages.push(SessionMessage {
+            role: MessageRole::Assistant,
+            content: "Read the log".to_string(),
+            tool_calls: vec![ToolCall {
+                id: "call_1".to_string(),
+                name: "read_file".to_string(),
+                input: r#"{"path": "app.log"}"#.to_string(),
+            }],
+            tool_results: vec![ToolResult {
+                tool_use_id: "call_1".to_string(),
+                success: true,
+                content: output.clone(),
+            }],
+            timestamp: Utc::now(),
+        });
+        store.save_session(&session).await.unwrap();
+
+        let loaded = store.get_session(&session.id).await.unwrap().unwrap();
+        assert_eq!(loaded.messages[0].tool_results[0].content, output);
+        assert_eq!(loaded.messages[0].tool_calls[0].name, "read_file");
+
+        let stats = store.storage_stats().unwrap();
+        assert_eq!(stats.messages, 1);
+        assert!(stats.logical_bytes > output.len());
+        assert!(stats.stored_bytes * 10 < stats.logical_bytes);
+    }
+
+    #[tokio::test]
+    async fn test_compression_reads_legacy_rows() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = SqliteStore::new(temp_dir.path().join("test.db"))
+            .unwrap()
+            .with_compression(true);
+
+        // A row written as plain TEXT before compression existed
+        let id = insert_session(&store, "/project", &[]).await;
+        {
+            let conn = store.conn.lock().unwrap();
+            conn.execute(
+                "INSERT INTO messages (session_id, idx, role, content, tool_calls, tool_results, timestamp)
+                 VALUES (?, 0, 'User', 'Hello', '[]', ?, 0)",
+                params![
+                    id,
+                    r#"[{"tool_use_id":"call_1","success":true,"content":"legacy"}]"#
+                ],
+            )
+            .unwrap();
+        }
+
+        let loaded = store.get_session(&id).await.unwrap().unwrap();
+        assert_eq!(loaded.messages[0].content, "Hello");
+        assert_eq!(loaded.messages[0].tool_results[0].content, "legacy");
+
+        let stats = store.storage_stats().unwrap();
+        assert_eq!(stats.stored_bytes, stats.logical_bytes);
+    }
+
+    #[test]
+    fn test_codec_rejects_unknown_format() {
+        assert!(
+            codec::decode::<Vec<ToolCall>>(rusqlite::types::Value::Blob(vec![9, 1, 2])).is_err()
+        );
+        let encoded =
+            codec::encode(&Vec::<ToolCall>::new(), SerializationFormat::Json, true).unwrap();
+        assert_eq!(encoded, rusqlite::types::Value::Text("[]".to_string()));
+        let decoded: Option<Vec<ToolCall>> = codec::decode(encoded).unwrap();
+        assert_eq!(decoded.map(|calls| calls.len()), Some(0));
+    }
+
+    fn tool_message(output: &str) -> SessionMessage {
+        SessionMessage {
+            role: MessageRole::Assistant,
+            content: "Read the log".to_string(),
+            tool_calls: vec![ToolCall {
+                id: "call_1".to_string(),
+                name: "read_file".to_string(),
+                input: r#"{"path": "app.log"}"#.to_string(),
+            }],
+            tool_results: vec![ToolResult {
+                tool_use_id: "call_1".to_string(),
+                success: false,
+                content: output.to_string(),
+            }],
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_message_pack_round_trip() {
+        for compress in [false, true] {
+            let temp_dir = TempDir::new().unwrap();
+            let store = SqliteStore::new(temp_dir.path().join("test.db"))
+                .unwrap()
+                .with_serialization_format(SerializationFormat::MessagePack)
+                .with_compression(compress);
+
+            let mut session = store.get_or_create_session().await.unwrap();
+            let output = "permission denied\n".repeat(500);
+            session.messages.push(tool_message(&output));
+            session.messages.push(SessionMessage {
+                role: MessageRole::User,
+                content: "Thanks".to_string(),
+                tool_calls: vec![],
+                tool_results: vec![],
+                timestamp: Utc::now(),
+            });
+            store.save_session(&session).await.unwrap();
+
+            let loaded = store.get_session(&session.id).await.unwrap().unwrap();
+            let message = &loaded.messages[0];
+            assert_eq!(message.tool_calls[0].id, "call_1");
+            assert_eq!(message.tool_calls[0].name, "read_file");
+            assert_eq!(message.tool_calls[0].input, r#"{"path": "app.log"}"#);
+            assert_eq!(message.tool_results[0].tool_use_id, "call_1");
+            assert!(!message.tool_results[0].success);
+            assert_eq!(message.tool_results[0].content, output);
+            assert!(loaded.messages[1].tool_calls.is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_serialization_formats_share_a_database() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("test.db");
+
+        let json_store = SqliteStore::new(&path).unwrap();
+        let mut json_session = json_store.get_or_create_session().await.unwrap();
+        json_session.messages.push(tool_message("from json"));
+        json_store.save_session(&json_session).await.unwrap();
+
+        let msgpack_store = SqliteStore::new(&path)
+            .unwrap()
+            .with_serialization_format(SerializationFormat::MessagePack);
+        let msgpack_id = insert_session(&msgpack_store, "/other", &[]).await;
+        let mut msgpack_session = msgpack_store
+            .get_session(&msgpack_id)
+            .await
+            .unwrap()
+            .unwrap();
+        msgpack_session.messages.push(tool_message("from msgpack"));
+        msgpack_store.save_session(&msgpack_session).await.unwrap();
+
+        for store in [&json_store, &msgpack_store] {
+            let json = store.get_session(&json_session.id).await.unwrap().unwrap();
+            assert_eq!(json.messages[0].tool_results[0].content, "from json");
+            let msgpack = store.get_session(&msgpack_id).await.unwrap().unwrap();
+            assert_eq!(msgpack.messages[0].tool_results[0].content, "from msgpack");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_message_pack_is_smaller_than_json() {
+        let mut sizes = Vec::new();
+        for format in [SerializationFormat::Json, SerializationFormat::MessagePack] {
+            let temp_dir = TempDir::new().unwrap();
+            let store = SqliteStore::new(temp_dir.path().join("test.db"))
+                .unwrap()
+                .with_serialization_format(format);
+
+            let mut session = store.get_or_create_session().await.unwrap();
+            for i in 0..20 {
+                session
+                    .messages
+                    .push(tool_message(&format!("result {}: \"quoted\"\n", i)));
+            }
+            store.save_session(&session).await.unwrap();
+            sizes.push(store.storage_stats().unwrap().stored_bytes);
+        }
+
+        let (json, msgpack) = (sizes[0], sizes[1]);
+        assert!(
+            msgpack < json,
+            "MessagePack ({} bytes) should be smaller than JSON ({} bytes)",
+            msgpack,
+            json
+        );
+    }
 }
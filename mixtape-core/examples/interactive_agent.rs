@@ -129,8 +129,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .add_tool(InteractWithProcessTool)
         .add_tool(KillProcessTool)
         .add_tool(ForceTerminateTool)
-        .add_tool(ListProcessesTool)
-        .add_tool(ListSessionsTool)
+        .add_tool(ListProcessesTool::new())
+        .add_tool(ListSessionsTool::new())
         // Web and search tools
         .add_tool(FetchTool::new())
         .add_tool(SearchTool::new())
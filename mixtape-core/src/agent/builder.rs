@@ -14,13 +14,22 @@ use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::RwLock;
 
-use crate::conversation::{BoxedConversationManager, SlidingWindowConversationManager};
+use crate::conversation::{BoxedConversationManager, ConversationStrategy};
+use crate::filter::ContentFilter;
+use crate::id::{IdGenerator, UuidIdGenerator};
+use crate::interceptor::RequestInterceptor;
 use crate::permission::{GrantStore, ToolAuthorizationPolicy, ToolCallAuthorizer};
-use crate::provider::ModelProvider;
-use crate::tool::{box_tool, DynTool, Tool};
+use crate::provider::{Feature, ModelProvider};
+use crate::tool::{box_tool, DynTool, JsonPointerTool, Tool};
 
 use super::context::{ContextConfig, ContextSource};
-use super::types::{DEFAULT_MAX_CONCURRENT_TOOLS, DEFAULT_PERMISSION_TIMEOUT};
+use super::hooks::HookRegistry;
+use super::repair::ToolUseRepair;
+use super::streaming::EmptyResponsePolicy;
+use super::tool_policy::ToolPolicy;
+use super::types::{
+    OverflowPolicy, RefusalPolicy, DEFAULT_MAX_CONCURRENT_TOOLS, DEFAULT_PERMISSION_TIMEOUT,
+};
 use super::Agent;
 
 #[cfg(feature = "session")]
@@ -43,6 +52,9 @@ type ProviderFactory = Box<
         + Send,
 >;
 
+/// Deferred tool construction, resolved when the agent is built
+type ToolSource = Pin<Box<dyn Future<Output = crate::error::Result<Vec<Box<dyn DynTool>>>> + Send>>;
+
 /// Builder for creating an Agent with fluent configuration
 ///
 /// Use `Agent::builder()` to create a new builder, configure it with
@@ -71,8 +83,12 @@ type ProviderFactory = Box<
 pub struct AgentBuilder {
     provider_factory: Option<ProviderFactory>,
     tools: Vec<Box<dyn DynTool>>,
+    tool_sources: Vec<ToolSource>,
+    name: Option<String>,
     system_prompt: Option<String>,
     max_concurrent_tools: usize,
+    /// Runtime for blocking tools; tokio's blocking pool when unset
+    tool_runtime: Option<tokio::runtime::Handle>,
     /// Custom grant store (if None, uses MemoryGrantStore)
     pub(super) grant_store: Option<Box<dyn GrantStore>>,
     /// Policy for tools without grants (default: AutoDeny)
@@ -82,13 +98,36 @@ pub struct AgentBuilder {
     /// Tools to automatically grant permissions for
     trusted_tools: Vec<String>,
     conversation_manager: Option<BoxedConversationManager>,
+    interceptors: Vec<Arc<dyn RequestInterceptor>>,
+    content_filters: Vec<Arc<dyn ContentFilter>>,
+    id_generator: Arc<dyn IdGenerator>,
+    tool_use_repair: ToolUseRepair,
+    empty_response_policy: EmptyResponsePolicy,
+    refusal_policy: RefusalPolicy,
+    overflow_policy: OverflowPolicy,
+    input_token_warning: Option<usize>,
+    tool_json_recovery: Option<usize>,
+    dry_run: bool,
+    stale_prefix_uncached: bool,
+    self_introspection: bool,
+    progress_tool: bool,
+    max_tool_result_bytes: Option<usize>,
+    tool_policy: ToolPolicy,
+    parallel_tool_use: Option<bool>,
+    seed: Option<u64>,
+    required_features: Vec<Feature>,
+    credential_check: bool,
     #[cfg(feature = "session")]
     session_store: Option<Arc<dyn SessionStore>>,
+    #[cfg(feature = "session")]
+    incremental_session_save: bool,
     // MCP fields - configured via mcp.rs
     #[cfg(feature = "mcp")]
     pub(super) mcp_servers: Vec<crate::mcp::McpServerConfig>,
     #[cfg(feature = "mcp")]
     pub(super) mcp_config_files: Vec<std::path::PathBuf>,
+    #[cfg(feature = "mcp")]
+    pub(super) mcp_best_effort: bool,
     // Context file fields
     /// Context file sources (resolved at runtime)
     context_sources: Vec<ContextSource>,
@@ -108,19 +147,45 @@ impl AgentBuilder {
         Self {
             provider_factory: None,
             tools: Vec::new(),
+            tool_sources: Vec::new(),
+            name: None,
             system_prompt: None,
             max_concurrent_tools: DEFAULT_MAX_CONCURRENT_TOOLS,
+            tool_runtime: None,
             grant_store: None,
             authorization_policy: ToolAuthorizationPolicy::default(), // AutoDeny by default
             authorization_timeout: DEFAULT_PERMISSION_TIMEOUT,
             trusted_tools: Vec::new(),
             conversation_manager: None,
+            interceptors: Vec::new(),
+            content_filters: Vec::new(),
+            id_generator: Arc::new(UuidIdGenerator),
+            tool_use_repair: ToolUseRepair::default(),
+            empty_response_policy: EmptyResponsePolicy::default(),
+            refusal_policy: RefusalPolicy::default(),
+            overflow_policy: OverflowPolicy::default(),
+            input_token_warning: None,
+            tool_json_recovery: None,
+            dry_run: false,
+            stale_prefix_uncached: false,
+            self_introspection: false,
+            progress_tool: false,
+            max_tool_result_bytes: None,
+            tool_policy: ToolPolicy::default(),
+            parallel_tool_use: None,
+            seed: None,
+            required_features: Vec::new(),
+            credential_check: false,
             #[cfg(feature = "session")]
             session_store: None,
+            #[cfg(feature = "session")]
+            incremental_session_save: false,
             #[cfg(feature = "mcp")]
             mcp_servers: Vec::new(),
             #[cfg(feature = "mcp")]
             mcp_config_files: Vec::new(),
+            #[cfg(feature = "mcp")]
+            mcp_best_effort: false,
             context_sources: Vec::new(),
             context_config: ContextConfig::default(),
         }
@@ -261,6 +326,30 @@ impl AgentBuilder {
         self
     }
 
+    /// Add a tool whose JSON results are narrowed with a JSON pointer
+    ///
+    /// Only the value at `pointer` (RFC 6901, e.g. `/items/0/name`) of each
+    /// [`ToolResult::Json`](crate::ToolResult::Json) result reaches the
+    /// model, saving tokens when a tool returns more than is needed. See
+    /// [`JsonPointerTool`].
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let agent = Agent::builder()
+    ///     .bedrock(ClaudeHaiku4_5)
+    ///     .add_tool_extracting(WeatherLookup, "/current/temperature")
+    ///     .build()
+    ///     .await?;
+    /// ```
+    pub fn add_tool_extracting(
+        self,
+        tool: impl Tool + 'static,
+        pointer: impl Into<String>,
+    ) -> Self {
+        self.add_tool(JsonPointerTool::new(tool, pointer))
+    }
+
     /// Add multiple tools to the agent
     ///
     /// Accepts pre-boxed dynamic tools, typically from tool group helper functions.
@@ -289,6 +378,65 @@ impl AgentBuilder {
         self
     }
 
+    /// Add tools from a source that may fail, such as a database-backed tool group
+    ///
+    /// The source runs when [`build`](Self::build) is called. If it returns an
+    /// error, `build()` fails with [`Error::ToolInit`](crate::Error::ToolInit)
+    /// carrying the source's error message.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let agent = Agent::builder()
+    ///     .bedrock(ClaudeHaiku4_5)
+    ///     .try_add_tools(|| -> Result<_, std::io::Error> {
+    ///         let config = std::fs::read_to_string("tools.toml")?;
+    ///         Ok(tools_from_config(&config))
+    ///     })
+    ///     .build()
+    ///     .await?;
+    /// ```
+    pub fn try_add_tools<F, E>(self, source: F) -> Self
+    where
+        F: FnOnce() -> Result<Vec<Box<dyn DynTool>>, E> + Send + 'static,
+        E: std::fmt::Display,
+    {
+        self.add_tools_async(async move { source() })
+    }
+
+    /// Add tools from an async source that may fail, such as one that opens a
+    /// connection
+    ///
+    /// The future is awaited when [`build`](Self::build) is called. If it
+    /// returns an error, `build()` fails with
+    /// [`Error::ToolInit`](crate::Error::ToolInit) carrying the source's error
+    /// message.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let agent = Agent::builder()
+    ///     .bedrock(ClaudeHaiku4_5)
+    ///     .add_tools_async(async {
+    ///         let pool = connect("postgres://localhost/app").await?;
+    ///         Ok::<_, DbError>(database_tools(pool))
+    ///     })
+    ///     .build()
+    ///     .await?;
+    /// ```
+    pub fn add_tools_async<F, E>(mut self, source: F) -> Self
+    where
+        F: Future<Output = Result<Vec<Box<dyn DynTool>>, E>> + Send + 'static,
+        E: std::fmt::Display,
+    {
+        self.tool_sources.push(Box::pin(async move {
+            source
+                .await
+                .map_err(|e| crate::error::Error::ToolInit(e.to_string()))
+        }));
+        self
+    }
+
     /// Add multiple trusted tools to the agent with automatic permission grants
     ///
     /// This is a convenience method that adds the tools and automatically grants
@@ -315,6 +463,17 @@ impl AgentBuilder {
         self
     }
 
+    /// Set the agent's name
+    ///
+    /// The name identifies the agent in multi-agent transcripts and is
+    /// reported in [`AgentEvent::RunStarted`](crate::AgentEvent::RunStarted)
+    /// and [`AgentEvent::RunCompleted`](crate::AgentEvent::RunCompleted).
+    /// It is not sent to the model.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
     /// Set the system prompt
     pub fn with_system_prompt(mut self, prompt: impl Into<String>) -> Self {
         self.system_prompt = Some(prompt.into());
@@ -327,6 +486,32 @@ impl AgentBuilder {
         self
     }
 
+    /// Run [blocking](crate::tool::Tool::is_blocking) tools on a dedicated runtime
+    ///
+    /// By default blocking tools run on the calling runtime's blocking thread
+    /// pool. A separate multi-threaded runtime caps the threads they can use
+    /// and lets aborted calls be cancelled at their next `.await`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let tool_runtime = tokio::runtime::Builder::new_multi_thread()
+    ///     .worker_threads(2)
+    ///     .enable_all()
+    ///     .build()?;
+    ///
+    /// let agent = Agent::builder()
+    ///     .bedrock(ClaudeSonnet4_5)
+    ///     .add_tool(ImageResizer)
+    ///     .with_tool_runtime(tool_runtime.handle().clone())
+    ///     .build()
+    ///     .await?;
+    /// ```
+    pub fn with_tool_runtime(mut self, handle: tokio::runtime::Handle) -> Self {
+        self.tool_runtime = Some(handle);
+        self
+    }
+
     // Authorization methods are in permission.rs:
     // - with_grant_store
     // - with_authorization_timeout
@@ -340,6 +525,317 @@ impl AgentBuilder {
         self
     }
 
+    /// Select a built-in conversation manager
+    ///
+    /// Defaults to [`ConversationStrategy::SlidingWindow`], which sizes the
+    /// context to the provider's context window. See [`ConversationStrategy`]
+    /// for the trade-offs of each. Replaces any manager set with
+    /// [`with_conversation_manager`](Self::with_conversation_manager).
+    pub fn with_conversation_strategy(mut self, strategy: ConversationStrategy) -> Self {
+        self.conversation_manager = Some(strategy.into_manager());
+        self
+    }
+
+    /// Set how tool uses without a matching tool result are repaired
+    ///
+    /// A run interrupted after the model requested a tool but before the
+    /// result was recorded leaves a tool use that providers reject. The
+    /// agent repairs these in history loaded from a session and in the
+    /// messages sent with each model call. Defaults to
+    /// [`ToolUseRepair::InterruptedResult`].
+    pub fn with_tool_use_repair(mut self, repair: ToolUseRepair) -> Self {
+        self.tool_use_repair = repair;
+        self
+    }
+
+    /// Set what happens when the model returns an assistant turn with no
+    /// content
+    ///
+    /// Defaults to [`EmptyResponsePolicy::Error`].
+    pub fn with_empty_response_policy(mut self, policy: EmptyResponsePolicy) -> Self {
+        self.empty_response_policy = policy;
+        self
+    }
+
+    /// Set what happens when the model refuses to respond
+    ///
+    /// Defaults to [`RefusalPolicy::Error`]. With [`RefusalPolicy::Pass`]
+    /// the refusal text is returned and [`AgentResponse::is_refusal`]
+    /// tells it apart from an answer.
+    ///
+    /// [`AgentResponse::is_refusal`]: crate::AgentResponse::is_refusal
+    pub fn with_refusal_policy(mut self, policy: RefusalPolicy) -> Self {
+        self.refusal_policy = policy;
+        self
+    }
+
+    /// Set what happens when a request won't fit the context window
+    ///
+    /// Defaults to [`OverflowPolicy::Error`], which fails the run with
+    /// [`AgentError::ContextOverflow`](crate::AgentError::ContextOverflow)
+    /// before the request is sent. [`OverflowPolicy::Trim`] drops the
+    /// oldest messages from the request instead.
+    pub fn with_overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.overflow_policy = policy;
+        self
+    }
+
+    /// Warn before model calls estimated to send more than `threshold`
+    /// input tokens
+    ///
+    /// Each such call is preceded by an
+    /// [`AgentEvent::BudgetWarning`](crate::AgentEvent::BudgetWarning), giving
+    /// a UI the chance to confirm an expensive call or cancel the run. The
+    /// call itself is not blocked.
+    pub fn with_input_token_warning(mut self, threshold: usize) -> Self {
+        self.input_token_warning = Some(threshold);
+        self
+    }
+
+    /// Break loops of tool calls with malformed JSON input
+    ///
+    /// After `max_attempts` consecutive calls to the same tool whose input
+    /// was not a valid JSON object, the next model call is made with tool
+    /// use disabled, and the tool results tell the model to reconsider the
+    /// input. That forces a text turn instead of another broken call. Each
+    /// recovery emits
+    /// [`AgentEvent::ToolJsonRecovery`](crate::AgentEvent::ToolJsonRecovery).
+    ///
+    /// Providers that cannot disable tool use for one call (see
+    /// [`ModelProvider::without_tool_use`](crate::ModelProvider::without_tool_use))
+    /// only get the instruction. Disabled by default: malformed input is
+    /// reported to the model as a tool error each time.
+    pub fn with_tool_json_recovery(mut self, max_attempts: usize) -> Self {
+        self.tool_json_recovery = Some(max_attempts.max(1));
+        self
+    }
+
+    /// Enable dry-run mode, in which tools are never executed
+    ///
+    /// Each tool call the model makes is reported with
+    /// [`AgentEvent::ToolSkippedDryRun`](crate::AgentEvent::ToolSkippedDryRun)
+    /// and answered with a placeholder success result so the run continues.
+    /// The calls are listed in
+    /// [`AgentResponse::intended_tool_calls`](crate::AgentResponse::intended_tool_calls).
+    /// Useful for checking which tools a prompt leads the model to use
+    /// without side effects.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Leave cache breakpoints off a prompt prefix that just changed
+    ///
+    /// When the system prompt or tools change, the next model call goes to
+    /// [`ModelProvider::without_stale_cache_breakpoints`](crate::ModelProvider::without_stale_cache_breakpoints),
+    /// so the changed part of the prefix is sent unmarked. That skips the
+    /// cache write for that call, but the call after it reads nothing from
+    /// the cache and writes the prefix then. This only saves money when the
+    /// prefix changes on nearly every call. Disabled by default: breakpoints
+    /// stay in place and
+    /// [`AgentEvent::CacheInvalidated`](crate::AgentEvent::CacheInvalidated)
+    /// is emitted either way.
+    pub fn with_stale_prefix_uncached(mut self, enabled: bool) -> Self {
+        self.stale_prefix_uncached = enabled;
+        self
+    }
+
+    /// Offer the model a built-in tool describing the agent itself
+    ///
+    /// The [`LIST_CAPABILITIES_TOOL`](crate::LIST_CAPABILITIES_TOOL) tool
+    /// returns the model name and the name and description of every other
+    /// tool, so the model can answer "what can you do?" without guessing.
+    /// It is read-only and runs without approval. A registered tool with
+    /// the same name takes its place.
+    pub fn with_self_introspection(mut self) -> Self {
+        self.self_introspection = true;
+        self
+    }
+
+    /// Offer the model a built-in tool for reporting progress
+    ///
+    /// Calls to the [`EMIT_PROGRESS_TOOL`](crate::EMIT_PROGRESS_TOOL) tool
+    /// are emitted as [`AgentEvent::Progress`](crate::AgentEvent::Progress)
+    /// and return an empty result, giving the model a way to report steps
+    /// of a long task without writing them into its answer. It does no IO
+    /// and runs without approval. A registered tool with the same name
+    /// takes its place.
+    pub fn with_progress_tool(mut self) -> Self {
+        self.progress_tool = true;
+        self
+    }
+
+    /// Cut text and JSON tool results sent to the model to `max_bytes`
+    ///
+    /// Keeps large outputs from using up the context window. Only the model's
+    /// copy is cut: [`AgentEvent::ToolCompleted`](crate::AgentEvent::ToolCompleted)
+    /// still carries the full result in `output`, with the truncated one in
+    /// `model_output`, so a UI can show everything the tool returned.
+    ///
+    /// Results are also cut to the provider's estimate,
+    /// [`ModelProvider::estimated_max_tool_result_bytes`], whether or not
+    /// this is set; the smaller of the two applies.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let agent = Agent::builder()
+    ///     .bedrock(ClaudeSonnet4_5)
+    ///     .with_max_tool_result_bytes(16 * 1024)
+    ///     .build()
+    ///     .await?;
+    /// ```
+    pub fn with_max_tool_result_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_tool_result_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Add tool-use guidance to the system prompt
+    ///
+    /// The text is added as a section at the end of the system prompt on
+    /// every run, whether or not any destructive tools are registered. It
+    /// replaces [`DEFAULT_TOOL_POLICY_PROMPT`](crate::DEFAULT_TOOL_POLICY_PROMPT),
+    /// which is otherwise added whenever a tool reports
+    /// [`is_destructive`](crate::Tool::is_destructive).
+    pub fn with_tool_policy_prompt(mut self, text: impl Into<String>) -> Self {
+        self.tool_policy = ToolPolicy::Custom(text.into());
+        self
+    }
+
+    /// Never add a tool-use policy to the system prompt
+    ///
+    /// Guidance from [`with_parallel_tool_use`](Self::with_parallel_tool_use)
+    /// is still added.
+    pub fn without_tool_policy_prompt(mut self) -> Self {
+        self.tool_policy = ToolPolicy::Disabled;
+        self
+    }
+
+    /// Allow or discourage several tool calls in one model turn
+    ///
+    /// Models batch independent tool calls by default. Pass `false` for
+    /// tools with side effects that must run in order, or `true` to nudge
+    /// the model to batch independent reads. Providers with a native
+    /// setting, such as `AnthropicProvider`, enforce it on every request
+    /// that offers tools; for other providers a line of guidance is added to
+    /// the system prompt's tool-use policy.
+    pub fn with_parallel_tool_use(mut self, enabled: bool) -> Self {
+        self.parallel_tool_use = Some(enabled);
+        self
+    }
+
+    /// Send a sampling seed with every model call, where the provider allows
+    ///
+    /// For reproducible evals. Seeding is best-effort: seeded models repeat
+    /// their output more often, not always. When the provider's model takes
+    /// no seed (see [`Feature::Seed`]), [`build`](Self::build) prints a
+    /// warning and the seed is ignored; use
+    /// [`require_feature`](Self::require_feature) to fail instead.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let agent = Agent::builder()
+    ///     .provider(provider)
+    ///     .with_seed(42)
+    ///     .build()
+    ///     .await?;
+    /// ```
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Fail [`build`](Self::build) unless the provider supports `feature`
+    ///
+    /// Use this when the agent depends on a capability, such as the 1M-token
+    /// context window, so a misconfigured provider is reported up front
+    /// instead of failing or silently degrading mid-run. See
+    /// [`ModelProvider::supports`].
+    ///
+    /// # Example
+    /// ```ignore
+    /// let agent = Agent::builder()
+    ///     .bedrock(ClaudeSonnet4_5)
+    ///     .require_feature(Feature::Thinking)
+    ///     .build()
+    ///     .await?;
+    /// ```
+    pub fn require_feature(mut self, feature: Feature) -> Self {
+        self.required_features.push(feature);
+        self
+    }
+
+    /// Check the provider's credentials during [`build`](Self::build)
+    ///
+    /// By default, bad or expired credentials only surface on the first
+    /// run. When enabled, `build` makes one cheap request (see
+    /// [`ModelProvider::check_credentials`]) and fails with
+    /// [`Error::CredentialCheckFailed`](crate::Error::CredentialCheckFailed)
+    /// if the provider rejects it. Disabled by default.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let agent = Agent::builder()
+    ///     .bedrock(ClaudeSonnet4_5)
+    ///     .with_credential_check(true)
+    ///     .build()
+    ///     .await?;
+    /// ```
+    pub fn with_credential_check(mut self, enabled: bool) -> Self {
+        self.credential_check = enabled;
+        self
+    }
+
+    /// Add a request interceptor
+    ///
+    /// Interceptors run in the order they are added, before every model
+    /// call, and may modify the messages, tools, and system prompt sent to
+    /// the provider. See [`RequestInterceptor`].
+    ///
+    /// # Example
+    /// ```ignore
+    /// let agent = Agent::builder()
+    ///     .bedrock(ClaudeSonnet4_5)
+    ///     .with_interceptor(PromptSuffix)
+    ///     .build()
+    ///     .await?;
+    /// ```
+    pub fn with_interceptor(mut self, interceptor: impl RequestInterceptor + 'static) -> Self {
+        self.interceptors.push(Arc::new(interceptor));
+        self
+    }
+
+    /// Add a content filter
+    ///
+    /// Filters run in the order they are added, on the user message before
+    /// it is sent and on each model response before it is recorded or
+    /// reported to hooks. See [`ContentFilter`].
+    ///
+    /// # Example
+    /// ```ignore
+    /// let agent = Agent::builder()
+    ///     .bedrock(ClaudeSonnet4_5)
+    ///     .with_content_filter(NoSecrets)
+    ///     .build()
+    ///     .await?;
+    /// ```
+    pub fn with_content_filter(mut self, filter: impl ContentFilter + 'static) -> Self {
+        self.content_filters.push(Arc::new(filter));
+        self
+    }
+
+    /// Set the generator for tool-use IDs the agent has to synthesize
+    ///
+    /// Tool-use IDs normally come from the provider. When a provider returns a
+    /// tool use without one, the agent assigns an ID from this generator.
+    /// Defaults to [`UuidIdGenerator`]; use
+    /// [`SequentialIdGenerator`](crate::SequentialIdGenerator) for
+    /// reproducible transcripts in tests.
+    pub fn with_id_generator(mut self, generator: impl IdGenerator + 'static) -> Self {
+        self.id_generator = Arc::new(generator);
+        self
+    }
+
     /// Enable session management for conversation memory
     #[cfg(feature = "session")]
     pub fn with_session_store(mut self, store: impl SessionStore + 'static) -> Self {
@@ -347,6 +843,36 @@ impl AgentBuilder {
         self
     }
 
+    /// Save the session after every tool turn instead of only when a run completes
+    ///
+    /// Off by default, since each save rewrites the session in the store.
+    ///
+    /// When enabled, the session is checkpointed as soon as a batch of tool
+    /// results is available. Tool calls and their results are always written
+    /// together, so a reloaded session never contains a `tool_use` without its
+    /// `tool_result`. If the process dies mid-run, the session holds everything
+    /// up to the last completed tool turn; anything after it (a pending model
+    /// call, tools still executing) is dropped. On the next `run()`, an
+    /// interrupted turn is restored and closed with a placeholder assistant
+    /// message so the conversation stays well-formed.
+    ///
+    /// Has no effect unless a session store is configured.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let agent = Agent::builder()
+    ///     .bedrock(ClaudeSonnet4_5)
+    ///     .with_session_store(store)
+    ///     .with_incremental_session_save(true)
+    ///     .build()
+    ///     .await?;
+    /// ```
+    #[cfg(feature = "session")]
+    pub fn with_incremental_session_save(mut self, enabled: bool) -> Self {
+        self.incremental_session_save = enabled;
+        self
+    }
+
     // Context file methods
 
     /// Add literal string content as context
@@ -508,7 +1034,9 @@ impl AgentBuilder {
 
     // MCP methods are in mcp.rs:
     // - with_mcp_server
+    // - with_mcp_servers
     // - with_mcp_config_file
+    // - with_mcp_best_effort
 
     /// Build the agent
     ///
@@ -535,11 +1063,54 @@ impl AgentBuilder {
                 "No provider configured. Call .bedrock(), .anthropic(), or .provider() before .build()".to_string()
             ))?;
 
-        let provider = provider_factory().await?;
+        let mut provider = provider_factory().await?;
+
+        if let Some(feature) = self
+            .required_features
+            .iter()
+            .find(|feature| !provider.supports(**feature))
+        {
+            return Err(crate::error::Error::Config(format!(
+                "{} does not support {}",
+                provider.name(),
+                feature
+            )));
+        }
+
+        if self.credential_check {
+            provider
+                .check_credentials()
+                .await
+                .map_err(crate::error::Error::CredentialCheckFailed)?;
+        }
+
+        // Prefer the provider's own switch; fall back to prompt guidance
+        let mut parallel_tool_guidance = None;
+        if let Some(enabled) = self.parallel_tool_use {
+            match provider.configure_parallel_tool_use(enabled) {
+                Some(configured) => provider = configured,
+                None => parallel_tool_guidance = Some(enabled),
+            }
+        }
+
+        if let Some(seed) = self.seed {
+            match provider.configure_seed(seed) {
+                Some(seeded) => provider = seeded,
+                None => eprintln!(
+                    "Warning: {} does not support a sampling seed; ignoring it",
+                    provider.name()
+                ),
+            }
+        }
+
+        let mut tools = self.tools;
+        for source in self.tool_sources {
+            tools.extend(source.await?);
+        }
 
         let conversation_manager = self
             .conversation_manager
-            .unwrap_or_else(|| Box::new(SlidingWindowConversationManager::new()));
+            .unwrap_or_else(|| ConversationStrategy::default().into_manager());
 
         // Create authorizer with custom store or default MemoryGrantStore,
         // and apply the configured policy
@@ -557,19 +1128,45 @@ impl AgentBuilder {
         #[allow(unused_mut)]
         let mut agent = Agent {
             provider,
+            name: self.name,
             system_prompt: self.system_prompt,
             max_concurrent_tools: self.max_concurrent_tools,
-            tools: self.tools,
-            hooks: Arc::new(parking_lot::RwLock::new(HashMap::new())),
+            tools: tools.into_iter().map(Arc::from).collect(),
+            tool_runtime: self.tool_runtime,
+            hooks: Arc::new(HookRegistry::default()),
             next_hook_id: AtomicU64::new(0),
+            interceptors: self.interceptors,
+            content_filters: self.content_filters,
+            id_generator: self.id_generator,
+            tool_use_repair: self.tool_use_repair,
+            empty_response_policy: self.empty_response_policy,
+            refusal_policy: self.refusal_policy,
+            overflow_policy: self.overflow_policy,
+            input_token_warning: self.input_token_warning,
+            tool_json_recovery: self.tool_json_recovery,
+            dry_run: self.dry_run,
+            stale_prefix_uncached: self.stale_prefix_uncached,
+            self_introspection: self.self_introspection,
+            progress_tool: self.progress_tool,
+            max_tool_result_bytes: self.max_tool_result_bytes,
+            tool_policy: self.tool_policy,
+            parallel_tool_guidance,
             authorizer: Arc::new(RwLock::new(authorizer)),
             authorization_timeout: self.authorization_timeout,
             pending_authorizations: Arc::new(RwLock::new(HashMap::new())),
+            running_tools: parking_lot::Mutex::new(HashMap::new()),
+            invalid_tool_inputs: parking_lot::Mutex::new(HashMap::new()),
+            cache_prefix: parking_lot::Mutex::new(None),
+            tool_permits: parking_lot::Mutex::new(HashMap::new()),
             #[cfg(feature = "mcp")]
             mcp_clients: Vec::new(),
+            #[cfg(feature = "mcp")]
+            mcp_failures: Vec::new(),
             conversation_manager: parking_lot::RwLock::new(conversation_manager),
             #[cfg(feature = "session")]
             session_store: self.session_store,
+            #[cfg(feature = "session")]
+            incremental_session_save: self.incremental_session_save,
             // Context file fields
             context_sources: self.context_sources,
             context_config: self.context_config,
@@ -579,10 +1176,17 @@ impl AgentBuilder {
         // Connect to MCP servers specified in builder
         #[cfg(feature = "mcp")]
         {
-            super::mcp::connect_mcp_servers(&mut agent, self.mcp_servers, self.mcp_config_files)
-                .await?;
+            super::mcp::connect_mcp_servers(
+                &mut agent,
+                self.mcp_servers,
+                self.mcp_config_files,
+                self.mcp_best_effort,
+            )
+            .await?;
         }
 
+        agent.check_tool_names()?;
+
         Ok(agent)
     }
 }
@@ -619,11 +1223,11 @@ impl Agent {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::box_tools;
     use crate::conversation::SimpleConversationManager;
     use crate::provider::{ModelProvider, ProviderError};
     use crate::types::{ContentBlock, Message, Role, StopReason, ToolDefinition};
     use crate::ModelResponse;
+    use crate::{box_tools, try_box_tools};
 
     /// Mock provider for builder tests
     #[derive(Clone)]
@@ -655,7 +1259,10 @@ mod tests {
                     content: vec![ContentBlock::Text("ok".to_string())],
                 },
                 stop_reason: StopReason::EndTurn,
+                stop_sequence: None,
                 usage: None,
+                service_tier: None,
+                model: None,
             })
         }
     }
@@ -676,6 +1283,18 @@ mod tests {
         assert_eq!(builder.authorization_timeout, DEFAULT_PERMISSION_TIMEOUT);
     }
 
+    #[test]
+    fn test_builder_name() {
+        let builder = Agent::builder().with_name("researcher");
+        assert_eq!(builder.name, Some("researcher".to_string()));
+    }
+
+    #[test]
+    fn test_builder_dry_run() {
+        assert!(!Agent::builder().dry_run);
+        assert!(Agent::builder().with_dry_run(true).dry_run);
+    }
+
     #[test]
     fn test_builder_system_prompt() {
         let builder = Agent::builder().with_system_prompt("Test prompt");
@@ -731,6 +1350,39 @@ mod tests {
         assert_eq!(agent.provider.name(), "MockProvider");
     }
 
+    #[tokio::test]
+    async fn test_build_with_conversation_strategy() {
+        for (strategy, expected) in [
+            (ConversationStrategy::NoOp, 3),
+            (ConversationStrategy::Simple { max_messages: 1 }, 1),
+            (ConversationStrategy::default(), 3),
+            (
+                ConversationStrategy::Summarizing {
+                    trigger: 0.75,
+                    keep_recent: 1,
+                },
+                3,
+            ),
+        ] {
+            let agent = Agent::builder()
+                .provider(MockProvider)
+                .with_conversation_strategy(strategy)
+                .build()
+                .await
+                .unwrap();
+            for text in ["one", "two", "three"] {
+                agent
+                    .conversation_manager
+                    .write()
+                    .add_message(Message::user(text));
+            }
+
+            let usage = agent.get_context_usage();
+            assert_eq!(usage.total_messages, 3);
+            assert_eq!(usage.context_messages, expected, "{:?}", strategy);
+        }
+    }
+
     #[tokio::test]
     async fn test_build_without_provider_fails() {
         let result = Agent::builder().build().await;
@@ -740,6 +1392,88 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_build_fails_without_required_feature() {
+        let result = Agent::builder()
+            .provider(MockProvider)
+            .require_feature(Feature::WebSearch)
+            .build()
+            .await;
+        let Err(err) = result else {
+            panic!("Expected error for unsupported feature");
+        };
+        assert!(err.is_config());
+        assert!(err.to_string().contains("does not support web search"));
+    }
+
+    /// Provider whose credentials are rejected
+    struct RejectedCredentialsProvider;
+
+    #[async_trait::async_trait]
+    impl ModelProvider for RejectedCredentialsProvider {
+        fn name(&self) -> &str {
+            "RejectedCredentialsProvider"
+        }
+
+        fn max_context_tokens(&self) -> usize {
+            200_000
+        }
+
+        fn max_output_tokens(&self) -> usize {
+            8_192
+        }
+
+        async fn count_tokens(
+            &self,
+            _messages: &[Message],
+            _tools: &[ToolDefinition],
+            _system_prompt: Option<&str>,
+        ) -> Result<usize, ProviderError> {
+            Err(ProviderError::Authentication("expired token".to_string()))
+        }
+
+        async fn generate(
+            &self,
+            _messages: Vec<Message>,
+            _tools: Vec<ToolDefinition>,
+            _system_prompt: Option<String>,
+        ) -> Result<ModelResponse, ProviderError> {
+            Err(ProviderError::Authentication("expired token".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_credential_check_fails_build_with_bad_credentials() {
+        let result = Agent::builder()
+            .provider(RejectedCredentialsProvider)
+            .with_credential_check(true)
+            .build()
+            .await;
+        let Err(err) = result else {
+            panic!("Expected credential check to fail the build");
+        };
+        assert!(err.is_credential_check_failed());
+        assert!(matches!(
+            err,
+            crate::Error::CredentialCheckFailed(ProviderError::Authentication(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_credential_check_is_off_by_default() {
+        assert!(Agent::builder()
+            .provider(RejectedCredentialsProvider)
+            .build()
+            .await
+            .is_ok());
+        assert!(Agent::builder()
+            .provider(MockProvider)
+            .with_credential_check(true)
+            .build()
+            .await
+            .is_ok());
+    }
+
     #[tokio::test]
     async fn test_builder_chaining() {
         let agent = Agent::builder()
@@ -974,4 +1708,223 @@ mod tests {
         assert!(names.contains(&"calculator"));
         assert!(names.contains(&"weather"));
     }
+
+    #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+    struct EmptyInput {}
+
+    struct SourcedTool(&'static str);
+
+    impl crate::tool::Tool for SourcedTool {
+        type Input = EmptyInput;
+        fn name(&self) -> &str {
+            self.0
+        }
+        fn description(&self) -> &str {
+            "A tool from a fallible source"
+        }
+        async fn execute(
+            &self,
+            _input: Self::Input,
+        ) -> Result<crate::tool::ToolResult, crate::tool::ToolError> {
+            Ok(crate::tool::ToolResult::text(self.0))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_build_with_fallible_tool_sources() {
+        let agent = Agent::builder()
+            .provider(MockProvider)
+            .add_tool(SourcedTool("direct"))
+            .try_add_tools(|| Ok::<_, std::io::Error>(box_tools![SourcedTool("sync")]))
+            .add_tools_async(async { Ok::<_, std::io::Error>(box_tools![SourcedTool("async")]) })
+            .build()
+            .await
+            .unwrap();
+
+        let names: Vec<String> = agent.list_tools().into_iter().map(|t| t.name).collect();
+        assert_eq!(names, vec!["direct", "sync", "async"]);
+    }
+
+    #[tokio::test]
+    async fn test_build_fails_when_tool_source_errors() {
+        let result = Agent::builder()
+            .provider(MockProvider)
+            .try_add_tools(|| Ok::<_, std::io::Error>(box_tools![SourcedTool("ok")]))
+            .add_tools_async(async {
+                Err::<Vec<Box<dyn DynTool>>, _>(std::io::Error::new(
+                    std::io::ErrorKind::ConnectionRefused,
+                    "could not connect to postgres://localhost/app",
+                ))
+            })
+            .build()
+            .await;
+
+        let Err(err) = result else {
+            panic!("Expected build to fail when a tool source errors");
+        };
+        assert!(err.is_tool_init());
+        assert_eq!(
+            err.to_string(),
+            "tool initialization failed: could not connect to postgres://localhost/app"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_try_box_tools_stops_at_first_error() {
+        fn connect(name: &'static str) -> Result<SourcedTool, std::io::Error> {
+            match name {
+                "db" => Err(std::io::Error::new(
+                    std::io::ErrorKind::ConnectionRefused,
+                    "could not connect to db",
+                )),
+                "unreachable" => panic!("constructors after a failure must not run"),
+                name => Ok(SourcedTool(name)),
+            }
+        }
+
+        let tools = try_box_tools![connect("a"), connect("b")].unwrap();
+        assert_eq!(tools.len(), 2);
+
+        let result = Agent::builder()
+            .provider(MockProvider)
+            .try_add_tools(|| try_box_tools![connect("a"), connect("db"), connect("unreachable")])
+            .build()
+            .await;
+
+        let Err(err) = result else {
+            panic!("Expected build to fail when a tool constructor errors");
+        };
+        assert!(err.is_tool_init());
+        assert_eq!(
+            err.to_string(),
+            "tool initialization failed: could not connect to db"
+        );
+    }
+
+    /// Provider that takes a seed and reports it in its settings
+    struct SeededProvider(Option<u64>);
+
+    #[async_trait::async_trait]
+    impl ModelProvider for SeededProvider {
+        fn name(&self) -> &str {
+            "SeededProvider"
+        }
+
+        fn max_context_tokens(&self) -> usize {
+            200_000
+        }
+
+        fn max_output_tokens(&self) -> usize {
+            8_192
+        }
+
+        fn configure_seed(&self, seed: u64) -> Option<Arc<dyn ModelProvider>> {
+            Some(Arc::new(SeededProvider(Some(seed))))
+        }
+
+        fn settings(&self) -> crate::provider::ProviderSettings {
+            crate::provider::ProviderSettings {
+                sampling: crate::provider::SamplingParams {
+                    seed: self.0,
+                    ..Default::default()
+                },
+                ..Default::default()
+            }
+        }
+
+        async fn generate(
+            &self,
+            messages: Vec<Message>,
+            tools: Vec<ToolDefinition>,
+            system_prompt: Option<String>,
+        ) -> Result<ModelResponse, ProviderError> {
+            MockProvider.generate(messages, tools, system_prompt).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_with_seed_configures_provider() {
+        let agent = Agent::builder()
+            .provider(SeededProvider(None))
+            .with_seed(7)
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(agent.provider.settings().sampling.seed, Some(7));
+
+        // A provider without seeding still builds; the seed is ignored
+        let agent = Agent::builder()
+            .provider(MockProvider)
+            .with_seed(7)
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(agent.provider.settings().sampling.seed, None);
+    }
+
+    /// Error from building an agent with the given tool names
+    async fn build_with_tools(names: Vec<&'static str>) -> crate::Error {
+        let tools: Vec<Box<dyn DynTool>> = names
+            .into_iter()
+            .map(|name| box_tool(SourcedTool(name)))
+            .collect();
+        let result = Agent::builder()
+            .provider(MockProvider)
+            .add_tools(tools)
+            .build()
+            .await;
+
+        let Err(err) = result else {
+            panic!("Expected build to reject the tool names");
+        };
+        err
+    }
+
+    #[tokio::test]
+    async fn test_build_rejects_tool_name_with_invalid_character() {
+        let err = build_with_tools(vec!["read file"]).await;
+        assert!(err.is_invalid_tool_name());
+        assert_eq!(
+            err.to_string(),
+            "invalid tool name 'read file': ' ' is not allowed; use ASCII letters, digits, '_', or '-'"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_build_rejects_over_length_tool_name() {
+        let name: &'static str = "a".repeat(70).leak();
+        let err = build_with_tools(vec![name]).await;
+        let crate::Error::InvalidToolName {
+            name: rejected,
+            reason,
+        } = err
+        else {
+            panic!("Expected InvalidToolName, got {:?}", err);
+        };
+        assert_eq!(rejected, name);
+        assert_eq!(reason, "name is 70 characters, more than the 64 allowed");
+    }
+
+    #[tokio::test]
+    async fn test_build_rejects_non_ascii_tool_name_by_character() {
+        // 40 characters but 80 bytes: rejected for the characters, not length
+        let name: &'static str = "é".repeat(40).leak();
+        let err = build_with_tools(vec![name]).await;
+        let crate::Error::InvalidToolName { reason, .. } = err else {
+            panic!("Expected InvalidToolName, got {:?}", err);
+        };
+        assert_eq!(
+            reason,
+            "'é' is not allowed; use ASCII letters, digits, '_', or '-'"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_build_rejects_duplicate_tool_names() {
+        let err = build_with_tools(vec!["search", "fetch", "search"]).await;
+        assert_eq!(
+            err.to_string(),
+            "invalid tool name 'search': another tool has the same name"
+        );
+    }
 }
@@ -0,0 +1,226 @@
+//! Sub-agent delegation - wrap an Agent as a Tool
+//!
+//! An [`AgentTool`] lets a supervisor agent hand tasks to specialist agents
+//! the same way it calls any other tool. Delegation state (the calling
+//! agent's hooks, how deep the current delegation chain is, and where a
+//! sub-agent's events are forwarded) travels in a task-local set around each
+//! tool execution, so nothing has to be wired up between the supervisor and
+//! its sub-agents at build time.
+
+use std::future::Future;
+use std::sync::Arc;
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::events::AgentEvent;
+use crate::tool::{Tool, ToolError, ToolResult};
+
+use super::hooks::HookRegistry;
+use super::Agent;
+
+/// Default maximum depth of nested sub-agent delegation
+pub const DEFAULT_MAX_DELEGATION_DEPTH: usize = 3;
+
+/// Delegation context for the agent currently executing a tool
+#[derive(Clone)]
+struct DelegationScope {
+    /// Hooks of the agent executing the tool
+    hooks: Arc<HookRegistry>,
+    /// Number of sub-agent hops between this agent and the top-level agent
+    depth: usize,
+    /// Where events of the sub-agent running in this scope are forwarded
+    forward: Option<Arc<EventForward>>,
+}
+
+/// Forwarding target for the events of one delegated run
+struct EventForward {
+    /// Name of the [`AgentTool`] that started the run
+    name: String,
+    /// Hooks of the agent that called the tool
+    parent: Arc<HookRegistry>,
+    /// The calling agent's own forwarding target, if it is itself a sub-agent
+    outer: Option<Arc<EventForward>>,
+}
+
+tokio::task_local! {
+    static DELEGATION: DelegationScope;
+}
+
+/// Run a tool execution future with `agent`'s hooks as the delegation parent
+///
+/// The depth and forwarding target are inherited from any enclosing
+/// delegation, so a sub-agent's tools see the depth its [`AgentTool`]
+/// assigned and events emitted while they run still reach the caller.
+///
+/// The returned future does not borrow `agent`, so it can be spawned.
+pub(super) fn with_delegation_scope<F: Future>(
+    agent: &Agent,
+    fut: F,
+) -> impl Future<Output = F::Output> {
+    let (depth, forward) = DELEGATION
+        .try_with(|scope| (scope.depth, scope.forward.clone()))
+        .unwrap_or((0, None));
+    let scope = DelegationScope {
+        hooks: agent.hooks.clone(),
+        depth,
+        forward,
+    };
+    DELEGATION.scope(scope, fut)
+}
+
+/// Forward an event emitted by a sub-agent up the delegation chain
+///
+/// Each level wraps the event in [`AgentEvent::SubAgent`] with the name of
+/// the [`AgentTool`] that started the run and hands it to that caller's hooks.
+/// Does nothing outside a delegated run.
+pub(super) async fn forward_event(event: &AgentEvent) {
+    let Some(mut forward) = DELEGATION
+        .try_with(|scope| scope.forward.clone())
+        .ok()
+        .flatten()
+    else {
+        return;
+    };
+    let mut event = event.clone();
+    loop {
+        event = AgentEvent::SubAgent {
+            agent: forward.name.clone(),
+            event: Box::new(event),
+        };
+        forward.parent.emit(&event).await;
+        match forward.outer.clone() {
+            Some(outer) => forward = outer,
+            None => break,
+        }
+    }
+}
+
+/// Input for [`AgentTool`]
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct AgentToolInput {
+    /// The task for the agent to complete, with all the context it needs
+    pub task: String,
+}
+
+/// A tool that delegates tasks to another agent
+///
+/// Executing the tool runs `sub_agent.run(task)` and returns the sub-agent's
+/// final response as text. The sub-agent keeps its own conversation history,
+/// tools, and permissions across calls.
+///
+/// # Events
+///
+/// While a delegated task runs, every event the sub-agent emits is wrapped in
+/// [`AgentEvent::SubAgent`] with this tool's name and forwarded to the calling
+/// agent's hooks. Deeper hierarchies nest the wrapping, so a supervisor sees a
+/// grandchild's events as `SubAgent { agent: "child", event: SubAgent { .. } }`.
+/// Forwarding is tied to the delegated run rather than registered on the
+/// sub-agent, so concurrent runs of the same sub-agent each reach only their
+/// own caller, and nothing is left behind if the run is cancelled.
+///
+/// # Depth
+///
+/// Delegation chains are limited to [`DEFAULT_MAX_DELEGATION_DEPTH`] levels
+/// below the top-level agent (see [`with_max_depth`](Self::with_max_depth)).
+/// A call that would exceed the limit fails without running the sub-agent.
+///
+/// # Example
+///
+/// ```ignore
+/// let researcher = Arc::new(
+///     Agent::builder()
+///         .bedrock(ClaudeSonnet4_5)
+///         .with_system_prompt("You research topics thoroughly.")
+///         .build()
+///         .await?,
+/// );
+///
+/// let supervisor = Agent::builder()
+///     .bedrock(ClaudeSonnet4_5)
+///     .add_trusted_tool(AgentTool::new("researcher", researcher))
+///     .build()
+///     .await?;
+/// ```
+pub struct AgentTool {
+    name: String,
+    description: String,
+    agent: Arc<Agent>,
+    max_depth: usize,
+}
+
+impl AgentTool {
+    /// Wrap `agent` as a tool called `name`
+    pub fn new(name: impl Into<String>, agent: Arc<Agent>) -> Self {
+        let name = name.into();
+        Self {
+            description: format!(
+                "Delegate a task to the {} agent and return its final response. \
+                 The agent does not see this conversation, so include all the context it needs.",
+                name
+            ),
+            name,
+            agent,
+            max_depth: DEFAULT_MAX_DELEGATION_DEPTH,
+        }
+    }
+
+    /// Set the description shown to the model
+    ///
+    /// Describe what the sub-agent is good at so the model knows when to
+    /// delegate to it.
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    /// Set the maximum delegation depth at which this sub-agent may run
+    ///
+    /// A sub-agent called directly by a top-level agent runs at depth 1.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+}
+
+impl Tool for AgentTool {
+    type Input = AgentToolInput;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    async fn execute(&self, input: Self::Input) -> Result<ToolResult, ToolError> {
+        let parent = DELEGATION.try_with(Clone::clone).ok();
+        let depth = parent.as_ref().map_or(0, |scope| scope.depth) + 1;
+        if depth > self.max_depth {
+            return Err(ToolError::Custom(format!(
+                "Cannot delegate to '{}': maximum delegation depth of {} reached",
+                self.name, self.max_depth
+            )));
+        }
+
+        let forward = parent.map(|scope| {
+            Arc::new(EventForward {
+                name: self.name.clone(),
+                parent: scope.hooks,
+                outer: scope.forward,
+            })
+        });
+
+        let scope = DelegationScope {
+            hooks: self.agent.hooks.clone(),
+            depth,
+            forward,
+        };
+        let response = DELEGATION
+            .scope(scope, self.agent.run(&input.task))
+            .await
+            .map_err(|e| ToolError::Custom(format!("Agent '{}' failed: {}", self.name, e)))?;
+        Ok(ToolResult::Text(response.text))
+    }
+}
@@ -0,0 +1,276 @@
+//! Document input for agent runs
+//!
+//! [`Agent::run_with_document`] attaches a document (usually a PDF) to the
+//! user message alongside a question about it. The document is checked
+//! against the provider's supported formats and [`MAX_DOCUMENT_BYTES`] before
+//! any model call is made, so a bad document fails fast with
+//! [`AgentError::InvalidDocument`].
+
+use std::path::{Path, PathBuf};
+
+use crate::tool::DocumentFormat;
+use crate::types::{DocumentBlock, DocumentSource, Message};
+
+use super::types::{AgentError, AgentResponse};
+use super::Agent;
+
+/// Maximum size of a document passed to [`Agent::run_with_document`]
+///
+/// This is the Anthropic API's request size limit. Bedrock enforces a lower
+/// per-document limit of its own.
+pub const MAX_DOCUMENT_BYTES: usize = 32 * 1024 * 1024;
+
+/// Every PDF file starts with this header
+const PDF_MAGIC: &[u8] = b"%PDF-";
+
+/// A document to attach to a user message
+///
+/// Paths and byte buffers convert with `into()`; bytes are assumed to be a
+/// PDF. Use [`DocumentInput::Bytes`] directly for other formats.
+#[derive(Debug, Clone)]
+pub enum DocumentInput {
+    /// Read the document from disk, taking the format from the file extension
+    Path(PathBuf),
+    /// In-memory document bytes
+    Bytes {
+        format: DocumentFormat,
+        data: Vec<u8>,
+        /// Optional document name/filename
+        name: Option<String>,
+    },
+    /// A file previously uploaded through the Anthropic Files API
+    FileId {
+        file_id: String,
+        format: DocumentFormat,
+    },
+}
+
+impl DocumentInput {
+    /// In-memory PDF bytes
+    pub fn pdf(data: impl Into<Vec<u8>>) -> Self {
+        Self::Bytes {
+            format: DocumentFormat::Pdf,
+            data: data.into(),
+            name: None,
+        }
+    }
+
+    /// A PDF uploaded through the Anthropic Files API
+    pub fn file_id(file_id: impl Into<String>) -> Self {
+        Self::FileId {
+            file_id: file_id.into(),
+            format: DocumentFormat::Pdf,
+        }
+    }
+
+    fn format(&self) -> Result<DocumentFormat, AgentError> {
+        match self {
+            Self::Path(path) => format_from_extension(path).ok_or_else(|| {
+                AgentError::InvalidDocument(format!(
+                    "unrecognized document type: {}",
+                    path.display()
+                ))
+            }),
+            Self::Bytes { format, .. } | Self::FileId { format, .. } => Ok(*format),
+        }
+    }
+
+    /// Load and validate the document content
+    async fn into_block(self) -> Result<DocumentBlock, AgentError> {
+        let format = self.format()?;
+        let (source, name) = match self {
+            Self::Path(path) => {
+                let read_error = |e: std::io::Error| {
+                    AgentError::InvalidDocument(format!("{}: {}", path.display(), e))
+                };
+                let len = tokio::fs::metadata(&path).await.map_err(read_error)?.len();
+                check_size(len as usize)?;
+                let data = tokio::fs::read(&path).await.map_err(read_error)?;
+                let name = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned());
+                (DocumentSource::Bytes(data), name)
+            }
+            Self::Bytes { data, name, .. } => (DocumentSource::Bytes(data), name),
+            Self::FileId { file_id, .. } => (DocumentSource::File(file_id), None),
+        };
+
+        if let DocumentSource::Bytes(data) = &source {
+            check_size(data.len())?;
+            if data.is_empty() {
+                return Err(AgentError::InvalidDocument("document is empty".to_string()));
+            }
+            if format == DocumentFormat::Pdf && !data.starts_with(PDF_MAGIC) {
+                return Err(AgentError::InvalidDocument(
+                    "document is not a PDF (missing %PDF- header)".to_string(),
+                ));
+            }
+        }
+
+        Ok(DocumentBlock {
+            format,
+            source,
+            name,
+        })
+    }
+}
+
+impl From<PathBuf> for DocumentInput {
+    fn from(path: PathBuf) -> Self {
+        Self::Path(path)
+    }
+}
+
+impl From<&Path> for DocumentInput {
+    fn from(path: &Path) -> Self {
+        Self::Path(path.to_path_buf())
+    }
+}
+
+impl From<Vec<u8>> for DocumentInput {
+    fn from(data: Vec<u8>) -> Self {
+        Self::pdf(data)
+    }
+}
+
+impl From<&[u8]> for DocumentInput {
+    fn from(data: &[u8]) -> Self {
+        Self::pdf(data)
+    }
+}
+
+fn check_size(len: usize) -> Result<(), AgentError> {
+    if len > MAX_DOCUMENT_BYTES {
+        return Err(AgentError::InvalidDocument(format!(
+            "document is {} bytes, larger than the {} byte limit",
+            len, MAX_DOCUMENT_BYTES
+        )));
+    }
+    Ok(())
+}
+
+fn format_from_extension(path: &Path) -> Option<DocumentFormat> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    Some(match extension.as_str() {
+        "pdf" => DocumentFormat::Pdf,
+        "csv" => DocumentFormat::Csv,
+        "doc" => DocumentFormat::Doc,
+        "docx" => DocumentFormat::Docx,
+        "html" | "htm" => DocumentFormat::Html,
+        "md" | "markdown" => DocumentFormat::Md,
+        "txt" => DocumentFormat::Txt,
+        "xls" => DocumentFormat::Xls,
+        "xlsx" => DocumentFormat::Xlsx,
+        _ => return None,
+    })
+}
+
+impl Agent {
+    /// Run the agent with a document and a question about it
+    ///
+    /// The document is sent ahead of the question in a single user message.
+    /// Only the question is recorded in events and the session history.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AgentError::InvalidDocument`] without calling the model if
+    /// the document cannot be read, is empty or larger than
+    /// [`MAX_DOCUMENT_BYTES`], is not a valid PDF when a PDF is expected, or
+    /// is in a format the provider does not accept (see
+    /// [`ModelProvider::supported_document_formats`](crate::provider::ModelProvider::supported_document_formats)).
+    /// Otherwise fails like [`run`](Self::run).
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let response = agent
+    ///     .run_with_document(Path::new("report.pdf"), "Summarize the key findings")
+    ///     .await?;
+    /// ```
+    pub async fn run_with_document(
+        &self,
+        document: impl Into<DocumentInput>,
+        question: &str,
+    ) -> Result<AgentResponse, AgentError> {
+        let document = document.into();
+        let format = document.format()?;
+        if !self.provider.supported_document_formats().contains(&format) {
+            return Err(AgentError::InvalidDocument(format!(
+                "{} does not support {:?} documents",
+                self.provider.name(),
+                format
+            )));
+        }
+
+        let block = document.into_block().await?;
+        self.run_message(Message::user_with_document(block, question))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_from_extension() {
+        assert_eq!(
+            format_from_extension(Path::new("report.PDF")),
+            Some(DocumentFormat::Pdf)
+        );
+        assert_eq!(
+            format_from_extension(Path::new("notes.md")),
+            Some(DocumentFormat::Md)
+        );
+        assert_eq!(format_from_extension(Path::new("image.png")), None);
+        assert_eq!(format_from_extension(Path::new("README")), None);
+    }
+
+    #[tokio::test]
+    async fn test_bytes_validated() {
+        let err = DocumentInput::pdf(Vec::new())
+            .into_block()
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("empty"));
+
+        let err = DocumentInput::pdf(b"hello".to_vec())
+            .into_block()
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not a PDF"));
+
+        let block = DocumentInput::pdf(b"%PDF-1.7".to_vec())
+            .into_block()
+            .await
+            .unwrap();
+        assert_eq!(block.format, DocumentFormat::Pdf);
+    }
+
+    #[test]
+    fn test_size_limit() {
+        assert!(check_size(MAX_DOCUMENT_BYTES).is_ok());
+        let err = check_size(MAX_DOCUMENT_BYTES + 1).unwrap_err();
+        assert!(matches!(err, AgentError::InvalidDocument(_)));
+    }
+
+    #[tokio::test]
+    async fn test_path_uses_file_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("paper.pdf");
+        std::fs::write(&path, b"%PDF-1.4 test").unwrap();
+
+        let block = DocumentInput::from(path).into_block().await.unwrap();
+        assert_eq!(block.name.as_deref(), Some("paper.pdf"));
+        assert!(matches!(block.source, DocumentSource::Bytes(ref data) if data.len() == 13));
+    }
+
+    #[tokio::test]
+    async fn test_missing_path_errors() {
+        let err = DocumentInput::from(Path::new("/nonexistent/paper.pdf"))
+            .into_block()
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("/nonexistent/paper.pdf"));
+    }
+}
@@ -0,0 +1,43 @@
+//! Content filter application for agent runs
+
+use crate::events::AgentEvent;
+use crate::filter::{FilterDecision, FilterStage};
+use crate::types::{ContentBlock, Message};
+
+use super::types::AgentError;
+use super::Agent;
+
+impl Agent {
+    /// Run the registered content filters over the text blocks of `message`
+    ///
+    /// Returns the message with any redactions applied. If a filter blocks
+    /// the text, emits [`AgentEvent::ContentFiltered`] and returns
+    /// [`AgentError::ContentFiltered`].
+    pub(super) async fn apply_content_filters(
+        &self,
+        stage: FilterStage,
+        mut message: Message,
+    ) -> Result<Message, AgentError> {
+        for block in &mut message.content {
+            let ContentBlock::Text(text) = block else {
+                continue;
+            };
+            for filter in &self.content_filters {
+                let decision = match stage {
+                    FilterStage::Input => filter.check_input(text).await,
+                    FilterStage::Output => filter.check_output(text).await,
+                };
+                match decision {
+                    FilterDecision::Allow => {}
+                    FilterDecision::Redact(replacement) => *text = replacement,
+                    FilterDecision::Block(reason) => {
+                        self.emit_event(AgentEvent::ContentFiltered { stage, reason })
+                            .await;
+                        return Err(AgentError::ContentFiltered);
+                    }
+                }
+            }
+        }
+        Ok(message)
+    }
+}
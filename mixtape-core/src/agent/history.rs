@@ -0,0 +1,63 @@
+//! Direct access to the agent's conversation history
+//!
+//! These methods read and rewrite the messages held by the conversation
+//! manager. They do not touch the session store: when one is configured,
+//! the next [`Agent::run`] rehydrates history from the stored session, so
+//! pair [`Agent::clear_history`] with `Agent::clear_session` to reset both.
+
+use crate::conversation::BoxedConversationManager;
+use crate::types::{ContentBlock, Message};
+
+use super::Agent;
+
+impl Agent {
+    /// Number of messages in the conversation history
+    pub fn message_count(&self) -> usize {
+        self.conversation_manager.read().all_messages().len()
+    }
+
+    /// A copy of the full conversation history
+    pub fn messages(&self) -> Vec<Message> {
+        self.conversation_manager.read().all_messages().to_vec()
+    }
+
+    /// Keep only the first `n` messages of the conversation history
+    ///
+    /// Like [`Vec::truncate`], this has no effect when `n` is at least the
+    /// current message count. A tool use is never kept without its tool
+    /// result: if the cut would separate them, the tool use message is
+    /// dropped as well.
+    ///
+    /// Returns the number of messages remaining.
+    pub fn truncate_to(&self, n: usize) -> usize {
+        truncate_conversation(&mut self.conversation_manager.write(), n)
+    }
+
+    /// Remove all messages from the conversation history
+    pub fn clear_history(&self) {
+        self.conversation_manager.write().clear();
+    }
+}
+
+/// [`Agent::truncate_to`] for any conversation
+pub(super) fn truncate_conversation(manager: &mut BoxedConversationManager, n: usize) -> usize {
+    let messages = manager.all_messages();
+    if n >= messages.len() {
+        return messages.len();
+    }
+
+    let mut kept = messages[..n].to_vec();
+    while kept.last().is_some_and(has_tool_use) {
+        kept.pop();
+    }
+    let remaining = kept.len();
+    manager.hydrate(kept);
+    remaining
+}
+
+fn has_tool_use(message: &Message) -> bool {
+    message
+        .content
+        .iter()
+        .any(|block| matches!(block, ContentBlock::ToolUse(_)))
+}
@@ -0,0 +1,43 @@
+//! Registered event hooks and event delivery
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::events::{AgentEvent, AgentHook, AsyncAgentHook, HookId};
+
+/// The sync and async hooks registered on an agent
+///
+/// Hooks are keyed by [`HookId`], which increases with each registration,
+/// so iterating a map visits hooks in registration order.
+#[derive(Default)]
+pub(crate) struct HookRegistry {
+    pub(super) sync_hooks: parking_lot::RwLock<BTreeMap<HookId, Arc<dyn AgentHook>>>,
+    pub(super) async_hooks: parking_lot::RwLock<BTreeMap<HookId, Arc<dyn AsyncAgentHook>>>,
+    /// Held while async hooks handle an event, so events reach them one at a time
+    delivery: tokio::sync::Mutex<()>,
+}
+
+impl HookRegistry {
+    /// Remove the hook with `id`, whichever kind it is
+    pub(super) fn remove(&self, id: HookId) -> bool {
+        self.sync_hooks.write().remove(&id).is_some()
+            || self.async_hooks.write().remove(&id).is_some()
+    }
+
+    /// Call the sync hooks, then await the async hooks
+    pub(super) async fn emit(&self, event: &AgentEvent) {
+        for hook in self.sync_hooks.read().values() {
+            hook.on_event(event);
+        }
+
+        if self.async_hooks.read().is_empty() {
+            return;
+        }
+        let _delivery = self.delivery.lock().await;
+        // Snapshot so the lock isn't held across awaits
+        let hooks: Vec<_> = self.async_hooks.read().values().cloned().collect();
+        for hook in hooks {
+            hook.on_event(event).await;
+        }
+    }
+}
@@ -0,0 +1,54 @@
+//! Built-in tool that lets the model ask what the agent can do
+//!
+//! Enabled with [`AgentBuilder::with_self_introspection`](super::AgentBuilder::with_self_introspection).
+//! The tool is answered by the agent itself from its live tool list, so
+//! tools added after the agent is built are included.
+
+use serde_json::json;
+
+use crate::tool::ToolResult;
+use crate::types::ToolDefinition;
+
+use super::Agent;
+
+/// Name of the built-in capabilities tool
+pub const LIST_CAPABILITIES_TOOL: &str = "list_capabilities";
+
+impl Agent {
+    /// Whether a call to `name` should be answered by the built-in
+    /// capabilities tool. A registered tool with the same name wins.
+    pub(super) fn is_capabilities_tool(&self, name: &str) -> bool {
+        self.self_introspection
+            && name == LIST_CAPABILITIES_TOOL
+            && !self.tools.iter().any(|t| t.name() == name)
+    }
+
+    /// Definition of the capabilities tool sent to the model
+    pub(super) fn capabilities_tool_definition(&self) -> Option<ToolDefinition> {
+        self.is_capabilities_tool(LIST_CAPABILITIES_TOOL)
+            .then(|| ToolDefinition {
+                name: LIST_CAPABILITIES_TOOL.to_string(),
+                description: "List the model and the tools available to you, with their \
+                              descriptions. Use this instead of guessing what you can do."
+                    .to_string(),
+                input_schema: json!({"type": "object", "properties": {}}),
+            })
+    }
+
+    /// The capabilities tool's result
+    ///
+    /// The tool leaves itself out of the list: the model already knows it
+    /// exists, and seeing it there invites calling it again.
+    pub(super) fn capabilities(&self) -> ToolResult {
+        let tools: Vec<_> = self
+            .list_tools()
+            .into_iter()
+            .map(|tool| json!({"name": tool.name, "description": tool.description}))
+            .collect();
+        ToolResult::Json(json!({
+            "agent_name": self.name,
+            "model": self.model_name(),
+            "tools": tools,
+        }))
+    }
+}
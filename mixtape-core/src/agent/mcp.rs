@@ -6,10 +6,13 @@
 
 use std::sync::Arc;
 
+use tokio::sync::Semaphore;
+
 use super::builder::AgentBuilder;
 use super::Agent;
 use crate::mcp::tool_adapter::McpToolAdapter;
-use crate::mcp::{load_config_file, McpClient, McpError, McpServerConfig};
+use crate::mcp::{load_config_file, McpClient, McpConnectFailure, McpError, McpServerConfig};
+use crate::tool::Tool;
 
 // ============================================================================
 // AgentBuilder MCP configuration methods
@@ -40,6 +43,31 @@ impl AgentBuilder {
         self
     }
 
+    /// Add several MCP servers to the agent
+    ///
+    /// Equivalent to calling [`with_mcp_server`](Self::with_mcp_server) for
+    /// each config. All servers are connected when `.build().await` is
+    /// called, and each server's tools are namespaced as its config says.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use mixtape_core::mcp::{McpServerConfig, McpTransport};
+    ///
+    /// let agent = Agent::builder()
+    ///     .bedrock(ClaudeSonnet4_5)
+    ///     .with_mcp_servers([
+    ///         McpServerConfig::new("filesystem", McpTransport::stdio("mcp-filesystem")),
+    ///         McpServerConfig::new("search", McpTransport::http("https://search.example.com/mcp")),
+    ///     ])
+    ///     .build()
+    ///     .await?;
+    /// ```
+    pub fn with_mcp_servers(mut self, configs: impl IntoIterator<Item = McpServerConfig>) -> Self {
+        self.mcp_servers.extend(configs);
+        self
+    }
+
     /// Add tools from MCP servers defined in a configuration file
     ///
     /// The file will be loaded and servers connected when `.build().await` is called.
@@ -58,69 +86,126 @@ impl AgentBuilder {
         self.mcp_config_files.push(path.as_ref().to_path_buf());
         self
     }
+
+    /// Build the agent even when some MCP servers fail to connect
+    ///
+    /// By default, `build()` fails with [`Error::Mcp`](crate::Error::Mcp)
+    /// naming every server or config file that could not be connected. In
+    /// best-effort mode those are skipped instead, and the agent reports
+    /// them from [`Agent::mcp_failures`].
+    pub fn with_mcp_best_effort(mut self, enabled: bool) -> Self {
+        self.mcp_best_effort = enabled;
+        self
+    }
 }
 
 // ============================================================================
-// Helper function for connecting MCP servers during build
+// Helper functions for connecting MCP servers
 // ============================================================================
 
 /// Connect to MCP servers and add their tools to the agent
 ///
 /// This is called from AgentBuilder::build() to set up MCP connections.
+/// Every server is attempted; failures are then either returned as one
+/// error or, in best-effort mode, kept on the agent.
 pub(super) async fn connect_mcp_servers(
     agent: &mut Agent,
     servers: Vec<McpServerConfig>,
     config_files: Vec<std::path::PathBuf>,
+    best_effort: bool,
 ) -> Result<(), crate::error::Error> {
+    let mut failures = Vec::new();
+
     // Connect to individually specified servers
     for config in servers {
-        let client = Arc::new(
-            McpClient::new(config.clone()).map_err(|e| crate::error::Error::Mcp(e.to_string()))?,
-        );
-        let tools = client
-            .list_tools()
-            .await
-            .map_err(|e| crate::error::Error::Mcp(e.to_string()))?;
-
-        for tool_def in tools {
-            if config.should_include_tool(&tool_def.name) {
-                let adapter = if let Some(namespace) = config.namespace() {
-                    McpToolAdapter::new_with_namespace(Arc::clone(&client), tool_def, namespace)
-                } else {
-                    McpToolAdapter::new(Arc::clone(&client), tool_def)
-                };
-                agent.add_tool(adapter);
-            }
+        let server = config.name.clone();
+        if let Err(error) = connect_server(agent, config, true).await {
+            failures.push(McpConnectFailure { server, error });
         }
-        agent.mcp_clients.push(client);
     }
 
     // Connect to servers from config files
     for path in config_files {
-        let server_configs = load_config_file(&path)
-            .await
-            .map_err(|e| crate::error::Error::Mcp(e.to_string()))?;
-
+        let server_configs = match load_config_file(&path).await {
+            Ok(configs) => configs,
+            Err(error) => {
+                failures.push(McpConnectFailure {
+                    server: path.display().to_string(),
+                    error,
+                });
+                continue;
+            }
+        };
         for config in server_configs {
-            let client = Arc::new(
-                McpClient::new(config.clone())
-                    .map_err(|e| crate::error::Error::Mcp(e.to_string()))?,
-            );
-            let tools = client
-                .list_tools()
-                .await
-                .map_err(|e| crate::error::Error::Mcp(e.to_string()))?;
-
-            for tool_def in tools {
-                if config.should_include_tool(&tool_def.name) {
-                    let adapter = McpToolAdapter::new(Arc::clone(&client), tool_def);
-                    agent.add_tool(adapter);
-                }
+            let server = config.name.clone();
+            if let Err(error) = connect_server(agent, config, false).await {
+                failures.push(McpConnectFailure { server, error });
             }
-            agent.mcp_clients.push(client);
         }
     }
 
+    if failures.is_empty() || best_effort {
+        agent.mcp_failures = failures;
+        return Ok(());
+    }
+
+    // The agent is discarded, so stop the servers that did start
+    agent.shutdown().await;
+    let failed: Vec<String> = failures.iter().map(ToString::to_string).collect();
+    Err(crate::error::Error::Mcp(format!(
+        "failed to connect to MCP servers: {}",
+        failed.join(", ")
+    )))
+}
+
+/// Connect to one MCP server and add the tools its config selects
+///
+/// Tools are prefixed with the config's namespace when `namespaced` is set;
+/// servers loaded from config files are not namespaced.
+async fn connect_server(
+    agent: &mut Agent,
+    config: McpServerConfig,
+    namespaced: bool,
+) -> Result<(), McpError> {
+    let client = Arc::new(McpClient::new(config.clone())?);
+    let tools = client.list_tools().await?;
+    let limit = config
+        .max_concurrent_calls()
+        .map(|max_calls| Arc::new(Semaphore::new(max_calls)));
+
+    // Filter tools based on the config's tool filter
+    let adapters: Vec<McpToolAdapter> = tools
+        .into_iter()
+        .filter(|tool_def| config.should_include_tool(&tool_def.name))
+        .map(|tool_def| {
+            let adapter = match config.namespace().filter(|_| namespaced) {
+                Some(namespace) => {
+                    McpToolAdapter::new_with_namespace(Arc::clone(&client), tool_def, namespace)
+                }
+                None => McpToolAdapter::new(Arc::clone(&client), tool_def),
+            };
+            match &limit {
+                Some(limit) => adapter.with_concurrency_limit(Arc::clone(limit)),
+                None => adapter,
+            }
+        })
+        .collect();
+
+    // Check every name first, so a rejected tool leaves none of the
+    // server's tools behind
+    if let Err(error) = agent.check_new_tool_names(adapters.iter().map(|a| a.name())) {
+        client.disconnect().await.ok();
+        return Err(McpError::Config(error.to_string()));
+    }
+    for adapter in adapters {
+        agent
+            .add_tool(adapter)
+            .map_err(|e| McpError::Config(e.to_string()))?;
+    }
+
+    // Store client for shutdown cleanup
+    agent.mcp_clients.push(client);
+
     Ok(())
 }
 
@@ -148,25 +233,7 @@ impl Agent {
     /// agent.add_mcp_server(config).await?;
     /// ```
     pub async fn add_mcp_server(&mut self, config: McpServerConfig) -> Result<(), McpError> {
-        let client = Arc::new(McpClient::new(config.clone())?);
-        let tools = client.list_tools().await?;
-
-        // Filter tools based on the config's tool filter
-        for tool_def in tools {
-            if config.should_include_tool(&tool_def.name) {
-                let adapter = if let Some(namespace) = config.namespace() {
-                    McpToolAdapter::new_with_namespace(Arc::clone(&client), tool_def, namespace)
-                } else {
-                    McpToolAdapter::new(Arc::clone(&client), tool_def)
-                };
-                self.add_tool(adapter);
-            }
-        }
-
-        // Store client for shutdown cleanup
-        self.mcp_clients.push(client);
-
-        Ok(())
+        connect_server(self, config, true).await
     }
 
     /// Add tools from MCP servers defined in a configuration file
@@ -183,24 +250,19 @@ impl Agent {
         &mut self,
         path: impl AsRef<std::path::Path>,
     ) -> Result<(), McpError> {
-        let server_configs = load_config_file(path).await?;
-
-        for config in server_configs {
-            let client = Arc::new(McpClient::new(config.clone())?);
-            let tools = client.list_tools().await?;
-
-            // Filter tools based on the config's tool filter
-            for tool_def in tools {
-                if config.should_include_tool(&tool_def.name) {
-                    let adapter = McpToolAdapter::new(Arc::clone(&client), tool_def);
-                    self.add_tool(adapter);
-                }
-            }
-
-            // Store client for shutdown cleanup
-            self.mcp_clients.push(client);
+        for config in load_config_file(path).await? {
+            connect_server(self, config, false).await?;
         }
 
         Ok(())
     }
+
+    /// MCP servers skipped during `build()` because they failed to connect
+    ///
+    /// Only populated with
+    /// [`AgentBuilder::with_mcp_best_effort`](super::AgentBuilder::with_mcp_best_effort);
+    /// without it such failures fail the build.
+    pub fn mcp_failures(&self) -> &[McpConnectFailure] {
+        &self.mcp_failures
+    }
 }
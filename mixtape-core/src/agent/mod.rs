@@ -5,12 +5,22 @@
 
 mod builder;
 mod context;
+mod delegate;
+mod document;
+mod filter;
 mod helpers;
+mod history;
+mod hooks;
+mod introspection;
 #[cfg(feature = "mcp")]
 mod mcp;
 mod permission;
+mod progress;
+mod repair;
 mod run;
+mod snapshot;
 mod streaming;
+mod tool_policy;
 mod tools;
 mod types;
 
@@ -20,14 +30,25 @@ mod session;
 // Re-export public types
 pub use builder::AgentBuilder;
 pub use context::{ContextConfig, ContextError, ContextLoadResult, ContextSource};
+pub use delegate::{AgentTool, AgentToolInput, DEFAULT_MAX_DELEGATION_DEPTH};
+pub use document::{DocumentInput, MAX_DOCUMENT_BYTES};
+pub use introspection::LIST_CAPABILITIES_TOOL;
+pub use progress::EMIT_PROGRESS_TOOL;
+pub use repair::ToolUseRepair;
+pub use snapshot::AgentConfigSnapshot;
+pub use streaming::{EmptyResponsePolicy, EMPTY_RESPONSE_PLACEHOLDER};
+pub use tool_policy::DEFAULT_TOOL_POLICY_PROMPT;
+pub(crate) use tools::DRY_RUN_TOOL_RESULT;
 pub use types::{
-    AgentError, AgentResponse, PermissionError, TokenUsageStats, ToolCallInfo, ToolInfo,
-    DEFAULT_MAX_CONCURRENT_TOOLS, DEFAULT_PERMISSION_TIMEOUT,
+    AgentError, AgentResponse, IntendedToolCall, OverflowPolicy, PermissionError, PlannedRequest,
+    RefusalPolicy, TokenUsageStats, ToolCallInfo, ToolInfo, DEFAULT_MAX_CONCURRENT_TOOLS,
+    DEFAULT_PERMISSION_TIMEOUT,
 };
 
 #[cfg(feature = "session")]
 pub use types::SessionInfo;
 
+use futures::future::AbortHandle;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
@@ -35,11 +56,15 @@ use std::time::Duration;
 use tokio::sync::{mpsc, RwLock};
 
 use crate::conversation::BoxedConversationManager;
-use crate::events::{AgentEvent, AgentHook, HookId};
+use crate::events::{AgentEvent, AgentHook, AsyncAgentHook, HookId};
+use crate::filter::ContentFilter;
+use crate::id::IdGenerator;
+use crate::interceptor::RequestInterceptor;
 use crate::permission::{AuthorizationResponse, ToolCallAuthorizer};
 use crate::provider::ModelProvider;
 use crate::tool::DynTool;
 use crate::types::Message;
+use hooks::HookRegistry;
 
 #[cfg(feature = "session")]
 use crate::session::SessionStore;
@@ -66,11 +91,47 @@ use crate::session::SessionStore;
 /// ```
 pub struct Agent {
     pub(super) provider: Arc<dyn ModelProvider>,
+    /// Display name set with `AgentBuilder::with_name`
+    pub(super) name: Option<String>,
     pub(super) system_prompt: Option<String>,
     pub(super) max_concurrent_tools: usize,
-    pub(super) tools: Vec<Box<dyn DynTool>>,
-    pub(super) hooks: Arc<parking_lot::RwLock<HashMap<HookId, Arc<dyn AgentHook>>>>,
+    pub(super) tools: Vec<Arc<dyn DynTool>>,
+    /// Runtime for blocking tools; tokio's blocking pool when unset
+    pub(super) tool_runtime: Option<tokio::runtime::Handle>,
+    pub(super) hooks: Arc<HookRegistry>,
     pub(super) next_hook_id: AtomicU64,
+    /// Request interceptors, run in order before each model call
+    pub(super) interceptors: Vec<Arc<dyn RequestInterceptor>>,
+    /// Content filters, run in order on user input and final responses
+    pub(super) content_filters: Vec<Arc<dyn ContentFilter>>,
+    /// How tool uses without a matching tool result are repaired
+    pub(super) tool_use_repair: ToolUseRepair,
+    /// What to do when the model returns an empty assistant turn
+    pub(super) empty_response_policy: EmptyResponsePolicy,
+    /// What to do when the model refuses to respond
+    pub(super) refusal_policy: RefusalPolicy,
+    /// What to do when a request won't fit the context window
+    pub(super) overflow_policy: OverflowPolicy,
+    /// Estimated input tokens above which a model call emits a budget warning
+    pub(super) input_token_warning: Option<usize>,
+    /// Consecutive malformed calls to one tool before a forced text turn
+    pub(super) tool_json_recovery: Option<usize>,
+    /// Report tool calls instead of executing them
+    pub(super) dry_run: bool,
+    /// Send the first call after a prefix change without its cache breakpoints
+    pub(super) stale_prefix_uncached: bool,
+    /// Offer the built-in capabilities tool to the model
+    pub(super) self_introspection: bool,
+    /// Offer the built-in progress tool to the model
+    pub(super) progress_tool: bool,
+    /// Longest tool result, in bytes, sent to the model
+    pub(super) max_tool_result_bytes: Option<usize>,
+    /// Tool-use guidance added to the system prompt
+    pub(super) tool_policy: tool_policy::ToolPolicy,
+    /// Parallel tool use guidance, when the provider cannot enforce it
+    pub(super) parallel_tool_guidance: Option<bool>,
+    /// Source of IDs for tool uses that arrive without one
+    pub(super) id_generator: Arc<dyn IdGenerator>,
     /// Tool call authorizer (always present, uses MemoryGrantStore by default)
     pub(super) authorizer: Arc<RwLock<ToolCallAuthorizer>>,
     /// Timeout for authorization requests
@@ -78,14 +139,31 @@ pub struct Agent {
     /// Pending authorization requests
     pub(super) pending_authorizations:
         Arc<RwLock<HashMap<String, mpsc::Sender<AuthorizationResponse>>>>,
+    /// Abort handles for executing tools, keyed by tool use ID
+    pub(super) running_tools: parking_lot::Mutex<HashMap<String, AbortHandle>>,
+    /// Parse errors for streamed tool inputs that could not be repaired,
+    /// keyed by tool use ID
+    pub(super) invalid_tool_inputs: parking_lot::Mutex<HashMap<String, String>>,
+    /// Hashes of the system prompt and tool definitions last sent, to spot
+    /// changes that invalidate a cached prompt prefix
+    pub(super) cache_prefix: parking_lot::Mutex<Option<(u64, u64)>>,
+    /// Permits for tools with a [`max_concurrency`](crate::tool::Tool::max_concurrency),
+    /// keyed by tool name
+    pub(super) tool_permits: parking_lot::Mutex<HashMap<String, Arc<tokio::sync::Semaphore>>>,
     /// MCP clients for graceful shutdown
     #[cfg(feature = "mcp")]
     pub(super) mcp_clients: Vec<Arc<crate::mcp::McpClient>>,
+    /// MCP servers skipped at build time in best-effort mode
+    #[cfg(feature = "mcp")]
+    pub(super) mcp_failures: Vec<crate::mcp::McpConnectFailure>,
     /// Conversation manager for context window handling
     pub(super) conversation_manager: parking_lot::RwLock<BoxedConversationManager>,
 
     #[cfg(feature = "session")]
     pub(super) session_store: Option<Arc<dyn SessionStore>>,
+    /// Persist the session after each tool turn, not just at the end of a run
+    #[cfg(feature = "session")]
+    pub(super) incremental_session_save: bool,
 
     // Context file fields
     /// Context file sources (resolved at runtime)
@@ -126,8 +204,19 @@ impl Agent {
     /// agent.remove_hook(hook_id);
     /// ```
     pub fn add_hook(&self, hook: impl AgentHook + 'static) -> HookId {
-        let id = HookId(self.next_hook_id.fetch_add(1, Ordering::SeqCst));
-        self.hooks.write().insert(id, Arc::new(hook));
+        let id = self.next_hook_id();
+        self.hooks.sync_hooks.write().insert(id, Arc::new(hook));
+        id
+    }
+
+    /// Add an async event hook, awaited in order at each event.
+    ///
+    /// See [`AsyncAgentHook`] for how async hooks are ordered relative to
+    /// each other and to sync hooks. Remove it with
+    /// [`remove_hook`](Self::remove_hook).
+    pub fn add_async_hook(&self, hook: impl AsyncAgentHook + 'static) -> HookId {
+        let id = self.next_hook_id();
+        self.hooks.async_hooks.write().insert(id, Arc::new(hook));
         id
     }
 
@@ -135,15 +224,26 @@ impl Agent {
     ///
     /// Returns `true` if the hook was found and removed, `false` otherwise.
     pub fn remove_hook(&self, id: HookId) -> bool {
-        self.hooks.write().remove(&id).is_some()
+        self.hooks.remove(id)
+    }
+
+    fn next_hook_id(&self) -> HookId {
+        HookId(self.next_hook_id.fetch_add(1, Ordering::SeqCst))
     }
 
     /// Emit an event to all registered hooks
-    pub(crate) fn emit_event(&self, event: AgentEvent) {
-        let hooks = self.hooks.read();
-        for hook in hooks.values() {
-            hook.on_event(&event);
-        }
+    ///
+    /// When this agent is running as a sub-agent, the event is also forwarded
+    /// to the agents that delegated to it.
+    pub(crate) async fn emit_event(&self, event: AgentEvent) {
+        self.hooks.emit(&event).await;
+        delegate::forward_event(&event).await;
+    }
+
+    /// The agent's name, if one was set with
+    /// [`AgentBuilder::with_name`](crate::AgentBuilder::with_name)
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
     }
 
     /// Get the model name for display
@@ -151,6 +251,24 @@ impl Agent {
         self.provider.name()
     }
 
+    /// The provider the agent calls, for making model calls directly
+    ///
+    /// Calls through this handle reuse the provider's configuration,
+    /// credentials, and retry behavior, but bypass everything else the agent
+    /// does: no tools are offered or executed, no permissions are checked,
+    /// and nothing is added to the conversation. Hooks, interceptors, and
+    /// content filters do not run.
+    ///
+    /// ```ignore
+    /// let response = agent
+    ///     .provider()
+    ///     .generate(vec![Message::user("Is this spam? ...")], vec![], None)
+    ///     .await?;
+    /// ```
+    pub fn provider(&self) -> Arc<dyn ModelProvider> {
+        Arc::clone(&self.provider)
+    }
+
     /// Gracefully shutdown the agent, disconnecting MCP servers
     ///
     /// Call this before dropping the agent to ensure clean subprocess termination.
@@ -175,6 +293,57 @@ impl Agent {
             .context_usage(limits, &estimate_tokens)
     }
 
+    /// Get exact context usage from the provider's token counting API
+    ///
+    /// Like [`Self::get_context_usage`], but `context_tokens` comes from
+    /// [`ModelProvider::count_tokens`] and covers the context messages, tool
+    /// definitions, and system prompt. Providers without a counting API
+    /// fall back to the estimate.
+    pub async fn count_context_tokens(
+        &self,
+    ) -> Result<crate::conversation::ContextUsage, AgentError> {
+        let limits = crate::conversation::ContextLimits::new(self.provider.max_context_tokens());
+        let provider = &self.provider;
+        let estimate_tokens = |msgs: &[Message]| provider.estimate_message_tokens(msgs);
+
+        let (context_messages, total_messages) = {
+            let manager = self.conversation_manager.read();
+            (
+                manager.messages_for_context(limits, &estimate_tokens),
+                manager.all_messages().len(),
+            )
+        };
+
+        let system_prompt = match self.last_context_result.read().as_ref() {
+            Some(context) => {
+                context::build_effective_prompt(self.system_prompt.as_deref(), context)
+            }
+            None => self.system_prompt.clone(),
+        };
+
+        let context_tokens = self
+            .provider
+            .count_tokens(
+                &context_messages,
+                &self.tool_definitions(),
+                system_prompt.as_deref(),
+            )
+            .await?;
+        let max_context_tokens = limits.max_context_tokens;
+
+        Ok(crate::conversation::ContextUsage {
+            context_tokens,
+            total_messages,
+            context_messages: context_messages.len(),
+            max_context_tokens,
+            usage_percentage: if max_context_tokens > 0 {
+                context_tokens as f32 / max_context_tokens as f32
+            } else {
+                0.0
+            },
+        })
+    }
+
     /// Get information about the most recently loaded context files
     ///
     /// Returns `None` if `run()` has not been called yet.
@@ -48,13 +48,15 @@
 //! - [`Agent::authorize_once()`] - One-time authorization
 //! - [`Agent::deny_authorization()`] - Deny the request
 
+use std::path::Path;
 use std::time::Duration;
 
 use super::builder::AgentBuilder;
 use super::types::PermissionError;
 use super::Agent;
 use crate::permission::{
-    AuthorizationResponse, Grant, GrantStore, Scope, ToolAuthorizationPolicy, ToolCallAuthorizer,
+    AuthorizationResponse, FileGrantStore, Grant, GrantStore, GrantStoreError, Scope,
+    ToolAuthorizationPolicy, ToolCallAuthorizer,
 };
 
 impl Agent {
@@ -63,6 +65,29 @@ impl Agent {
         &self.authorizer
     }
 
+    /// List all grants in the agent's grant store.
+    pub async fn list_grants(&self) -> Result<Vec<Grant>, GrantStoreError> {
+        self.authorizer.read().await.grants().await
+    }
+
+    /// Revoke a grant.
+    ///
+    /// Pass `None` for `params_hash` to revoke the tool-wide grant. Returns
+    /// `true` if a grant was removed. Subsequent calls to the tool go through
+    /// the authorization policy again.
+    pub async fn revoke_grant(
+        &self,
+        tool: &str,
+        params_hash: Option<&str>,
+    ) -> Result<bool, GrantStoreError> {
+        self.authorizer.read().await.revoke(tool, params_hash).await
+    }
+
+    /// Remove every grant from the agent's grant store.
+    pub async fn clear_grants(&self) -> Result<(), GrantStoreError> {
+        self.authorizer.read().await.clear().await
+    }
+
     /// Respond to an authorization request with a choice.
     ///
     /// Use this to respond to [`crate::AgentEvent::PermissionRequired`] events.
@@ -151,6 +176,25 @@ impl AgentBuilder {
         self
     }
 
+    /// Persist grants separately for the project in `dir`.
+    ///
+    /// Shorthand for `with_grant_store(FileGrantStore::for_directory(dir))`:
+    /// a tool trusted while working in one repository is not auto-approved
+    /// in another.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let agent = Agent::builder()
+    ///     .bedrock(ClaudeSonnet4_5)
+    ///     .with_project_grants(std::env::current_dir()?)
+    ///     .build()
+    ///     .await?;
+    /// ```
+    pub fn with_project_grants(self, dir: impl AsRef<Path>) -> Self {
+        self.with_grant_store(FileGrantStore::for_directory(dir))
+    }
+
     /// Set the timeout for authorization requests.
     ///
     /// If an authorization request is not responded to within this duration,
@@ -0,0 +1,101 @@
+//! Built-in tool that lets the model report progress
+//!
+//! Enabled with [`AgentBuilder::with_progress_tool`](super::AgentBuilder::with_progress_tool).
+//! Calls do no IO: each one becomes an [`AgentEvent::Progress`] for hooks
+//! to render, and the model gets an empty result back.
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::events::AgentEvent;
+use crate::types::ToolDefinition;
+
+use super::Agent;
+
+/// Name of the built-in progress tool
+pub const EMIT_PROGRESS_TOOL: &str = "emit_progress";
+
+/// Input accepted by the progress tool
+#[derive(Debug, Deserialize)]
+struct ProgressInput {
+    message: String,
+    #[serde(default)]
+    fraction: Option<f64>,
+}
+
+impl Agent {
+    /// Whether a call to `name` should be answered by the built-in
+    /// progress tool. A registered tool with the same name wins.
+    pub(super) fn is_progress_tool(&self, name: &str) -> bool {
+        self.progress_tool
+            && name == EMIT_PROGRESS_TOOL
+            && !self.tools.iter().any(|t| t.name() == name)
+    }
+
+    /// Definition of the progress tool sent to the model
+    pub(super) fn progress_tool_definition(&self) -> Option<ToolDefinition> {
+        self.is_progress_tool(EMIT_PROGRESS_TOOL)
+            .then(|| ToolDefinition {
+                name: EMIT_PROGRESS_TOOL.to_string(),
+                description: "Report progress on a long task to the user, such as \
+                              \"step 3 of 5 complete\". Shown outside your answer; \
+                              returns nothing."
+                    .to_string(),
+                input_schema: json!({
+                    "type": "object",
+                    "properties": {
+                        "message": {
+                            "type": "string",
+                            "description": "Short description of what has been done"
+                        },
+                        "fraction": {
+                            "type": "number",
+                            "minimum": 0,
+                            "maximum": 1,
+                            "description": "Portion of the task complete, from 0 to 1"
+                        }
+                    },
+                    "required": ["message"]
+                }),
+            })
+    }
+
+    /// Turn a progress tool call into an event
+    ///
+    /// Fractions outside `0.0..=1.0` are clamped; a missing message is an
+    /// error so the model can correct the call.
+    pub(super) fn progress_event(input: Value) -> Result<AgentEvent, String> {
+        let input: ProgressInput = serde_json::from_value(input)
+            .map_err(|e| format!("Invalid {} input: {}", EMIT_PROGRESS_TOOL, e))?;
+        Ok(AgentEvent::Progress {
+            message: input.message,
+            fraction: input
+                .fraction
+                .filter(|f| f.is_finite())
+                .map(|f| f.clamp(0.0, 1.0)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_event_clamps_fraction() {
+        let event = Agent::progress_event(json!({"message": "Halfway", "fraction": 1.5})).unwrap();
+        assert!(matches!(
+            event,
+            AgentEvent::Progress { message, fraction: Some(f) } if message == "Halfway" && f == 1.0
+        ));
+
+        let event = Agent::progress_event(json!({"message": "Started"})).unwrap();
+        assert!(matches!(event, AgentEvent::Progress { fraction: None, .. }));
+    }
+
+    #[test]
+    fn test_progress_event_requires_message() {
+        let err = Agent::progress_event(json!({"fraction": 0.5})).unwrap_err();
+        assert!(err.contains("message"));
+    }
+}
@@ -0,0 +1,229 @@
+//! Repair of tool uses left without a matching tool result
+//!
+//! Providers reject a conversation in which an assistant tool use is not
+//! answered by a tool result in the next message. That happens when a run
+//! is interrupted between the model requesting a tool and the result being
+//! recorded, for example by a crash before a session save completes.
+
+use std::collections::HashSet;
+
+use crate::tool::ToolResult;
+use crate::types::{ContentBlock, Message, Role, ToolResultBlock, ToolResultStatus};
+
+/// Tool result text used to answer a tool use that never got a result
+pub(super) const INTERRUPTED_TOOL_RESULT: &str =
+    "Tool execution was interrupted before it produced a result.";
+
+/// Assistant text used when dropping tool uses leaves a message empty
+pub(super) const DROPPED_TOOL_USE_PLACEHOLDER: &str =
+    "[Tool calls from an interrupted turn were removed.]";
+
+/// How the agent repairs tool uses that have no matching tool result
+///
+/// Repairs are applied to history loaded from a session and to the messages
+/// sent with each model call.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ToolUseRepair {
+    /// Answer each dangling tool use with an error tool result saying the
+    /// call was interrupted, so the model knows the tool never ran
+    #[default]
+    InterruptedResult,
+    /// Remove dangling tool uses from the assistant message
+    DropToolUse,
+}
+
+/// Make every tool use in `messages` have a tool result in the next message
+pub(super) fn repair_tool_uses(messages: Vec<Message>, repair: ToolUseRepair) -> Vec<Message> {
+    let mut repaired = Vec::with_capacity(messages.len());
+    let mut messages = messages.into_iter().peekable();
+
+    while let Some(mut message) = messages.next() {
+        if message.role != Role::Assistant {
+            repaired.push(message);
+            continue;
+        }
+
+        let answered: HashSet<String> = messages
+            .peek()
+            .filter(|next| next.role == Role::User)
+            .map(|next| tool_result_ids(next).map(str::to_string).collect())
+            .unwrap_or_default();
+        let dangling: Vec<String> = message
+            .tool_uses()
+            .into_iter()
+            .filter(|tool_use| !answered.contains(&tool_use.id))
+            .map(|tool_use| tool_use.id.clone())
+            .collect();
+        if dangling.is_empty() {
+            repaired.push(message);
+            continue;
+        }
+
+        match repair {
+            ToolUseRepair::InterruptedResult => {
+                let results: Vec<ContentBlock> = dangling
+                    .into_iter()
+                    .map(|tool_use_id| {
+                        ContentBlock::ToolResult(ToolResultBlock {
+                            tool_use_id,
+                            content: ToolResult::Text(INTERRUPTED_TOOL_RESULT.to_string()),
+                            status: ToolResultStatus::Error,
+                        })
+                    })
+                    .collect();
+                repaired.push(message);
+
+                // Tool results lead the next user message, so add them there
+                // rather than putting two user messages in a row
+                match messages.next_if(|next| next.role == Role::User) {
+                    Some(mut next) => {
+                        let rest = std::mem::take(&mut next.content);
+                        next.content = results;
+                        next.content.extend(rest);
+                        repaired.push(next);
+                    }
+                    None => repaired.push(Message {
+                        role: Role::User,
+                        content: results,
+                    }),
+                }
+            }
+            ToolUseRepair::DropToolUse => {
+                message.content.retain(|block| {
+                    !matches!(block, ContentBlock::ToolUse(tool_use) if dangling.contains(&tool_use.id))
+                });
+                if message.content.is_empty() {
+                    message = Message::assistant(DROPPED_TOOL_USE_PLACEHOLDER);
+                }
+                repaired.push(message);
+            }
+        }
+    }
+
+    repaired
+}
+
+fn tool_result_ids(message: &Message) -> impl Iterator<Item = &str> {
+    message.content.iter().filter_map(|block| match block {
+        ContentBlock::ToolResult(result) => Some(result.tool_use_id.as_str()),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ToolUseBlock;
+
+    fn tool_use(id: &str) -> ToolUseBlock {
+        ToolUseBlock {
+            id: id.to_string(),
+            name: "search".to_string(),
+            input: serde_json::json!({}),
+        }
+    }
+
+    fn result(id: &str) -> ToolResultBlock {
+        ToolResultBlock {
+            tool_use_id: id.to_string(),
+            content: ToolResult::Text("ok".to_string()),
+            status: ToolResultStatus::Success,
+        }
+    }
+
+    fn result_ids(message: &Message) -> Vec<&str> {
+        tool_result_ids(message).collect()
+    }
+
+    #[test]
+    fn test_balanced_history_unchanged() {
+        let messages = vec![
+            Message::user("Find it"),
+            Message::assistant_with_tool_use("", vec![tool_use("a")]),
+            Message::tool_results(vec![result("a")]),
+            Message::assistant("Found it"),
+        ];
+
+        let repaired = repair_tool_uses(messages, ToolUseRepair::InterruptedResult);
+        assert_eq!(repaired.len(), 4);
+        assert_eq!(result_ids(&repaired[2]), vec!["a"]);
+    }
+
+    #[test]
+    fn test_interrupted_result_appended_at_end() {
+        let messages = vec![
+            Message::user("Find it"),
+            Message::assistant_with_tool_use("Searching", vec![tool_use("a")]),
+        ];
+
+        let repaired = repair_tool_uses(messages, ToolUseRepair::InterruptedResult);
+        assert_eq!(repaired.len(), 3);
+        assert_eq!(repaired[2].role, Role::User);
+        match &repaired[2].content[0] {
+            ContentBlock::ToolResult(block) => {
+                assert_eq!(block.tool_use_id, "a");
+                assert_eq!(block.status, ToolResultStatus::Error);
+                assert_eq!(block.content.as_text(), INTERRUPTED_TOOL_RESULT);
+            }
+            other => panic!("expected tool result, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_interrupted_results_lead_next_user_message() {
+        let messages = vec![
+            Message::user("Find it"),
+            Message::assistant_with_tool_use("", vec![tool_use("a"), tool_use("b")]),
+            Message::tool_results(vec![result("a")]),
+            Message::assistant("Partial"),
+            Message::user("Try again"),
+        ];
+
+        let repaired = repair_tool_uses(messages, ToolUseRepair::InterruptedResult);
+        assert_eq!(repaired.len(), 5);
+        assert_eq!(result_ids(&repaired[2]), vec!["b", "a"]);
+
+        // A text-only user message also gets the results in front
+        let messages = vec![
+            Message::assistant_with_tool_use("", vec![tool_use("a")]),
+            Message::user("Never mind"),
+        ];
+        let repaired = repair_tool_uses(messages, ToolUseRepair::InterruptedResult);
+        assert_eq!(repaired.len(), 2);
+        assert_eq!(result_ids(&repaired[1]), vec!["a"]);
+        assert_eq!(repaired[1].text(), "Never mind");
+    }
+
+    #[test]
+    fn test_drop_tool_use() {
+        let messages = vec![
+            Message::user("Find it"),
+            Message::assistant_with_tool_use("Searching", vec![tool_use("a"), tool_use("b")]),
+            Message::tool_results(vec![result("a")]),
+        ];
+
+        let repaired = repair_tool_uses(messages, ToolUseRepair::DropToolUse);
+        assert_eq!(repaired.len(), 3);
+        let ids: Vec<_> = repaired[1]
+            .tool_uses()
+            .into_iter()
+            .map(|t| t.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["a"]);
+        assert_eq!(repaired[1].text(), "Searching");
+    }
+
+    #[test]
+    fn test_drop_tool_use_keeps_placeholder_turn() {
+        let messages = vec![
+            Message::user("Find it"),
+            Message::assistant_with_content(vec![ContentBlock::ToolUse(tool_use("a"))]),
+            Message::user("Hello?"),
+        ];
+
+        let repaired = repair_tool_uses(messages, ToolUseRepair::DropToolUse);
+        assert_eq!(repaired.len(), 3);
+        assert_eq!(repaired[1].role, Role::Assistant);
+        assert_eq!(repaired[1].text(), DROPPED_TOOL_USE_PLACEHOLDER);
+    }
+}
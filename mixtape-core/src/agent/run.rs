@@ -1,20 +1,34 @@
 //! The agentic loop - core execution logic for Agent
 
-use std::time::Instant;
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::time::{Duration, Instant};
 
-use crate::events::AgentEvent;
-use crate::types::{Message, StopReason, ToolDefinition};
+use futures::StreamExt;
+
+use crate::conversation::{
+    opens_turn, transcript, BoxedConversationManager, ContextLimits, ConversationStrategy,
+};
+use crate::events::{AgentEvent, TokenUsage};
+use crate::filter::FilterStage;
+use crate::interceptor::RequestContext;
+use crate::types::{ContentBlock, Message, StopReason};
 
 use super::context::{build_effective_prompt, resolve_context, ContextLoadResult, PathVariables};
 use super::helpers::extract_text_response;
-use super::types::{AgentError, AgentResponse, TokenUsageStats, ToolCallInfo};
+use super::history::truncate_conversation;
+use super::repair::repair_tool_uses;
+use super::types::{
+    AgentError, AgentResponse, IntendedToolCall, OverflowPolicy, PlannedRequest, RefusalPolicy,
+    TokenUsageStats, ToolCallInfo,
+};
 use super::Agent;
 
 #[cfg(feature = "session")]
 use crate::session::{MessageRole, Session, SessionMessage, ToolCall, ToolResult};
 
 #[cfg(feature = "session")]
-use super::session::convert_session_message_to_mixtape;
+use super::session::convert_session_history;
 
 impl Agent {
     /// Run the agent with a user message
@@ -36,16 +50,224 @@ impl Agent {
     /// - `Session` - Session storage errors (if session feature enabled)
     /// - `NoResponse` - Model returned no text
     /// - `MaxTokensExceeded` - Response hit token limit
-    /// - `ContentFiltered` - Response was filtered
+    /// - `ContentFiltered` - Input or response was blocked by the provider or a
+    ///   [`ContentFilter`](crate::filter::ContentFilter)
+    /// - `Refused` - The model declined to respond, under
+    ///   [`RefusalPolicy::Error`]
     /// - `ToolDenied` - Tool execution was denied by user/policy
+    /// - `ContextOverflow` - The request does not fit the model's context
+    ///   window, under [`OverflowPolicy::Error`] or
+    ///   [`OverflowPolicy::Trim`]
     pub async fn run(&self, user_message: &str) -> Result<AgentResponse, AgentError> {
+        self.run_message(Message::user(user_message)).await
+    }
+
+    /// Run the agent with a wall-clock limit on the whole run
+    ///
+    /// The limit covers everything [`run`](Self::run) does: model calls,
+    /// provider retries, tool executions, and permission prompts. Per-tool
+    /// timeouts and retry budgets still apply within it. When the limit is
+    /// reached the run is dropped where it stands, cancelling any model
+    /// call or tool in flight, and a [`AgentEvent::RunFailed`] is emitted.
+    ///
+    /// # Errors
+    ///
+    /// Besides the errors of [`run`](Self::run), returns
+    /// [`AgentError::Timeout`] on expiry. Its `last_turn` holds the last
+    /// assistant response the model completed before the deadline, the
+    /// same turn reported by the latest [`AgentEvent::TurnCompleted`].
+    /// Messages already added to the conversation are kept; tool uses left
+    /// without results are repaired before the next model call.
+    ///
+    /// ```ignore
+    /// match agent.run_with_timeout("Audit the repo", Duration::from_secs(300)).await {
+    ///     Err(AgentError::Timeout { last_turn: Some(turn), .. }) => println!("{}", turn.text()),
+    ///     result => println!("{}", result?),
+    /// }
+    /// ```
+    pub async fn run_with_timeout(
+        &self,
+        user_message: &str,
+        timeout: Duration,
+    ) -> Result<AgentResponse, AgentError> {
         let run_start = Instant::now();
+        let last_turn = parking_lot::Mutex::new(None);
+        let run = self.run_in(Message::user(user_message), None, Some(&last_turn));
+        match tokio::time::timeout(timeout, run).await {
+            Ok(result) => result,
+            Err(_elapsed) => {
+                let error = AgentError::Timeout {
+                    timeout,
+                    last_turn: last_turn.into_inner(),
+                };
+                self.emit_event(AgentEvent::RunFailed {
+                    error: error.to_string(),
+                    duration: run_start.elapsed(),
+                })
+                .await;
+                Err(error)
+            }
+        }
+    }
+
+    /// Run the agent until it finishes or `interrupt` completes
+    ///
+    /// Use this to let a user cancel a run, e.g. on Ctrl+C. As with
+    /// [`run_with_timeout`](Self::run_with_timeout), an interrupted run is
+    /// dropped where it stands and a [`AgentEvent::RunFailed`] is emitted.
+    ///
+    /// # Errors
+    ///
+    /// Besides the errors of [`run`](Self::run), returns
+    /// [`AgentError::Interrupted`] when `interrupt` completes first.
+    ///
+    /// ```ignore
+    /// let (cancel, cancelled) = tokio::sync::oneshot::channel::<()>();
+    /// let result = agent.run_until("Audit the repo", async { cancelled.await.ok(); }).await;
+    /// ```
+    pub async fn run_until(
+        &self,
+        user_message: &str,
+        interrupt: impl std::future::Future<Output = ()>,
+    ) -> Result<AgentResponse, AgentError> {
+        let run_start = Instant::now();
+        tokio::select! {
+            result = self.run(user_message) => result,
+            () = interrupt => {
+                let error = AgentError::Interrupted;
+                self.emit_event(AgentEvent::RunFailed {
+                    error: error.to_string(),
+                    duration: run_start.elapsed(),
+                })
+                .await;
+                Err(error)
+            }
+        }
+    }
+
+    /// Run several independent inputs concurrently
+    ///
+    /// At most `concurrency` runs are in flight at once (at least one).
+    /// Each input starts from an empty conversation managed by
+    /// [`ConversationStrategy::default`], so runs never see each other's
+    /// messages, and the agent's own history and session are left
+    /// untouched. The runs share the agent's provider, tools, and hooks;
+    /// events from different runs interleave.
+    ///
+    /// Results are returned in input order.
+    ///
+    /// ```ignore
+    /// let results = agent
+    ///     .run_many(vec!["Summarize a.txt".into(), "Summarize b.txt".into()], 4)
+    ///     .await;
+    /// ```
+    pub async fn run_many(
+        &self,
+        inputs: Vec<String>,
+        concurrency: usize,
+    ) -> Vec<Result<AgentResponse, AgentError>> {
+        futures::stream::iter(inputs)
+            .map(|input| async move {
+                let conversation =
+                    parking_lot::RwLock::new(ConversationStrategy::default().into_manager());
+                self.run_in(Message::user(input), Some(&conversation), None)
+                    .await
+            })
+            .buffered(concurrency.max(1))
+            .collect()
+            .await
+    }
+
+    /// Assemble the request the next [`run`](Self::run) would send first,
+    /// without calling the model
+    ///
+    /// The plan goes through the same steps as a run: context files are
+    /// loaded into the system prompt, `input` is added to the conversation
+    /// and windowed by the conversation manager, and request interceptors
+    /// are applied. Content filters are not, session history is not loaded,
+    /// and the conversation is left unchanged.
+    ///
+    /// ```ignore
+    /// let plan = agent.plan_request("Summarize the README").await?;
+    /// println!("{:?}", plan.system_prompt);
+    /// ```
+    pub async fn plan_request(&self, input: &str) -> Result<PlannedRequest, AgentError> {
+        let context_result = self.resolve_context_files()?;
+        let system_prompt = self.effective_system_prompt(&context_result);
+
+        // Window the input with the live manager, then put the history back
+        // exactly as it was
+        let messages = {
+            let mut manager = self.conversation_manager.write();
+            let history = manager.all_messages().to_vec();
+            manager.add_message(Message::user(input));
+            let messages = self.context_messages(&manager);
+            manager.hydrate(history);
+            messages
+        };
+
+        let request = self.prepare_request(messages, system_prompt).await?;
+        Ok(PlannedRequest {
+            messages: request.messages,
+            tools: request.tools,
+            system_prompt: request.system_prompt,
+            sampling: self.provider.settings().sampling,
+        })
+    }
+
+    /// Run the agentic loop starting from `message`
+    ///
+    /// The message's text is what gets reported in events and saved to the
+    /// session.
+    pub(super) async fn run_message(&self, message: Message) -> Result<AgentResponse, AgentError> {
+        self.run_in(message, None, None).await
+    }
+
+    /// Run the agentic loop in `conversation`, or in the agent's own
+    /// conversation when `None`
+    ///
+    /// A separate conversation is never loaded from or saved to the session.
+    /// Each completed assistant turn is recorded in `last_turn`, if given.
+    async fn run_in(
+        &self,
+        message: Message,
+        conversation: Option<&parking_lot::RwLock<BoxedConversationManager>>,
+        last_turn: Option<&parking_lot::Mutex<Option<Message>>>,
+    ) -> Result<AgentResponse, AgentError> {
+        #[cfg(feature = "session")]
+        let session_store = self
+            .session_store
+            .as_ref()
+            .filter(|_| conversation.is_none());
+        let conversation = conversation.unwrap_or(&self.conversation_manager);
+        let run_start = Instant::now();
+
+        // Moderate the user message before anything else sees it
+        let message = match self
+            .apply_content_filters(FilterStage::Input, message)
+            .await
+        {
+            Ok(message) => message,
+            Err(error) => {
+                self.emit_event(AgentEvent::RunFailed {
+                    error: error.to_string(),
+                    duration: run_start.elapsed(),
+                })
+                .await;
+                return Err(error);
+            }
+        };
+        let user_message = message.text();
+        let user_message = user_message.as_str();
 
         // Track execution statistics
         let mut tool_call_infos: Vec<ToolCallInfo> = Vec::new();
+        let mut intended_tool_calls: Vec<IntendedToolCall> = Vec::new();
         let mut total_input_tokens: usize = 0;
         let mut total_output_tokens: usize = 0;
         let mut model_call_count: usize = 0;
+        let mut model_latency = Duration::ZERO;
+        let mut run_messages: Vec<Message> = Vec::new();
 
         // Resolve context files at runtime
         let context_result = self.resolve_context_files()?;
@@ -53,34 +275,33 @@ impl Agent {
         // Store for inspection via last_context_info()
         *self.last_context_result.write() = Some(context_result.clone());
 
-        // Build effective system prompt with context files
-        let effective_system_prompt =
-            build_effective_prompt(self.system_prompt.as_deref(), &context_result);
+        // Build effective system prompt with context files and tool policy
+        let effective_system_prompt = self.effective_system_prompt(&context_result);
 
         // Emit run started event
         self.emit_event(AgentEvent::RunStarted {
+            agent_name: self.name.clone(),
             input: user_message.to_string(),
             timestamp: run_start,
-        });
+        })
+        .await;
 
         // Load or create session if session store is configured
         #[cfg(feature = "session")]
-        let mut session: Option<Session> = if let Some(store) = &self.session_store {
+        let mut session: Option<Session> = if let Some(store) = session_store {
             let sess = store.get_or_create_session().await?;
 
             // Hydrate conversation manager from session history
             if !sess.messages.is_empty() {
-                let mut messages: Vec<Message> = vec![];
-                for msg in &sess.messages {
-                    messages.extend(convert_session_message_to_mixtape(msg)?);
-                }
-                self.conversation_manager.write().hydrate(messages);
+                let messages = convert_session_history(&sess.messages, self.tool_use_repair)?;
+                conversation.write().hydrate(messages);
 
                 self.emit_event(AgentEvent::SessionResumed {
                     session_id: sess.id.clone(),
                     message_count: sess.messages.len(),
                     created_at: sess.created_at,
-                });
+                })
+                .await;
             }
 
             Some(sess)
@@ -93,78 +314,168 @@ impl Agent {
         #[cfg(feature = "session")]
         let mut session_tool_results: Vec<ToolResult> = Vec::new();
 
-        // Add new user message to conversation manager
-        self.conversation_manager
-            .write()
-            .add_message(Message::user(user_message));
+        // Add new user message to conversation manager, remembering where
+        // this run's messages start in case its output is blocked
+        let history_len = {
+            let mut manager = conversation.write();
+            let len = manager.all_messages().len();
+            manager.add_message(message);
+            len
+        };
+
+        // Consecutive malformed inputs per tool, and whether the next model
+        // call must answer in text to break a loop of them
+        let mut malformed_calls: HashMap<String, usize> = HashMap::new();
+        let mut text_only_turn = false;
 
         loop {
-            // Build tool definitions
-            let tool_defs: Vec<ToolDefinition> = self
-                .tools
-                .iter()
-                .map(|t| ToolDefinition {
-                    name: t.name().to_string(),
-                    description: t.description().to_string(),
-                    input_schema: t.input_schema(),
-                })
-                .collect();
+            // Let the conversation manager fold older messages into a summary
+            if let Some(usage) = self.summarize_conversation(conversation).await {
+                total_input_tokens += usage.input_tokens;
+                total_output_tokens += usage.output_tokens;
+            }
 
             // Get messages for context from conversation manager
-            let limits =
-                crate::conversation::ContextLimits::new(self.provider.max_context_tokens());
-            let provider = &self.provider;
-            let estimate_tokens = |msgs: &[Message]| provider.estimate_message_tokens(msgs);
-            let context_messages = self
-                .conversation_manager
-                .read()
-                .messages_for_context(limits, &estimate_tokens);
+            let context_messages = self.context_messages(&conversation.read());
+            // An oversized request is removed from history so the next run
+            // doesn't overflow on it again
+            let request = match self
+                .prepare_request(context_messages, effective_system_prompt.clone())
+                .await
+            {
+                Ok(request) => request,
+                Err(error) => {
+                    truncate_conversation(&mut conversation.write(), history_len);
+                    self.emit_event(AgentEvent::RunFailed {
+                        error: error.to_string(),
+                        duration: run_start.elapsed(),
+                    })
+                    .await;
+                    return Err(error);
+                }
+            };
+
+            if let Some(threshold) = self.input_token_warning {
+                let estimated_input_tokens = self.estimate_request_tokens(&request);
+                if estimated_input_tokens > threshold {
+                    self.emit_event(AgentEvent::BudgetWarning {
+                        estimated_input_tokens,
+                        threshold,
+                    })
+                    .await;
+                }
+            }
+
+            let mut stale_tools = None;
+            if let Some(event) = self.track_cache_prefix(&request) {
+                if let AgentEvent::CacheInvalidated { tools_changed, .. } = event {
+                    stale_tools = self.stale_prefix_uncached.then_some(tools_changed);
+                }
+                self.emit_event(event).await;
+            }
 
             // Emit model call started event
-            let model_call_start = Instant::now();
             self.emit_event(AgentEvent::ModelCallStarted {
-                message_count: context_messages.len(),
-                tool_count: tool_defs.len(),
-                timestamp: model_call_start,
-            });
-
-            // Call the model via provider with streaming
+                message_count: request.messages.len(),
+                tool_count: request.tools.len(),
+                timestamp: Instant::now(),
+            })
+            .await;
+
+            // Call the model via provider with streaming, timed after the
+            // hooks above so their time isn't counted as model latency
+            let mut call_provider = if std::mem::take(&mut text_only_turn) {
+                self.provider.without_tool_use()
+            } else {
+                None
+            };
+            if let Some(tools_changed) = stale_tools {
+                let base = call_provider.as_deref().unwrap_or(self.provider.as_ref());
+                if let Some(provider) = base.without_stale_cache_breakpoints(tools_changed) {
+                    call_provider = Some(provider);
+                }
+            }
+            let provider = call_provider.as_deref().unwrap_or(self.provider.as_ref());
+            let model_call_start = Instant::now();
             let response = self
                 .generate_with_streaming(
-                    context_messages,
-                    tool_defs,
-                    effective_system_prompt.clone(),
+                    provider,
+                    request.messages,
+                    request.tools,
+                    request.system_prompt,
                 )
                 .await?;
+            let model_call_duration = model_call_start.elapsed();
 
             // Track model call stats
             model_call_count += 1;
+            model_latency += model_call_duration;
+            let served_model = response.model.clone();
             if let Some(ref usage) = response.usage {
                 total_input_tokens += usage.input_tokens;
                 total_output_tokens += usage.output_tokens;
             }
 
-            // Emit model call completed event
-            let response_text = response.message.text();
+            // Moderate the response before any hook sees it. A blocked run is
+            // removed from history so the model never sees the filtered text again.
+            let response_message = match self
+                .apply_content_filters(FilterStage::Output, response.message)
+                .await
+            {
+                Ok(message) => message,
+                Err(error) => {
+                    truncate_conversation(&mut conversation.write(), history_len);
+                    self.emit_event(AgentEvent::RunFailed {
+                        error: error.to_string(),
+                        duration: run_start.elapsed(),
+                    })
+                    .await;
+                    return Err(error);
+                }
+            };
+            let response_text = response_message.text();
+
+            // Deltas are held back while filters are registered, so the
+            // filtered text is streamed in one piece
+            if !self.content_filters.is_empty() && !response_text.is_empty() {
+                self.emit_event(AgentEvent::ModelCallStreaming {
+                    delta: response_text.clone(),
+                    accumulated_length: response_text.len(),
+                })
+                .await;
+            }
 
+            // Emit model call completed event
             self.emit_event(AgentEvent::ModelCallCompleted {
                 response_content: response_text,
                 tokens: response.usage,
-                duration: model_call_start.elapsed(),
+                duration: model_call_duration,
                 stop_reason: Some(response.stop_reason),
-            });
+            })
+            .await;
 
             // Add assistant response to conversation manager
-            self.conversation_manager
-                .write()
-                .add_message(response.message.clone());
+            conversation.write().add_message(response_message.clone());
+            run_messages.push(response_message.clone());
+            if let Some(last_turn) = last_turn {
+                *last_turn.lock() = Some(response_message.clone());
+            }
+
+            self.emit_event(AgentEvent::TurnCompleted {
+                turn_index: model_call_count - 1,
+                stop_reason: response.stop_reason,
+            })
+            .await;
 
             match response.stop_reason {
                 StopReason::ToolUse => {
+                    let recoveries =
+                        self.tool_json_recoveries(&response_message, &mut malformed_calls);
                     let tool_results = self
                         .process_tool_calls(
-                            &response.message,
+                            &response_message,
                             &mut tool_call_infos,
+                            &mut intended_tool_calls,
                             #[cfg(feature = "session")]
                             &mut session_tool_calls,
                             #[cfg(feature = "session")]
@@ -173,19 +484,43 @@ impl Agent {
                         .await;
 
                     // Add tool results to conversation manager
-                    self.conversation_manager
-                        .write()
-                        .add_message(Message::tool_results(tool_results));
+                    let mut results_message = Message::tool_results(tool_results);
+                    for (name, attempts) in recoveries {
+                        self.emit_event(AgentEvent::ToolJsonRecovery {
+                            name: name.clone(),
+                            attempts,
+                        })
+                        .await;
+                        results_message
+                            .content
+                            .push(ContentBlock::Text(tool_json_recovery_note(&name, attempts)));
+                        text_only_turn = true;
+                    }
+                    conversation.write().add_message(results_message.clone());
+                    run_messages.push(results_message);
+
+                    #[cfg(feature = "session")]
+                    self.checkpoint_session(
+                        &session,
+                        user_message,
+                        &session_tool_calls,
+                        &session_tool_results,
+                    )
+                    .await?;
                 }
                 StopReason::EndTurn => {
                     return self
                         .finalize_run(
-                            &response.message,
+                            response_message,
+                            run_messages,
                             user_message,
                             tool_call_infos,
+                            intended_tool_calls,
                             total_input_tokens,
                             total_output_tokens,
                             model_call_count,
+                            model_latency,
+                            served_model,
                             run_start,
                             #[cfg(feature = "session")]
                             &mut session,
@@ -200,26 +535,53 @@ impl Agent {
                     self.emit_event(AgentEvent::RunFailed {
                         error: AgentError::MaxTokensExceeded.to_string(),
                         duration: run_start.elapsed(),
-                    });
+                    })
+                    .await;
                     return Err(AgentError::MaxTokensExceeded);
                 }
                 StopReason::ContentFiltered => {
                     self.emit_event(AgentEvent::RunFailed {
                         error: AgentError::ContentFiltered.to_string(),
                         duration: run_start.elapsed(),
-                    });
+                    })
+                    .await;
                     return Err(AgentError::ContentFiltered);
                 }
-                StopReason::StopSequence => {
-                    // Treat stop sequence similar to EndTurn - extract text response
+                StopReason::Refusal if self.refusal_policy == RefusalPolicy::Error => {
+                    let reason = extract_text_response(&response_message).unwrap_or_default();
+                    self.emit_event(AgentEvent::Refused {
+                        reason: reason.clone(),
+                    })
+                    .await;
+
+                    let error = AgentError::Refused(reason);
+                    self.emit_event(AgentEvent::RunFailed {
+                        error: error.to_string(),
+                        duration: run_start.elapsed(),
+                    })
+                    .await;
+                    return Err(error);
+                }
+                StopReason::StopSequence | StopReason::Refusal => {
+                    // Treat stop sequence similar to EndTurn - extract text response.
+                    // A refusal only gets here under RefusalPolicy::Pass.
                     let final_response =
-                        extract_text_response(&response.message).unwrap_or_default();
+                        extract_text_response(&response_message).unwrap_or_default();
+                    let refused = response.stop_reason == StopReason::Refusal;
+                    if refused {
+                        self.emit_event(AgentEvent::Refused {
+                            reason: final_response.clone(),
+                        })
+                        .await;
+                    }
 
                     let duration = run_start.elapsed();
                     self.emit_event(AgentEvent::RunCompleted {
+                        agent_name: self.name.clone(),
                         output: final_response.clone(),
                         duration,
-                    });
+                    })
+                    .await;
 
                     let token_usage = if total_input_tokens > 0 || total_output_tokens > 0 {
                         Some(TokenUsageStats {
@@ -233,9 +595,15 @@ impl Agent {
                     return Ok(AgentResponse {
                         text: final_response,
                         tool_calls: tool_call_infos,
+                        intended_tool_calls,
                         token_usage,
                         duration,
                         model_calls: model_call_count,
+                        model_latency,
+                        model: served_model,
+                        message: response_message,
+                        messages: run_messages,
+                        refused,
                     });
                 }
                 StopReason::PauseTurn => {
@@ -247,7 +615,8 @@ impl Agent {
                     self.emit_event(AgentEvent::RunFailed {
                         error: error.to_string(),
                         duration: run_start.elapsed(),
-                    });
+                    })
+                    .await;
                     return Err(error);
                 }
             }
@@ -259,18 +628,22 @@ impl Agent {
     #[allow(unused_variables)] // user_message only used with session feature
     async fn finalize_run(
         &self,
-        message: &Message,
+        message: Message,
+        messages: Vec<Message>,
         user_message: &str,
         tool_call_infos: Vec<ToolCallInfo>,
+        intended_tool_calls: Vec<IntendedToolCall>,
         total_input_tokens: usize,
         total_output_tokens: usize,
         model_call_count: usize,
+        model_latency: Duration,
+        model: Option<String>,
         run_start: Instant,
         #[cfg(feature = "session")] session: &mut Option<Session>,
         #[cfg(feature = "session")] session_tool_calls: &[ToolCall],
         #[cfg(feature = "session")] session_tool_results: &[ToolResult],
     ) -> Result<AgentResponse, AgentError> {
-        let final_response = extract_text_response(message).ok_or(AgentError::NoResponse)?;
+        let final_response = extract_text_response(&message).ok_or(AgentError::NoResponse)?;
 
         // Save session if configured
         #[cfg(feature = "session")]
@@ -302,15 +675,18 @@ impl Agent {
             self.emit_event(AgentEvent::SessionSaved {
                 session_id: sess.id.clone(),
                 message_count: sess.messages.len(),
-            });
+            })
+            .await;
         }
 
         // Emit run completed event
         let duration = run_start.elapsed();
         self.emit_event(AgentEvent::RunCompleted {
+            agent_name: self.name.clone(),
             output: final_response.clone(),
             duration,
-        });
+        })
+        .await;
 
         // Build token usage stats
         let token_usage = if total_input_tokens > 0 || total_output_tokens > 0 {
@@ -325,12 +701,167 @@ impl Agent {
         Ok(AgentResponse {
             text: final_response,
             tool_calls: tool_call_infos,
+            intended_tool_calls,
             token_usage,
             duration,
             model_calls: model_call_count,
+            model_latency,
+            model,
+            message,
+            messages,
+            refused: false,
+        })
+    }
+
+    /// System prompt with loaded context files and tool policy guidance
+    fn effective_system_prompt(&self, context_result: &ContextLoadResult) -> Option<String> {
+        let prompt = build_effective_prompt(self.system_prompt.as_deref(), context_result);
+        match (prompt, self.tool_policy_prompt()) {
+            (Some(prompt), Some(policy)) => Some(format!("{}\n\n{}", prompt, policy)),
+            (prompt, policy) => prompt.or(policy),
+        }
+    }
+
+    /// Summarize older messages when the conversation manager asks for it,
+    /// returning the token usage of the summary call
+    ///
+    /// A failed summary call is skipped; the manager windows out the
+    /// unsummarized messages instead and asks again before the next call.
+    async fn summarize_conversation(
+        &self,
+        conversation: &parking_lot::RwLock<BoxedConversationManager>,
+    ) -> Option<TokenUsage> {
+        let limits = ContextLimits::new(self.provider.max_context_tokens());
+        let provider = &self.provider;
+        let estimate_tokens = |msgs: &[Message]| provider.estimate_message_tokens(msgs);
+        let messages = conversation
+            .read()
+            .messages_to_summarize(limits, &estimate_tokens)?;
+
+        let prompt = format!("{}\n\n{}", SUMMARY_PROMPT, transcript(&messages));
+        let response = self
+            .provider
+            .generate(vec![Message::user(prompt)], Vec::new(), None)
+            .await
+            .ok()?;
+        conversation
+            .write()
+            .apply_summary(messages.len(), response.message.text());
+        response.usage
+    }
+
+    /// The conversation's messages that fit the model's context window
+    fn context_messages(&self, manager: &BoxedConversationManager) -> Vec<Message> {
+        let limits = crate::conversation::ContextLimits::new(self.provider.max_context_tokens());
+        let provider = &self.provider;
+        let estimate_tokens = |msgs: &[Message]| provider.estimate_message_tokens(msgs);
+        let messages = manager.messages_for_context(limits, &estimate_tokens);
+
+        // A window with no room for even the latest message would send an
+        // empty request; keep that message so the overflow policy sees it
+        match manager.all_messages().last() {
+            Some(latest) if messages.is_empty() && self.overflow_policy != OverflowPolicy::Send => {
+                vec![latest.clone()]
+            }
+            _ => messages,
+        }
+    }
+
+    /// Add tool definitions, let interceptors modify the request, apply
+    /// the overflow policy, and repair dangling tool uses
+    async fn prepare_request(
+        &self,
+        messages: Vec<Message>,
+        system_prompt: Option<String>,
+    ) -> Result<RequestContext, AgentError> {
+        let mut request = RequestContext {
+            messages,
+            tools: self.tool_definitions(),
+            system_prompt,
+        };
+        for interceptor in &self.interceptors {
+            interceptor.before_generate(&mut request).await;
+        }
+        self.fit_context_window(&mut request)?;
+        request.messages = repair_tool_uses(request.messages, self.tool_use_repair);
+        Ok(request)
+    }
+
+    /// Record the request's cacheable prefix, returning a
+    /// [`AgentEvent::CacheInvalidated`] when it differs from the last call's
+    fn track_cache_prefix(&self, request: &RequestContext) -> Option<AgentEvent> {
+        let system_prompt = prefix_hash(&request.system_prompt);
+        let tools = prefix_hash(&serde_json::to_string(&request.tools).unwrap_or_default());
+
+        let previous = self.cache_prefix.lock().replace((system_prompt, tools))?;
+        let system_prompt_changed = previous.0 != system_prompt;
+        let tools_changed = previous.1 != tools;
+        (system_prompt_changed || tools_changed).then_some(AgentEvent::CacheInvalidated {
+            system_prompt_changed,
+            tools_changed,
         })
     }
 
+    /// Check the request's estimated size against the context window,
+    /// trimming it under [`OverflowPolicy::Trim`]
+    fn fit_context_window(&self, request: &mut RequestContext) -> Result<(), AgentError> {
+        if self.overflow_policy == OverflowPolicy::Send {
+            return Ok(());
+        }
+
+        let provider = &self.provider;
+        let max_context_tokens = provider.max_context_tokens();
+        let fixed_tokens = self.fixed_request_tokens(request);
+        let estimate =
+            |messages: &[Message]| fixed_tokens + provider.estimate_message_tokens(messages);
+
+        let mut estimated_tokens = estimate(&request.messages);
+        if self.overflow_policy == OverflowPolicy::Trim {
+            while estimated_tokens > max_context_tokens && request.messages.len() > 1 {
+                request.messages.remove(0);
+                // The request must open with a user turn that isn't only
+                // results for tool uses just dropped
+                while request.messages.len() > 1 && !opens_turn(&request.messages[0]) {
+                    request.messages.remove(0);
+                }
+                estimated_tokens = estimate(&request.messages);
+            }
+        }
+
+        if estimated_tokens > max_context_tokens {
+            return Err(AgentError::ContextOverflow {
+                estimated_tokens,
+                max_context_tokens,
+            });
+        }
+        Ok(())
+    }
+
+    /// Estimated input tokens of a request
+    fn estimate_request_tokens(&self, request: &RequestContext) -> usize {
+        self.fixed_request_tokens(request)
+            + self.provider.estimate_message_tokens(&request.messages)
+    }
+
+    /// Estimated tokens of a request's system prompt and tool definitions
+    fn fixed_request_tokens(&self, request: &RequestContext) -> usize {
+        let provider = &self.provider;
+        let prompt_tokens = request
+            .system_prompt
+            .as_deref()
+            .map_or(0, |prompt| provider.estimate_token_count(prompt));
+        let tool_tokens: usize = request
+            .tools
+            .iter()
+            .map(|tool| {
+                provider.estimate_token_count(&tool.name)
+                    + provider.estimate_token_count(&tool.description)
+                    + provider.estimate_token_count(&tool.input_schema.to_string())
+            })
+            .sum();
+        prompt_tokens + tool_tokens
+    }
+
     /// Resolve context files from configured sources
     fn resolve_context_files(&self) -> Result<ContextLoadResult, AgentError> {
         if self.context_sources.is_empty() {
@@ -341,3 +872,26 @@ impl Agent {
         resolve_context(&self.context_sources, &vars, &self.context_config).map_err(|e| e.into())
     }
 }
+
+/// Instruction sent ahead of the transcript when summarizing a conversation
+const SUMMARY_PROMPT: &str = "Summarize the conversation below so that it can \
+    stand in for the original messages. Keep the user's goals, decisions made, \
+    facts learned, tool results still relevant, and any open tasks. Reply with \
+    the summary only.";
+
+/// Hash of one part of a request's cacheable prefix
+fn prefix_hash<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Instruction sent with the tool results that trigger a tool JSON recovery
+fn tool_json_recovery_note(name: &str, attempts: usize) -> String {
+    format!(
+        "Your last {} calls to `{}` had input that was not a valid JSON object. \
+         Do not call tools in your next reply: say in text what input you meant \
+         to send, then call the tool again with corrected input.",
+        attempts, name
+    )
+}
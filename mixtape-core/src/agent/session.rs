@@ -3,14 +3,21 @@
 //! This module provides session persistence and message conversion.
 //! Only available when the `session` feature is enabled.
 
-use crate::session::{MessageRole, SessionError, SessionMessage};
+use crate::session::{
+    MessageRole, Session, SessionError, SessionMessage, SessionSummary, ToolCall,
+};
 use crate::tool::ToolResult;
 use crate::types::{ContentBlock, Message, Role, ToolResultBlock, ToolResultStatus, ToolUseBlock};
 use serde_json::Value;
 
-use super::types::SessionInfo;
+use super::repair::{repair_tool_uses, ToolUseRepair};
+use super::types::{AgentError, SessionInfo};
 use super::Agent;
 
+/// Assistant text used to close a turn that was interrupted after an incremental save
+pub(super) const INTERRUPTED_TURN_PLACEHOLDER: &str =
+    "[The previous turn was interrupted before a final response was produced.]";
+
 // =============================================================================
 // Agent methods
 // =============================================================================
@@ -48,6 +55,20 @@ impl Agent {
         }
     }
 
+    /// Search stored sessions for messages matching `query`
+    ///
+    /// Returns an empty list when no session store is configured.
+    pub async fn search_sessions(
+        &self,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<SessionSummary>, SessionError> {
+        match &self.session_store {
+            Some(store) => store.search(query, limit).await,
+            None => Ok(Vec::new()),
+        }
+    }
+
     /// Clear the current session (delete stored history for this directory).
     ///
     /// This is idempotent: if no session store is configured, it succeeds silently.
@@ -58,12 +79,84 @@ impl Agent {
         }
         Ok(())
     }
+
+    /// Persist the in-progress turn without modifying the run's session snapshot
+    ///
+    /// Writes the prior history plus the current user message and an assistant
+    /// message holding the tool calls/results recorded so far. The final save in
+    /// `finalize_run` overwrites this checkpoint with the completed turn.
+    pub(super) async fn checkpoint_session(
+        &self,
+        session: &Option<Session>,
+        user_message: &str,
+        tool_calls: &[ToolCall],
+        tool_results: &[crate::session::ToolResult],
+    ) -> Result<(), AgentError> {
+        let (Some(sess), Some(store)) = (session, &self.session_store) else {
+            return Ok(());
+        };
+        if !self.incremental_session_save || tool_calls.is_empty() {
+            return Ok(());
+        }
+
+        use chrono::Utc;
+
+        let mut checkpoint = sess.clone();
+        checkpoint.messages.push(SessionMessage {
+            role: MessageRole::User,
+            content: user_message.to_string(),
+            tool_calls: vec![],
+            tool_results: vec![],
+            timestamp: Utc::now(),
+        });
+        checkpoint.messages.push(SessionMessage {
+            role: MessageRole::Assistant,
+            content: String::new(),
+            tool_calls: tool_calls.to_vec(),
+            tool_results: tool_results.to_vec(),
+            timestamp: Utc::now(),
+        });
+
+        store.save_session(&checkpoint).await?;
+
+        self.emit_event(crate::events::AgentEvent::SessionSaved {
+            session_id: checkpoint.id.clone(),
+            message_count: checkpoint.messages.len(),
+        })
+        .await;
+
+        Ok(())
+    }
 }
 
 // =============================================================================
 // Session message conversion
 // =============================================================================
 
+/// Convert a full session history to mixtape messages for hydration.
+///
+/// Tool calls saved without a result are repaired according to `repair`.
+/// A session checkpointed by an incremental save and never finalized ends with
+/// tool results but no assistant reply. That turn is kept, and a placeholder
+/// assistant message is appended so the next user message doesn't follow
+/// another user message.
+pub(super) fn convert_session_history(
+    session_messages: &[SessionMessage],
+    repair: ToolUseRepair,
+) -> Result<Vec<Message>, SessionError> {
+    let mut messages = Vec::new();
+    for msg in session_messages {
+        messages.extend(convert_session_message_to_mixtape(msg)?);
+    }
+    let mut messages = repair_tool_uses(messages, repair);
+
+    if matches!(messages.last(), Some(last) if last.role == Role::User) {
+        messages.push(Message::assistant(INTERRUPTED_TURN_PLACEHOLDER));
+    }
+
+    Ok(messages)
+}
+
 /// Convert a session message to one or more mixtape messages.
 ///
 /// This may return multiple messages because the format requires:
@@ -208,4 +301,63 @@ mod tests {
         assert!(matches!(messages[0].role, Role::Assistant));
         assert_eq!(messages[0].text(), "Here's my response");
     }
+
+    #[test]
+    fn test_convert_history_closes_interrupted_turn() {
+        let history = vec![
+            SessionMessage {
+                role: MessageRole::User,
+                content: "Search for it".to_string(),
+                tool_calls: vec![],
+                tool_results: vec![],
+                timestamp: Utc::now(),
+            },
+            SessionMessage {
+                role: MessageRole::Assistant,
+                content: "".to_string(),
+                tool_calls: vec![ToolCall {
+                    id: "call_1".to_string(),
+                    name: "search".to_string(),
+                    input: r#"{"query": "test"}"#.to_string(),
+                }],
+                tool_results: vec![ToolResult {
+                    tool_use_id: "call_1".to_string(),
+                    success: true,
+                    content: "Found it".to_string(),
+                }],
+                timestamp: Utc::now(),
+            },
+        ];
+
+        let messages = convert_session_history(&history, ToolUseRepair::default()).unwrap();
+        // user, assistant(tool_use), user(tool_result), placeholder assistant
+        assert_eq!(messages.len(), 4);
+        assert!(matches!(messages[2].role, Role::User));
+        assert!(matches!(messages[3].role, Role::Assistant));
+        assert_eq!(messages[3].text(), INTERRUPTED_TURN_PLACEHOLDER);
+    }
+
+    #[test]
+    fn test_convert_history_complete_turn_unchanged() {
+        let history = vec![
+            SessionMessage {
+                role: MessageRole::User,
+                content: "Hello".to_string(),
+                tool_calls: vec![],
+                tool_results: vec![],
+                timestamp: Utc::now(),
+            },
+            SessionMessage {
+                role: MessageRole::Assistant,
+                content: "Hi".to_string(),
+                tool_calls: vec![],
+                tool_results: vec![],
+                timestamp: Utc::now(),
+            },
+        ];
+
+        let messages = convert_session_history(&history, ToolUseRepair::default()).unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1].text(), "Hi");
+    }
 }
@@ -0,0 +1,54 @@
+//! Serializable description of an agent's configuration
+//!
+//! [`Agent::config_snapshot`] records what produced a run: the model,
+//! sampling and retry settings, system prompt, tools, and conversation
+//! strategy. It is descriptive metadata for logs and for comparing
+//! environments; credentials and clients are never included.
+
+use serde::{Deserialize, Serialize};
+
+use crate::provider::ProviderSettings;
+
+use super::Agent;
+
+/// An agent's configuration at a point in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentConfigSnapshot {
+    /// Name set with [`AgentBuilder::with_name`](crate::AgentBuilder::with_name)
+    pub name: Option<String>,
+    /// Model display name (e.g. "Claude Sonnet 4.5")
+    pub model: String,
+    /// Model ID, sampling parameters, and retry configuration
+    pub provider: ProviderSettings,
+    /// System prompt as configured, before tool guidance is appended
+    pub system_prompt: Option<String>,
+    /// Names of the registered tools, including those from MCP servers
+    pub tools: Vec<String>,
+    /// Conversation strategy (see [`ConversationManager::strategy`](crate::conversation::ConversationManager::strategy))
+    pub conversation_strategy: String,
+    /// Maximum number of tools run at once
+    pub max_concurrent_tools: usize,
+    /// Whether tool calls are reported instead of executed
+    pub dry_run: bool,
+}
+
+impl Agent {
+    /// Snapshot of this agent's configuration
+    ///
+    /// ```ignore
+    /// let snapshot = agent.config_snapshot();
+    /// println!("{}", serde_json::to_string_pretty(&snapshot)?);
+    /// ```
+    pub fn config_snapshot(&self) -> AgentConfigSnapshot {
+        AgentConfigSnapshot {
+            name: self.name.clone(),
+            model: self.provider.name().to_string(),
+            provider: self.provider.settings(),
+            system_prompt: self.system_prompt.clone(),
+            tools: self.tools.iter().map(|t| t.name().to_string()).collect(),
+            conversation_strategy: self.conversation_manager.read().strategy(),
+            max_concurrent_tools: self.max_concurrent_tools,
+            dry_run: self.dry_run,
+        }
+    }
+}
@@ -2,85 +2,414 @@
 
 use futures::StreamExt;
 
-use crate::events::{AgentEvent, TokenUsage};
+use crate::events::AgentEvent;
 use crate::model::ModelResponse;
-use crate::provider::StreamEvent;
-use crate::types::{ContentBlock, Message, Role, StopReason, ToolDefinition, ToolUseBlock};
+use crate::provider::{ModelProvider, ResponseAssembler, StreamEvent};
+use crate::types::{ContentBlock, Message, ToolDefinition};
 
 use super::types::AgentError;
 use super::Agent;
 
+/// Assistant text stored in place of an empty response under
+/// [`EmptyResponsePolicy::Placeholder`]
+pub const EMPTY_RESPONSE_PLACEHOLDER: &str = "[No response]";
+
+/// What the agent does when the model returns an assistant turn with no
+/// content
+///
+/// An empty assistant message can't be stored as-is: providers reject it
+/// when the conversation is sent on the next turn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EmptyResponsePolicy {
+    /// Fail the run with [`AgentError::EmptyResponse`]
+    #[default]
+    Error,
+    /// Repeat the model call once, then fail with
+    /// [`AgentError::EmptyResponse`] if it is still empty
+    RetryOnce,
+    /// Store and return [`EMPTY_RESPONSE_PLACEHOLDER`] as the response text
+    Placeholder,
+}
+
 impl Agent {
-    /// Call the model with streaming, emitting events for each text delta
+    /// Call the model with streaming, handling an empty response according
+    /// to the agent's [`EmptyResponsePolicy`]
     pub(super) async fn generate_with_streaming(
         &self,
+        provider: &dyn ModelProvider,
         messages: Vec<Message>,
         tools: Vec<ToolDefinition>,
         system_prompt: Option<String>,
     ) -> Result<ModelResponse, AgentError> {
-        let mut stream = self
-            .provider
+        let retry = (self.empty_response_policy == EmptyResponsePolicy::RetryOnce)
+            .then(|| (messages.clone(), tools.clone(), system_prompt.clone()));
+
+        let mut response = self
+            .stream_response(provider, messages, tools, system_prompt)
+            .await?;
+        if response.message.content.is_empty() {
+            if let Some((messages, tools, system_prompt)) = retry {
+                response = self
+                    .stream_response(provider, messages, tools, system_prompt)
+                    .await?;
+            }
+        }
+
+        // Safety: AWS Bedrock requires at least one content block, so an
+        // empty turn must never reach the conversation
+        if response.message.content.is_empty() {
+            match self.empty_response_policy {
+                EmptyResponsePolicy::Placeholder => response
+                    .message
+                    .content
+                    .push(ContentBlock::Text(EMPTY_RESPONSE_PLACEHOLDER.to_string())),
+                EmptyResponsePolicy::Error | EmptyResponsePolicy::RetryOnce => {
+                    return Err(AgentError::EmptyResponse)
+                }
+            }
+        }
+
+        Ok(response)
+    }
+
+    /// Call the model with streaming, emitting events for each text delta
+    async fn stream_response(
+        &self,
+        provider: &dyn ModelProvider,
+        messages: Vec<Message>,
+        tools: Vec<ToolDefinition>,
+        system_prompt: Option<String>,
+    ) -> Result<ModelResponse, AgentError> {
+        let mut stream = provider
             .generate_stream(messages, tools, system_prompt)
             .await?;
 
-        let mut text_content = String::new();
-        let mut tool_uses: Vec<ToolUseBlock> = Vec::new();
-        let mut stop_reason = StopReason::EndTurn;
-        let mut usage: Option<TokenUsage> = None;
+        let mut response = ResponseAssembler::new();
+        let mut text_length = 0;
+        // Input text of the next tool use, if the provider had to repair it
+        let mut repaired_input: Option<String> = None;
 
         while let Some(event_result) = stream.next().await {
-            match event_result {
-                Ok(event) => match event {
-                    StreamEvent::TextDelta(delta) => {
-                        text_content.push_str(&delta);
-                        self.emit_event(AgentEvent::ModelCallStreaming {
-                            delta,
-                            accumulated_length: text_content.len(),
-                        });
-                    }
-                    StreamEvent::ToolUse(tool_use) => {
-                        tool_uses.push(tool_use);
-                    }
-                    StreamEvent::ThinkingDelta(_thinking) => {
-                        // Extended thinking delta - we don't expose thinking content to events yet
-                        // but it's processed through the stream
+            let event = match event_result.map_err(AgentError::Provider)? {
+                StreamEvent::ToolUse(mut tool_use) => {
+                    if tool_use.id.is_empty() {
+                        tool_use.id = self.id_generator.next_tool_use_id();
                     }
-                    StreamEvent::Stop {
-                        stop_reason: reason,
-                        usage: u,
-                    } => {
-                        stop_reason = reason;
-                        usage = u;
+                    if let Some(original) = repaired_input.take() {
+                        self.emit_event(AgentEvent::ToolInputRepaired {
+                            tool_use_id: tool_use.id.clone(),
+                            original,
+                            repaired: tool_use.input.clone(),
+                        })
+                        .await;
                     }
-                },
-                Err(e) => {
-                    return Err(AgentError::Provider(e));
+                    StreamEvent::ToolUse(tool_use)
+                }
+                StreamEvent::ToolInputRepaired { raw_input, .. } => {
+                    // Reported with the repaired input once the tool use arrives
+                    repaired_input = Some(raw_input);
+                    continue;
+                }
+                StreamEvent::ToolInputDelta {
+                    tool_use_id,
+                    partial_json,
+                } => {
+                    self.emit_event(AgentEvent::ToolArgsDelta {
+                        tool_use_id,
+                        partial_json,
+                    })
+                    .await;
+                    continue;
+                }
+                StreamEvent::ToolInputInvalid {
+                    tool_use_id, error, ..
+                } => {
+                    // Answered with an error result when the tool use is executed
+                    self.invalid_tool_inputs.lock().insert(tool_use_id, error);
+                    continue;
+                }
+                event => event,
+            };
+
+            // Unfiltered text must not reach hooks, so with filters
+            // registered the run streams the filtered response once complete
+            if let StreamEvent::TextDelta(delta) = &event {
+                if self.content_filters.is_empty() {
+                    text_length += delta.len();
+                    self.emit_event(AgentEvent::ModelCallStreaming {
+                        delta: delta.clone(),
+                        accumulated_length: text_length,
+                    })
+                    .await;
                 }
             }
+            response.push(event);
+        }
+
+        Ok(response.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::{ModelProvider, ProviderError};
+    use crate::types::{StopReason, ToolUseBlock};
+    use futures::stream::BoxStream;
+    use parking_lot::Mutex;
+
+    /// Provider that replays a fixed sequence of stream events
+    struct ScriptedStreamProvider {
+        events: Mutex<Vec<StreamEvent>>,
+    }
+
+    #[async_trait::async_trait]
+    impl ModelProvider for ScriptedStreamProvider {
+        fn name(&self) -> &str {
+            "ScriptedStreamProvider"
         }
 
-        // Build the response message
-        let mut content = Vec::new();
-        if !text_content.is_empty() {
-            content.push(ContentBlock::Text(text_content));
+        fn max_context_tokens(&self) -> usize {
+            200_000
         }
-        for tool_use in tool_uses {
-            content.push(ContentBlock::ToolUse(tool_use));
+
+        fn max_output_tokens(&self) -> usize {
+            8_192
         }
 
-        // Safety: AWS Bedrock requires at least one content block
-        // This should not happen with proper streaming, but guard against it
-        if content.is_empty() {
-            return Err(AgentError::EmptyResponse);
+        async fn generate(
+            &self,
+            _messages: Vec<Message>,
+            _tools: Vec<ToolDefinition>,
+            _system_prompt: Option<String>,
+        ) -> Result<ModelResponse, ProviderError> {
+            Err(ProviderError::Other("only streaming is used".into()))
+        }
+
+        async fn generate_stream(
+            &self,
+            _messages: Vec<Message>,
+            _tools: Vec<ToolDefinition>,
+            _system_prompt: Option<String>,
+        ) -> Result<BoxStream<'static, Result<StreamEvent, ProviderError>>, ProviderError> {
+            let events: Vec<_> = self.events.lock().drain(..).map(Ok).collect();
+            Ok(Box::pin(futures::stream::iter(events)))
+        }
+    }
+
+    async fn stream_response(events: Vec<StreamEvent>) -> ModelResponse {
+        let agent = Agent::builder()
+            .provider(ScriptedStreamProvider {
+                events: Mutex::new(events),
+            })
+            .build()
+            .await
+            .unwrap();
+        agent
+            .generate_with_streaming(
+                agent.provider.as_ref(),
+                vec![Message::user("Hi")],
+                Vec::new(),
+                None,
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_assembles_thinking_text_and_tool_use_in_order() {
+        let tool_use = ToolUseBlock {
+            id: "tool_1".to_string(),
+            name: "search".to_string(),
+            input: serde_json::json!({"q": "rust"}),
+        };
+
+        let response = stream_response(vec![
+            StreamEvent::ThinkingDelta("Let me ".to_string()),
+            StreamEvent::ThinkingDelta("think".to_string()),
+            StreamEvent::Thinking {
+                thinking: "Let me think".to_string(),
+                signature: "sig_123".to_string(),
+            },
+            StreamEvent::TextDelta("I'll ".to_string()),
+            StreamEvent::TextDelta("search.".to_string()),
+            StreamEvent::ToolUse(tool_use),
+            StreamEvent::Stop {
+                stop_reason: StopReason::ToolUse,
+                stop_sequence: None,
+                usage: None,
+                service_tier: None,
+                model: None,
+            },
+        ])
+        .await;
+
+        assert_eq!(response.stop_reason, StopReason::ToolUse);
+        let content = &response.message.content;
+        assert_eq!(content.len(), 3);
+        match &content[0] {
+            ContentBlock::Thinking {
+                thinking,
+                signature,
+            } => {
+                assert_eq!(thinking, "Let me think");
+                assert_eq!(signature, "sig_123");
+            }
+            other => panic!("Expected Thinking block, got {:?}", other),
         }
+        assert!(matches!(&content[1], ContentBlock::Text(t) if t == "I'll search."));
+        assert!(matches!(&content[2], ContentBlock::ToolUse(t) if t.id == "tool_1"));
+    }
+
+    #[tokio::test]
+    async fn test_text_after_tool_use_starts_new_block() {
+        let tool_use = ToolUseBlock {
+            id: "tool_1".to_string(),
+            name: "search".to_string(),
+            input: serde_json::json!({}),
+        };
 
-        Ok(ModelResponse {
-            message: Message {
-                role: Role::Assistant,
-                content,
+        let response = stream_response(vec![
+            StreamEvent::TextDelta("Before".to_string()),
+            StreamEvent::ToolUse(tool_use),
+            StreamEvent::TextDelta("".to_string()),
+            StreamEvent::TextDelta("After".to_string()),
+            StreamEvent::Stop {
+                stop_reason: StopReason::EndTurn,
+                stop_sequence: None,
+                usage: None,
+                service_tier: None,
+                model: None,
             },
-            stop_reason,
-            usage,
-        })
+        ])
+        .await;
+
+        let content = &response.message.content;
+        assert_eq!(content.len(), 3);
+        assert!(matches!(&content[0], ContentBlock::Text(t) if t == "Before"));
+        assert!(matches!(&content[1], ContentBlock::ToolUse(_)));
+        assert!(matches!(&content[2], ContentBlock::Text(t) if t == "After"));
+    }
+
+    #[tokio::test]
+    async fn test_stop_sequence_carried_into_response() {
+        let response = stream_response(vec![
+            StreamEvent::TextDelta("1, 2, 3".to_string()),
+            StreamEvent::Stop {
+                stop_reason: StopReason::StopSequence,
+                stop_sequence: Some("4".to_string()),
+                usage: None,
+                service_tier: None,
+                model: None,
+            },
+        ])
+        .await;
+
+        assert_eq!(response.stop_reason, StopReason::StopSequence);
+        assert_eq!(response.stop_sequence.as_deref(), Some("4"));
+    }
+
+    #[tokio::test]
+    async fn test_citations_follow_their_text() {
+        let citation = |start| crate::types::Citation {
+            source: crate::types::CitationSource::Document {
+                index: 0,
+                title: None,
+            },
+            cited_text: "cited".to_string(),
+            location: crate::types::CitationLocation::Chars {
+                start,
+                end: start + 5,
+            },
+        };
+
+        let response = stream_response(vec![
+            StreamEvent::TextDelta("Cited".to_string()),
+            StreamEvent::Citation(citation(0)),
+            StreamEvent::Citation(citation(10)),
+            StreamEvent::TextDelta(" and more".to_string()),
+            StreamEvent::Stop {
+                stop_reason: StopReason::EndTurn,
+                stop_sequence: None,
+                usage: None,
+                service_tier: None,
+                model: None,
+            },
+        ])
+        .await;
+
+        let content = &response.message.content;
+        assert_eq!(content.len(), 3);
+        assert!(matches!(&content[0], ContentBlock::Text(t) if t == "Cited"));
+        match &content[1] {
+            ContentBlock::Citations { citations } => {
+                assert_eq!(citations, &vec![citation(0), citation(10)]);
+            }
+            other => panic!("Expected Citations block, got {:?}", other),
+        }
+        assert!(matches!(&content[2], ContentBlock::Text(t) if t == " and more"));
+        assert_eq!(response.message.text(), "Cited and more");
+    }
+
+    #[tokio::test]
+    async fn test_repaired_tool_input_emits_event() {
+        let raw_input = r#"{"path": "a.t"#;
+        let mut events = crate::provider::tool_input::tool_use_events(
+            "tool_1".to_string(),
+            "read".to_string(),
+            raw_input,
+        );
+        events.push(StreamEvent::Stop {
+            stop_reason: StopReason::MaxTokens,
+            stop_sequence: None,
+            usage: None,
+            service_tier: None,
+            model: None,
+        });
+
+        let agent = Agent::builder()
+            .provider(ScriptedStreamProvider {
+                events: Mutex::new(events),
+            })
+            .build()
+            .await
+            .unwrap();
+        let collector = crate::test_utils::EventCollector::new();
+        agent.add_hook(collector.clone());
+
+        let response = agent
+            .generate_with_streaming(
+                agent.provider.as_ref(),
+                vec![Message::user("Hi")],
+                Vec::new(),
+                None,
+            )
+            .await
+            .unwrap();
+
+        let repairs: Vec<_> = collector
+            .events()
+            .into_iter()
+            .filter_map(|event| match event {
+                AgentEvent::ToolInputRepaired {
+                    tool_use_id,
+                    original,
+                    repaired,
+                } => Some((tool_use_id, original, repaired)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            repairs,
+            vec![(
+                "tool_1".to_string(),
+                raw_input.to_string(),
+                serde_json::json!({"path": "a.t"})
+            )]
+        );
+        assert!(matches!(
+            &response.message.content[0],
+            ContentBlock::ToolUse(t) if t.input == serde_json::json!({"path": "a.t"})
+        ));
     }
 }
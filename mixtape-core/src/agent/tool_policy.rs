@@ -0,0 +1,71 @@
+//! Tool-use guidance added to the system prompt
+//!
+//! Tool definitions tell the model what a tool does, not how carefully to
+//! use it. When any registered tool reports [`is_destructive`](crate::Tool::is_destructive),
+//! the agent appends [`DEFAULT_TOOL_POLICY_PROMPT`] to the system prompt.
+//! [`AgentBuilder::with_tool_policy_prompt`](super::AgentBuilder::with_tool_policy_prompt)
+//! replaces it and [`AgentBuilder::without_tool_policy_prompt`](super::AgentBuilder::without_tool_policy_prompt)
+//! turns it off.
+//!
+//! [`AgentBuilder::with_parallel_tool_use`](super::AgentBuilder::with_parallel_tool_use)
+//! adds a line to the same section when the provider has no native switch
+//! for parallel tool calls.
+
+use super::Agent;
+
+/// Tool-use guidance added when a destructive tool is registered
+pub const DEFAULT_TOOL_POLICY_PROMPT: &str = "\
+Some of your tools can delete, overwrite, or otherwise irreversibly change data.
+- Validate paths, identifiers, and other arguments before calling a tool.
+- Prefer read-only tools to inspect the current state before changing it.
+- Ask the user before a destructive operation unless they clearly requested it.
+- Never retry a failed destructive operation with broader arguments.";
+
+/// Guidance when the model should call one tool at a time
+const SEQUENTIAL_TOOL_USE_PROMPT: &str = "\
+Call at most one tool per response, and wait for its result before calling the next.";
+
+/// Guidance when the model may batch independent tool calls
+const PARALLEL_TOOL_USE_PROMPT: &str = "\
+When several tool calls do not depend on each other's results, make them together in one response.";
+
+/// Which tool-use guidance the agent adds to the system prompt
+#[derive(Debug, Clone, Default)]
+pub(crate) enum ToolPolicy {
+    /// The default policy, only when a destructive tool is registered
+    #[default]
+    Auto,
+    /// Caller-supplied policy, always added
+    Custom(String),
+    /// No policy section
+    Disabled,
+}
+
+impl Agent {
+    /// The tool-use policy section for the current tool list, if any
+    pub(super) fn tool_policy_prompt(&self) -> Option<String> {
+        let policy = match &self.tool_policy {
+            ToolPolicy::Auto if self.tools.iter().any(|t| t.is_destructive()) => {
+                Some(DEFAULT_TOOL_POLICY_PROMPT)
+            }
+            ToolPolicy::Auto | ToolPolicy::Disabled => None,
+            ToolPolicy::Custom(text) => Some(text.as_str()),
+        };
+        let parallel = self
+            .parallel_tool_guidance
+            .filter(|_| !self.tools.is_empty())
+            .map(|enabled| {
+                if enabled {
+                    PARALLEL_TOOL_USE_PROMPT
+                } else {
+                    SEQUENTIAL_TOOL_USE_PROMPT
+                }
+            });
+
+        let lines: Vec<&str> = policy.into_iter().chain(parallel).collect();
+        if lines.is_empty() {
+            return None;
+        }
+        Some(format!("## Tool use policy\n{}", lines.join("\n")))
+    }
+}
@@ -1,39 +1,70 @@
 //! Tool management and execution for Agent
 
-use std::time::Instant;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use futures::future::{abortable, AbortHandle, BoxFuture};
 use futures::stream::{self, StreamExt};
 use serde_json::Value;
+use tokio::sync::Semaphore;
 
 use crate::events::AgentEvent;
 use crate::permission::{Authorization, AuthorizationResponse};
-use crate::tool::{box_tool, ToolResult};
-use crate::types::{Message, ToolResultBlock, ToolResultStatus, ToolUseBlock};
+use crate::tool::{box_tool, DynTool, ToolError, ToolResult};
+use crate::types::{Message, ToolDefinition, ToolResultBlock, ToolResultStatus, ToolUseBlock};
 
-use super::types::{AgentError, ToolCallInfo, ToolInfo};
+use super::delegate::with_delegation_scope;
+use super::introspection::LIST_CAPABILITIES_TOOL;
+use super::progress::EMIT_PROGRESS_TOOL;
+use super::types::{AgentError, IntendedToolCall, ToolCallInfo, ToolInfo};
 use super::Agent;
 
 #[cfg(feature = "session")]
 use crate::session::ToolCall;
 
+/// Tool result sent to the model for a tool skipped in dry-run mode
+pub(crate) const DRY_RUN_TOOL_RESULT: &str = "Dry run: the tool was not executed.";
+
 impl Agent {
     /// Add a tool to the agent's toolbox
-    pub fn add_tool<T: crate::tool::Tool + 'static>(&mut self, tool: T)
+    ///
+    /// The name is checked the same way as when the agent is built: it must
+    /// satisfy the provider's naming rules and differ from every registered
+    /// tool, or [`Error::InvalidToolName`](crate::Error::InvalidToolName) is
+    /// returned and the tool is not added.
+    pub fn add_tool<T: crate::tool::Tool + 'static>(&mut self, tool: T) -> crate::error::Result<()>
     where
         T::Input: serde::Serialize,
     {
-        let tool_name = tool.name().to_string();
+        self.check_new_tool_names([tool.name()])?;
+        self.tools.push(Arc::from(box_tool(tool)));
+        Ok(())
+    }
 
-        // Check for duplicate tool names
-        if self.tools.iter().any(|t| t.name() == tool_name) {
-            eprintln!(
-                "Warning: Tool '{}' is already registered. This will cause errors when calling the model.",
-                tool_name
-            );
-            eprintln!("   Consider using .with_namespace() on MCP servers to avoid conflicts.");
+    /// Check the names of tools about to be added against the provider's
+    /// rules, the registered tools, and each other
+    pub(super) fn check_new_tool_names<'a>(
+        &self,
+        names: impl IntoIterator<Item = &'a str>,
+    ) -> crate::error::Result<()> {
+        let mut seen: std::collections::HashSet<&str> =
+            self.tools.iter().map(|t| t.name()).collect();
+        for name in names {
+            if let Err(reason) = self.provider.validate_tool_name(name) {
+                return Err(crate::error::Error::InvalidToolName {
+                    name: name.to_string(),
+                    reason,
+                });
+            }
+            if !seen.insert(name) {
+                return Err(crate::error::Error::InvalidToolName {
+                    name: name.to_string(),
+                    reason: "another tool has the same name".to_string(),
+                });
+            }
         }
-
-        self.tools.push(box_tool(tool));
+        Ok(())
     }
 
     /// List all configured tools
@@ -43,10 +74,70 @@ impl Agent {
             .map(|t| ToolInfo {
                 name: t.name().to_string(),
                 description: t.description().to_string(),
+                output_schema: t.output_schema(),
             })
             .collect()
     }
 
+    /// Check every tool name against the provider's rules and each other
+    ///
+    /// One bad or repeated name gets every request rejected, so the build
+    /// fails up front instead.
+    pub(super) fn check_tool_names(&self) -> crate::error::Result<()> {
+        let mut seen = std::collections::HashSet::new();
+        for definition in self.tool_definitions() {
+            let name = definition.name;
+            if let Err(reason) = self.provider.validate_tool_name(&name) {
+                return Err(crate::error::Error::InvalidToolName { name, reason });
+            }
+            if !seen.insert(name.clone()) {
+                return Err(crate::error::Error::InvalidToolName {
+                    name,
+                    reason: "another tool has the same name".to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Build the tool definitions sent to the model
+    pub(super) fn tool_definitions(&self) -> Vec<ToolDefinition> {
+        self.tools
+            .iter()
+            .map(|t| ToolDefinition {
+                name: t.name().to_string(),
+                description: t.description().to_string(),
+                input_schema: t.input_schema(),
+            })
+            .chain(self.capabilities_tool_definition())
+            .chain(self.progress_tool_definition())
+            .collect()
+    }
+
+    /// Cancel an executing tool, letting the rest of the run continue
+    ///
+    /// The tool's future is dropped and the model receives an error result
+    /// saying the call was aborted. Other tools in the same turn are not
+    /// affected. Returns `false` if no tool with this ID is executing (it
+    /// finished, is still awaiting permission, or never existed).
+    pub fn abort_tool(&self, tool_use_id: &str) -> bool {
+        match self.running_tools.lock().get(tool_use_id) {
+            Some(handle) => {
+                handle.abort();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// IDs of the tools currently executing
+    ///
+    /// A tool is listed from its [`AgentEvent::ToolExecuting`] event until it
+    /// completes, fails, or is aborted.
+    pub fn running_tool_ids(&self) -> Vec<String> {
+        self.running_tools.lock().keys().cloned().collect()
+    }
+
     /// Format tool input parameters for presentation
     ///
     /// Returns formatted string if the tool has a custom presenter,
@@ -95,7 +186,22 @@ impl Agent {
             tool_use_id: tool_id.clone(),
             name: tool_name.clone(),
             input: input.clone(),
-        });
+        })
+        .await;
+
+        // The streamed input was cut off and could not be recovered
+        let invalid_input = self.invalid_tool_inputs.lock().remove(&tool_id);
+        if let Some(error) = invalid_input {
+            let error_msg = format!("Tool input was not valid JSON: {}", error);
+            self.emit_event(AgentEvent::ToolFailed {
+                tool_use_id: tool_id,
+                name: tool_name,
+                error: error_msg.clone(),
+                duration: tool_start.elapsed(),
+            })
+            .await;
+            return Err(AgentError::InvalidToolInput(error_msg));
+        }
 
         // Validate that input is a JSON object (per Anthropic/Bedrock spec)
         if !input.is_object() {
@@ -113,58 +219,231 @@ impl Agent {
                 name: tool_name,
                 error: error_msg.clone(),
                 duration: tool_start.elapsed(),
-            });
+            })
+            .await;
             return Err(AgentError::InvalidToolInput(error_msg));
         }
 
-        let tool = self
-            .tools
-            .iter()
-            .find(|t| t.name() == tool_use.name)
-            .ok_or_else(|| {
-                self.emit_event(AgentEvent::ToolFailed {
-                    tool_use_id: tool_id.clone(),
-                    name: tool_name.clone(),
-                    error: format!("Tool not found: {}", tool_name),
-                    duration: tool_start.elapsed(),
-                });
-                AgentError::ToolNotFound(tool_name.clone())
-            })?;
+        // Built-in and read-only, so it needs no approval
+        if self.is_capabilities_tool(&tool_name) {
+            self.emit_event(AgentEvent::ToolExecuting {
+                tool_use_id: tool_id.clone(),
+                name: tool_name.clone(),
+            })
+            .await;
+            let result = self.capabilities();
+            return Ok(self
+                .complete_tool(tool_id, tool_name, result, tool_start.elapsed())
+                .await);
+        }
+
+        // Built-in and side-effect free: the call only becomes an event
+        if self.is_progress_tool(&tool_name) {
+            self.emit_event(AgentEvent::ToolExecuting {
+                tool_use_id: tool_id.clone(),
+                name: tool_name.clone(),
+            })
+            .await;
+            return match Self::progress_event(input) {
+                Ok(event) => {
+                    self.emit_event(event).await;
+                    Ok(self
+                        .complete_tool(
+                            tool_id,
+                            tool_name,
+                            ToolResult::Text(String::new()),
+                            tool_start.elapsed(),
+                        )
+                        .await)
+                }
+                Err(error_msg) => {
+                    self.emit_event(AgentEvent::ToolFailed {
+                        tool_use_id: tool_id,
+                        name: tool_name,
+                        error: error_msg.clone(),
+                        duration: tool_start.elapsed(),
+                    })
+                    .await;
+                    Err(AgentError::InvalidToolInput(error_msg))
+                }
+            };
+        }
+
+        let Some(tool) = self.tools.iter().find(|t| t.name() == tool_use.name) else {
+            let mut available: Vec<String> =
+                self.tools.iter().map(|t| t.name().to_string()).collect();
+            if self.is_capabilities_tool(LIST_CAPABILITIES_TOOL) {
+                available.push(LIST_CAPABILITIES_TOOL.to_string());
+            }
+            if self.is_progress_tool(EMIT_PROGRESS_TOOL) {
+                available.push(EMIT_PROGRESS_TOOL.to_string());
+            }
+            let error = AgentError::ToolNotFound {
+                name: tool_name.clone(),
+                available,
+            };
+            self.emit_event(AgentEvent::ToolFailed {
+                tool_use_id: tool_id.clone(),
+                name: tool_name,
+                error: error.to_string(),
+                duration: tool_start.elapsed(),
+            })
+            .await;
+            return Err(error);
+        };
+
+        // Reject input the tool can't accept before anyone is asked to approve it
+        if let Err(e) = tool.validate_raw(&input) {
+            let error_msg = e.to_string();
+            self.emit_event(AgentEvent::ToolFailed {
+                tool_use_id: tool_id,
+                name: tool_name,
+                error: error_msg.clone(),
+                duration: tool_start.elapsed(),
+            })
+            .await;
+            return Err(AgentError::InvalidToolInput(error_msg));
+        }
+
+        // Nothing runs in dry-run mode, so there is nothing to approve
+        if self.dry_run {
+            self.emit_event(AgentEvent::ToolSkippedDryRun {
+                tool_use_id: tool_id,
+                name: tool_name,
+                input,
+            })
+            .await;
+            return Ok(ToolResult::Text(DRY_RUN_TOOL_RESULT.to_string()));
+        }
 
         // Check approval (emits permission events as needed)
         self.check_tool_approval(&tool_id, &tool_name, &input, tool_start)
             .await?;
 
+        // Wait for a free slot if the tool limits its own concurrency
+        let semaphore = tool.max_concurrency().map(|limit| {
+            self.tool_permits
+                .lock()
+                .entry(tool_name.clone())
+                .or_insert_with(|| Arc::new(Semaphore::new(limit.max(1))))
+                .clone()
+        });
+        let _permit = match semaphore {
+            Some(semaphore) => Some(
+                semaphore
+                    .acquire_owned()
+                    .await
+                    .expect("tool semaphores are never closed"),
+            ),
+            None => None,
+        };
+
         // Emit ToolExecuting (after permission granted)
         self.emit_event(AgentEvent::ToolExecuting {
             tool_use_id: tool_id.clone(),
             name: tool_name.clone(),
-        });
+        })
+        .await;
 
-        // Execute the tool
-        match tool.execute_raw(input).await {
-            Ok(result) => {
-                self.emit_event(AgentEvent::ToolCompleted {
-                    tool_use_id: tool_id,
-                    name: tool_name,
-                    output: result.clone(),
-                    duration: tool_start.elapsed(),
-                });
-                Ok(result)
-            }
-            Err(e) => {
+        // Execute the tool, registered so it can be aborted. Timed from here
+        // so waiting for approval isn't counted as execution time.
+        let execution_start = Instant::now();
+        let (execution, abort_handle) = abortable(self.spawn_tool(tool, input));
+        let _registration = RunningTool::register(self, &tool_id, abort_handle);
+
+        // Reject images the model would refuse before they reach the request
+        let outcome = execution
+            .await
+            .map(|executed| executed.and_then(|result| result.validate().map(|()| result)));
+
+        match outcome {
+            Ok(Ok(result)) => Ok(self
+                .complete_tool(tool_id, tool_name, result, execution_start.elapsed())
+                .await),
+            Ok(Err(e)) => {
                 let error_msg = e.to_string();
                 self.emit_event(AgentEvent::ToolFailed {
                     tool_use_id: tool_id,
                     name: tool_name,
                     error: error_msg,
-                    duration: tool_start.elapsed(),
-                });
+                    duration: execution_start.elapsed(),
+                })
+                .await;
                 Err(AgentError::Tool(e))
             }
+            Err(_aborted) => {
+                self.emit_event(AgentEvent::ToolFailed {
+                    tool_use_id: tool_id,
+                    name: tool_name.clone(),
+                    error: "Tool execution aborted by user".to_string(),
+                    duration: execution_start.elapsed(),
+                })
+                .await;
+                Err(AgentError::ToolAborted(tool_name))
+            }
+        }
+    }
+
+    /// Run a tool, moving blocking tools off the async worker threads
+    fn spawn_tool(
+        &self,
+        tool: &Arc<dyn DynTool>,
+        input: Value,
+    ) -> BoxFuture<'static, Result<ToolResult, ToolError>> {
+        let blocking = tool.is_blocking();
+        let tool = Arc::clone(tool);
+        let execution = with_delegation_scope(self, async move { tool.execute_raw(input).await });
+        if !blocking {
+            return Box::pin(execution);
+        }
+
+        match &self.tool_runtime {
+            Some(runtime) => {
+                let task = runtime.spawn(execution);
+                // Dropping the call (abort, run deadline) cancels the task
+                let cancel = AbortOnDrop(task.abort_handle());
+                Box::pin(async move {
+                    let _cancel = cancel;
+                    task.await.unwrap_or_else(|e| Err(tool_task_error(e)))
+                })
+            }
+            None => {
+                let runtime = tokio::runtime::Handle::current();
+                // A blocking task cannot be cancelled; an abandoned call
+                // runs to completion and its result is dropped
+                let task = tokio::task::spawn_blocking(move || runtime.block_on(execution));
+                Box::pin(async move { task.await.unwrap_or_else(|e| Err(tool_task_error(e))) })
+            }
         }
     }
 
+    /// Emit `ToolCompleted` with the full result and return the model's copy
+    async fn complete_tool(
+        &self,
+        tool_use_id: String,
+        name: String,
+        result: ToolResult,
+        duration: Duration,
+    ) -> ToolResult {
+        let max_bytes = match (
+            self.max_tool_result_bytes,
+            self.provider.estimated_max_tool_result_bytes(),
+        ) {
+            (Some(configured), Some(provider)) => Some(configured.min(provider)),
+            (configured, provider) => configured.or(provider),
+        };
+        let model_output = max_bytes.and_then(|max_bytes| result.truncated(max_bytes));
+        self.emit_event(AgentEvent::ToolCompleted {
+            tool_use_id,
+            name,
+            output: result.clone(),
+            model_output: model_output.clone(),
+            duration,
+        })
+        .await;
+        model_output.unwrap_or(result)
+    }
+
     /// Check if a tool is authorized for execution
     async fn check_tool_approval(
         &self,
@@ -181,7 +460,8 @@ impl Agent {
                     tool_use_id: tool_id.to_string(),
                     tool_name: tool_name.to_string(),
                     scope: Some(grant.scope),
-                });
+                })
+                .await;
                 Ok(())
             }
             Authorization::Denied { reason } => {
@@ -189,13 +469,15 @@ impl Agent {
                     tool_use_id: tool_id.to_string(),
                     tool_name: tool_name.to_string(),
                     reason: reason.clone(),
-                });
+                })
+                .await;
                 self.emit_event(AgentEvent::ToolFailed {
                     tool_use_id: tool_id.to_string(),
                     name: tool_name.to_string(),
                     error: reason,
                     duration: tool_start.elapsed(),
-                });
+                })
+                .await;
                 Err(AgentError::ToolDenied(tool_name.to_string()))
             }
             Authorization::PendingApproval { params_hash } => {
@@ -234,7 +516,8 @@ impl Agent {
             tool_name: tool_name.to_string(),
             params: input.clone(),
             params_hash: params_hash.clone(),
-        });
+        })
+        .await;
 
         // Wait for response with timeout
         let response = match tokio::time::timeout(self.authorization_timeout, rx.recv()).await {
@@ -247,7 +530,8 @@ impl Agent {
                     tool_use_id: tool_id.to_string(),
                     tool_name: tool_name.to_string(),
                     reason: "Authorization request timed out".to_string(),
-                });
+                })
+                .await;
                 AuthorizationResponse::Deny {
                     reason: Some("Timeout".to_string()),
                 }
@@ -266,7 +550,8 @@ impl Agent {
                     tool_use_id: tool_id.to_string(),
                     tool_name: tool_name.to_string(),
                     scope: None,
-                });
+                })
+                .await;
                 Ok(())
             }
             AuthorizationResponse::Trust { grant } => {
@@ -286,7 +571,8 @@ impl Agent {
                     tool_use_id: tool_id.to_string(),
                     tool_name: tool_name.to_string(),
                     scope: Some(grant.scope),
-                });
+                })
+                .await;
                 Ok(())
             }
             AuthorizationResponse::Deny { reason } => {
@@ -296,18 +582,58 @@ impl Agent {
                     tool_use_id: tool_id.to_string(),
                     tool_name: tool_name.to_string(),
                     reason: reason_str,
-                });
+                })
+                .await;
                 self.emit_event(AgentEvent::ToolFailed {
                     tool_use_id: tool_id.to_string(),
                     name: tool_name.to_string(),
                     error: "Tool execution denied by user".to_string(),
                     duration: tool_start.elapsed(),
-                });
+                })
+                .await;
                 Err(AgentError::ToolDenied(tool_name.to_string()))
             }
         }
     }
 
+    /// Count consecutive malformed inputs per tool and return the tools
+    /// whose count reached the [`with_tool_json_recovery`] limit, with their
+    /// counts
+    ///
+    /// Input is malformed when it is not a JSON object, or when its streamed
+    /// JSON could not be parsed. Must run before the calls are executed,
+    /// which consumes the record of unparseable input.
+    ///
+    /// [`with_tool_json_recovery`]: super::AgentBuilder::with_tool_json_recovery
+    pub(super) fn tool_json_recoveries(
+        &self,
+        message: &Message,
+        malformed_calls: &mut HashMap<String, usize>,
+    ) -> Vec<(String, usize)> {
+        let Some(max_attempts) = self.tool_json_recovery else {
+            return Vec::new();
+        };
+
+        let invalid_inputs = self.invalid_tool_inputs.lock();
+        let mut recoveries = Vec::new();
+        for tool_use in message.tool_uses() {
+            let malformed =
+                !tool_use.input.is_object() || invalid_inputs.contains_key(&tool_use.id);
+            if !malformed {
+                malformed_calls.remove(&tool_use.name);
+                continue;
+            }
+
+            let attempts = malformed_calls.entry(tool_use.name.clone()).or_default();
+            *attempts += 1;
+            if *attempts >= max_attempts {
+                recoveries.push((tool_use.name.clone(), *attempts));
+                malformed_calls.remove(&tool_use.name);
+            }
+        }
+        recoveries
+    }
+
     /// Process tool calls from a model response
     ///
     /// Executes all tool calls in parallel (up to max_concurrent_tools),
@@ -316,6 +642,7 @@ impl Agent {
         &self,
         message: &Message,
         tool_call_infos: &mut Vec<ToolCallInfo>,
+        intended_tool_calls: &mut Vec<IntendedToolCall>,
         #[cfg(feature = "session")] session_tool_calls: &mut Vec<ToolCall>,
         #[cfg(feature = "session")] session_tool_results: &mut Vec<crate::session::ToolResult>,
     ) -> Vec<ToolResultBlock> {
@@ -357,13 +684,24 @@ impl Agent {
                 match result {
                     Ok(ref tool_result) => {
                         // Record tool call info for response
-                        tool_call_infos.push(ToolCallInfo {
-                            name: tool_use.name.clone(),
-                            input: tool_use.input.clone(),
-                            output: tool_result.as_text(),
-                            success: true,
-                            duration,
-                        });
+                        if self.dry_run
+                            && !self.is_capabilities_tool(&tool_use.name)
+                            && !self.is_progress_tool(&tool_use.name)
+                        {
+                            intended_tool_calls.push(IntendedToolCall {
+                                tool_use_id: tool_use.id.clone(),
+                                name: tool_use.name.clone(),
+                                input: tool_use.input.clone(),
+                            });
+                        } else {
+                            tool_call_infos.push(ToolCallInfo {
+                                name: tool_use.name.clone(),
+                                input: tool_use.input.clone(),
+                                output: tool_result.as_text(),
+                                success: true,
+                                duration,
+                            });
+                        }
 
                         // Record tool result for session
                         #[cfg(feature = "session")]
@@ -415,6 +753,49 @@ impl Agent {
     }
 }
 
+/// Registration of an executing tool's abort handle, removed on drop
+struct RunningTool<'a> {
+    agent: &'a Agent,
+    tool_use_id: String,
+}
+
+impl<'a> RunningTool<'a> {
+    fn register(agent: &'a Agent, tool_use_id: &str, handle: AbortHandle) -> Self {
+        agent
+            .running_tools
+            .lock()
+            .insert(tool_use_id.to_string(), handle);
+        Self {
+            agent,
+            tool_use_id: tool_use_id.to_string(),
+        }
+    }
+}
+
+impl Drop for RunningTool<'_> {
+    fn drop(&mut self) {
+        self.agent.running_tools.lock().remove(&self.tool_use_id);
+    }
+}
+
+/// Aborts a tool task on a dedicated runtime when the call is dropped
+struct AbortOnDrop(tokio::task::AbortHandle);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Error for a tool task that panicked or whose runtime shut down
+fn tool_task_error(error: tokio::task::JoinError) -> ToolError {
+    if error.is_panic() {
+        ToolError::Custom("Tool panicked".to_string())
+    } else {
+        ToolError::Custom("Tool task was cancelled".to_string())
+    }
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -451,7 +832,10 @@ mod tests {
             let response = ModelResponse {
                 message,
                 stop_reason: StopReason::EndTurn,
+                stop_sequence: None,
                 usage: None,
+                service_tier: None,
+                model: None,
             };
             self.responses.lock().push(response);
             self
@@ -571,7 +955,7 @@ mod tests {
         assert_eq!(agent.list_tools().len(), 0);
 
         // Add a tool
-        agent.add_tool(EchoTool);
+        agent.add_tool(EchoTool).unwrap();
 
         // Should have one tool
         let tools = agent.list_tools();
@@ -580,13 +964,51 @@ mod tests {
         assert_eq!(tools[0].description, "Echoes the input back");
     }
 
+    /// Echo tool registered under another name
+    struct RenamedEchoTool(&'static str);
+
+    impl Tool for RenamedEchoTool {
+        type Input = EchoInput;
+
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn description(&self) -> &str {
+            "Echoes the input back"
+        }
+
+        async fn execute(&self, input: Self::Input) -> Result<MxToolResult, ToolError> {
+            Ok(MxToolResult::text(input.message))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_add_tool_rejects_invalid_and_duplicate_names() {
+        let provider = MockProvider::new().with_text("ok");
+        let mut agent = Agent::builder().provider(provider).build().await.unwrap();
+        agent.add_tool(EchoTool).unwrap();
+
+        let err = agent.add_tool(RenamedEchoTool("echo")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid tool name 'echo': another tool has the same name"
+        );
+
+        let err = agent.add_tool(RenamedEchoTool("echo back")).unwrap_err();
+        assert!(err.is_invalid_tool_name());
+
+        // Rejected tools are not registered
+        assert_eq!(agent.list_tools().len(), 1);
+    }
+
     #[tokio::test]
     async fn test_add_multiple_tools() {
         let provider = MockProvider::new().with_text("ok");
         let mut agent = Agent::builder().provider(provider).build().await.unwrap();
 
-        agent.add_tool(EchoTool);
-        agent.add_tool(AddTool);
+        agent.add_tool(EchoTool).unwrap();
+        agent.add_tool(AddTool).unwrap();
 
         let tools = agent.list_tools();
         assert_eq!(tools.len(), 2);
@@ -627,9 +1049,9 @@ mod tests {
         let provider = MockProvider::new().with_text("ok");
         let mut agent = Agent::builder().provider(provider).build().await.unwrap();
 
-        agent.add_tool(EchoTool);
-        agent.add_tool(AddTool);
-        agent.add_tool(FailingTool);
+        agent.add_tool(EchoTool).unwrap();
+        agent.add_tool(AddTool).unwrap();
+        agent.add_tool(FailingTool).unwrap();
 
         let tools = agent.list_tools();
         assert_eq!(tools[0].name, "echo");
@@ -644,7 +1066,7 @@ mod tests {
         let provider = MockProvider::new().with_text("ok");
         let mut agent = Agent::builder().provider(provider).build().await.unwrap();
 
-        agent.add_tool(EchoTool);
+        agent.add_tool(EchoTool).unwrap();
 
         // Grant permission to the echo tool
         agent
@@ -679,7 +1101,13 @@ mod tests {
 
         let result = agent.execute_tool(&tool_use).await;
         assert!(result.is_err());
-        assert!(matches!(result.unwrap_err(), AgentError::ToolNotFound(_)));
+        match result.unwrap_err() {
+            AgentError::ToolNotFound { name, available } => {
+                assert_eq!(name, "nonexistent_tool");
+                assert!(available.is_empty());
+            }
+            other => panic!("Expected ToolNotFound, got {:?}", other),
+        }
     }
 
     #[tokio::test]
@@ -687,7 +1115,7 @@ mod tests {
         let provider = MockProvider::new().with_text("ok");
         let mut agent = Agent::builder().provider(provider).build().await.unwrap();
 
-        agent.add_tool(EchoTool);
+        agent.add_tool(EchoTool).unwrap();
 
         // Test with string input (not an object)
         let tool_use = ToolUseBlock {
@@ -709,7 +1137,7 @@ mod tests {
         let provider = MockProvider::new().with_text("ok");
         let mut agent = Agent::builder().provider(provider).build().await.unwrap();
 
-        agent.add_tool(EchoTool);
+        agent.add_tool(EchoTool).unwrap();
 
         let tool_use = ToolUseBlock {
             id: "tool_123".to_string(),
@@ -730,7 +1158,7 @@ mod tests {
         let provider = MockProvider::new().with_text("ok");
         let mut agent = Agent::builder().provider(provider).build().await.unwrap();
 
-        agent.add_tool(EchoTool);
+        agent.add_tool(EchoTool).unwrap();
 
         let tool_use = ToolUseBlock {
             id: "tool_123".to_string(),
@@ -746,12 +1174,37 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_execute_tool_unrepairable_streamed_input() {
+        let provider = MockProvider::new().with_text("ok");
+        let mut agent = Agent::builder().provider(provider).build().await.unwrap();
+
+        agent.add_tool(EchoTool).unwrap();
+        agent
+            .invalid_tool_inputs
+            .lock()
+            .insert("tool_123".to_string(), "expected value".to_string());
+
+        let tool_use = ToolUseBlock {
+            id: "tool_123".to_string(),
+            name: "echo".to_string(),
+            input: serde_json::json!({}),
+        };
+
+        let err = agent.execute_tool(&tool_use).await.unwrap_err();
+        match err {
+            AgentError::InvalidToolInput(msg) => assert!(msg.contains("expected value")),
+            other => panic!("expected InvalidToolInput, got {:?}", other),
+        }
+        assert!(agent.invalid_tool_inputs.lock().is_empty());
+    }
+
     #[tokio::test]
     async fn test_execute_tool_execution_failure() {
         let provider = MockProvider::new().with_text("ok");
         let mut agent = Agent::builder().provider(provider).build().await.unwrap();
 
-        agent.add_tool(FailingTool);
+        agent.add_tool(FailingTool).unwrap();
 
         // Grant permission to the failing tool
         agent
@@ -780,7 +1233,7 @@ mod tests {
         let provider = MockProvider::new().with_text("ok");
         let mut agent = Agent::builder().provider(provider).build().await.unwrap();
 
-        agent.add_tool(EchoTool);
+        agent.add_tool(EchoTool).unwrap();
 
         let params = serde_json::json!({"message": "test"});
         let formatted = agent.format_tool_input("echo", &params, crate::presentation::Display::Cli);
@@ -806,7 +1259,7 @@ mod tests {
         let provider = MockProvider::new().with_text("ok");
         let mut agent = Agent::builder().provider(provider).build().await.unwrap();
 
-        agent.add_tool(EchoTool);
+        agent.add_tool(EchoTool).unwrap();
 
         let result = crate::tool::ToolResult::text("output");
         let formatted =
@@ -4,8 +4,9 @@ use serde_json::Value;
 use std::time::Duration;
 use thiserror::Error;
 
-use crate::provider::ProviderError;
+use crate::provider::{ProviderError, SamplingParams};
 use crate::tool::ToolError;
+use crate::types::{Citation, Message, ToolDefinition};
 
 use super::context::ContextError;
 
@@ -40,17 +41,33 @@ pub enum AgentError {
     #[error("Response exceeded maximum token limit. Try asking the model to be more concise or break the task into smaller steps.")]
     MaxTokensExceeded,
 
-    /// Response was filtered by content moderation
-    #[error("Response was filtered by content moderation")]
+    /// Input or response was filtered by content moderation
+    #[error("Content was filtered by content moderation")]
     ContentFiltered,
 
+    /// Model declined to respond, under [`RefusalPolicy::Error`]
+    #[error("Model refused to respond: {0}")]
+    Refused(String),
+
     /// Tool execution was denied by user or policy
     #[error("Tool execution denied: {0}")]
     ToolDenied(String),
 
-    /// Tool not found
-    #[error("Tool not found: {0}")]
-    ToolNotFound(String),
+    /// The model called a tool that is not registered
+    ///
+    /// The run continues: the model gets this error, listing the tools it
+    /// can call, as the tool result.
+    #[error("Unknown tool: {name}. Available tools: {}", list_or_none(available))]
+    ToolNotFound {
+        /// The name the model used
+        name: String,
+        /// Names of the tools the model can call
+        available: Vec<String>,
+    },
+
+    /// Tool execution was aborted with [`Agent::abort_tool`](super::Agent::abort_tool)
+    #[error("Tool execution aborted: {0}")]
+    ToolAborted(String),
 
     /// Invalid tool input from model
     #[error("Invalid tool input: {0}")]
@@ -64,9 +81,53 @@ pub enum AgentError {
     #[error("Unexpected stop reason: {0}")]
     UnexpectedStopReason(String),
 
+    /// Document could not be attached to the request
+    #[error("Invalid document: {0}")]
+    InvalidDocument(String),
+
     /// Context file loading error
     #[error("Context error: {0}")]
     Context(#[from] ContextError),
+
+    /// The run did not finish within the limit given to
+    /// [`Agent::run_with_timeout`](super::Agent::run_with_timeout)
+    #[error("Run timed out after {timeout:?}")]
+    Timeout {
+        /// The limit that was exceeded
+        timeout: Duration,
+        /// The last assistant response the model completed before the
+        /// deadline, if any. It may contain tool uses whose results never
+        /// arrived.
+        last_turn: Option<Message>,
+    },
+
+    /// The run was stopped by the interrupt given to
+    /// [`Agent::run_until`](super::Agent::run_until)
+    #[error("Interrupted")]
+    Interrupted,
+
+    /// The request was estimated to exceed the model's context window and
+    /// was not sent, under [`OverflowPolicy::Error`] or when
+    /// [`OverflowPolicy::Trim`] could not make it fit
+    #[error(
+        "Request of about {estimated_tokens} tokens exceeds the model's context window of \
+         {max_context_tokens} tokens"
+    )]
+    ContextOverflow {
+        /// Estimated size of the messages, system prompt, and tool
+        /// definitions
+        estimated_tokens: usize,
+        /// The provider's context window
+        max_context_tokens: usize,
+    },
+}
+
+fn list_or_none(names: &[String]) -> String {
+    if names.is_empty() {
+        "none".to_string()
+    } else {
+        names.join(", ")
+    }
 }
 
 /// Errors that can occur during permission operations
@@ -92,6 +153,8 @@ pub struct ToolInfo {
     pub name: String,
     /// Tool description
     pub description: String,
+    /// JSON schema of the tool's JSON output, if the tool declares one
+    pub output_schema: Option<serde_json::Value>,
 }
 
 /// Information about the current session
@@ -116,6 +179,40 @@ pub const DEFAULT_PERMISSION_TIMEOUT: Duration = Duration::from_secs(300);
 /// Default maximum concurrent tool executions
 pub const DEFAULT_MAX_CONCURRENT_TOOLS: usize = 12;
 
+/// What the agent does when a request won't fit the model's context window
+///
+/// Checked before every model call, after the conversation manager has
+/// chosen the messages to send, using the provider's token estimate of the
+/// messages, system prompt, and tool definitions. A single oversized
+/// message, such as a pasted file, can overflow even a managed conversation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Fail the run with [`AgentError::ContextOverflow`] instead of
+    /// sending the request
+    #[default]
+    Error,
+    /// Drop the oldest messages from the request until it fits, then fail
+    /// with [`AgentError::ContextOverflow`] if the latest message alone is
+    /// still too large. The conversation history itself is kept.
+    Trim,
+    /// Send the request unchecked and let the provider reject it
+    Send,
+}
+
+/// What the agent does when the model refuses to respond
+///
+/// Either way an [`AgentEvent::Refused`](crate::AgentEvent::Refused) is
+/// emitted first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RefusalPolicy {
+    /// Fail the run with [`AgentError::Refused`]
+    #[default]
+    Error,
+    /// Return the refusal as the response, with
+    /// [`AgentResponse::is_refusal`] set
+    Pass,
+}
+
 /// Response from Agent.run() containing the result and execution statistics
 #[derive(Debug, Clone)]
 pub struct AgentResponse {
@@ -123,12 +220,31 @@ pub struct AgentResponse {
     pub text: String,
     /// All tool calls made during this run
     pub tool_calls: Vec<ToolCallInfo>,
+    /// Tool calls the model requested but that were not executed because
+    /// the agent is in dry-run mode
+    pub intended_tool_calls: Vec<IntendedToolCall>,
     /// Total token usage across all model calls (if available)
     pub token_usage: Option<TokenUsageStats>,
     /// Total execution time
     pub duration: Duration,
     /// Number of model calls made (includes retries after tool use)
     pub model_calls: usize,
+    /// Total time spent waiting on model calls, the sum of the `duration`
+    /// of each [`AgentEvent::ModelCallCompleted`](crate::AgentEvent::ModelCallCompleted)
+    pub model_latency: Duration,
+    /// Model that produced the final response, as reported by the provider
+    ///
+    /// See [`ModelResponse::model`](crate::ModelResponse::model).
+    pub model: Option<String>,
+    /// The final assistant message, with all of its content blocks
+    pub message: Message,
+    /// Messages added to the conversation during this run, excluding the
+    /// user's prompt: each assistant response and the tool results sent
+    /// back to the model. The last entry is `message`.
+    pub messages: Vec<Message>,
+    /// Whether the model refused to respond; only set under
+    /// [`RefusalPolicy::Pass`]
+    pub refused: bool,
 }
 
 impl AgentResponse {
@@ -136,6 +252,29 @@ impl AgentResponse {
     pub fn text(&self) -> &str {
         &self.text
     }
+
+    /// The final assistant message
+    pub fn message(&self) -> &Message {
+        &self.message
+    }
+
+    /// The messages produced during this run, in conversation order
+    pub fn all_messages(&self) -> &[Message] {
+        &self.messages
+    }
+
+    /// Whether `text` is the model's refusal rather than an answer
+    pub fn is_refusal(&self) -> bool {
+        self.refused
+    }
+
+    /// Sources cited by the final message, in the order they were given
+    ///
+    /// Empty unless the provider was asked for citations, e.g. with
+    /// `AnthropicProvider::with_citations`.
+    pub fn citations(&self) -> Vec<Citation> {
+        self.message.citations().into_iter().cloned().collect()
+    }
 }
 
 impl std::fmt::Display for AgentResponse {
@@ -156,6 +295,19 @@ impl PartialEq<&str> for AgentResponse {
     }
 }
 
+/// The first request a run would send, from [`Agent::plan_request`](crate::Agent::plan_request)
+#[derive(Debug, Clone)]
+pub struct PlannedRequest {
+    /// Messages sent to the model, after conversation windowing
+    pub messages: Vec<Message>,
+    /// Tools offered to the model, including built-in tools
+    pub tools: Vec<ToolDefinition>,
+    /// System prompt, with context files and tool guidance added
+    pub system_prompt: Option<String>,
+    /// Sampling parameters the provider sends
+    pub sampling: SamplingParams,
+}
+
 /// Information about a tool call made during agent execution
 #[derive(Debug, Clone)]
 pub struct ToolCallInfo {
@@ -171,6 +323,17 @@ pub struct ToolCallInfo {
     pub duration: Duration,
 }
 
+/// A tool call skipped in dry-run mode
+#[derive(Debug, Clone)]
+pub struct IntendedToolCall {
+    /// Tool use ID assigned by the model
+    pub tool_use_id: String,
+    /// Tool name
+    pub name: String,
+    /// Input parameters (as JSON)
+    pub input: Value,
+}
+
 /// Cumulative token usage statistics
 #[derive(Debug, Clone, Copy, Default)]
 pub struct TokenUsageStats {
@@ -205,11 +368,19 @@ mod tests {
         let response = AgentResponse {
             text: "Hello".to_string(),
             tool_calls: vec![],
+            intended_tool_calls: vec![],
             token_usage: None,
             duration: Duration::from_secs(1),
             model_calls: 1,
+            model_latency: Duration::ZERO,
+            model: None,
+            message: Message::assistant("Hello"),
+            messages: vec![Message::assistant("Hello")],
+            refused: false,
         };
         assert_eq!(response.text(), "Hello");
+        assert_eq!(response.message().text(), "Hello");
+        assert_eq!(response.all_messages().len(), 1);
         assert_eq!(format!("{}", response), "Hello");
         assert!(response == "Hello");
     }
@@ -16,8 +16,33 @@
 //!   messages that fit within the context window. Never fails due to context overflow.
 //! - [`SimpleConversationManager`] - Keeps last N messages. May fail if N is too large.
 //! - [`NoOpConversationManager`] - Pass-through, no truncation. Fails on overflow.
+//! - [`SummarizingConversationManager`] - Replaces older messages with a
+//!   model-written summary once the context fills up.
+//!
+//! [`ConversationStrategy`] selects one of these with its parameters, for use
+//! with `AgentBuilder::with_conversation_strategy`.
+//!
+//! [`replay`] rebuilds a conversation from recorded [`AgentEvent`]s.
+
+use crate::events::AgentEvent;
+use crate::tool::ToolResult;
+use crate::types::{ContentBlock, Message, Role, ToolResultBlock, ToolResultStatus, ToolUseBlock};
+
+/// Default fraction of the context window reserved for the system prompt
+const DEFAULT_SYSTEM_PROMPT_RESERVE: f32 = 0.10;
+
+/// Default fraction of the context window reserved for the model's response
+const DEFAULT_RESPONSE_RESERVE: f32 = 0.20;
+
+/// Default fraction of the available context at which older messages are
+/// summarized
+const DEFAULT_SUMMARY_TRIGGER: f32 = 0.75;
 
-use crate::types::Message;
+/// Default number of recent messages kept verbatim when summarizing
+const DEFAULT_KEEP_RECENT: usize = 10;
+
+/// Heading that introduces a conversation summary in the context
+const SUMMARY_HEADING: &str = "Summary of the earlier conversation:";
 
 /// Context limits for message selection
 ///
@@ -88,6 +113,32 @@ pub trait ConversationManager: Send + Sync {
     /// Clear all messages from the conversation
     fn clear(&mut self);
 
+    /// Older messages to replace with a summary before the next model call
+    ///
+    /// The agent calls this before each model call. When it returns
+    /// messages, the agent asks the model to summarize them and passes the
+    /// summary to [`apply_summary`](Self::apply_summary). The default never
+    /// summarizes.
+    fn messages_to_summarize(
+        &self,
+        _limits: ContextLimits,
+        _estimate_tokens: TokenEstimator<'_>,
+    ) -> Option<Vec<Message>> {
+        None
+    }
+
+    /// Use `summary` in place of the `summarized` messages last returned by
+    /// [`messages_to_summarize`](Self::messages_to_summarize)
+    fn apply_summary(&mut self, _summarized: usize, _summary: String) {}
+
+    /// Short description of the strategy, for configuration snapshots
+    ///
+    /// Built-in managers include their settings, e.g.
+    /// `simple(max_messages=50)`.
+    fn strategy(&self) -> String {
+        "custom".to_string()
+    }
+
     /// Get context usage statistics
     fn context_usage(
         &self,
@@ -155,8 +206,8 @@ impl SlidingWindowConversationManager {
     pub fn new() -> Self {
         Self {
             messages: Vec::new(),
-            system_prompt_reserve: 0.10,
-            response_reserve: 0.20,
+            system_prompt_reserve: DEFAULT_SYSTEM_PROMPT_RESERVE,
+            response_reserve: DEFAULT_RESPONSE_RESERVE,
         }
     }
 
@@ -225,6 +276,13 @@ impl ConversationManager for SlidingWindowConversationManager {
     fn clear(&mut self) {
         self.messages.clear();
     }
+
+    fn strategy(&self) -> String {
+        format!(
+            "sliding_window(reserve_system={}, reserve_response={})",
+            self.system_prompt_reserve, self.response_reserve
+        )
+    }
 }
 
 /// Simple count-based conversation manager
@@ -283,6 +341,10 @@ impl ConversationManager for SimpleConversationManager {
     fn clear(&mut self) {
         self.messages.clear();
     }
+
+    fn strategy(&self) -> String {
+        format!("simple(max_messages={})", self.max_messages)
+    }
 }
 
 /// No-op conversation manager
@@ -337,15 +399,418 @@ impl ConversationManager for NoOpConversationManager {
     fn clear(&mut self) {
         self.messages.clear();
     }
+
+    fn strategy(&self) -> String {
+        "noop".to_string()
+    }
+}
+
+/// Summarizing conversation manager
+///
+/// Sends the whole conversation until it fills `trigger` of the available
+/// context, then has the agent ask the model to summarize all but the
+/// last `keep_recent` or so messages. The summary is sent ahead of the
+/// remaining messages from then on and is itself folded into the next
+/// summary. The full history is kept; only the context sent changes.
+///
+/// The same reserves as [`SlidingWindowConversationManager::new`] apply,
+/// and anything that still doesn't fit is windowed out the same way.
+///
+/// # Example
+/// ```
+/// use mixtape_core::conversation::SummarizingConversationManager;
+///
+/// // Summarize at 75% of the available context, keeping the last 10 messages
+/// let manager = SummarizingConversationManager::new();
+///
+/// // Or summarize earlier and keep more
+/// let manager = SummarizingConversationManager::with_trigger(0.5, 20);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SummarizingConversationManager {
+    messages: Vec<Message>,
+    /// Summary of `messages[..summarized]`
+    summary: Option<String>,
+    summarized: usize,
+    /// Fraction of the available context that triggers a summary (0.0 - 1.0)
+    trigger: f32,
+    keep_recent: usize,
+}
+
+impl Default for SummarizingConversationManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SummarizingConversationManager {
+    /// Create a summarizing manager with the default trigger (75% of the
+    /// available context) that keeps the last 10 messages
+    pub fn new() -> Self {
+        Self::with_trigger(DEFAULT_SUMMARY_TRIGGER, DEFAULT_KEEP_RECENT)
+    }
+
+    /// Create a manager that summarizes once the context fills `trigger` of
+    /// the available tokens, keeping at least the last `keep_recent`
+    /// messages verbatim
+    ///
+    /// `trigger` is clamped to 0.0 - 1.0.
+    pub fn with_trigger(trigger: f32, keep_recent: usize) -> Self {
+        Self {
+            messages: Vec::new(),
+            summary: None,
+            summarized: 0,
+            trigger: trigger.clamp(0.0, 1.0),
+            keep_recent,
+        }
+    }
+
+    /// Tokens left for messages after the default reserves
+    fn available_tokens(limits: ContextLimits) -> usize {
+        let max = limits.max_context_tokens;
+        let reserved =
+            (max as f32 * (DEFAULT_SYSTEM_PROMPT_RESERVE + DEFAULT_RESPONSE_RESERVE)) as usize;
+        max.saturating_sub(reserved)
+    }
+
+    /// The unsummarized messages, with the summary leading the first one
+    fn unsummarized(&self) -> Vec<Message> {
+        let mut messages = self.messages[self.summarized..].to_vec();
+        if let (Some(summary), Some(first)) = (&self.summary, messages.first_mut()) {
+            first.content.insert(
+                0,
+                ContentBlock::Text(format!("{}\n{}\n\n", SUMMARY_HEADING, summary)),
+            );
+        }
+        messages
+    }
+}
+
+impl ConversationManager for SummarizingConversationManager {
+    fn add_message(&mut self, message: Message) {
+        self.messages.push(message);
+    }
+
+    fn messages_for_context(
+        &self,
+        limits: ContextLimits,
+        estimate_tokens: TokenEstimator<'_>,
+    ) -> Vec<Message> {
+        let available = Self::available_tokens(limits);
+        let mut messages = self.unsummarized();
+
+        // Window out whatever still doesn't fit, oldest first
+        let mut total_tokens = 0;
+        let mut start = messages.len();
+        for message in messages.iter().rev() {
+            let msg_tokens = estimate_tokens(std::slice::from_ref(message));
+            if total_tokens + msg_tokens > available {
+                break;
+            }
+            total_tokens += msg_tokens;
+            start -= 1;
+        }
+        messages.split_off(start)
+    }
+
+    fn messages_to_summarize(
+        &self,
+        limits: ContextLimits,
+        estimate_tokens: TokenEstimator<'_>,
+    ) -> Option<Vec<Message>> {
+        let messages = self.unsummarized();
+        let threshold = (Self::available_tokens(limits) as f32 * self.trigger) as usize;
+        if estimate_tokens(&messages) <= threshold {
+            return None;
+        }
+
+        // Cut where a user turn opens, so the kept messages start with one
+        let keep_from = self.messages.len().saturating_sub(self.keep_recent);
+        let cut = (self.summarized + 1..=keep_from)
+            .rev()
+            .find(|&i| opens_turn(&self.messages[i]))?;
+        Some(messages[..cut - self.summarized].to_vec())
+    }
+
+    fn apply_summary(&mut self, summarized: usize, summary: String) {
+        self.summarized = (self.summarized + summarized).min(self.messages.len());
+        self.summary = Some(summary);
+    }
+
+    fn all_messages(&self) -> &[Message] {
+        &self.messages
+    }
+
+    fn hydrate(&mut self, messages: Vec<Message>) {
+        // Restoring the same history, as sessions do on every run, keeps
+        // the summary; anything else starts over
+        let same_prefix = messages.len() >= self.summarized
+            && self.messages[..self.summarized]
+                .iter()
+                .zip(&messages)
+                .all(|(old, new)| old.role == new.role && old.text() == new.text());
+        if !same_prefix {
+            self.summary = None;
+            self.summarized = 0;
+        }
+        self.messages = messages;
+    }
+
+    fn clear(&mut self) {
+        self.messages.clear();
+        self.summary = None;
+        self.summarized = 0;
+    }
+
+    fn strategy(&self) -> String {
+        format!(
+            "summarizing(trigger={}, keep_recent={})",
+            self.trigger, self.keep_recent
+        )
+    }
+}
+
+/// Whether a message can start a request: a user turn with more than tool
+/// results
+pub(crate) fn opens_turn(message: &Message) -> bool {
+    message.role == Role::User
+        && message
+            .content
+            .iter()
+            .any(|block| !matches!(block, ContentBlock::ToolResult(_)))
+}
+
+/// Render messages as a plain-text transcript, for asking a model to
+/// summarize them
+pub(crate) fn transcript(messages: &[Message]) -> String {
+    let mut lines = Vec::new();
+    for message in messages {
+        let speaker = match message.role {
+            Role::User => "User",
+            Role::Assistant => "Assistant",
+        };
+        for block in &message.content {
+            let line = match block {
+                ContentBlock::Text(text) => text.clone(),
+                ContentBlock::ToolUse(tool_use) => {
+                    format!("[called {} with {}]", tool_use.name, tool_use.input)
+                }
+                ContentBlock::ToolResult(result) => {
+                    format!("[tool result: {}]", result.content.as_text())
+                }
+                ContentBlock::Document(document) => format!(
+                    "[document: {}]",
+                    document.name.as_deref().unwrap_or("unnamed")
+                ),
+                ContentBlock::Thinking { .. } | ContentBlock::Citations { .. } => continue,
+            };
+            lines.push(format!("{}: {}", speaker, line));
+        }
+    }
+    lines.join("\n\n")
 }
 
 /// Boxed conversation manager for type erasure
 pub type BoxedConversationManager = Box<dyn ConversationManager>;
 
+/// Which built-in conversation manager an agent uses, with its parameters
+///
+/// Every strategy keeps the full history; they differ only in which
+/// messages are sent to the model. Only `Summarizing` keeps any trace of
+/// the messages it leaves out of the context.
+///
+/// # Example
+/// ```
+/// use mixtape_core::conversation::ConversationStrategy;
+///
+/// // Keep the last 20 messages regardless of size
+/// let strategy = ConversationStrategy::Simple { max_messages: 20 };
+/// let manager = strategy.into_manager();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConversationStrategy {
+    /// Send as many recent messages as fit in the model's context window
+    /// after the reserves. Never overflows the context, but long
+    /// conversations silently lose their oldest messages. The default.
+    SlidingWindow {
+        /// Fraction of the context window reserved for the system prompt,
+        /// clamped to 0.0 - 0.5
+        system_prompt_reserve: f32,
+        /// Fraction of the context window reserved for the model's
+        /// response, clamped to 0.0 - 0.5
+        response_reserve: f32,
+    },
+    /// Send the last `max_messages` messages. Predictable, but a few large
+    /// messages such as file contents can still overflow the context.
+    Simple {
+        /// Number of most recent messages to send
+        max_messages: usize,
+    },
+    /// Send every message. The model sees the whole conversation until it
+    /// no longer fits, after which every model call fails.
+    NoOp,
+    /// Replace older messages with a model-written summary once the context
+    /// fills `trigger` of the available tokens. Keeps the gist of long
+    /// conversations, at the cost of an extra model call per summary and
+    /// detail lost from summarized messages.
+    Summarizing {
+        /// Fraction of the available context that triggers a summary,
+        /// clamped to 0.0 - 1.0
+        trigger: f32,
+        /// Number of most recent messages kept out of the summary
+        keep_recent: usize,
+    },
+}
+
+impl Default for ConversationStrategy {
+    fn default() -> Self {
+        Self::SlidingWindow {
+            system_prompt_reserve: DEFAULT_SYSTEM_PROMPT_RESERVE,
+            response_reserve: DEFAULT_RESPONSE_RESERVE,
+        }
+    }
+}
+
+impl ConversationStrategy {
+    /// Construct the conversation manager for this strategy
+    pub fn into_manager(self) -> BoxedConversationManager {
+        match self {
+            Self::SlidingWindow {
+                system_prompt_reserve,
+                response_reserve,
+            } => Box::new(SlidingWindowConversationManager::with_reserve(
+                system_prompt_reserve,
+                response_reserve,
+            )),
+            Self::Simple { max_messages } => Box::new(SimpleConversationManager::new(max_messages)),
+            Self::NoOp => Box::new(NoOpConversationManager::new()),
+            Self::Summarizing {
+                trigger,
+                keep_recent,
+            } => Box::new(SummarizingConversationManager::with_trigger(
+                trigger,
+                keep_recent,
+            )),
+        }
+    }
+}
+
+/// Rebuild a conversation from recorded agent events
+///
+/// Each `RunStarted` becomes a user message and each `ModelCallCompleted`
+/// an assistant message, to which the following `ToolRequested` events add
+/// their tool uses. `ToolCompleted`, `ToolFailed`, and `ToolSkippedDryRun`
+/// results are gathered into the user message that answers them. Other
+/// events are ignored.
+///
+/// Replay is best-effort: events carry less than the messages did, so
+/// thinking blocks and document input are lost, and a failed tool's result
+/// holds the event's error text, which may be worded differently from what
+/// the model saw. Streams that begin or end mid-run are replayed as far as
+/// they go; tool uses before any `ModelCallCompleted` get an assistant
+/// message of their own.
+///
+/// ```ignore
+/// let messages = mixtape_core::conversation::replay(&recorded_events);
+/// for message in &messages {
+///     println!("{:?}: {}", message.role, message.text());
+/// }
+/// ```
+pub fn replay(events: &[AgentEvent]) -> Vec<Message> {
+    let mut messages: Vec<Message> = Vec::new();
+    let mut results: Vec<ToolResultBlock> = Vec::new();
+    // Index of the assistant message that tool uses are added to
+    let mut assistant: Option<usize> = None;
+
+    let flush = |messages: &mut Vec<Message>, results: &mut Vec<ToolResultBlock>| {
+        if !results.is_empty() {
+            messages.push(Message::tool_results(std::mem::take(results)));
+        }
+    };
+
+    for event in events {
+        match event {
+            AgentEvent::RunStarted { input, .. } => {
+                flush(&mut messages, &mut results);
+                messages.push(Message::user(input.clone()));
+                assistant = None;
+            }
+            AgentEvent::ModelCallCompleted {
+                response_content, ..
+            } => {
+                flush(&mut messages, &mut results);
+                let mut message = Message {
+                    role: Role::Assistant,
+                    content: Vec::new(),
+                };
+                if !response_content.is_empty() {
+                    message
+                        .content
+                        .push(ContentBlock::Text(response_content.clone()));
+                }
+                messages.push(message);
+                assistant = Some(messages.len() - 1);
+            }
+            AgentEvent::ToolRequested {
+                tool_use_id,
+                name,
+                input,
+            } => {
+                let index = *assistant.get_or_insert_with(|| {
+                    flush(&mut messages, &mut results);
+                    messages.push(Message {
+                        role: Role::Assistant,
+                        content: Vec::new(),
+                    });
+                    messages.len() - 1
+                });
+                messages[index]
+                    .content
+                    .push(ContentBlock::ToolUse(ToolUseBlock {
+                        id: tool_use_id.clone(),
+                        name: name.clone(),
+                        input: input.clone(),
+                    }));
+            }
+            AgentEvent::ToolCompleted {
+                tool_use_id,
+                output,
+                model_output,
+                ..
+            } => {
+                results.push(ToolResultBlock {
+                    tool_use_id: tool_use_id.clone(),
+                    content: model_output.as_ref().unwrap_or(output).clone(),
+                    status: ToolResultStatus::Success,
+                });
+            }
+            AgentEvent::ToolFailed {
+                tool_use_id, error, ..
+            } => {
+                results.push(ToolResultBlock {
+                    tool_use_id: tool_use_id.clone(),
+                    content: ToolResult::Text(format!("Error: {}", error)),
+                    status: ToolResultStatus::Error,
+                });
+            }
+            AgentEvent::ToolSkippedDryRun { tool_use_id, .. } => {
+                results.push(ToolResultBlock {
+                    tool_use_id: tool_use_id.clone(),
+                    content: ToolResult::Text(crate::agent::DRY_RUN_TOOL_RESULT.to_string()),
+                    status: ToolResultStatus::Success,
+                });
+            }
+            _ => {}
+        }
+    }
+    flush(&mut messages, &mut results);
+
+    messages
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::{ContentBlock, Role};
 
     fn make_message(text: &str) -> Message {
         Message {
@@ -389,6 +854,103 @@ mod tests {
         assert_eq!(context.last().unwrap().text(), "Short");
     }
 
+    #[test]
+    fn test_strategy_managers() {
+        // Fits "Short" and nothing before it
+        let limits = ContextLimits::new(35);
+        let fill = |manager: &mut BoxedConversationManager| {
+            manager.add_message(make_message("This is a long message one"));
+            manager.add_message(make_message("This is a long message two"));
+            manager.add_message(make_message("Short"));
+        };
+
+        let mut manager = ConversationStrategy::NoOp.into_manager();
+        fill(&mut manager);
+        assert_eq!(
+            manager.messages_for_context(limits, &estimate_tokens).len(),
+            3
+        );
+
+        let mut manager = ConversationStrategy::Simple { max_messages: 2 }.into_manager();
+        fill(&mut manager);
+        let context = manager.messages_for_context(limits, &estimate_tokens);
+        assert_eq!(context.len(), 2);
+        assert_eq!(context[0].text(), "This is a long message two");
+
+        let mut manager = ConversationStrategy::SlidingWindow {
+            system_prompt_reserve: 0.0,
+            response_reserve: 0.0,
+        }
+        .into_manager();
+        fill(&mut manager);
+        let context = manager.messages_for_context(limits, &estimate_tokens);
+        assert_eq!(context.len(), 1);
+        assert_eq!(context[0].text(), "Short");
+        assert_eq!(manager.all_messages().len(), 3);
+    }
+
+    #[test]
+    fn test_summarizing_manager() {
+        let limits = ContextLimits::new(1000);
+        let mut manager = ConversationStrategy::Summarizing {
+            trigger: 0.0,
+            keep_recent: 1,
+        }
+        .into_manager();
+        assert_eq!(manager.strategy(), "summarizing(trigger=0, keep_recent=1)");
+
+        // Nothing older than the kept messages yet
+        manager.add_message(make_message("first question"));
+        assert!(manager
+            .messages_to_summarize(limits, &estimate_tokens)
+            .is_none());
+
+        manager.add_message(Message::assistant("first answer"));
+        manager.add_message(make_message("second question"));
+        let older = manager
+            .messages_to_summarize(limits, &estimate_tokens)
+            .unwrap();
+        assert_eq!(older.len(), 2);
+        assert_eq!(older[0].text(), "first question");
+
+        manager.apply_summary(older.len(), "asked one question".to_string());
+        let context = manager.messages_for_context(limits, &estimate_tokens);
+        assert_eq!(context.len(), 1);
+        assert_eq!(
+            context[0].text(),
+            "Summary of the earlier conversation:\nasked one question\n\nsecond question"
+        );
+        assert_eq!(manager.all_messages().len(), 3);
+
+        // Restoring the same history keeps the summary, a different one drops it
+        manager.hydrate(manager.all_messages().to_vec());
+        assert_eq!(
+            manager.messages_for_context(limits, &estimate_tokens).len(),
+            1
+        );
+        manager.hydrate(vec![make_message("other")]);
+        assert_eq!(
+            manager.messages_for_context(limits, &estimate_tokens)[0].text(),
+            "other"
+        );
+    }
+
+    #[test]
+    fn test_summarizing_manager_below_trigger() {
+        let limits = ContextLimits::new(1000);
+        let mut manager = SummarizingConversationManager::new();
+        for text in ["one", "two", "three"] {
+            manager.add_message(make_message(text));
+        }
+        assert!(manager
+            .messages_to_summarize(limits, &estimate_tokens)
+            .is_none());
+        assert_eq!(
+            manager.messages_for_context(limits, &estimate_tokens).len(),
+            3
+        );
+    }
+
     #[test]
     fn test_sliding_window_hydrate() {
         let mut manager = SlidingWindowConversationManager::new();
@@ -462,4 +1024,47 @@ mod tests {
         manager.clear();
         assert_eq!(manager.all_messages().len(), 0);
     }
+
+    // ===== replay =====
+
+    #[test]
+    fn test_replay_partial_stream_is_best_effort() {
+        // Recording started mid-run: no RunStarted or ModelCallCompleted
+        let events = vec![
+            AgentEvent::ToolRequested {
+                tool_use_id: "t1".to_string(),
+                name: "calculate".to_string(),
+                input: serde_json::json!({"expression": "1/0"}),
+            },
+            AgentEvent::ToolFailed {
+                tool_use_id: "t1".to_string(),
+                name: "calculate".to_string(),
+                error: "division by zero".to_string(),
+                duration: std::time::Duration::ZERO,
+            },
+            AgentEvent::ModelCallCompleted {
+                response_content: "That is undefined".to_string(),
+                tokens: None,
+                duration: std::time::Duration::ZERO,
+                stop_reason: None,
+            },
+        ];
+
+        let messages = replay(&events);
+
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[0].role, Role::Assistant);
+        assert_eq!(messages[0].tool_uses()[0].id, "t1");
+        let ContentBlock::ToolResult(result) = &messages[1].content[0] else {
+            panic!("expected a tool result");
+        };
+        assert_eq!(result.status, ToolResultStatus::Error);
+        assert_eq!(result.content.as_text(), "Error: division by zero");
+        assert_eq!(messages[2].text(), "That is undefined");
+    }
+
+    #[test]
+    fn test_replay_empty() {
+        assert!(replay(&[]).is_empty());
+    }
 }
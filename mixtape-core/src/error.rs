@@ -24,7 +24,10 @@ use crate::session::SessionError;
 /// - [`Error::Unavailable`] - Service is down, wait and retry
 /// - [`Error::Model`] - Model-side issues (content filtered, context too long)
 /// - [`Error::Tool`] - Tool execution failed
+/// - [`Error::ToolInit`] - A tool source failed while building the agent
 /// - [`Error::Config`] - Fix configuration (bad model ID, missing parameters)
+/// - [`Error::InvalidToolName`] - A tool's name is invalid for the provider or not unique
+/// - [`Error::CredentialCheckFailed`] - The provider rejected the build-time credential check
 #[derive(Debug, Error)]
 pub enum Error {
     /// Authentication failed (invalid or expired credentials)
@@ -51,10 +54,30 @@ pub enum Error {
     #[error("tool error: {0}")]
     Tool(String),
 
+    /// A fallible tool source failed while building the agent
+    #[error("tool initialization failed: {0}")]
+    ToolInit(String),
+
     /// Configuration error (bad model ID, missing parameters)
     #[error("configuration error: {0}")]
     Config(String),
 
+    /// A tool's name breaks the provider's naming rules or is shared with
+    /// another tool
+    #[error("invalid tool name '{name}': {reason}")]
+    InvalidToolName {
+        /// The offending tool name
+        name: String,
+        /// Why the name was rejected
+        reason: String,
+    },
+
+    /// The credential check requested with
+    /// [`AgentBuilder::with_credential_check`](crate::AgentBuilder::with_credential_check)
+    /// failed; holds the classified provider error
+    #[error("credential check failed: {0}")]
+    CredentialCheckFailed(ProviderError),
+
     /// Session storage error
     #[cfg(feature = "session")]
     #[error("session error: {0}")]
@@ -101,11 +124,26 @@ impl Error {
         matches!(self, Self::Tool(_))
     }
 
+    /// Returns true if a tool source failed during agent construction
+    pub fn is_tool_init(&self) -> bool {
+        matches!(self, Self::ToolInit(_))
+    }
+
     /// Returns true if this is a configuration error
     pub fn is_config(&self) -> bool {
         matches!(self, Self::Config(_))
     }
 
+    /// Returns true if a tool name was rejected while building the agent
+    pub fn is_invalid_tool_name(&self) -> bool {
+        matches!(self, Self::InvalidToolName { .. })
+    }
+
+    /// Returns true if the build-time credential check failed
+    pub fn is_credential_check_failed(&self) -> bool {
+        matches!(self, Self::CredentialCheckFailed(_))
+    }
+
     /// Returns true if this error is potentially retryable
     ///
     /// Retryable errors include rate limiting, network issues, and service
@@ -167,16 +205,30 @@ impl From<AgentError> for Error {
                     .to_string(),
             ),
             AgentError::ContentFiltered => {
-                Self::Model("response was filtered by content moderation".to_string())
+                Self::Model("content was filtered by content moderation".to_string())
             }
+            AgentError::Refused(reason) => Self::Model(format!("model refused: {}", reason)),
             AgentError::ToolDenied(msg) => Self::Tool(format!("denied: {}", msg)),
-            AgentError::ToolNotFound(name) => Self::Tool(format!("not found: {}", name)),
+            AgentError::ToolNotFound { name, .. } => Self::Tool(format!("not found: {}", name)),
+            AgentError::ToolAborted(name) => Self::Tool(format!("aborted: {}", name)),
             AgentError::InvalidToolInput(msg) => Self::Tool(format!("invalid input: {}", msg)),
             AgentError::PermissionFailed(msg) => Self::Tool(format!("permission failed: {}", msg)),
             AgentError::UnexpectedStopReason(reason) => {
                 Self::Model(format!("unexpected stop reason: {}", reason))
             }
+            AgentError::InvalidDocument(msg) => Self::Config(format!("invalid document: {}", msg)),
             AgentError::Context(e) => Self::Model(format!("context error: {}", e)),
+            AgentError::ContextOverflow {
+                estimated_tokens,
+                max_context_tokens,
+            } => Self::Model(format!(
+                "request of about {} tokens exceeds the context window of {} tokens",
+                estimated_tokens, max_context_tokens
+            )),
+            AgentError::Timeout { timeout, .. } => {
+                Self::Other(format!("run timed out after {:?}", timeout))
+            }
+            AgentError::Interrupted => Self::Other("run interrupted".to_string()),
         }
     }
 }
@@ -216,7 +268,11 @@ mod tests {
         let err: Error = AgentError::MaxTokensExceeded.into();
         assert!(err.is_model());
 
-        let err: Error = AgentError::ToolNotFound("calculator".into()).into();
+        let err: Error = AgentError::ToolNotFound {
+            name: "calculator".into(),
+            available: Vec::new(),
+        }
+        .into();
         assert!(err.is_tool());
 
         let err: Error = AgentError::Provider(ProviderError::RateLimited("slow".into())).into();
@@ -231,6 +287,7 @@ mod tests {
         assert!(Error::Unavailable("x".into()).is_unavailable());
         assert!(Error::Model("x".into()).is_model());
         assert!(Error::Tool("x".into()).is_tool());
+        assert!(Error::ToolInit("x".into()).is_tool_init());
         assert!(Error::Config("x".into()).is_config());
     }
 }
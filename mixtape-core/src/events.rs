@@ -1,7 +1,9 @@
 use std::time::{Duration, Instant};
 
+use async_trait::async_trait;
 use serde_json::Value;
 
+use crate::filter::FilterStage;
 use crate::permission::Scope;
 use crate::tool::ToolResult;
 use crate::types::StopReason;
@@ -15,6 +17,8 @@ pub enum AgentEvent {
     // ===== Agent Lifecycle =====
     /// Agent.run() started
     RunStarted {
+        /// Name of the agent, if one was set with `AgentBuilder::with_name`
+        agent_name: Option<String>,
         /// User input message
         input: String,
         /// Timestamp
@@ -23,6 +27,8 @@ pub enum AgentEvent {
 
     /// Agent.run() completed
     RunCompleted {
+        /// Name of the agent, if one was set with `AgentBuilder::with_name`
+        agent_name: Option<String>,
         /// Final response to user
         output: String,
         /// Total execution duration
@@ -38,6 +44,33 @@ pub enum AgentEvent {
     },
 
     // ===== Model API Lifecycle =====
+    /// The next request's estimated input is over the warning threshold
+    ///
+    /// Emitted before `ModelCallStarted` for each model call whose input is
+    /// estimated above the threshold set with
+    /// [`AgentBuilder::with_input_token_warning`](crate::AgentBuilder::with_input_token_warning).
+    /// The warning is advisory; the call goes ahead unless the run is
+    /// cancelled.
+    BudgetWarning {
+        /// Estimated input tokens of the request
+        estimated_input_tokens: usize,
+        /// The configured warning threshold
+        threshold: usize,
+    },
+
+    /// The system prompt or tools differ from the previous model call
+    ///
+    /// Providers cache the prompt prefix made of the tool definitions and
+    /// system prompt, so a change means the next call misses any cached
+    /// prefix and pays to write a new one. Emitted before `ModelCallStarted`,
+    /// whether or not the provider caches prompts.
+    CacheInvalidated {
+        /// Whether the system prompt changed
+        system_prompt_changed: bool,
+        /// Whether the tool definitions changed
+        tools_changed: bool,
+    },
+
     /// Model API call started
     ModelCallStarted {
         /// Messages being sent to model
@@ -68,7 +101,47 @@ pub enum AgentEvent {
         stop_reason: Option<StopReason>,
     },
 
+    /// A model turn finished and its response was added to the conversation
+    ///
+    /// Fires once per model response in both streaming and non-streaming
+    /// runs, after `ModelCallCompleted` and before any tools the response
+    /// requested are run. A run that uses tools has one turn per model call,
+    /// so this marks the boundary between text written before and after
+    /// tool results.
+    TurnCompleted {
+        /// Position of the turn within the run, starting at 0
+        turn_index: usize,
+        /// Why the model stopped; `ToolUse` means another turn follows
+        stop_reason: StopReason,
+    },
+
     // ===== Tool Lifecycle =====
+    /// A fragment of a tool call's input arrived from a streaming model
+    ///
+    /// Fragments concatenate to the input JSON text. They precede the
+    /// `ToolRequested` event for the same call, which carries the complete
+    /// parsed input.
+    ToolArgsDelta {
+        /// ID of the tool use, matching the later ToolRequested
+        tool_use_id: String,
+        /// The next piece of the input JSON text
+        partial_json: String,
+    },
+
+    /// A streamed tool input was cut off mid-JSON and has been repaired
+    ///
+    /// Fires just before the `ToolRequested` event for the same call, whose
+    /// input is the repaired value. The model may have meant more than what
+    /// was kept, so hosts can treat these calls with suspicion.
+    ToolInputRepaired {
+        /// ID of the tool use, matching the following ToolRequested
+        tool_use_id: String,
+        /// The input text as received
+        original: String,
+        /// The input after closing what was left open
+        repaired: Value,
+    },
+
     /// Model requested a tool (fires exactly once per tool use)
     ToolRequested {
         /// Unique ID for this tool use
@@ -93,9 +166,13 @@ pub enum AgentEvent {
         tool_use_id: String,
         /// Tool name
         name: String,
-        /// Tool output
+        /// Tool output, in full
         output: ToolResult,
-        /// Execution duration
+        /// What the model was sent instead of `output`, when the result was
+        /// cut to [`AgentBuilder::with_max_tool_result_bytes`](crate::AgentBuilder::with_max_tool_result_bytes)
+        /// or the provider's [`estimated_max_tool_result_bytes`](crate::ModelProvider::estimated_max_tool_result_bytes)
+        model_output: Option<ToolResult>,
+        /// Execution duration, excluding time spent waiting for approval
         duration: Duration,
     },
 
@@ -107,10 +184,32 @@ pub enum AgentEvent {
         name: String,
         /// Error message
         error: String,
-        /// How long before failure
+        /// How long before failure. For failures during execution this
+        /// excludes time spent waiting for approval.
         duration: Duration,
     },
 
+    /// Tool was not executed because the agent is in dry-run mode
+    ToolSkippedDryRun {
+        /// Matching ID from ToolRequested
+        tool_use_id: String,
+        /// Tool name
+        name: String,
+        /// Input parameters the tool would have received
+        input: Value,
+    },
+
+    /// A tool got malformed input too many times in a row, so the next
+    /// model call is made with tool use disabled
+    ///
+    /// See [`AgentBuilder::with_tool_json_recovery`](crate::AgentBuilder::with_tool_json_recovery).
+    ToolJsonRecovery {
+        /// Tool name
+        name: String,
+        /// Consecutive calls with malformed input
+        attempts: usize,
+    },
+
     // ===== Permission Events =====
     /// Tool execution requires permission
     PermissionRequired {
@@ -144,6 +243,50 @@ pub enum AgentEvent {
         reason: String,
     },
 
+    // ===== Moderation Events =====
+    /// A content filter blocked the user input or the model's response
+    ///
+    /// The run then fails with
+    /// [`AgentError::ContentFiltered`](crate::AgentError::ContentFiltered).
+    ContentFiltered {
+        /// Whether input or output was blocked
+        stage: FilterStage,
+        /// Reason given by the filter
+        reason: String,
+    },
+
+    /// The model declined to respond
+    ///
+    /// Under [`RefusalPolicy::Error`](crate::RefusalPolicy::Error) the run
+    /// then fails with [`AgentError::Refused`](crate::AgentError::Refused).
+    Refused {
+        /// The model's refusal text, if it gave one
+        reason: String,
+    },
+
+    // ===== Progress Events =====
+    /// The model reported progress with the built-in progress tool
+    ///
+    /// Only emitted when the agent was built with
+    /// [`AgentBuilder::with_progress_tool`](crate::AgentBuilder::with_progress_tool).
+    Progress {
+        /// What the model says it has done
+        message: String,
+        /// Portion of the task complete, from 0.0 to 1.0, if given
+        fraction: Option<f64>,
+    },
+
+    // ===== Delegation Events =====
+    /// Event emitted by a sub-agent while running a delegated task
+    ///
+    /// Forwarded to the calling agent's hooks by [`AgentTool`](crate::AgentTool).
+    SubAgent {
+        /// Name of the `AgentTool` that ran the sub-agent
+        agent: String,
+        /// The sub-agent's original event
+        event: Box<AgentEvent>,
+    },
+
     // ===== Session Events =====
     #[cfg(feature = "session")]
     /// Session resumed from storage
@@ -218,10 +361,48 @@ where
     }
 }
 
+/// Hook for observing agent events with async work
+///
+/// Use this instead of [`AgentHook`] when handling an event needs to await
+/// something, such as a database write or a bounded channel send. The agent
+/// awaits each call before moving on, so a slow hook slows the agent down
+/// rather than letting events pile up.
+///
+/// # Ordering
+///
+/// For each event, sync hooks are called first, then async hooks are
+/// awaited one at a time, both in registration order. Async delivery is
+/// serialized per agent: an async hook sees events in the order they were
+/// emitted and never handles two at once, even while tools run
+/// concurrently. Sync hooks do not wait for async hooks, so with
+/// concurrent tools a sync hook may see a later event before an async hook
+/// has finished with an earlier one.
+///
+/// # Example
+/// ```
+/// use async_trait::async_trait;
+/// use mixtape_core::events::{AgentEvent, AsyncAgentHook};
+/// use tokio::sync::mpsc;
+///
+/// struct Forward(mpsc::Sender<String>);
+///
+/// #[async_trait]
+/// impl AsyncAgentHook for Forward {
+///     async fn on_event(&self, event: &AgentEvent) {
+///         let _ = self.0.send(format!("{:?}", event)).await;
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait AsyncAgentHook: Send + Sync {
+    /// Called when an event occurs
+    async fn on_event(&self, event: &AgentEvent);
+}
+
 /// Unique identifier for a registered hook.
 ///
 /// Used to remove hooks via [`crate::Agent::remove_hook`].
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct HookId(pub(crate) u64);
 
 #[cfg(test)]
@@ -0,0 +1,75 @@
+//! Content filters for moderating agent input and output
+//!
+//! A [`ContentFilter`] inspects the user's message before it reaches the
+//! model and each model response before it is recorded or reported. Each check
+//! returns a [`FilterDecision`]: the text can pass unchanged, be replaced,
+//! or stop the run with [`AgentError::ContentFiltered`](crate::AgentError::ContentFiltered).
+
+use async_trait::async_trait;
+
+/// The outcome of a content filter check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterDecision {
+    /// Pass the text through unchanged
+    Allow,
+    /// Stop the run, with the reason reported in
+    /// [`AgentEvent::ContentFiltered`](crate::AgentEvent::ContentFiltered)
+    Block(String),
+    /// Replace the text with the given string
+    Redact(String),
+}
+
+/// Which side of the conversation a filter decision applied to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterStage {
+    /// The user message, before it is sent to the model
+    Input,
+    /// A model response, before it is recorded or reported
+    Output,
+}
+
+/// Moderation check run on user input and model output
+///
+/// Filters run in registration order. A redaction is passed on to the next
+/// filter, and the first block ends the run. Only text is checked: tool
+/// calls, tool results, and attached documents are not.
+///
+/// Output is checked for every model response, including the text that
+/// accompanies tool calls, before `ModelCallCompleted` is emitted. While any
+/// filter is registered, text deltas are held back and the filtered response
+/// is sent as a single
+/// [`AgentEvent::ModelCallStreaming`](crate::AgentEvent::ModelCallStreaming).
+///
+/// Both methods allow everything by default, so a filter only needs to
+/// implement the side it cares about.
+///
+/// # Example
+/// ```
+/// use async_trait::async_trait;
+/// use mixtape_core::filter::{ContentFilter, FilterDecision};
+///
+/// struct NoSecrets;
+///
+/// #[async_trait]
+/// impl ContentFilter for NoSecrets {
+///     async fn check_output(&self, text: &str) -> FilterDecision {
+///         if text.contains("AKIA") {
+///             FilterDecision::Redact(text.replace("AKIA", "[REDACTED]"))
+///         } else {
+///             FilterDecision::Allow
+///         }
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait ContentFilter: Send + Sync {
+    /// Check the text of a user message before it is sent to the model
+    async fn check_input(&self, _text: &str) -> FilterDecision {
+        FilterDecision::Allow
+    }
+
+    /// Check the text of a model response before it is recorded or reported
+    async fn check_output(&self, _text: &str) -> FilterDecision {
+        FilterDecision::Allow
+    }
+}
@@ -0,0 +1,97 @@
+//! Identifier generation for tool uses
+//!
+//! Providers normally assign tool-use IDs and mixtape echoes them back. When
+//! an ID has to be synthesized instead (a provider that omits one, or a mock
+//! provider in tests), it comes from an [`IdGenerator`]. The default produces
+//! random UUIDs; [`SequentialIdGenerator`] makes transcripts and caches
+//! reproducible.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Source of synthesized tool-use IDs
+pub trait IdGenerator: Send + Sync {
+    /// Return a new, unique tool-use ID
+    fn next_tool_use_id(&self) -> String;
+}
+
+/// Generates random `tool_<uuid>` IDs (the default)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UuidIdGenerator;
+
+impl IdGenerator for UuidIdGenerator {
+    fn next_tool_use_id(&self) -> String {
+        format!("tool_{}", uuid::Uuid::new_v4())
+    }
+}
+
+/// Generates `tool_1`, `tool_2`, ... for deterministic tests
+///
+/// # Example
+/// ```
+/// use mixtape_core::{IdGenerator, SequentialIdGenerator};
+///
+/// let ids = SequentialIdGenerator::new();
+/// assert_eq!(ids.next_tool_use_id(), "tool_1");
+/// assert_eq!(ids.next_tool_use_id(), "tool_2");
+/// ```
+#[derive(Debug)]
+pub struct SequentialIdGenerator {
+    prefix: String,
+    next: AtomicU64,
+}
+
+impl SequentialIdGenerator {
+    /// Create a generator that starts at `tool_1`
+    pub fn new() -> Self {
+        Self::with_prefix("tool_")
+    }
+
+    /// Create a generator that starts at `<prefix>1`
+    pub fn with_prefix(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            next: AtomicU64::new(1),
+        }
+    }
+}
+
+impl Default for SequentialIdGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IdGenerator for SequentialIdGenerator {
+    fn next_tool_use_id(&self) -> String {
+        let n = self.next.fetch_add(1, Ordering::Relaxed);
+        format!("{}{}", self.prefix, n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sequential_ids_increment() {
+        let ids = SequentialIdGenerator::new();
+        assert_eq!(ids.next_tool_use_id(), "tool_1");
+        assert_eq!(ids.next_tool_use_id(), "tool_2");
+        assert_eq!(ids.next_tool_use_id(), "tool_3");
+    }
+
+    #[test]
+    fn test_sequential_ids_custom_prefix() {
+        let ids = SequentialIdGenerator::with_prefix("call-");
+        assert_eq!(ids.next_tool_use_id(), "call-1");
+    }
+
+    #[test]
+    fn test_uuid_ids_are_unique() {
+        let ids = UuidIdGenerator;
+        let a = ids.next_tool_use_id();
+        let b = ids.next_tool_use_id();
+        assert!(a.starts_with("tool_"));
+        assert_ne!(a, b);
+    }
+}
@@ -0,0 +1,52 @@
+//! Request interceptors for modifying model calls before they are sent
+//!
+//! Hooks ([`AgentHook`](crate::events::AgentHook)) observe agent execution;
+//! interceptors change it. Each registered [`RequestInterceptor`] receives a
+//! mutable [`RequestContext`] immediately before every provider call and may
+//! rewrite the messages, tools, or system prompt that will be sent.
+
+use async_trait::async_trait;
+
+use crate::types::{Message, ToolDefinition};
+
+/// The request about to be sent to the model provider
+///
+/// Changes made here affect only the current model call. The agent's
+/// conversation history is not modified, so a message appended by an
+/// interceptor is not replayed on the next call unless the interceptor
+/// appends it again.
+#[derive(Debug, Clone)]
+pub struct RequestContext {
+    /// Messages selected from the conversation for this call
+    pub messages: Vec<Message>,
+    /// Tools offered to the model
+    pub tools: Vec<ToolDefinition>,
+    /// System prompt, including any resolved context files
+    pub system_prompt: Option<String>,
+}
+
+/// Middleware that can modify each request before it reaches the provider
+///
+/// Interceptors run in registration order, each seeing the changes made by
+/// the ones before it.
+///
+/// # Example
+/// ```
+/// use async_trait::async_trait;
+/// use mixtape_core::interceptor::{RequestContext, RequestInterceptor};
+///
+/// struct Timestamp;
+///
+/// #[async_trait]
+/// impl RequestInterceptor for Timestamp {
+///     async fn before_generate(&self, ctx: &mut RequestContext) {
+///         let suffix = "\n\nThe current year is 2026.";
+///         ctx.system_prompt = Some(ctx.system_prompt.take().unwrap_or_default() + suffix);
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait RequestInterceptor: Send + Sync {
+    /// Called before each model call with the request about to be sent
+    async fn before_generate(&self, ctx: &mut RequestContext);
+}
@@ -128,10 +128,14 @@ pub mod agent;
 pub mod conversation;
 pub mod error;
 pub mod events;
+pub mod filter;
+pub mod id;
+pub mod interceptor;
 pub mod model;
 pub mod models;
 pub mod permission;
 pub mod presentation;
+pub mod prompt;
 pub mod provider;
 pub mod tokenizer;
 pub mod tool;
@@ -147,17 +151,24 @@ pub mod session;
 pub mod test_utils;
 
 pub use agent::{
-    Agent, AgentBuilder, AgentError, AgentResponse, ContextConfig, ContextError, ContextLoadResult,
-    ContextSource, PermissionError, TokenUsageStats, ToolCallInfo, ToolInfo,
-    DEFAULT_MAX_CONCURRENT_TOOLS, DEFAULT_PERMISSION_TIMEOUT,
+    Agent, AgentBuilder, AgentConfigSnapshot, AgentError, AgentResponse, AgentTool, AgentToolInput,
+    ContextConfig, ContextError, ContextLoadResult, ContextSource, DocumentInput,
+    EmptyResponsePolicy, IntendedToolCall, OverflowPolicy, PermissionError, PlannedRequest,
+    RefusalPolicy, TokenUsageStats, ToolCallInfo, ToolInfo, ToolUseRepair,
+    DEFAULT_MAX_CONCURRENT_TOOLS, DEFAULT_MAX_DELEGATION_DEPTH, DEFAULT_PERMISSION_TIMEOUT,
+    DEFAULT_TOOL_POLICY_PROMPT, EMIT_PROGRESS_TOOL, EMPTY_RESPONSE_PLACEHOLDER,
+    LIST_CAPABILITIES_TOOL, MAX_DOCUMENT_BYTES,
 };
 pub use conversation::{
     BoxedConversationManager, ContextLimits, ContextUsage, ConversationManager,
-    NoOpConversationManager, SimpleConversationManager, SlidingWindowConversationManager,
-    TokenEstimator,
+    ConversationStrategy, NoOpConversationManager, SimpleConversationManager,
+    SlidingWindowConversationManager, SummarizingConversationManager, TokenEstimator,
 };
 pub use error::{Error, Result};
-pub use events::{AgentEvent, AgentHook, HookId, TokenUsage};
+pub use events::{AgentEvent, AgentHook, AsyncAgentHook, HookId, TokenUsage};
+pub use filter::{ContentFilter, FilterDecision, FilterStage};
+pub use id::{IdGenerator, SequentialIdGenerator, UuidIdGenerator};
+pub use interceptor::{RequestContext, RequestInterceptor};
 
 pub use model::{
     AnthropicModel, BedrockModel, InferenceProfile, Model, ModelRequest, ModelResponse,
@@ -169,15 +180,19 @@ pub use permission::{
     GrantStoreError, MemoryGrantStore, Scope, ToolAuthorizationPolicy, ToolCallAuthorizer,
 };
 pub use presentation::Display;
+pub use prompt::{PromptError, PromptTemplate};
 
 // Providers - core types always available
-pub use provider::{ModelProvider, ProviderError, RetryConfig, RetryInfo, StreamEvent};
+pub use provider::{
+    Feature, GuardedProvider, JitterStrategy, ModelProvider, ProviderConcurrencyGuard,
+    ProviderError, ProviderSettings, RetryConfig, RetryInfo, SamplingParams, StreamEvent,
+};
 
 // Provider implementations - feature-gated
 #[cfg(feature = "anthropic")]
-pub use provider::AnthropicProvider;
+pub use provider::{AnthropicProvider, CachingStrategy, ServiceTier, ToolChoiceMode};
 #[cfg(feature = "bedrock")]
-pub use provider::BedrockProvider;
+pub use provider::{BedrockBeta, BedrockProvider};
 
 // Models — separate `pub use` blocks per vendor so cargo fmt sorts within each
 // block independently rather than merging across vendor boundaries.
@@ -217,10 +232,14 @@ pub use models::{
 };
 
 pub use tokenizer::CharacterTokenizer;
-pub use tool::{box_tool, DocumentFormat, DynTool, ImageFormat, Tool, ToolError, ToolResult};
+pub use tool::{
+    box_tool, schema_of, DocumentFormat, DynTool, ImageFormat, JsonPointerTool, Tool, ToolError,
+    ToolResult, MAX_IMAGE_BYTES,
+};
 pub use types::{
-    ContentBlock, Message, Role, StopReason, ThinkingConfig, ToolDefinition, ToolResultBlock,
-    ToolResultStatus, ToolUseBlock,
+    Citation, CitationLocation, CitationSource, ContentBlock, DocumentBlock, DocumentSource,
+    Message, Role, StopReason, ThinkingConfig, ToolDefinition, ToolResultBlock, ToolResultStatus,
+    ToolUseBlock,
 };
 
 #[cfg(feature = "session")]
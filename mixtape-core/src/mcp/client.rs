@@ -1,10 +1,12 @@
 use super::{McpError, McpServerConfig, McpTransport};
-use rmcp::service::RunningService;
+use rmcp::service::{Peer, RunningService, ServiceError};
 use rmcp::transport::streamable_http_client::{
     StreamableHttpClientTransport, StreamableHttpClientTransportConfig,
 };
 use rmcp::transport::TokioChildProcess;
 use rmcp::{model::CallToolRequestParam, RoleClient, ServiceExt};
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::process::Command;
 use tokio::sync::RwLock;
@@ -16,6 +18,8 @@ pub struct McpClient {
     name: String,
     config: McpServerConfig,
     service: Arc<RwLock<Option<RunningService<RoleClient, ()>>>>,
+    /// Incremented on every new connection
+    generation: AtomicU64,
 }
 
 impl McpClient {
@@ -28,6 +32,7 @@ impl McpClient {
             name: config.name.clone(),
             config,
             service: Arc::new(RwLock::new(None)),
+            generation: AtomicU64::new(0),
         })
     }
 
@@ -39,13 +44,19 @@ impl McpClient {
     /// Connect to the MCP server
     ///
     /// This method is idempotent - calling it multiple times is safe and will not
-    /// create multiple connections.
+    /// create multiple connections. A connection whose transport has closed is
+    /// replaced.
     pub async fn connect(&self) -> Result<(), McpError> {
         let mut service_guard = self.service.write().await;
 
         // Already connected
-        if service_guard.is_some() {
-            return Ok(());
+        if let Some(service) = service_guard.as_ref() {
+            if !service.is_transport_closed() {
+                return Ok(());
+            }
+        }
+        if let Some(closed) = service_guard.take() {
+            let _ = closed.cancel().await;
         }
 
         // Create the service based on transport type
@@ -72,7 +83,12 @@ impl McpClient {
                     McpError::Connection(format!("Failed to connect to server: {}", e))
                 })?
             }
-            McpTransport::Http { url, headers } => {
+            McpTransport::Http {
+                url,
+                headers,
+                keep_alive,
+                idle_timeout,
+            } => {
                 // Create HTTP transport config
                 let config = StreamableHttpClientTransportConfig::with_uri(url.clone());
 
@@ -88,12 +104,16 @@ impl McpClient {
                     header_map.insert(header_name, header_value);
                 }
 
-                let http_client = reqwest::Client::builder()
-                    .default_headers(header_map)
-                    .build()
-                    .map_err(|e| {
-                        McpError::Transport(format!("Failed to create HTTP client: {}", e))
-                    })?;
+                let mut http_client = reqwest::Client::builder().default_headers(header_map);
+                if let Some(interval) = keep_alive {
+                    http_client = http_client.tcp_keepalive(*interval);
+                }
+                if let Some(timeout) = idle_timeout {
+                    http_client = http_client.pool_idle_timeout(*timeout);
+                }
+                let http_client = http_client.build().map_err(|e| {
+                    McpError::Transport(format!("Failed to create HTTP client: {}", e))
+                })?;
 
                 // Create transport with custom client
                 let transport = StreamableHttpClientTransport::with_client(http_client, config);
@@ -105,6 +125,7 @@ impl McpClient {
         };
 
         *service_guard = Some(service);
+        self.generation.fetch_add(1, Ordering::SeqCst);
         Ok(())
     }
 
@@ -113,21 +134,93 @@ impl McpClient {
         self.connect().await
     }
 
-    /// List available tools from the MCP server
-    ///
-    /// Returns a list of tool definitions including name, description, and input schema.
-    pub async fn list_tools(&self) -> Result<Vec<ToolDefinition>, McpError> {
+    /// The current connection's peer and generation, connecting if needed
+    async fn peer(&self) -> Result<(Peer<RoleClient>, u64), McpError> {
         self.ensure_connected().await?;
 
         let service_guard = self.service.read().await;
         let service = service_guard
             .as_ref()
             .ok_or_else(|| McpError::Connection("Not connected".to_string()))?;
+        Ok((
+            service.peer().clone(),
+            self.generation.load(Ordering::SeqCst),
+        ))
+    }
+
+    /// Send a request, reconnecting if an HTTP connection was lost
+    ///
+    /// HTTP servers commonly close idle connections or expire sessions
+    /// between tool calls. A lost connection is discarded so the next
+    /// request starts a fresh one. Requests that are safe to repeat
+    /// (`idempotent`) are also retried once on it; others fail with
+    /// [`McpError::Transport`], since the server may already have received
+    /// and acted on them. Any other error goes through `map_err`.
+    async fn request<T, F, Fut>(
+        &self,
+        idempotent: bool,
+        send: F,
+        map_err: impl Fn(ServiceError) -> McpError,
+    ) -> Result<T, McpError>
+    where
+        F: Fn(Peer<RoleClient>) -> Fut,
+        Fut: Future<Output = Result<T, ServiceError>>,
+    {
+        let (peer, generation) = self.peer().await?;
+        let error = match send(peer).await {
+            Err(e) if self.reconnects() && is_connection_lost(&e) => e,
+            result => return result.map_err(map_err),
+        };
 
-        let result = service
-            .list_tools(Default::default())
-            .await
-            .map_err(|e| McpError::Protocol(format!("Failed to list tools: {}", e)))?;
+        self.drop_connection(generation).await;
+        if !idempotent {
+            return Err(McpError::Transport(format!(
+                "Connection lost ({}); the request was not retried because the server \
+                 may already have received it",
+                error
+            )));
+        }
+
+        let (peer, _) = self.peer().await?;
+        send(peer).await.map_err(|e| {
+            if is_connection_lost(&e) {
+                McpError::Transport(format!(
+                    "Connection lost ({}), and retrying failed: {}",
+                    error, e
+                ))
+            } else {
+                map_err(e)
+            }
+        })
+    }
+
+    /// Whether lost connections are re-established automatically
+    fn reconnects(&self) -> bool {
+        matches!(self.config.transport, McpTransport::Http { .. })
+    }
+
+    /// Discard connection `generation`, unless it was already replaced
+    async fn drop_connection(&self, generation: u64) {
+        let mut service_guard = self.service.write().await;
+        if self.generation.load(Ordering::SeqCst) != generation {
+            return;
+        }
+        if let Some(service) = service_guard.take() {
+            let _ = service.cancel().await;
+        }
+    }
+
+    /// List available tools from the MCP server
+    ///
+    /// Returns a list of tool definitions including name, description, and input schema.
+    pub async fn list_tools(&self) -> Result<Vec<ToolDefinition>, McpError> {
+        let result = self
+            .request(
+                true,
+                |peer| async move { peer.list_tools(Default::default()).await },
+                |e| McpError::Protocol(format!("Failed to list tools: {}", e)),
+            )
+            .await?;
 
         Ok(result
             .tools
@@ -142,6 +235,10 @@ impl McpClient {
 
     /// Call a tool on the MCP server
     ///
+    /// If an HTTP connection is lost during the call, the call fails with
+    /// [`McpError::Transport`] rather than being retried, as the server may
+    /// already have run it. The next request reconnects.
+    ///
     /// # Arguments
     /// * `name` - The name of the tool to call
     /// * `arguments` - JSON object containing the tool arguments
@@ -150,22 +247,23 @@ impl McpClient {
         name: String,
         arguments: serde_json::Value,
     ) -> Result<serde_json::Value, McpError> {
-        self.ensure_connected().await?;
-
-        let service_guard = self.service.read().await;
-        let service = service_guard
-            .as_ref()
-            .ok_or_else(|| McpError::Connection("Not connected".to_string()))?;
-
         let params = CallToolRequestParam {
             name: name.into(),
             arguments: arguments.as_object().cloned(),
         };
 
-        let result = service
-            .call_tool(params)
-            .await
-            .map_err(|e| McpError::ToolExecution(format!("Tool execution failed: {}", e)))?;
+        // Tool calls may have side effects, so a call whose connection is
+        // lost is never sent again
+        let result = self
+            .request(
+                false,
+                |peer| {
+                    let params = params.clone();
+                    async move { peer.call_tool(params).await }
+                },
+                |e| McpError::ToolExecution(format!("Tool execution failed: {}", e)),
+            )
+            .await?;
 
         // Convert the result to JSON
         // The result contains a Vec<Content>, we'll serialize it
@@ -189,6 +287,14 @@ impl McpClient {
     }
 }
 
+/// Whether `error` means the connection failed rather than the request
+fn is_connection_lost(error: &ServiceError) -> bool {
+    matches!(
+        error,
+        ServiceError::TransportSend(_) | ServiceError::TransportClosed
+    )
+}
+
 /// Tool definition from an MCP server
 #[derive(Debug, Clone)]
 pub struct ToolDefinition {
@@ -379,6 +485,8 @@ mod tests {
             McpTransport::Http {
                 url: "https://example.com/mcp".to_string(),
                 headers,
+                keep_alive: None,
+                idle_timeout: None,
             },
         );
 
@@ -405,6 +513,8 @@ mod tests {
             McpTransport::Http {
                 url: "https://example.com/mcp".to_string(),
                 headers,
+                keep_alive: None,
+                idle_timeout: None,
             },
         );
 
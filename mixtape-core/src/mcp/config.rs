@@ -80,6 +80,8 @@ fn entry_to_config(name: String, entry: McpServerEntry) -> Result<McpServerConfi
             McpTransport::Http {
                 url,
                 headers: entry.headers.unwrap_or_default(),
+                keep_alive: None,
+                idle_timeout: None,
             }
         }
         "sse" => {
@@ -90,6 +92,8 @@ fn entry_to_config(name: String, entry: McpServerEntry) -> Result<McpServerConfi
             McpTransport::Http {
                 url,
                 headers: entry.headers.unwrap_or_default(),
+                keep_alive: None,
+                idle_timeout: None,
             }
         }
         other => {
@@ -244,7 +248,7 @@ mod tests {
 
         let config = entry_to_config("test".to_string(), entry).unwrap();
         match &config.transport {
-            McpTransport::Http { url, headers } => {
+            McpTransport::Http { url, headers, .. } => {
                 assert_eq!(url, "https://api.example.com");
                 assert_eq!(headers.get("Authorization").unwrap(), "Bearer token");
             }
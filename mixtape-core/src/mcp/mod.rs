@@ -79,3 +79,20 @@ pub enum McpError {
     #[error("MCP protocol error: {0}")]
     Protocol(String),
 }
+
+/// An MCP server that could not be connected while building an agent
+///
+/// See [`Agent::mcp_failures`](crate::Agent::mcp_failures).
+#[derive(Debug)]
+pub struct McpConnectFailure {
+    /// Server name, or the path of a config file that could not be loaded
+    pub server: String,
+    /// Why the connection failed
+    pub error: McpError,
+}
+
+impl std::fmt::Display for McpConnectFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.server, self.error)
+    }
+}
@@ -1,6 +1,7 @@
 use super::client::{McpClient, ToolDefinition};
 use crate::tool::{Tool, ToolError, ToolResult};
 use std::sync::Arc;
+use tokio::sync::Semaphore;
 
 /// Adapter that wraps an MCP tool as a mixtape Tool
 pub struct McpToolAdapter {
@@ -8,6 +9,8 @@ pub struct McpToolAdapter {
     definition: ToolDefinition,
     /// The original tool name (before namespacing)
     original_name: String,
+    /// Permits shared by all tools of the same server, if it is limited
+    limit: Option<Arc<Semaphore>>,
 }
 
 impl McpToolAdapter {
@@ -18,6 +21,7 @@ impl McpToolAdapter {
             client,
             definition,
             original_name,
+            limit: None,
         }
     }
 
@@ -38,9 +42,19 @@ impl McpToolAdapter {
             client,
             definition: namespaced_def,
             original_name,
+            limit: None,
         }
     }
 
+    /// Hold a permit from `limit` while each call runs
+    ///
+    /// Share one semaphore between all adapters for a server to cap that
+    /// server's concurrent calls.
+    pub fn with_concurrency_limit(mut self, limit: Arc<Semaphore>) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
     /// Get the tool definition
     #[cfg(test)]
     pub fn definition(&self) -> &ToolDefinition {
@@ -61,6 +75,12 @@ impl Tool for McpToolAdapter {
     }
 
     async fn execute(&self, input: Self::Input) -> Result<ToolResult, ToolError> {
+        // The semaphore is never closed, so acquiring only waits
+        let _permit = match &self.limit {
+            Some(limit) => limit.acquire().await.ok(),
+            None => None,
+        };
+
         // Call the MCP server with the original (un-namespaced) tool name
         let result = self
             .client
@@ -1,4 +1,5 @@
 use std::collections::{HashMap, HashSet};
+use std::time::Duration;
 
 /// Configuration for an MCP server connection
 #[derive(Debug, Clone)]
@@ -11,6 +12,8 @@ pub struct McpServerConfig {
     tool_filter: Option<ToolFilter>,
     /// Optional namespace prefix for tool names (e.g., "perplexity_")
     namespace: Option<String>,
+    /// Optional limit on tool calls in flight at once
+    concurrency: Option<usize>,
 }
 
 /// Filter for selecting which tools to expose from an MCP server
@@ -45,6 +48,7 @@ impl McpServerConfig {
             transport: transport.into(),
             tool_filter: None,
             namespace: Some(namespace),
+            concurrency: None,
         }
     }
 
@@ -136,6 +140,31 @@ impl McpServerConfig {
         self
     }
 
+    /// Limit how many of this server's tools can run at once
+    ///
+    /// Calls beyond the limit wait for an earlier call to finish, so a
+    /// single-threaded server isn't flooded when the model requests several
+    /// of its tools together. Calls to other servers are unaffected. A limit
+    /// of 0 is treated as 1.
+    ///
+    /// # Example
+    /// ```
+    /// # use mixtape_core::mcp::{McpServerConfig, McpTransport};
+    /// let config = McpServerConfig::new("browser",
+    ///     McpTransport::stdio("npx").args(["-y", "chrome-devtools-mcp@latest"])
+    /// )
+    /// .concurrency(1);  // One tool call at a time
+    /// ```
+    pub fn concurrency(mut self, max_calls: usize) -> Self {
+        self.concurrency = Some(max_calls.max(1));
+        self
+    }
+
+    /// Get the concurrent call limit if set
+    pub(crate) fn max_concurrent_calls(&self) -> Option<usize> {
+        self.concurrency
+    }
+
     /// Check if a tool should be included based on the filter
     pub(crate) fn should_include_tool(&self, tool_name: &str) -> bool {
         match &self.tool_filter {
@@ -167,11 +196,21 @@ pub enum McpTransport {
     /// Connect to the server's `/mcp` endpoint.
     ///
     /// All custom headers are passed to the server with each request.
+    ///
+    /// If the server drops the connection or forgets the session between
+    /// requests, the client reconnects. Listing tools is retried once on the
+    /// new connection; a tool call fails instead, since the server may
+    /// already have run it. Set an `idle_timeout` shorter than the server's
+    /// to avoid reusing connections it is about to close.
     Http {
         /// Server URL (typically ending in `/mcp`)
         url: String,
         /// HTTP headers (for authentication, API keys, etc.)
         headers: HashMap<String, String>,
+        /// Interval between TCP keep-alive probes on open connections
+        keep_alive: Option<Duration>,
+        /// How long an unused connection stays in the pool before it is closed
+        idle_timeout: Option<Duration>,
     },
 }
 
@@ -279,6 +318,8 @@ impl From<StdioBuilder> for McpTransport {
 pub struct HttpBuilder {
     url: String,
     headers: HashMap<String, String>,
+    keep_alive: Option<Duration>,
+    idle_timeout: Option<Duration>,
 }
 
 impl HttpBuilder {
@@ -287,6 +328,8 @@ impl HttpBuilder {
         Self {
             url: url.into(),
             headers: HashMap::new(),
+            keep_alive: None,
+            idle_timeout: None,
         }
     }
 
@@ -308,6 +351,24 @@ impl HttpBuilder {
         self
     }
 
+    /// Send TCP keep-alive probes on open connections every `interval`
+    ///
+    /// Keeps NAT gateways and load balancers from silently dropping a
+    /// connection that sits unused between tool calls.
+    pub fn keep_alive(mut self, interval: Duration) -> Self {
+        self.keep_alive = Some(interval);
+        self
+    }
+
+    /// Close pooled connections that have been unused for `timeout`
+    ///
+    /// Set this below the server's own idle timeout so the client opens a
+    /// fresh connection instead of reusing one the server is about to close.
+    pub fn idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
+
     /// Build the transport (explicit conversion)
     pub fn build(self) -> McpTransport {
         self.into()
@@ -319,6 +380,8 @@ impl From<HttpBuilder> for McpTransport {
         McpTransport::Http {
             url: builder.url,
             headers: builder.headers,
+            keep_alive: builder.keep_alive,
+            idle_timeout: builder.idle_timeout,
         }
     }
 }
@@ -427,9 +490,17 @@ mod tests {
     fn test_http_builder_basic() {
         let transport = McpTransport::http("https://example.com/mcp").build();
 
-        if let McpTransport::Http { url, headers } = transport {
+        if let McpTransport::Http {
+            url,
+            headers,
+            keep_alive,
+            idle_timeout,
+        } = transport
+        {
             assert_eq!(url, "https://example.com/mcp");
             assert!(headers.is_empty());
+            assert_eq!(keep_alive, None);
+            assert_eq!(idle_timeout, None);
         } else {
             panic!("Expected Http transport");
         }
@@ -473,7 +544,7 @@ mod tests {
             .header("X-Request-Id", "123")
             .build();
 
-        if let McpTransport::Http { url, headers } = transport {
+        if let McpTransport::Http { url, headers, .. } = transport {
             assert_eq!(url, "https://gitmcp.io/owner/repo");
             assert_eq!(headers.len(), 3);
         } else {
@@ -481,6 +552,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_http_builder_with_keep_alive_and_idle_timeout() {
+        let transport = McpTransport::http("https://api.example.com")
+            .keep_alive(Duration::from_secs(30))
+            .idle_timeout(Duration::from_secs(60))
+            .build();
+
+        if let McpTransport::Http {
+            keep_alive,
+            idle_timeout,
+            ..
+        } = transport
+        {
+            assert_eq!(keep_alive, Some(Duration::from_secs(30)));
+            assert_eq!(idle_timeout, Some(Duration::from_secs(60)));
+        } else {
+            panic!("Expected Http transport");
+        }
+    }
+
     #[test]
     fn test_http_builder_into_transport() {
         let builder = McpTransport::http("https://test.com");
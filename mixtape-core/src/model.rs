@@ -28,8 +28,18 @@ pub struct ModelResponse {
     pub message: Message,
     /// Why the model stopped generating
     pub stop_reason: StopReason,
+    /// The stop sequence that ended generation, when the provider reports it
+    pub stop_sequence: Option<String>,
     /// Token usage statistics (if provided by the model)
     pub usage: Option<TokenUsage>,
+    /// Service tier that handled the request, as reported by the provider
+    pub service_tier: Option<String>,
+    /// Model that served the request
+    ///
+    /// The version the provider reports, which can be more specific than
+    /// the alias or inference profile requested. Built-in providers fall
+    /// back to the configured model ID when the API doesn't report one.
+    pub model: Option<String>,
 }
 
 /// Core model metadata trait
@@ -47,6 +57,14 @@ pub trait Model: Send + Sync {
     /// Maximum output tokens the model can generate
     fn max_output_tokens(&self) -> usize;
 
+    /// Context window available behind the 1M-context beta, if the model has one
+    ///
+    /// Providers report [`Feature::Context1M`](crate::provider::Feature::Context1M)
+    /// support from this.
+    fn extended_context_tokens(&self) -> Option<usize> {
+        None
+    }
+
     /// Estimate token count for text
     ///
     /// Models should implement this to provide accurate token estimation.
@@ -108,6 +126,9 @@ pub trait Model: Send + Sync {
                 // Estimate tokens for thinking content
                 self.estimate_token_count(thinking) + self.estimate_token_count(signature) + 10
             }
+            ContentBlock::Document(document) => document.estimated_tokens(),
+            // Never sent to the model
+            ContentBlock::Citations { .. } => 0,
         }
     }
 }
@@ -121,6 +142,9 @@ pub trait Model: Send + Sync {
 /// Some newer models (Claude 4/4.5, Nova 2 Lite) require inference profiles
 /// and don't support direct single-region invocation.
 ///
+/// Custom application inference profiles are identified by ARN instead; see
+/// `BedrockProvider::with_inference_profile_arn`.
+///
 /// See: <https://docs.aws.amazon.com/bedrock/latest/userguide/cross-region-inference.html>
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum InferenceProfile {
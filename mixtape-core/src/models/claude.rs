@@ -69,6 +69,7 @@ define_model!(
         bedrock_id: "anthropic.claude-sonnet-4-20250514-v1:0",
         context_tokens: 200_000,
         output_tokens: 64_000,
+        extended_context_tokens: 1_000_000,
         anthropic_id: "claude-sonnet-4-20250514",
         default_inference_profile: InferenceProfile::Global
     }
@@ -81,6 +82,7 @@ define_model!(
         bedrock_id: "anthropic.claude-sonnet-4-6",
         context_tokens: 200_000,
         output_tokens: 64_000,
+        extended_context_tokens: 1_000_000,
         anthropic_id: "claude-sonnet-4-6",
         default_inference_profile: InferenceProfile::Global
     }
@@ -93,6 +95,7 @@ define_model!(
         bedrock_id: "anthropic.claude-sonnet-4-5-20250929-v1:0",
         context_tokens: 200_000,
         output_tokens: 64_000,
+        extended_context_tokens: 1_000_000,
         anthropic_id: "claude-sonnet-4-5-20250929",
         default_inference_profile: InferenceProfile::Global
     }
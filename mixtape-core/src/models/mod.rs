@@ -50,6 +50,7 @@ pub use qwen::*;
 /// - `AnthropicModel` trait (if `anthropic_id` is provided)
 ///
 /// Optional fields:
+/// - `extended_context_tokens` - Context window behind the 1M-context beta
 /// - `anthropic_id` - Anthropic API model ID (enables AnthropicModel trait)
 /// - `default_inference_profile` - Default inference profile for Bedrock (e.g., Global)
 macro_rules! define_model {
@@ -60,6 +61,7 @@ macro_rules! define_model {
             bedrock_id: $bedrock_id:expr,
             context_tokens: $context_tokens:expr,
             output_tokens: $output_tokens:expr
+            $(, extended_context_tokens: $extended_context_tokens:expr)?
             $(, anthropic_id: $anthropic_id:expr)?
             $(, default_inference_profile: $profile:expr)?
         }
@@ -81,6 +83,8 @@ macro_rules! define_model {
                 $output_tokens
             }
 
+            $crate::models::define_model!(@extended_context $($extended_context_tokens)?);
+
             fn estimate_token_count(&self, text: &str) -> usize {
                 // Default heuristic: ~4 characters per token
                 text.len().div_ceil(4)
@@ -104,6 +108,16 @@ macro_rules! define_model {
         )?
     };
 
+    // Helper: generate extended_context_tokens method if a window is specified
+    (@extended_context $tokens:expr) => {
+        fn extended_context_tokens(&self) -> Option<usize> {
+            Some($tokens)
+        }
+    };
+
+    // Helper: no-op if no extended window specified (uses trait default)
+    (@extended_context) => {};
+
     // Helper: generate default_inference_profile method if profile is specified
     (@inference_profile $profile:expr) => {
         fn default_inference_profile(&self) -> $crate::model::InferenceProfile {
@@ -139,6 +153,15 @@ mod tests {
         assert!(model.anthropic_id().contains("claude-sonnet-4-5"));
     }
 
+    #[test]
+    fn test_extended_context_only_where_declared() {
+        assert_eq!(ClaudeSonnet4.extended_context_tokens(), Some(1_000_000));
+        assert_eq!(ClaudeSonnet4_5.extended_context_tokens(), Some(1_000_000));
+        assert_eq!(ClaudeSonnet4_6.extended_context_tokens(), Some(1_000_000));
+        assert_eq!(ClaudeHaiku4_5.extended_context_tokens(), None);
+        assert_eq!(NovaMicro.extended_context_tokens(), None);
+    }
+
     #[test]
     fn test_nova_only_implements_bedrock() {
         let model = NovaMicro;
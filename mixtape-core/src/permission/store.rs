@@ -3,7 +3,7 @@
 use super::grant::Grant;
 use async_trait::async_trait;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::RwLock;
 
 /// Errors that can occur in grant store operations.
@@ -132,6 +132,10 @@ impl GrantStore for MemoryGrantStore {
 ///
 /// Grants are persisted to a JSON file. The file is created automatically
 /// when the first grant is stored.
+///
+/// Use [`FileGrantStore::for_directory`] to keep a separate set of grants
+/// per project, so trusting a tool in one repository doesn't approve it
+/// in another.
 pub struct FileGrantStore {
     path: PathBuf,
     cache: RwLock<Option<HashMap<String, Vec<Grant>>>>,
@@ -149,6 +153,33 @@ impl FileGrantStore {
         }
     }
 
+    /// Create a store for grants made while working in `dir`.
+    ///
+    /// Grants are kept in `~/.mixtape/grants/`, in a file named after a
+    /// hash of the directory's canonical path. Each directory gets its own
+    /// grants; subdirectories do not share their parent's.
+    pub fn for_directory(dir: impl AsRef<Path>) -> Self {
+        let root = dirs::home_dir()
+            .unwrap_or_default()
+            .join(".mixtape")
+            .join("grants");
+        Self::for_directory_in(root, dir)
+    }
+
+    /// Like [`for_directory`](Self::for_directory), with the grant files
+    /// kept in `root` instead of `~/.mixtape/grants/`.
+    pub fn for_directory_in(root: impl AsRef<Path>, dir: impl AsRef<Path>) -> Self {
+        Self::new(
+            root.as_ref()
+                .join(format!("{}.json", directory_key(dir.as_ref()))),
+        )
+    }
+
+    /// Path of the JSON file grants are stored in.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
     /// Load grants from file into cache if not already loaded.
     fn ensure_loaded(&self) -> Result<(), GrantStoreError> {
         let mut cache = self.cache.write().expect("RwLock poisoned");
@@ -188,6 +219,18 @@ impl FileGrantStore {
     }
 }
 
+/// Stable file name component for a project directory.
+///
+/// The path is canonicalized when possible so `.` and the absolute path of
+/// the same directory share grants.
+fn directory_key(dir: &Path) -> String {
+    use sha2::{Digest, Sha256};
+
+    let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    let hash = Sha256::digest(dir.to_string_lossy().as_bytes());
+    format!("{:x}", hash)[..16].to_string()
+}
+
 #[async_trait]
 impl GrantStore for FileGrantStore {
     async fn save(&self, grant: Grant) -> Result<(), GrantStoreError> {
@@ -382,6 +425,29 @@ mod tests {
         assert!(path.exists());
     }
 
+    #[tokio::test]
+    async fn test_file_store_for_directory_isolates_projects() {
+        let root = tempfile::tempdir().unwrap();
+        let repo_a = tempfile::tempdir().unwrap();
+        let repo_b = tempfile::tempdir().unwrap();
+
+        let store_a = FileGrantStore::for_directory_in(root.path(), repo_a.path());
+        store_a.save(Grant::tool("write_file")).await.unwrap();
+        assert!(store_a.path().starts_with(root.path()));
+
+        // Another project sees none of repo A's grants
+        let store_b = FileGrantStore::for_directory_in(root.path(), repo_b.path());
+        assert_ne!(store_a.path(), store_b.path());
+        assert!(store_b.load("write_file").await.unwrap().is_empty());
+        assert!(store_b.load_all().await.unwrap().is_empty());
+
+        // Repo A gets its grants back, however its path is spelled
+        let spelled_differently = repo_a.path().join(".");
+        let store_a2 = FileGrantStore::for_directory_in(root.path(), spelled_differently);
+        assert_eq!(store_a2.path(), store_a.path());
+        assert_eq!(store_a2.load("write_file").await.unwrap().len(), 1);
+    }
+
     #[tokio::test]
     async fn test_file_store_handles_empty_file() {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -0,0 +1,241 @@
+//! Prompt templates with `{{variable}}` substitution
+//!
+//! Templates are plain text: nothing is escaped, and substituted values are
+//! inserted verbatim without being scanned for further placeholders. Use the
+//! rendered string anywhere a prompt is accepted, such as
+//! [`AgentBuilder::with_system_prompt`](crate::AgentBuilder::with_system_prompt)
+//! or [`Agent::run`](crate::Agent::run).
+//!
+//! ```
+//! use mixtape_core::PromptTemplate;
+//!
+//! let template = PromptTemplate::new("Hello {{name}}, about {{topic}}");
+//! let prompt = template.render(&[("name", "Ada"), ("topic", "Rust")]).unwrap();
+//! assert_eq!(prompt, "Hello Ada, about Rust");
+//! ```
+
+use thiserror::Error;
+
+/// Errors from rendering a [`PromptTemplate`]
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum PromptError {
+    /// The template uses a variable that was not given a value
+    #[error("No value for template variable: {0}")]
+    MissingVariable(String),
+
+    /// A value was given for a variable the template does not use
+    #[error("Unknown template variable: {0}")]
+    UnknownVariable(String),
+
+    /// A `{{...}}` placeholder is empty or its name is not a valid identifier
+    #[error("Invalid template placeholder: {{{{{0}}}}}")]
+    InvalidPlaceholder(String),
+
+    /// A `{{` is never closed
+    #[error("Unclosed template placeholder at byte {0}")]
+    Unclosed(usize),
+}
+
+/// A piece of a parsed template
+enum Segment<'a> {
+    Text(&'a str),
+    Variable(&'a str),
+}
+
+/// Text with `{{variable}}` placeholders
+///
+/// Variable names are letters, digits, and underscores; whitespace inside
+/// the braces is ignored, so `{{ name }}` and `{{name}}` are the same.
+/// Rendering fails if any placeholder is left without a value, and by
+/// default also if a value is given for a variable the template does not
+/// use. [`allow_unknown`](Self::allow_unknown) relaxes the latter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptTemplate {
+    source: String,
+    allow_unknown: bool,
+}
+
+impl PromptTemplate {
+    /// Create a template from its source text
+    pub fn new(source: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            allow_unknown: false,
+        }
+    }
+
+    /// Ignore values for variables the template does not use
+    ///
+    /// Useful when one set of values is shared by several templates.
+    pub fn allow_unknown(mut self) -> Self {
+        self.allow_unknown = true;
+        self
+    }
+
+    /// The template's source text
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Names of the variables the template uses, in order of first use
+    pub fn variables(&self) -> Result<Vec<&str>, PromptError> {
+        let mut names = Vec::new();
+        for segment in self.parse()? {
+            if let Segment::Variable(name) = segment {
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+        Ok(names)
+    }
+
+    /// Substitute `values` into the template
+    ///
+    /// When a variable appears more than once in `values`, the last value
+    /// wins.
+    pub fn render(&self, values: &[(&str, &str)]) -> Result<String, PromptError> {
+        let segments = self.parse()?;
+
+        if !self.allow_unknown {
+            for (name, _) in values {
+                let used = segments
+                    .iter()
+                    .any(|segment| matches!(segment, Segment::Variable(v) if v == name));
+                if !used {
+                    return Err(PromptError::UnknownVariable(name.to_string()));
+                }
+            }
+        }
+
+        let mut rendered = String::with_capacity(self.source.len());
+        for segment in segments {
+            match segment {
+                Segment::Text(text) => rendered.push_str(text),
+                Segment::Variable(name) => {
+                    let (_, value) = values
+                        .iter()
+                        .rev()
+                        .find(|(n, _)| *n == name)
+                        .ok_or_else(|| PromptError::MissingVariable(name.to_string()))?;
+                    rendered.push_str(value);
+                }
+            }
+        }
+        Ok(rendered)
+    }
+
+    /// Split the source into text and placeholders
+    fn parse(&self) -> Result<Vec<Segment<'_>>, PromptError> {
+        let mut segments = Vec::new();
+        let mut rest = self.source.as_str();
+        let mut offset = 0;
+
+        while let Some(open) = rest.find("{{") {
+            if open > 0 {
+                segments.push(Segment::Text(&rest[..open]));
+            }
+            let inner_start = open + 2;
+            let close = rest[inner_start..]
+                .find("}}")
+                .ok_or(PromptError::Unclosed(offset + open))?;
+            let inner = &rest[inner_start..inner_start + close];
+            let name = inner.trim();
+            if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                return Err(PromptError::InvalidPlaceholder(inner.to_string()));
+            }
+            segments.push(Segment::Variable(name));
+
+            let consumed = inner_start + close + 2;
+            rest = &rest[consumed..];
+            offset += consumed;
+        }
+        if !rest.is_empty() {
+            segments.push(Segment::Text(rest));
+        }
+        Ok(segments)
+    }
+}
+
+impl From<&str> for PromptTemplate {
+    fn from(source: &str) -> Self {
+        Self::new(source)
+    }
+}
+
+impl From<String> for PromptTemplate {
+    fn from(source: String) -> Self {
+        Self::new(source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_substitutes_every_occurrence() {
+        let template = PromptTemplate::new("{{ name }} asks about {{topic}}. Thanks, {{name}}!");
+        assert_eq!(
+            template
+                .render(&[("name", "Ada"), ("topic", "Rust")])
+                .unwrap(),
+            "Ada asks about Rust. Thanks, Ada!"
+        );
+        assert_eq!(template.variables().unwrap(), vec!["name", "topic"]);
+    }
+
+    #[test]
+    fn test_values_are_not_rescanned() {
+        let template = PromptTemplate::new("Echo: {{input}}");
+        assert_eq!(
+            template.render(&[("input", "{{input}}")]).unwrap(),
+            "Echo: {{input}}"
+        );
+    }
+
+    #[test]
+    fn test_missing_variable_is_an_error() {
+        let template = PromptTemplate::new("Hello {{name}}, about {{topic}}");
+        assert_eq!(
+            template.render(&[("name", "Ada")]),
+            Err(PromptError::MissingVariable("topic".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_unknown_variable_strictness() {
+        let template = PromptTemplate::new("Hello {{name}}");
+        let values = [("name", "Ada"), ("mood", "cheerful")];
+        assert_eq!(
+            template.render(&values),
+            Err(PromptError::UnknownVariable("mood".to_string()))
+        );
+        assert_eq!(
+            template.allow_unknown().render(&values).unwrap(),
+            "Hello Ada"
+        );
+    }
+
+    #[test]
+    fn test_malformed_placeholders() {
+        assert_eq!(
+            PromptTemplate::new("Hello {{}}").render(&[]),
+            Err(PromptError::InvalidPlaceholder(String::new()))
+        );
+        assert_eq!(
+            PromptTemplate::new("Hello {{first name}}").render(&[]),
+            Err(PromptError::InvalidPlaceholder("first name".to_string()))
+        );
+        assert_eq!(
+            PromptTemplate::new("ab {{name").render(&[("name", "Ada")]),
+            Err(PromptError::Unclosed(3))
+        );
+    }
+
+    #[test]
+    fn test_text_without_placeholders() {
+        let template = PromptTemplate::new("Plain text with } and { braces");
+        assert_eq!(template.render(&[]).unwrap(), template.source());
+    }
+}
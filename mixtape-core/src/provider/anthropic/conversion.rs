@@ -3,13 +3,15 @@
 use super::ProviderError;
 use crate::tool::{DocumentFormat, ImageFormat, ToolResult};
 use crate::types::{
-    ContentBlock, Message, Role, StopReason, ToolDefinition, ToolResultStatus, ToolUseBlock,
+    Citation, CitationLocation, CitationSource, ContentBlock, DocumentBlock,
+    DocumentSource as MixtapeDocumentSource, Message, Role, StopReason, ToolDefinition,
+    ToolResultStatus, ToolUseBlock,
 };
 use base64::Engine;
 use mixtape_anthropic_sdk::{
-    ContentBlock as AnthropicContentBlock, ContentBlockParam, DocumentSource, ImageSource,
-    Message as AnthropicMessage, MessageContent, MessageParam, Role as AnthropicRole,
-    StopReason as AnthropicStopReason, Tool as AnthropicTool, ToolInputSchema,
+    CitationsConfig, ContentBlock as AnthropicContentBlock, ContentBlockParam, DocumentSource,
+    ImageSource, Message as AnthropicMessage, MessageContent, MessageParam, Role as AnthropicRole,
+    StopReason as AnthropicStopReason, TextCitation, Tool as AnthropicTool, ToolInputSchema,
     ToolResultContent as AnthropicToolResultContent, ToolResultContentBlock,
 };
 
@@ -24,6 +26,8 @@ pub fn to_anthropic_message(msg: &Message) -> Result<MessageParam, ProviderError
     let content_blocks: Vec<ContentBlockParam> = msg
         .content
         .iter()
+        // Citations annotate the model's own text and are not sent back
+        .filter(|block| !matches!(block, ContentBlock::Citations { .. }))
         .map(to_anthropic_content_block)
         .collect::<Result<Vec<_>, _>>()?;
 
@@ -75,13 +79,27 @@ fn to_anthropic_content_block(block: &ContentBlock) -> Result<ContentBlockParam,
                 }
             };
             let is_error = matches!(result.status, ToolResultStatus::Error);
+            // The API rejects empty text blocks; an empty result has no content
+            let content = match &content_block {
+                ToolResultContentBlock::Text { text } if text.is_empty() => None,
+                _ => Some(AnthropicToolResultContent::Blocks(vec![content_block])),
+            };
             Ok(ContentBlockParam::ToolResult {
                 tool_use_id: result.tool_use_id.clone(),
-                content: Some(AnthropicToolResultContent::Blocks(vec![content_block])),
+                content,
                 is_error: Some(is_error),
                 cache_control: None,
             })
         }
+        ContentBlock::Thinking {
+            thinking,
+            signature,
+        } if thinking.is_empty() => {
+            // Redacted thinking is stored with its encrypted data as the signature
+            Ok(ContentBlockParam::RedactedThinking {
+                data: signature.clone(),
+            })
+        }
         ContentBlock::Thinking {
             thinking,
             signature,
@@ -92,6 +110,86 @@ fn to_anthropic_content_block(block: &ContentBlock) -> Result<ContentBlockParam,
                 signature: signature.clone(),
             })
         }
+        ContentBlock::Document(document) => Ok(ContentBlockParam::Document {
+            source: to_anthropic_document_source(document)?,
+            cache_control: None,
+            title: document.name.clone(),
+            context: None,
+            citations: None,
+        }),
+        ContentBlock::Citations { .. } => Err(ProviderError::Configuration(
+            "citations cannot be sent to the model".to_string(),
+        )),
+    }
+}
+
+/// Convert a user document to an Anthropic document source
+///
+/// The Messages API accepts PDFs as base64 data and plain text as text; other
+/// formats are rejected rather than sent and refused by the API.
+fn to_anthropic_document_source(document: &DocumentBlock) -> Result<DocumentSource, ProviderError> {
+    match (&document.source, document.format) {
+        (MixtapeDocumentSource::File(file_id), _) => Ok(DocumentSource::File {
+            file_id: file_id.clone(),
+        }),
+        (MixtapeDocumentSource::Bytes(data), DocumentFormat::Pdf) => Ok(DocumentSource::Base64 {
+            media_type: document_format_to_media_type(DocumentFormat::Pdf),
+            data: base64::engine::general_purpose::STANDARD.encode(data),
+        }),
+        (MixtapeDocumentSource::Bytes(data), DocumentFormat::Txt) => {
+            let text = String::from_utf8(data.clone()).map_err(|_| {
+                ProviderError::Configuration("Text document is not valid UTF-8".to_string())
+            })?;
+            Ok(DocumentSource::Text {
+                data: text,
+                media_type: document_format_to_media_type(DocumentFormat::Txt),
+            })
+        }
+        (MixtapeDocumentSource::Bytes(_), format) => Err(ProviderError::Configuration(format!(
+            "Anthropic does not support {:?} documents; only PDF and plain text are accepted",
+            format
+        ))),
+    }
+}
+
+/// Whether any message references a file uploaded through the Files API
+pub fn references_files(messages: &[MessageParam]) -> bool {
+    messages.iter().any(|msg| match &msg.content {
+        MessageContent::Blocks(blocks) => blocks.iter().any(|block| {
+            matches!(
+                block,
+                ContentBlockParam::Document {
+                    source: DocumentSource::File { .. },
+                    ..
+                }
+            )
+        }),
+        MessageContent::Text(_) => false,
+    })
+}
+
+/// Whether the last message returns tool results to the model
+pub fn answers_tool_use(messages: &[MessageParam]) -> bool {
+    messages.last().is_some_and(|msg| match &msg.content {
+        MessageContent::Blocks(blocks) => blocks
+            .iter()
+            .any(|block| matches!(block, ContentBlockParam::ToolResult { .. })),
+        MessageContent::Text(_) => false,
+    })
+}
+
+/// Turn on citations for every document in the messages
+pub fn enable_citations(messages: &mut [MessageParam]) {
+    for msg in messages {
+        if let MessageContent::Blocks(blocks) = &mut msg.content {
+            for block in blocks {
+                if let ContentBlockParam::Document { citations, .. } = block {
+                    *citations = Some(CitationsConfig {
+                        enabled: Some(true),
+                    });
+                }
+            }
+        }
     }
 }
 
@@ -148,13 +246,7 @@ fn convert_json_to_tool_schema(
 
 /// Convert ImageFormat to MIME type string
 fn image_format_to_media_type(format: ImageFormat) -> String {
-    match format {
-        ImageFormat::Png => "image/png",
-        ImageFormat::Jpeg => "image/jpeg",
-        ImageFormat::Gif => "image/gif",
-        ImageFormat::Webp => "image/webp",
-    }
-    .to_string()
+    format.media_type().to_string()
 }
 
 /// Convert DocumentFormat to MIME type string
@@ -183,18 +275,98 @@ pub fn from_anthropic_message(msg: &AnthropicMessage) -> Message {
         AnthropicRole::Assistant => Role::Assistant,
     };
 
-    let content: Vec<ContentBlock> = msg
-        .content
-        .iter()
-        .filter_map(from_anthropic_content_block)
-        .collect();
+    let mut content: Vec<ContentBlock> = Vec::new();
+    for block in &msg.content {
+        content.extend(from_anthropic_content_block(block));
+        // Citations follow the text they belong to
+        if let AnthropicContentBlock::Text {
+            citations: Some(citations),
+            ..
+        } = block
+        {
+            let citations: Vec<Citation> = citations
+                .iter()
+                .filter_map(from_anthropic_citation)
+                .collect();
+            if !citations.is_empty() {
+                content.push(ContentBlock::Citations { citations });
+            }
+        }
+    }
 
     Message { role, content }
 }
 
+/// Convert an Anthropic citation, skipping citation types mixtape does not know
+pub fn from_anthropic_citation(citation: &TextCitation) -> Option<Citation> {
+    let document = |index: &u32, title: &Option<String>| CitationSource::Document {
+        index: *index as usize,
+        title: title.clone(),
+    };
+    let citation = match citation {
+        TextCitation::CharLocation {
+            cited_text,
+            document_index,
+            document_title,
+            start_char_index,
+            end_char_index,
+        } => Citation {
+            source: document(document_index, document_title),
+            cited_text: cited_text.clone(),
+            location: CitationLocation::Chars {
+                start: *start_char_index as usize,
+                end: *end_char_index as usize,
+            },
+        },
+        TextCitation::PageLocation {
+            cited_text,
+            document_index,
+            document_title,
+            start_page_number,
+            end_page_number,
+        } => Citation {
+            source: document(document_index, document_title),
+            cited_text: cited_text.clone(),
+            location: CitationLocation::Pages {
+                start: *start_page_number as usize,
+                end: *end_page_number as usize,
+            },
+        },
+        TextCitation::ContentBlockLocation {
+            cited_text,
+            document_index,
+            document_title,
+            start_block_index,
+            end_block_index,
+        } => Citation {
+            source: document(document_index, document_title),
+            cited_text: cited_text.clone(),
+            location: CitationLocation::ContentBlocks {
+                start: *start_block_index as usize,
+                end: *end_block_index as usize,
+            },
+        },
+        TextCitation::WebSearchResultLocation {
+            cited_text,
+            url,
+            title,
+            ..
+        } => Citation {
+            source: CitationSource::WebSearchResult {
+                url: url.clone(),
+                title: title.clone(),
+            },
+            cited_text: cited_text.clone(),
+            location: CitationLocation::Whole,
+        },
+        TextCitation::Unknown => return None,
+    };
+    Some(citation)
+}
+
 fn from_anthropic_content_block(block: &AnthropicContentBlock) -> Option<ContentBlock> {
     match block {
-        AnthropicContentBlock::Text { text } => Some(ContentBlock::Text(text.clone())),
+        AnthropicContentBlock::Text { text, .. } => Some(ContentBlock::Text(text.clone())),
         AnthropicContentBlock::ToolUse { id, name, input } => {
             Some(ContentBlock::ToolUse(ToolUseBlock {
                 id: id.clone(),
@@ -227,7 +399,7 @@ pub fn from_anthropic_stop_reason(reason: &AnthropicStopReason) -> StopReason {
         AnthropicStopReason::MaxTokens => StopReason::MaxTokens,
         AnthropicStopReason::StopSequence => StopReason::StopSequence,
         AnthropicStopReason::PauseTurn => StopReason::PauseTurn,
-        AnthropicStopReason::Refusal => StopReason::ContentFiltered,
+        AnthropicStopReason::Refusal => StopReason::Refusal,
     }
 }
 
@@ -365,6 +537,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_empty_tool_result_has_no_content() {
+        let msg = Message {
+            role: Role::User,
+            content: vec![ContentBlock::ToolResult(ToolResultBlock {
+                tool_use_id: "tool_empty".to_string(),
+                content: ToolResult::Text(String::new()),
+                status: ToolResultStatus::Success,
+            })],
+        };
+
+        let anthropic_msg = to_anthropic_message(&msg).unwrap();
+        match &anthropic_msg.content {
+            MessageContent::Blocks(blocks) => assert!(matches!(
+                &blocks[0],
+                ContentBlockParam::ToolResult { content: None, .. }
+            )),
+            _ => panic!("Expected blocks content"),
+        }
+    }
+
     #[test]
     fn test_tool_result_json_conversion() {
         let result = ToolResultBlock {
@@ -465,7 +658,7 @@ mod tests {
     fn test_stop_reason_refusal() {
         assert_eq!(
             from_anthropic_stop_reason(&AnthropicStopReason::Refusal),
-            StopReason::ContentFiltered
+            StopReason::Refusal
         );
     }
 
@@ -611,6 +804,75 @@ mod tests {
         );
     }
 
+    // ===== User Document Conversion Tests =====
+
+    fn document_message(format: DocumentFormat, source: MixtapeDocumentSource) -> Message {
+        let document = DocumentBlock {
+            format,
+            source,
+            name: Some("report.pdf".to_string()),
+        };
+        Message::user_with_document(document, "Summarize this")
+    }
+
+    #[test]
+    fn test_pdf_document_to_anthropic() {
+        let msg = document_message(
+            DocumentFormat::Pdf,
+            MixtapeDocumentSource::Bytes(b"%PDF-1.4".to_vec()),
+        );
+        let anthropic_msg = to_anthropic_message(&msg).unwrap();
+
+        let json = serde_json::to_value(&anthropic_msg).unwrap();
+        assert_eq!(
+            json["content"][0],
+            serde_json::json!({
+                "type": "document",
+                "source": {
+                    "type": "base64",
+                    "media_type": "application/pdf",
+                    "data": "JVBERi0xLjQ="
+                },
+                "title": "report.pdf"
+            })
+        );
+        assert_eq!(json["content"][1]["text"], "Summarize this");
+        assert!(!references_files(&[anthropic_msg]));
+    }
+
+    #[test]
+    fn test_text_and_file_documents_to_anthropic() {
+        let msg = document_message(
+            DocumentFormat::Txt,
+            MixtapeDocumentSource::Bytes(b"plain notes".to_vec()),
+        );
+        let json = serde_json::to_value(to_anthropic_message(&msg).unwrap()).unwrap();
+        assert_eq!(json["content"][0]["source"]["type"], "text");
+        assert_eq!(json["content"][0]["source"]["data"], "plain notes");
+
+        let msg = document_message(
+            DocumentFormat::Pdf,
+            MixtapeDocumentSource::File("file_abc".to_string()),
+        );
+        let anthropic_msg = to_anthropic_message(&msg).unwrap();
+        let json = serde_json::to_value(&anthropic_msg).unwrap();
+        assert_eq!(
+            json["content"][0]["source"],
+            serde_json::json!({"type": "file", "file_id": "file_abc"})
+        );
+        assert!(references_files(&[anthropic_msg]));
+    }
+
+    #[test]
+    fn test_unsupported_document_format_rejected() {
+        let msg = document_message(
+            DocumentFormat::Docx,
+            MixtapeDocumentSource::Bytes(vec![0x50, 0x4b]),
+        );
+        let err = to_anthropic_message(&msg).unwrap_err();
+        assert!(err.to_string().contains("Docx"));
+    }
+
     // ===== Thinking Block Conversion Tests =====
 
     #[test]
@@ -653,6 +915,7 @@ mod tests {
             role: AnthropicRole::Assistant,
             content: vec![AnthropicContentBlock::Text {
                 text: "Hello there!".to_string(),
+                citations: None,
             }],
             model: "claude-sonnet-4-20250514".to_string(),
             stop_reason: Some(AnthropicStopReason::EndTurn),
@@ -719,6 +982,7 @@ mod tests {
                 },
                 AnthropicContentBlock::Text {
                     text: "Here's my answer.".to_string(),
+                    citations: None,
                 },
             ],
             model: "claude-sonnet-4-20250514".to_string(),
@@ -779,6 +1043,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_redacted_thinking_round_trip() {
+        let block = ContentBlock::Thinking {
+            thinking: String::new(),
+            signature: "encrypted_data".to_string(),
+        };
+
+        match to_anthropic_content_block(&block).unwrap() {
+            ContentBlockParam::RedactedThinking { data } => assert_eq!(data, "encrypted_data"),
+            other => panic!("Expected RedactedThinking block, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_from_anthropic_message_user_role() {
         use mixtape_anthropic_sdk::Message as AnthropicMessage;
@@ -790,6 +1067,7 @@ mod tests {
             role: AnthropicRole::User,
             content: vec![AnthropicContentBlock::Text {
                 text: "User message".to_string(),
+                citations: None,
             }],
             model: "claude-sonnet-4-20250514".to_string(),
             stop_reason: None,
@@ -2,27 +2,34 @@
 
 mod conversion;
 
-use super::retry::{retry_with_backoff, RetryCallback, RetryConfig, RetryInfo};
-use super::{ModelProvider, ProviderError, StreamEvent};
+use super::retry::{retry_with_backoff, JitterStrategy, RetryCallback, RetryConfig, RetryInfo};
+use super::tool_input::tool_use_events;
+use super::{
+    check_max_tokens, check_top_k, check_unit_range, clamp_max_tokens, clamp_unit_range,
+    default_max_tokens, Feature, ModelProvider, ProviderError, ProviderSettings, SamplingParams,
+    StreamEvent, DEFAULT_MAX_TOKENS_CAP,
+};
 use crate::events::TokenUsage;
 use crate::model::{AnthropicModel, ModelResponse};
-use crate::types::{Message, StopReason, ThinkingConfig, ToolDefinition, ToolUseBlock};
+use crate::tool::DocumentFormat;
+use crate::types::{Citation, Message, StopReason, ThinkingConfig, ToolDefinition};
 use conversion::{
-    from_anthropic_message, from_anthropic_stop_reason, to_anthropic_message, to_anthropic_tool,
+    answers_tool_use, enable_citations, from_anthropic_citation, from_anthropic_message,
+    from_anthropic_stop_reason, references_files, to_anthropic_message, to_anthropic_tool,
 };
 use futures::stream::BoxStream;
 use futures::StreamExt;
 use mixtape_anthropic_sdk::{
-    Anthropic, AnthropicError, BetaFeature, ContentBlock as AnthropicContentBlock,
-    ContentBlockDelta, MessageCreateParams, MessageStreamEvent, Tool as AnthropicTool,
+    Anthropic, AnthropicError, BetaFeature, CacheControl, ContentBlock as AnthropicContentBlock,
+    ContentBlockDelta, CountTokensParams, MessageCreateParams, MessageStreamEvent, Metadata,
+    SystemPrompt, Tool as AnthropicTool, ToolChoice,
 };
+
+pub use mixtape_anthropic_sdk::ServiceTier;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
-/// Default maximum tokens to generate
-const DEFAULT_MAX_TOKENS: i32 = 4096;
-
 // ===== Error Classification =====
 
 fn classify_anthropic_error(err: &AnthropicError) -> ProviderError {
@@ -43,6 +50,74 @@ fn classify_anthropic_error(err: &AnthropicError) -> ProviderError {
     }
 }
 
+// ===== Tool Choice =====
+
+/// How the model must use the tools offered with a request
+///
+/// Set with [`AnthropicProvider::with_tool_choice`]. Not every choice
+/// combines with every other setting; see that method for the rules.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ToolChoiceMode {
+    /// The model decides whether to call tools (the API default)
+    Auto,
+    /// The model must call at least one tool
+    Any,
+    /// The model must call the named tool
+    Tool(String),
+    /// The model may not call tools
+    None,
+}
+
+// ===== Prompt Caching =====
+
+/// Where the provider places prompt cache breakpoints
+///
+/// Set with [`AnthropicProvider::with_prompt_caching`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CachingStrategy {
+    /// No cache breakpoints are sent
+    #[default]
+    Disabled,
+    /// Cache the stable prefix: breakpoints after the system prompt and after
+    /// the tool definitions, once that prefix reaches the model's minimum
+    /// cacheable length. Conversation messages are never marked. Agents
+    /// built with `with_stale_prefix_uncached` leave a segment that just
+    /// changed unmarked for one call.
+    Auto,
+}
+
+/// Part of the cacheable prefix that changed since the last call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StalePrefix {
+    /// Only the system prompt changed; the tools breakpoint still hits
+    SystemPrompt,
+    /// The tool definitions changed, and with them everything after
+    Tools,
+}
+
+/// The model the API reports serving, or the configured one if it's blank
+fn served_model(reported: &str, configured: &str) -> String {
+    if reported.is_empty() {
+        configured.to_string()
+    } else {
+        reported.to_string()
+    }
+}
+
+/// Smallest prefix, in tokens, that Anthropic will cache for a model
+fn min_cacheable_tokens(model_id: &str) -> usize {
+    if ["opus-4-5", "opus-4-6", "haiku-4-5"]
+        .iter()
+        .any(|m| model_id.contains(m))
+    {
+        4096
+    } else if model_id.contains("haiku") {
+        2048
+    } else {
+        1024
+    }
+}
+
 // ===== AnthropicProvider =====
 
 /// Anthropic direct API model provider
@@ -61,12 +136,26 @@ pub struct AnthropicProvider {
     model_name: &'static str,
     max_context_tokens: usize,
     max_output_tokens: usize,
-    max_tokens: i32,
+    /// Context window the model offers behind the 1M-context beta, if any
+    extended_context_tokens: Option<usize>,
+    /// Explicit per-request token limit; defaults to the model limit, capped
+    max_tokens: Option<i32>,
+    max_tokens_cap: usize,
     temperature: Option<f32>,
     top_p: Option<f32>,
     top_k: Option<u32>,
     thinking_config: Option<ThinkingConfig>,
     betas: Option<Vec<BetaFeature>>,
+    user_id: Option<String>,
+    /// Whether documents are sent with citations enabled
+    citations: bool,
+    service_tier: Option<ServiceTier>,
+    /// Whether the model may call several tools per turn; API default when unset
+    parallel_tool_use: Option<bool>,
+    tool_choice: Option<ToolChoiceMode>,
+    prompt_caching: CachingStrategy,
+    /// Prefix left without breakpoints because it just changed
+    stale_prefix: Option<StalePrefix>,
     retry_config: RetryConfig,
     on_retry: Option<RetryCallback>,
 }
@@ -79,12 +168,21 @@ impl Clone for AnthropicProvider {
             model_name: self.model_name,
             max_context_tokens: self.max_context_tokens,
             max_output_tokens: self.max_output_tokens,
+            extended_context_tokens: self.extended_context_tokens,
             max_tokens: self.max_tokens,
+            max_tokens_cap: self.max_tokens_cap,
             temperature: self.temperature,
             top_p: self.top_p,
             top_k: self.top_k,
             thinking_config: self.thinking_config,
             betas: self.betas.clone(),
+            user_id: self.user_id.clone(),
+            citations: self.citations,
+            service_tier: self.service_tier,
+            parallel_tool_use: self.parallel_tool_use,
+            tool_choice: self.tool_choice.clone(),
+            prompt_caching: self.prompt_caching,
+            stale_prefix: self.stale_prefix,
             retry_config: self.retry_config.clone(),
             on_retry: self.on_retry.clone(),
         }
@@ -133,38 +231,115 @@ impl AnthropicProvider {
             model_name: model.name(),
             max_context_tokens: model.max_context_tokens(),
             max_output_tokens: model.max_output_tokens(),
-            max_tokens: DEFAULT_MAX_TOKENS,
+            extended_context_tokens: model.extended_context_tokens(),
+            max_tokens: None,
+            max_tokens_cap: DEFAULT_MAX_TOKENS_CAP,
             temperature: None,
             top_p: None,
             top_k: None,
             thinking_config: None,
             betas: None,
+            user_id: None,
+            citations: false,
+            service_tier: None,
+            parallel_tool_use: None,
+            tool_choice: None,
+            prompt_caching: CachingStrategy::Disabled,
+            stale_prefix: None,
             retry_config: RetryConfig::default(),
             on_retry: None,
         }
     }
 
     /// Set the maximum number of tokens to generate per request
+    ///
+    /// Overrides the default of the model's output limit capped at
+    /// [`DEFAULT_MAX_TOKENS_CAP`]. Values above the model's output limit
+    /// are clamped to it with a warning, since the API would reject them;
+    /// use [`try_with_max_tokens`](Self::try_with_max_tokens) to reject them.
     pub fn with_max_tokens(mut self, max_tokens: i32) -> Self {
-        self.max_tokens = max_tokens;
+        self.max_tokens = Some(clamp_max_tokens(max_tokens, self.max_output_tokens));
+        self
+    }
+
+    /// Set `max_tokens`, rejecting values outside 1 to the model's output limit
+    pub fn try_with_max_tokens(mut self, max_tokens: i32) -> Result<Self, ProviderError> {
+        self.max_tokens = Some(check_max_tokens(max_tokens, self.max_output_tokens)?);
+        Ok(self)
+    }
+
+    /// Cap the default `max_tokens` derived from the model's output limit
+    ///
+    /// Has no effect once [`with_max_tokens`](Self::with_max_tokens) is set.
+    /// You are billed for tokens actually generated, not for `max_tokens`,
+    /// but a higher limit lets responses run longer (and cost more), and
+    /// some rate limits count `max_tokens` against your output quota.
+    pub fn with_max_tokens_cap(mut self, cap: usize) -> Self {
+        self.max_tokens_cap = cap;
         self
     }
 
+    /// Effective `max_tokens` sent with each request
+    fn max_tokens(&self) -> i32 {
+        self.max_tokens
+            .unwrap_or_else(|| default_max_tokens(self.max_output_tokens, self.max_tokens_cap))
+    }
+
     /// Set the temperature (0.0 to 1.0)
+    ///
+    /// Out-of-range values are clamped with a warning; use
+    /// [`try_with_temperature`](Self::try_with_temperature) to reject them.
     pub fn with_temperature(mut self, temperature: f32) -> Self {
-        self.temperature = Some(temperature);
+        self.temperature = clamp_unit_range("temperature", temperature);
         self
     }
 
+    /// Set the temperature, rejecting values outside 0.0 to 1.0
+    pub fn try_with_temperature(mut self, temperature: f32) -> Result<Self, ProviderError> {
+        self.temperature = Some(check_unit_range("temperature", temperature)?);
+        Ok(self)
+    }
+
     /// Set top_p (0.0 to 1.0)
+    ///
+    /// Out-of-range values are clamped with a warning; use
+    /// [`try_with_top_p`](Self::try_with_top_p) to reject them.
     pub fn with_top_p(mut self, top_p: f32) -> Self {
-        self.top_p = Some(top_p);
+        self.top_p = clamp_unit_range("top_p", top_p);
         self
     }
 
+    /// Set top_p, rejecting values outside 0.0 to 1.0
+    pub fn try_with_top_p(mut self, top_p: f32) -> Result<Self, ProviderError> {
+        self.top_p = Some(check_unit_range("top_p", top_p)?);
+        Ok(self)
+    }
+
     /// Set top_k (only sample from the top K options)
+    ///
+    /// A value of 0 is ignored with a warning; use
+    /// [`try_with_top_k`](Self::try_with_top_k) to reject it.
     pub fn with_top_k(mut self, top_k: u32) -> Self {
-        self.top_k = Some(top_k);
+        if top_k == 0 {
+            eprintln!("Warning: top_k must be at least 1; leaving it unset");
+        }
+        self.top_k = check_top_k(top_k).ok();
+        self
+    }
+
+    /// Set top_k, rejecting 0
+    pub fn try_with_top_k(mut self, top_k: u32) -> Result<Self, ProviderError> {
+        self.top_k = Some(check_top_k(top_k)?);
+        Ok(self)
+    }
+
+    /// Request a sampling seed (not supported; ignored with a warning)
+    ///
+    /// The Anthropic API takes no seed. This exists so code written against
+    /// several providers can call it; use a temperature of 0.0 for the most
+    /// repeatable output.
+    pub fn with_seed(self, _seed: u64) -> Self {
+        eprintln!("Warning: the Anthropic API does not support a sampling seed; ignoring it");
         self
     }
 
@@ -184,6 +359,31 @@ impl AnthropicProvider {
         self
     }
 
+    /// Let the model think between tool calls (beta)
+    ///
+    /// With extended thinking alone, Claude thinks once before its first
+    /// response. Interleaved thinking lets it reason again after each tool
+    /// result. Thinking blocks from earlier turns are sent back with their
+    /// signatures so the model can continue its reasoning.
+    ///
+    /// Requires [`with_thinking`](Self::with_thinking); requests fail with
+    /// [`ProviderError::Configuration`] otherwise.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let provider = AnthropicProvider::from_env(ClaudeSonnet4_5)?
+    ///     .with_thinking(4096)
+    ///     .with_interleaved_thinking(true);
+    /// ```
+    pub fn with_interleaved_thinking(mut self, enabled: bool) -> Self {
+        let betas = self.betas.get_or_insert_with(Vec::new);
+        betas.retain(|beta| *beta != BetaFeature::InterleavedThinking);
+        if enabled {
+            betas.push(BetaFeature::InterleavedThinking);
+        }
+        self
+    }
+
     /// Enable 1M token context window for Claude Sonnet 4/4.5 (beta)
     ///
     /// Expands the context window from 200K to 1 million tokens.
@@ -216,6 +416,140 @@ impl AnthropicProvider {
         self
     }
 
+    /// Allow or forbid several tool calls in one assistant turn
+    ///
+    /// Sets `tool_choice.disable_parallel_tool_use` on requests that offer
+    /// tools. Forbidding parallel calls suits tools with side effects that
+    /// must happen in order; the API allows them by default.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let provider = AnthropicProvider::from_env(ClaudeSonnet4_5)?
+    ///     .with_parallel_tool_use(false);
+    /// ```
+    pub fn with_parallel_tool_use(mut self, enabled: bool) -> Self {
+        self.parallel_tool_use = Some(enabled);
+        self
+    }
+
+    /// Require, forbid, or leave to the model the use of tools
+    ///
+    /// Some combinations are rejected by the API. They are checked before
+    /// each request, which fails with [`ProviderError::Configuration`]
+    /// instead of being sent:
+    ///
+    /// | Tool choice   | Extended thinking | Parallel tool use set | Tools in request       |
+    /// |---------------|-------------------|-----------------------|------------------------|
+    /// | `Auto`        | allowed           | allowed               | optional               |
+    /// | `Any`         | **rejected**      | allowed               | required               |
+    /// | `Tool(name)`  | **rejected**      | allowed               | must include `name`    |
+    /// | `None`        | allowed           | **rejected**          | optional               |
+    ///
+    /// `Any` and `Tool` apply only to the first request after a user turn.
+    /// Requests that return tool results to the model use `Auto`, so the
+    /// model can finish with a text answer instead of calling tools forever.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let provider = AnthropicProvider::from_env(ClaudeSonnet4_5)?
+    ///     .with_tool_choice(ToolChoiceMode::Tool("extract_invoice".to_string()))
+    ///     .with_parallel_tool_use(false);
+    /// ```
+    pub fn with_tool_choice(mut self, tool_choice: ToolChoiceMode) -> Self {
+        self.tool_choice = Some(tool_choice);
+        self
+    }
+
+    /// Let the provider place prompt cache breakpoints
+    ///
+    /// [`CachingStrategy::Auto`] caches the system prompt and tool
+    /// definitions, which rarely change between turns. Requests whose prefix
+    /// is shorter than the model's minimum cacheable length are sent without
+    /// breakpoints, so it is safe to enable unconditionally.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let provider = AnthropicProvider::from_env(ClaudeSonnet4_5)?
+    ///     .with_prompt_caching(CachingStrategy::Auto);
+    /// ```
+    pub fn with_prompt_caching(mut self, strategy: CachingStrategy) -> Self {
+        self.prompt_caching = strategy;
+        self
+    }
+
+    /// Attach an end-user identifier to every request as `metadata.user_id`
+    ///
+    /// Lets Anthropic attribute usage and abuse signals to individual users of
+    /// your application. Use an opaque value such as a UUID or hash; never an
+    /// email address, name, or other personal information.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let provider = AnthropicProvider::from_env(ClaudeSonnet4_5)?
+    ///     .with_metadata("3f2a9c1e-user");
+    /// ```
+    pub fn with_metadata(mut self, user_id: impl Into<String>) -> Self {
+        self.user_id = Some(user_id.into());
+        self
+    }
+
+    /// Ask the model to cite the documents it is given
+    ///
+    /// Every document in the conversation is sent with citations enabled.
+    /// The model's answer then carries [`ContentBlock::Citations`] blocks
+    /// naming the document passages each piece of text relies on; read them
+    /// with [`AgentResponse::citations`](crate::AgentResponse::citations).
+    ///
+    /// [`ContentBlock::Citations`]: crate::ContentBlock::Citations
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let provider = AnthropicProvider::from_env(ClaudeSonnet4_5)?
+    ///     .with_citations(true);
+    /// ```
+    pub fn with_citations(mut self, enabled: bool) -> Self {
+        self.citations = enabled;
+        self
+    }
+
+    /// Select the service tier for every request
+    ///
+    /// [`ServiceTier::Auto`] lets Anthropic use priority capacity when it is
+    /// available to your organization; [`ServiceTier::StandardOnly`] always
+    /// uses standard capacity. The tier that actually handled a request is
+    /// reported in [`ModelResponse::service_tier`].
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use mixtape_core::ServiceTier;
+    ///
+    /// let provider = AnthropicProvider::from_env(ClaudeSonnet4_5)?
+    ///     .with_service_tier(ServiceTier::StandardOnly);
+    /// ```
+    pub fn with_service_tier(mut self, service_tier: ServiceTier) -> Self {
+        self.service_tier = Some(service_tier);
+        self
+    }
+
+    /// Set the `User-Agent` header sent with every request
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let provider = AnthropicProvider::from_env(ClaudeSonnet4_5)?
+    ///     .with_user_agent("my-app/1.2.0");
+    /// ```
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.client = self.client.with_user_agent(user_agent);
+        self
+    }
+
     /// Configure retry behavior for transient errors (throttling, rate limits)
     ///
     /// Default: 8 attempts with exponential backoff starting at 500ms, capped at 30s
@@ -224,14 +558,30 @@ impl AnthropicProvider {
         self
     }
 
-    /// Set the maximum number of retry attempts for transient errors
+    /// Set the maximum number of attempts for transient errors
     ///
-    /// Default: 8
+    /// `attempts` counts the first request, so `with_max_retries(3)` sends
+    /// a request at most three times. Both 0 and 1 mean a single attempt; use
+    /// [`with_retries_disabled`](Self::with_retries_disabled) to say so
+    /// directly. Default: 8
     pub fn with_max_retries(mut self, attempts: usize) -> Self {
         self.retry_config.max_attempts = attempts;
         self
     }
 
+    /// Send each request exactly once, failing on the first error
+    ///
+    /// Turns off both this provider's retries and the HTTP client's own
+    /// retries of 429 and 5xx responses. Useful for tests and interactive
+    /// use where a fast failure beats waiting out a backoff.
+    pub fn with_retries_disabled(mut self) -> Self {
+        self.retry_config = RetryConfig::disabled();
+        self.client = self
+            .client
+            .with_retry_config(mixtape_anthropic_sdk::RetryConfig::disabled());
+        self
+    }
+
     /// Set the maximum delay between retries
     ///
     /// Default: 30 seconds
@@ -248,6 +598,25 @@ impl AnthropicProvider {
         self
     }
 
+    /// Set how retry delays are randomized
+    ///
+    /// Default: [`JitterStrategy::Full`]
+    pub fn with_retry_jitter(mut self, jitter: JitterStrategy) -> Self {
+        self.retry_config.jitter = jitter;
+        self
+    }
+
+    /// Cap the total time spent waiting between retries
+    ///
+    /// Once the next delay would exceed the budget, the last error is
+    /// returned instead of retrying.
+    ///
+    /// Default: no limit
+    pub fn with_retry_budget(mut self, budget: Duration) -> Self {
+        self.retry_config.max_total_delay = Some(budget);
+        self
+    }
+
     /// Set a callback to be notified when retries occur
     ///
     /// # Example
@@ -266,17 +635,121 @@ impl AnthropicProvider {
         self
     }
 
+    /// Reject interleaved thinking without extended thinking enabled
+    fn check_thinking_config(&self) -> Result<(), ProviderError> {
+        let interleaved = self
+            .betas
+            .as_ref()
+            .is_some_and(|betas| betas.contains(&BetaFeature::InterleavedThinking));
+        if interleaved && !matches!(self.thinking_config, Some(ThinkingConfig::Enabled { .. })) {
+            return Err(ProviderError::Configuration(
+                "Interleaved thinking requires extended thinking; call with_thinking()".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// The `tool_choice` for a request offering `tools`
+    ///
+    /// Rejects the combinations listed on
+    /// [`with_tool_choice`](Self::with_tool_choice). A forced choice falls
+    /// back to `Auto` when the request `answers_tool_use`.
+    fn tool_choice_for(
+        &self,
+        tools: &[AnthropicTool],
+        answers_tool_use: bool,
+    ) -> Result<Option<ToolChoice>, ProviderError> {
+        let thinking = matches!(self.thinking_config, Some(ThinkingConfig::Enabled { .. }));
+        let disable_parallel_tool_use = self.parallel_tool_use.map(|enabled| !enabled);
+        let conflict = |msg: String| Err(ProviderError::Configuration(msg));
+
+        match &self.tool_choice {
+            Some(ToolChoiceMode::Any | ToolChoiceMode::Tool(_)) if thinking => conflict(
+                "Extended thinking only supports the Auto and None tool choices; \
+                 a forced tool choice cannot be combined with with_thinking()"
+                    .to_string(),
+            ),
+            Some(ToolChoiceMode::None) if self.parallel_tool_use.is_some() => conflict(
+                "with_parallel_tool_use() has no effect when tool choice is None".to_string(),
+            ),
+            Some(ToolChoiceMode::Any) if tools.is_empty() => {
+                conflict("Tool choice Any requires at least one tool in the request".to_string())
+            }
+            Some(ToolChoiceMode::Tool(name)) if !tools.iter().any(|t| &t.name == name) => {
+                conflict(format!(
+                    "Tool choice names '{}', which is not among the request's tools",
+                    name
+                ))
+            }
+            _ if tools.is_empty() => Ok(None),
+            Some(ToolChoiceMode::Any | ToolChoiceMode::Tool(_)) if answers_tool_use => {
+                Ok(Some(ToolChoice::Auto {
+                    disable_parallel_tool_use,
+                }))
+            }
+            Some(ToolChoiceMode::Any) => Ok(Some(ToolChoice::Any {
+                disable_parallel_tool_use,
+            })),
+            Some(ToolChoiceMode::Tool(name)) => Ok(Some(ToolChoice::Tool {
+                name: name.clone(),
+                disable_parallel_tool_use,
+            })),
+            Some(ToolChoiceMode::None) => Ok(Some(ToolChoice::None)),
+            Some(ToolChoiceMode::Auto) => Ok(Some(ToolChoice::Auto {
+                disable_parallel_tool_use,
+            })),
+            None => Ok(disable_parallel_tool_use.map(|disable| ToolChoice::Auto {
+                disable_parallel_tool_use: Some(disable),
+            })),
+        }
+    }
+
+    /// Whether the system prompt and tools are long enough to cache
+    fn should_cache_prefix(&self, system_prompt: Option<&str>, tools: &[AnthropicTool]) -> bool {
+        if self.prompt_caching != CachingStrategy::Auto {
+            return false;
+        }
+        let tools_json = serde_json::to_string(tools).unwrap_or_default();
+        let prefix_tokens = self.estimate_token_count(system_prompt.unwrap_or_default())
+            + self.estimate_token_count(&tools_json);
+        prefix_tokens >= min_cacheable_tokens(&self.model_id)
+    }
+
     fn build_params(
         &self,
-        messages: Vec<mixtape_anthropic_sdk::MessageParam>,
-        tools: Vec<AnthropicTool>,
+        mut messages: Vec<mixtape_anthropic_sdk::MessageParam>,
+        mut tools: Vec<AnthropicTool>,
         system_prompt: Option<String>,
-    ) -> MessageCreateParams {
-        let mut builder =
-            MessageCreateParams::builder(&self.model_id, self.max_tokens as u32).messages(messages);
+    ) -> Result<MessageCreateParams, ProviderError> {
+        if self.citations {
+            enable_citations(&mut messages);
+        }
+        let tool_choice = self.tool_choice_for(&tools, answers_tool_use(&messages))?;
+        let mut betas = self.betas.clone().unwrap_or_default();
+        if references_files(&messages) && !betas.contains(&BetaFeature::FilesApi) {
+            betas.push(BetaFeature::FilesApi);
+        }
+
+        // Tools come first in the prefix, so a stale tools segment leaves
+        // nothing worth marking; a stale system prompt keeps the tools mark
+        let cache_prefix = self.should_cache_prefix(system_prompt.as_deref(), &tools);
+        let cache_tools = cache_prefix && self.stale_prefix != Some(StalePrefix::Tools);
+        let cache_system = cache_prefix && self.stale_prefix.is_none();
+        if cache_tools {
+            if let Some(last) = tools.last_mut() {
+                last.cache_control = Some(CacheControl::ephemeral());
+            }
+        }
+
+        let mut builder = MessageCreateParams::builder(&self.model_id, self.max_tokens() as u32)
+            .messages(messages);
 
         if let Some(system) = system_prompt {
-            builder = builder.system(system);
+            builder = if cache_system {
+                builder.system(SystemPrompt::cached(system, CacheControl::ephemeral()))
+            } else {
+                builder.system(system)
+            };
         }
         if let Some(temp) = self.temperature {
             builder = builder.temperature(temp);
@@ -290,6 +763,9 @@ impl AnthropicProvider {
         if !tools.is_empty() {
             builder = builder.tools(tools);
         }
+        if let Some(tool_choice) = tool_choice {
+            builder = builder.tool_choice(tool_choice);
+        }
         if let Some(config) = self.thinking_config {
             let sdk_config = match config {
                 ThinkingConfig::Enabled { budget_tokens } => {
@@ -299,11 +775,19 @@ impl AnthropicProvider {
             };
             builder = builder.thinking_config(sdk_config);
         }
-        if let Some(betas) = &self.betas {
-            builder = builder.betas(betas.clone());
+        if !betas.is_empty() {
+            builder = builder.betas(betas);
+        }
+        if let Some(user_id) = &self.user_id {
+            builder = builder.metadata(Metadata {
+                user_id: Some(user_id.clone()),
+            });
+        }
+        if let Some(tier) = self.service_tier {
+            builder = builder.service_tier(tier);
         }
 
-        builder.build()
+        Ok(builder.build())
     }
 }
 
@@ -321,12 +805,110 @@ impl ModelProvider for AnthropicProvider {
         self.max_output_tokens
     }
 
+    fn supported_document_formats(&self) -> &[DocumentFormat] {
+        &[DocumentFormat::Pdf, DocumentFormat::Txt]
+    }
+
+    fn configure_parallel_tool_use(&self, enabled: bool) -> Option<Arc<dyn ModelProvider>> {
+        Some(Arc::new(self.clone().with_parallel_tool_use(enabled)))
+    }
+
+    fn without_tool_use(&self) -> Option<Arc<dyn ModelProvider>> {
+        // The API rejects a parallel tool use setting alongside `none`
+        let mut provider = self.clone().with_tool_choice(ToolChoiceMode::None);
+        provider.parallel_tool_use = None;
+        Some(Arc::new(provider))
+    }
+
+    fn without_stale_cache_breakpoints(
+        &self,
+        tools_changed: bool,
+    ) -> Option<Arc<dyn ModelProvider>> {
+        if self.prompt_caching == CachingStrategy::Disabled {
+            return None;
+        }
+        let mut provider = self.clone();
+        provider.stale_prefix = Some(if tools_changed {
+            StalePrefix::Tools
+        } else {
+            StalePrefix::SystemPrompt
+        });
+        Some(Arc::new(provider))
+    }
+
+    fn supports(&self, feature: Feature) -> bool {
+        match feature {
+            Feature::Thinking
+            | Feature::WebSearch
+            | Feature::PromptCaching
+            | Feature::Documents
+            | Feature::ParallelToolUse => true,
+            Feature::Context1M => self.extended_context_tokens.is_some(),
+            Feature::Seed => false,
+        }
+    }
+
+    fn settings(&self) -> ProviderSettings {
+        ProviderSettings {
+            model_id: Some(self.model_id.clone()),
+            sampling: SamplingParams {
+                max_tokens: Some(self.max_tokens()),
+                temperature: self.temperature,
+                top_p: self.top_p,
+                top_k: self.top_k,
+                seed: None,
+            },
+            retry: Some(self.retry_config.clone()),
+        }
+    }
+
+    async fn count_tokens(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+        system_prompt: Option<&str>,
+    ) -> Result<usize, ProviderError> {
+        let anthropic_messages: Vec<mixtape_anthropic_sdk::MessageParam> = messages
+            .iter()
+            .map(to_anthropic_message)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let anthropic_tools: Vec<AnthropicTool> = tools
+            .iter()
+            .map(to_anthropic_tool)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let params = CountTokensParams {
+            model: self.model_id.clone(),
+            messages: anthropic_messages,
+            system: system_prompt.map(str::to_string),
+            tools: (!anthropic_tools.is_empty()).then_some(anthropic_tools),
+        };
+
+        let response = retry_with_backoff(
+            || async {
+                self.client
+                    .messages()
+                    .count_tokens(params.clone())
+                    .await
+                    .map_err(|e| classify_anthropic_error(&e))
+            },
+            &self.retry_config,
+            &self.on_retry,
+        )
+        .await?;
+
+        Ok(response.input_tokens as usize)
+    }
+
     async fn generate(
         &self,
         messages: Vec<Message>,
         tools: Vec<ToolDefinition>,
         system_prompt: Option<String>,
     ) -> Result<ModelResponse, ProviderError> {
+        self.check_thinking_config()?;
+
         // Convert mixtape types to Anthropic types
         let anthropic_messages: Vec<mixtape_anthropic_sdk::MessageParam> = messages
             .iter()
@@ -338,7 +920,7 @@ impl ModelProvider for AnthropicProvider {
             .map(to_anthropic_tool)
             .collect::<Result<Vec<_>, _>>()?;
 
-        let params = self.build_params(anthropic_messages, anthropic_tools, system_prompt);
+        let params = self.build_params(anthropic_messages, anthropic_tools, system_prompt)?;
 
         let response = retry_with_backoff(
             || async {
@@ -370,7 +952,10 @@ impl ModelProvider for AnthropicProvider {
         Ok(ModelResponse {
             message,
             stop_reason,
+            stop_sequence: response.stop_sequence.clone(),
             usage,
+            service_tier: response.usage.service_tier.clone(),
+            model: Some(served_model(&response.model, &self.model_id)),
         })
     }
 
@@ -380,6 +965,8 @@ impl ModelProvider for AnthropicProvider {
         tools: Vec<ToolDefinition>,
         system_prompt: Option<String>,
     ) -> Result<BoxStream<'static, Result<StreamEvent, ProviderError>>, ProviderError> {
+        self.check_thinking_config()?;
+
         // Convert mixtape types to Anthropic types
         let anthropic_messages: Vec<mixtape_anthropic_sdk::MessageParam> = messages
             .iter()
@@ -391,7 +978,7 @@ impl ModelProvider for AnthropicProvider {
             .map(to_anthropic_tool)
             .collect::<Result<Vec<_>, _>>()?;
 
-        let params = self.build_params(anthropic_messages, anthropic_tools, system_prompt);
+        let params = self.build_params(anthropic_messages, anthropic_tools, system_prompt)?;
 
         let stream = retry_with_backoff(
             || async {
@@ -407,11 +994,18 @@ impl ModelProvider for AnthropicProvider {
         .await?;
 
         // Convert the SDK stream into our StreamEvent stream
+        let configured_model = self.model_id.clone();
         let event_stream = async_stream::stream! {
             let mut stream = stream;
             let mut tool_uses_in_progress: HashMap<usize, (String, String, String)> = HashMap::new();
+            // Thinking blocks in progress: (thinking, signature)
+            let mut thinking_in_progress: HashMap<usize, (String, String)> = HashMap::new();
+            // Citations of text blocks in progress, emitted when the block stops
+            let mut citations_in_progress: HashMap<usize, Vec<Citation>> = HashMap::new();
             let mut input_tokens: usize = 0;
             let mut output_tokens: usize = 0;
+            let mut service_tier: Option<String> = None;
+            let mut model = configured_model.clone();
 
             while let Some(event_result) = stream.next().await {
                 match event_result {
@@ -419,6 +1013,8 @@ impl ModelProvider for AnthropicProvider {
                         MessageStreamEvent::MessageStart { message } => {
                             // Capture input tokens from the initial message
                             input_tokens = message.usage.input_tokens as usize;
+                            service_tier = message.usage.service_tier;
+                            model = served_model(&message.model, &configured_model);
                         }
                         MessageStreamEvent::ContentBlockStart {
                             index,
@@ -426,8 +1022,22 @@ impl ModelProvider for AnthropicProvider {
                         } => {
                             tool_uses_in_progress.insert(index, (id, name, String::new()));
                         }
+                        MessageStreamEvent::ContentBlockStart {
+                            index,
+                            content_block: AnthropicContentBlock::Thinking { thinking, signature },
+                        } => {
+                            thinking_in_progress.insert(index, (thinking, signature));
+                        }
+                        MessageStreamEvent::ContentBlockStart {
+                            content_block: AnthropicContentBlock::RedactedThinking { data },
+                            ..
+                        } => {
+                            // Redacted thinking arrives whole; keep it as thinking with
+                            // empty content, matching the non-streaming conversion
+                            yield Ok(StreamEvent::Thinking { thinking: String::new(), signature: data });
+                        }
                         MessageStreamEvent::ContentBlockStart { .. } => {
-                            // Ignore non-tool-use content blocks (e.g., text blocks)
+                            // Text blocks are streamed through their deltas
                         }
                         MessageStreamEvent::ContentBlockDelta { index, delta } => {
                             match delta {
@@ -437,19 +1047,40 @@ impl ModelProvider for AnthropicProvider {
                                 ContentBlockDelta::InputJsonDelta { partial_json } => {
                                     if let Some(entry) = tool_uses_in_progress.get_mut(&index) {
                                         entry.2.push_str(&partial_json);
+                                        yield Ok(StreamEvent::ToolInputDelta {
+                                            tool_use_id: entry.0.clone(),
+                                            partial_json,
+                                        });
                                     }
                                 }
                                 ContentBlockDelta::ThinkingDelta { thinking } => {
+                                    if let Some(entry) = thinking_in_progress.get_mut(&index) {
+                                        entry.0.push_str(&thinking);
+                                    }
                                     yield Ok(StreamEvent::ThinkingDelta(thinking));
                                 }
-                                // Signature deltas are internal to thinking verification
-                                ContentBlockDelta::SignatureDelta { .. } => {}
+                                ContentBlockDelta::SignatureDelta { signature } => {
+                                    if let Some(entry) = thinking_in_progress.get_mut(&index) {
+                                        entry.1.push_str(&signature);
+                                    }
+                                }
+                                ContentBlockDelta::CitationsDelta { citation } => {
+                                    if let Some(citation) = from_anthropic_citation(&citation) {
+                                        citations_in_progress.entry(index).or_default().push(citation);
+                                    }
+                                }
                             }
                         }
                         MessageStreamEvent::ContentBlockStop { index } => {
                             if let Some((id, name, input_json)) = tool_uses_in_progress.remove(&index) {
-                                let input = serde_json::from_str(&input_json).unwrap_or_default();
-                                yield Ok(StreamEvent::ToolUse(ToolUseBlock { id, name, input }));
+                                for event in tool_use_events(id, name, &input_json) {
+                                    yield Ok(event);
+                                }
+                            } else if let Some((thinking, signature)) = thinking_in_progress.remove(&index) {
+                                yield Ok(StreamEvent::Thinking { thinking, signature });
+                            }
+                            for citation in citations_in_progress.remove(&index).unwrap_or_default() {
+                                yield Ok(StreamEvent::Citation(citation));
                             }
                         }
                         MessageStreamEvent::MessageStop => {
@@ -465,7 +1096,10 @@ impl ModelProvider for AnthropicProvider {
                             if let Some(stop_reason) = delta.stop_reason {
                                 yield Ok(StreamEvent::Stop {
                                     stop_reason: from_anthropic_stop_reason(&stop_reason),
+                                    stop_sequence: delta.stop_sequence,
                                     usage: Some(TokenUsage { input_tokens, output_tokens }),
+                                    service_tier: service_tier.clone(),
+                                    model: Some(model.clone()),
                                 });
                             }
                         }
@@ -477,6 +1111,16 @@ impl ModelProvider for AnthropicProvider {
                     }
                 }
             }
+
+            // A stream cut off mid-block never sends its stop; keep what
+            // arrived of any tool use still in progress
+            let mut unfinished: Vec<_> = tool_uses_in_progress.into_iter().collect();
+            unfinished.sort_by_key(|(index, _)| *index);
+            for (_, (id, name, input_json)) in unfinished {
+                for event in tool_use_events(id, name, &input_json) {
+                    yield Ok(event);
+                }
+            }
         };
 
         Ok(Box::pin(event_stream))
@@ -487,6 +1131,7 @@ impl ModelProvider for AnthropicProvider {
 mod tests {
     use super::*;
     use crate::model::Model;
+    use crate::models::{ClaudeHaiku4_5, ClaudeSonnet4_5, ClaudeSonnet4_6};
 
     /// Test model for unit tests
     struct TestModel {
@@ -516,45 +1161,162 @@ mod tests {
     }
 
     #[test]
-    fn test_builder_max_tokens() {
-        // Skip if no API key available
-        if std::env::var("ANTHROPIC_API_KEY").is_err() {
-            return;
-        }
-
+    fn test_default_max_tokens_follows_model_output_limit() {
         let test_model = TestModel {
             name: "Test Model",
             anthropic_id: "claude-test-model",
         };
-        let provider = AnthropicProvider::from_env(test_model)
-            .unwrap()
-            .with_max_tokens(2048);
-
-        assert_eq!(provider.max_tokens, 2048);
+        // TestModel outputs 64K, above the cap
+        let provider = AnthropicProvider::new("sk-ant-test", test_model).unwrap();
+        assert_eq!(provider.max_tokens(), DEFAULT_MAX_TOKENS_CAP as i32);
+        let params = provider.build_params(Vec::new(), Vec::new(), None).unwrap();
+        assert_eq!(params.max_tokens, DEFAULT_MAX_TOKENS_CAP as u32);
+
+        let provider = provider.with_max_tokens_cap(8_192);
+        assert_eq!(provider.max_tokens(), 8_192);
+        let provider = provider.with_max_tokens_cap(1_000_000);
+        assert_eq!(provider.max_tokens(), 64_000);
     }
 
     #[test]
-    fn test_builder_temperature() {
-        if std::env::var("ANTHROPIC_API_KEY").is_err() {
-            return;
-        }
-
+    fn test_explicit_max_tokens_wins_over_cap() {
         let test_model = TestModel {
             name: "Test Model",
             anthropic_id: "claude-test-model",
         };
-        let provider = AnthropicProvider::from_env(test_model)
+        let provider = AnthropicProvider::new("sk-ant-test", test_model)
             .unwrap()
-            .with_temperature(0.7);
+            .with_max_tokens(2048)
+            .with_max_tokens_cap(100_000);
 
-        assert_eq!(provider.temperature, Some(0.7));
+        assert_eq!(provider.max_tokens(), 2048);
+        let params = provider.build_params(Vec::new(), Vec::new(), None).unwrap();
+        assert_eq!(params.max_tokens, 2048);
     }
 
     #[test]
-    fn test_builder_chaining() {
-        if std::env::var("ANTHROPIC_API_KEY").is_err() {
-            return;
-        }
+    fn test_settings_report_model_id_and_sampling() {
+        let test_model = TestModel {
+            name: "Test Model",
+            anthropic_id: "claude-test-model",
+        };
+        let provider = AnthropicProvider::new("sk-ant-test", test_model)
+            .unwrap()
+            .with_max_tokens(2048)
+            .with_temperature(0.5);
+
+        let settings = provider.settings();
+        assert_eq!(settings.model_id.as_deref(), Some("claude-test-model"));
+        assert_eq!(settings.sampling.max_tokens, Some(2048));
+        assert_eq!(settings.sampling.temperature, Some(0.5));
+        assert_eq!(settings.sampling.top_p, None);
+        assert_eq!(settings.retry.unwrap().max_attempts, 8);
+
+        let json = serde_json::to_string(&provider.settings()).unwrap();
+        assert!(!json.contains("sk-ant-test"));
+    }
+
+    #[test]
+    fn test_supports_reports_model_capabilities() {
+        let provider = AnthropicProvider::new("sk-ant-test", ClaudeSonnet4_5).unwrap();
+        assert!(provider.supports(Feature::WebSearch));
+        assert!(provider.supports(Feature::Thinking));
+        assert!(provider.supports(Feature::ParallelToolUse));
+        assert!(provider.supports(Feature::Context1M));
+
+        let provider = AnthropicProvider::new("sk-ant-test", ClaudeSonnet4_6).unwrap();
+        assert!(provider.supports(Feature::Context1M));
+
+        let provider = AnthropicProvider::new("sk-ant-test", ClaudeHaiku4_5).unwrap();
+        assert!(provider.supports(Feature::WebSearch));
+        assert!(!provider.supports(Feature::Context1M));
+    }
+
+    #[test]
+    fn test_builder_max_tokens() {
+        // Skip if no API key available
+        if std::env::var("ANTHROPIC_API_KEY").is_err() {
+            return;
+        }
+
+        let test_model = TestModel {
+            name: "Test Model",
+            anthropic_id: "claude-test-model",
+        };
+        let provider = AnthropicProvider::from_env(test_model)
+            .unwrap()
+            .with_max_tokens(2048);
+
+        assert_eq!(provider.max_tokens(), 2048);
+    }
+
+    #[test]
+    fn test_builder_temperature() {
+        if std::env::var("ANTHROPIC_API_KEY").is_err() {
+            return;
+        }
+
+        let test_model = TestModel {
+            name: "Test Model",
+            anthropic_id: "claude-test-model",
+        };
+        let provider = AnthropicProvider::from_env(test_model)
+            .unwrap()
+            .with_temperature(0.7);
+
+        assert_eq!(provider.temperature, Some(0.7));
+    }
+
+    #[test]
+    fn test_builder_validates_sampling_params() {
+        let test_model = || TestModel {
+            name: "Test Model",
+            anthropic_id: "claude-test-model",
+        };
+        let provider = AnthropicProvider::new("sk-ant-test", test_model())
+            .unwrap()
+            .with_temperature(2.0)
+            .with_top_k(0);
+        assert_eq!(provider.temperature, Some(1.0));
+        assert_eq!(provider.top_k, None);
+
+        let result = AnthropicProvider::new("sk-ant-test", test_model())
+            .unwrap()
+            .try_with_top_p(1.5);
+        assert!(matches!(result, Err(ProviderError::Configuration(_))));
+    }
+
+    #[test]
+    fn test_builder_validates_max_tokens_against_model_limit() {
+        let test_model = || TestModel {
+            name: "Test Model",
+            anthropic_id: "claude-test-model",
+        };
+        let provider = AnthropicProvider::new("sk-ant-test", test_model())
+            .unwrap()
+            .with_max_tokens(100_000);
+        assert_eq!(provider.max_tokens(), 64_000);
+
+        let provider = AnthropicProvider::new("sk-ant-test", test_model())
+            .unwrap()
+            .try_with_max_tokens(64_000)
+            .unwrap();
+        assert_eq!(provider.max_tokens(), 64_000);
+
+        for max_tokens in [64_001, 0] {
+            let result = provider.clone().try_with_max_tokens(max_tokens);
+            match result {
+                Err(ProviderError::Configuration(message)) => assert!(message.contains("64000")),
+                other => panic!("expected configuration error, got {:?}", other.err()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_builder_chaining() {
+        if std::env::var("ANTHROPIC_API_KEY").is_err() {
+            return;
+        }
 
         let test_model = TestModel {
             name: "Test Model",
@@ -569,7 +1331,7 @@ mod tests {
 
         assert_eq!(provider.model_id, "claude-test-model");
         assert_eq!(provider.model_name, "Test Model");
-        assert_eq!(provider.max_tokens, 1000);
+        assert_eq!(provider.max_tokens(), 1000);
         assert_eq!(provider.temperature, Some(0.5));
         assert_eq!(provider.top_p, Some(0.8));
         assert_eq!(provider.top_k, Some(50));
@@ -589,6 +1351,7 @@ mod tests {
             max_attempts: 5,
             base_delay_ms: 100,
             max_delay_ms: 5000,
+            ..Default::default()
         };
 
         let provider = AnthropicProvider::from_env(test_model)
@@ -652,6 +1415,1634 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_builder_metadata_in_params() {
+        let test_model = TestModel {
+            name: "Test Model",
+            anthropic_id: "claude-test-model",
+        };
+        let provider = AnthropicProvider::new("sk-ant-test", test_model).unwrap();
+
+        let params = provider.build_params(Vec::new(), Vec::new(), None).unwrap();
+        assert!(params.metadata.is_none());
+
+        let provider = provider.with_metadata("user-123");
+        let params = provider.build_params(Vec::new(), Vec::new(), None).unwrap();
+        let metadata = params.metadata.expect("metadata should be set");
+        assert_eq!(metadata.user_id.as_deref(), Some("user-123"));
+    }
+
+    #[tokio::test]
+    async fn test_user_agent_and_metadata_sent_with_request() {
+        use wiremock::matchers::{body_partial_json, header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(header("user-agent", "my-app/1.2.0"))
+            .and(body_partial_json(serde_json::json!({
+                "metadata": {"user_id": "user-123"}
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "msg_test",
+                "type": "message",
+                "role": "assistant",
+                "content": [{"type": "text", "text": "Hi"}],
+                "model": "claude-test-model",
+                "stop_reason": "end_turn",
+                "stop_sequence": null,
+                "usage": {"input_tokens": 3, "output_tokens": 1}
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = Anthropic::builder()
+            .api_key("test-key")
+            .api_base(mock_server.uri())
+            .build()
+            .unwrap();
+        let provider = AnthropicProvider::with_client(
+            client,
+            TestModel {
+                name: "Test Model",
+                anthropic_id: "claude-test-model",
+            },
+        )
+        .with_user_agent("my-app/1.2.0")
+        .with_metadata("user-123");
+
+        let response = provider
+            .generate(vec![Message::user("Hello")], Vec::new(), None)
+            .await
+            .unwrap();
+        assert_eq!(response.message.text(), "Hi");
+    }
+
+    #[tokio::test]
+    async fn test_retries_disabled_sends_one_request() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(429).set_body_json(serde_json::json!({
+                "type": "error",
+                "error": {"type": "rate_limit_error", "message": "Slow down"}
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        // The client retries 429s on its own unless told not to
+        let client = Anthropic::builder()
+            .api_key("test-key")
+            .api_base(mock_server.uri())
+            .build()
+            .unwrap();
+        let provider = AnthropicProvider::with_client(
+            client,
+            TestModel {
+                name: "Test Model",
+                anthropic_id: "claude-test-model",
+            },
+        )
+        .with_retries_disabled();
+
+        let err = provider
+            .generate(vec![Message::user("Hello")], Vec::new(), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ProviderError::RateLimited(_)), "{:?}", err);
+    }
+
+    #[tokio::test]
+    async fn test_generate_reports_matched_stop_sequence() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "msg_test",
+                "type": "message",
+                "role": "assistant",
+                "content": [{"type": "text", "text": "1, 2, 3"}],
+                "model": "claude-test-model",
+                "stop_reason": "stop_sequence",
+                "stop_sequence": "4",
+                "usage": {"input_tokens": 3, "output_tokens": 5}
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let client = Anthropic::builder()
+            .api_key("test-key")
+            .api_base(mock_server.uri())
+            .build()
+            .unwrap();
+        let provider = AnthropicProvider::with_client(
+            client,
+            TestModel {
+                name: "Test Model",
+                anthropic_id: "claude-test-model",
+            },
+        );
+
+        let response = provider
+            .generate(vec![Message::user("Count")], Vec::new(), None)
+            .await
+            .unwrap();
+        assert_eq!(response.stop_reason, StopReason::StopSequence);
+        assert_eq!(response.stop_sequence.as_deref(), Some("4"));
+    }
+
+    #[test]
+    fn test_parallel_tool_use_sets_tool_choice() {
+        let provider = AnthropicProvider::new(
+            "sk-ant-test",
+            TestModel {
+                name: "Test Model",
+                anthropic_id: "claude-test-model",
+            },
+        )
+        .unwrap();
+        let tool = || {
+            vec![to_anthropic_tool(&ToolDefinition {
+                name: "echo".to_string(),
+                description: "Echo".to_string(),
+                input_schema: serde_json::json!({"type": "object"}),
+            })
+            .unwrap()]
+        };
+
+        let params = provider.build_params(Vec::new(), tool(), None).unwrap();
+        assert!(params.tool_choice.is_none());
+
+        let provider = provider.with_parallel_tool_use(false);
+        let params = provider.build_params(Vec::new(), tool(), None).unwrap();
+        assert!(matches!(
+            params.tool_choice,
+            Some(ToolChoice::Auto {
+                disable_parallel_tool_use: Some(true)
+            })
+        ));
+
+        // tool_choice is only valid alongside tools
+        let params = provider.build_params(Vec::new(), Vec::new(), None).unwrap();
+        assert!(params.tool_choice.is_none());
+    }
+
+    #[test]
+    fn test_auto_caching_marks_system_and_tools_only() {
+        let provider = AnthropicProvider::new(
+            "sk-ant-test",
+            TestModel {
+                name: "Test Model",
+                anthropic_id: "claude-test-model",
+            },
+        )
+        .unwrap()
+        .with_prompt_caching(CachingStrategy::Auto);
+        let tools = || {
+            ["echo", "reverse"]
+                .iter()
+                .map(|name| {
+                    to_anthropic_tool(&ToolDefinition {
+                        name: name.to_string(),
+                        description: "Transform text".to_string(),
+                        input_schema: serde_json::json!({"type": "object"}),
+                    })
+                    .unwrap()
+                })
+                .collect::<Vec<_>>()
+        };
+        let messages = || vec![mixtape_anthropic_sdk::MessageParam::user("What changed?")];
+
+        // ~1250 tokens, above the 1024 minimum for this model
+        let long_prompt = "You are a careful assistant. ".repeat(175);
+        let params = provider
+            .build_params(messages(), tools(), Some(long_prompt.clone()))
+            .unwrap();
+
+        match params.system.unwrap() {
+            SystemPrompt::Blocks(blocks) => {
+                assert_eq!(blocks.len(), 1);
+                assert!(matches!(
+                    &blocks[0],
+                    mixtape_anthropic_sdk::ContentBlockParam::Text {
+                        text,
+                        cache_control: Some(_),
+                    } if *text == long_prompt
+                ));
+            }
+            other => panic!("expected cached system blocks, got {:?}", other),
+        }
+        let tools_sent = params.tools.unwrap();
+        assert!(tools_sent[0].cache_control.is_none());
+        assert!(tools_sent[1].cache_control.is_some());
+        let messages_json = serde_json::to_string(&params.messages).unwrap();
+        assert!(!messages_json.contains("cache_control"));
+
+        // A short prefix is below the minimum, so nothing is marked
+        let params = provider
+            .build_params(messages(), tools(), Some("Be brief".to_string()))
+            .unwrap();
+        assert!(matches!(params.system, Some(SystemPrompt::Text(_))));
+        assert!(params
+            .tools
+            .unwrap()
+            .iter()
+            .all(|tool| tool.cache_control.is_none()));
+
+        // Disabled by default
+        let provider = provider.with_prompt_caching(CachingStrategy::Disabled);
+        let params = provider
+            .build_params(messages(), tools(), Some(long_prompt))
+            .unwrap();
+        assert!(matches!(params.system, Some(SystemPrompt::Text(_))));
+    }
+
+    #[test]
+    fn test_stale_prefix_is_left_unmarked() {
+        let provider = AnthropicProvider::new(
+            "sk-ant-test",
+            TestModel {
+                name: "Test Model",
+                anthropic_id: "claude-test-model",
+            },
+        )
+        .unwrap()
+        .with_prompt_caching(CachingStrategy::Auto);
+        let build = |provider: &AnthropicProvider| {
+            let tool = to_anthropic_tool(&ToolDefinition {
+                name: "echo".to_string(),
+                description: "Echoes the input back".to_string(),
+                input_schema: serde_json::json!({"type": "object"}),
+            })
+            .unwrap();
+            provider
+                .build_params(
+                    vec![mixtape_anthropic_sdk::MessageParam::user("Hi")],
+                    vec![tool],
+                    Some("You are a careful assistant. ".repeat(175)),
+                )
+                .unwrap()
+        };
+
+        // A new system prompt keeps the tools mark, which still hits
+        let mut stale = provider.clone();
+        stale.stale_prefix = Some(StalePrefix::SystemPrompt);
+        let params = build(&stale);
+        assert!(matches!(params.system, Some(SystemPrompt::Text(_))));
+        assert!(params.tools.unwrap()[0].cache_control.is_some());
+
+        // New tools invalidate everything after them
+        stale.stale_prefix = Some(StalePrefix::Tools);
+        let params = build(&stale);
+        assert!(matches!(params.system, Some(SystemPrompt::Text(_))));
+        assert!(params.tools.unwrap()[0].cache_control.is_none());
+
+        // Without caching there are no breakpoints to leave off
+        assert!(provider
+            .clone()
+            .with_prompt_caching(CachingStrategy::Disabled)
+            .without_stale_cache_breakpoints(true)
+            .is_none());
+    }
+
+    #[test]
+    fn test_min_cacheable_tokens_by_model() {
+        assert_eq!(min_cacheable_tokens("claude-sonnet-4-5-20250929"), 1024);
+        assert_eq!(min_cacheable_tokens("claude-opus-4-5-20251101"), 4096);
+        assert_eq!(min_cacheable_tokens("claude-haiku-4-5-20251001"), 4096);
+        assert_eq!(min_cacheable_tokens("claude-3-5-haiku-20241022"), 2048);
+    }
+
+    fn choice_test_provider() -> AnthropicProvider {
+        AnthropicProvider::new(
+            "sk-ant-test",
+            TestModel {
+                name: "Test Model",
+                anthropic_id: "claude-test-model",
+            },
+        )
+        .unwrap()
+    }
+
+    fn echo_tool() -> Vec<AnthropicTool> {
+        vec![to_anthropic_tool(&ToolDefinition {
+            name: "echo".to_string(),
+            description: "Echo".to_string(),
+            input_schema: serde_json::json!({"type": "object"}),
+        })
+        .unwrap()]
+    }
+
+    fn assert_tool_choice_conflict(
+        provider: &AnthropicProvider,
+        tools: Vec<AnthropicTool>,
+        msg: &str,
+    ) {
+        match provider.build_params(Vec::new(), tools, None) {
+            Err(ProviderError::Configuration(err)) => assert!(err.contains(msg), "{}", err),
+            other => panic!("Expected configuration error, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn test_forced_tool_choice_sets_tool_choice() {
+        let provider = choice_test_provider()
+            .with_tool_choice(ToolChoiceMode::Tool("echo".to_string()))
+            .with_parallel_tool_use(false);
+        let params = provider
+            .build_params(Vec::new(), echo_tool(), None)
+            .unwrap();
+        assert!(matches!(
+            params.tool_choice,
+            Some(ToolChoice::Tool { ref name, disable_parallel_tool_use: Some(true) }) if name == "echo"
+        ));
+
+        let provider = choice_test_provider().with_tool_choice(ToolChoiceMode::Any);
+        let params = provider
+            .build_params(Vec::new(), echo_tool(), None)
+            .unwrap();
+        assert!(matches!(
+            params.tool_choice,
+            Some(ToolChoice::Any {
+                disable_parallel_tool_use: None
+            })
+        ));
+
+        // Thinking allows Auto and None
+        let provider = choice_test_provider()
+            .with_thinking(2048)
+            .with_tool_choice(ToolChoiceMode::None);
+        let params = provider
+            .build_params(Vec::new(), echo_tool(), None)
+            .unwrap();
+        assert!(matches!(params.tool_choice, Some(ToolChoice::None)));
+    }
+
+    #[test]
+    fn test_thinking_rejects_forced_tool_choice() {
+        let provider = choice_test_provider()
+            .with_thinking(2048)
+            .with_tool_choice(ToolChoiceMode::Any);
+        assert_tool_choice_conflict(&provider, echo_tool(), "Extended thinking");
+
+        let provider = choice_test_provider()
+            .with_thinking(2048)
+            .with_tool_choice(ToolChoiceMode::Tool("echo".to_string()));
+        assert_tool_choice_conflict(&provider, echo_tool(), "Extended thinking");
+    }
+
+    #[test]
+    fn test_tool_choice_none_rejects_parallel_tool_use() {
+        let provider = choice_test_provider()
+            .with_tool_choice(ToolChoiceMode::None)
+            .with_parallel_tool_use(true);
+        assert_tool_choice_conflict(&provider, echo_tool(), "with_parallel_tool_use");
+    }
+
+    #[test]
+    fn test_forced_tool_choice_requires_tools() {
+        let provider = choice_test_provider().with_tool_choice(ToolChoiceMode::Any);
+        assert_tool_choice_conflict(&provider, Vec::new(), "at least one tool");
+
+        let provider =
+            choice_test_provider().with_tool_choice(ToolChoiceMode::Tool("search".to_string()));
+        assert_tool_choice_conflict(&provider, echo_tool(), "'search'");
+    }
+
+    /// A streamed assistant turn with one content block
+    fn sse_turn(block: serde_json::Value, delta: serde_json::Value, stop: &str) -> String {
+        sse_turn_with_deltas(block, &[delta], stop)
+    }
+
+    /// A streamed assistant turn with one content block split across deltas
+    fn sse_turn_with_deltas(
+        block: serde_json::Value,
+        deltas: &[serde_json::Value],
+        stop: &str,
+    ) -> String {
+        let mut events = vec![
+            serde_json::json!({"type": "message_start", "message": {
+                "id": "msg_test", "type": "message", "role": "assistant", "content": [],
+                "model": "claude-test-model", "stop_reason": null, "stop_sequence": null,
+                "usage": {"input_tokens": 3, "output_tokens": 1}}}),
+            serde_json::json!({"type": "content_block_start", "index": 0, "content_block": block}),
+        ];
+        events.extend(deltas.iter().map(
+            |delta| serde_json::json!({"type": "content_block_delta", "index": 0, "delta": delta}),
+        ));
+        events.extend([
+            serde_json::json!({"type": "content_block_stop", "index": 0}),
+            serde_json::json!({"type": "message_delta",
+                "delta": {"stop_reason": stop, "stop_sequence": null},
+                "usage": {"output_tokens": 5}}),
+            serde_json::json!({"type": "message_stop"}),
+        ]);
+        events
+            .iter()
+            .map(|event| {
+                format!(
+                    "event: {}\ndata: {}\n\n",
+                    event["type"].as_str().unwrap(),
+                    event
+                )
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_agent_streams_tool_args_before_tool_requested() {
+        use crate::events::AgentEvent;
+        use crate::test_utils::EventCollector;
+        use crate::tool::{Tool, ToolError, ToolResult};
+        use crate::Agent;
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        #[derive(serde::Deserialize, schemars::JsonSchema)]
+        struct SearchInput {
+            query: String,
+        }
+
+        struct SearchTool;
+
+        impl Tool for SearchTool {
+            type Input = SearchInput;
+
+            fn name(&self) -> &str {
+                "search"
+            }
+
+            fn description(&self) -> &str {
+                "Searches the web"
+            }
+
+            async fn execute(&self, input: Self::Input) -> Result<ToolResult, ToolError> {
+                Ok(ToolResult::text(format!("results for {}", input.query)))
+            }
+        }
+
+        let mock_server = MockServer::start().await;
+        let turns = [
+            sse_turn_with_deltas(
+                serde_json::json!({"type": "tool_use", "id": "toolu_1", "name": "search", "input": {}}),
+                &[
+                    serde_json::json!({"type": "input_json_delta", "partial_json": "{\"query\": "}),
+                    serde_json::json!({"type": "input_json_delta", "partial_json": "\"rust "}),
+                    serde_json::json!({"type": "input_json_delta", "partial_json": "async\"}"}),
+                ],
+                "tool_use",
+            ),
+            sse_turn(
+                serde_json::json!({"type": "text", "text": ""}),
+                serde_json::json!({"type": "text_delta", "text": "Found it"}),
+                "end_turn",
+            ),
+        ];
+        for turn in turns {
+            Mock::given(method("POST"))
+                .and(path("/v1/messages"))
+                .respond_with(ResponseTemplate::new(200).set_body_raw(turn, "text/event-stream"))
+                .up_to_n_times(1)
+                .mount(&mock_server)
+                .await;
+        }
+
+        let client = Anthropic::builder()
+            .api_key("test-key")
+            .api_base(mock_server.uri())
+            .build()
+            .unwrap();
+        let provider = AnthropicProvider::with_client(
+            client,
+            TestModel {
+                name: "Test Model",
+                anthropic_id: "claude-test-model",
+            },
+        );
+        let agent = Agent::builder()
+            .provider(provider)
+            .add_trusted_tool(SearchTool)
+            .build()
+            .await
+            .unwrap();
+        let collector = EventCollector::new();
+        agent.add_hook(collector.clone());
+
+        agent.run("Search for rust async").await.unwrap();
+
+        let events = collector.events();
+        let requested = events
+            .iter()
+            .position(|e| matches!(e, AgentEvent::ToolRequested { .. }))
+            .unwrap();
+        let deltas: Vec<_> = events[..requested]
+            .iter()
+            .filter_map(|e| match e {
+                AgentEvent::ToolArgsDelta {
+                    tool_use_id,
+                    partial_json,
+                } => {
+                    assert_eq!(tool_use_id, "toolu_1");
+                    Some(partial_json.as_str())
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(deltas.len(), 3);
+        assert_eq!(deltas.concat(), r#"{"query": "rust async"}"#);
+        assert!(!events[requested..]
+            .iter()
+            .any(|e| matches!(e, AgentEvent::ToolArgsDelta { .. })));
+
+        match &events[requested] {
+            AgentEvent::ToolRequested { input, .. } => {
+                assert_eq!(input, &serde_json::json!({"query": "rust async"}));
+            }
+            other => panic!("Expected ToolRequested, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tool_json_recovery_disables_tool_use_for_one_call() {
+        use crate::tool::{Tool, ToolError, ToolResult};
+        use crate::Agent;
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        #[derive(serde::Deserialize, schemars::JsonSchema)]
+        struct CalculateInput {
+            expression: String,
+        }
+
+        struct CalculateTool;
+
+        impl Tool for CalculateTool {
+            type Input = CalculateInput;
+
+            fn name(&self) -> &str {
+                "calculate"
+            }
+
+            fn description(&self) -> &str {
+                "Evaluates an arithmetic expression"
+            }
+
+            async fn execute(&self, input: Self::Input) -> Result<ToolResult, ToolError> {
+                Ok(ToolResult::text(input.expression))
+            }
+        }
+
+        let mock_server = MockServer::start().await;
+        let text_turn = |text: &str| {
+            sse_turn(
+                serde_json::json!({"type": "text", "text": ""}),
+                serde_json::json!({"type": "text_delta", "text": text}),
+                "end_turn",
+            )
+        };
+        let turns = [
+            // Input that is not a JSON object
+            sse_turn(
+                serde_json::json!({"type": "tool_use", "id": "toolu_1", "name": "calculate", "input": {}}),
+                serde_json::json!({"type": "input_json_delta", "partial_json": "\"2+2\""}),
+                "tool_use",
+            ),
+            text_turn("I could not call the tool"),
+            text_turn("Ask me again"),
+        ];
+        for turn in turns {
+            Mock::given(method("POST"))
+                .and(path("/v1/messages"))
+                .respond_with(ResponseTemplate::new(200).set_body_raw(turn, "text/event-stream"))
+                .up_to_n_times(1)
+                .mount(&mock_server)
+                .await;
+        }
+
+        let client = Anthropic::builder()
+            .api_key("test-key")
+            .api_base(mock_server.uri())
+            .build()
+            .unwrap();
+        let provider = AnthropicProvider::with_client(
+            client,
+            TestModel {
+                name: "Test Model",
+                anthropic_id: "claude-test-model",
+            },
+        );
+        let agent = Agent::builder()
+            .provider(provider)
+            .add_trusted_tool(CalculateTool)
+            .with_tool_json_recovery(1)
+            .build()
+            .await
+            .unwrap();
+
+        agent.run("What is 2+2?").await.unwrap();
+        agent.run("And 3+3?").await.unwrap();
+
+        let tool_choices: Vec<_> = mock_server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|request| {
+                let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+                body["tool_choice"]["type"].as_str().map(str::to_string)
+            })
+            .collect();
+        assert_eq!(tool_choices, vec![None, Some("none".to_string()), None]);
+    }
+
+    #[tokio::test]
+    async fn test_dropped_stream_closes_upstream_connection() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let api_base = format!("http://{}", listener.local_addr().unwrap());
+
+        // Sends the start of a turn, then trickles pings until the client
+        // hangs up; resolves once it sees the connection close
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let turn = sse_turn(
+                serde_json::json!({"type": "text", "text": ""}),
+                serde_json::json!({"type": "text_delta", "text": "Hel"}),
+                "end_turn",
+            );
+            let opening = turn.split("\n\n").take(3).collect::<Vec<_>>().join("\n\n");
+            let head = "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\n\r\n";
+            socket
+                .write_all(format!("{}{}\n\n", head, opening).as_bytes())
+                .await
+                .unwrap();
+
+            let mut buf = [0u8; 4096];
+            loop {
+                tokio::select! {
+                    read = socket.read(&mut buf) => {
+                        if matches!(read, Ok(0) | Err(_)) {
+                            return;
+                        }
+                    }
+                    _ = tokio::time::sleep(Duration::from_millis(20)) => {
+                        let ping = "event: ping\ndata: {\"type\": \"ping\"}\n\n";
+                        if socket.write_all(ping.as_bytes()).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+            }
+        });
+
+        let client = Anthropic::builder()
+            .api_key("test-key")
+            .api_base(api_base)
+            .build()
+            .unwrap();
+        let provider = AnthropicProvider::with_client(
+            client,
+            TestModel {
+                name: "Test Model",
+                anthropic_id: "claude-test-model",
+            },
+        );
+        let mut stream = provider
+            .generate_stream(vec![Message::user("Hi")], Vec::new(), None)
+            .await
+            .unwrap();
+        assert!(matches!(
+            stream.next().await,
+            Some(Ok(StreamEvent::TextDelta(ref text))) if text == "Hel"
+        ));
+
+        drop(stream);
+        tokio::time::timeout(Duration::from_secs(5), server)
+            .await
+            .expect("upstream connection still open after the stream was dropped")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_agent_breakpoints_on_changed_system_prompt() {
+        use crate::{Agent, RequestContext, RequestInterceptor};
+        use std::sync::Mutex;
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        struct SwapSystemPrompt(Arc<Mutex<String>>);
+
+        #[async_trait::async_trait]
+        impl RequestInterceptor for SwapSystemPrompt {
+            async fn before_generate(&self, ctx: &mut RequestContext) {
+                ctx.system_prompt = Some(self.0.lock().unwrap().clone());
+                ctx.tools = vec![ToolDefinition {
+                    name: "echo".to_string(),
+                    description: "Echoes the input back".to_string(),
+                    input_schema: serde_json::json!({"type": "object"}),
+                }];
+            }
+        }
+
+        // Breakpoints stay unless the agent opts in to dropping stale ones
+        let all_marked = vec![(true, true); 3];
+        let stale_unmarked = vec![(true, true), (false, true), (true, true)];
+        for (uncached, expected) in [(false, all_marked), (true, stale_unmarked)] {
+            let mock_server = MockServer::start().await;
+            Mock::given(method("POST"))
+                .and(path("/v1/messages"))
+                .respond_with(ResponseTemplate::new(200).set_body_raw(
+                    sse_turn(
+                        serde_json::json!({"type": "text", "text": ""}),
+                        serde_json::json!({"type": "text_delta", "text": "Done"}),
+                        "end_turn",
+                    ),
+                    "text/event-stream",
+                ))
+                .expect(3)
+                .mount(&mock_server)
+                .await;
+
+            let client = Anthropic::builder()
+                .api_key("test-key")
+                .api_base(mock_server.uri())
+                .build()
+                .unwrap();
+            let provider = AnthropicProvider::with_client(
+                client,
+                TestModel {
+                    name: "Test Model",
+                    anthropic_id: "claude-test-model",
+                },
+            )
+            .with_prompt_caching(CachingStrategy::Auto);
+            // ~1250 tokens, above the 1024 minimum for this model
+            let prompt = Arc::new(Mutex::new("You are a careful assistant. ".repeat(175)));
+            let agent = Agent::builder()
+                .provider(provider)
+                .with_interceptor(SwapSystemPrompt(prompt.clone()))
+                .with_stale_prefix_uncached(uncached)
+                .build()
+                .await
+                .unwrap();
+
+            agent.run("first").await.unwrap();
+            *prompt.lock().unwrap() = "You are a thorough assistant. ".repeat(175);
+            agent.run("second").await.unwrap();
+            agent.run("third").await.unwrap();
+
+            // (system prompt marked, tools marked) for each request sent
+            let marks = mock_server
+                .received_requests()
+                .await
+                .unwrap()
+                .iter()
+                .map(|request| {
+                    let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+                    (
+                        body["system"][0]["cache_control"].is_object(),
+                        body["tools"][0]["cache_control"].is_object(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(marks, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_agent_without_parallel_tool_use_runs_calls_in_sequence() {
+        use crate::tool::{Tool, ToolError, ToolResult};
+        use crate::Agent;
+        use std::sync::Mutex;
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        #[derive(serde::Deserialize, schemars::JsonSchema)]
+        struct EchoInput {
+            message: String,
+        }
+
+        struct EchoTool(Arc<Mutex<Vec<String>>>);
+
+        impl Tool for EchoTool {
+            type Input = EchoInput;
+
+            fn name(&self) -> &str {
+                "echo"
+            }
+
+            fn description(&self) -> &str {
+                "Echoes the input back"
+            }
+
+            async fn execute(&self, input: Self::Input) -> Result<ToolResult, ToolError> {
+                self.0.lock().unwrap().push(input.message.clone());
+                Ok(ToolResult::text(input.message))
+            }
+        }
+
+        fn tool_turn(id: &str, message: &str) -> String {
+            sse_turn(
+                serde_json::json!({"type": "tool_use", "id": id, "name": "echo", "input": {}}),
+                serde_json::json!({"type": "input_json_delta",
+                    "partial_json": serde_json::json!({"message": message}).to_string()}),
+                "tool_use",
+            )
+        }
+
+        let mock_server = MockServer::start().await;
+        let turns = [
+            tool_turn("toolu_1", "first"),
+            tool_turn("toolu_2", "second"),
+            sse_turn(
+                serde_json::json!({"type": "text", "text": ""}),
+                serde_json::json!({"type": "text_delta", "text": "Done"}),
+                "end_turn",
+            ),
+        ];
+        for turn in turns {
+            Mock::given(method("POST"))
+                .and(path("/v1/messages"))
+                .and(body_partial_json(serde_json::json!({
+                    "tool_choice": {"type": "auto", "disable_parallel_tool_use": true}
+                })))
+                .respond_with(ResponseTemplate::new(200).set_body_raw(turn, "text/event-stream"))
+                .up_to_n_times(1)
+                .expect(1)
+                .mount(&mock_server)
+                .await;
+        }
+
+        let client = Anthropic::builder()
+            .api_key("test-key")
+            .api_base(mock_server.uri())
+            .build()
+            .unwrap();
+        let provider = AnthropicProvider::with_client(
+            client,
+            TestModel {
+                name: "Test Model",
+                anthropic_id: "claude-test-model",
+            },
+        );
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let agent = Agent::builder()
+            .provider(provider)
+            .add_trusted_tool(EchoTool(calls.clone()))
+            .with_parallel_tool_use(false)
+            .build()
+            .await
+            .unwrap();
+
+        let response = agent.run("Echo twice").await.unwrap();
+        assert_eq!(response.text(), "Done");
+        assert_eq!(*calls.lock().unwrap(), vec!["first", "second"]);
+    }
+
+    #[tokio::test]
+    async fn test_agent_with_forced_tool_choice_finishes() {
+        use crate::tool::{Tool, ToolError, ToolResult};
+        use crate::Agent;
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        #[derive(serde::Deserialize, schemars::JsonSchema)]
+        struct EchoInput {
+            message: String,
+        }
+
+        struct EchoTool;
+
+        impl Tool for EchoTool {
+            type Input = EchoInput;
+
+            fn name(&self) -> &str {
+                "echo"
+            }
+
+            fn description(&self) -> &str {
+                "Echoes the input back"
+            }
+
+            async fn execute(&self, input: Self::Input) -> Result<ToolResult, ToolError> {
+                Ok(ToolResult::text(input.message))
+            }
+        }
+
+        let mock_server = MockServer::start().await;
+
+        // The first call must use the tool
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(body_partial_json(serde_json::json!({
+                "tool_choice": {"type": "tool", "name": "echo"}
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                sse_turn(
+                    serde_json::json!({"type": "tool_use", "id": "toolu_1", "name": "echo", "input": {}}),
+                    serde_json::json!({"type": "input_json_delta",
+                        "partial_json": "{\"message\": \"hi\"}"}),
+                    "tool_use",
+                ),
+                "text/event-stream",
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        // The call answering the tool result leaves the model free to stop
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(body_partial_json(serde_json::json!({
+                "tool_choice": {"type": "auto"}
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                sse_turn(
+                    serde_json::json!({"type": "text", "text": ""}),
+                    serde_json::json!({"type": "text_delta", "text": "Done"}),
+                    "end_turn",
+                ),
+                "text/event-stream",
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = Anthropic::builder()
+            .api_key("test-key")
+            .api_base(mock_server.uri())
+            .build()
+            .unwrap();
+        let provider = AnthropicProvider::with_client(
+            client,
+            TestModel {
+                name: "Test Model",
+                anthropic_id: "claude-test-model",
+            },
+        )
+        .with_tool_choice(ToolChoiceMode::Tool("echo".to_string()));
+        let agent = Agent::builder()
+            .provider(provider)
+            .add_trusted_tool(EchoTool)
+            .build()
+            .await
+            .unwrap();
+
+        let response = tokio::time::timeout(Duration::from_secs(5), agent.run("Echo hi"))
+            .await
+            .expect("a forced tool choice must not loop forever")
+            .unwrap();
+        assert_eq!(response.text(), "Done");
+        assert_eq!(response.tool_calls.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_agent_sends_tool_image_back_to_model() {
+        use crate::tool::{ImageFormat, Tool, ToolError, ToolResult};
+        use crate::Agent;
+        use base64::Engine;
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        /// A 1x1 transparent PNG
+        const PIXEL_PNG: &[u8] = &[
+            0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0x00, 0x00, 0x0D, 0x49, 0x48,
+            0x44, 0x52, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00,
+            0x00, 0x1F, 0x15, 0xC4, 0x89, 0x00, 0x00, 0x00, 0x0B, 0x49, 0x44, 0x41, 0x54, 0x78,
+            0x9C, 0x63, 0x60, 0x00, 0x02, 0x00, 0x00, 0x05, 0x00, 0x01, 0x7A, 0x5E, 0xAB, 0x3F,
+            0x00, 0x00, 0x00, 0x00, 0x49, 0x45, 0x4E, 0x44, 0xAE, 0x42, 0x60, 0x82,
+        ];
+
+        #[derive(serde::Deserialize, schemars::JsonSchema)]
+        struct ScreenshotInput {}
+
+        struct ScreenshotTool;
+
+        impl Tool for ScreenshotTool {
+            type Input = ScreenshotInput;
+
+            fn name(&self) -> &str {
+                "screenshot"
+            }
+
+            fn description(&self) -> &str {
+                "Captures the screen"
+            }
+
+            async fn execute(&self, _input: Self::Input) -> Result<ToolResult, ToolError> {
+                Ok(ToolResult::image(ImageFormat::Png, PIXEL_PNG.to_vec()))
+            }
+        }
+
+        let mock_server = MockServer::start().await;
+
+        // Second turn: the screenshot must come back as an image block
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(body_partial_json(serde_json::json!({
+                "messages": [{}, {}, {
+                    "role": "user",
+                    "content": [{
+                        "type": "tool_result",
+                        "tool_use_id": "toolu_1",
+                        "content": [{
+                            "type": "image",
+                            "source": {
+                                "type": "base64",
+                                "media_type": "image/png",
+                                "data": base64::engine::general_purpose::STANDARD.encode(PIXEL_PNG)
+                            }
+                        }]
+                    }]
+                }]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                sse_turn(
+                    serde_json::json!({"type": "text", "text": ""}),
+                    serde_json::json!({"type": "text_delta", "text": "A single pixel"}),
+                    "end_turn",
+                ),
+                "text/event-stream",
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        // First turn: ask for a screenshot
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                sse_turn(
+                    serde_json::json!({"type": "tool_use", "id": "toolu_1", "name": "screenshot", "input": {}}),
+                    serde_json::json!({"type": "input_json_delta", "partial_json": "{}"}),
+                    "tool_use",
+                ),
+                "text/event-stream",
+            ))
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = Anthropic::builder()
+            .api_key("test-key")
+            .api_base(mock_server.uri())
+            .build()
+            .unwrap();
+        let provider = AnthropicProvider::with_client(
+            client,
+            TestModel {
+                name: "Test Model",
+                anthropic_id: "claude-test-model",
+            },
+        );
+        let agent = Agent::builder()
+            .provider(provider)
+            .add_trusted_tool(ScreenshotTool)
+            .build()
+            .await
+            .unwrap();
+
+        let response = agent.run("What is on screen?").await.unwrap();
+        assert_eq!(response.text(), "A single pixel");
+    }
+
+    #[test]
+    fn test_builder_service_tier_in_params() {
+        let test_model = TestModel {
+            name: "Test Model",
+            anthropic_id: "claude-test-model",
+        };
+        let provider = AnthropicProvider::new("sk-ant-test", test_model).unwrap();
+
+        let params = provider.build_params(Vec::new(), Vec::new(), None).unwrap();
+        assert!(params.service_tier.is_none());
+
+        let provider = provider.with_service_tier(ServiceTier::StandardOnly);
+        let params = provider.build_params(Vec::new(), Vec::new(), None).unwrap();
+        assert_eq!(params.service_tier, Some(ServiceTier::StandardOnly));
+    }
+
+    #[tokio::test]
+    async fn test_service_tier_reported_in_response() {
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(body_partial_json(
+                serde_json::json!({"service_tier": "auto"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "msg_test",
+                "type": "message",
+                "role": "assistant",
+                "content": [{"type": "text", "text": "Hi"}],
+                "model": "claude-test-model",
+                "stop_reason": "end_turn",
+                "stop_sequence": null,
+                "usage": {"input_tokens": 3, "output_tokens": 1, "service_tier": "priority"}
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = Anthropic::builder()
+            .api_key("test-key")
+            .api_base(mock_server.uri())
+            .build()
+            .unwrap();
+        let provider = AnthropicProvider::with_client(
+            client,
+            TestModel {
+                name: "Test Model",
+                anthropic_id: "claude-test-model",
+            },
+        )
+        .with_service_tier(ServiceTier::Auto);
+
+        let response = provider
+            .generate(vec![Message::user("Hello")], Vec::new(), None)
+            .await
+            .unwrap();
+        assert_eq!(response.service_tier.as_deref(), Some("priority"));
+    }
+
+    #[tokio::test]
+    async fn test_served_model_reported_in_response() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "msg_test",
+                "type": "message",
+                "role": "assistant",
+                "content": [{"type": "text", "text": "Hi"}],
+                "model": "claude-test-20250101",
+                "stop_reason": "end_turn",
+                "stop_sequence": null,
+                "usage": {"input_tokens": 3, "output_tokens": 1}
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = Anthropic::builder()
+            .api_key("test-key")
+            .api_base(mock_server.uri())
+            .build()
+            .unwrap();
+        let provider = AnthropicProvider::with_client(
+            client,
+            TestModel {
+                name: "Test Model",
+                anthropic_id: "claude-test-latest",
+            },
+        );
+
+        let response = provider
+            .generate(vec![Message::user("Hello")], Vec::new(), None)
+            .await
+            .unwrap();
+        assert_eq!(response.model.as_deref(), Some("claude-test-20250101"));
+    }
+
+    #[tokio::test]
+    async fn test_agent_response_reports_streamed_model() {
+        use crate::Agent;
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                sse_turn(
+                    serde_json::json!({"type": "text", "text": ""}),
+                    serde_json::json!({"type": "text_delta", "text": "Hi"}),
+                    "end_turn",
+                ),
+                "text/event-stream",
+            ))
+            .mount(&mock_server)
+            .await;
+
+        let client = Anthropic::builder()
+            .api_key("test-key")
+            .api_base(mock_server.uri())
+            .build()
+            .unwrap();
+        let provider = AnthropicProvider::with_client(
+            client,
+            TestModel {
+                name: "Test Model",
+                anthropic_id: "claude-test-latest",
+            },
+        );
+        let agent = Agent::builder().provider(provider).build().await.unwrap();
+
+        let response = agent.run("Hello").await.unwrap();
+        // The model named in message_start, not the configured alias
+        assert_eq!(response.model.as_deref(), Some("claude-test-model"));
+    }
+
+    #[test]
+    fn test_builder_interleaved_thinking() {
+        let test_model = TestModel {
+            name: "Test Model",
+            anthropic_id: "claude-test-model",
+        };
+        let provider = AnthropicProvider::new("sk-ant-test", test_model)
+            .unwrap()
+            .with_thinking(2048)
+            .with_interleaved_thinking(true)
+            .with_interleaved_thinking(true);
+
+        let params = provider.build_params(Vec::new(), Vec::new(), None).unwrap();
+        assert_eq!(params.betas, Some(vec![BetaFeature::InterleavedThinking]));
+
+        let provider = provider.with_interleaved_thinking(false);
+        let params = provider.build_params(Vec::new(), Vec::new(), None).unwrap();
+        assert!(params.betas.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_interleaved_thinking_requires_thinking() {
+        let test_model = TestModel {
+            name: "Test Model",
+            anthropic_id: "claude-test-model",
+        };
+        let provider = AnthropicProvider::new("sk-ant-test", test_model)
+            .unwrap()
+            .with_interleaved_thinking(true);
+
+        let err = provider
+            .generate(vec![Message::user("Hello")], Vec::new(), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ProviderError::Configuration(_)), "{:?}", err);
+
+        let err = provider
+            .generate_stream(vec![Message::user("Hello")], Vec::new(), None)
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(err, ProviderError::Configuration(_)), "{:?}", err);
+    }
+
+    #[tokio::test]
+    async fn test_interleaved_thinking_survives_tool_round_trip() {
+        use crate::tool::ToolResult;
+        use crate::types::{ContentBlock, ToolResultBlock, ToolResultStatus};
+        use wiremock::matchers::{body_partial_json, header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+
+        // Second turn: the thinking block must be sent back with its signature
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(header("anthropic-beta", "interleaved-thinking-2025-05-14"))
+            .and(body_partial_json(serde_json::json!({
+                "messages": [{}, {
+                    "role": "assistant",
+                    "content": [
+                        {"type": "thinking", "thinking": "Check the weather", "signature": "sig_1"},
+                        {"type": "tool_use", "id": "toolu_1", "name": "weather"}
+                    ]
+                }, {}]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "msg_2",
+                "type": "message",
+                "role": "assistant",
+                "content": [
+                    {"type": "thinking", "thinking": "It is sunny", "signature": "sig_2"},
+                    {"type": "text", "text": "Sunny today"}
+                ],
+                "model": "claude-test-model",
+                "stop_reason": "end_turn",
+                "stop_sequence": null,
+                "usage": {"input_tokens": 20, "output_tokens": 8}
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        // First turn: thinking followed by a tool call
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(header("anthropic-beta", "interleaved-thinking-2025-05-14"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "msg_1",
+                "type": "message",
+                "role": "assistant",
+                "content": [
+                    {"type": "thinking", "thinking": "Check the weather", "signature": "sig_1"},
+                    {"type": "tool_use", "id": "toolu_1", "name": "weather", "input": {}}
+                ],
+                "model": "claude-test-model",
+                "stop_reason": "tool_use",
+                "stop_sequence": null,
+                "usage": {"input_tokens": 10, "output_tokens": 12}
+            })))
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = Anthropic::builder()
+            .api_key("test-key")
+            .api_base(mock_server.uri())
+            .build()
+            .unwrap();
+        let provider = AnthropicProvider::with_client(
+            client,
+            TestModel {
+                name: "Test Model",
+                anthropic_id: "claude-test-model",
+            },
+        )
+        .with_thinking(2048)
+        .with_interleaved_thinking(true);
+
+        let first = provider
+            .generate(vec![Message::user("Weather?")], Vec::new(), None)
+            .await
+            .unwrap();
+        assert_eq!(first.stop_reason, StopReason::ToolUse);
+        assert!(matches!(
+            &first.message.content[0],
+            ContentBlock::Thinking { thinking, signature }
+                if thinking == "Check the weather" && signature == "sig_1"
+        ));
+
+        let tool_result = Message::tool_results(vec![ToolResultBlock {
+            tool_use_id: "toolu_1".to_string(),
+            content: ToolResult::Text("sunny".to_string()),
+            status: ToolResultStatus::Success,
+        }]);
+        let second = provider
+            .generate(
+                vec![Message::user("Weather?"), first.message, tool_result],
+                Vec::new(),
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(second.message.text(), "Sunny today");
+    }
+
+    #[tokio::test]
+    async fn test_file_document_enables_files_api_beta() {
+        use crate::types::{DocumentBlock, DocumentSource};
+        use wiremock::matchers::{body_partial_json, header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(header("anthropic-beta", "files-api-2025-04-14"))
+            .and(body_partial_json(serde_json::json!({
+                "messages": [{
+                    "role": "user",
+                    "content": [
+                        {"type": "document", "source": {"type": "file", "file_id": "file_abc"}},
+                        {"type": "text", "text": "Summarize"}
+                    ]
+                }]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "msg_test",
+                "type": "message",
+                "role": "assistant",
+                "content": [{"type": "text", "text": "A summary"}],
+                "model": "claude-test-model",
+                "stop_reason": "end_turn",
+                "stop_sequence": null,
+                "usage": {"input_tokens": 3, "output_tokens": 2}
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = Anthropic::builder()
+            .api_key("test-key")
+            .api_base(mock_server.uri())
+            .build()
+            .unwrap();
+        let provider = AnthropicProvider::with_client(
+            client,
+            TestModel {
+                name: "Test Model",
+                anthropic_id: "claude-test-model",
+            },
+        );
+
+        let document = DocumentBlock {
+            format: DocumentFormat::Pdf,
+            source: DocumentSource::File("file_abc".to_string()),
+            name: None,
+        };
+        let response = provider
+            .generate(
+                vec![Message::user_with_document(document, "Summarize")],
+                Vec::new(),
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.message.text(), "A summary");
+    }
+
+    #[tokio::test]
+    async fn test_generate_extracts_document_citations() {
+        use crate::types::{Citation, CitationLocation, CitationSource, ContentBlock};
+        use crate::types::{DocumentBlock, DocumentSource};
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(body_partial_json(serde_json::json!({
+                "messages": [{
+                    "content": [{"type": "document", "citations": {"enabled": true}}, {}]
+                }]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "msg_test",
+                "type": "message",
+                "role": "assistant",
+                "content": [
+                    {"type": "text", "text": "According to the report, "},
+                    {"type": "text", "text": "revenue grew 12%", "citations": [{
+                        "type": "page_location",
+                        "cited_text": "Revenue grew 12% year over year.",
+                        "document_index": 0,
+                        "document_title": "report.pdf",
+                        "start_page_number": 3,
+                        "end_page_number": 4
+                    }]},
+                    {"type": "text", "text": "."}
+                ],
+                "model": "claude-test-model",
+                "stop_reason": "end_turn",
+                "stop_sequence": null,
+                "usage": {"input_tokens": 30, "output_tokens": 12}
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = Anthropic::builder()
+            .api_key("test-key")
+            .api_base(mock_server.uri())
+            .build()
+            .unwrap();
+        let provider = AnthropicProvider::with_client(
+            client,
+            TestModel {
+                name: "Test Model",
+                anthropic_id: "claude-test-model",
+            },
+        )
+        .with_citations(true);
+
+        let document = DocumentBlock {
+            format: DocumentFormat::Pdf,
+            source: DocumentSource::Bytes(b"%PDF-1.4".to_vec()),
+            name: Some("report.pdf".to_string()),
+        };
+        let response = provider
+            .generate(
+                vec![Message::user_with_document(
+                    document,
+                    "How did revenue change?",
+                )],
+                Vec::new(),
+                None,
+            )
+            .await
+            .unwrap();
+
+        let message = &response.message;
+        assert_eq!(message.text(), "According to the report, revenue grew 12%.");
+        assert!(matches!(
+            &message.content[2],
+            ContentBlock::Citations { .. }
+        ));
+        assert_eq!(
+            message.citations(),
+            vec![&Citation {
+                source: CitationSource::Document {
+                    index: 0,
+                    title: Some("report.pdf".to_string()),
+                },
+                cited_text: "Revenue grew 12% year over year.".to_string(),
+                location: CitationLocation::Pages { start: 3, end: 4 },
+            }]
+        );
+
+        // Citations are not sent back in later turns
+        let param = to_anthropic_message(message).unwrap();
+        match param.content {
+            mixtape_anthropic_sdk::MessageContent::Blocks(blocks) => assert_eq!(blocks.len(), 3),
+            _ => panic!("Expected blocks content"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_agent_response_collects_streamed_citations() {
+        use crate::types::{CitationLocation, CitationSource};
+        use crate::Agent;
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                sse_turn_with_deltas(
+                    serde_json::json!({"type": "text", "text": "", "citations": []}),
+                    &[
+                        serde_json::json!({"type": "citations_delta", "citation": {
+                            "type": "char_location",
+                            "cited_text": "The grass is green.",
+                            "document_index": 0,
+                            "document_title": null,
+                            "start_char_index": 0,
+                            "end_char_index": 19
+                        }}),
+                        serde_json::json!({"type": "citations_delta", "citation": {
+                            "type": "web_search_result_location",
+                            "cited_text": "Grass is green because of chlorophyll.",
+                            "url": "https://example.com/grass",
+                            "title": "Why grass is green",
+                            "encrypted_index": "abc"
+                        }}),
+                        serde_json::json!({"type": "text_delta", "text": "The grass is green."}),
+                    ],
+                    "end_turn",
+                ),
+                "text/event-stream",
+            ))
+            .mount(&mock_server)
+            .await;
+
+        let client = Anthropic::builder()
+            .api_key("test-key")
+            .api_base(mock_server.uri())
+            .build()
+            .unwrap();
+        let provider = AnthropicProvider::with_client(
+            client,
+            TestModel {
+                name: "Test Model",
+                anthropic_id: "claude-test-model",
+            },
+        );
+        let agent = Agent::builder().provider(provider).build().await.unwrap();
+
+        let response = agent.run("What color is grass?").await.unwrap();
+        assert_eq!(response.text(), "The grass is green.");
+
+        let citations = response.citations();
+        assert_eq!(citations.len(), 2);
+        assert_eq!(
+            citations[0].source,
+            CitationSource::Document {
+                index: 0,
+                title: None
+            }
+        );
+        assert_eq!(
+            citations[0].location,
+            CitationLocation::Chars { start: 0, end: 19 }
+        );
+        assert_eq!(
+            citations[1].source,
+            CitationSource::WebSearchResult {
+                url: "https://example.com/grass".to_string(),
+                title: Some("Why grass is green".to_string()),
+            }
+        );
+        assert_eq!(citations[1].location, CitationLocation::Whole);
+    }
+
     #[test]
     fn test_builder_max_retries() {
         let test_model = TestModel {
@@ -691,6 +3082,35 @@ mod tests {
         assert_eq!(provider.retry_config.base_delay_ms, 250);
     }
 
+    #[test]
+    fn test_builder_retry_jitter() {
+        let test_model = TestModel {
+            name: "Test Model",
+            anthropic_id: "claude-test-model",
+        };
+        let provider = AnthropicProvider::new("sk-ant-test", test_model)
+            .unwrap()
+            .with_retry_jitter(JitterStrategy::Equal);
+
+        assert_eq!(provider.retry_config.jitter, JitterStrategy::Equal);
+    }
+
+    #[test]
+    fn test_builder_retry_budget() {
+        let test_model = TestModel {
+            name: "Test Model",
+            anthropic_id: "claude-test-model",
+        };
+        let provider = AnthropicProvider::new("sk-ant-test", test_model)
+            .unwrap()
+            .with_retry_budget(Duration::from_secs(5));
+
+        assert_eq!(
+            provider.retry_config.max_total_delay,
+            Some(Duration::from_secs(5))
+        );
+    }
+
     #[test]
     fn test_builder_retry_callback() {
         use std::sync::atomic::{AtomicBool, Ordering};
@@ -731,7 +3151,7 @@ mod tests {
 
         assert_eq!(cloned.model_id, provider.model_id);
         assert_eq!(cloned.model_name, provider.model_name);
-        assert_eq!(cloned.max_tokens, provider.max_tokens);
+        assert_eq!(cloned.max_tokens(), provider.max_tokens());
         assert_eq!(cloned.temperature, provider.temperature);
         assert_eq!(cloned.top_p, provider.top_p);
         assert_eq!(cloned.top_k, provider.top_k);
@@ -747,7 +3167,7 @@ mod tests {
         let provider = AnthropicProvider::new("sk-ant-test", test_model).unwrap();
 
         // Default max tokens
-        assert_eq!(provider.max_tokens, DEFAULT_MAX_TOKENS);
+        assert_eq!(provider.max_tokens(), 32_000);
         // No temperature by default
         assert!(provider.temperature.is_none());
         // No top_p by default
@@ -772,6 +3192,53 @@ mod tests {
         assert_eq!(provider.max_output_tokens(), 64_000);
     }
 
+    #[tokio::test]
+    async fn test_count_tokens_calls_api() {
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages/count_tokens"))
+            .and(body_partial_json(serde_json::json!({
+                "model": "claude-test-model",
+                "system": "Be brief",
+                "tools": [{"name": "echo"}]
+            })))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "input_tokens": 1234 })),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let client = Anthropic::builder()
+            .api_key("test-key")
+            .api_base(mock_server.uri())
+            .build()
+            .unwrap();
+        let provider = AnthropicProvider::with_client(
+            client,
+            TestModel {
+                name: "Test Model",
+                anthropic_id: "claude-test-model",
+            },
+        );
+
+        let tools = vec![ToolDefinition {
+            name: "echo".to_string(),
+            description: "Echo input".to_string(),
+            input_schema: serde_json::json!({"type": "object"}),
+        }];
+        let count = provider
+            .count_tokens(&[Message::user("Hello")], &tools, Some("Be brief"))
+            .await
+            .unwrap();
+
+        assert_eq!(count, 1234);
+    }
+
     // ===== Error Classification Tests =====
 
     #[test]
@@ -0,0 +1,90 @@
+//! Assembly of stream events into a complete response
+
+use super::StreamEvent;
+use crate::events::TokenUsage;
+use crate::model::ModelResponse;
+use crate::types::{ContentBlock, Message, Role, StopReason};
+
+/// Builds the response a stream describes, one event at a time
+///
+/// Content blocks keep the order the model produced them, so the assembled
+/// message matches what a non-streaming call would return. Events that carry
+/// no content (deltas of tool input or thinking, repair notices) are ignored.
+pub(crate) struct ResponseAssembler {
+    content: Vec<ContentBlock>,
+    stop_reason: StopReason,
+    stop_sequence: Option<String>,
+    usage: Option<TokenUsage>,
+    service_tier: Option<String>,
+    model: Option<String>,
+}
+
+impl ResponseAssembler {
+    pub(crate) fn new() -> Self {
+        Self {
+            content: Vec::new(),
+            stop_reason: StopReason::EndTurn,
+            stop_sequence: None,
+            usage: None,
+            service_tier: None,
+            model: None,
+        }
+    }
+
+    /// Fold one stream event into the response
+    pub(crate) fn push(&mut self, event: StreamEvent) {
+        match event {
+            StreamEvent::TextDelta(delta) => match self.content.last_mut() {
+                Some(ContentBlock::Text(text)) => text.push_str(&delta),
+                _ if delta.is_empty() => {}
+                _ => self.content.push(ContentBlock::Text(delta)),
+            },
+            StreamEvent::ToolUse(tool_use) => self.content.push(ContentBlock::ToolUse(tool_use)),
+            StreamEvent::Thinking {
+                thinking,
+                signature,
+            } => self.content.push(ContentBlock::Thinking {
+                thinking,
+                signature,
+            }),
+            StreamEvent::Citation(citation) => match self.content.last_mut() {
+                Some(ContentBlock::Citations { citations }) => citations.push(citation),
+                _ => self.content.push(ContentBlock::Citations {
+                    citations: vec![citation],
+                }),
+            },
+            StreamEvent::Stop {
+                stop_reason,
+                stop_sequence,
+                usage,
+                service_tier,
+                model,
+            } => {
+                self.stop_reason = stop_reason;
+                self.stop_sequence = stop_sequence;
+                self.usage = usage;
+                self.service_tier = service_tier;
+                self.model = model;
+            }
+            StreamEvent::ToolInputDelta { .. }
+            | StreamEvent::ToolInputRepaired { .. }
+            | StreamEvent::ToolInputInvalid { .. }
+            | StreamEvent::ThinkingDelta(_) => {}
+        }
+    }
+
+    /// The assembled response
+    pub(crate) fn finish(self) -> ModelResponse {
+        ModelResponse {
+            message: Message {
+                role: Role::Assistant,
+                content: self.content,
+            },
+            stop_reason: self.stop_reason,
+            stop_sequence: self.stop_sequence,
+            usage: self.usage,
+            service_tier: self.service_tier,
+            model: self.model,
+        }
+    }
+}
@@ -11,8 +11,9 @@ use aws_sdk_bedrockruntime::{
         ContentBlock as BedrockContentBlock, ConversationRole, DocumentBlock,
         DocumentFormat as BedrockDocFormat, DocumentSource, ImageBlock,
         ImageFormat as BedrockImageFormat, ImageSource, Message as BedrockMessage,
-        Tool as BedrockTool, ToolInputSchema, ToolResultBlock as BedrockToolResultBlock,
-        ToolResultContentBlock, ToolResultStatus as BedrockToolResultStatus, ToolSpecification,
+        ReasoningContentBlock, ReasoningTextBlock, Tool as BedrockTool, ToolInputSchema,
+        ToolResultBlock as BedrockToolResultBlock, ToolResultContentBlock,
+        ToolResultStatus as BedrockToolResultStatus, ToolSpecification,
         ToolUseBlock as BedrockToolUseBlock,
     },
 };
@@ -20,6 +21,10 @@ use aws_smithy_types::Document;
 
 // ===== Type Conversion: Mixtape -> Bedrock =====
 
+/// Text sent for a tool result with no output, since Bedrock rejects
+/// blank text blocks
+const EMPTY_TOOL_RESULT_TEXT: &str = "ok";
+
 pub fn to_bedrock_message(msg: &Message) -> Result<BedrockMessage, ProviderError> {
     let role = match msg.role {
         Role::User => ConversationRole::User,
@@ -29,6 +34,8 @@ pub fn to_bedrock_message(msg: &Message) -> Result<BedrockMessage, ProviderError
     let content: Vec<BedrockContentBlock> = msg
         .content
         .iter()
+        // Citations annotate the model's own text and are not sent back
+        .filter(|block| !matches!(block, ContentBlock::Citations { .. }))
         .map(to_bedrock_content_block)
         .collect::<Result<Vec<_>, _>>()?;
 
@@ -54,6 +61,9 @@ fn to_bedrock_content_block(block: &ContentBlock) -> Result<BedrockContentBlock,
         }
         ContentBlock::ToolResult(result) => {
             let content = match &result.content {
+                ToolResult::Text(text) if text.trim().is_empty() => {
+                    ToolResultContentBlock::Text(EMPTY_TOOL_RESULT_TEXT.to_string())
+                }
                 ToolResult::Text(text) => ToolResultContentBlock::Text(text.clone()),
                 ToolResult::Json(json) => ToolResultContentBlock::Json(json_to_document(json)),
                 ToolResult::Image { format, data } => {
@@ -88,14 +98,76 @@ fn to_bedrock_content_block(block: &ContentBlock) -> Result<BedrockContentBlock,
                 .map_err(|e| ProviderError::Configuration(e.to_string()))?;
             Ok(BedrockContentBlock::ToolResult(block))
         }
+        ContentBlock::Thinking {
+            thinking,
+            signature,
+        } if !thinking.is_empty() && !signature.is_empty() => {
+            // Signed reasoning must be sent back unmodified for multi-turn thinking
+            let block = ReasoningTextBlock::builder()
+                .text(thinking)
+                .signature(signature)
+                .build()
+                .map_err(|e| ProviderError::Configuration(e.to_string()))?;
+            Ok(BedrockContentBlock::ReasoningContent(
+                ReasoningContentBlock::ReasoningText(block),
+            ))
+        }
         ContentBlock::Thinking { thinking, .. } => {
-            // Pass thinking blocks as text for multi-turn conversations
-            // Bedrock handles thinking through additionalModelRequestFields
+            // Unsigned thinking (e.g. from another provider) is passed as text
             Ok(BedrockContentBlock::Text(format!(
                 "<thinking>{}</thinking>",
                 thinking
             )))
         }
+        ContentBlock::Document(document) => {
+            let data = match &document.source {
+                crate::types::DocumentSource::Bytes(data) => data.clone(),
+                crate::types::DocumentSource::File(file_id) => {
+                    return Err(ProviderError::Configuration(format!(
+                        "Bedrock does not support Files API document references ({}); \
+                         pass the document bytes instead",
+                        file_id
+                    )))
+                }
+            };
+            let name = bedrock_document_name(document.name.as_deref().unwrap_or("document"));
+            let block = DocumentBlock::builder()
+                .format(to_bedrock_doc_format(document.format))
+                .source(DocumentSource::Bytes(Blob::new(data)))
+                .name(name)
+                .build()
+                .map_err(|e| ProviderError::Configuration(e.to_string()))?;
+            Ok(BedrockContentBlock::Document(block))
+        }
+        ContentBlock::Citations { .. } => Err(ProviderError::Configuration(
+            "citations cannot be sent to the model".to_string(),
+        )),
+    }
+}
+
+/// Make a document name acceptable to Bedrock
+///
+/// Bedrock only allows alphanumerics, single spaces, hyphens, parentheses,
+/// and square brackets, so a filename like `report.pdf` becomes `report-pdf`.
+fn bedrock_document_name(name: &str) -> String {
+    let mut sanitized = String::with_capacity(name.len());
+    for c in name.chars() {
+        let c = if c.is_ascii_alphanumeric() || "-()[]".contains(c) {
+            c
+        } else if c.is_whitespace() {
+            ' '
+        } else {
+            '-'
+        };
+        if !(c == ' ' && sanitized.ends_with(' ')) {
+            sanitized.push(c);
+        }
+    }
+    let sanitized = sanitized.trim();
+    if sanitized.is_empty() {
+        "document".to_string()
+    } else {
+        sanitized.to_string()
     }
 }
 
@@ -191,7 +263,13 @@ fn from_bedrock_content_block(block: &BedrockContentBlock) -> Option<ContentBloc
                 input,
             }))
         }
-        _ => None, // Skip other content types (images, etc.)
+        BedrockContentBlock::ReasoningContent(ReasoningContentBlock::ReasoningText(reasoning)) => {
+            Some(ContentBlock::Thinking {
+                thinking: reasoning.text().to_string(),
+                signature: reasoning.signature().unwrap_or_default().to_string(),
+            })
+        }
+        _ => None, // Skip other content types (images, redacted reasoning, etc.)
     }
 }
 
@@ -223,12 +301,29 @@ pub fn from_bedrock_stop_reason(reason: &aws_sdk_bedrockruntime::types::StopReas
         aws_sdk_bedrockruntime::types::StopReason::EndTurn => StopReason::EndTurn,
         aws_sdk_bedrockruntime::types::StopReason::ToolUse => StopReason::ToolUse,
         aws_sdk_bedrockruntime::types::StopReason::MaxTokens => StopReason::MaxTokens,
-        aws_sdk_bedrockruntime::types::StopReason::ContentFiltered => StopReason::ContentFiltered,
+        aws_sdk_bedrockruntime::types::StopReason::ContentFiltered
+        | aws_sdk_bedrockruntime::types::StopReason::GuardrailIntervened => {
+            StopReason::ContentFiltered
+        }
         aws_sdk_bedrockruntime::types::StopReason::StopSequence => StopReason::StopSequence,
         _ => StopReason::Unknown,
     }
 }
 
+/// The stop sequence a response matched, from its additional response fields
+///
+/// Claude models on Bedrock report it as `stop_sequence`; other models
+/// report nothing.
+pub fn stop_sequence_from_fields(fields: Option<&Document>) -> Option<String> {
+    match fields? {
+        Document::Object(fields) => match fields.get("stop_sequence")? {
+            Document::String(sequence) => Some(sequence.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -337,6 +432,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_content_block_tool_result_empty_text_conversion() {
+        let block = ContentBlock::ToolResult(ToolResultBlock {
+            tool_use_id: "tool_progress".to_string(),
+            content: ToolResult::Text(String::new()),
+            status: ToolResultStatus::Success,
+        });
+
+        let BedrockContentBlock::ToolResult(tr) = to_bedrock_content_block(&block).unwrap() else {
+            panic!("Expected ToolResult block");
+        };
+        match tr.content() {
+            [ToolResultContentBlock::Text(text)] => assert_eq!(text, "ok"),
+            other => panic!("Expected one Text content block, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_content_block_tool_result_json_conversion() {
         let result = ToolResultBlock {
@@ -496,6 +608,51 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_content_block_document_conversion() {
+        use crate::tool::DocumentFormat;
+        use crate::types::DocumentBlock;
+
+        let block = ContentBlock::Document(DocumentBlock {
+            format: DocumentFormat::Pdf,
+            source: crate::types::DocumentSource::Bytes(b"%PDF-1.4".to_vec()),
+            name: Some("Q3 report.final.pdf".to_string()),
+        });
+
+        let BedrockContentBlock::Document(doc) = to_bedrock_content_block(&block).unwrap() else {
+            panic!("Expected Document block");
+        };
+        assert_eq!(doc.format(), &BedrockDocFormat::Pdf);
+        assert_eq!(doc.name(), "Q3 report-final-pdf");
+        match doc.source() {
+            Some(DocumentSource::Bytes(blob)) => assert_eq!(blob.as_ref(), b"%PDF-1.4"),
+            other => panic!("Expected bytes source, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_content_block_document_file_reference_rejected() {
+        use crate::tool::DocumentFormat;
+        use crate::types::DocumentBlock;
+
+        let block = ContentBlock::Document(DocumentBlock {
+            format: DocumentFormat::Pdf,
+            source: crate::types::DocumentSource::File("file_abc".to_string()),
+            name: None,
+        });
+
+        let err = to_bedrock_content_block(&block).unwrap_err();
+        assert!(err.to_string().contains("Files API"));
+    }
+
+    #[test]
+    fn test_bedrock_document_name_sanitized() {
+        assert_eq!(bedrock_document_name("notes"), "notes");
+        assert_eq!(bedrock_document_name("a  b\tc"), "a b c");
+        assert_eq!(bedrock_document_name("(draft) [v2]"), "(draft) [v2]");
+        assert_eq!(bedrock_document_name("   "), "document");
+    }
+
     // ===== Image Format Conversion Tests =====
 
     #[test]
@@ -628,6 +785,29 @@ mod tests {
             from_bedrock_stop_reason(&BedrockStopReason::StopSequence),
             StopReason::StopSequence
         );
+        assert_eq!(
+            from_bedrock_stop_reason(&BedrockStopReason::GuardrailIntervened),
+            StopReason::ContentFiltered
+        );
+    }
+
+    #[test]
+    fn test_stop_reason_conversion_unmapped_variants() {
+        use aws_sdk_bedrockruntime::types::StopReason as BedrockStopReason;
+
+        for reason in [
+            BedrockStopReason::MalformedModelOutput,
+            BedrockStopReason::MalformedToolUse,
+            BedrockStopReason::ModelContextWindowExceeded,
+            BedrockStopReason::from("something_new"),
+        ] {
+            assert_eq!(
+                from_bedrock_stop_reason(&reason),
+                StopReason::Unknown,
+                "{:?}",
+                reason
+            );
+        }
     }
 
     // ===== Role Conversion Tests =====
@@ -662,7 +842,26 @@ mod tests {
 
         let bedrock_block = to_bedrock_content_block(&block).unwrap();
 
-        // Thinking blocks are converted to text with <thinking> tags for Bedrock
+        // Signed thinking blocks are sent back as reasoning content
+        match bedrock_block {
+            BedrockContentBlock::ReasoningContent(ReasoningContentBlock::ReasoningText(r)) => {
+                assert_eq!(r.text(), "Let me analyze this problem...");
+                assert_eq!(r.signature(), Some("sig_abc123"));
+            }
+            _ => panic!("Expected ReasoningContent block for thinking"),
+        }
+    }
+
+    #[test]
+    fn test_content_block_unsigned_thinking_conversion() {
+        let block = ContentBlock::Thinking {
+            thinking: "Let me analyze this problem...".to_string(),
+            signature: String::new(),
+        };
+
+        let bedrock_block = to_bedrock_content_block(&block).unwrap();
+
+        // Unsigned thinking blocks are converted to text with <thinking> tags
         match bedrock_block {
             BedrockContentBlock::Text(text) => {
                 assert!(text.contains("<thinking>"));
@@ -673,6 +872,44 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_from_bedrock_reasoning_content() {
+        let reasoning = ReasoningTextBlock::builder()
+            .text("Step by step")
+            .signature("sig_bedrock")
+            .build()
+            .unwrap();
+        let bedrock_msg = BedrockMessage::builder()
+            .role(ConversationRole::Assistant)
+            .content(BedrockContentBlock::ReasoningContent(
+                ReasoningContentBlock::ReasoningText(reasoning),
+            ))
+            .content(BedrockContentBlock::Text("Answer".to_string()))
+            .build()
+            .unwrap();
+
+        let msg = from_bedrock_message(&bedrock_msg);
+        assert_eq!(msg.content.len(), 2);
+        match &msg.content[0] {
+            ContentBlock::Thinking {
+                thinking,
+                signature,
+            } => {
+                assert_eq!(thinking, "Step by step");
+                assert_eq!(signature, "sig_bedrock");
+            }
+            other => panic!("Expected Thinking block, got {:?}", other),
+        }
+        assert_eq!(msg.text(), "Answer");
+
+        // Round-trips back to reasoning content
+        let back = to_bedrock_message(&msg).unwrap();
+        assert!(matches!(
+            &back.content()[0],
+            BedrockContentBlock::ReasoningContent(_)
+        ));
+    }
+
     // ===== JSON/Document Number Edge Cases =====
 
     #[test]
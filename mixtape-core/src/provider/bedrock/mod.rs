@@ -1,31 +1,90 @@
 //! AWS Bedrock provider implementation
 
 mod conversion;
+mod stream;
 
-use super::retry::{retry_with_backoff, RetryCallback, RetryConfig, RetryInfo};
-use super::{ModelProvider, ProviderError, StreamEvent};
+use super::retry::{retry_with_backoff, JitterStrategy, RetryCallback, RetryConfig, RetryInfo};
+use super::{
+    check_max_tokens, check_top_k, check_unit_range, clamp_max_tokens, clamp_unit_range,
+    default_max_tokens, estimate_request_tokens, Feature, ModelProvider, ProviderError,
+    ProviderSettings, SamplingParams, StreamEvent, DEFAULT_MAX_TOKENS_CAP,
+};
 use crate::events::TokenUsage;
 use crate::model::{BedrockModel, ModelResponse};
-use crate::types::{Message, ThinkingConfig, ToolDefinition, ToolUseBlock};
+use crate::tool::DocumentFormat;
+use crate::types::{Message, ThinkingConfig, ToolDefinition};
 use aws_sdk_bedrockruntime::error::SdkError;
 use aws_sdk_bedrockruntime::{
     operation::converse::ConverseOutput,
     operation::converse_stream::ConverseStreamOutput as StreamOutputResult,
+    operation::count_tokens::CountTokensError,
     types::{
-        ContentBlockDelta, ContentBlockStart, ConverseStreamOutput, Message as BedrockMessage,
-        SystemContentBlock, Tool as BedrockTool, ToolConfiguration,
+        ConverseTokensRequest, CountTokensInput, Message as BedrockMessage, SystemContentBlock,
+        Tool as BedrockTool, ToolConfiguration,
     },
     Client,
 };
 use conversion::{
-    from_bedrock_message, from_bedrock_stop_reason, json_to_document, to_bedrock_message,
-    to_bedrock_tool,
+    from_bedrock_message, from_bedrock_stop_reason, json_to_document, stop_sequence_from_fields,
+    to_bedrock_message, to_bedrock_tool,
 };
 use futures::stream::BoxStream;
 use std::collections::HashMap;
 use std::error::Error as StdError;
 use std::sync::Arc;
 use std::time::Duration;
+use stream::ConverseStreamState;
+
+/// Additional request field that carries Anthropic beta flags
+const ANTHROPIC_BETA_KEY: &str = "anthropic_beta";
+/// Additional request field that carries the sampling seed
+const SEED_KEY: &str = "seed";
+/// Model ID prefixes of the models known to accept [`SEED_KEY`]
+///
+/// Converse rejects additional fields a model doesn't take, so a seed is
+/// only sent to these.
+const SEED_MODEL_PREFIXES: &[&str] = &["cohere.command-r"];
+
+/// Anthropic beta features for Claude models on Bedrock
+///
+/// Sent in the `anthropic_beta` array of `additionalModelRequestFields`.
+/// Enable with [`BedrockProvider::with_beta`]. Betas not listed here can
+/// still be sent with [`BedrockProvider::with_additional_field`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum BedrockBeta {
+    /// 1M token context window for Claude Sonnet 4/4.5
+    ///
+    /// Also raises the provider's context window; see
+    /// [`BedrockProvider::with_1m_context`].
+    Context1M,
+    /// Files API, for referencing previously uploaded files by ID
+    FilesApi,
+    /// Thinking between tool calls; requires extended thinking
+    ///
+    /// See [`BedrockProvider::with_interleaved_thinking`].
+    InterleavedThinking,
+    /// Token-efficient tool use, which shortens tool call output
+    TokenEfficientTools,
+}
+
+impl BedrockBeta {
+    /// The identifier sent in the `anthropic_beta` array
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BedrockBeta::Context1M => "context-1m-2025-08-07",
+            BedrockBeta::FilesApi => "files-api-2025-04-14",
+            BedrockBeta::InterleavedThinking => "interleaved-thinking-2025-05-14",
+            BedrockBeta::TokenEfficientTools => "token-efficient-tools-2025-02-19",
+        }
+    }
+}
+
+impl std::fmt::Display for BedrockBeta {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
 
 // ===== Error Handling Helpers =====
 
@@ -55,6 +114,19 @@ where
     classify_error_message(&combined, root_message)
 }
 
+/// Whether a CountTokens failure still shows the credentials were accepted
+///
+/// Bedrock authenticates the caller before it authorizes or validates the
+/// request, so an AccessDeniedException (the role lacks
+/// `bedrock:CountTokens`) or a ValidationException (the model can't count
+/// tokens) means the signature checked out.
+fn rejected_after_authentication<R>(err: &SdkError<CountTokensError, R>) -> bool {
+    matches!(
+        err.as_service_error(),
+        Some(CountTokensError::AccessDeniedException(_) | CountTokensError::ValidationException(_))
+    )
+}
+
 /// Classify an error based on the combined error message text.
 ///
 /// This matches patterns from AWS Bedrock error types:
@@ -160,9 +232,6 @@ fn collect_error_messages(err: &dyn StdError, messages: &mut Vec<String>) {
     }
 }
 
-/// Default maximum tokens to generate
-const DEFAULT_MAX_TOKENS: i32 = 4096;
-
 // Re-export InferenceProfile from model module for backwards compatibility
 pub use crate::model::InferenceProfile;
 
@@ -194,6 +263,24 @@ trait BedrockClient: Send + Sync {
         &self,
         request: ConverseRequest,
     ) -> Result<StreamOutputResult, ProviderError>;
+
+    /// Count input tokens for a converse request
+    async fn count_tokens(&self, request: ConverseRequest) -> Result<usize, ProviderError>;
+
+    /// Check that Bedrock accepts the client's credentials, using a
+    /// CountTokens request
+    ///
+    /// Succeeds whenever the caller is authenticated, whether or not it may
+    /// count tokens.
+    async fn check_credentials(&self, request: ConverseRequest) -> Result<(), ProviderError> {
+        self.count_tokens(request).await.map(|_| ())
+    }
+
+    /// A copy of this client that makes one attempt per request, if it
+    /// retries on its own
+    fn without_retries(&self) -> Option<Arc<dyn BedrockClient>> {
+        None
+    }
 }
 
 /// Production implementation wrapping the AWS SDK client
@@ -285,6 +372,63 @@ impl BedrockClient for SdkBedrockClient {
 
         request.send().await.map_err(classify_aws_error)
     }
+
+    async fn count_tokens(&self, req: ConverseRequest) -> Result<usize, ProviderError> {
+        let mut input = ConverseTokensRequest::builder().set_messages(Some(req.messages));
+
+        if let Some(prompt) = req.system_prompt {
+            input = input.system(SystemContentBlock::Text(prompt));
+        }
+
+        if !req.tools.is_empty() {
+            input = input.tool_config(
+                ToolConfiguration::builder()
+                    .set_tools(Some(req.tools))
+                    .build()
+                    .map_err(|e| ProviderError::Configuration(e.to_string()))?,
+            );
+        }
+
+        let output = self
+            .client
+            .count_tokens()
+            .model_id(req.model_id)
+            .input(CountTokensInput::Converse(input.build()))
+            .send()
+            .await
+            .map_err(classify_aws_error)?;
+
+        Ok(output.input_tokens().max(0) as usize)
+    }
+
+    async fn check_credentials(&self, req: ConverseRequest) -> Result<(), ProviderError> {
+        let input = ConverseTokensRequest::builder()
+            .set_messages(Some(req.messages))
+            .build();
+
+        match self
+            .client
+            .count_tokens()
+            .model_id(req.model_id)
+            .input(CountTokensInput::Converse(input))
+            .send()
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(err) if rejected_after_authentication(&err) => Ok(()),
+            Err(err) => Err(classify_aws_error(err)),
+        }
+    }
+
+    fn without_retries(&self) -> Option<Arc<dyn BedrockClient>> {
+        let config = self
+            .client
+            .config()
+            .to_builder()
+            .retry_config(aws_sdk_bedrockruntime::config::retry::RetryConfig::disabled())
+            .build();
+        Some(Arc::new(SdkBedrockClient::new(Client::from_conf(config))))
+    }
 }
 
 /// Build additional model request fields for parameters not in InferenceConfiguration
@@ -347,10 +491,18 @@ pub struct BedrockProvider {
     client: Arc<dyn BedrockClient>,
     base_model_id: String,
     inference_profile: InferenceProfile,
+    /// Custom inference profile ARN used in place of the model ID
+    inference_profile_arn: Option<String>,
+    /// Whether the model defaults to an inference profile
+    requires_inference_profile: bool,
     model_name: &'static str,
     max_context_tokens: usize,
     max_output_tokens: usize,
-    max_tokens: i32,
+    /// Context window the model offers behind the 1M-context beta, if any
+    extended_context_tokens: Option<usize>,
+    /// Explicit per-request token limit; defaults to the model limit, capped
+    max_tokens: Option<i32>,
+    max_tokens_cap: usize,
     temperature: Option<f32>,
     top_p: Option<f32>,
     top_k: Option<u32>,
@@ -363,7 +515,37 @@ pub struct BedrockProvider {
 impl BedrockProvider {
     /// Get the effective model ID based on inference profile configuration
     fn effective_model_id(&self) -> String {
-        self.inference_profile.apply_to(&self.base_model_id)
+        match &self.inference_profile_arn {
+            Some(arn) => arn.clone(),
+            None => self.inference_profile.apply_to(&self.base_model_id),
+        }
+    }
+}
+
+/// Check that `arn` looks like a Bedrock inference profile ARN
+///
+/// Accepts both application and system-defined profiles, e.g.
+/// `arn:aws:bedrock:us-east-1:123456789012:application-inference-profile/abc123`.
+fn check_inference_profile_arn(arn: &str) -> Result<(), ProviderError> {
+    let parts: Vec<&str> = arn.splitn(6, ':').collect();
+    let valid = match parts.as_slice() {
+        ["arn", partition, "bedrock", region, account, resource] => {
+            partition.starts_with("aws")
+                && !region.is_empty()
+                && !account.is_empty()
+                && ["application-inference-profile/", "inference-profile/"]
+                    .iter()
+                    .any(|kind| resource.strip_prefix(kind).is_some_and(|id| !id.is_empty()))
+        }
+        _ => false,
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(ProviderError::Configuration(format!(
+            "'{}' is not a Bedrock inference profile ARN",
+            arn
+        )))
     }
 }
 
@@ -373,10 +555,14 @@ impl Clone for BedrockProvider {
             client: Arc::clone(&self.client),
             base_model_id: self.base_model_id.clone(),
             inference_profile: self.inference_profile,
+            inference_profile_arn: self.inference_profile_arn.clone(),
+            requires_inference_profile: self.requires_inference_profile,
             model_name: self.model_name,
             max_context_tokens: self.max_context_tokens,
             max_output_tokens: self.max_output_tokens,
+            extended_context_tokens: self.extended_context_tokens,
             max_tokens: self.max_tokens,
+            max_tokens_cap: self.max_tokens_cap,
             temperature: self.temperature,
             top_p: self.top_p,
             top_k: self.top_k,
@@ -415,10 +601,14 @@ impl BedrockProvider {
             client: Arc::new(SdkBedrockClient::new(client)),
             base_model_id: model.bedrock_id().to_string(),
             inference_profile: model.default_inference_profile(),
+            inference_profile_arn: None,
+            requires_inference_profile: model.default_inference_profile() != InferenceProfile::None,
             model_name: model.name(),
             max_context_tokens: model.max_context_tokens(),
             max_output_tokens: model.max_output_tokens(),
-            max_tokens: DEFAULT_MAX_TOKENS,
+            extended_context_tokens: model.extended_context_tokens(),
+            max_tokens: None,
+            max_tokens_cap: DEFAULT_MAX_TOKENS_CAP,
             temperature: None,
             top_p: None,
             top_k: None,
@@ -435,10 +625,14 @@ impl BedrockProvider {
             client: Arc::new(SdkBedrockClient::new(client)),
             base_model_id: model.bedrock_id().to_string(),
             inference_profile: model.default_inference_profile(),
+            inference_profile_arn: None,
+            requires_inference_profile: model.default_inference_profile() != InferenceProfile::None,
             model_name: model.name(),
             max_context_tokens: model.max_context_tokens(),
             max_output_tokens: model.max_output_tokens(),
-            max_tokens: DEFAULT_MAX_TOKENS,
+            extended_context_tokens: model.extended_context_tokens(),
+            max_tokens: None,
+            max_tokens_cap: DEFAULT_MAX_TOKENS_CAP,
             temperature: None,
             top_p: None,
             top_k: None,
@@ -456,10 +650,14 @@ impl BedrockProvider {
             client,
             base_model_id: model.bedrock_id().to_string(),
             inference_profile: model.default_inference_profile(),
+            inference_profile_arn: None,
+            requires_inference_profile: model.default_inference_profile() != InferenceProfile::None,
             model_name: model.name(),
             max_context_tokens: model.max_context_tokens(),
             max_output_tokens: model.max_output_tokens(),
-            max_tokens: DEFAULT_MAX_TOKENS,
+            extended_context_tokens: model.extended_context_tokens(),
+            max_tokens: None,
+            max_tokens_cap: DEFAULT_MAX_TOKENS_CAP,
             temperature: None,
             top_p: None,
             top_k: None,
@@ -478,6 +676,8 @@ impl BedrockProvider {
     /// Note: Models that require inference profiles (Claude 4/4.5, Nova 2 Lite)
     /// automatically default to `InferenceProfile::Global`. Use this method to
     /// change to a regional profile (US, EU, APAC) for data residency requirements.
+    /// Choosing `InferenceProfile::None` for one of those models prints a
+    /// warning; see [`with_direct_invocation`](Self::with_direct_invocation).
     ///
     /// # Example
     ///
@@ -493,34 +693,182 @@ impl BedrockProvider {
     ///     .with_inference_profile(InferenceProfile::EU);
     /// ```
     pub fn with_inference_profile(mut self, profile: InferenceProfile) -> Self {
+        if profile == InferenceProfile::None && self.requires_inference_profile {
+            eprintln!(
+                "Warning: {} normally requires an inference profile. Invoking '{}' directly \
+                 fails in regions without on-demand throughput for the base model.",
+                self.model_name, self.base_model_id
+            );
+        }
         self.inference_profile = profile;
+        self.inference_profile_arn = None;
         self
     }
 
+    /// Invoke the model through a custom inference profile, by ARN
+    ///
+    /// Application inference profiles carry cost-allocation tags, so routing
+    /// each team's requests through its own profile tracks their spend
+    /// separately. The ARN is sent as the model ID unchanged, replacing any
+    /// [`InferenceProfile`]. The profile must be for the same model this
+    /// provider was created with, since token limits and request options
+    /// still come from that model.
+    ///
+    /// Returns [`ProviderError::Configuration`] if `arn` isn't a Bedrock
+    /// inference profile ARN.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use mixtape_core::{BedrockProvider, ClaudeSonnet4_5};
+    ///
+    /// let provider = BedrockProvider::new(ClaudeSonnet4_5).await?
+    ///     .with_inference_profile_arn(
+    ///         "arn:aws:bedrock:us-east-1:123456789012:application-inference-profile/a1b2c3d4e5f6",
+    ///     )?;
+    /// ```
+    pub fn with_inference_profile_arn(
+        mut self,
+        arn: impl Into<String>,
+    ) -> Result<Self, ProviderError> {
+        let arn = arn.into();
+        check_inference_profile_arn(&arn)?;
+        self.inference_profile_arn = Some(arn);
+        Ok(self)
+    }
+
+    /// Invoke the base model ID directly, without an inference profile
+    ///
+    /// Equivalent to `with_inference_profile(InferenceProfile::None)`.
+    /// Requests go to the configured region only, with no cross-region
+    /// routing. Models that normally use an inference profile (Claude 4/4.5,
+    /// Nova 2 Lite) only accept direct invocation in regions and accounts
+    /// with on-demand or provisioned throughput for the base model; elsewhere
+    /// Bedrock rejects the request with a validation error. A warning is
+    /// printed when this is used with one of those models.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use mixtape_core::{BedrockProvider, ClaudeSonnet4_5};
+    ///
+    /// // Call anthropic.claude-sonnet-4-5-... instead of global.anthropic.claude-sonnet-4-5-...
+    /// let provider = BedrockProvider::new(ClaudeSonnet4_5).await?
+    ///     .with_direct_invocation();
+    /// ```
+    pub fn with_direct_invocation(self) -> Self {
+        self.with_inference_profile(InferenceProfile::None)
+    }
+
     /// Set the maximum number of tokens to generate per request
+    ///
+    /// Overrides the default of the model's output limit capped at
+    /// [`DEFAULT_MAX_TOKENS_CAP`]. Values above the model's output limit
+    /// are clamped to it with a warning, since the API would reject them;
+    /// use [`try_with_max_tokens`](Self::try_with_max_tokens) to reject them.
     pub fn with_max_tokens(mut self, max_tokens: i32) -> Self {
-        self.max_tokens = max_tokens;
+        self.max_tokens = Some(clamp_max_tokens(max_tokens, self.max_output_tokens));
         self
     }
 
+    /// Set `max_tokens`, rejecting values outside 1 to the model's output limit
+    pub fn try_with_max_tokens(mut self, max_tokens: i32) -> Result<Self, ProviderError> {
+        self.max_tokens = Some(check_max_tokens(max_tokens, self.max_output_tokens)?);
+        Ok(self)
+    }
+
+    /// Cap the default `max_tokens` derived from the model's output limit
+    ///
+    /// Has no effect once [`with_max_tokens`](Self::with_max_tokens) is set.
+    /// You are billed for tokens actually generated, not for `max_tokens`,
+    /// but a higher limit lets responses run longer (and cost more), and
+    /// some rate limits count `max_tokens` against your output quota.
+    pub fn with_max_tokens_cap(mut self, cap: usize) -> Self {
+        self.max_tokens_cap = cap;
+        self
+    }
+
+    /// Effective `max_tokens` sent with each request
+    fn max_tokens(&self) -> i32 {
+        self.max_tokens
+            .unwrap_or_else(|| default_max_tokens(self.max_output_tokens, self.max_tokens_cap))
+    }
+
     /// Set the temperature (0.0 to 1.0)
+    ///
+    /// Out-of-range values are clamped with a warning; use
+    /// [`try_with_temperature`](Self::try_with_temperature) to reject them.
     pub fn with_temperature(mut self, temperature: f32) -> Self {
-        self.temperature = Some(temperature);
+        self.temperature = clamp_unit_range("temperature", temperature);
         self
     }
 
+    /// Set the temperature, rejecting values outside 0.0 to 1.0
+    pub fn try_with_temperature(mut self, temperature: f32) -> Result<Self, ProviderError> {
+        self.temperature = Some(check_unit_range("temperature", temperature)?);
+        Ok(self)
+    }
+
     /// Set top_p (0.0 to 1.0)
+    ///
+    /// Out-of-range values are clamped with a warning; use
+    /// [`try_with_top_p`](Self::try_with_top_p) to reject them.
     pub fn with_top_p(mut self, top_p: f32) -> Self {
-        self.top_p = Some(top_p);
+        self.top_p = clamp_unit_range("top_p", top_p);
         self
     }
 
+    /// Set top_p, rejecting values outside 0.0 to 1.0
+    pub fn try_with_top_p(mut self, top_p: f32) -> Result<Self, ProviderError> {
+        self.top_p = Some(check_unit_range("top_p", top_p)?);
+        Ok(self)
+    }
+
     /// Set top_k (only sample from the top K options)
     ///
+    /// A value of 0 is ignored with a warning; use
+    /// [`try_with_top_k`](Self::try_with_top_k) to reject it.
+    ///
     /// Note: This is passed via `additionalModelRequestFields` as Bedrock's
     /// InferenceConfiguration doesn't natively support top_k.
     pub fn with_top_k(mut self, top_k: u32) -> Self {
-        self.top_k = Some(top_k);
+        if top_k == 0 {
+            eprintln!("Warning: top_k must be at least 1; leaving it unset");
+        }
+        self.top_k = check_top_k(top_k).ok();
+        self
+    }
+
+    /// Set top_k, rejecting 0
+    pub fn try_with_top_k(mut self, top_k: u32) -> Result<Self, ProviderError> {
+        self.top_k = Some(check_top_k(top_k)?);
+        Ok(self)
+    }
+
+    /// Send a sampling seed with each request (best-effort)
+    ///
+    /// Passed as `seed` in `additionalModelRequestFields` for models known
+    /// to accept one (currently Cohere Command R and R+). Seeding makes
+    /// output more repeatable but not guaranteed identical. Other models,
+    /// including Claude, Nova, Llama, Titan, and Mistral, reject unknown
+    /// fields, so for them the seed is left unset with a warning.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let provider = BedrockProvider::new(SomeModel).await?
+    ///     .with_temperature(0.0)
+    ///     .with_seed(42);
+    /// ```
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        if self.supports(Feature::Seed) {
+            self.additional_fields
+                .insert(SEED_KEY.to_string(), serde_json::json!(seed));
+        } else {
+            eprintln!(
+                "Warning: {} does not support a sampling seed; leaving it unset",
+                self.base_model_id
+            );
+        }
         self
     }
 
@@ -564,31 +912,92 @@ impl BedrockProvider {
     ///     .with_inference_profile(InferenceProfile::US)
     ///     .with_1m_context();
     /// ```
-    pub fn with_1m_context(mut self) -> Self {
-        const BETA_KEY: &str = "anthropic_beta";
-        const CONTEXT_1M: &str = "context-1m-2025-08-07";
-
-        // Check if already enabled (idempotent)
-        if let Some(existing) = self.additional_fields.get(BETA_KEY) {
-            if let Some(arr) = existing.as_array() {
-                if arr.iter().any(|v| v.as_str() == Some(CONTEXT_1M)) {
-                    return self;
-                }
-            }
+    pub fn with_1m_context(self) -> Self {
+        self.with_beta(BedrockBeta::Context1M)
+    }
+
+    /// Let the model think between tool calls (relies on Anthropic beta feature)
+    ///
+    /// With extended thinking alone, Claude thinks once before its first
+    /// response. Interleaved thinking lets it reason again after each tool
+    /// result. Reasoning from earlier turns is sent back with its signature
+    /// so the model can continue its reasoning.
+    ///
+    /// Requires [`with_thinking`](Self::with_thinking); requests fail with
+    /// [`ProviderError::Configuration`] otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let provider = BedrockProvider::new(ClaudeSonnet4_5)
+    ///     .await?
+    ///     .with_thinking(4096)
+    ///     .with_interleaved_thinking(true);
+    /// ```
+    pub fn with_interleaved_thinking(mut self, enabled: bool) -> Self {
+        self.set_anthropic_beta(BedrockBeta::InterleavedThinking, enabled);
+        self
+    }
+
+    /// Enable an Anthropic beta feature
+    ///
+    /// Adds the beta to the `anthropic_beta` array sent with each request.
+    /// Enabling the same beta twice sends it once. [`BedrockBeta::Context1M`]
+    /// also raises the context window to 1M tokens.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let provider = BedrockProvider::new(ClaudeSonnet4_5)
+    ///     .await?
+    ///     .with_beta(BedrockBeta::TokenEfficientTools)
+    ///     .with_beta(BedrockBeta::FilesApi);
+    /// ```
+    pub fn with_beta(mut self, beta: BedrockBeta) -> Self {
+        self.set_anthropic_beta(beta, true);
+        if beta == BedrockBeta::Context1M {
+            self.max_context_tokens = 1_000_000;
         }
+        self
+    }
 
-        // Add the beta feature
+    /// Add or remove an entry in the `anthropic_beta` additional field
+    fn set_anthropic_beta(&mut self, beta: BedrockBeta, enabled: bool) {
+        let beta = beta.as_str();
         let betas = self
             .additional_fields
-            .entry(BETA_KEY.to_string())
+            .entry(ANTHROPIC_BETA_KEY.to_string())
             .or_insert_with(|| serde_json::json!([]));
 
         if let Some(arr) = betas.as_array_mut() {
-            arr.push(serde_json::json!(CONTEXT_1M));
+            let present = arr.iter().any(|v| v.as_str() == Some(beta));
+            if !enabled {
+                arr.retain(|v| v.as_str() != Some(beta));
+            } else if !present {
+                arr.push(serde_json::json!(beta));
+            }
+            if arr.is_empty() {
+                self.additional_fields.remove(ANTHROPIC_BETA_KEY);
+            }
         }
+    }
 
-        self.max_context_tokens = 1_000_000;
-        self
+    /// Reject interleaved thinking without extended thinking enabled
+    fn check_thinking_config(&self) -> Result<(), ProviderError> {
+        let interleaved = self
+            .additional_fields
+            .get(ANTHROPIC_BETA_KEY)
+            .and_then(|betas| betas.as_array())
+            .is_some_and(|arr| {
+                arr.iter()
+                    .any(|v| v.as_str() == Some(BedrockBeta::InterleavedThinking.as_str()))
+            });
+        if interleaved && !matches!(self.thinking_config, Some(ThinkingConfig::Enabled { .. })) {
+            return Err(ProviderError::Configuration(
+                "Interleaved thinking requires extended thinking; call with_thinking()".to_string(),
+            ));
+        }
+        Ok(())
     }
 
     /// Add a custom field to `additionalModelRequestFields`
@@ -611,6 +1020,23 @@ impl BedrockProvider {
         self
     }
 
+    /// Attach an end-user identifier to every request as `metadata.user_id`
+    ///
+    /// Sent through `additionalModelRequestFields`, which Anthropic models on
+    /// Bedrock accept for per-user abuse attribution. Models that don't
+    /// recognize the field may reject the request, so only set this for
+    /// Anthropic models. Use an opaque value such as a UUID or hash.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let provider = BedrockProvider::new(ClaudeSonnet4_5).await?
+    ///     .with_metadata("3f2a9c1e-user");
+    /// ```
+    pub fn with_metadata(self, user_id: impl Into<String>) -> Self {
+        self.with_additional_field("metadata", serde_json::json!({ "user_id": user_id.into() }))
+    }
+
     /// Configure retry behavior for transient errors (throttling, rate limits)
     ///
     /// Default: 8 attempts with exponential backoff starting at 500ms, capped at 30s
@@ -619,14 +1045,30 @@ impl BedrockProvider {
         self
     }
 
-    /// Set the maximum number of retry attempts for transient errors
+    /// Set the maximum number of attempts for transient errors
     ///
-    /// Default: 8
+    /// `attempts` counts the first request, so `with_max_retries(3)` sends
+    /// a request at most three times. Both 0 and 1 mean a single attempt; use
+    /// [`with_retries_disabled`](Self::with_retries_disabled) to say so
+    /// directly. Default: 8
     pub fn with_max_retries(mut self, attempts: usize) -> Self {
         self.retry_config.max_attempts = attempts;
         self
     }
 
+    /// Send each request exactly once, failing on the first error
+    ///
+    /// Turns off both this provider's retries and the AWS SDK's own retry
+    /// layer. Useful for tests and interactive use where a fast failure
+    /// beats waiting out a backoff.
+    pub fn with_retries_disabled(mut self) -> Self {
+        self.retry_config = RetryConfig::disabled();
+        if let Some(client) = self.client.without_retries() {
+            self.client = client;
+        }
+        self
+    }
+
     /// Set the maximum delay between retries
     ///
     /// Default: 30 seconds
@@ -643,6 +1085,25 @@ impl BedrockProvider {
         self
     }
 
+    /// Set how retry delays are randomized
+    ///
+    /// Default: [`JitterStrategy::Full`]
+    pub fn with_retry_jitter(mut self, jitter: JitterStrategy) -> Self {
+        self.retry_config.jitter = jitter;
+        self
+    }
+
+    /// Cap the total time spent waiting between retries
+    ///
+    /// Once the next delay would exceed the budget, the last error is
+    /// returned instead of retrying.
+    ///
+    /// Default: no limit
+    pub fn with_retry_budget(mut self, budget: Duration) -> Self {
+        self.retry_config.max_total_delay = Some(budget);
+        self
+    }
+
     /// Set a callback to be notified when retries occur
     ///
     /// # Example
@@ -670,7 +1131,7 @@ impl BedrockProvider {
         ConverseRequest {
             model_id: self.effective_model_id(),
             messages,
-            max_tokens: self.max_tokens,
+            max_tokens: self.max_tokens(),
             temperature: self.temperature,
             top_p: self.top_p,
             top_k: self.top_k,
@@ -696,12 +1157,120 @@ impl ModelProvider for BedrockProvider {
         self.max_output_tokens
     }
 
+    fn supported_document_formats(&self) -> &[DocumentFormat] {
+        &[
+            DocumentFormat::Pdf,
+            DocumentFormat::Csv,
+            DocumentFormat::Doc,
+            DocumentFormat::Docx,
+            DocumentFormat::Html,
+            DocumentFormat::Md,
+            DocumentFormat::Txt,
+            DocumentFormat::Xls,
+            DocumentFormat::Xlsx,
+        ]
+    }
+
+    fn configure_seed(&self, seed: u64) -> Option<Arc<dyn ModelProvider>> {
+        self.supports(Feature::Seed)
+            .then(|| Arc::new(self.clone().with_seed(seed)) as Arc<dyn ModelProvider>)
+    }
+
+    fn supports(&self, feature: Feature) -> bool {
+        let is_claude = self.base_model_id.starts_with("anthropic.claude");
+        match feature {
+            // Reasoning settings are passed to Claude through additional fields
+            Feature::Thinking => is_claude,
+            // Converse caches prompts for Claude and Nova
+            Feature::PromptCaching => is_claude || self.base_model_id.starts_with("amazon.nova"),
+            Feature::Documents => !self.supported_document_formats().is_empty(),
+            Feature::Context1M => self.extended_context_tokens.is_some(),
+            // Other models reject a seed as an unknown field
+            Feature::Seed => SEED_MODEL_PREFIXES
+                .iter()
+                .any(|prefix| self.base_model_id.starts_with(prefix)),
+            // Converse has no server tools and no parallel tool switch
+            Feature::WebSearch | Feature::ParallelToolUse => false,
+        }
+    }
+
+    fn settings(&self) -> ProviderSettings {
+        ProviderSettings {
+            model_id: Some(self.effective_model_id()),
+            sampling: SamplingParams {
+                max_tokens: Some(self.max_tokens()),
+                temperature: self.temperature,
+                top_p: self.top_p,
+                top_k: self.top_k,
+                seed: self
+                    .additional_fields
+                    .get(SEED_KEY)
+                    .and_then(|seed| seed.as_u64()),
+            },
+            retry: Some(self.retry_config.clone()),
+        }
+    }
+
+    /// Count tokens with the Bedrock CountTokens API
+    ///
+    /// Not every model supports token counting. When Bedrock rejects the
+    /// request as invalid for this model, the local estimate is returned.
+    async fn count_tokens(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+        system_prompt: Option<&str>,
+    ) -> Result<usize, ProviderError> {
+        let bedrock_messages: Vec<BedrockMessage> = messages
+            .iter()
+            .map(to_bedrock_message)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let bedrock_tools: Vec<BedrockTool> = tools
+            .iter()
+            .map(to_bedrock_tool)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // CountTokens takes a foundation model ID, not an inference profile
+        let mut request = self.build_request(
+            bedrock_messages,
+            bedrock_tools,
+            system_prompt.map(str::to_string),
+        );
+        request.model_id = self.base_model_id.clone();
+
+        match self.client.count_tokens(request).await {
+            Err(ProviderError::Configuration(_)) => Ok(estimate_request_tokens(
+                self,
+                messages,
+                tools,
+                system_prompt,
+            )),
+            result => result,
+        }
+    }
+
+    /// Check credentials with a CountTokens request that needs no extra
+    /// permission
+    ///
+    /// The default check fails for roles without `bedrock:CountTokens`, even
+    /// though their credentials are fine. Bedrock only denies access after
+    /// authenticating the caller, so access denied counts as accepted.
+    async fn check_credentials(&self) -> Result<(), ProviderError> {
+        let message = to_bedrock_message(&Message::user("ping"))?;
+        let mut request = self.build_request(vec![message], Vec::new(), None);
+        request.model_id = self.base_model_id.clone();
+        self.client.check_credentials(request).await
+    }
+
     async fn generate(
         &self,
         messages: Vec<Message>,
         tools: Vec<ToolDefinition>,
         system_prompt: Option<String>,
     ) -> Result<ModelResponse, ProviderError> {
+        self.check_thinking_config()?;
+
         // Convert mixtape types to Bedrock types
         let bedrock_messages: Vec<BedrockMessage> = messages
             .iter()
@@ -753,7 +1322,13 @@ impl ModelProvider for BedrockProvider {
         Ok(ModelResponse {
             message,
             stop_reason,
+            stop_sequence: stop_sequence_from_fields(
+                response.additional_model_response_fields.as_ref(),
+            ),
             usage,
+            service_tier: None,
+            // Converse doesn't report the model that served the request
+            model: Some(self.effective_model_id()),
         })
     }
 
@@ -763,6 +1338,8 @@ impl ModelProvider for BedrockProvider {
         tools: Vec<ToolDefinition>,
         system_prompt: Option<String>,
     ) -> Result<BoxStream<'static, Result<StreamEvent, ProviderError>>, ProviderError> {
+        self.check_thinking_config()?;
+
         // Convert mixtape types to Bedrock types
         let bedrock_messages: Vec<BedrockMessage> = messages
             .iter()
@@ -790,105 +1367,34 @@ impl ModelProvider for BedrockProvider {
         let stream = output.stream;
 
         // Return an async stream that yields events as they arrive
+        let model_id = self.effective_model_id();
         let event_stream = async_stream::stream! {
             let mut stream = stream;
-
-            // Track tool uses in progress by content_block_index
-            // Each entry: (tool_use_id, name, input_json_string)
-            let mut tool_uses_in_progress: HashMap<i32, (String, String, String)> = HashMap::new();
-
-            // Track token usage from metadata event
-            let mut usage: Option<TokenUsage> = None;
+            let mut state = ConverseStreamState::new(model_id);
 
             loop {
                 match stream.recv().await {
-                    Ok(Some(output)) => match output {
-                        ConverseStreamOutput::ContentBlockStart(start) => {
-                            // Handle tool use start
-                            if let Some(ContentBlockStart::ToolUse(tool_start)) = start.start {
-                                let index = start.content_block_index;
-                                let id = tool_start.tool_use_id;
-                                let name = tool_start.name;
-                                tool_uses_in_progress.insert(index, (id, name, String::new()));
-                            }
-                        }
-                        ConverseStreamOutput::ContentBlockDelta(delta) => {
-                            match delta.delta {
-                                Some(ContentBlockDelta::Text(text)) => {
-                                    yield Ok(StreamEvent::TextDelta(text));
-                                }
-                                Some(ContentBlockDelta::ToolUse(tool_delta)) => {
-                                    // Append to the tool input JSON string
-                                    if let Some(entry) = tool_uses_in_progress.get_mut(&delta.content_block_index) {
-                                        entry.2.push_str(&tool_delta.input);
-                                    }
-                                }
-                                _ => {}
-                            }
+                    Ok(Some(output)) => {
+                        for event in state.on_event(output) {
+                            yield Ok(event);
                         }
-                        ConverseStreamOutput::ContentBlockStop(stop) => {
-                            // Finalize tool use if this was a tool block
-                            if let Some((id, name, input_json)) = tool_uses_in_progress.remove(&stop.content_block_index) {
-                                // Parse the accumulated JSON input
-                                let input = match serde_json::from_str::<serde_json::Value>(&input_json) {
-                                    Ok(v) => v,
-                                    Err(_) => serde_json::json!({}),
-                                };
-
-                                let tool_use = ToolUseBlock {
-                                    id,
-                                    name,
-                                    input,
-                                };
-                                yield Ok(StreamEvent::ToolUse(tool_use));
-                            }
-                        }
-                        ConverseStreamOutput::Metadata(meta) => {
-                            // Capture token usage from metadata event
-                            if let Some(u) = meta.usage {
-                                usage = Some(TokenUsage {
-                                    input_tokens: u.input_tokens as usize,
-                                    output_tokens: u.output_tokens as usize,
-                                });
-                            }
-                        }
-                        ConverseStreamOutput::MessageStop(stop) => {
-                            // Don't break yet - wait for Metadata event which comes after
-                            let stop_reason = from_bedrock_stop_reason(&stop.stop_reason);
-
-                            // Continue reading to get Metadata, then emit Stop
-                            loop {
-                                match stream.recv().await {
-                                    Ok(Some(ConverseStreamOutput::Metadata(meta))) => {
-                                        if let Some(u) = meta.usage {
-                                            usage = Some(TokenUsage {
-                                                input_tokens: u.input_tokens as usize,
-                                                output_tokens: u.output_tokens as usize,
-                                            });
-                                        }
-                                        break;
-                                    }
-                                    Ok(None) => break,
-                                    Err(_) => break,
-                                    _ => continue, // Skip any other events
-                                }
-                            }
-
-                            yield Ok(StreamEvent::Stop {
-                                stop_reason,
-                                usage,
-                            });
+                        if state.is_done() {
                             break;
                         }
-                        _ => {}
-                    },
+                    }
                     Ok(None) => break,
+                    // After MessageStop only the usage metadata is missing
+                    Err(_) if state.stopped() => break,
                     Err(e) => {
                         yield Err(ProviderError::Other(e.to_string()));
                         break;
                     }
                 }
             }
+
+            for event in state.finish() {
+                yield Ok(event);
+            }
         };
 
         Ok(Box::pin(event_stream))
@@ -901,7 +1407,9 @@ mod tests {
 
     use super::*;
     use crate::model::Model;
-    use crate::models::{ClaudeSonnet4_5, NovaMicro};
+    use crate::models::{
+        ClaudeHaiku4_5, ClaudeSonnet4_5, ClaudeSonnet4_6, CohereCommandRPlus, NovaMicro,
+    };
     use std::sync::Mutex;
 
     /// Test model for unit tests
@@ -940,6 +1448,7 @@ mod tests {
     struct TestBedrockClient {
         converse_responses: Mutex<Vec<Result<ConverseOutput, ProviderError>>>,
         stream_responses: Mutex<Vec<Result<StreamOutputResult, ProviderError>>>,
+        count_tokens_responses: Mutex<Vec<Result<usize, ProviderError>>>,
         converse_call_count: Mutex<usize>,
         stream_call_count: Mutex<usize>,
     }
@@ -949,6 +1458,7 @@ mod tests {
             Self {
                 converse_responses: Mutex::new(Vec::new()),
                 stream_responses: Mutex::new(Vec::new()),
+                count_tokens_responses: Mutex::new(Vec::new()),
                 converse_call_count: Mutex::new(0),
                 stream_call_count: Mutex::new(0),
             }
@@ -963,6 +1473,11 @@ mod tests {
             self.stream_responses.lock().unwrap().push(response);
             self
         }
+
+        fn with_count_tokens_response(self, response: Result<usize, ProviderError>) -> Self {
+            self.count_tokens_responses.lock().unwrap().push(response);
+            self
+        }
     }
 
     #[async_trait::async_trait]
@@ -995,6 +1510,18 @@ mod tests {
                     ))
                 })
         }
+
+        async fn count_tokens(&self, _req: ConverseRequest) -> Result<usize, ProviderError> {
+            self.count_tokens_responses
+                .lock()
+                .unwrap()
+                .pop()
+                .unwrap_or_else(|| {
+                    Err(ProviderError::Other(
+                        "No mock response configured".to_string(),
+                    ))
+                })
+        }
     }
 
     #[test]
@@ -1003,7 +1530,41 @@ mod tests {
         let provider = BedrockProvider::with_bedrock_client(Arc::new(client), TEST_MODEL)
             .with_max_tokens(2048);
 
-        assert_eq!(provider.max_tokens, 2048);
+        assert_eq!(provider.max_tokens(), 2048);
+    }
+
+    #[test]
+    fn test_default_max_tokens_follows_model_output_limit() {
+        // Below the cap, the model's own limit is used (TEST_MODEL outputs 4K)
+        let provider =
+            BedrockProvider::with_bedrock_client(Arc::new(TestBedrockClient::new()), TEST_MODEL);
+        assert_eq!(provider.max_tokens(), 4_096);
+
+        // Above the cap, the cap is used (Sonnet 4.5 outputs 64K)
+        let provider = BedrockProvider::with_bedrock_client(
+            Arc::new(TestBedrockClient::new()),
+            crate::ClaudeSonnet4_5,
+        );
+        assert_eq!(provider.max_tokens(), DEFAULT_MAX_TOKENS_CAP as i32);
+        let request = provider.build_request(Vec::new(), Vec::new(), None);
+        assert_eq!(request.max_tokens, DEFAULT_MAX_TOKENS_CAP as i32);
+
+        let provider = provider.with_max_tokens_cap(100_000);
+        assert_eq!(provider.max_tokens(), 64_000);
+    }
+
+    #[test]
+    fn test_explicit_max_tokens_wins_over_cap() {
+        let provider = BedrockProvider::with_bedrock_client(
+            Arc::new(TestBedrockClient::new()),
+            crate::ClaudeSonnet4_5,
+        )
+        .with_max_tokens(2048)
+        .with_max_tokens_cap(100_000);
+
+        assert_eq!(provider.max_tokens(), 2048);
+        let request = provider.build_request(Vec::new(), Vec::new(), None);
+        assert_eq!(request.max_tokens, 2048);
     }
 
     #[test]
@@ -1024,6 +1585,58 @@ mod tests {
         assert_eq!(provider.top_p, Some(0.9));
     }
 
+    #[test]
+    fn test_builder_clamps_sampling_params() {
+        let client = TestBedrockClient::new();
+        let provider = BedrockProvider::with_bedrock_client(Arc::new(client), TEST_MODEL)
+            .with_temperature(1.5)
+            .with_top_p(-0.2)
+            .with_top_k(0);
+
+        assert_eq!(provider.temperature, Some(1.0));
+        assert_eq!(provider.top_p, Some(0.0));
+        assert_eq!(provider.top_k, None);
+
+        let client = TestBedrockClient::new();
+        let provider = BedrockProvider::with_bedrock_client(Arc::new(client), TEST_MODEL)
+            .with_temperature(f32::NAN);
+        assert_eq!(provider.temperature, None);
+    }
+
+    #[test]
+    fn test_builder_try_rejects_sampling_params() {
+        let client = TestBedrockClient::new();
+        let provider = BedrockProvider::with_bedrock_client(Arc::new(client), TEST_MODEL)
+            .try_with_temperature(1.0)
+            .and_then(|p| p.try_with_top_p(0.0))
+            .and_then(|p| p.try_with_top_k(40))
+            .unwrap();
+        assert_eq!(provider.temperature, Some(1.0));
+        assert_eq!(provider.top_p, Some(0.0));
+        assert_eq!(provider.top_k, Some(40));
+
+        for result in [
+            provider.clone().try_with_temperature(1.01),
+            provider.clone().try_with_temperature(f32::NAN),
+            provider.clone().try_with_top_p(-0.1),
+            provider.clone().try_with_top_k(0),
+        ] {
+            assert!(matches!(result, Err(ProviderError::Configuration(_))));
+        }
+    }
+
+    #[test]
+    fn test_builder_validates_max_tokens_against_model_limit() {
+        let client = Arc::new(TestBedrockClient::new());
+        let provider = BedrockProvider::with_bedrock_client(client.clone(), TEST_MODEL)
+            .with_max_tokens(10_000);
+        assert_eq!(provider.max_tokens(), 4_096);
+
+        let result =
+            BedrockProvider::with_bedrock_client(client, TEST_MODEL).try_with_max_tokens(4_097);
+        assert!(matches!(result, Err(ProviderError::Configuration(_))));
+    }
+
     #[test]
     fn test_builder_chaining() {
         let client = TestBedrockClient::new();
@@ -1034,7 +1647,7 @@ mod tests {
 
         assert_eq!(provider.base_model_id, "test.model-v1:0");
         assert_eq!(provider.model_name, "Test Model");
-        assert_eq!(provider.max_tokens, 1000);
+        assert_eq!(provider.max_tokens(), 1000);
         assert_eq!(provider.temperature, Some(0.5));
         assert_eq!(provider.top_p, Some(0.8));
     }
@@ -1068,6 +1681,125 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("API Error"));
     }
 
+    #[tokio::test]
+    async fn test_generate_reports_matched_stop_sequence() {
+        use aws_sdk_bedrockruntime::types::{
+            ContentBlock as BedrockContentBlock, ConversationRole,
+            ConverseOutput as BedrockConverseOutput, Message as BedrockMessage,
+            StopReason as BedrockStopReason,
+        };
+        use aws_smithy_types::Document;
+
+        let message = BedrockMessage::builder()
+            .role(ConversationRole::Assistant)
+            .content(BedrockContentBlock::Text("1, 2, 3".to_string()))
+            .build()
+            .unwrap();
+        let output = ConverseOutput::builder()
+            .output(BedrockConverseOutput::Message(message))
+            .stop_reason(BedrockStopReason::StopSequence)
+            .additional_model_response_fields(Document::Object(
+                [(
+                    "stop_sequence".to_string(),
+                    Document::String("4".to_string()),
+                )]
+                .into(),
+            ))
+            .build()
+            .unwrap();
+        let client = TestBedrockClient::new().with_converse_response(Ok(output));
+        let provider = BedrockProvider::with_bedrock_client(Arc::new(client), TEST_MODEL);
+
+        let response = provider
+            .generate(vec![Message::user("Count to 4")], vec![], None)
+            .await
+            .unwrap();
+        assert_eq!(response.stop_reason, crate::types::StopReason::StopSequence);
+        assert_eq!(response.stop_sequence.as_deref(), Some("4"));
+        assert_eq!(response.message.text(), "1, 2, 3");
+    }
+
+    #[tokio::test]
+    async fn test_count_tokens_uses_api() {
+        let client = TestBedrockClient::new().with_count_tokens_response(Ok(321));
+        let provider = BedrockProvider::with_bedrock_client(Arc::new(client), TEST_MODEL);
+
+        let count = provider
+            .count_tokens(&[Message::user("Hi")], &[], None)
+            .await
+            .unwrap();
+        assert_eq!(count, 321);
+    }
+
+    #[tokio::test]
+    async fn test_count_tokens_falls_back_when_unsupported() {
+        let client = TestBedrockClient::new().with_count_tokens_response(Err(
+            ProviderError::Configuration("model does not support CountTokens".to_string()),
+        ));
+        let provider = BedrockProvider::with_bedrock_client(Arc::new(client), TEST_MODEL);
+        let messages = [Message::user("Hi")];
+
+        let count = provider.count_tokens(&messages, &[], None).await.unwrap();
+        assert_eq!(count, provider.estimate_message_tokens(&messages));
+    }
+
+    #[tokio::test]
+    async fn test_count_tokens_propagates_other_errors() {
+        let client = TestBedrockClient::new()
+            .with_count_tokens_response(Err(ProviderError::Authentication("expired".to_string())));
+        let provider = BedrockProvider::with_bedrock_client(Arc::new(client), TEST_MODEL);
+
+        let result = provider
+            .count_tokens(&[Message::user("Hi")], &[], None)
+            .await;
+        assert!(matches!(result, Err(ProviderError::Authentication(_))));
+    }
+
+    #[test]
+    fn test_access_denied_on_count_tokens_accepts_credentials() {
+        use aws_sdk_bedrockruntime::error::ErrorMetadata;
+        use aws_sdk_bedrockruntime::types::error::{AccessDeniedException, ValidationException};
+
+        let rejected = |err: CountTokensError| {
+            rejected_after_authentication(&SdkError::service_error(err, ()))
+        };
+
+        // Authenticated, but not allowed to count tokens or not for this model
+        assert!(rejected(CountTokensError::AccessDeniedException(
+            AccessDeniedException::builder()
+                .message("not authorized to perform: bedrock:CountTokens")
+                .build()
+        )));
+        assert!(rejected(CountTokensError::ValidationException(
+            ValidationException::builder()
+                .message("The provided model doesn't support counting tokens")
+                .build()
+        )));
+
+        // Credentials Bedrock doesn't recognize
+        assert!(!rejected(CountTokensError::generic(
+            ErrorMetadata::builder()
+                .code("UnrecognizedClientException")
+                .message("The security token included in the request is invalid")
+                .build()
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_check_credentials_uses_client_check() {
+        let client = TestBedrockClient::new().with_count_tokens_response(Ok(1));
+        let provider = BedrockProvider::with_bedrock_client(Arc::new(client), TEST_MODEL);
+        provider.check_credentials().await.unwrap();
+
+        let client = TestBedrockClient::new()
+            .with_count_tokens_response(Err(ProviderError::Authentication("expired".to_string())));
+        let provider = BedrockProvider::with_bedrock_client(Arc::new(client), TEST_MODEL);
+        assert!(matches!(
+            provider.check_credentials().await,
+            Err(ProviderError::Authentication(_))
+        ));
+    }
+
     #[tokio::test]
     async fn test_clone_provider() {
         let client = TestBedrockClient::new();
@@ -1076,7 +1808,7 @@ mod tests {
 
         let cloned = provider.clone();
         assert_eq!(cloned.base_model_id, "test.model-v1:0");
-        assert_eq!(cloned.max_tokens, 500);
+        assert_eq!(cloned.max_tokens(), 500);
     }
 
     // ===== Error Classification Tests =====
@@ -1278,6 +2010,7 @@ mod tests {
             max_attempts: 5,
             base_delay_ms: 100,
             max_delay_ms: 5000,
+            ..Default::default()
         };
 
         let provider = BedrockProvider::with_bedrock_client(Arc::new(client), TEST_MODEL)
@@ -1297,6 +2030,27 @@ mod tests {
         assert_eq!(provider.retry_config.max_attempts, 3);
     }
 
+    #[tokio::test]
+    async fn test_retries_disabled_makes_one_attempt() {
+        let client = Arc::new(
+            TestBedrockClient::new()
+                .with_converse_response(Err(ProviderError::RateLimited("throttled".into())))
+                .with_converse_response(Err(ProviderError::RateLimited("throttled".into()))),
+        );
+        let provider = BedrockProvider::with_bedrock_client(client.clone(), TEST_MODEL)
+            .with_base_retry_delay(Duration::from_millis(1))
+            .with_retries_disabled();
+
+        let err = provider
+            .generate(vec![Message::user("Hello")], Vec::new(), None)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, ProviderError::RateLimited(_)), "{:?}", err);
+        assert_eq!(*client.converse_call_count.lock().unwrap(), 1);
+        assert_eq!(provider.retry_config.max_attempts, 1);
+    }
+
     #[test]
     fn test_provider_with_max_retry_delay() {
         let client = TestBedrockClient::new();
@@ -1315,6 +2069,107 @@ mod tests {
         assert_eq!(provider.retry_config.base_delay_ms, 200);
     }
 
+    #[test]
+    fn test_provider_with_retry_jitter() {
+        let client = TestBedrockClient::new();
+        let provider = BedrockProvider::with_bedrock_client(Arc::new(client), TEST_MODEL)
+            .with_retry_jitter(JitterStrategy::Decorrelated);
+
+        assert_eq!(provider.retry_config.jitter, JitterStrategy::Decorrelated);
+    }
+
+    #[test]
+    fn test_provider_with_retry_budget() {
+        let client = TestBedrockClient::new();
+        let provider = BedrockProvider::with_bedrock_client(Arc::new(client), TEST_MODEL)
+            .with_retry_budget(Duration::from_secs(5));
+
+        assert_eq!(
+            provider.retry_config.max_total_delay,
+            Some(Duration::from_secs(5))
+        );
+    }
+
+    #[test]
+    fn test_provider_with_interleaved_thinking() {
+        let client = TestBedrockClient::new();
+        let provider = BedrockProvider::with_bedrock_client(Arc::new(client), TEST_MODEL)
+            .with_thinking(2048)
+            .with_1m_context()
+            .with_interleaved_thinking(true)
+            .with_interleaved_thinking(true);
+
+        assert_eq!(
+            provider.additional_fields.get(ANTHROPIC_BETA_KEY),
+            Some(&serde_json::json!([
+                "context-1m-2025-08-07",
+                "interleaved-thinking-2025-05-14"
+            ]))
+        );
+
+        let provider = provider.with_interleaved_thinking(false);
+        assert_eq!(
+            provider.additional_fields.get(ANTHROPIC_BETA_KEY),
+            Some(&serde_json::json!(["context-1m-2025-08-07"]))
+        );
+    }
+
+    #[test]
+    fn test_betas_accumulate_without_duplicates() {
+        let client = TestBedrockClient::new();
+        let provider = BedrockProvider::with_bedrock_client(Arc::new(client), TEST_MODEL)
+            .with_beta(BedrockBeta::TokenEfficientTools)
+            .with_beta(BedrockBeta::Context1M)
+            .with_beta(BedrockBeta::TokenEfficientTools)
+            .with_1m_context()
+            .with_beta(BedrockBeta::FilesApi);
+
+        assert_eq!(
+            provider.additional_fields.get(ANTHROPIC_BETA_KEY),
+            Some(&serde_json::json!([
+                "token-efficient-tools-2025-02-19",
+                "context-1m-2025-08-07",
+                "files-api-2025-04-14"
+            ]))
+        );
+        assert_eq!(provider.max_context_tokens(), 1_000_000);
+    }
+
+    #[tokio::test]
+    async fn test_interleaved_thinking_requires_thinking() {
+        let client = Arc::new(TestBedrockClient::new());
+        let provider = BedrockProvider::with_bedrock_client(client.clone(), TEST_MODEL)
+            .with_interleaved_thinking(true);
+
+        let err = provider
+            .generate(vec![Message::user("Hello")], Vec::new(), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ProviderError::Configuration(_)), "{:?}", err);
+        assert_eq!(*client.converse_call_count.lock().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_supports_1m_context_for_sonnet_4_models() {
+        let client = Arc::new(TestBedrockClient::new());
+        let provider = BedrockProvider::with_bedrock_client(client.clone(), ClaudeSonnet4_5);
+        assert!(provider.supports(Feature::Context1M));
+        assert!(provider.supports(Feature::Thinking));
+        assert!(!provider.supports(Feature::WebSearch));
+        assert!(!provider.supports(Feature::ParallelToolUse));
+
+        let provider = BedrockProvider::with_bedrock_client(client.clone(), ClaudeSonnet4_6);
+        assert!(provider.supports(Feature::Context1M));
+
+        let provider = BedrockProvider::with_bedrock_client(client.clone(), ClaudeHaiku4_5);
+        assert!(!provider.supports(Feature::Context1M));
+
+        let provider = BedrockProvider::with_bedrock_client(client, NovaMicro);
+        assert!(!provider.supports(Feature::Context1M));
+        assert!(!provider.supports(Feature::Thinking));
+        assert!(provider.supports(Feature::PromptCaching));
+    }
+
     // ===== Inference Profile Default Tests =====
 
     #[test]
@@ -1357,6 +2212,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_builder_metadata() {
+        let client = TestBedrockClient::new();
+        let provider = BedrockProvider::with_bedrock_client(Arc::new(client), TEST_MODEL)
+            .with_metadata("user-123");
+
+        assert_eq!(
+            provider.additional_fields["metadata"],
+            serde_json::json!({"user_id": "user-123"})
+        );
+        let request = provider.build_request(Vec::new(), Vec::new(), None);
+        assert_eq!(
+            request.additional_fields["metadata"]["user_id"],
+            serde_json::json!("user-123")
+        );
+    }
+
     #[test]
     fn test_builder_additional_field() {
         let client = TestBedrockClient::new();
@@ -1381,6 +2253,17 @@ mod tests {
         assert_eq!(provider.inference_profile, InferenceProfile::US);
     }
 
+    #[test]
+    fn test_builder_direct_invocation() {
+        let client = TestBedrockClient::new();
+        let provider = BedrockProvider::with_bedrock_client(Arc::new(client), ClaudeSonnet4_5)
+            .with_direct_invocation();
+
+        assert_eq!(provider.inference_profile, InferenceProfile::None);
+        assert_eq!(provider.effective_model_id(), ClaudeSonnet4_5.bedrock_id());
+        assert!(provider.requires_inference_profile);
+    }
+
     #[test]
     fn test_builder_retry_callback() {
         use std::sync::atomic::{AtomicBool, Ordering};
@@ -1402,7 +2285,7 @@ mod tests {
         let client = TestBedrockClient::new();
         let provider = BedrockProvider::with_bedrock_client(Arc::new(client), TEST_MODEL);
 
-        assert_eq!(provider.max_tokens, DEFAULT_MAX_TOKENS);
+        assert_eq!(provider.max_tokens(), 4_096);
         assert!(provider.temperature.is_none());
         assert!(provider.top_p.is_none());
         assert!(provider.top_k.is_none());
@@ -1439,6 +2322,46 @@ mod tests {
         assert!(effective_id.contains(ClaudeSonnet4_5.bedrock_id()));
     }
 
+    #[test]
+    fn test_effective_model_id_with_inference_profile_arn() {
+        let arn =
+            "arn:aws:bedrock:us-east-1:123456789012:application-inference-profile/a1b2c3d4e5f6";
+        let client = TestBedrockClient::new();
+        let provider = BedrockProvider::with_bedrock_client(Arc::new(client), ClaudeSonnet4_5)
+            .with_inference_profile_arn(arn)
+            .unwrap();
+        assert_eq!(provider.effective_model_id(), arn);
+
+        // A later profile choice replaces the ARN
+        let provider = provider.with_inference_profile(InferenceProfile::US);
+        assert_eq!(
+            provider.effective_model_id(),
+            InferenceProfile::US.apply_to(ClaudeSonnet4_5.bedrock_id())
+        );
+    }
+
+    #[test]
+    fn test_inference_profile_arn_validation() {
+        assert!(check_inference_profile_arn(
+            "arn:aws:bedrock:us-west-2:123456789012:inference-profile/us.anthropic.claude-sonnet-4-5-20250929-v1:0"
+        )
+        .is_ok());
+        assert!(check_inference_profile_arn(
+            "arn:aws-us-gov:bedrock:us-gov-west-1:123456789012:application-inference-profile/x1"
+        )
+        .is_ok());
+
+        for arn in [
+            "us.anthropic.claude-sonnet-4-5-20250929-v1:0",
+            "arn:aws:s3:::bucket/inference-profile/x",
+            "arn:aws:bedrock:us-east-1:123456789012:foundation-model/anthropic.claude-v2",
+            "arn:aws:bedrock:us-east-1:123456789012:application-inference-profile/",
+        ] {
+            let err = check_inference_profile_arn(arn).unwrap_err();
+            assert!(matches!(err, ProviderError::Configuration(_)), "{}", arn);
+        }
+    }
+
     // ===== Additional Error Classification Tests =====
 
     #[test]
@@ -1570,4 +2493,34 @@ mod tests {
             assert!(fields.contains_key("extra"));
         }
     }
+
+    #[test]
+    fn test_seed_sent_in_additional_fields() {
+        let client = Arc::new(TestBedrockClient::new());
+        let provider =
+            BedrockProvider::with_bedrock_client(client.clone(), CohereCommandRPlus).with_seed(42);
+
+        let fields =
+            build_additional_model_fields(None, None, &provider.additional_fields).unwrap();
+        let aws_smithy_types::Document::Object(fields) = fields else {
+            panic!("Expected an object");
+        };
+        assert_eq!(
+            fields.get("seed"),
+            Some(&json_to_document(&serde_json::json!(42)))
+        );
+        assert_eq!(provider.settings().sampling.seed, Some(42));
+
+        // Models not known to take a seed reject unknown fields, so it is left out
+        for provider in [
+            BedrockProvider::with_bedrock_client(client.clone(), ClaudeSonnet4_5),
+            BedrockProvider::with_bedrock_client(client.clone(), NovaMicro),
+            BedrockProvider::with_bedrock_client(client, TEST_MODEL),
+        ] {
+            let provider = provider.with_seed(42);
+            assert!(!provider.supports(Feature::Seed));
+            assert!(provider.configure_seed(42).is_none());
+            assert!(!provider.additional_fields.contains_key("seed"));
+        }
+    }
 }
@@ -0,0 +1,313 @@
+//! Conversion of Converse stream events into provider stream events
+
+use std::collections::HashMap;
+
+use aws_sdk_bedrockruntime::types::{
+    ContentBlockDelta, ContentBlockStart, ConverseStreamOutput, ReasoningContentBlockDelta,
+};
+
+use super::conversion::{from_bedrock_stop_reason, stop_sequence_from_fields};
+use crate::events::TokenUsage;
+use crate::provider::tool_input::tool_use_events;
+use crate::provider::StreamEvent;
+use crate::types::StopReason;
+
+/// State carried across the events of one Converse stream
+///
+/// Bedrock sends `MessageStop` before the `Metadata` event with the final
+/// token usage, and some models send their only `Metadata` earlier or not at
+/// all. The `Stop` event is therefore held back until metadata arrives after
+/// `MessageStop` or the stream ends, and reports the last usage seen.
+pub(super) struct ConverseStreamState {
+    model_id: String,
+    /// Tool uses in progress by content block index: (id, name, input JSON)
+    tool_uses_in_progress: HashMap<i32, (String, String, String)>,
+    /// Reasoning blocks in progress by content block index: (text, signature)
+    reasoning_in_progress: HashMap<i32, (String, String)>,
+    /// Usage from the most recent `Metadata` event that carried any
+    usage: Option<TokenUsage>,
+    /// Stop reason from `MessageStop`, until the `Stop` event is emitted
+    stop_reason: Option<StopReason>,
+    /// Stop sequence the model matched, reported with `MessageStop`
+    stop_sequence: Option<String>,
+    /// Whether the `Stop` event has been emitted
+    done: bool,
+}
+
+impl ConverseStreamState {
+    pub(super) fn new(model_id: String) -> Self {
+        Self {
+            model_id,
+            tool_uses_in_progress: HashMap::new(),
+            reasoning_in_progress: HashMap::new(),
+            usage: None,
+            stop_reason: None,
+            stop_sequence: None,
+            done: false,
+        }
+    }
+
+    /// Whether `MessageStop` has arrived, so only usage metadata is missing
+    pub(super) fn stopped(&self) -> bool {
+        self.stop_reason.is_some() || self.done
+    }
+
+    /// Whether the `Stop` event has been emitted and reading can end
+    pub(super) fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Events for one Converse stream event
+    pub(super) fn on_event(&mut self, output: ConverseStreamOutput) -> Vec<StreamEvent> {
+        // After MessageStop only the usage metadata matters
+        if self.stopped() {
+            if let ConverseStreamOutput::Metadata(meta) = output {
+                self.record_usage(meta.usage);
+                return self.stop_event().into_iter().collect();
+            }
+            return Vec::new();
+        }
+
+        match output {
+            ConverseStreamOutput::ContentBlockStart(start) => {
+                if let Some(ContentBlockStart::ToolUse(tool_start)) = start.start {
+                    self.tool_uses_in_progress.insert(
+                        start.content_block_index,
+                        (tool_start.tool_use_id, tool_start.name, String::new()),
+                    );
+                }
+                Vec::new()
+            }
+            ConverseStreamOutput::ContentBlockDelta(delta) => match delta.delta {
+                Some(ContentBlockDelta::Text(text)) => vec![StreamEvent::TextDelta(text)],
+                Some(ContentBlockDelta::ToolUse(tool_delta)) => {
+                    match self
+                        .tool_uses_in_progress
+                        .get_mut(&delta.content_block_index)
+                    {
+                        Some(entry) => {
+                            entry.2.push_str(&tool_delta.input);
+                            vec![StreamEvent::ToolInputDelta {
+                                tool_use_id: entry.0.clone(),
+                                partial_json: tool_delta.input,
+                            }]
+                        }
+                        None => Vec::new(),
+                    }
+                }
+                Some(ContentBlockDelta::ReasoningContent(reasoning)) => {
+                    let entry = self
+                        .reasoning_in_progress
+                        .entry(delta.content_block_index)
+                        .or_default();
+                    match reasoning {
+                        ReasoningContentBlockDelta::Text(text) => {
+                            entry.0.push_str(&text);
+                            vec![StreamEvent::ThinkingDelta(text)]
+                        }
+                        ReasoningContentBlockDelta::Signature(signature) => {
+                            entry.1.push_str(&signature);
+                            Vec::new()
+                        }
+                        // Redacted reasoning is opaque bytes with no text form
+                        _ => Vec::new(),
+                    }
+                }
+                _ => Vec::new(),
+            },
+            ConverseStreamOutput::ContentBlockStop(stop) => {
+                let index = stop.content_block_index;
+                if let Some((id, name, input_json)) = self.tool_uses_in_progress.remove(&index) {
+                    tool_use_events(id, name, &input_json)
+                } else if let Some((thinking, signature)) =
+                    self.reasoning_in_progress.remove(&index)
+                {
+                    vec![StreamEvent::Thinking {
+                        thinking,
+                        signature,
+                    }]
+                } else {
+                    Vec::new()
+                }
+            }
+            ConverseStreamOutput::Metadata(meta) => {
+                self.record_usage(meta.usage);
+                Vec::new()
+            }
+            ConverseStreamOutput::MessageStop(stop) => {
+                self.stop_reason = Some(from_bedrock_stop_reason(&stop.stop_reason));
+                self.stop_sequence =
+                    stop_sequence_from_fields(stop.additional_model_response_fields.as_ref());
+                Vec::new()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Events for the end of the stream: a held-back `Stop`, then what
+    /// arrived of any tool use a cut-off stream never finished
+    pub(super) fn finish(mut self) -> Vec<StreamEvent> {
+        let mut events: Vec<StreamEvent> = self.stop_event().into_iter().collect();
+
+        let mut unfinished: Vec<_> = self.tool_uses_in_progress.into_iter().collect();
+        unfinished.sort_by_key(|(index, _)| *index);
+        for (_, (id, name, input_json)) in unfinished {
+            events.extend(tool_use_events(id, name, &input_json));
+        }
+        events
+    }
+
+    /// Keep usage from a metadata event, if it carried any
+    fn record_usage(&mut self, usage: Option<aws_sdk_bedrockruntime::types::TokenUsage>) {
+        if let Some(u) = usage {
+            self.usage = Some(TokenUsage {
+                input_tokens: u.input_tokens as usize,
+                output_tokens: u.output_tokens as usize,
+            });
+        }
+    }
+
+    /// The `Stop` event, if `MessageStop` arrived and it wasn't yet emitted
+    fn stop_event(&mut self) -> Option<StreamEvent> {
+        let stop_reason = self.stop_reason.take()?;
+        self.done = true;
+        Some(StreamEvent::Stop {
+            stop_reason,
+            stop_sequence: self.stop_sequence.take(),
+            usage: self.usage,
+            service_tier: None,
+            model: Some(self.model_id.clone()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_sdk_bedrockruntime::types::{
+        ContentBlockDeltaEvent, ConverseStreamMetadataEvent, MessageStopEvent,
+        StopReason as BedrockStopReason, TokenUsage as BedrockTokenUsage,
+    };
+
+    fn text(text: &str) -> ConverseStreamOutput {
+        ConverseStreamOutput::ContentBlockDelta(
+            ContentBlockDeltaEvent::builder()
+                .content_block_index(0)
+                .delta(ContentBlockDelta::Text(text.to_string()))
+                .build()
+                .unwrap(),
+        )
+    }
+
+    fn metadata(input_tokens: i32, output_tokens: i32) -> ConverseStreamOutput {
+        let usage = BedrockTokenUsage::builder()
+            .input_tokens(input_tokens)
+            .output_tokens(output_tokens)
+            .total_tokens(input_tokens + output_tokens)
+            .build()
+            .unwrap();
+        ConverseStreamOutput::Metadata(ConverseStreamMetadataEvent::builder().usage(usage).build())
+    }
+
+    fn message_stop() -> ConverseStreamOutput {
+        ConverseStreamOutput::MessageStop(
+            MessageStopEvent::builder()
+                .stop_reason(BedrockStopReason::EndTurn)
+                .build()
+                .unwrap(),
+        )
+    }
+
+    fn run(outputs: Vec<ConverseStreamOutput>) -> Vec<StreamEvent> {
+        let mut state = ConverseStreamState::new("model".to_string());
+        let mut events = Vec::new();
+        for output in outputs {
+            events.extend(state.on_event(output));
+            if state.is_done() {
+                break;
+            }
+        }
+        events.extend(state.finish());
+        events
+    }
+
+    /// Input and output tokens reported by the final `Stop` event
+    fn stop_usage(events: &[StreamEvent]) -> Option<(usize, usize)> {
+        match events.last() {
+            Some(StreamEvent::Stop { usage, .. }) => {
+                usage.map(|usage| (usage.input_tokens, usage.output_tokens))
+            }
+            other => panic!("Expected Stop last, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_stop_carries_matched_stop_sequence() {
+        let fields = aws_smithy_types::Document::Object(
+            [(
+                "stop_sequence".to_string(),
+                aws_smithy_types::Document::String("END".to_string()),
+            )]
+            .into(),
+        );
+        let stop = ConverseStreamOutput::MessageStop(
+            MessageStopEvent::builder()
+                .stop_reason(BedrockStopReason::StopSequence)
+                .additional_model_response_fields(fields)
+                .build()
+                .unwrap(),
+        );
+
+        let events = run(vec![text("Hi"), stop]);
+        match events.last() {
+            Some(StreamEvent::Stop {
+                stop_reason,
+                stop_sequence,
+                ..
+            }) => {
+                assert_eq!(*stop_reason, StopReason::StopSequence);
+                assert_eq!(stop_sequence.as_deref(), Some("END"));
+            }
+            other => panic!("Expected Stop last, got {:?}", other),
+        }
+
+        // Other stop reasons carry none
+        let events = run(vec![text("Hi"), message_stop()]);
+        assert!(matches!(
+            events.last(),
+            Some(StreamEvent::Stop {
+                stop_sequence: None,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_stop_carries_usage_from_metadata_after_message_stop() {
+        let events = run(vec![text("Hi"), message_stop(), metadata(10, 5)]);
+        assert!(matches!(&events[0], StreamEvent::TextDelta(t) if t == "Hi"));
+        assert_eq!(stop_usage(&events), Some((10, 5)));
+    }
+
+    #[test]
+    fn test_stop_keeps_usage_from_metadata_before_message_stop() {
+        let events = run(vec![metadata(12, 7), text("Hi"), message_stop()]);
+        assert_eq!(events.len(), 2);
+        assert_eq!(stop_usage(&events), Some((12, 7)));
+    }
+
+    #[test]
+    fn test_metadata_without_usage_keeps_earlier_usage() {
+        let empty = ConverseStreamOutput::Metadata(ConverseStreamMetadataEvent::builder().build());
+        let events = run(vec![metadata(3, 2), message_stop(), empty]);
+        assert_eq!(stop_usage(&events), Some((3, 2)));
+    }
+
+    #[test]
+    fn test_no_stop_without_message_stop() {
+        let events = run(vec![text("Hi"), metadata(1, 1)]);
+        assert!(!events
+            .iter()
+            .any(|event| matches!(event, StreamEvent::Stop { .. })));
+    }
+}
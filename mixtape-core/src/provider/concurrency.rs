@@ -0,0 +1,328 @@
+//! Shared limit on in-flight model calls
+//!
+//! Agents that share an account share its quota of concurrent requests. A
+//! [`ProviderConcurrencyGuard`] bounds how many `generate` and
+//! `generate_stream` calls run at once across every provider it guards, no
+//! matter which agent makes them. Calls over the limit wait for a slot.
+
+use std::sync::Arc;
+
+use futures::stream::{BoxStream, StreamExt};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use super::{Feature, ModelProvider, ProviderError, ProviderSettings, StreamEvent};
+use crate::model::ModelResponse;
+use crate::tool::DocumentFormat;
+use crate::types::{Message, ToolDefinition};
+
+/// Limit on concurrent model calls, shared by the providers it guards
+///
+/// Clones share the same limit. Wrap each provider with
+/// [`guard`](Self::guard) before handing it to an agent.
+///
+/// # Example
+///
+/// ```ignore
+/// use mixtape_core::{Agent, ClaudeHaiku4_5, ProviderConcurrencyGuard};
+///
+/// // At most four model calls in flight across both agents
+/// let guard = ProviderConcurrencyGuard::new(4);
+///
+/// let researcher = Agent::builder()
+///     .provider(guard.guard(BedrockProvider::new(ClaudeHaiku4_5).await?))
+///     .build()
+///     .await?;
+/// let writer = Agent::builder()
+///     .provider(guard.guard(BedrockProvider::new(ClaudeHaiku4_5).await?))
+///     .build()
+///     .await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct ProviderConcurrencyGuard {
+    semaphore: Arc<Semaphore>,
+    max_calls: usize,
+}
+
+impl ProviderConcurrencyGuard {
+    /// Allow at most `max_calls` model calls at once (at least one)
+    pub fn new(max_calls: usize) -> Self {
+        let max_calls = max_calls.max(1);
+        Self {
+            semaphore: Arc::new(Semaphore::new(max_calls)),
+            max_calls,
+        }
+    }
+
+    /// The most model calls allowed at once
+    pub fn max_calls(&self) -> usize {
+        self.max_calls
+    }
+
+    /// Model calls currently holding a slot
+    ///
+    /// A streaming call holds its slot until its stream ends or is dropped.
+    pub fn in_flight(&self) -> usize {
+        self.max_calls - self.semaphore.available_permits()
+    }
+
+    /// Wrap `provider` so its model calls count against this limit
+    pub fn guard<P: ModelProvider>(&self, provider: P) -> GuardedProvider<P> {
+        GuardedProvider {
+            inner: provider,
+            guard: self.clone(),
+        }
+    }
+
+    /// Wait for a slot
+    async fn acquire(&self) -> OwnedSemaphorePermit {
+        Arc::clone(&self.semaphore)
+            .acquire_owned()
+            .await
+            .expect("concurrency guard semaphore is never closed")
+    }
+}
+
+/// A provider whose model calls are limited by a [`ProviderConcurrencyGuard`]
+///
+/// Created with [`ProviderConcurrencyGuard::guard`]. Everything but
+/// `generate` and `generate_stream` passes straight through.
+pub struct GuardedProvider<P> {
+    inner: P,
+    guard: ProviderConcurrencyGuard,
+}
+
+impl<P> GuardedProvider<P> {
+    /// The wrapped provider
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    /// The guard limiting this provider's calls
+    pub fn concurrency_guard(&self) -> &ProviderConcurrencyGuard {
+        &self.guard
+    }
+
+    /// Guard a provider derived from the wrapped one
+    fn guard_derived(&self, provider: Arc<dyn ModelProvider>) -> Arc<dyn ModelProvider> {
+        Arc::new(self.guard.guard(provider))
+    }
+}
+
+#[async_trait::async_trait]
+impl<P: ModelProvider> ModelProvider for GuardedProvider<P> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn max_context_tokens(&self) -> usize {
+        self.inner.max_context_tokens()
+    }
+
+    fn max_output_tokens(&self) -> usize {
+        self.inner.max_output_tokens()
+    }
+
+    fn estimate_token_count(&self, text: &str) -> usize {
+        self.inner.estimate_token_count(text)
+    }
+
+    fn estimate_message_tokens(&self, messages: &[Message]) -> usize {
+        self.inner.estimate_message_tokens(messages)
+    }
+
+    fn supported_document_formats(&self) -> &[DocumentFormat] {
+        self.inner.supported_document_formats()
+    }
+
+    fn configure_parallel_tool_use(&self, enabled: bool) -> Option<Arc<dyn ModelProvider>> {
+        self.inner
+            .configure_parallel_tool_use(enabled)
+            .map(|provider| self.guard_derived(provider))
+    }
+
+    fn without_tool_use(&self) -> Option<Arc<dyn ModelProvider>> {
+        self.inner
+            .without_tool_use()
+            .map(|provider| self.guard_derived(provider))
+    }
+
+    fn without_stale_cache_breakpoints(
+        &self,
+        tools_changed: bool,
+    ) -> Option<Arc<dyn ModelProvider>> {
+        self.inner
+            .without_stale_cache_breakpoints(tools_changed)
+            .map(|provider| self.guard_derived(provider))
+    }
+
+    fn configure_seed(&self, seed: u64) -> Option<Arc<dyn ModelProvider>> {
+        self.inner
+            .configure_seed(seed)
+            .map(|provider| self.guard_derived(provider))
+    }
+
+    fn supports(&self, feature: Feature) -> bool {
+        self.inner.supports(feature)
+    }
+
+    fn settings(&self) -> ProviderSettings {
+        self.inner.settings()
+    }
+
+    async fn count_tokens(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+        system_prompt: Option<&str>,
+    ) -> Result<usize, ProviderError> {
+        self.inner
+            .count_tokens(messages, tools, system_prompt)
+            .await
+    }
+
+    fn estimated_max_tool_result_bytes(&self) -> Option<usize> {
+        self.inner.estimated_max_tool_result_bytes()
+    }
+
+    fn validate_tool_name(&self, name: &str) -> Result<(), String> {
+        self.inner.validate_tool_name(name)
+    }
+
+    async fn check_credentials(&self) -> Result<(), ProviderError> {
+        self.inner.check_credentials().await
+    }
+
+    async fn generate(
+        &self,
+        messages: Vec<Message>,
+        tools: Vec<ToolDefinition>,
+        system_prompt: Option<String>,
+    ) -> Result<ModelResponse, ProviderError> {
+        let _permit = self.guard.acquire().await;
+        self.inner.generate(messages, tools, system_prompt).await
+    }
+
+    async fn generate_stream(
+        &self,
+        messages: Vec<Message>,
+        tools: Vec<ToolDefinition>,
+        system_prompt: Option<String>,
+    ) -> Result<BoxStream<'static, Result<StreamEvent, ProviderError>>, ProviderError> {
+        let permit = self.guard.acquire().await;
+        let stream = self
+            .inner
+            .generate_stream(messages, tools, system_prompt)
+            .await?;
+
+        // The slot is released when the stream is dropped
+        Ok(stream
+            .map(move |event| {
+                let _ = &permit;
+                event
+            })
+            .boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::StopReason;
+    use std::time::Duration;
+
+    /// Provider whose calls take a while and record the most seen at once
+    struct SlowProvider {
+        active: Arc<std::sync::atomic::AtomicUsize>,
+        peak: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl ModelProvider for SlowProvider {
+        fn name(&self) -> &str {
+            "slow"
+        }
+
+        fn max_context_tokens(&self) -> usize {
+            200_000
+        }
+
+        fn max_output_tokens(&self) -> usize {
+            8_192
+        }
+
+        async fn generate(
+            &self,
+            _messages: Vec<Message>,
+            _tools: Vec<ToolDefinition>,
+            _system_prompt: Option<String>,
+        ) -> Result<ModelResponse, ProviderError> {
+            use std::sync::atomic::Ordering;
+
+            let active = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(active, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            self.active.fetch_sub(1, Ordering::SeqCst);
+
+            Ok(ModelResponse {
+                message: Message::assistant("done"),
+                stop_reason: StopReason::EndTurn,
+                stop_sequence: None,
+                usage: None,
+                service_tier: None,
+                model: None,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shared_guard_serializes_calls_across_providers() {
+        let active = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let peak = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let slow = || SlowProvider {
+            active: active.clone(),
+            peak: peak.clone(),
+        };
+
+        let guard = ProviderConcurrencyGuard::new(1);
+        let first = guard.guard(slow());
+        let second = guard.guard(slow());
+
+        let prompt = || vec![Message::user("Hi")];
+        let (a, b, c, d) = tokio::join!(
+            first.generate(prompt(), Vec::new(), None),
+            second.generate(prompt(), Vec::new(), None),
+            first.generate(prompt(), Vec::new(), None),
+            second.generate(prompt(), Vec::new(), None),
+        );
+        for result in [a, b, c, d] {
+            result.unwrap();
+        }
+
+        assert_eq!(peak.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert_eq!(guard.in_flight(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_stream_holds_slot_until_dropped() {
+        let guard = ProviderConcurrencyGuard::new(2);
+        let provider = guard.guard(SlowProvider {
+            active: Arc::default(),
+            peak: Arc::default(),
+        });
+
+        let stream = provider
+            .generate_stream(vec![Message::user("Hi")], Vec::new(), None)
+            .await
+            .unwrap();
+        assert_eq!(guard.in_flight(), 1);
+
+        drop(stream);
+        assert_eq!(guard.in_flight(), 0);
+        assert_eq!(guard.max_calls(), 2);
+    }
+
+    #[test]
+    fn test_guard_allows_at_least_one_call() {
+        assert_eq!(ProviderConcurrencyGuard::new(0).max_calls(), 1);
+    }
+}
@@ -5,25 +5,219 @@
 
 #[cfg(feature = "anthropic")]
 pub mod anthropic;
+mod assemble;
 #[cfg(feature = "bedrock")]
 pub mod bedrock;
+pub mod concurrency;
+#[cfg(feature = "test-utils")]
+mod parity;
 pub mod retry;
+#[cfg(any(feature = "anthropic", feature = "bedrock"))]
+pub(crate) mod tool_input;
 
 use crate::events::TokenUsage;
+use crate::tool::DocumentFormat;
 use crate::types::{Message, StopReason, ToolDefinition, ToolUseBlock};
 use futures::stream::BoxStream;
 use std::error::Error;
 
 // Re-export provider types at provider level
 #[cfg(feature = "anthropic")]
-pub use anthropic::AnthropicProvider;
+pub use anthropic::{AnthropicProvider, CachingStrategy, ServiceTier, ToolChoiceMode};
+pub(crate) use assemble::ResponseAssembler;
 #[cfg(feature = "bedrock")]
-pub use bedrock::{BedrockProvider, InferenceProfile};
-pub use retry::{RetryCallback, RetryConfig, RetryInfo};
+pub use bedrock::{BedrockBeta, BedrockProvider, InferenceProfile};
+pub use concurrency::{GuardedProvider, ProviderConcurrencyGuard};
+#[cfg(feature = "test-utils")]
+pub use parity::{test_parity, ParityScenario};
+pub use retry::{JitterStrategy, RetryCallback, RetryConfig, RetryInfo};
 
 // Re-export ModelResponse from model module
 pub use crate::model::ModelResponse;
 
+/// Upper bound on the default `max_tokens` of a provider
+///
+/// Providers default `max_tokens` to the model's output limit, capped at this
+/// value. Override per provider with `with_max_tokens` or `with_max_tokens_cap`.
+pub const DEFAULT_MAX_TOKENS_CAP: usize = 32_000;
+
+/// Default `max_tokens` for a model: its output limit, capped at `cap`
+#[cfg(any(feature = "anthropic", feature = "bedrock"))]
+pub(crate) fn default_max_tokens(max_output_tokens: usize, cap: usize) -> i32 {
+    max_output_tokens.min(cap).min(i32::MAX as usize) as i32
+}
+
+/// Check that `max_tokens` is between 1 and the model's output limit
+#[cfg(any(feature = "anthropic", feature = "bedrock"))]
+pub(crate) fn check_max_tokens(
+    max_tokens: i32,
+    max_output_tokens: usize,
+) -> Result<i32, ProviderError> {
+    let limit = default_max_tokens(max_output_tokens, usize::MAX);
+    if (1..=limit).contains(&max_tokens) {
+        Ok(max_tokens)
+    } else {
+        Err(ProviderError::Configuration(format!(
+            "max_tokens must be between 1 and the model's output limit of {}, got {}",
+            limit, max_tokens
+        )))
+    }
+}
+
+/// Clamp `max_tokens` into `1..=max_output_tokens`, warning when it changes
+#[cfg(any(feature = "anthropic", feature = "bedrock"))]
+pub(crate) fn clamp_max_tokens(max_tokens: i32, max_output_tokens: usize) -> i32 {
+    if check_max_tokens(max_tokens, max_output_tokens).is_ok() {
+        return max_tokens;
+    }
+    let clamped = max_tokens.clamp(1, default_max_tokens(max_output_tokens, usize::MAX));
+    eprintln!(
+        "Warning: max_tokens {} is outside 1 to the model's output limit; using {}",
+        max_tokens, clamped
+    );
+    clamped
+}
+
+/// Check that `temperature` or `top_p` is within `0.0..=1.0`
+#[cfg(any(feature = "anthropic", feature = "bedrock"))]
+pub(crate) fn check_unit_range(name: &str, value: f32) -> Result<f32, ProviderError> {
+    if (0.0..=1.0).contains(&value) {
+        Ok(value)
+    } else {
+        Err(ProviderError::Configuration(format!(
+            "{} must be between 0.0 and 1.0, got {}",
+            name, value
+        )))
+    }
+}
+
+/// Clamp `temperature` or `top_p` into `0.0..=1.0`, warning when it changes
+///
+/// NaN has no nearest valid value, so it leaves the parameter unset.
+#[cfg(any(feature = "anthropic", feature = "bedrock"))]
+pub(crate) fn clamp_unit_range(name: &str, value: f32) -> Option<f32> {
+    if value.is_nan() {
+        eprintln!("Warning: {} is NaN; leaving it unset", name);
+        return None;
+    }
+    if check_unit_range(name, value).is_err() {
+        let clamped = value.clamp(0.0, 1.0);
+        eprintln!(
+            "Warning: {} {} is outside 0.0 to 1.0; using {}",
+            name, value, clamped
+        );
+        return Some(clamped);
+    }
+    Some(value)
+}
+
+/// Check that `top_k` is at least 1
+#[cfg(any(feature = "anthropic", feature = "bedrock"))]
+pub(crate) fn check_top_k(top_k: u32) -> Result<u32, ProviderError> {
+    if top_k == 0 {
+        return Err(ProviderError::Configuration(
+            "top_k must be at least 1".to_string(),
+        ));
+    }
+    Ok(top_k)
+}
+
+/// Longest tool name Anthropic and Bedrock accept
+const MAX_TOOL_NAME_LEN: usize = 64;
+
+/// Check a tool name against the Anthropic and Bedrock naming rules
+pub(crate) fn validate_tool_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("name is empty".to_string());
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || *c == '_' || *c == '-'))
+    {
+        return Err(format!(
+            "'{}' is not allowed; use ASCII letters, digits, '_', or '-'",
+            c
+        ));
+    }
+    // All ASCII from here, so bytes and characters agree
+    if name.len() > MAX_TOOL_NAME_LEN {
+        return Err(format!(
+            "name is {} characters, more than the {} allowed",
+            name.len(),
+            MAX_TOOL_NAME_LEN
+        ));
+    }
+    Ok(())
+}
+
+/// Sampling parameters a provider sends with each request
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SamplingParams {
+    /// Effective `max_tokens`, including any default derived from the model
+    pub max_tokens: Option<i32>,
+    /// Temperature, when set
+    pub temperature: Option<f32>,
+    /// Nucleus sampling threshold, when set
+    pub top_p: Option<f32>,
+    /// Top-k sampling limit, when set
+    pub top_k: Option<u32>,
+    /// Sampling seed, when set and sent to the model
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+/// Descriptive provider configuration, without credentials
+///
+/// Returned by [`ModelProvider::settings`] and included in
+/// [`AgentConfigSnapshot`](crate::AgentConfigSnapshot).
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct ProviderSettings {
+    /// Model ID sent to the API (e.g. `claude-sonnet-4-5-20250929`)
+    pub model_id: Option<String>,
+    /// Sampling parameters sent with each request
+    pub sampling: SamplingParams,
+    /// Retry behavior on transient errors
+    pub retry: Option<RetryConfig>,
+}
+
+/// Optional capabilities a provider may offer for its model
+///
+/// Query with [`ModelProvider::supports`] before relying on a feature, or
+/// have [`AgentBuilder::require_feature`](crate::AgentBuilder::require_feature)
+/// fail the build when it is missing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Feature {
+    /// Extended thinking before responding
+    Thinking,
+    /// Server-side web search tool
+    WebSearch,
+    /// Prompt caching with cache breakpoints
+    PromptCaching,
+    /// Document blocks in user messages
+    Documents,
+    /// A native switch to allow or forbid several tool calls per turn
+    ParallelToolUse,
+    /// The 1M-token context window
+    Context1M,
+    /// A sampling seed for more reproducible output
+    Seed,
+}
+
+impl std::fmt::Display for Feature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Feature::Thinking => "extended thinking",
+            Feature::WebSearch => "web search",
+            Feature::PromptCaching => "prompt caching",
+            Feature::Documents => "document input",
+            Feature::ParallelToolUse => "parallel tool use control",
+            Feature::Context1M => "1M-token context",
+            Feature::Seed => "seeded sampling",
+        };
+        f.write_str(name)
+    }
+}
+
 /// Events from streaming model responses
 #[derive(Debug, Clone)]
 pub enum StreamEvent {
@@ -31,14 +225,64 @@ pub enum StreamEvent {
     TextDelta(String),
     /// Tool use detected
     ToolUse(ToolUseBlock),
+    /// Fragment of a tool use's input JSON, as the model streams it
+    ///
+    /// The complete, parsed input arrives in the following `ToolUse`.
+    ToolInputDelta {
+        /// ID of the tool use, as reported by the provider
+        tool_use_id: String,
+        /// The next piece of the input JSON text
+        partial_json: String,
+    },
+    /// The streamed input of the next `ToolUse` was truncated JSON that was
+    /// completed by closing its open strings, arrays, and objects
+    ToolInputRepaired {
+        /// ID of the tool use
+        tool_use_id: String,
+        /// The input text as received
+        raw_input: String,
+    },
+    /// The streamed input of the next `ToolUse` was not valid JSON and could
+    /// not be repaired
+    ///
+    /// The `ToolUse` carries `{}` as its input. The agent answers it with an
+    /// error result instead of running the tool.
+    ToolInputInvalid {
+        /// ID of the tool use
+        tool_use_id: String,
+        /// The input text as received
+        raw_input: String,
+        /// Why the input could not be parsed
+        error: String,
+    },
     /// Incremental thinking delta (extended thinking)
     ThinkingDelta(String),
+    /// Completed thinking block, emitted after its deltas
+    ///
+    /// Carries the signature the provider requires when the block is sent
+    /// back in a later turn.
+    Thinking {
+        /// The full thinking content
+        thinking: String,
+        /// Signature for multi-turn thinking verification
+        signature: String,
+    },
+    /// A source cited by the text streamed before it
+    ///
+    /// Providers emit a text block's citations after its last `TextDelta`.
+    Citation(crate::types::Citation),
     /// Streaming stopped
     Stop {
         /// Why the model stopped
         stop_reason: StopReason,
+        /// The stop sequence that ended generation (if reported)
+        stop_sequence: Option<String>,
         /// Token usage for this response (if available)
         usage: Option<TokenUsage>,
+        /// Service tier that handled the request (if reported)
+        service_tier: Option<String>,
+        /// Model that served the request (if known)
+        model: Option<String>,
     },
 }
 
@@ -117,12 +361,158 @@ pub trait ModelProvider: Send + Sync {
         for message in messages {
             total += 4; // Role overhead
             for block in &message.content {
-                total += self.estimate_token_count(&format!("{:?}", block));
+                total += match block {
+                    // Debug output of raw document bytes would grossly overestimate
+                    crate::types::ContentBlock::Document(document) => document.estimated_tokens(),
+                    _ => self.estimate_token_count(&format!("{:?}", block)),
+                };
             }
         }
         total
     }
 
+    /// Document formats this provider accepts in user messages
+    ///
+    /// [`Agent::run_with_document`](crate::Agent::run_with_document) rejects
+    /// documents in any other format before calling the provider. The default
+    /// is empty: providers must opt in to document input.
+    fn supported_document_formats(&self) -> &[DocumentFormat] {
+        &[]
+    }
+
+    /// This provider, configured to allow or forbid several tool calls per turn
+    ///
+    /// Providers whose API has a native switch return a copy with it set.
+    /// The default returns `None`, and
+    /// [`AgentBuilder::with_parallel_tool_use`](crate::AgentBuilder::with_parallel_tool_use)
+    /// falls back to guidance in the system prompt.
+    fn configure_parallel_tool_use(
+        &self,
+        _enabled: bool,
+    ) -> Option<std::sync::Arc<dyn ModelProvider>> {
+        None
+    }
+
+    /// This provider, configured so the model answers without calling tools
+    ///
+    /// Tools are still sent with the request, so a conversation containing
+    /// tool uses stays valid. The default returns `None`, and
+    /// [`AgentBuilder::with_tool_json_recovery`](crate::AgentBuilder::with_tool_json_recovery)
+    /// falls back to asking the model in the tool results.
+    fn without_tool_use(&self) -> Option<std::sync::Arc<dyn ModelProvider>> {
+        None
+    }
+
+    /// This provider, configured to leave cache breakpoints off a prompt
+    /// prefix that just changed
+    ///
+    /// Agents built with
+    /// [`AgentBuilder::with_stale_prefix_uncached`](crate::AgentBuilder::with_stale_prefix_uncached)
+    /// call this for the first model call after the system prompt or tools
+    /// change (see [`AgentEvent::CacheInvalidated`](crate::AgentEvent::CacheInvalidated)).
+    /// The changed part of the prefix misses the cache either way. Leaving it
+    /// unmarked skips that call's cache write, but the next call then has to
+    /// write it instead of reading it, so this usually costs more unless the
+    /// prefix keeps changing. Breakpoints return on the next call.
+    /// `tools_changed` is true when the tool definitions changed; otherwise
+    /// only the system prompt did. The default returns `None`, for providers
+    /// that place no cache breakpoints.
+    fn without_stale_cache_breakpoints(
+        &self,
+        _tools_changed: bool,
+    ) -> Option<std::sync::Arc<dyn ModelProvider>> {
+        None
+    }
+
+    /// A copy of this provider that sends `seed` with each request
+    ///
+    /// Used by [`AgentBuilder::with_seed`](crate::AgentBuilder::with_seed).
+    /// Seeding is best-effort: even a seeded model may not repeat its output
+    /// exactly. The default returns `None` for providers whose model takes no
+    /// seed, and the builder warns that the seed is ignored.
+    fn configure_seed(&self, _seed: u64) -> Option<std::sync::Arc<dyn ModelProvider>> {
+        None
+    }
+
+    /// Whether this provider offers `feature` for its model
+    ///
+    /// The default reports document input when
+    /// [`supported_document_formats`](Self::supported_document_formats) is
+    /// non-empty, and nothing else.
+    fn supports(&self, feature: Feature) -> bool {
+        feature == Feature::Documents && !self.supported_document_formats().is_empty()
+    }
+
+    /// Model ID, sampling parameters, and retry configuration
+    ///
+    /// Descriptive only: credentials and clients are never included. The
+    /// default reports nothing beyond empty settings.
+    fn settings(&self) -> ProviderSettings {
+        ProviderSettings::default()
+    }
+
+    /// Count input tokens for a request
+    ///
+    /// Providers with a token counting API should override this to return
+    /// the exact count the model will see. The default implementation falls
+    /// back to the local estimate.
+    ///
+    /// # Arguments
+    /// * `messages` - The conversation history
+    /// * `tools` - Available tools for the model to use
+    /// * `system_prompt` - Optional system prompt
+    async fn count_tokens(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+        system_prompt: Option<&str>,
+    ) -> Result<usize, ProviderError> {
+        Ok(estimate_request_tokens(
+            self,
+            messages,
+            tools,
+            system_prompt,
+        ))
+    }
+
+    /// Estimated largest tool result, in bytes, to send the model in one
+    /// request
+    ///
+    /// Text and JSON results over the limit are cut, with a note saying so,
+    /// before they reach the provider, so one oversized result can't get the
+    /// whole request rejected. This is a heuristic, not a limit the provider
+    /// reports: the default allows about half the context window at the four
+    /// bytes per token of [`estimate_token_count`](Self::estimate_token_count),
+    /// well below the request size limits of Anthropic and Bedrock. Return
+    /// `None` to send results of any size.
+    fn estimated_max_tool_result_bytes(&self) -> Option<usize> {
+        Some(self.max_context_tokens().saturating_mul(2))
+    }
+
+    /// Check a tool name against the provider's naming rules
+    ///
+    /// Returns why the name is rejected, if it is. Checked for every tool by
+    /// [`AgentBuilder::build`](crate::AgentBuilder::build), since one bad name
+    /// gets the whole request rejected. The default is the rule shared by
+    /// Anthropic and Bedrock: 1 to 64 ASCII letters, digits, underscores,
+    /// and hyphens.
+    fn validate_tool_name(&self, name: &str) -> Result<(), String> {
+        validate_tool_name(name)
+    }
+
+    /// Check that the provider's credentials are accepted
+    ///
+    /// Used by [`AgentBuilder::with_credential_check`](crate::AgentBuilder::with_credential_check).
+    /// The default implementation counts the tokens of a one-word message,
+    /// which is free with Anthropic and needs no model output. Bedrock
+    /// overrides it so roles without `bedrock:CountTokens` still pass.
+    /// Providers that count tokens locally have nothing to check and succeed.
+    async fn check_credentials(&self) -> Result<(), ProviderError> {
+        self.count_tokens(&[Message::user("ping")], &[], None)
+            .await
+            .map(|_| ())
+    }
+
     /// Send a request to the model and get a response
     ///
     /// # Arguments
@@ -151,39 +541,63 @@ pub trait ModelProvider: Send + Sync {
         // Default implementation: call generate and return complete response
         let response = self.generate(messages, tools, system_prompt).await?;
 
-        // Extract text content and tool uses from response message
-        let mut text_content = String::new();
-        let mut tool_uses = Vec::new();
-
-        for content in &response.message.content {
+        // Replay the response content as stream events, preserving block order
+        let mut events = Vec::new();
+        for content in response.message.content {
             match content {
-                crate::types::ContentBlock::Text(text) => {
-                    text_content.push_str(text);
+                crate::types::ContentBlock::Text(text) if !text.is_empty() => {
+                    events.push(Ok(StreamEvent::TextDelta(text)));
                 }
                 crate::types::ContentBlock::ToolUse(tool_use) => {
-                    tool_uses.push(tool_use.clone());
+                    events.push(Ok(StreamEvent::ToolUse(tool_use)));
+                }
+                crate::types::ContentBlock::Thinking {
+                    thinking,
+                    signature,
+                } => {
+                    events.push(Ok(StreamEvent::ThinkingDelta(thinking.clone())));
+                    events.push(Ok(StreamEvent::Thinking {
+                        thinking,
+                        signature,
+                    }));
+                }
+                crate::types::ContentBlock::Citations { citations } => {
+                    events.extend(citations.into_iter().map(|c| Ok(StreamEvent::Citation(c))));
                 }
                 _ => {}
             }
         }
-
-        // Create a stream with the complete response
-        let mut events = Vec::new();
-        if !text_content.is_empty() {
-            events.push(Ok(StreamEvent::TextDelta(text_content)));
-        }
-        for tool_use in tool_uses {
-            events.push(Ok(StreamEvent::ToolUse(tool_use)));
-        }
         events.push(Ok(StreamEvent::Stop {
             stop_reason: response.stop_reason,
+            stop_sequence: response.stop_sequence,
             usage: response.usage,
+            service_tier: response.service_tier,
+            model: response.model,
         }));
 
         Ok(Box::pin(futures::stream::iter(events)))
     }
 }
 
+/// Estimate input tokens for a full request (messages, tools, and system prompt)
+pub(crate) fn estimate_request_tokens<P: ModelProvider + ?Sized>(
+    provider: &P,
+    messages: &[Message],
+    tools: &[ToolDefinition],
+    system_prompt: Option<&str>,
+) -> usize {
+    let mut total = provider.estimate_message_tokens(messages);
+    if let Some(system) = system_prompt {
+        total += provider.estimate_token_count(system);
+    }
+    for tool in tools {
+        total += provider.estimate_token_count(&tool.name);
+        total += provider.estimate_token_count(&tool.description);
+        total += provider.estimate_token_count(&tool.input_schema.to_string());
+    }
+    total
+}
+
 // Implement ModelProvider for Arc<dyn ModelProvider> to support dynamic dispatch
 #[async_trait::async_trait]
 impl ModelProvider for std::sync::Arc<dyn ModelProvider> {
@@ -207,6 +621,61 @@ impl ModelProvider for std::sync::Arc<dyn ModelProvider> {
         (**self).estimate_message_tokens(messages)
     }
 
+    fn supported_document_formats(&self) -> &[DocumentFormat] {
+        (**self).supported_document_formats()
+    }
+
+    fn configure_parallel_tool_use(
+        &self,
+        enabled: bool,
+    ) -> Option<std::sync::Arc<dyn ModelProvider>> {
+        (**self).configure_parallel_tool_use(enabled)
+    }
+
+    fn without_tool_use(&self) -> Option<std::sync::Arc<dyn ModelProvider>> {
+        (**self).without_tool_use()
+    }
+
+    fn without_stale_cache_breakpoints(
+        &self,
+        tools_changed: bool,
+    ) -> Option<std::sync::Arc<dyn ModelProvider>> {
+        (**self).without_stale_cache_breakpoints(tools_changed)
+    }
+
+    fn configure_seed(&self, seed: u64) -> Option<std::sync::Arc<dyn ModelProvider>> {
+        (**self).configure_seed(seed)
+    }
+
+    fn supports(&self, feature: Feature) -> bool {
+        (**self).supports(feature)
+    }
+
+    fn settings(&self) -> ProviderSettings {
+        (**self).settings()
+    }
+
+    async fn count_tokens(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+        system_prompt: Option<&str>,
+    ) -> Result<usize, ProviderError> {
+        (**self).count_tokens(messages, tools, system_prompt).await
+    }
+
+    fn estimated_max_tool_result_bytes(&self) -> Option<usize> {
+        (**self).estimated_max_tool_result_bytes()
+    }
+
+    fn validate_tool_name(&self, name: &str) -> Result<(), String> {
+        (**self).validate_tool_name(name)
+    }
+
+    async fn check_credentials(&self) -> Result<(), ProviderError> {
+        (**self).check_credentials().await
+    }
+
     async fn generate(
         &self,
         messages: Vec<Message>,
@@ -0,0 +1,276 @@
+//! Checks that a provider's streaming and non-streaming calls agree
+//!
+//! The agent streams every model call, but providers implement `generate`
+//! and `generate_stream` separately, and the two drift: a thinking block
+//! dropped from the stream, usage missing from its `Stop` event, tool input
+//! parsed differently. [`test_parity`] sends one request both ways and
+//! fails if the responses differ.
+//!
+//! Enable with the `test-utils` feature.
+
+use futures::StreamExt;
+
+use super::{ModelProvider, ResponseAssembler};
+use crate::model::ModelResponse;
+use crate::types::{Message, ToolDefinition};
+
+/// A request sent once with `generate` and once with `generate_stream`
+///
+/// # Example
+///
+/// ```rust
+/// use mixtape_core::provider::ParityScenario;
+/// use mixtape_core::ToolDefinition;
+/// use serde_json::json;
+///
+/// let scenario = ParityScenario::new("What's the weather in Paris?")
+///     .with_system_prompt("You are a weather assistant")
+///     .with_tool(ToolDefinition {
+///         name: "get_weather".to_string(),
+///         description: "Current weather for a city".to_string(),
+///         input_schema: json!({"type": "object"}),
+///     });
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ParityScenario {
+    /// Conversation sent to the model
+    pub messages: Vec<Message>,
+    /// Tools offered to the model
+    pub tools: Vec<ToolDefinition>,
+    /// System prompt, if any
+    pub system_prompt: Option<String>,
+}
+
+impl ParityScenario {
+    /// A scenario with a single user message
+    pub fn new(prompt: impl Into<String>) -> Self {
+        Self {
+            messages: vec![Message::user(prompt)],
+            ..Self::default()
+        }
+    }
+
+    /// Offer a tool to the model
+    pub fn with_tool(mut self, tool: ToolDefinition) -> Self {
+        self.tools.push(tool);
+        self
+    }
+
+    /// Set the system prompt
+    pub fn with_system_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.system_prompt = Some(prompt.into());
+        self
+    }
+}
+
+/// Run `scenario` through `generate` and `generate_stream` and assert the
+/// responses agree
+///
+/// The streamed events are assembled the way the agent assembles them, then
+/// compared with the non-streaming response: message content (text,
+/// thinking, tool uses with their input, citations), stop reason, stop
+/// sequence, and token usage. Returns the non-streaming response for further
+/// assertions.
+///
+/// The provider must answer the scenario identically both times, so point
+/// it at recorded responses (a mock server, or a mock provider queued with
+/// the same response twice) rather than a live model.
+///
+/// # Panics
+///
+/// Panics if either call fails or the responses differ.
+///
+/// # Example
+///
+/// ```rust
+/// use mixtape_core::provider::{test_parity, ParityScenario};
+/// use mixtape_core::test_utils::MockProvider;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let provider = MockProvider::new().with_text("Hello!").with_text("Hello!");
+///
+/// let response = test_parity(&provider, ParityScenario::new("Hi")).await;
+/// assert_eq!(response.message.text(), "Hello!");
+/// # }
+/// ```
+pub async fn test_parity<P: ModelProvider + ?Sized>(
+    provider: &P,
+    scenario: ParityScenario,
+) -> ModelResponse {
+    let ParityScenario {
+        messages,
+        tools,
+        system_prompt,
+    } = scenario;
+
+    let generated = provider
+        .generate(messages.clone(), tools.clone(), system_prompt.clone())
+        .await
+        .unwrap_or_else(|e| panic!("{}: generate failed: {}", provider.name(), e));
+
+    let mut stream = provider
+        .generate_stream(messages, tools, system_prompt)
+        .await
+        .unwrap_or_else(|e| panic!("{}: generate_stream failed: {}", provider.name(), e));
+    let mut assembler = ResponseAssembler::new();
+    while let Some(event) = stream.next().await {
+        let event = event.unwrap_or_else(|e| panic!("{}: stream failed: {}", provider.name(), e));
+        assembler.push(event);
+    }
+    let streamed = assembler.finish();
+
+    assert_eq!(
+        format!("{:#?}", generated.message),
+        format!("{:#?}", streamed.message),
+        "{}: streamed message differs from generated (left: generate, right: stream)",
+        provider.name()
+    );
+    assert_eq!(
+        generated.stop_reason,
+        streamed.stop_reason,
+        "{}: stop reason differs (left: generate, right: stream)",
+        provider.name()
+    );
+    assert_eq!(
+        generated.stop_sequence,
+        streamed.stop_sequence,
+        "{}: stop sequence differs (left: generate, right: stream)",
+        provider.name()
+    );
+    let tokens = |response: &ModelResponse| {
+        response
+            .usage
+            .map(|usage| (usage.input_tokens, usage.output_tokens))
+    };
+    assert_eq!(
+        tokens(&generated),
+        tokens(&streamed),
+        "{}: usage (input, output tokens) differs (left: generate, right: stream)",
+        provider.name()
+    );
+
+    generated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::TokenUsage;
+    use crate::provider::{ProviderError, StreamEvent};
+    use crate::test_utils::MockProvider;
+    use crate::types::{ContentBlock, Role, StopReason, ToolUseBlock};
+    use futures::stream::BoxStream;
+    use serde_json::json;
+
+    fn response_with_thinking_and_tool_use() -> ModelResponse {
+        ModelResponse {
+            message: Message {
+                role: Role::Assistant,
+                content: vec![
+                    ContentBlock::Thinking {
+                        thinking: "Need the weather".to_string(),
+                        signature: "sig".to_string(),
+                    },
+                    ContentBlock::Text("Checking.".to_string()),
+                    ContentBlock::ToolUse(ToolUseBlock {
+                        id: "tool_1".to_string(),
+                        name: "get_weather".to_string(),
+                        input: json!({"city": "Paris"}),
+                    }),
+                ],
+            },
+            stop_reason: StopReason::ToolUse,
+            stop_sequence: None,
+            usage: Some(TokenUsage {
+                input_tokens: 42,
+                output_tokens: 17,
+            }),
+            service_tier: None,
+            model: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mock_provider_has_parity() {
+        let response = response_with_thinking_and_tool_use();
+        let provider = MockProvider::new()
+            .with_response(response.clone())
+            .with_response(response);
+
+        let generated = test_parity(&provider, ParityScenario::new("Weather in Paris?")).await;
+
+        assert_eq!(generated.message.content.len(), 3);
+        assert_eq!(provider.call_count(), 2);
+    }
+
+    /// Provider whose stream drops usage from its `Stop` event
+    struct UsageDroppingProvider(MockProvider);
+
+    #[async_trait::async_trait]
+    impl ModelProvider for UsageDroppingProvider {
+        fn name(&self) -> &str {
+            "UsageDroppingProvider"
+        }
+
+        fn max_context_tokens(&self) -> usize {
+            self.0.max_context_tokens()
+        }
+
+        fn max_output_tokens(&self) -> usize {
+            self.0.max_output_tokens()
+        }
+
+        async fn generate(
+            &self,
+            messages: Vec<Message>,
+            tools: Vec<ToolDefinition>,
+            system_prompt: Option<String>,
+        ) -> Result<ModelResponse, ProviderError> {
+            self.0.generate(messages, tools, system_prompt).await
+        }
+
+        async fn generate_stream(
+            &self,
+            messages: Vec<Message>,
+            tools: Vec<ToolDefinition>,
+            system_prompt: Option<String>,
+        ) -> Result<BoxStream<'static, Result<StreamEvent, ProviderError>>, ProviderError> {
+            let stream = self
+                .0
+                .generate_stream(messages, tools, system_prompt)
+                .await?;
+            Ok(stream
+                .map(|event| match event {
+                    Ok(StreamEvent::Stop {
+                        stop_reason,
+                        stop_sequence,
+                        service_tier,
+                        model,
+                        ..
+                    }) => Ok(StreamEvent::Stop {
+                        stop_reason,
+                        stop_sequence,
+                        usage: None,
+                        service_tier,
+                        model,
+                    }),
+                    event => event,
+                })
+                .boxed())
+        }
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "UsageDroppingProvider: usage (input, output tokens) differs")]
+    async fn test_detects_usage_missing_from_stream() {
+        let response = response_with_thinking_and_tool_use();
+        let provider = UsageDroppingProvider(
+            MockProvider::new()
+                .with_response(response.clone())
+                .with_response(response),
+        );
+
+        test_parity(&provider, ParityScenario::new("Weather in Paris?")).await;
+    }
+}
@@ -5,18 +5,56 @@
 //! network issues.
 
 use super::ProviderError;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::time::Duration;
+
+/// How randomness is applied to retry delays
+///
+/// Strategies follow the AWS Architecture Blog post "Exponential Backoff and
+/// Jitter". With `cap = min(max_delay, base_delay * 2^(attempt - 1))`,
+/// counting attempts from 1:
+///
+/// | Strategy       | Delay                                              |
+/// |----------------|----------------------------------------------------|
+/// | `None`         | `cap`                                              |
+/// | `Full`         | `random(0, cap)`                                   |
+/// | `Equal`        | `cap / 2 + random(0, cap / 2)`                     |
+/// | `Decorrelated` | `min(max_delay, random(base_delay, previous * 3))` |
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JitterStrategy {
+    /// Plain exponential backoff with no randomness
+    None,
+    /// Uniformly random delay between zero and the exponential cap
+    #[default]
+    Full,
+    /// Half the exponential cap plus a random amount up to the other half
+    Equal,
+    /// Random delay between the base and three times the previous delay
+    Decorrelated,
+}
 
 /// Configuration for retry behavior on transient errors (throttling, rate limits)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RetryConfig {
-    /// Maximum number of retry attempts (default: 8)
+    /// Maximum number of attempts, counting the first (default: 8)
+    ///
+    /// A request is sent at most this many times, so the number of retries
+    /// is `max_attempts - 1`. Both 0 and 1 mean a single attempt.
     pub max_attempts: usize,
     /// Base delay in milliseconds for exponential backoff (default: 500ms)
     pub base_delay_ms: u64,
     /// Maximum delay cap in milliseconds (default: 30000ms)
     pub max_delay_ms: u64,
+    /// Jitter applied to each delay (default: [`JitterStrategy::Full`])
+    pub jitter: JitterStrategy,
+    /// Cap on the total time spent sleeping between retries (default: none)
+    ///
+    /// When the next delay would push the cumulative sleep past this budget,
+    /// retrying stops and the last error is returned.
+    pub max_total_delay: Option<Duration>,
 }
 
 impl Default for RetryConfig {
@@ -25,6 +63,18 @@ impl Default for RetryConfig {
             max_attempts: 8,
             base_delay_ms: 500,
             max_delay_ms: 30_000,
+            jitter: JitterStrategy::default(),
+            max_total_delay: None,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// A configuration that sends each request exactly once
+    pub fn disabled() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
         }
     }
 }
@@ -63,31 +113,55 @@ pub fn is_retryable_error(err: &ProviderError) -> bool {
 }
 
 /// Calculate backoff delay for a given attempt using exponential backoff with jitter
+///
+/// Decorrelated jitter depends on the previous delay; this treats the
+/// previous delay as the base delay. [`retry_with_backoff`] tracks the
+/// actual previous delay across attempts.
 pub fn backoff_delay(attempt: usize, config: &RetryConfig) -> Duration {
+    next_delay(attempt, None, config, &mut rand::thread_rng())
+}
+
+/// Exponential cap for an attempt: `min(max_delay, base_delay * 2^(attempt - 1))`
+fn exponential_cap_ms(attempt: usize, config: &RetryConfig) -> u64 {
     let shift = (attempt.saturating_sub(1)).min(10) as u32;
     let exp = 1_u64.checked_shl(shift).unwrap_or(u64::MAX);
-    let base = config.base_delay_ms.saturating_mul(exp);
-    let capped = base.min(config.max_delay_ms);
-    let jittered = jitter_ms(capped);
-    Duration::from_millis(jittered)
+    config
+        .base_delay_ms
+        .saturating_mul(exp)
+        .min(config.max_delay_ms)
 }
 
-/// Apply ±20% jitter to a base delay
-fn jitter_ms(base_ms: u64) -> u64 {
-    let nanos = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .subsec_nanos() as i64;
-    let jitter_pct = (nanos % 41) - 20; // -20..20
-    let base = base_ms as i64;
-    let jittered = base + (base * jitter_pct / 100);
-    jittered.max(0) as u64
+/// Calculate the delay before the next attempt using the configured jitter strategy
+fn next_delay<R: Rng + ?Sized>(
+    attempt: usize,
+    previous: Option<Duration>,
+    config: &RetryConfig,
+    rng: &mut R,
+) -> Duration {
+    let delay_ms = match config.jitter {
+        JitterStrategy::None => exponential_cap_ms(attempt, config),
+        JitterStrategy::Full => rng.gen_range(0..=exponential_cap_ms(attempt, config)),
+        JitterStrategy::Equal => {
+            let cap = exponential_cap_ms(attempt, config);
+            let half = cap / 2;
+            half + rng.gen_range(0..=cap - half)
+        }
+        JitterStrategy::Decorrelated => {
+            let base = config.base_delay_ms.min(config.max_delay_ms);
+            let previous_ms = previous.map_or(base, |d| d.as_millis() as u64);
+            let upper = previous_ms.saturating_mul(3).max(base);
+            rng.gen_range(base..=upper).min(config.max_delay_ms)
+        }
+    };
+    Duration::from_millis(delay_ms)
 }
 
 /// Retry an async operation with exponential backoff
 ///
 /// Only retries on transient errors (rate limiting, service unavailable, network).
 /// Permanent errors (authentication, configuration, model) fail immediately.
+/// Retrying also stops, returning the last error, once the next delay would
+/// exceed [`RetryConfig::max_total_delay`].
 ///
 /// # Example
 ///
@@ -108,6 +182,8 @@ where
     Fut: std::future::Future<Output = Result<T, ProviderError>>,
 {
     let mut attempt = 0;
+    let mut previous_delay = None;
+    let mut total_delay = Duration::ZERO;
     loop {
         attempt += 1;
         match op().await {
@@ -116,7 +192,15 @@ where
                 if attempt >= config.max_attempts || !is_retryable_error(&err) {
                     return Err(err);
                 }
-                let delay = backoff_delay(attempt, config);
+                let delay = next_delay(attempt, previous_delay, config, &mut rand::thread_rng());
+                total_delay += delay;
+                if config
+                    .max_total_delay
+                    .is_some_and(|budget| total_delay > budget)
+                {
+                    return Err(err);
+                }
+                previous_delay = Some(delay);
 
                 // Notify callback if set
                 if let Some(callback) = on_retry {
@@ -144,6 +228,7 @@ mod tests {
         assert_eq!(config.max_attempts, 8);
         assert_eq!(config.base_delay_ms, 500);
         assert_eq!(config.max_delay_ms, 30_000);
+        assert_eq!(config.max_total_delay, None);
     }
 
     #[test]
@@ -200,10 +285,8 @@ mod tests {
         let config = RetryConfig::default();
         let delay = backoff_delay(1, &config);
 
-        // First attempt: base_delay (500ms) * 2^0 = 500ms, with jitter
-        // Allow for ±20% jitter
-        assert!(delay.as_millis() >= 400);
-        assert!(delay.as_millis() <= 600);
+        // First attempt: full jitter over base_delay (500ms) * 2^0
+        assert!(delay.as_millis() <= 500);
     }
 
     #[test]
@@ -212,16 +295,14 @@ mod tests {
             base_delay_ms: 100,
             max_delay_ms: 10_000,
             max_attempts: 10,
+            jitter: JitterStrategy::None,
+            max_total_delay: None,
         };
 
-        let delay1 = backoff_delay(1, &config);
-        let delay2 = backoff_delay(2, &config);
-        let delay3 = backoff_delay(3, &config);
-
-        // Each delay should roughly double (accounting for jitter)
-        // delay1 ~ 100ms, delay2 ~ 200ms, delay3 ~ 400ms
-        assert!(delay2.as_millis() > delay1.as_millis());
-        assert!(delay3.as_millis() > delay2.as_millis());
+        // Without jitter each delay doubles exactly
+        assert_eq!(backoff_delay(1, &config), Duration::from_millis(100));
+        assert_eq!(backoff_delay(2, &config), Duration::from_millis(200));
+        assert_eq!(backoff_delay(3, &config), Duration::from_millis(400));
     }
 
     #[test]
@@ -230,24 +311,154 @@ mod tests {
             base_delay_ms: 1000,
             max_delay_ms: 2000,
             max_attempts: 10,
+            ..Default::default()
         };
 
         // After several attempts, should cap at max_delay_ms
-        let delay = backoff_delay(10, &config);
-        // With jitter, should be around 2000ms ± 20%
-        assert!(delay.as_millis() <= 2400);
+        for strategy in [
+            JitterStrategy::None,
+            JitterStrategy::Full,
+            JitterStrategy::Equal,
+            JitterStrategy::Decorrelated,
+        ] {
+            let config = RetryConfig {
+                jitter: strategy,
+                ..config.clone()
+            };
+            let delay = backoff_delay(10, &config);
+            assert!(delay.as_millis() <= 2000, "{:?}: {:?}", strategy, delay);
+        }
     }
 
     #[test]
-    fn test_jitter_ms_produces_variation() {
-        // Jitter should produce values within ±20% of base
-        let base = 1000u64;
-
-        // Call multiple times and verify range
-        // Due to deterministic time-based jitter, we just verify it's in range
-        let jittered = jitter_ms(base);
-        assert!(jittered >= 800); // base - 20%
-        assert!(jittered <= 1200); // base + 20%
+    fn test_jitter_strategy_default_is_full() {
+        assert_eq!(JitterStrategy::default(), JitterStrategy::Full);
+        assert_eq!(RetryConfig::default().jitter, JitterStrategy::Full);
+    }
+
+    fn seeded_rng() -> rand::rngs::StdRng {
+        use rand::SeedableRng;
+        rand::rngs::StdRng::seed_from_u64(42)
+    }
+
+    fn jitter_config(jitter: JitterStrategy) -> RetryConfig {
+        RetryConfig {
+            max_attempts: 10,
+            base_delay_ms: 100,
+            max_delay_ms: 5_000,
+            jitter,
+            max_total_delay: None,
+        }
+    }
+
+    #[test]
+    fn test_full_jitter_bounds() {
+        let config = jitter_config(JitterStrategy::Full);
+        let mut rng = seeded_rng();
+
+        for attempt in 1..=10 {
+            let cap = exponential_cap_ms(attempt, &config);
+            for _ in 0..100 {
+                let delay = next_delay(attempt, None, &config, &mut rng).as_millis() as u64;
+                assert!(delay <= cap, "attempt {}: {} > {}", attempt, delay, cap);
+            }
+        }
+    }
+
+    #[test]
+    fn test_full_jitter_spreads_across_range() {
+        let config = jitter_config(JitterStrategy::Full);
+        let mut rng = seeded_rng();
+
+        // Attempt 4 has a cap of 800ms; full jitter should reach both halves
+        let delays: Vec<u64> = (0..200)
+            .map(|_| next_delay(4, None, &config, &mut rng).as_millis() as u64)
+            .collect();
+        assert!(delays.iter().any(|&d| d < 400));
+        assert!(delays.iter().any(|&d| d >= 400));
+    }
+
+    #[test]
+    fn test_equal_jitter_bounds() {
+        let config = jitter_config(JitterStrategy::Equal);
+        let mut rng = seeded_rng();
+
+        for attempt in 1..=10 {
+            let cap = exponential_cap_ms(attempt, &config);
+            for _ in 0..100 {
+                let delay = next_delay(attempt, None, &config, &mut rng).as_millis() as u64;
+                assert!(
+                    delay >= cap / 2 && delay <= cap,
+                    "attempt {}: {} outside [{}, {}]",
+                    attempt,
+                    delay,
+                    cap / 2,
+                    cap
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_decorrelated_jitter_bounds() {
+        let config = jitter_config(JitterStrategy::Decorrelated);
+        let mut rng = seeded_rng();
+
+        let mut previous = None;
+        for attempt in 1..=50 {
+            let upper = previous
+                .map_or(config.base_delay_ms, |d: Duration| d.as_millis() as u64)
+                .saturating_mul(3)
+                .min(config.max_delay_ms);
+            let delay = next_delay(attempt, previous, &config, &mut rng);
+            let ms = delay.as_millis() as u64;
+            assert!(
+                ms >= config.base_delay_ms && ms <= upper,
+                "attempt {}: {} outside [{}, {}]",
+                attempt,
+                ms,
+                config.base_delay_ms,
+                upper
+            );
+            previous = Some(delay);
+        }
+    }
+
+    #[test]
+    fn test_decorrelated_jitter_is_deterministic_with_seed() {
+        let config = jitter_config(JitterStrategy::Decorrelated);
+
+        let sequence = |mut rng: rand::rngs::StdRng| {
+            let mut previous = None;
+            (1..=8)
+                .map(|attempt| {
+                    let delay = next_delay(attempt, previous, &config, &mut rng);
+                    previous = Some(delay);
+                    delay
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(sequence(seeded_rng()), sequence(seeded_rng()));
+    }
+
+    #[test]
+    fn test_no_jitter_is_exact() {
+        let config = jitter_config(JitterStrategy::None);
+        let mut rng = seeded_rng();
+
+        assert_eq!(
+            next_delay(1, None, &config, &mut rng),
+            Duration::from_millis(100)
+        );
+        assert_eq!(
+            next_delay(5, None, &config, &mut rng),
+            Duration::from_millis(1_600)
+        );
+        assert_eq!(
+            next_delay(10, None, &config, &mut rng),
+            Duration::from_millis(5_000)
+        );
     }
 
     #[tokio::test]
@@ -256,6 +467,7 @@ mod tests {
             max_attempts: 3,
             base_delay_ms: 10,
             max_delay_ms: 100,
+            ..Default::default()
         };
 
         let mut call_count = 0;
@@ -280,6 +492,7 @@ mod tests {
             max_attempts: 3,
             base_delay_ms: 1, // Very short for testing
             max_delay_ms: 10,
+            ..Default::default()
         };
 
         let call_count = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
@@ -312,6 +525,7 @@ mod tests {
             max_attempts: 2,
             base_delay_ms: 1,
             max_delay_ms: 10,
+            ..Default::default()
         };
 
         let call_count = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
@@ -331,12 +545,41 @@ mod tests {
         assert_eq!(call_count.load(std::sync::atomic::Ordering::SeqCst), 2);
     }
 
+    #[tokio::test]
+    async fn test_max_attempts_counts_the_first_attempt() {
+        let attempts = |max_attempts| async move {
+            let config = RetryConfig {
+                max_attempts,
+                base_delay_ms: 1,
+                max_delay_ms: 1,
+                ..Default::default()
+            };
+            let mut call_count = 0;
+            let _: Result<(), _> = retry_with_backoff(
+                || {
+                    call_count += 1;
+                    async { Err(ProviderError::RateLimited("throttled".into())) }
+                },
+                &config,
+                &None,
+            )
+            .await;
+            call_count
+        };
+
+        assert_eq!(attempts(0).await, 1);
+        assert_eq!(attempts(1).await, 1);
+        assert_eq!(attempts(3).await, 3);
+        assert_eq!(RetryConfig::disabled().max_attempts, 1);
+    }
+
     #[tokio::test]
     async fn test_retry_with_backoff_no_retry_on_permanent_error() {
         let config = RetryConfig {
             max_attempts: 5,
             base_delay_ms: 1,
             max_delay_ms: 10,
+            ..Default::default()
         };
 
         let call_count = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
@@ -363,6 +606,7 @@ mod tests {
             max_attempts: 3,
             base_delay_ms: 1,
             max_delay_ms: 10,
+            ..Default::default()
         };
 
         let callback_count = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
@@ -396,4 +640,35 @@ mod tests {
         // Callback should be invoked for each retry (not the initial attempt)
         assert_eq!(callback_count.load(std::sync::atomic::Ordering::SeqCst), 2);
     }
+
+    #[tokio::test]
+    async fn test_retry_with_backoff_stops_at_total_budget() {
+        let config = RetryConfig {
+            max_attempts: 10,
+            base_delay_ms: 10,
+            max_delay_ms: 10,
+            jitter: JitterStrategy::None,
+            max_total_delay: Some(Duration::from_millis(25)),
+        };
+
+        let call_count = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let count_clone = call_count.clone();
+
+        let result: Result<(), ProviderError> = retry_with_backoff(
+            || {
+                let count = count_clone.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                async move { Err(ProviderError::RateLimited(format!("throttled {}", count))) }
+            },
+            &config,
+            &None,
+        )
+        .await;
+
+        // Two 10ms sleeps fit in the 25ms budget; a third would exceed it
+        assert_eq!(call_count.load(std::sync::atomic::Ordering::SeqCst), 3);
+        assert!(matches!(
+            result,
+            Err(ProviderError::RateLimited(msg)) if msg == "throttled 2"
+        ));
+    }
 }
@@ -0,0 +1,294 @@
+//! Parsing of tool input JSON accumulated from a stream
+//!
+//! Streaming providers send a tool's input as JSON fragments. When a stream
+//! is cut short, for example by `max_tokens` or a dropped connection, the
+//! accumulated text is a valid prefix of the intended JSON. This module
+//! closes what was left open so the nearly complete input is kept instead
+//! of being thrown away.
+
+use serde_json::Value;
+
+use super::StreamEvent;
+use crate::types::ToolUseBlock;
+
+/// Stream events for a finished tool use block
+///
+/// Yields the `ToolUse` event, preceded by `ToolInputRepaired` or
+/// `ToolInputInvalid` when the accumulated input was not valid JSON. An
+/// invalid input is replaced with `{}` so the tool use can still be sent
+/// back with an error result.
+pub(crate) fn tool_use_events(id: String, name: String, input_json: &str) -> Vec<StreamEvent> {
+    let mut events = Vec::with_capacity(2);
+    let input = if input_json.trim().is_empty() {
+        Value::Object(Default::default())
+    } else {
+        match serde_json::from_str(input_json) {
+            Ok(value) => value,
+            Err(parse_error) => match repair_json(input_json)
+                .and_then(|repaired| serde_json::from_str(&repaired).ok())
+            {
+                Some(value) => {
+                    events.push(StreamEvent::ToolInputRepaired {
+                        tool_use_id: id.clone(),
+                        raw_input: input_json.to_string(),
+                    });
+                    value
+                }
+                None => {
+                    events.push(StreamEvent::ToolInputInvalid {
+                        tool_use_id: id.clone(),
+                        raw_input: input_json.to_string(),
+                        error: parse_error.to_string(),
+                    });
+                    Value::Object(Default::default())
+                }
+            },
+        }
+    };
+    events.push(StreamEvent::ToolUse(ToolUseBlock { id, name, input }));
+    events
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Expect {
+    Key,
+    Colon,
+    Value,
+    CommaOrEnd,
+}
+
+struct Frame {
+    close: char,
+    expect: Expect,
+    /// Where the member being written starts: just after the opening
+    /// bracket, or at the comma before it
+    member_start: usize,
+}
+
+/// Best-effort completion of truncated JSON
+///
+/// Closes an open string, completes or drops a partial literal or number,
+/// drops a trailing comma or a key with no value, and closes open arrays
+/// and objects. Returns `None` when the text is not a JSON prefix this can
+/// fix. The result still needs to be parsed to know whether it worked.
+pub(crate) fn repair_json(input: &str) -> Option<String> {
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut string_is_key = false;
+    let mut scalar_start: Option<usize> = None;
+
+    for (i, c) in input.char_indices() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+                if let Some(frame) = stack.last_mut() {
+                    frame.expect = if string_is_key {
+                        Expect::Colon
+                    } else {
+                        Expect::CommaOrEnd
+                    };
+                }
+            }
+            continue;
+        }
+
+        if scalar_start.is_some() {
+            if !(c.is_whitespace() || matches!(c, ',' | '}' | ']' | ':')) {
+                continue;
+            }
+            scalar_start = None;
+            if let Some(frame) = stack.last_mut() {
+                frame.expect = Expect::CommaOrEnd;
+            }
+        }
+
+        match c {
+            '{' => stack.push(Frame {
+                close: '}',
+                expect: Expect::Key,
+                member_start: i + 1,
+            }),
+            '[' => stack.push(Frame {
+                close: ']',
+                expect: Expect::Value,
+                member_start: i + 1,
+            }),
+            '}' | ']' => {
+                if stack.pop().map(|frame| frame.close) != Some(c) {
+                    return None;
+                }
+                if let Some(frame) = stack.last_mut() {
+                    frame.expect = Expect::CommaOrEnd;
+                }
+            }
+            '"' => {
+                in_string = true;
+                string_is_key = matches!(
+                    stack.last(),
+                    Some(frame) if frame.close == '}' && frame.expect == Expect::Key
+                );
+            }
+            ':' => stack.last_mut()?.expect = Expect::Value,
+            ',' => {
+                let frame = stack.last_mut()?;
+                frame.expect = if frame.close == '}' {
+                    Expect::Key
+                } else {
+                    Expect::Value
+                };
+                frame.member_start = i;
+            }
+            c if c.is_whitespace() => {}
+            _ => scalar_start = Some(i),
+        }
+    }
+
+    // Nothing to close: the input is either complete or not fixable here
+    if stack.is_empty() {
+        return None;
+    }
+
+    let mut out = input.to_string();
+    let mut drop_member = false;
+
+    if in_string {
+        if string_is_key {
+            drop_member = true;
+        } else {
+            if escaped {
+                out.pop();
+            }
+            // A \u escape cut off before its four hex digits
+            if let Some(pos) = out.rfind("\\u") {
+                let digits = &out[pos + 2..];
+                if digits.len() < 4 && digits.chars().all(|c| c.is_ascii_hexdigit()) {
+                    out.truncate(pos);
+                }
+            }
+            out.push('"');
+        }
+    } else if let Some(start) = scalar_start {
+        let token = out[start..].to_string();
+        if let Some(literal) = ["true", "false", "null"]
+            .iter()
+            .find(|literal| literal.starts_with(token.as_str()))
+        {
+            out.push_str(&literal[token.len()..]);
+        } else {
+            let number = token.trim_end_matches(['-', '+', '.', 'e', 'E']);
+            if number.parse::<f64>().is_ok() {
+                out.truncate(start + number.len());
+            } else {
+                drop_member = true;
+            }
+        }
+    } else {
+        let frame = stack.last()?;
+        drop_member = match frame.expect {
+            Expect::CommaOrEnd => false,
+            // A key with no colon, or a colon with no value
+            Expect::Colon => true,
+            Expect::Value if frame.close == '}' => true,
+            // Right after an opening bracket there is nothing to drop; after
+            // a comma, the comma goes
+            Expect::Key | Expect::Value => out.as_bytes().get(frame.member_start) == Some(&b','),
+        };
+    }
+
+    if drop_member {
+        out.truncate(stack.last()?.member_start);
+    }
+    for frame in stack.iter().rev() {
+        out.push(frame.close);
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn repaired(input: &str) -> Value {
+        let text = repair_json(input).unwrap_or_else(|| panic!("no repair for {:?}", input));
+        serde_json::from_str(&text).unwrap_or_else(|e| panic!("{:?} -> {:?}: {}", input, text, e))
+    }
+
+    #[test]
+    fn test_repair_truncated_inputs() {
+        let cases = [
+            (r#"{"path": "/tmp/fi"#, json!({"path": "/tmp/fi"})),
+            (r#"{"path": "/tmp/file""#, json!({"path": "/tmp/file"})),
+            (r#"{"a": 1, "#, json!({"a": 1})),
+            (r#"{"a": 1, "b"#, json!({"a": 1})),
+            (r#"{"a": 1, "b":"#, json!({"a": 1})),
+            (r#"{"a": 1, "b": "#, json!({"a": 1})),
+            (r#"{"a": tr"#, json!({"a": true})),
+            (r#"{"a": nu"#, json!({"a": null})),
+            (r#"{"a": 12.5"#, json!({"a": 12.5})),
+            (r#"{"a": 12."#, json!({"a": 12})),
+            (r#"{"a": -"#, json!({})),
+            (r#"{"items": [1, 2, "#, json!({"items": [1, 2]})),
+            (r#"{"items": ["x", "y"#, json!({"items": ["x", "y"]})),
+            (
+                r#"{"nested": {"deep": [{"k": "v"#,
+                json!({"nested": {"deep": [{"k": "v"}]}}),
+            ),
+            (r#"{"text": "line\"#, json!({"text": "line"})),
+            (r#"{"text": "caf\u00"#, json!({"text": "caf"})),
+            ("{", json!({})),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(repaired(input), expected, "input: {}", input);
+        }
+    }
+
+    #[test]
+    fn test_repair_rejects_broken_json() {
+        assert!(repair_json(r#"{"a": 1]"#).is_none());
+        assert!(repair_json(r#"{"a": 1}"#).is_none());
+        assert!(repair_json("not json").is_none());
+        // Closes cleanly but still isn't valid JSON
+        let text = repair_json(r#"{"a" "b"#).unwrap();
+        assert!(serde_json::from_str::<Value>(&text).is_err());
+    }
+
+    #[test]
+    fn test_tool_use_events() {
+        let events = tool_use_events("t1".into(), "read".into(), r#"{"path": "a.txt"}"#);
+        assert_eq!(events.len(), 1);
+        assert!(matches!(
+            &events[0],
+            StreamEvent::ToolUse(t) if t.input == json!({"path": "a.txt"})
+        ));
+
+        // No input at all is an empty object
+        let events = tool_use_events("t1".into(), "now".into(), "");
+        assert!(matches!(&events[0], StreamEvent::ToolUse(t) if t.input == json!({})));
+
+        let events = tool_use_events("t1".into(), "read".into(), r#"{"path": "a.t"#);
+        assert_eq!(events.len(), 2);
+        assert!(matches!(
+            &events[0],
+            StreamEvent::ToolInputRepaired { tool_use_id, raw_input }
+                if tool_use_id == "t1" && raw_input == r#"{"path": "a.t"#
+        ));
+        assert!(matches!(
+            &events[1],
+            StreamEvent::ToolUse(t) if t.input == json!({"path": "a.t"})
+        ));
+
+        let events = tool_use_events("t1".into(), "read".into(), r#"{"path": ]"#);
+        assert_eq!(events.len(), 2);
+        assert!(matches!(
+            &events[0],
+            StreamEvent::ToolInputInvalid { tool_use_id, .. } if tool_use_id == "t1"
+        ));
+        assert!(matches!(&events[1], StreamEvent::ToolUse(t) if t.input == json!({})));
+    }
+}
@@ -152,6 +152,85 @@ pub trait SessionStore: Send + Sync {
 
     /// Delete session
     async fn delete_session(&self, id: &str) -> Result<(), SessionError>;
+
+    /// Search message content across all sessions
+    ///
+    /// Returns up to `limit` matching sessions, best match first, each with a
+    /// [`snippet`](SessionSummary::snippet) from a matching message. The
+    /// default implementation loads every session and does a case-insensitive
+    /// substring match, ranked by recency; stores with an index should
+    /// override it.
+    async fn search(&self, query: &str, limit: usize) -> Result<Vec<SessionSummary>, SessionError> {
+        let query = query.trim();
+        if query.is_empty() || limit == 0 {
+            return Ok(Vec::new());
+        }
+        let needle = query.to_lowercase();
+
+        let mut summaries = self.list_sessions().await?;
+        summaries.sort_by_key(|s| std::cmp::Reverse(s.updated_at));
+
+        let mut matches = Vec::new();
+        for summary in summaries {
+            let Some(session) = self.get_session(&summary.id).await? else {
+                continue;
+            };
+            let hit = session
+                .messages
+                .iter()
+                .find(|m| m.content.to_lowercase().contains(&needle));
+            if let Some(message) = hit {
+                matches.push(SessionSummary {
+                    snippet: Some(search_snippet(&message.content, query)),
+                    ..summary
+                });
+                if matches.len() == limit {
+                    break;
+                }
+            }
+        }
+        Ok(matches)
+    }
+}
+
+/// Characters of context kept on each side of a match in [`search_snippet`]
+#[cfg(feature = "session")]
+const SNIPPET_CONTEXT_CHARS: usize = 40;
+
+/// Build a short, single-line excerpt of `content` around the first
+/// case-insensitive occurrence of `query`
+///
+/// Falls back to the start of `content` when `query` does not occur in it.
+#[cfg(feature = "session")]
+pub fn search_snippet(content: &str, query: &str) -> String {
+    let chars: Vec<char> = content.chars().collect();
+    let needle: Vec<char> = query.to_lowercase().chars().collect();
+    let lower: Vec<char> = chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+
+    let position = if needle.is_empty() {
+        None
+    } else {
+        lower
+            .windows(needle.len())
+            .position(|w| w == needle.as_slice())
+    };
+    let match_len = if position.is_some() { needle.len() } else { 0 };
+    let position = position.unwrap_or(0);
+
+    let start = position.saturating_sub(SNIPPET_CONTEXT_CHARS);
+    let end = (position + match_len + SNIPPET_CONTEXT_CHARS).min(chars.len());
+    let excerpt: String = chars[start..end].iter().collect();
+    let excerpt = excerpt.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    format!(
+        "{}{}{}",
+        if start > 0 { "…" } else { "" },
+        excerpt,
+        if end < chars.len() { "…" } else { "" }
+    )
 }
 
 /// Summary of a session (for listing)
@@ -168,6 +247,8 @@ pub struct SessionSummary {
     pub created_at: DateTime<Utc>,
     /// Last update time
     pub updated_at: DateTime<Utc>,
+    /// Excerpt of a matching message (only set by [`SessionStore::search`])
+    pub snippet: Option<String>,
 }
 
 /// Errors that can occur during session operations
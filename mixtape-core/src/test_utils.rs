@@ -33,6 +33,7 @@
 use std::sync::{Arc, Mutex};
 
 use crate::events::AgentEvent;
+use crate::id::{IdGenerator, UuidIdGenerator};
 use crate::model::ModelResponse;
 use crate::provider::{ModelProvider, ProviderError};
 use crate::types::{ContentBlock, Message, Role, StopReason, ToolDefinition, ToolUseBlock};
@@ -61,6 +62,7 @@ use crate::types::{ContentBlock, Message, Role, StopReason, ToolDefinition, Tool
 pub struct MockProvider {
     responses: Arc<Mutex<Vec<ModelResponse>>>,
     call_count: Arc<Mutex<usize>>,
+    id_generator: Arc<dyn IdGenerator>,
 }
 
 impl MockProvider {
@@ -69,9 +71,29 @@ impl MockProvider {
         Self {
             responses: Arc::new(Mutex::new(Vec::new())),
             call_count: Arc::new(Mutex::new(0)),
+            id_generator: Arc::new(UuidIdGenerator),
         }
     }
 
+    /// Set the generator for tool-use IDs in queued tool use responses.
+    ///
+    /// Call before [`with_tool_use`](Self::with_tool_use) so the queued
+    /// responses use it.
+    ///
+    /// ```rust
+    /// use mixtape_core::{test_utils::MockProvider, SequentialIdGenerator};
+    /// use serde_json::json;
+    ///
+    /// // Tool use IDs will be tool_1, tool_2, ...
+    /// let provider = MockProvider::new()
+    ///     .with_id_generator(SequentialIdGenerator::new())
+    ///     .with_tool_use("calculator", json!({"expr": "2+2"}));
+    /// ```
+    pub fn with_id_generator(mut self, generator: impl IdGenerator + 'static) -> Self {
+        self.id_generator = Arc::new(generator);
+        self
+    }
+
     /// Add a text response to the queue.
     ///
     /// The response will have `StopReason::EndTurn`.
@@ -81,7 +103,10 @@ impl MockProvider {
         let response = ModelResponse {
             message,
             stop_reason: StopReason::EndTurn,
+            stop_sequence: None,
             usage: None,
+            service_tier: None,
+            model: None,
         };
 
         self.responses.lock().unwrap().push(response);
@@ -97,7 +122,7 @@ impl MockProvider {
         tool_input: serde_json::Value,
     ) -> Self {
         let tool_use = ToolUseBlock {
-            id: format!("tool_{}", uuid::Uuid::new_v4()),
+            id: self.id_generator.next_tool_use_id(),
             name: tool_name.into(),
             input: tool_input,
         };
@@ -110,13 +135,25 @@ impl MockProvider {
         let response = ModelResponse {
             message,
             stop_reason: StopReason::ToolUse,
+            stop_sequence: None,
             usage: None,
+            service_tier: None,
+            model: None,
         };
 
         self.responses.lock().unwrap().push(response);
         self
     }
 
+    /// Add an arbitrary response to the queue.
+    ///
+    /// Use for responses the other helpers can't build, such as ones with
+    /// thinking blocks or token usage.
+    pub fn with_response(self, response: ModelResponse) -> Self {
+        self.responses.lock().unwrap().push(response);
+        self
+    }
+
     /// Get the number of times `generate` was called.
     pub fn call_count(&self) -> usize {
         *self.call_count.lock().unwrap()
@@ -257,16 +294,27 @@ impl EventCollector {
             AgentEvent::RunStarted { .. } => "run_started",
             AgentEvent::RunCompleted { .. } => "run_completed",
             AgentEvent::RunFailed { .. } => "run_failed",
+            AgentEvent::BudgetWarning { .. } => "budget_warning",
+            AgentEvent::CacheInvalidated { .. } => "cache_invalidated",
             AgentEvent::ModelCallStarted { .. } => "model_call_started",
             AgentEvent::ModelCallStreaming { .. } => "model_streaming",
             AgentEvent::ModelCallCompleted { .. } => "model_call_completed",
+            AgentEvent::TurnCompleted { .. } => "turn_completed",
+            AgentEvent::ToolArgsDelta { .. } => "tool_args_delta",
+            AgentEvent::ToolInputRepaired { .. } => "tool_input_repaired",
             AgentEvent::ToolRequested { .. } => "tool_requested",
             AgentEvent::ToolExecuting { .. } => "tool_executing",
             AgentEvent::ToolCompleted { .. } => "tool_completed",
             AgentEvent::ToolFailed { .. } => "tool_failed",
+            AgentEvent::ToolSkippedDryRun { .. } => "tool_skipped_dry_run",
+            AgentEvent::ToolJsonRecovery { .. } => "tool_json_recovery",
             AgentEvent::PermissionRequired { .. } => "permission_required",
             AgentEvent::PermissionGranted { .. } => "permission_granted",
             AgentEvent::PermissionDenied { .. } => "permission_denied",
+            AgentEvent::ContentFiltered { .. } => "content_filtered",
+            AgentEvent::Refused { .. } => "refused",
+            AgentEvent::Progress { .. } => "progress",
+            AgentEvent::SubAgent { .. } => "sub_agent",
             #[cfg(feature = "session")]
             AgentEvent::SessionResumed { .. } => "session_resumed",
             #[cfg(feature = "session")]
@@ -326,6 +374,19 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_mock_provider_sequential_tool_use_ids() {
+        let provider = MockProvider::new()
+            .with_id_generator(crate::SequentialIdGenerator::new())
+            .with_tool_use("first", serde_json::json!({}))
+            .with_tool_use("second", serde_json::json!({}));
+
+        for expected in ["tool_1", "tool_2"] {
+            let response = provider.generate(vec![], vec![], None).await.unwrap();
+            assert_eq!(response.message.tool_uses()[0].id, expected);
+        }
+    }
+
     #[test]
     fn test_event_collector() {
         let collector = EventCollector::new();
@@ -337,6 +398,7 @@ mod tests {
             .lock()
             .unwrap()
             .push(AgentEvent::RunStarted {
+                agent_name: None,
                 input: "test".to_string(),
                 timestamp: std::time::Instant::now(),
             });
@@ -345,6 +407,7 @@ mod tests {
             .lock()
             .unwrap()
             .push(AgentEvent::RunCompleted {
+                agent_name: None,
                 output: "done".to_string(),
                 duration: std::time::Duration::from_secs(1),
             });
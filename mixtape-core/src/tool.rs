@@ -13,6 +13,39 @@ pub enum ImageFormat {
     Webp,
 }
 
+/// Maximum size of an image returned in a [`ToolResult::Image`]
+///
+/// This is the Anthropic API's per-image limit. Bedrock enforces a lower
+/// limit of its own.
+pub const MAX_IMAGE_BYTES: usize = 5 * 1024 * 1024;
+
+impl ImageFormat {
+    /// Media type sent to the model, e.g. `image/png`
+    pub fn media_type(&self) -> &'static str {
+        match self {
+            ImageFormat::Png => "image/png",
+            ImageFormat::Jpeg => "image/jpeg",
+            ImageFormat::Gif => "image/gif",
+            ImageFormat::Webp => "image/webp",
+        }
+    }
+
+    /// Detect the format of image bytes from their magic number
+    pub fn detect(data: &[u8]) -> Option<Self> {
+        if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some(ImageFormat::Png)
+        } else if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            Some(ImageFormat::Jpeg)
+        } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+            Some(ImageFormat::Gif)
+        } else if data.len() >= 12 && data.starts_with(b"RIFF") && &data[8..12] == b"WEBP" {
+            Some(ImageFormat::Webp)
+        } else {
+            None
+        }
+    }
+}
+
 /// Document formats supported for tool results
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -116,6 +149,67 @@ impl ToolResult {
         }
     }
 
+    /// Check that an image result can be sent back to the model
+    ///
+    /// Images must be non-empty, at most [`MAX_IMAGE_BYTES`], and their bytes
+    /// must match the declared [`ImageFormat`]. Other variants always pass.
+    pub fn validate(&self) -> Result<(), ToolError> {
+        let ToolResult::Image { format, data } = self else {
+            return Ok(());
+        };
+        if data.is_empty() {
+            return Err(ToolError::Custom("image result is empty".to_string()));
+        }
+        if data.len() > MAX_IMAGE_BYTES {
+            return Err(ToolError::Custom(format!(
+                "image is {} bytes, larger than the {} byte limit",
+                data.len(),
+                MAX_IMAGE_BYTES
+            )));
+        }
+        match ImageFormat::detect(data) {
+            Some(detected) if detected == *format => Ok(()),
+            Some(detected) => Err(ToolError::Custom(format!(
+                "image declared as {} but data is {}",
+                format.media_type(),
+                detected.media_type()
+            ))),
+            None => Err(ToolError::Custom(format!(
+                "image data is not a valid {}",
+                format.media_type()
+            ))),
+        }
+    }
+
+    /// This result cut to at most `max_bytes` of text, or `None` if it fits
+    ///
+    /// Text and JSON results over the limit become text ending in a note
+    /// of how much was kept. Images and documents are never truncated.
+    pub fn truncated(&self, max_bytes: usize) -> Option<ToolResult> {
+        let text = match self {
+            ToolResult::Text(text) if text.len() > max_bytes => text.clone(),
+            ToolResult::Json(value) => {
+                let text = value.to_string();
+                if text.len() <= max_bytes {
+                    return None;
+                }
+                text
+            }
+            _ => return None,
+        };
+
+        let mut end = max_bytes;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        Some(ToolResult::Text(format!(
+            "{}\n[truncated: showing {} of {} bytes]",
+            &text[..end],
+            end,
+            text.len()
+        )))
+    }
+
     /// Get a reference to the text content if this is a Text variant
     pub fn as_str(&self) -> Option<&str> {
         match self {
@@ -264,8 +358,80 @@ pub trait Tool: Send + Sync {
     /// This is automatically implemented using the `JsonSchema` derive on `Input`.
     /// The schema is generated at runtime from the type definition.
     fn input_schema(&self) -> Value {
-        let schema = schemars::schema_for!(Self::Input);
-        serde_json::to_value(schema).expect("Failed to serialize schema")
+        schema_of::<Self::Input>()
+    }
+
+    /// Get the JSON schema for this tool's [`ToolResult::Json`] output, if declared.
+    ///
+    /// Clients can use this to validate results or build typed views of them.
+    /// The default is `None`, meaning the output shape is unspecified. Tools
+    /// that return a serializable type can declare it with [`schema_of`]:
+    ///
+    /// ```ignore
+    /// fn output_schema(&self) -> Option<Value> {
+    ///     Some(schema_of::<WeatherReport>())
+    /// }
+    /// ```
+    fn output_schema(&self) -> Option<Value> {
+        None
+    }
+
+    /// Check semantic constraints on the input before it is executed
+    ///
+    /// Called by agents after the input deserializes, before the call is
+    /// approved or [`execute`](Self::execute) runs, so no one is asked to
+    /// approve input that would be rejected. An error here is sent to the
+    /// model as the tool's error result and `execute` is not called, so use
+    /// it for rules the schema cannot express, such as a query that must be
+    /// a `SELECT`. Make the message say how to fix the input. The default
+    /// accepts everything.
+    ///
+    /// ```ignore
+    /// fn validate(&self, input: &Self::Input) -> Result<(), ToolError> {
+    ///     if !input.path.ends_with(".csv") {
+    ///         return Err("path must name a .csv file".into());
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    fn validate(&self, _input: &Self::Input) -> Result<(), ToolError> {
+        Ok(())
+    }
+
+    /// Whether this tool can delete, overwrite, or otherwise irreversibly
+    /// change things outside the agent.
+    ///
+    /// Agents add tool-use guidance to the system prompt when a destructive
+    /// tool is registered. The default is `false`.
+    fn is_destructive(&self) -> bool {
+        false
+    }
+
+    /// How many calls to this tool may run at once
+    ///
+    /// Agents never run more calls to the tool at once than this, whatever
+    /// [`AgentBuilder::with_max_concurrent_tools`](crate::AgentBuilder::with_max_concurrent_tools)
+    /// allows. Return `Some(1)` for tools that must run one at a time, such
+    /// as database writes. The default, `None`, leaves only the agent-wide
+    /// limit.
+    fn max_concurrency(&self) -> Option<usize> {
+        None
+    }
+
+    /// Whether `execute` does CPU-heavy or blocking work
+    ///
+    /// Agents run blocking tools off the async worker threads, on tokio's
+    /// blocking thread pool or the runtime given to
+    /// [`AgentBuilder::with_tool_runtime`](crate::AgentBuilder::with_tool_runtime),
+    /// so they cannot stall other tools, model calls, or agents. Mark tools
+    /// that parse, hash, or transform large inputs, or call blocking APIs.
+    ///
+    /// A blocking call that is aborted or outlives a run deadline keeps
+    /// running on the blocking pool until `execute` returns; its result is
+    /// discarded. On a dedicated runtime it is cancelled at its next
+    /// `.await`. The default is `false`.
+    fn is_blocking(&self) -> bool {
+        false
     }
 
     // ========================================================================
@@ -322,6 +488,20 @@ pub trait DynTool: Send + Sync {
     fn name(&self) -> &str;
     fn description(&self) -> &str;
     fn input_schema(&self) -> Value;
+    fn output_schema(&self) -> Option<Value> {
+        None
+    }
+    fn is_destructive(&self) -> bool;
+    fn max_concurrency(&self) -> Option<usize> {
+        None
+    }
+    fn is_blocking(&self) -> bool {
+        false
+    }
+    /// Check input before the call is approved or executed
+    fn validate_raw(&self, _input: &Value) -> Result<(), ToolError> {
+        Ok(())
+    }
     fn execute_raw(
         &self,
         input: Value,
@@ -338,6 +518,12 @@ pub trait DynTool: Send + Sync {
     fn format_output_markdown(&self, result: &ToolResult) -> String;
 }
 
+/// Generate the JSON schema for `T` as a `serde_json::Value`.
+pub fn schema_of<T: JsonSchema>() -> Value {
+    let schema = schemars::schema_for!(T);
+    serde_json::to_value(schema).expect("Failed to serialize schema")
+}
+
 /// Convert a `Tool` into a type-erased `Box<dyn DynTool>` for storage in collections.
 pub fn box_tool<T: Tool + 'static>(tool: T) -> Box<dyn DynTool> {
     Box::new(ToolWrapper(tool))
@@ -374,6 +560,54 @@ macro_rules! box_tools {
     };
 }
 
+/// Create a `Result<Vec<Box<dyn DynTool>>, E>` from fallible tool constructors.
+///
+/// Like [`box_tools!`], but each expression is a `Result<impl Tool, E>`. The
+/// tools are boxed in order and the first error is returned without
+/// evaluating the remaining expressions. Pairs with
+/// [`crate::AgentBuilder::try_add_tools()`].
+///
+/// # Example
+///
+/// ```ignore
+/// use mixtape_core::{Agent, try_box_tools, ClaudeSonnet4};
+///
+/// let agent = Agent::builder()
+///     .bedrock(ClaudeSonnet4)
+///     .try_add_tools(move || {
+///         try_box_tools![
+///             QueryTool::connect(&database_url),
+///             SchemaTool::connect(&database_url),
+///         ]
+///     })
+///     .build()
+///     .await?;
+/// ```
+#[macro_export]
+macro_rules! try_box_tools {
+    ($($tool:expr),* $(,)?) => {
+        'tools: {
+            ::std::result::Result::Ok(vec![$(
+                match $tool {
+                    ::std::result::Result::Ok(tool) => $crate::tool::box_tool(tool),
+                    ::std::result::Result::Err(err) => {
+                        break 'tools ::std::result::Result::Err(err)
+                    }
+                }
+            ),*])
+        }
+    };
+}
+
+/// Deserialize a tool's input and check it with [`Tool::validate`]
+fn typed_input<T: Tool>(tool: &T, input: Value) -> Result<T::Input, ToolError> {
+    let typed_input: T::Input = serde_json::from_value(input)
+        .map_err(|e| ToolError::Custom(format!("Failed to deserialize input: {}", e)))?;
+    tool.validate(&typed_input)
+        .map_err(|e| ToolError::Custom(format!("Invalid input: {}", e)))?;
+    Ok(typed_input)
+}
+
 /// Internal wrapper that implements DynTool for any Tool
 struct ToolWrapper<T>(T);
 
@@ -390,6 +624,26 @@ impl<T: Tool + 'static> DynTool for ToolWrapper<T> {
         self.0.input_schema()
     }
 
+    fn output_schema(&self) -> Option<Value> {
+        self.0.output_schema()
+    }
+
+    fn is_destructive(&self) -> bool {
+        self.0.is_destructive()
+    }
+
+    fn max_concurrency(&self) -> Option<usize> {
+        self.0.max_concurrency()
+    }
+
+    fn is_blocking(&self) -> bool {
+        self.0.is_blocking()
+    }
+
+    fn validate_raw(&self, input: &Value) -> Result<(), ToolError> {
+        typed_input(&self.0, input.clone()).map(|_| ())
+    }
+
     fn execute_raw(
         &self,
         input: Value,
@@ -397,9 +651,7 @@ impl<T: Tool + 'static> DynTool for ToolWrapper<T> {
         Box<dyn std::future::Future<Output = Result<ToolResult, ToolError>> + Send + '_>,
     > {
         Box::pin(async move {
-            let typed_input: T::Input = serde_json::from_value(input)
-                .map_err(|e| ToolError::Custom(format!("Failed to deserialize input: {}", e)))?;
-
+            let typed_input = typed_input(&self.0, input)?;
             self.0.execute(typed_input).await
         })
     }
@@ -429,6 +681,104 @@ impl<T: Tool + 'static> DynTool for ToolWrapper<T> {
     }
 }
 
+/// A tool whose JSON results are narrowed to one subtree
+///
+/// After the wrapped tool runs, the [RFC 6901](https://www.rfc-editor.org/rfc/rfc6901)
+/// JSON pointer is applied to a [`ToolResult::Json`] result and only the
+/// value it points to is returned, so the rest never enters the
+/// conversation. A pointer that doesn't resolve makes the call fail. Other
+/// result types pass through unchanged.
+///
+/// Usually created with [`AgentBuilder::add_tool_extracting`](crate::AgentBuilder::add_tool_extracting).
+///
+/// ```ignore
+/// // The model sees only the forecast array, not the full API response
+/// let tool = JsonPointerTool::new(WeatherLookup, "/forecast/daily");
+/// ```
+pub struct JsonPointerTool<T> {
+    tool: T,
+    pointer: String,
+}
+
+impl<T: Tool> JsonPointerTool<T> {
+    /// Wrap `tool`, extracting `pointer` from its JSON results
+    pub fn new(tool: T, pointer: impl Into<String>) -> Self {
+        Self {
+            tool,
+            pointer: pointer.into(),
+        }
+    }
+
+    /// The JSON pointer applied to results
+    pub fn pointer(&self) -> &str {
+        &self.pointer
+    }
+}
+
+impl<T: Tool> Tool for JsonPointerTool<T> {
+    type Input = T::Input;
+
+    fn name(&self) -> &str {
+        self.tool.name()
+    }
+
+    fn description(&self) -> &str {
+        self.tool.description()
+    }
+
+    fn execute(
+        &self,
+        input: Self::Input,
+    ) -> impl std::future::Future<Output = Result<ToolResult, ToolError>> + Send {
+        // Start the inner call here so `input` isn't held across an await
+        let result = self.tool.execute(input);
+        async move {
+            match result.await? {
+                ToolResult::Json(mut value) => match value.pointer_mut(&self.pointer) {
+                    Some(extracted) => Ok(ToolResult::Json(extracted.take())),
+                    None => Err(ToolError::Custom(format!(
+                        "JSON pointer '{}' did not match the tool result",
+                        self.pointer
+                    ))),
+                },
+                other => Ok(other),
+            }
+        }
+    }
+
+    fn input_schema(&self) -> Value {
+        self.tool.input_schema()
+    }
+
+    fn validate(&self, input: &Self::Input) -> Result<(), ToolError> {
+        self.tool.validate(input)
+    }
+
+    fn is_destructive(&self) -> bool {
+        self.tool.is_destructive()
+    }
+
+    fn max_concurrency(&self) -> Option<usize> {
+        self.tool.max_concurrency()
+    }
+
+    fn is_blocking(&self) -> bool {
+        self.tool.is_blocking()
+    }
+
+    fn format_input_plain(&self, params: &Value) -> String {
+        self.tool.format_input_plain(params)
+    }
+
+    fn format_input_ansi(&self, params: &Value) -> String {
+        self.tool.format_input_ansi(params)
+    }
+
+    fn format_input_markdown(&self, params: &Value) -> String {
+        self.tool.format_input_markdown(params)
+    }
+}
+
 // ============================================================================
 // Default formatting helpers
 // ============================================================================
@@ -959,4 +1309,76 @@ mod tests {
         };
         assert!(doc.as_str().is_none());
     }
+
+    #[test]
+    fn test_image_format_detect() {
+        assert_eq!(
+            ImageFormat::detect(b"\x89PNG\r\n\x1a\nrest"),
+            Some(ImageFormat::Png)
+        );
+        assert_eq!(
+            ImageFormat::detect(&[0xFF, 0xD8, 0xFF, 0xE0]),
+            Some(ImageFormat::Jpeg)
+        );
+        assert_eq!(ImageFormat::detect(b"GIF89a..."), Some(ImageFormat::Gif));
+        assert_eq!(
+            ImageFormat::detect(b"RIFF\0\0\0\0WEBPVP8 "),
+            Some(ImageFormat::Webp)
+        );
+        assert_eq!(ImageFormat::detect(b"RIFF\0\0\0\0WAVE"), None);
+        assert_eq!(ImageFormat::detect(b"not an image"), None);
+    }
+
+    #[test]
+    fn test_tool_result_validate_image() {
+        let png = ToolResult::image(ImageFormat::Png, b"\x89PNG\r\n\x1a\n".to_vec());
+        assert!(png.validate().is_ok());
+
+        let empty = ToolResult::image(ImageFormat::Png, Vec::new());
+        assert!(empty.validate().unwrap_err().to_string().contains("empty"));
+
+        let mismatched = ToolResult::image(ImageFormat::Jpeg, b"\x89PNG\r\n\x1a\n".to_vec());
+        let err = mismatched.validate().unwrap_err().to_string();
+        assert!(
+            err.contains("image/jpeg") && err.contains("image/png"),
+            "{}",
+            err
+        );
+
+        let garbage = ToolResult::image(ImageFormat::Gif, vec![1, 2, 3]);
+        assert!(garbage.validate().is_err());
+
+        let mut data = b"\x89PNG\r\n\x1a\n".to_vec();
+        data.resize(MAX_IMAGE_BYTES + 1, 0);
+        let oversized = ToolResult::image(ImageFormat::Png, data);
+        assert!(oversized
+            .validate()
+            .unwrap_err()
+            .to_string()
+            .contains("byte limit"));
+    }
+
+    #[test]
+    fn test_tool_result_validate_non_image() {
+        assert!(ToolResult::text("hi").validate().is_ok());
+        assert!(ToolResult::document(DocumentFormat::Txt, Vec::new())
+            .validate()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_tool_result_truncated() {
+        assert!(ToolResult::text("short").truncated(10).is_none());
+        assert!(ToolResult::image(ImageFormat::Png, vec![0; 100])
+            .truncated(10)
+            .is_none());
+
+        let cut = ToolResult::text("héllo world").truncated(2).unwrap();
+        assert_eq!(cut.as_text(), "h\n[truncated: showing 1 of 12 bytes]");
+
+        let json = ToolResult::Json(serde_json::json!({"items": [1, 2, 3]}));
+        let cut = json.truncated(8).unwrap();
+        assert!(cut.as_text().starts_with("{\"items\""));
+        assert!(json.truncated(100).is_none());
+    }
 }
@@ -39,6 +39,20 @@ impl Message {
         }
     }
 
+    /// Create a new user message with a document followed by a question
+    ///
+    /// The document comes first, which is the ordering providers recommend
+    /// for questions about long documents.
+    pub fn user_with_document(document: DocumentBlock, text: impl Into<String>) -> Self {
+        Self {
+            role: Role::User,
+            content: vec![
+                ContentBlock::Document(document),
+                ContentBlock::Text(text.into()),
+            ],
+        }
+    }
+
     /// Create a new assistant message with text content
     pub fn assistant(text: impl Into<String>) -> Self {
         Self {
@@ -101,6 +115,17 @@ impl Message {
             })
             .collect()
     }
+
+    /// Get the citations of all text in this message, in order
+    pub fn citations(&self) -> Vec<&Citation> {
+        self.content
+            .iter()
+            .flat_map(|c| match c {
+                ContentBlock::Citations { citations } => citations.as_slice(),
+                _ => &[],
+            })
+            .collect()
+    }
 }
 
 /// Content block within a message
@@ -120,6 +145,120 @@ pub enum ContentBlock {
         /// Signature for multi-turn thinking verification
         signature: String,
     },
+    /// Document attached by the user (PDF, plain text, etc.)
+    Document(DocumentBlock),
+    /// Sources the model cited for the text block just before this one
+    ///
+    /// Kept apart from the text so that code reading text blocks is
+    /// unaffected. Citations are not sent back to the model.
+    Citations {
+        /// The cited sources, in the order the model gave them
+        citations: Vec<Citation>,
+    },
+}
+
+/// A span of source material that model text is based on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Citation {
+    /// Where the cited text comes from
+    pub source: CitationSource,
+    /// The text quoted from the source
+    pub cited_text: String,
+    /// Where the cited text sits within the source
+    pub location: CitationLocation,
+}
+
+/// The source of a [`Citation`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CitationSource {
+    /// A document sent to the model
+    Document {
+        /// Position of the document among all documents in the request
+        index: usize,
+        /// Document title, if it was given one
+        title: Option<String>,
+    },
+    /// A web search result
+    WebSearchResult {
+        /// URL of the result
+        url: String,
+        /// Page title
+        title: Option<String>,
+    },
+}
+
+/// Position of cited text within its source
+///
+/// Ranges include `start` and exclude `end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CitationLocation {
+    /// Character range of a plain text document, 0-indexed
+    Chars { start: usize, end: usize },
+    /// Page range of a PDF document, 1-indexed
+    Pages { start: usize, end: usize },
+    /// Content block range of a custom content document, 0-indexed
+    ContentBlocks { start: usize, end: usize },
+    /// The source has no finer position, as for web search results
+    Whole,
+}
+
+/// A document attached to a user message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentBlock {
+    /// Document format
+    pub format: crate::tool::DocumentFormat,
+    /// Where the document content comes from
+    pub source: DocumentSource,
+    /// Optional document name/filename
+    pub name: Option<String>,
+}
+
+/// Tokens per PDF page; models read each page's text and an image of it
+const PDF_TOKENS_PER_PAGE: usize = 2_000;
+
+/// Bytes per page assumed for a PDF whose pages can't be counted
+const PDF_BYTES_PER_PAGE: usize = 50_000;
+
+impl DocumentBlock {
+    /// Rough token estimate used for context management
+    pub(crate) fn estimated_tokens(&self) -> usize {
+        match &self.source {
+            DocumentSource::Bytes(data) if self.format == crate::tool::DocumentFormat::Pdf => {
+                let pages = match pdf_page_count(data) {
+                    0 => data.len().div_ceil(PDF_BYTES_PER_PAGE).max(1),
+                    pages => pages,
+                };
+                pages * PDF_TOKENS_PER_PAGE
+            }
+            DocumentSource::Bytes(data) => data.len() / 500 + 50,
+            // The size of an uploaded file is not known locally
+            DocumentSource::File(_) => 50,
+        }
+    }
+}
+
+/// Page objects in an uncompressed PDF page tree; 0 when none are visible
+fn pdf_page_count(data: &[u8]) -> usize {
+    [&b"/Type /Page"[..], &b"/Type/Page"[..]]
+        .iter()
+        .map(|marker| {
+            data.windows(marker.len() + 1)
+                .filter(|w| w.starts_with(marker) && w[marker.len()] != b's')
+                .count()
+        })
+        .sum()
+}
+
+/// Content of a [`DocumentBlock`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentSource {
+    /// Raw document bytes (not base64 encoded)
+    Bytes(Vec<u8>),
+    /// ID of a file previously uploaded through the Anthropic Files API
+    File(String),
 }
 
 /// A tool use request from the model
@@ -175,7 +314,10 @@ pub enum StopReason {
     MaxTokens,
     /// Content was filtered
     ContentFiltered,
-    /// Stop sequence encountered
+    /// Model declined to respond
+    Refusal,
+    /// Stop sequence encountered; the matched sequence is in
+    /// [`ModelResponse::stop_sequence`](crate::ModelResponse::stop_sequence)
     StopSequence,
     /// Model paused for extended thinking continuation
     PauseTurn,
@@ -219,6 +361,24 @@ mod tests {
         assert_eq!(format!("{}", Role::Assistant), "assistant");
     }
 
+    #[test]
+    fn test_pdf_estimate_counts_pages() {
+        let pdf = |data: &[u8]| DocumentBlock {
+            format: crate::tool::DocumentFormat::Pdf,
+            source: DocumentSource::Bytes(data.to_vec()),
+            name: None,
+        };
+
+        let three_pages = b"<< /Type /Pages /Count 3 >> \
+            << /Type /Page >> << /Type/Page >> << /Type /Page >>";
+        assert_eq!(pdf(three_pages).estimated_tokens(), 3 * PDF_TOKENS_PER_PAGE);
+
+        // A compressed page tree falls back to the file size
+        let compressed = vec![0u8; 3 * PDF_BYTES_PER_PAGE];
+        assert_eq!(pdf(&compressed).estimated_tokens(), 3 * PDF_TOKENS_PER_PAGE);
+        assert_eq!(pdf(b"%PDF-1.7").estimated_tokens(), PDF_TOKENS_PER_PAGE);
+    }
+
     #[test]
     fn test_tool_result_from_string() {
         let result: ToolResult = String::from("hello world").into();
@@ -4,7 +4,18 @@ use common::{
     AutoApproveGrantStore, Calculator, DataTool, DetailedEventCollector, ErrorTool, EventCollector,
     MockProvider,
 };
-use mixtape_core::{Agent, AgentEvent, ToolResult};
+use mixtape_core::{
+    Agent, AgentError, AgentEvent, AgentTool, AsyncAgentHook, ContentBlock, ContentFilter,
+    ConversationManager, ConversationStrategy, DocumentFormat, DocumentInput, DocumentSource,
+    EmptyResponsePolicy, FilterDecision, FilterStage, Message, ModelProvider, ModelResponse,
+    OverflowPolicy, ProviderError, RefusalPolicy, RequestContext, RequestInterceptor, Role,
+    SequentialIdGenerator, SlidingWindowConversationManager, StopReason, ToolDefinition,
+    ToolResult, ToolResultStatus, ToolUseBlock, DEFAULT_TOOL_POLICY_PROMPT,
+    EMPTY_RESPONSE_PLACEHOLDER,
+};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 #[tokio::test]
 async fn test_agent_simple_text_response() {
@@ -52,6 +63,33 @@ async fn test_agent_with_system_prompt() {
     assert_eq!(response, "I am helpful!");
 }
 
+#[tokio::test]
+async fn test_config_snapshot_lists_tools_and_system_prompt() {
+    let agent = Agent::builder()
+        .provider(MockProvider::new())
+        .with_name("calculator-agent")
+        .with_system_prompt("You are a helpful assistant")
+        .add_tool(Calculator)
+        .add_tool(DataTool)
+        .with_conversation_manager(SlidingWindowConversationManager::new())
+        .build()
+        .await
+        .unwrap();
+
+    let snapshot = agent.config_snapshot();
+    assert_eq!(snapshot.name.as_deref(), Some("calculator-agent"));
+    assert_eq!(
+        snapshot.system_prompt.as_deref(),
+        Some("You are a helpful assistant")
+    );
+    assert_eq!(snapshot.tools, vec!["calculate", "get_data"]);
+    assert!(snapshot.conversation_strategy.starts_with("sliding_window"));
+
+    let json = serde_json::to_value(&snapshot).unwrap();
+    assert_eq!(json["tools"], serde_json::json!(["calculate", "get_data"]));
+    assert_eq!(json["system_prompt"], "You are a helpful assistant");
+}
+
 #[tokio::test]
 async fn test_agent_multiple_tool_calls() {
     // Test that agent handles multiple sequential tool calls
@@ -87,6 +125,43 @@ async fn test_agent_tool_not_found() {
     assert_eq!(response, "Fallback response");
 }
 
+#[tokio::test]
+async fn test_unknown_tool_lists_available_tools_and_recovers() {
+    let provider = MockProvider::new()
+        .with_tool_use("calculator", serde_json::json!({"expression": "2+2"}))
+        .with_tool_use("calculate", serde_json::json!({"expression": "2+2"}))
+        .with_text("The answer is 4");
+
+    let agent = Agent::builder()
+        .provider(provider.clone())
+        .add_tool(Calculator)
+        .add_tool(DataTool)
+        .with_grant_store(AutoApproveGrantStore)
+        .build()
+        .await
+        .unwrap();
+    let collector = DetailedEventCollector::new();
+    agent.add_hook(collector.clone());
+
+    let response = agent.run("What is 2+2?").await.unwrap();
+    assert_eq!(response, "The answer is 4");
+
+    let expected = "Unknown tool: calculator. Available tools: calculate, get_data";
+    let result = first_tool_result(&provider);
+    assert_eq!(result.status, ToolResultStatus::Error);
+    assert!(result.content.as_text().contains(expected));
+
+    let failure = collector
+        .events()
+        .into_iter()
+        .find_map(|e| match e {
+            AgentEvent::ToolFailed { name, error, .. } => Some((name, error)),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(failure, ("calculator".to_string(), expected.to_string()));
+}
+
 #[tokio::test]
 async fn test_max_concurrent_tools() {
     let provider = MockProvider::new()
@@ -106,6 +181,195 @@ async fn test_max_concurrent_tools() {
     assert_eq!(response, "Done");
 }
 
+/// Tool that records the most calls to it running at once
+struct ConcurrencyProbe {
+    name: &'static str,
+    max_concurrency: Option<usize>,
+    running: Arc<AtomicUsize>,
+    peak: Arc<AtomicUsize>,
+}
+
+impl ConcurrencyProbe {
+    fn new(name: &'static str, max_concurrency: Option<usize>) -> Self {
+        Self {
+            name,
+            max_concurrency,
+            running: Arc::new(AtomicUsize::new(0)),
+            peak: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+}
+
+impl mixtape_core::Tool for ConcurrencyProbe {
+    type Input = serde_json::Value;
+
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn description(&self) -> &str {
+        "Records concurrent calls"
+    }
+
+    fn max_concurrency(&self) -> Option<usize> {
+        self.max_concurrency
+    }
+
+    async fn execute(&self, _input: Self::Input) -> Result<ToolResult, mixtape_core::ToolError> {
+        let now = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+        self.peak.fetch_max(now, Ordering::SeqCst);
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        self.running.fetch_sub(1, Ordering::SeqCst);
+        Ok(ToolResult::text("ok"))
+    }
+}
+
+#[tokio::test]
+async fn test_per_tool_max_concurrency_serializes_calls() {
+    let tool_use = |id: &str, name: &str| ToolUseBlock {
+        id: id.to_string(),
+        name: name.to_string(),
+        input: serde_json::json!({}),
+    };
+    let provider = MockProvider::new()
+        .with_tool_uses(vec![
+            tool_use("w1", "write_query"),
+            tool_use("r1", "read_query"),
+            tool_use("w2", "write_query"),
+            tool_use("r2", "read_query"),
+        ])
+        .with_text("Done");
+
+    let write = ConcurrencyProbe::new("write_query", Some(1));
+    let read = ConcurrencyProbe::new("read_query", None);
+    let (write_peak, read_peak) = (write.peak.clone(), read.peak.clone());
+
+    let agent = Agent::builder()
+        .provider(provider)
+        .add_trusted_tool(write)
+        .add_trusted_tool(read)
+        .build()
+        .await
+        .unwrap();
+
+    agent.run("Query").await.unwrap();
+    assert_eq!(write_peak.load(Ordering::SeqCst), 1);
+    assert_eq!(read_peak.load(Ordering::SeqCst), 2);
+}
+
+/// CPU-bound tool that spins until the IO tool signals it finished
+struct SpinTool {
+    blocking: bool,
+    io_done: Arc<AtomicBool>,
+}
+
+impl mixtape_core::Tool for SpinTool {
+    type Input = serde_json::Value;
+
+    fn name(&self) -> &str {
+        "spin"
+    }
+
+    fn description(&self) -> &str {
+        "Busy-waits"
+    }
+
+    fn is_blocking(&self) -> bool {
+        self.blocking
+    }
+
+    async fn execute(&self, _input: Self::Input) -> Result<ToolResult, mixtape_core::ToolError> {
+        let deadline = std::time::Instant::now() + Duration::from_secs(1);
+        while !self.io_done.load(Ordering::SeqCst) {
+            if std::time::Instant::now() > deadline {
+                return Ok(ToolResult::text("starved"));
+            }
+            std::hint::spin_loop();
+        }
+        Ok(ToolResult::text(
+            std::thread::current().name().unwrap_or("").to_string(),
+        ))
+    }
+}
+
+/// IO-bound tool that sleeps, then signals the spin tool
+struct WaitTool(Arc<AtomicBool>);
+
+impl mixtape_core::Tool for WaitTool {
+    type Input = serde_json::Value;
+
+    fn name(&self) -> &str {
+        "wait"
+    }
+
+    fn description(&self) -> &str {
+        "Sleeps briefly"
+    }
+
+    async fn execute(&self, _input: Self::Input) -> Result<ToolResult, mixtape_core::ToolError> {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        self.0.store(true, Ordering::SeqCst);
+        Ok(ToolResult::text("waited"))
+    }
+}
+
+async fn run_spin_and_wait(blocking: bool, tool_runtime: Option<tokio::runtime::Handle>) -> String {
+    let tool_use = |id: &str, name: &str| ToolUseBlock {
+        id: id.to_string(),
+        name: name.to_string(),
+        input: serde_json::json!({}),
+    };
+    let provider = MockProvider::new()
+        .with_tool_uses(vec![tool_use("s1", "spin"), tool_use("w1", "wait")])
+        .with_text("Done");
+    let io_done = Arc::new(AtomicBool::new(false));
+
+    let mut builder = Agent::builder()
+        .provider(provider)
+        .add_trusted_tool(SpinTool {
+            blocking,
+            io_done: io_done.clone(),
+        })
+        .add_trusted_tool(WaitTool(io_done));
+    if let Some(handle) = tool_runtime {
+        builder = builder.with_tool_runtime(handle);
+    }
+    let agent = builder.build().await.unwrap();
+
+    let response = agent.run("Go").await.unwrap();
+    let spin = response
+        .tool_calls
+        .iter()
+        .find(|c| c.name == "spin")
+        .unwrap();
+    spin.output.clone()
+}
+
+#[tokio::test]
+async fn test_blocking_tool_does_not_starve_io_tools() {
+    // On this single-threaded runtime, a CPU-bound tool run inline keeps
+    // the IO tool from ever being polled
+    assert_eq!(run_spin_and_wait(false, None).await, "starved");
+
+    let output = run_spin_and_wait(true, None).await;
+    assert_ne!(output, "starved");
+}
+
+#[tokio::test]
+async fn test_blocking_tool_runs_on_configured_runtime() {
+    let tool_runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .thread_name("tool-runtime")
+        .enable_all()
+        .build()
+        .unwrap();
+
+    let output = run_spin_and_wait(true, Some(tool_runtime.handle().clone())).await;
+    assert_eq!(output, "tool-runtime");
+
+    tool_runtime.shutdown_background();
+}
+
 #[tokio::test]
 async fn test_provider_call_count() {
     let provider = MockProvider::new()
@@ -232,6 +496,54 @@ async fn test_tool_json_result() {
     assert_eq!(response, "Got the data");
 }
 
+/// The tool result the model was sent after the first tool call
+fn first_tool_result(provider: &MockProvider) -> mixtape_core::ToolResultBlock {
+    match &provider.received_messages()[1].last().unwrap().content[0] {
+        ContentBlock::ToolResult(result) => result.clone(),
+        other => panic!("expected tool result, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_tool_json_result_extracted_by_pointer() {
+    let provider = MockProvider::new()
+        .with_tool_use("get_data", serde_json::json!({"key": "test"}))
+        .with_text("Got the field");
+
+    let agent = Agent::builder()
+        .provider(provider.clone())
+        .add_tool_extracting(DataTool, "/nested/field")
+        .with_grant_store(AutoApproveGrantStore)
+        .build()
+        .await
+        .unwrap();
+
+    agent.run("Get data").await.unwrap();
+    let result = first_tool_result(&provider);
+    assert_eq!(result.status, mixtape_core::ToolResultStatus::Success);
+    assert!(matches!(result.content, ToolResult::Json(ref v) if v == "test"));
+}
+
+#[tokio::test]
+async fn test_tool_json_pointer_without_match_fails() {
+    let provider = MockProvider::new()
+        .with_tool_use("get_data", serde_json::json!({"key": "test"}))
+        .with_text("No field");
+
+    let agent = Agent::builder()
+        .provider(provider.clone())
+        .add_tool_extracting(DataTool, "/missing")
+        .with_grant_store(AutoApproveGrantStore)
+        .build()
+        .await
+        .unwrap();
+
+    agent.run("Get data").await.unwrap();
+    let result = first_tool_result(&provider);
+    assert_eq!(result.status, mixtape_core::ToolResultStatus::Error);
+    assert!(result.content.as_text().contains("/missing"));
+}
+
 // ===== Error Path Tests =====
 
 #[tokio::test]
@@ -315,7 +627,10 @@ async fn test_event_data_verification() {
 
     // Verify RunCompleted event
     let run_completed = events.iter().find_map(|e| {
-        if let AgentEvent::RunCompleted { output, duration } = e {
+        if let AgentEvent::RunCompleted {
+            output, duration, ..
+        } = e
+        {
             Some((output, duration))
         } else {
             None
@@ -327,6 +642,52 @@ async fn test_event_data_verification() {
     assert!(duration.as_nanos() > 0); // Should have taken some time (use nanos for faster tests)
 }
 
+#[tokio::test]
+async fn test_agent_name_in_run_events() {
+    let provider = MockProvider::new().with_text("Found it");
+    let collector = DetailedEventCollector::new();
+
+    let agent = Agent::builder()
+        .provider(provider)
+        .with_name("researcher")
+        .build()
+        .await
+        .unwrap();
+    agent.add_hook(collector.clone());
+    assert_eq!(agent.name(), Some("researcher"));
+    assert_eq!(agent.model_name(), "MockProvider");
+
+    agent.run("Look it up").await.unwrap();
+
+    let events = collector.events();
+    assert!(matches!(
+        events.first(),
+        Some(AgentEvent::RunStarted { agent_name: Some(name), .. }) if name == "researcher"
+    ));
+    assert!(matches!(
+        events.last(),
+        Some(AgentEvent::RunCompleted { agent_name: Some(name), .. }) if name == "researcher"
+    ));
+
+    // Unnamed agents report no name
+    let unnamed = Agent::builder()
+        .provider(MockProvider::new().with_text("Hi"))
+        .build()
+        .await
+        .unwrap();
+    let collector = DetailedEventCollector::new();
+    unnamed.add_hook(collector.clone());
+    unnamed.run("Hello").await.unwrap();
+    assert!(unnamed.name().is_none());
+    assert!(matches!(
+        collector.events().first(),
+        Some(AgentEvent::RunStarted {
+            agent_name: None,
+            ..
+        })
+    ));
+}
+
 #[tokio::test]
 async fn test_model_call_events() {
     let provider = MockProvider::new().with_text("Response");
@@ -379,6 +740,150 @@ async fn test_model_call_events() {
     assert!(stop_reason.is_some());
 }
 
+#[tokio::test(start_paused = true)]
+async fn test_run_with_timeout_expires_during_model_call() {
+    let provider = MockProvider::new()
+        .with_text("Too late")
+        .with_delay(Duration::from_secs(10));
+    let collector = DetailedEventCollector::new();
+    let agent = Agent::builder().provider(provider).build().await.unwrap();
+    agent.add_hook(collector.clone());
+
+    let err = agent
+        .run_with_timeout("Hello", Duration::from_secs(1))
+        .await
+        .unwrap_err();
+
+    assert!(matches!(
+        err,
+        AgentError::Timeout { timeout, last_turn: None } if timeout == Duration::from_secs(1)
+    ));
+    assert!(collector
+        .events()
+        .iter()
+        .any(|e| matches!(e, AgentEvent::RunFailed { error, .. } if error.contains("timed out"))));
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_run_until_interrupt_fails_run() {
+    let provider = MockProvider::new()
+        .with_text("Too late")
+        .with_delay(Duration::from_secs(10));
+    let collector = DetailedEventCollector::new();
+    let agent = Agent::builder().provider(provider).build().await.unwrap();
+    agent.add_hook(collector.clone());
+
+    let err = agent
+        .run_until("Hello", tokio::time::sleep(Duration::from_secs(1)))
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, AgentError::Interrupted));
+    assert!(matches!(
+        collector.events().last(),
+        Some(AgentEvent::RunFailed { error, .. }) if error == "Interrupted"
+    ));
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_run_with_timeout_keeps_last_complete_turn() {
+    // Each model call takes 10s: the tool-use turn completes, the next does not
+    let provider = MockProvider::new()
+        .with_text_and_tool_use(
+            "Let me calculate.",
+            "calculate",
+            serde_json::json!({"expression": "2+2"}),
+        )
+        .with_text("It's 4.")
+        .with_delay(Duration::from_secs(10));
+    let agent = Agent::builder()
+        .provider(provider)
+        .add_tool(Calculator)
+        .with_grant_store(AutoApproveGrantStore)
+        .build()
+        .await
+        .unwrap();
+
+    let err = agent
+        .run_with_timeout("What is 2+2?", Duration::from_secs(15))
+        .await
+        .unwrap_err();
+
+    let AgentError::Timeout {
+        last_turn: Some(turn),
+        ..
+    } = err
+    else {
+        panic!("Expected a timeout with a partial turn, got {:?}", err);
+    };
+    assert_eq!(turn.text(), "Let me calculate.");
+    assert!(turn
+        .content
+        .iter()
+        .any(|block| matches!(block, ContentBlock::ToolUse(_))));
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_run_with_timeout_returns_response_within_deadline() {
+    let provider = MockProvider::new()
+        .with_text("Quick answer")
+        .with_delay(Duration::from_secs(1));
+    let agent = Agent::builder().provider(provider).build().await.unwrap();
+
+    let response = agent
+        .run_with_timeout("Hello", Duration::from_secs(5))
+        .await
+        .unwrap();
+    assert_eq!(response.text(), "Quick answer");
+}
+
+#[tokio::test]
+async fn test_turn_completed_marks_each_model_turn() {
+    let provider = MockProvider::new()
+        .with_text_and_tool_use(
+            "Let me calculate.",
+            "calculate",
+            serde_json::json!({"expression": "2+2"}),
+        )
+        .with_text("It's 4.");
+    let collector = DetailedEventCollector::new();
+
+    let agent = Agent::builder()
+        .provider(provider)
+        .add_tool(Calculator)
+        .with_grant_store(AutoApproveGrantStore)
+        .build()
+        .await
+        .unwrap();
+    agent.add_hook(collector.clone());
+
+    agent.run("What is 2+2?").await.unwrap();
+
+    let events = collector.events();
+    let turns: Vec<_> = events
+        .iter()
+        .filter_map(|e| match e {
+            AgentEvent::TurnCompleted {
+                turn_index,
+                stop_reason,
+            } => Some((*turn_index, *stop_reason)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        turns,
+        vec![(0, StopReason::ToolUse), (1, StopReason::EndTurn)]
+    );
+
+    // The first turn ends after its model call and before its tool runs
+    let position = |pred: &dyn Fn(&AgentEvent) -> bool| events.iter().position(pred).unwrap();
+    let first_turn = position(&|e| matches!(e, AgentEvent::TurnCompleted { turn_index: 0, .. }));
+    let first_call = position(&|e| matches!(e, AgentEvent::ModelCallCompleted { .. }));
+    let tool_done = position(&|e| matches!(e, AgentEvent::ToolCompleted { .. }));
+    assert!(first_call < first_turn);
+    assert!(first_turn < tool_done);
+}
+
 #[tokio::test]
 async fn test_tool_event_details() {
     let provider = MockProvider::new()
@@ -435,6 +940,47 @@ async fn test_tool_event_details() {
     assert!(duration.as_nanos() > 0);
 }
 
+#[tokio::test]
+async fn test_model_and_tool_latency_reported() {
+    let delay = std::time::Duration::from_millis(20);
+    let provider = MockProvider::new()
+        .with_delay(delay)
+        .with_tool_use("calculate", serde_json::json!({"expression": "2+2"}))
+        .with_text("Done");
+    let collector = DetailedEventCollector::new();
+
+    let agent = Agent::builder()
+        .provider(provider)
+        .add_tool(Calculator)
+        .with_grant_store(AutoApproveGrantStore)
+        .build()
+        .await
+        .unwrap();
+    agent.add_hook(collector.clone());
+
+    let response = agent.run("Calculate").await.unwrap();
+
+    let events = collector.events();
+    let model_durations: Vec<_> = events
+        .iter()
+        .filter_map(|e| match e {
+            AgentEvent::ModelCallCompleted { duration, .. } => Some(*duration),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(model_durations.len(), 2);
+    assert!(model_durations.iter().all(|d| *d >= delay));
+    assert_eq!(
+        response.model_latency,
+        model_durations.iter().sum::<std::time::Duration>()
+    );
+
+    assert!(events.iter().any(|e| matches!(
+        e,
+        AgentEvent::ToolCompleted { duration, .. } if !duration.is_zero()
+    )));
+}
+
 #[tokio::test]
 async fn test_multiple_hooks() {
     let provider = MockProvider::new().with_text("Response");
@@ -487,7 +1033,7 @@ async fn test_tool_not_found_emits_failure() {
     assert!(tool_failed.is_some());
     let (name, error) = tool_failed.unwrap();
     assert_eq!(name, "nonexistent");
-    assert!(error.contains("Tool not found"));
+    assert!(error.contains("Unknown tool: nonexistent"));
 }
 
 // ===== Agent Helper Method Tests =====
@@ -500,6 +1046,28 @@ async fn test_model_name() {
     assert_eq!(agent.model_name(), "MockProvider");
 }
 
+#[tokio::test]
+async fn test_provider_handle_calls_model_directly() {
+    let provider = MockProvider::new().with_text("spam");
+    let agent = Agent::builder()
+        .provider(provider.clone())
+        .add_tool(Calculator)
+        .build()
+        .await
+        .unwrap();
+
+    let response = agent
+        .provider()
+        .generate(vec![Message::user("Classify: WIN NOW")], vec![], None)
+        .await
+        .unwrap();
+    assert_eq!(response.message.text(), "spam");
+    assert_eq!(provider.call_count(), 1);
+
+    // The call bypasses the agent's conversation
+    assert_eq!(agent.message_count(), 0);
+}
+
 #[tokio::test]
 async fn test_list_tools() {
     let provider = MockProvider::new();
@@ -529,4 +1097,2314 @@ async fn test_list_tools() {
     assert_eq!(tools[0].description, "Evaluate a mathematical expression");
     assert_eq!(tools[1].name, "get_data");
     assert_eq!(tools[1].description, "Get structured data");
+    assert!(tools.iter().all(|t| t.output_schema.is_none()));
+}
+
+// ===== Token Counting Tests =====
+
+/// Message count, tool count, and system prompt passed to `count_tokens`
+type CountRequest = (usize, usize, Option<String>);
+
+/// Provider with an exact token counter that records what it was asked to count
+struct CountingProvider {
+    inner: MockProvider,
+    seen: Arc<Mutex<Option<CountRequest>>>,
+}
+
+#[async_trait::async_trait]
+impl ModelProvider for CountingProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn max_context_tokens(&self) -> usize {
+        1_000
+    }
+
+    fn max_output_tokens(&self) -> usize {
+        self.inner.max_output_tokens()
+    }
+
+    async fn count_tokens(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+        system_prompt: Option<&str>,
+    ) -> Result<usize, ProviderError> {
+        *self.seen.lock().unwrap() = Some((
+            messages.len(),
+            tools.len(),
+            system_prompt.map(str::to_string),
+        ));
+        Ok(250)
+    }
+
+    async fn generate(
+        &self,
+        messages: Vec<Message>,
+        tools: Vec<ToolDefinition>,
+        system_prompt: Option<String>,
+    ) -> Result<ModelResponse, ProviderError> {
+        self.inner.generate(messages, tools, system_prompt).await
+    }
+}
+
+#[tokio::test]
+async fn test_count_context_tokens_uses_provider_count() {
+    let seen = Arc::new(Mutex::new(None));
+    let provider = CountingProvider {
+        inner: MockProvider::new().with_text("Hi there"),
+        seen: seen.clone(),
+    };
+
+    let agent = Agent::builder()
+        .provider(provider)
+        .with_system_prompt("Be brief")
+        .add_tool(Calculator)
+        .build()
+        .await
+        .unwrap();
+    agent.run("Hello").await.unwrap();
+
+    let usage = agent.count_context_tokens().await.unwrap();
+    assert_eq!(usage.context_tokens, 250);
+    assert_eq!(usage.context_messages, 2);
+    assert_eq!(usage.total_messages, 2);
+    assert_eq!(usage.max_context_tokens, 1_000);
+    assert!((usage.usage_percentage - 0.25).abs() < f32::EPSILON);
+
+    let seen = seen.lock().unwrap().clone();
+    assert_eq!(seen, Some((2, 1, Some("Be brief".to_string()))));
+}
+
+#[tokio::test]
+async fn test_count_context_tokens_falls_back_to_estimate() {
+    let provider = MockProvider::new().with_text("Hi there");
+
+    let agent = Agent::builder().provider(provider).build().await.unwrap();
+    agent.run("Hello").await.unwrap();
+
+    let exact = agent.count_context_tokens().await.unwrap();
+    let estimated = agent.get_context_usage();
+    assert_eq!(exact.context_tokens, estimated.context_tokens);
+    assert_eq!(exact.context_messages, estimated.context_messages);
+}
+
+#[tokio::test]
+async fn test_budget_warning_fires_per_large_call() {
+    let provider = MockProvider::new()
+        .with_text("Short answer")
+        .with_tool_use("calculate", serde_json::json!({"expression": "2+2"}))
+        .with_text("The answer is 4");
+
+    let agent = Agent::builder()
+        .provider(provider)
+        .add_tool(Calculator)
+        .with_grant_store(AutoApproveGrantStore)
+        .with_input_token_warning(1_000)
+        .build()
+        .await
+        .unwrap();
+    let collector = DetailedEventCollector::new();
+    agent.add_hook(collector.clone());
+
+    // A small request stays under the threshold
+    agent.run("Hello").await.unwrap();
+    assert!(!collector
+        .events()
+        .iter()
+        .any(|e| matches!(e, AgentEvent::BudgetWarning { .. })));
+
+    // Both model calls of this run carry the large message
+    agent.run(&"x".repeat(8_000)).await.unwrap();
+    let events = collector.events();
+    let warnings: Vec<usize> = events
+        .iter()
+        .filter_map(|e| match e {
+            AgentEvent::BudgetWarning {
+                estimated_input_tokens,
+                threshold,
+            } => {
+                assert_eq!(*threshold, 1_000);
+                Some(*estimated_input_tokens)
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(warnings.len(), 2);
+    assert!(warnings.iter().all(|tokens| *tokens > 2_000));
+
+    // Each warning comes just before its model call
+    for (i, event) in events.iter().enumerate() {
+        if matches!(event, AgentEvent::BudgetWarning { .. }) {
+            assert!(matches!(events[i + 1], AgentEvent::ModelCallStarted { .. }));
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_summarizing_strategy_replaces_older_messages() {
+    let provider = MockProvider::new()
+        .with_text("first answer")
+        .with_text("asked one question")
+        .with_text("second answer");
+
+    let agent = Agent::builder()
+        .provider(provider.clone())
+        .with_conversation_strategy(ConversationStrategy::Summarizing {
+            trigger: 0.0,
+            keep_recent: 1,
+        })
+        .build()
+        .await
+        .unwrap();
+
+    agent.run("first question").await.unwrap();
+    let response = agent.run("second question").await.unwrap();
+    assert_eq!(response, "second answer");
+
+    // The older turn goes to the model as a transcript to summarize
+    let received = provider.received_messages();
+    assert_eq!(received.len(), 3);
+    assert_eq!(received[1].len(), 1);
+    let request = received[1][0].text();
+    assert!(request.contains("User: first question"));
+    assert!(request.contains("Assistant: first answer"));
+
+    // ...and the summary stands in for it from then on
+    assert_eq!(received[2].len(), 1);
+    let context = received[2][0].text();
+    assert!(context.contains("asked one question"));
+    assert!(context.ends_with("second question"));
+    assert_eq!(agent.message_count(), 4);
+}
+
+// ===== Request Interceptor Tests =====
+
+/// Messages and system prompt received by the provider on each call
+type ReceivedRequest = (Vec<Message>, usize, Option<String>);
+
+/// Provider that records every request it receives
+struct RecordingProvider {
+    inner: MockProvider,
+    received: Arc<Mutex<Vec<ReceivedRequest>>>,
+}
+
+#[async_trait::async_trait]
+impl ModelProvider for RecordingProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn max_context_tokens(&self) -> usize {
+        self.inner.max_context_tokens()
+    }
+
+    fn max_output_tokens(&self) -> usize {
+        self.inner.max_output_tokens()
+    }
+
+    fn supported_document_formats(&self) -> &[DocumentFormat] {
+        &[DocumentFormat::Pdf]
+    }
+
+    async fn generate(
+        &self,
+        messages: Vec<Message>,
+        tools: Vec<ToolDefinition>,
+        system_prompt: Option<String>,
+    ) -> Result<ModelResponse, ProviderError> {
+        self.received
+            .lock()
+            .unwrap()
+            .push((messages.clone(), tools.len(), system_prompt.clone()));
+        self.inner.generate(messages, tools, system_prompt).await
+    }
+}
+
+/// Appends a fixed user message to every request
+struct AppendMessage(&'static str);
+
+#[async_trait::async_trait]
+impl RequestInterceptor for AppendMessage {
+    async fn before_generate(&self, ctx: &mut RequestContext) {
+        ctx.messages.push(Message::user(self.0));
+    }
+}
+
+/// Appends a suffix to the system prompt and removes all tools
+struct SuffixAndStripTools(&'static str);
+
+#[async_trait::async_trait]
+impl RequestInterceptor for SuffixAndStripTools {
+    async fn before_generate(&self, ctx: &mut RequestContext) {
+        let prompt = ctx.system_prompt.take().unwrap_or_default();
+        ctx.system_prompt = Some(format!("{}{}", prompt, self.0));
+        ctx.tools.clear();
+    }
+}
+
+#[tokio::test]
+async fn test_interceptor_appended_message_reaches_provider() {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let provider = RecordingProvider {
+        inner: MockProvider::new().with_text("Done"),
+        received: received.clone(),
+    };
+
+    let agent = Agent::builder()
+        .provider(provider)
+        .with_interceptor(AppendMessage("injected"))
+        .build()
+        .await
+        .unwrap();
+    agent.run("Hello").await.unwrap();
+
+    let received = received.lock().unwrap();
+    assert_eq!(received.len(), 1);
+    let messages = &received[0].0;
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[0].text(), "Hello");
+    assert_eq!(messages[1].text(), "injected");
+
+    // The injected message only affects the request, not the conversation
+    assert_eq!(agent.get_context_usage().total_messages, 2);
+}
+
+#[tokio::test]
+async fn test_interceptors_run_in_order_before_each_call() {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let provider = RecordingProvider {
+        inner: MockProvider::new()
+            .with_tool_use("calculate", serde_json::json!({"expression": "2+2"}))
+            .with_text("4"),
+        received: received.clone(),
+    };
+
+    let agent = Agent::builder()
+        .provider(provider)
+        .with_system_prompt("Base")
+        .add_trusted_tool(Calculator)
+        .with_interceptor(SuffixAndStripTools(" one"))
+        .with_interceptor(SuffixAndStripTools(" two"))
+        .build()
+        .await
+        .unwrap();
+    agent.run("What is 2+2?").await.unwrap();
+
+    let received = received.lock().unwrap();
+    assert_eq!(received.len(), 2);
+    for (_, tool_count, system_prompt) in received.iter() {
+        assert_eq!(*tool_count, 0);
+        assert_eq!(system_prompt.as_deref(), Some("Base one two"));
+    }
+}
+
+#[tokio::test]
+async fn test_plan_request_matches_first_request() {
+    let dir = tempfile::tempdir().unwrap();
+    let context_path = dir.path().join("AGENTS.md");
+    std::fs::write(&context_path, "Always answer in French.").unwrap();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let provider = RecordingProvider {
+        inner: MockProvider::new()
+            .with_text("Bonjour")
+            .with_text("Au revoir"),
+        received: received.clone(),
+    };
+    let agent = Agent::builder()
+        .provider(provider)
+        .with_system_prompt("You are terse.")
+        .add_context_file(context_path.to_string_lossy())
+        .add_tool(Calculator)
+        .with_conversation_manager(mixtape_core::conversation::SimpleConversationManager::new(
+            2,
+        ))
+        .build()
+        .await
+        .unwrap();
+    agent.run("Hello").await.unwrap();
+
+    let plan = agent.plan_request("Goodbye").await.unwrap();
+    let system_prompt = plan.system_prompt.clone().unwrap();
+    assert!(system_prompt.contains("You are terse."));
+    assert!(system_prompt.contains("Always answer in French."));
+    assert!(plan.tools.iter().any(|t| t.name == "calculate"));
+    // The window keeps the last two messages: the reply and the new input
+    let texts: Vec<String> = plan.messages.iter().map(|m| m.text()).collect();
+    assert_eq!(texts, vec!["Bonjour", "Goodbye"]);
+    // Planning leaves the conversation as it was
+    assert_eq!(agent.message_count(), 2);
+
+    agent.run("Goodbye").await.unwrap();
+    let received = received.lock().unwrap();
+    let (messages, tool_count, sent_prompt) = &received[1];
+    let sent: Vec<String> = messages.iter().map(|m| m.text()).collect();
+    assert_eq!(sent, texts);
+    assert_eq!(*tool_count, plan.tools.len());
+    assert_eq!(sent_prompt.as_deref(), Some(system_prompt.as_str()));
+}
+
+/// Tool that never finishes within a test's deadline
+struct HangTool;
+
+impl mixtape_core::Tool for HangTool {
+    type Input = serde_json::Value;
+
+    fn name(&self) -> &str {
+        "hang"
+    }
+
+    fn description(&self) -> &str {
+        "Takes a minute"
+    }
+
+    async fn execute(&self, _input: Self::Input) -> Result<ToolResult, mixtape_core::ToolError> {
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        Ok(ToolResult::text("finished"))
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_plan_request_keeps_unanswered_tool_use() {
+    let provider = MockProvider::new().with_tool_use("hang", serde_json::json!({}));
+    let agent = Agent::builder()
+        .provider(provider)
+        .add_trusted_tool(HangTool)
+        .build()
+        .await
+        .unwrap();
+
+    // Timing out mid-tool leaves the assistant's tool use unanswered
+    agent
+        .run_with_timeout("Start", Duration::from_secs(1))
+        .await
+        .unwrap_err();
+    let history = agent.messages();
+    assert!(history
+        .last()
+        .unwrap()
+        .content
+        .iter()
+        .any(|block| matches!(block, ContentBlock::ToolUse(_))));
+
+    agent.plan_request("Next").await.unwrap();
+    assert_eq!(format!("{:?}", agent.messages()), format!("{:?}", history));
+}
+
+#[tokio::test]
+async fn test_context_overflow_fails_before_sending() {
+    let provider = MockProvider::new().with_text("never sent");
+    let agent = Agent::builder()
+        .provider(provider.clone())
+        .build()
+        .await
+        .unwrap();
+
+    let result = agent.run(&"x".repeat(1_000_000)).await;
+    match result {
+        Err(AgentError::ContextOverflow {
+            estimated_tokens,
+            max_context_tokens,
+        }) => {
+            assert!(estimated_tokens > 200_000);
+            assert_eq!(max_context_tokens, 200_000);
+        }
+        other => panic!("expected ContextOverflow, got {:?}", other),
+    }
+    assert_eq!(provider.call_count(), 0);
+    // The oversized input is not kept
+    assert_eq!(agent.message_count(), 0);
+}
+
+#[tokio::test]
+async fn test_overflow_policy_trim_drops_oldest_turns() {
+    let provider = MockProvider::new().with_text("first").with_text("second");
+    let agent = Agent::builder()
+        .provider(provider.clone())
+        .with_conversation_manager(mixtape_core::conversation::SimpleConversationManager::new(
+            10,
+        ))
+        .with_overflow_policy(OverflowPolicy::Trim)
+        .build()
+        .await
+        .unwrap();
+
+    agent.run(&"a".repeat(500_000)).await.unwrap();
+    let response = agent.run(&"b".repeat(500_000)).await.unwrap();
+    assert_eq!(response, "second");
+
+    // The first turn no longer fits, so the second request starts fresh
+    let received = provider.received_messages();
+    assert_eq!(received[1].len(), 1);
+    assert!(received[1][0].text().starts_with('b'));
+}
+
+#[tokio::test]
+async fn test_overflow_policy_send_leaves_request_alone() {
+    let provider = MockProvider::new().with_text("sent anyway");
+    let agent = Agent::builder()
+        .provider(provider.clone())
+        .with_overflow_policy(OverflowPolicy::Send)
+        .build()
+        .await
+        .unwrap();
+
+    let response = agent.run(&"x".repeat(1_000_000)).await.unwrap();
+    assert_eq!(response, "sent anyway");
+    assert_eq!(provider.call_count(), 1);
+}
+
+/// Replaces the system prompt with the current value of a shared string
+struct SwapSystemPrompt(Arc<Mutex<String>>);
+
+#[async_trait::async_trait]
+impl RequestInterceptor for SwapSystemPrompt {
+    async fn before_generate(&self, ctx: &mut RequestContext) {
+        ctx.system_prompt = Some(self.0.lock().unwrap().clone());
+    }
+}
+
+#[tokio::test]
+async fn test_cache_invalidated_when_system_prompt_changes() {
+    let prompt = Arc::new(Mutex::new("You are terse.".to_string()));
+    let provider = MockProvider::new()
+        .with_text("one")
+        .with_text("two")
+        .with_text("three");
+    let collector = DetailedEventCollector::new();
+
+    let agent = Agent::builder()
+        .provider(provider)
+        .add_tool(Calculator)
+        .with_interceptor(SwapSystemPrompt(prompt.clone()))
+        .build()
+        .await
+        .unwrap();
+    agent.add_hook(collector.clone());
+
+    let invalidations = || {
+        collector
+            .events()
+            .into_iter()
+            .filter_map(|e| match e {
+                AgentEvent::CacheInvalidated {
+                    system_prompt_changed,
+                    tools_changed,
+                } => Some((system_prompt_changed, tools_changed)),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // The first call and an unchanged prefix are not invalidations
+    agent.run("first").await.unwrap();
+    agent.run("second").await.unwrap();
+    assert!(invalidations().is_empty());
+
+    *prompt.lock().unwrap() = "You are verbose.".to_string();
+    agent.run("third").await.unwrap();
+    assert_eq!(invalidations(), vec![(true, false)]);
+}
+
+/// Provider that counts requests to drop stale cache breakpoints
+struct StaleBreakpointProvider {
+    inner: MockProvider,
+    requests: Arc<AtomicUsize>,
+}
+
+#[async_trait::async_trait]
+impl ModelProvider for StaleBreakpointProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn max_context_tokens(&self) -> usize {
+        self.inner.max_context_tokens()
+    }
+
+    fn max_output_tokens(&self) -> usize {
+        self.inner.max_output_tokens()
+    }
+
+    async fn generate(
+        &self,
+        messages: Vec<Message>,
+        tools: Vec<ToolDefinition>,
+        system_prompt: Option<String>,
+    ) -> Result<ModelResponse, ProviderError> {
+        self.inner.generate(messages, tools, system_prompt).await
+    }
+
+    fn without_stale_cache_breakpoints(
+        &self,
+        _tools_changed: bool,
+    ) -> Option<Arc<dyn ModelProvider>> {
+        self.requests.fetch_add(1, Ordering::SeqCst);
+        None
+    }
+}
+
+#[tokio::test]
+async fn test_stale_cache_breakpoints_kept_unless_enabled() {
+    for (enabled, expected) in [(false, 0), (true, 1)] {
+        let prompt = Arc::new(Mutex::new("You are terse.".to_string()));
+        let requests = Arc::new(AtomicUsize::new(0));
+        let provider = StaleBreakpointProvider {
+            inner: MockProvider::new().with_text("one").with_text("two"),
+            requests: requests.clone(),
+        };
+
+        let agent = Agent::builder()
+            .provider(provider)
+            .with_interceptor(SwapSystemPrompt(prompt.clone()))
+            .with_stale_prefix_uncached(enabled)
+            .build()
+            .await
+            .unwrap();
+
+        agent.run("first").await.unwrap();
+        *prompt.lock().unwrap() = "You are verbose.".to_string();
+        agent.run("second").await.unwrap();
+        assert_eq!(requests.load(Ordering::SeqCst), expected);
+    }
+}
+
+// ===== Tool Policy Prompt Tests =====
+
+/// Tool that reports itself as destructive
+struct DeleteEverything;
+
+impl mixtape_core::Tool for DeleteEverything {
+    type Input = serde_json::Value;
+
+    fn name(&self) -> &str {
+        "delete_everything"
+    }
+
+    fn description(&self) -> &str {
+        "Delete all files"
+    }
+
+    fn is_destructive(&self) -> bool {
+        true
+    }
+
+    async fn execute(&self, _input: Self::Input) -> Result<ToolResult, mixtape_core::ToolError> {
+        Ok("Deleted".into())
+    }
+}
+
+/// The system prompt sent on the first model call
+async fn first_system_prompt(builder: mixtape_core::AgentBuilder) -> Option<String> {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let provider = RecordingProvider {
+        inner: MockProvider::new().with_text("Done"),
+        received: received.clone(),
+    };
+    let agent = builder.provider(provider).build().await.unwrap();
+    agent.run("Hello").await.unwrap();
+    let received = received.lock().unwrap();
+    received[0].2.clone()
+}
+
+#[tokio::test]
+async fn test_default_tool_policy_only_with_destructive_tools() {
+    let with_destructive = first_system_prompt(
+        Agent::builder()
+            .with_system_prompt("Base")
+            .add_tool(Calculator)
+            .add_tool(DeleteEverything),
+    )
+    .await
+    .unwrap();
+    assert!(with_destructive.starts_with("Base\n\n"));
+    assert!(with_destructive.contains(DEFAULT_TOOL_POLICY_PROMPT));
+
+    let without_destructive = first_system_prompt(
+        Agent::builder()
+            .with_system_prompt("Base")
+            .add_tool(Calculator),
+    )
+    .await;
+    assert_eq!(without_destructive.as_deref(), Some("Base"));
+}
+
+#[tokio::test]
+async fn test_tool_policy_prompt_override_and_suppress() {
+    let custom = first_system_prompt(
+        Agent::builder()
+            .add_tool(Calculator)
+            .with_tool_policy_prompt("Always validate paths."),
+    )
+    .await
+    .unwrap();
+    assert!(custom.contains("Always validate paths."));
+    assert!(!custom.contains(DEFAULT_TOOL_POLICY_PROMPT));
+
+    let suppressed = first_system_prompt(
+        Agent::builder()
+            .add_tool(DeleteEverything)
+            .without_tool_policy_prompt(),
+    )
+    .await;
+    assert_eq!(suppressed, None);
+}
+
+#[tokio::test]
+async fn test_parallel_tool_use_falls_back_to_prompt_guidance() {
+    let sequential = first_system_prompt(
+        Agent::builder()
+            .add_tool(Calculator)
+            .with_parallel_tool_use(false),
+    )
+    .await
+    .unwrap();
+    assert!(sequential.starts_with("## Tool use policy\n"));
+    assert!(sequential.contains("at most one tool per response"));
+
+    let parallel = first_system_prompt(
+        Agent::builder()
+            .add_tool(DeleteEverything)
+            .with_parallel_tool_use(true),
+    )
+    .await
+    .unwrap();
+    assert!(parallel.contains(DEFAULT_TOOL_POLICY_PROMPT));
+    assert!(parallel.contains("make them together in one response"));
+
+    // Nothing to say when the model has no tools
+    let no_tools = first_system_prompt(Agent::builder().with_parallel_tool_use(false)).await;
+    assert_eq!(no_tools, None);
+}
+
+// ===== Sub-agent Delegation Tests =====
+
+/// Build an agent backed by `provider` with an optional delegation tool
+async fn delegating_agent(provider: MockProvider, tool: Option<AgentTool>) -> Agent {
+    let mut builder = Agent::builder().provider(provider);
+    if let Some(tool) = tool {
+        builder = builder.add_trusted_tool(tool);
+    }
+    builder.build().await.unwrap()
+}
+
+#[tokio::test]
+async fn test_supervisor_delegates_to_sub_agent() {
+    let sub_provider = MockProvider::new().with_text("The answer is 42");
+    let researcher = Arc::new(delegating_agent(sub_provider.clone(), None).await);
+
+    let provider = MockProvider::new()
+        .with_tool_use("researcher", serde_json::json!({"task": "Find the answer"}))
+        .with_text("The researcher says 42");
+    let supervisor = delegating_agent(
+        provider,
+        Some(AgentTool::new("researcher", researcher.clone())),
+    )
+    .await;
+
+    let collector = DetailedEventCollector::new();
+    supervisor.add_hook(collector.clone());
+
+    let response = supervisor.run("What is the answer?").await.unwrap();
+    assert_eq!(response.text, "The researcher says 42");
+    assert_eq!(response.tool_calls.len(), 1);
+    assert!(response.tool_calls[0].success);
+    assert_eq!(response.tool_calls[0].output, "The answer is 42");
+    assert_eq!(sub_provider.call_count(), 1);
+
+    // Sub-agent events are forwarded, wrapped with the tool name
+    let forwarded: Vec<AgentEvent> = collector
+        .events()
+        .into_iter()
+        .filter_map(|event| match event {
+            AgentEvent::SubAgent { agent, event } => {
+                assert_eq!(agent, "researcher");
+                Some(*event)
+            }
+            _ => None,
+        })
+        .collect();
+    assert!(matches!(
+        forwarded.first(),
+        Some(AgentEvent::RunStarted { input, .. }) if input == "Find the answer"
+    ));
+    assert!(matches!(
+        forwarded.last(),
+        Some(AgentEvent::RunCompleted { output, .. }) if output == "The answer is 42"
+    ));
+
+    // The forwarding hook is removed once the delegated run finishes
+    // (the mock has no responses left, so this direct run fails)
+    let event_count = collector.events().len();
+    researcher.run("Direct call").await.unwrap_err();
+    assert_eq!(collector.events().len(), event_count);
+}
+
+#[tokio::test]
+async fn test_nested_delegation_forwards_events_and_bounds_depth() {
+    let leaf_provider = MockProvider::new().with_text("leaf result");
+    let leaf = Arc::new(delegating_agent(leaf_provider.clone(), None).await);
+
+    // The middle agent may only call the leaf when it is itself top-level
+    let middle_provider = MockProvider::new()
+        .with_tool_use("leaf", serde_json::json!({"task": "go deeper"}))
+        .with_text("middle result");
+    let middle = Arc::new(
+        delegating_agent(
+            middle_provider,
+            Some(AgentTool::new("leaf", leaf).with_max_depth(1)),
+        )
+        .await,
+    );
+
+    let provider = MockProvider::new()
+        .with_tool_use("middle", serde_json::json!({"task": "delegate"}))
+        .with_text("done");
+    let supervisor = delegating_agent(provider, Some(AgentTool::new("middle", middle))).await;
+
+    let collector = DetailedEventCollector::new();
+    supervisor.add_hook(collector.clone());
+
+    let response = supervisor.run("Start").await.unwrap();
+    assert_eq!(response.text, "done");
+    assert_eq!(response.tool_calls[0].output, "middle result");
+
+    // The leaf would run at depth 2, so the middle agent's call is refused
+    assert_eq!(leaf_provider.call_count(), 0);
+    let leaf_failure = collector
+        .events()
+        .into_iter()
+        .find_map(|event| match event {
+            AgentEvent::SubAgent { agent, event } if agent == "middle" => match *event {
+                AgentEvent::ToolFailed { name, error, .. } if name == "leaf" => Some(error),
+                _ => None,
+            },
+            _ => None,
+        });
+    let error = leaf_failure.expect("middle agent's leaf call should fail");
+    assert!(error.contains("maximum delegation depth"), "{}", error);
+}
+
+#[tokio::test]
+async fn test_nested_sub_agent_events_reach_supervisor() {
+    let leaf = Arc::new(delegating_agent(MockProvider::new().with_text("leaf result"), None).await);
+    let middle_provider = MockProvider::new()
+        .with_tool_use("leaf", serde_json::json!({"task": "go deeper"}))
+        .with_text("middle result");
+    let middle =
+        Arc::new(delegating_agent(middle_provider, Some(AgentTool::new("leaf", leaf))).await);
+
+    let provider = MockProvider::new()
+        .with_tool_use("middle", serde_json::json!({"task": "delegate"}))
+        .with_text("done");
+    let supervisor = delegating_agent(provider, Some(AgentTool::new("middle", middle))).await;
+
+    let collector = DetailedEventCollector::new();
+    supervisor.add_hook(collector.clone());
+    supervisor.run("Start").await.unwrap();
+
+    let nested_start = collector.events().into_iter().any(|event| match event {
+        AgentEvent::SubAgent { agent, event } if agent == "middle" => matches!(
+            *event,
+            AgentEvent::SubAgent { ref agent, ref event }
+                if agent == "leaf"
+                    && matches!(**event, AgentEvent::RunStarted { ref input, .. } if input == "go deeper")
+        ),
+        _ => false,
+    });
+    assert!(nested_start, "leaf events should reach the supervisor");
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_concurrent_delegations_forward_only_their_own_events() {
+    let sub_provider = MockProvider::new()
+        .with_text("first")
+        .with_text("second")
+        .with_delay(Duration::from_secs(1));
+    let researcher = Arc::new(delegating_agent(sub_provider, None).await);
+
+    let supervisor = |task: &str| {
+        let provider = MockProvider::new()
+            .with_tool_use("researcher", serde_json::json!({ "task": task }))
+            .with_text("done");
+        delegating_agent(
+            provider,
+            Some(AgentTool::new("researcher", researcher.clone())),
+        )
+    };
+    let first = supervisor("task one").await;
+    let second = supervisor("task two").await;
+    let first_events = DetailedEventCollector::new();
+    let second_events = DetailedEventCollector::new();
+    first.add_hook(first_events.clone());
+    second.add_hook(second_events.clone());
+
+    let (a, b) = tokio::join!(first.run("Start"), second.run("Start"));
+    a.unwrap();
+    b.unwrap();
+
+    let forwarded_starts = |collector: &DetailedEventCollector| -> Vec<String> {
+        collector
+            .events()
+            .into_iter()
+            .filter_map(|event| match event {
+                AgentEvent::SubAgent { event, .. } => match *event {
+                    AgentEvent::RunStarted { input, .. } => Some(input),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect()
+    };
+    assert_eq!(forwarded_starts(&first_events), vec!["task one"]);
+    assert_eq!(forwarded_starts(&second_events), vec!["task two"]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_cancelled_delegation_stops_forwarding() {
+    let sub_provider = MockProvider::new()
+        .with_text("too late")
+        .with_delay(Duration::from_secs(10));
+    let researcher = Arc::new(delegating_agent(sub_provider, None).await);
+
+    let provider = MockProvider::new()
+        .with_tool_use("researcher", serde_json::json!({"task": "Find the answer"}))
+        .with_text("unreachable");
+    let supervisor = delegating_agent(
+        provider,
+        Some(AgentTool::new("researcher", researcher.clone())),
+    )
+    .await;
+    let collector = DetailedEventCollector::new();
+    supervisor.add_hook(collector.clone());
+
+    let err = supervisor
+        .run_with_timeout("Start", Duration::from_secs(1))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AgentError::Timeout { .. }));
+
+    // The timed-out delegation left nothing behind on the sub-agent
+    let event_count = collector.events().len();
+    researcher.run("Direct call").await.unwrap();
+    assert_eq!(collector.events().len(), event_count);
+}
+
+// ===== Tool Use ID Tests =====
+
+/// Provider that returns tool uses without IDs, then a final answer
+struct MissingIdProvider {
+    received: Arc<Mutex<Vec<Message>>>,
+}
+
+#[async_trait::async_trait]
+impl ModelProvider for MissingIdProvider {
+    fn name(&self) -> &str {
+        "MissingIdProvider"
+    }
+
+    fn max_context_tokens(&self) -> usize {
+        200_000
+    }
+
+    fn max_output_tokens(&self) -> usize {
+        8_192
+    }
+
+    async fn generate(
+        &self,
+        messages: Vec<Message>,
+        _tools: Vec<ToolDefinition>,
+        _system_prompt: Option<String>,
+    ) -> Result<ModelResponse, ProviderError> {
+        if messages.len() > 1 {
+            *self.received.lock().unwrap() = messages;
+            return Ok(ModelResponse {
+                message: Message::assistant("Both are 4"),
+                stop_reason: StopReason::EndTurn,
+                stop_sequence: None,
+                usage: None,
+                service_tier: None,
+                model: None,
+            });
+        }
+
+        let tool_use = |expression: &str| {
+            ContentBlock::ToolUse(ToolUseBlock {
+                id: String::new(),
+                name: "calculate".to_string(),
+                input: serde_json::json!({ "expression": expression }),
+            })
+        };
+        Ok(ModelResponse {
+            message: Message {
+                role: Role::Assistant,
+                content: vec![tool_use("2+2"), tool_use("1+3")],
+            },
+            stop_reason: StopReason::ToolUse,
+            stop_sequence: None,
+            usage: None,
+            service_tier: None,
+            model: None,
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_sequential_id_generator_assigns_missing_tool_use_ids() {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let agent = Agent::builder()
+        .provider(MissingIdProvider {
+            received: received.clone(),
+        })
+        .add_trusted_tool(Calculator)
+        .with_id_generator(SequentialIdGenerator::new())
+        .build()
+        .await
+        .unwrap();
+
+    let collector = DetailedEventCollector::new();
+    agent.add_hook(collector.clone());
+    agent.run("What are 2+2 and 1+3?").await.unwrap();
+
+    let mut requested: Vec<String> = collector
+        .events()
+        .into_iter()
+        .filter_map(|event| match event {
+            AgentEvent::ToolRequested { tool_use_id, .. } => Some(tool_use_id),
+            _ => None,
+        })
+        .collect();
+    requested.sort();
+    assert_eq!(requested, vec!["tool_1", "tool_2"]);
+
+    // The stored assistant turn and the tool results use the same IDs
+    let received = received.lock().unwrap();
+    let assistant_ids: Vec<&str> = received[1]
+        .tool_uses()
+        .iter()
+        .map(|t| t.id.as_str())
+        .collect();
+    assert_eq!(assistant_ids, vec!["tool_1", "tool_2"]);
+
+    let mut result_ids: Vec<&str> = received[2]
+        .content
+        .iter()
+        .filter_map(|block| match block {
+            ContentBlock::ToolResult(result) => Some(result.tool_use_id.as_str()),
+            _ => None,
+        })
+        .collect();
+    result_ids.sort();
+    assert_eq!(result_ids, vec!["tool_1", "tool_2"]);
+}
+
+// ===== Aborting a single tool =====
+
+/// Tool that never finishes on its own
+struct HangingTool;
+
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+struct HangingInput {}
+
+impl mixtape_core::Tool for HangingTool {
+    type Input = HangingInput;
+
+    fn name(&self) -> &str {
+        "hang"
+    }
+
+    fn description(&self) -> &str {
+        "Waits forever"
+    }
+
+    async fn execute(&self, _input: Self::Input) -> Result<ToolResult, mixtape_core::ToolError> {
+        std::future::pending().await
+    }
+}
+
+#[tokio::test]
+async fn test_abort_tool_cancels_one_of_two_concurrent_tools() {
+    let provider = MockProvider::new()
+        .with_tool_uses(vec![
+            ToolUseBlock {
+                id: "hang_1".to_string(),
+                name: "hang".to_string(),
+                input: serde_json::json!({}),
+            },
+            ToolUseBlock {
+                id: "calc_1".to_string(),
+                name: "calculate".to_string(),
+                input: serde_json::json!({"expression": "2+2"}),
+            },
+        ])
+        .with_text("Calculated 4; the other tool was aborted");
+
+    let agent = Arc::new(
+        Agent::builder()
+            .provider(provider)
+            .add_trusted_tool(HangingTool)
+            .add_trusted_tool(Calculator)
+            .build()
+            .await
+            .unwrap(),
+    );
+    let collector = DetailedEventCollector::new();
+    agent.add_hook(collector.clone());
+
+    assert!(!agent.abort_tool("hang_1"));
+
+    let aborter = {
+        let agent = agent.clone();
+        tokio::spawn(async move {
+            while !agent.running_tool_ids().contains(&"hang_1".to_string()) {
+                tokio::task::yield_now().await;
+            }
+            assert!(agent.abort_tool("hang_1"));
+        })
+    };
+
+    let response = tokio::time::timeout(std::time::Duration::from_secs(5), agent.run("go"))
+        .await
+        .expect("run should not hang after abort")
+        .unwrap();
+    aborter.await.unwrap();
+
+    assert_eq!(response.text, "Calculated 4; the other tool was aborted");
+    assert!(agent.running_tool_ids().is_empty());
+
+    let hang = response
+        .tool_calls
+        .iter()
+        .find(|c| c.name == "hang")
+        .unwrap();
+    assert!(!hang.success);
+    assert!(hang.output.contains("aborted"));
+
+    let calc = response
+        .tool_calls
+        .iter()
+        .find(|c| c.name == "calculate")
+        .unwrap();
+    assert!(calc.success);
+    assert_eq!(calc.output, "4");
+
+    let events = collector.events();
+    assert!(events.iter().any(|e| matches!(
+        e,
+        AgentEvent::ToolFailed { tool_use_id, error, .. }
+            if tool_use_id == "hang_1" && error.contains("aborted")
+    )));
+}
+
+// ===== Document Input Tests =====
+
+/// A minimal single-page PDF
+const TINY_PDF: &[u8] = b"%PDF-1.4\n1 0 obj<</Type/Catalog/Pages 2 0 R>>endobj\n\
+2 0 obj<</Type/Pages/Kids[3 0 R]/Count 1>>endobj\n\
+3 0 obj<</Type/Page/Parent 2 0 R/MediaBox[0 0 72 72]>>endobj\n\
+trailer<</Root 1 0 R>>\n%%EOF\n";
+
+#[tokio::test]
+async fn test_run_with_document_sends_document_before_question() {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let provider = RecordingProvider {
+        inner: MockProvider::new().with_text("One blank page"),
+        received: received.clone(),
+    };
+    let agent = Agent::builder().provider(provider).build().await.unwrap();
+
+    let response = agent
+        .run_with_document(TINY_PDF, "What is in this document?")
+        .await
+        .unwrap();
+    assert_eq!(response, "One blank page");
+
+    let received = received.lock().unwrap();
+    let message = &received[0].0[0];
+    assert_eq!(message.role, Role::User);
+    match &message.content[..] {
+        [ContentBlock::Document(doc), ContentBlock::Text(question)] => {
+            assert_eq!(doc.format, DocumentFormat::Pdf);
+            assert!(matches!(&doc.source, DocumentSource::Bytes(data) if data == TINY_PDF));
+            assert_eq!(question, "What is in this document?");
+        }
+        other => panic!("Expected document then question, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_run_with_document_unsupported_provider() {
+    let provider = MockProvider::new().with_text("unused");
+    let agent = Agent::builder().provider(provider).build().await.unwrap();
+
+    let err = agent
+        .run_with_document(TINY_PDF, "Summarize")
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AgentError::InvalidDocument(_)));
+    assert!(err.to_string().contains("does not support Pdf documents"));
+    assert_eq!(agent.get_context_usage().total_messages, 0);
+}
+
+#[tokio::test]
+async fn test_run_with_document_rejects_invalid_pdf() {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let provider = RecordingProvider {
+        inner: MockProvider::new().with_text("unused"),
+        received: received.clone(),
+    };
+    let agent = Agent::builder().provider(provider).build().await.unwrap();
+
+    let err = agent
+        .run_with_document(DocumentInput::pdf(b"plain text".to_vec()), "Summarize")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("not a PDF"));
+    assert!(received.lock().unwrap().is_empty());
+}
+
+// ===== Conversation History Tests =====
+
+#[tokio::test]
+async fn test_clear_history() {
+    let provider = MockProvider::new()
+        .with_text("First answer")
+        .with_text("Second answer");
+    let agent = Agent::builder().provider(provider).build().await.unwrap();
+
+    agent.run("First question").await.unwrap();
+    assert_eq!(agent.message_count(), 2);
+    assert_eq!(agent.messages()[0].text(), "First question");
+
+    agent.clear_history();
+    assert_eq!(agent.message_count(), 0);
+    assert!(agent.messages().is_empty());
+
+    agent.run("Second question").await.unwrap();
+    assert_eq!(agent.message_count(), 2);
+    assert_eq!(agent.messages()[0].text(), "Second question");
+}
+
+#[tokio::test]
+async fn test_truncate_to_keeps_tool_use_with_result() {
+    let provider = MockProvider::new()
+        .with_tool_use("calculate", serde_json::json!({"expression": "2+2"}))
+        .with_text("The answer is 4");
+    let agent = Agent::builder()
+        .provider(provider)
+        .add_tool(Calculator)
+        .with_grant_store(AutoApproveGrantStore)
+        .build()
+        .await
+        .unwrap();
+
+    agent.run("What is 2+2?").await.unwrap();
+    // user, assistant tool use, user tool result, assistant answer
+    assert_eq!(agent.message_count(), 4);
+    assert_eq!(agent.truncate_to(10), 4);
+
+    // Cutting after the tool result keeps the pair intact
+    assert_eq!(agent.truncate_to(3), 3);
+    let messages = agent.messages();
+    assert!(matches!(messages[1].content[0], ContentBlock::ToolUse(_)));
+    assert!(matches!(
+        messages[2].content[0],
+        ContentBlock::ToolResult(_)
+    ));
+
+    // Cutting between the tool use and its result drops the tool use too
+    assert_eq!(agent.truncate_to(2), 1);
+    let messages = agent.messages();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].text(), "What is 2+2?");
+}
+
+// ===== Content Filter Tests =====
+
+/// Blocks user input containing a banned phrase
+struct BlockBannedInput(&'static str);
+
+#[async_trait::async_trait]
+impl ContentFilter for BlockBannedInput {
+    async fn check_input(&self, text: &str) -> FilterDecision {
+        if text.contains(self.0) {
+            FilterDecision::Block(format!("input contains '{}'", self.0))
+        } else {
+            FilterDecision::Allow
+        }
+    }
+}
+
+/// Redacts a banned phrase from model output
+struct RedactBannedOutput(&'static str);
+
+#[async_trait::async_trait]
+impl ContentFilter for RedactBannedOutput {
+    async fn check_output(&self, text: &str) -> FilterDecision {
+        if text.contains(self.0) {
+            FilterDecision::Redact(text.replace(self.0, "[redacted]"))
+        } else {
+            FilterDecision::Allow
+        }
+    }
+}
+
+/// Blocks model output containing a banned phrase
+struct BlockBannedOutput(&'static str);
+
+#[async_trait::async_trait]
+impl ContentFilter for BlockBannedOutput {
+    async fn check_output(&self, text: &str) -> FilterDecision {
+        if text.contains(self.0) {
+            FilterDecision::Block(format!("output contains '{}'", self.0))
+        } else {
+            FilterDecision::Allow
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_content_filter_blocks_input() {
+    let provider = MockProvider::new().with_text("unused");
+    let agent = Agent::builder()
+        .provider(provider.clone())
+        .with_content_filter(BlockBannedInput("launch codes"))
+        .build()
+        .await
+        .unwrap();
+    let collector = DetailedEventCollector::new();
+    agent.add_hook(collector.clone());
+
+    let err = agent.run("Tell me the launch codes").await.unwrap_err();
+    assert!(matches!(err, AgentError::ContentFiltered));
+    assert_eq!(provider.call_count(), 0);
+    assert_eq!(agent.message_count(), 0);
+
+    let events = collector.events();
+    assert!(events.iter().any(|e| matches!(
+        e,
+        AgentEvent::ContentFiltered { stage: FilterStage::Input, reason }
+            if reason == "input contains 'launch codes'"
+    )));
+    assert!(matches!(events.last(), Some(AgentEvent::RunFailed { .. })));
+
+    // Clean input still goes through
+    let response = agent.run("Tell me a joke").await.unwrap();
+    assert_eq!(response, "unused");
+}
+
+#[tokio::test]
+async fn test_content_filter_redacts_output() {
+    let provider = MockProvider::new().with_text("The password is hunter2.");
+    let agent = Agent::builder()
+        .provider(provider)
+        .with_content_filter(RedactBannedOutput("hunter2"))
+        .build()
+        .await
+        .unwrap();
+
+    let response = agent.run("What is the password?").await.unwrap();
+    assert_eq!(response, "The password is [redacted].");
+
+    // The redacted text is what stays in history
+    let messages = agent.messages();
+    assert_eq!(messages[1].text(), "The password is [redacted].");
+}
+
+#[tokio::test]
+async fn test_content_filter_redacts_every_turn_before_events() {
+    let provider = MockProvider::new()
+        .with_text_and_tool_use(
+            "Checking hunter2.",
+            "calculate",
+            serde_json::json!({"expression": "2+2"}),
+        )
+        .with_text("hunter2 is 4.");
+    let agent = Agent::builder()
+        .provider(provider)
+        .add_tool(Calculator)
+        .with_grant_store(AutoApproveGrantStore)
+        .with_content_filter(RedactBannedOutput("hunter2"))
+        .build()
+        .await
+        .unwrap();
+    let collector = DetailedEventCollector::new();
+    agent.add_hook(collector.clone());
+
+    let response = agent.run("What is 2+2?").await.unwrap();
+    assert_eq!(response, "[redacted] is 4.");
+    assert_eq!(response.messages[0].text(), "Checking [redacted].");
+
+    // Hooks only ever see the redacted text
+    let mut streamed = Vec::new();
+    let mut completed = Vec::new();
+    for event in collector.events() {
+        match event {
+            AgentEvent::ModelCallStreaming { delta, .. } => streamed.push(delta),
+            AgentEvent::ModelCallCompleted {
+                response_content, ..
+            } => completed.push(response_content),
+            _ => {}
+        }
+    }
+    assert_eq!(streamed, ["Checking [redacted].", "[redacted] is 4."]);
+    assert_eq!(completed, streamed);
+}
+
+#[tokio::test]
+async fn test_content_filter_blocked_output_removed_from_history() {
+    let provider = MockProvider::new()
+        .with_text("First answer")
+        .with_text("Second answer");
+    let agent = Agent::builder()
+        .provider(provider)
+        .with_content_filter(BlockBannedOutput("Second"))
+        .build()
+        .await
+        .unwrap();
+    let collector = EventCollector::new();
+    agent.add_hook(collector.clone());
+
+    agent.run("First question").await.unwrap();
+    assert_eq!(agent.message_count(), 2);
+
+    let err = agent.run("Second question").await.unwrap_err();
+    assert!(matches!(err, AgentError::ContentFiltered));
+    assert_eq!(agent.message_count(), 2);
+    assert_eq!(agent.messages()[1].text(), "First answer");
+
+    let events = collector.events();
+    assert!(events.contains(&"content_filtered".to_string()));
+    assert_eq!(events.last().map(String::as_str), Some("run_failed"));
+}
+
+#[tokio::test]
+async fn test_refusal_fails_run_by_default() {
+    let provider = MockProvider::new().with_refusal("I can't help with that.");
+    let agent = Agent::builder().provider(provider).build().await.unwrap();
+    let collector = DetailedEventCollector::new();
+    agent.add_hook(collector.clone());
+
+    let err = agent.run("Do something harmful").await.unwrap_err();
+    assert!(matches!(&err, AgentError::Refused(reason) if reason == "I can't help with that."));
+
+    let events = collector.events();
+    assert!(events.iter().any(|e| matches!(
+        e,
+        AgentEvent::Refused { reason } if reason == "I can't help with that."
+    )));
+    assert!(matches!(events.last(), Some(AgentEvent::RunFailed { .. })));
+}
+
+#[tokio::test]
+async fn test_refusal_policy_pass_returns_flagged_response() {
+    let provider = MockProvider::new()
+        .with_refusal("I can't help with that.")
+        .with_text("Paris");
+    let agent = Agent::builder()
+        .provider(provider)
+        .with_refusal_policy(RefusalPolicy::Pass)
+        .build()
+        .await
+        .unwrap();
+    let collector = EventCollector::new();
+    agent.add_hook(collector.clone());
+
+    let response = agent.run("Do something harmful").await.unwrap();
+    assert!(response.is_refusal());
+    assert_eq!(response, "I can't help with that.");
+    assert!(collector.events().contains(&"refused".to_string()));
+
+    let response = agent.run("What is the capital of France?").await.unwrap();
+    assert!(!response.is_refusal());
+}
+
+#[tokio::test]
+async fn test_response_exposes_messages_from_run() {
+    let provider = MockProvider::new()
+        .with_text("Hi")
+        .with_tool_use("calculate", serde_json::json!({"expression": "2+2"}))
+        .with_content(vec![
+            ContentBlock::Thinking {
+                thinking: "The tool said 4".to_string(),
+                signature: "sig".to_string(),
+            },
+            ContentBlock::Text("The answer is 4".to_string()),
+        ]);
+    let agent = Agent::builder()
+        .provider(provider)
+        .add_tool(Calculator)
+        .with_grant_store(AutoApproveGrantStore)
+        .build()
+        .await
+        .unwrap();
+
+    agent.run("Hello").await.unwrap();
+    let response = agent.run("What is 2+2?").await.unwrap();
+
+    let message = response.message();
+    assert_eq!(message.role, Role::Assistant);
+    assert_eq!(message.content.len(), 2);
+    assert!(matches!(
+        &message.content[0],
+        ContentBlock::Thinking { thinking, .. } if thinking == "The tool said 4"
+    ));
+    assert!(matches!(&message.content[1], ContentBlock::Text(t) if t == "The answer is 4"));
+
+    // Tool use, tool result, final answer; no user prompt or earlier runs
+    let messages = response.all_messages();
+    assert_eq!(messages.len(), 3);
+    assert!(matches!(
+        &messages[0].content[0],
+        ContentBlock::ToolUse(tool_use) if tool_use.name == "calculate"
+    ));
+    assert!(matches!(
+        &messages[1].content[0],
+        ContentBlock::ToolResult(_)
+    ));
+    assert_eq!(messages[2].text(), "The answer is 4");
+}
+
+/// Async hook that sends every event over a channel
+struct ChannelHook {
+    tx: tokio::sync::mpsc::Sender<AgentEvent>,
+    log: Arc<Mutex<Vec<&'static str>>>,
+}
+
+#[async_trait::async_trait]
+impl AsyncAgentHook for ChannelHook {
+    async fn on_event(&self, event: &AgentEvent) {
+        self.tx.send(event.clone()).await.unwrap();
+        self.log.lock().unwrap().push("async");
+    }
+}
+
+#[tokio::test]
+async fn test_async_hook_receives_events_in_order() {
+    let provider = MockProvider::new()
+        .with_tool_use("calculate", serde_json::json!({"expression": "2+2"}))
+        .with_text("Done");
+    let agent = Agent::builder()
+        .provider(provider)
+        .add_tool(Calculator)
+        .with_grant_store(AutoApproveGrantStore)
+        .build()
+        .await
+        .unwrap();
+
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let sync_log = log.clone();
+    let collector = DetailedEventCollector::new();
+    agent.add_hook(collector.clone());
+    agent.add_hook(move |_: &AgentEvent| sync_log.lock().unwrap().push("sync"));
+
+    // A capacity of one means each send waits for the reader
+    let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+    let hook_id = agent.add_async_hook(ChannelHook {
+        tx,
+        log: log.clone(),
+    });
+    let reader = tokio::spawn(async move {
+        let mut received = Vec::new();
+        while let Some(event) = rx.recv().await {
+            received.push(event);
+        }
+        received
+    });
+
+    agent.run("What is 2+2?").await.unwrap();
+    assert!(agent.remove_hook(hook_id));
+    drop(agent);
+
+    let received = reader.await.unwrap();
+    let expected = collector.events();
+    assert_eq!(received.len(), expected.len());
+    for (got, want) in received.iter().zip(&expected) {
+        assert_eq!(
+            std::mem::discriminant(got),
+            std::mem::discriminant(want),
+            "async hook saw {:?}, sync hook saw {:?}",
+            got,
+            want
+        );
+    }
+    assert!(matches!(received[0], AgentEvent::RunStarted { .. }));
+    assert!(matches!(
+        received.last(),
+        Some(AgentEvent::RunCompleted { .. })
+    ));
+
+    // Each event reaches sync hooks before async hooks
+    let log = log.lock().unwrap();
+    assert_eq!(log.len(), expected.len() * 2);
+    assert!(log.chunks(2).all(|pair| pair == ["sync", "async"]));
+}
+
+#[tokio::test]
+async fn test_dangling_tool_use_repaired_before_generate() {
+    let mut manager = SlidingWindowConversationManager::new();
+    manager.hydrate(vec![
+        Message::user("Calculate"),
+        Message::assistant_with_tool_use(
+            "Calculating",
+            vec![ToolUseBlock {
+                id: "tool_1".to_string(),
+                name: "calculate".to_string(),
+                input: serde_json::json!({"expression": "2+2"}),
+            }],
+        ),
+    ]);
+    let provider = MockProvider::new().with_text("Done");
+    let agent = Agent::builder()
+        .provider(provider.clone())
+        .with_conversation_manager(manager)
+        .build()
+        .await
+        .unwrap();
+
+    agent.run("Continue").await.unwrap();
+
+    // The interrupted result leads the new user message
+    let received = provider.received_messages();
+    let messages = &received[0];
+    assert_eq!(messages.len(), 3);
+    assert!(matches!(
+        &messages[2].content[0],
+        ContentBlock::ToolResult(result) if result.tool_use_id == "tool_1"
+    ));
+    assert_eq!(messages[2].text(), "Continue");
+}
+
+// ===== Dry run =====
+
+/// Tool that records every write it is asked to make
+struct WriteFileTool(Arc<Mutex<Vec<String>>>);
+
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+struct WriteFileInput {
+    path: String,
+}
+
+impl mixtape_core::Tool for WriteFileTool {
+    type Input = WriteFileInput;
+
+    fn name(&self) -> &str {
+        "write_file"
+    }
+
+    fn description(&self) -> &str {
+        "Writes a file"
+    }
+
+    async fn execute(&self, input: Self::Input) -> Result<ToolResult, mixtape_core::ToolError> {
+        self.0.lock().unwrap().push(input.path);
+        Ok(ToolResult::text("written"))
+    }
+}
+
+#[tokio::test]
+async fn test_dry_run_reports_tool_without_executing() {
+    let writes = Arc::new(Mutex::new(Vec::new()));
+    let provider = MockProvider::new()
+        .with_tool_use(
+            "write_file",
+            serde_json::json!({"path": "out.txt", "content": "data"}),
+        )
+        .with_text("Wrote the file");
+    let collector = DetailedEventCollector::new();
+
+    // No grant store: dry run must not ask for approval either
+    let agent = Agent::builder()
+        .provider(provider.clone())
+        .add_tool(WriteFileTool(writes.clone()))
+        .with_dry_run(true)
+        .build()
+        .await
+        .unwrap();
+    agent.add_hook(collector.clone());
+
+    let response = agent.run("Write out.txt").await.unwrap();
+    assert_eq!(response, "Wrote the file");
+    assert!(writes.lock().unwrap().is_empty());
+
+    assert!(response.tool_calls.is_empty());
+    assert_eq!(response.intended_tool_calls.len(), 1);
+    let intended = &response.intended_tool_calls[0];
+    assert_eq!(intended.name, "write_file");
+    assert_eq!(
+        intended.input,
+        serde_json::json!({"path": "out.txt", "content": "data"})
+    );
+
+    let events = collector.events();
+    assert!(events.iter().any(|e| matches!(
+        e,
+        AgentEvent::ToolSkippedDryRun { tool_use_id, name, .. }
+            if *tool_use_id == intended.tool_use_id && name == "write_file"
+    )));
+    assert!(!events
+        .iter()
+        .any(|e| matches!(e, AgentEvent::ToolExecuting { .. })));
+
+    // The model got a success result so the loop could continue
+    let received = provider.received_messages();
+    assert!(matches!(
+        &received[1].last().unwrap().content[0],
+        ContentBlock::ToolResult(result) if result.status == mixtape_core::ToolResultStatus::Success
+    ));
+}
+
+// ===== Self introspection =====
+
+#[tokio::test]
+async fn test_list_capabilities_returns_other_tools() {
+    let provider = MockProvider::new()
+        .with_tool_use(mixtape_core::LIST_CAPABILITIES_TOOL, serde_json::json!({}))
+        .with_text("I can calculate and fetch data");
+
+    // No grant store: the built-in tool runs without approval
+    let agent = Agent::builder()
+        .provider(provider.clone())
+        .add_tool(Calculator)
+        .add_tool(DataTool)
+        .with_self_introspection()
+        .build()
+        .await
+        .unwrap();
+
+    agent.run("What can you do?").await.unwrap();
+
+    let received = provider.received_messages();
+    let result = match &received[1].last().unwrap().content[0] {
+        ContentBlock::ToolResult(result) => result,
+        other => panic!("expected tool result, got {:?}", other),
+    };
+    assert_eq!(result.status, mixtape_core::ToolResultStatus::Success);
+    let ToolResult::Json(capabilities) = &result.content else {
+        panic!("expected JSON result, got {:?}", result.content);
+    };
+    assert_eq!(capabilities["model"], "MockProvider");
+    let names: Vec<_> = capabilities["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|tool| tool["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["calculate", "get_data"]);
+}
+
+#[tokio::test]
+async fn test_list_capabilities_not_offered_by_default() {
+    let provider = MockProvider::new()
+        .with_tool_use(mixtape_core::LIST_CAPABILITIES_TOOL, serde_json::json!({}))
+        .with_text("Done");
+    let agent = Agent::builder()
+        .provider(provider.clone())
+        .build()
+        .await
+        .unwrap();
+
+    agent.run("What can you do?").await.unwrap();
+
+    let received = provider.received_messages();
+    assert!(matches!(
+        &received[1].last().unwrap().content[0],
+        ContentBlock::ToolResult(result) if result.status == mixtape_core::ToolResultStatus::Error
+    ));
+}
+
+#[tokio::test]
+async fn test_emit_progress_becomes_event_with_empty_result() {
+    let provider = MockProvider::new()
+        .with_tool_use(
+            mixtape_core::EMIT_PROGRESS_TOOL,
+            serde_json::json!({"message": "Step 3 of 5 complete", "fraction": 0.6}),
+        )
+        .with_text("All done");
+    let collector = DetailedEventCollector::new();
+    let agent = Agent::builder()
+        .provider(provider.clone())
+        .with_progress_tool()
+        .build()
+        .await
+        .unwrap();
+    agent.add_hook(collector.clone());
+
+    let response = agent.run("Process the files").await.unwrap();
+    assert_eq!(response.text, "All done");
+
+    let events = collector.events();
+    assert!(events.iter().any(|e| matches!(
+        e,
+        AgentEvent::Progress { message, fraction: Some(f) }
+            if message == "Step 3 of 5 complete" && (*f - 0.6).abs() < f64::EPSILON
+    )));
+    assert!(events.iter().any(|e| matches!(
+        e,
+        AgentEvent::ToolCompleted { output: ToolResult::Text(text), .. } if text.is_empty()
+    )));
+
+    let received = provider.received_messages();
+    assert!(matches!(
+        &received[1].last().unwrap().content[0],
+        ContentBlock::ToolResult(result)
+            if result.status == ToolResultStatus::Success
+                && matches!(&result.content, ToolResult::Text(text) if text.is_empty())
+    ));
+}
+
+#[tokio::test]
+async fn test_emit_progress_not_offered_by_default() {
+    let provider = MockProvider::new()
+        .with_tool_use(
+            mixtape_core::EMIT_PROGRESS_TOOL,
+            serde_json::json!({"message": "Working"}),
+        )
+        .with_text("Done");
+    let collector = DetailedEventCollector::new();
+    let agent = Agent::builder()
+        .provider(provider.clone())
+        .build()
+        .await
+        .unwrap();
+    agent.add_hook(collector.clone());
+
+    agent.run("Go").await.unwrap();
+
+    assert!(!collector
+        .events()
+        .iter()
+        .any(|e| matches!(e, AgentEvent::Progress { .. })));
+}
+
+// ===== Concurrent runs =====
+
+/// Provider that echoes the conversation back, taking longer for longer
+/// inputs so that concurrent runs finish out of order
+struct EchoProvider;
+
+#[async_trait::async_trait]
+impl ModelProvider for EchoProvider {
+    fn name(&self) -> &str {
+        "EchoProvider"
+    }
+
+    fn max_context_tokens(&self) -> usize {
+        200_000
+    }
+
+    fn max_output_tokens(&self) -> usize {
+        8_192
+    }
+
+    async fn generate(
+        &self,
+        messages: Vec<Message>,
+        _tools: Vec<ToolDefinition>,
+        _system_prompt: Option<String>,
+    ) -> Result<ModelResponse, ProviderError> {
+        let texts: Vec<_> = messages.iter().map(|m| m.text()).collect();
+        let delay = 10 * texts.iter().map(String::len).sum::<usize>() as u64;
+        tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+        Ok(ModelResponse {
+            message: Message::assistant(texts.join(" | ")),
+            stop_reason: StopReason::EndTurn,
+            stop_sequence: None,
+            usage: None,
+            service_tier: None,
+            model: None,
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_run_many_returns_results_in_input_order() {
+    let agent = Agent::builder()
+        .provider(EchoProvider)
+        .build()
+        .await
+        .unwrap();
+
+    let results = agent
+        .run_many(vec!["first".into(), "two".into(), "3".into()], 3)
+        .await;
+
+    // Each run saw only its own input
+    let texts: Vec<_> = results.into_iter().map(|r| r.unwrap().text).collect();
+    assert_eq!(texts, vec!["first", "two", "3"]);
+
+    // The agent's own conversation is untouched
+    assert_eq!(agent.message_count(), 0);
+}
+
+// ===== Empty responses =====
+
+#[tokio::test]
+async fn test_empty_response_errors_by_default() {
+    let provider = MockProvider::new().with_content(vec![]).with_text("Late");
+    let agent = Agent::builder()
+        .provider(provider.clone())
+        .build()
+        .await
+        .unwrap();
+
+    let result = agent.run("Hello").await;
+    assert!(matches!(result, Err(AgentError::EmptyResponse)));
+    assert_eq!(provider.call_count(), 1);
+    // The empty turn never reaches the conversation
+    assert_eq!(agent.message_count(), 1);
+}
+
+#[tokio::test]
+async fn test_empty_response_retried_once() {
+    let provider = MockProvider::new().with_content(vec![]).with_text("Hi");
+    let agent = Agent::builder()
+        .provider(provider.clone())
+        .with_empty_response_policy(EmptyResponsePolicy::RetryOnce)
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(agent.run("Hello").await.unwrap().text, "Hi");
+    assert_eq!(provider.call_count(), 2);
+
+    let provider = MockProvider::new()
+        .with_content(vec![])
+        .with_content(vec![])
+        .with_text("Too late");
+    let agent = Agent::builder()
+        .provider(provider.clone())
+        .with_empty_response_policy(EmptyResponsePolicy::RetryOnce)
+        .build()
+        .await
+        .unwrap();
+    let result = agent.run("Hello").await;
+    assert!(matches!(result, Err(AgentError::EmptyResponse)));
+    assert_eq!(provider.call_count(), 2);
+}
+
+#[tokio::test]
+async fn test_empty_response_placeholder() {
+    let provider = MockProvider::new().with_content(vec![]);
+    let agent = Agent::builder()
+        .provider(provider)
+        .with_empty_response_policy(EmptyResponsePolicy::Placeholder)
+        .build()
+        .await
+        .unwrap();
+
+    let response = agent.run("Hello").await.unwrap();
+    assert_eq!(response.text, EMPTY_RESPONSE_PLACEHOLDER);
+    assert_eq!(agent.messages()[1].text(), EMPTY_RESPONSE_PLACEHOLDER);
+}
+
+// ===== Grant management =====
+
+#[tokio::test]
+async fn test_revoked_grant_requires_authorization_again() {
+    let provider = MockProvider::new()
+        .with_tool_use("calculate", serde_json::json!({"expression": "2+2"}))
+        .with_text("The answer is 4")
+        .with_tool_use("calculate", serde_json::json!({"expression": "2+2"}))
+        .with_text("I wasn't allowed to calculate");
+    let collector = DetailedEventCollector::new();
+
+    let agent = Agent::builder()
+        .provider(provider)
+        .add_tool(Calculator)
+        .with_grant_store(mixtape_core::MemoryGrantStore::new())
+        .build()
+        .await
+        .unwrap();
+    agent.add_hook(collector.clone());
+
+    agent
+        .authorizer()
+        .read()
+        .await
+        .grant_tool("calculate")
+        .await
+        .unwrap();
+    let grants = agent.list_grants().await.unwrap();
+    assert_eq!(grants.len(), 1);
+    assert_eq!(grants[0].tool, "calculate");
+    assert!(grants[0].is_tool_wide());
+
+    agent.run("What is 2+2?").await.unwrap();
+    assert!(collector
+        .events()
+        .iter()
+        .any(|e| matches!(e, AgentEvent::ToolCompleted { .. })));
+
+    assert!(agent.revoke_grant("calculate", None).await.unwrap());
+    assert!(!agent.revoke_grant("calculate", None).await.unwrap());
+    assert!(agent.list_grants().await.unwrap().is_empty());
+
+    let seen = collector.events().len();
+    agent.run("What is 2+2 again?").await.unwrap();
+    let events = collector.events().split_off(seen);
+    assert!(events.iter().any(|e| matches!(
+        e,
+        AgentEvent::PermissionDenied { tool_name, .. } if tool_name == "calculate"
+    )));
+    assert!(!events
+        .iter()
+        .any(|e| matches!(e, AgentEvent::ToolCompleted { .. })));
+}
+
+#[tokio::test]
+async fn test_clear_grants_removes_all() {
+    let agent = Agent::builder()
+        .provider(MockProvider::new())
+        .with_grant_store(mixtape_core::MemoryGrantStore::new())
+        .build()
+        .await
+        .unwrap();
+
+    {
+        let auth = agent.authorizer().read().await;
+        auth.grant_tool("calculate").await.unwrap();
+        auth.grant_params("get_data", &serde_json::json!({"key": "a"}))
+            .await
+            .unwrap();
+    }
+    assert_eq!(agent.list_grants().await.unwrap().len(), 2);
+
+    agent.clear_grants().await.unwrap();
+    assert!(agent.list_grants().await.unwrap().is_empty());
+}
+
+// ===== Transcript replay =====
+
+#[tokio::test]
+async fn test_replay_reconstructs_conversation_from_events() {
+    let provider = MockProvider::new()
+        .with_tool_use("calculate", serde_json::json!({"expression": "2+2"}))
+        .with_text("The answer is 4")
+        .with_tool_use("get_data", serde_json::json!({"key": "a"}))
+        .with_text("Here is the data");
+    let collector = DetailedEventCollector::new();
+
+    let agent = Agent::builder()
+        .provider(provider)
+        .add_tool(Calculator)
+        .add_tool(DataTool)
+        .with_grant_store(AutoApproveGrantStore)
+        .build()
+        .await
+        .unwrap();
+    agent.add_hook(collector.clone());
+
+    agent.run("What is 2+2?").await.unwrap();
+    agent.run("Fetch the data").await.unwrap();
+
+    let replayed = mixtape_core::conversation::replay(&collector.events());
+    // Message has no PartialEq; its Debug output covers every field
+    assert_eq!(format!("{:?}", replayed), format!("{:?}", agent.messages()));
+}
+
+// ===== Tool result size limit =====
+
+/// Returns a log of `self.0` bytes
+struct LogTool(usize);
+
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+struct LogInput {}
+
+impl mixtape_core::Tool for LogTool {
+    type Input = LogInput;
+
+    fn name(&self) -> &str {
+        "read_log"
+    }
+
+    fn description(&self) -> &str {
+        "Reads a long log"
+    }
+
+    async fn execute(&self, _input: Self::Input) -> Result<ToolResult, mixtape_core::ToolError> {
+        Ok(ToolResult::text("x".repeat(self.0)))
+    }
+}
+
+#[tokio::test]
+async fn test_tool_result_truncated_for_model_but_full_in_event() {
+    let provider = MockProvider::new()
+        .with_tool_use("read_log", serde_json::json!({}))
+        .with_text("The log is all x");
+    let collector = DetailedEventCollector::new();
+
+    let agent = Agent::builder()
+        .provider(provider.clone())
+        .add_tool(LogTool(1000))
+        .with_grant_store(AutoApproveGrantStore)
+        .with_max_tool_result_bytes(100)
+        .build()
+        .await
+        .unwrap();
+    agent.add_hook(collector.clone());
+
+    agent.run("Read the log").await.unwrap();
+
+    let (output, model_output) = collector
+        .events()
+        .into_iter()
+        .find_map(|e| match e {
+            AgentEvent::ToolCompleted {
+                output,
+                model_output,
+                ..
+            } => Some((output, model_output)),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(output.as_text().len(), 1000);
+    let model_output = model_output.unwrap().as_text();
+    assert!(model_output.starts_with(&"x".repeat(100)));
+    assert!(model_output.ends_with("[truncated: showing 100 of 1000 bytes]"));
+
+    // The conversation and the model hold the truncated copy
+    let ContentBlock::ToolResult(stored) = &agent.messages()[2].content[0] else {
+        panic!("expected a tool result");
+    };
+    assert_eq!(stored.content.as_text(), model_output);
+    let received = provider.received_messages();
+    assert!(matches!(
+        &received[1].last().unwrap().content[0],
+        ContentBlock::ToolResult(result) if result.content.as_text() == model_output
+    ));
+}
+
+#[tokio::test]
+async fn test_oversized_tool_result_cut_to_provider_estimate() {
+    let provider = MockProvider::new()
+        .with_tool_use("read_log", serde_json::json!({}))
+        .with_text("The log is all x");
+    let limit = provider.estimated_max_tool_result_bytes().unwrap();
+    assert_eq!(limit, 400_000);
+
+    // A larger configured budget does not lift the provider's estimate
+    let agent = Agent::builder()
+        .provider(provider.clone())
+        .add_tool(LogTool(1_000_000))
+        .with_grant_store(AutoApproveGrantStore)
+        .with_max_tool_result_bytes(2_000_000)
+        .build()
+        .await
+        .unwrap();
+
+    agent.run("Read the log").await.unwrap();
+
+    let received = provider.received_messages();
+    let ContentBlock::ToolResult(sent) = &received[1].last().unwrap().content[0] else {
+        panic!("expected a tool result");
+    };
+    let sent = sent.content.as_text();
+    assert!(sent.starts_with(&"x".repeat(limit)));
+    assert!(sent.ends_with("\n[truncated: showing 400000 of 1000000 bytes]"));
+}
+
+// ===== Tool JSON recovery =====
+
+#[tokio::test]
+async fn test_tool_json_recovery_after_repeated_malformed_input() {
+    let provider = MockProvider::new()
+        .with_tool_use("calculate", serde_json::json!("2+2"))
+        .with_tool_use("calculate", serde_json::json!("2+2"))
+        .with_tool_use("calculate", serde_json::json!({"expression": "2+2"}))
+        .with_text("The answer is 4");
+    let collector = DetailedEventCollector::new();
+
+    let agent = Agent::builder()
+        .provider(provider.clone())
+        .add_tool(Calculator)
+        .with_grant_store(AutoApproveGrantStore)
+        .with_tool_json_recovery(2)
+        .build()
+        .await
+        .unwrap();
+    agent.add_hook(collector.clone());
+
+    let response = agent.run("What is 2+2?").await.unwrap();
+    assert_eq!(response, "The answer is 4");
+    let outcomes: Vec<bool> = response.tool_calls.iter().map(|c| c.success).collect();
+    assert_eq!(outcomes, vec![false, false, true]);
+
+    // One recovery, after the second malformed call
+    let recoveries: Vec<(String, usize)> = collector
+        .events()
+        .into_iter()
+        .filter_map(|e| match e {
+            AgentEvent::ToolJsonRecovery { name, attempts } => Some((name, attempts)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(recoveries, vec![("calculate".to_string(), 2)]);
+
+    // Only the results of the second call ask the model to reconsider
+    let received = provider.received_messages();
+    let asks_to_reconsider =
+        |call: usize| {
+            received[call].last().unwrap().content.iter().any(
+                |block| matches!(block, ContentBlock::Text(text) if text.contains("`calculate`")),
+            )
+        };
+    assert!(!asks_to_reconsider(1));
+    assert!(asks_to_reconsider(2));
+    assert!(!asks_to_reconsider(3));
+}
+
+#[tokio::test]
+async fn test_malformed_tool_input_without_recovery() {
+    let provider = MockProvider::new()
+        .with_tool_use("calculate", serde_json::json!("2+2"))
+        .with_tool_use("calculate", serde_json::json!("2+2"))
+        .with_text("I could not calculate that");
+    let collector = DetailedEventCollector::new();
+
+    let agent = Agent::builder()
+        .provider(provider)
+        .add_tool(Calculator)
+        .with_grant_store(AutoApproveGrantStore)
+        .build()
+        .await
+        .unwrap();
+    agent.add_hook(collector.clone());
+
+    agent.run("What is 2+2?").await.unwrap();
+    assert!(!collector
+        .events()
+        .iter()
+        .any(|e| matches!(e, AgentEvent::ToolJsonRecovery { .. })));
+}
+
+// ===== Input validation =====
+
+/// Tool that only accepts `.csv` paths and counts its executions
+struct CsvTool(Arc<AtomicUsize>);
+
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+struct CsvInput {
+    path: String,
+}
+
+impl mixtape_core::Tool for CsvTool {
+    type Input = CsvInput;
+
+    fn name(&self) -> &str {
+        "load_csv"
+    }
+
+    fn description(&self) -> &str {
+        "Loads a CSV file"
+    }
+
+    fn validate(&self, input: &Self::Input) -> Result<(), mixtape_core::ToolError> {
+        if !input.path.ends_with(".csv") {
+            return Err(format!("path must name a .csv file, got '{}'", input.path).into());
+        }
+        Ok(())
+    }
+
+    async fn execute(&self, input: Self::Input) -> Result<ToolResult, mixtape_core::ToolError> {
+        self.0.fetch_add(1, Ordering::SeqCst);
+        Ok(ToolResult::text(format!("loaded {}", input.path)))
+    }
+}
+
+#[tokio::test]
+async fn test_validate_rejects_input_before_execute() {
+    let executions = Arc::new(AtomicUsize::new(0));
+    let provider = MockProvider::new()
+        .with_tool_use("load_csv", serde_json::json!({"path": "data.json"}))
+        .with_tool_use("load_csv", serde_json::json!({"path": "data.csv"}))
+        .with_text("Loaded");
+
+    let agent = Agent::builder()
+        .provider(provider.clone())
+        .add_tool(CsvTool(executions.clone()))
+        .with_grant_store(AutoApproveGrantStore)
+        .build()
+        .await
+        .unwrap();
+
+    let response = agent.run("Load the data").await.unwrap();
+    assert_eq!(response, "Loaded");
+
+    // Only the valid call ran
+    assert_eq!(executions.load(Ordering::SeqCst), 1);
+    let outcomes: Vec<bool> = response.tool_calls.iter().map(|c| c.success).collect();
+    assert_eq!(outcomes, vec![false, true]);
+
+    // The model saw the validation message as an error result
+    let received = provider.received_messages();
+    let ContentBlock::ToolResult(result) = &received[1].last().unwrap().content[0] else {
+        panic!("Expected a tool result");
+    };
+    assert_eq!(result.status, ToolResultStatus::Error);
+    assert!(result
+        .content
+        .as_text()
+        .contains("Invalid input: path must name a .csv file, got 'data.json'"));
+}
+
+#[tokio::test]
+async fn test_invalid_input_is_rejected_before_approval() {
+    let executions = Arc::new(AtomicUsize::new(0));
+    let provider = MockProvider::new()
+        .with_tool_use("load_csv", serde_json::json!({"path": "data.json"}))
+        .with_text("Could not load");
+
+    // Interactive with no grants: a valid call would wait for approval
+    let agent = Agent::builder()
+        .provider(provider)
+        .add_tool(CsvTool(executions.clone()))
+        .interactive()
+        .build()
+        .await
+        .unwrap();
+    let collector = EventCollector::new();
+    agent.add_hook(collector.clone());
+
+    let response = tokio::time::timeout(Duration::from_secs(5), agent.run("Load the data"))
+        .await
+        .expect("invalid input must not wait for approval")
+        .unwrap();
+    assert_eq!(response, "Could not load");
+    assert_eq!(executions.load(Ordering::SeqCst), 0);
+
+    let events = collector.events();
+    assert!(events.contains(&"tool_failed".to_string()));
+    assert!(!events.contains(&"permission_required".to_string()));
+    assert!(!events.contains(&"tool_executing".to_string()));
+}
+
+#[tokio::test]
+async fn test_wrapped_tool_still_validates_before_approval() {
+    let executions = Arc::new(AtomicUsize::new(0));
+    let provider = MockProvider::new()
+        .with_tool_use("load_csv", serde_json::json!({"path": "data.json"}))
+        .with_text("Could not load");
+
+    let agent = Agent::builder()
+        .provider(provider)
+        .add_tool_extracting(CsvTool(executions.clone()), "/rows")
+        .interactive()
+        .build()
+        .await
+        .unwrap();
+    let collector = EventCollector::new();
+    agent.add_hook(collector.clone());
+
+    let response = tokio::time::timeout(Duration::from_secs(5), agent.run("Load the data"))
+        .await
+        .expect("invalid input must not wait for approval")
+        .unwrap();
+    assert_eq!(response, "Could not load");
+    assert_eq!(executions.load(Ordering::SeqCst), 0);
+    assert!(!collector
+        .events()
+        .contains(&"permission_required".to_string()));
 }
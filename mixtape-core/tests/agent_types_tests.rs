@@ -1,4 +1,5 @@
 use mixtape_core::agent::{AgentResponse, TokenUsageStats, ToolCallInfo};
+use mixtape_core::Message;
 use std::time::Duration;
 
 // ===== AgentResponse Tests =====
@@ -7,9 +8,15 @@ fn make_test_response(text: &str) -> AgentResponse {
     AgentResponse {
         text: text.to_string(),
         tool_calls: vec![],
+        intended_tool_calls: vec![],
         token_usage: None,
         duration: Duration::from_millis(100),
         model_calls: 1,
+        model_latency: Duration::ZERO,
+        model: None,
+        message: Message::assistant(text),
+        messages: vec![Message::assistant(text)],
+        refused: false,
     }
 }
 
@@ -59,12 +66,18 @@ fn test_agent_response_with_tool_calls() {
                 duration: Duration::from_millis(10),
             },
         ],
+        intended_tool_calls: vec![],
         token_usage: Some(TokenUsageStats {
             input_tokens: 100,
             output_tokens: 50,
         }),
         duration: Duration::from_secs(1),
         model_calls: 2,
+        model_latency: Duration::ZERO,
+        model: None,
+        message: Message::assistant("Done"),
+        messages: vec![Message::assistant("Done")],
+        refused: false,
     };
 
     assert_eq!(response.tool_calls.len(), 2);
@@ -0,0 +1,248 @@
+//! Mock Streamable HTTP MCP server for integration tests
+//!
+//! Speaks just enough HTTP/1.1 and MCP to initialize a session, list tools,
+//! and call an `echo` tool. Like servers behind an aggressive idle timeout,
+//! it drops a kept-alive connection that has sat unused for longer than the
+//! configured idle period: the next request on it is read and then the
+//! socket is closed without a response.
+//!
+//! A fragile server instead streams each `echo` result slowly over SSE and
+//! fails any call made while too many others are still running, like a
+//! single-threaded server flooded with requests.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+/// A running mock server
+pub struct MockHttpMcpServer {
+    url: String,
+    state: Arc<ServerState>,
+}
+
+#[derive(Default)]
+struct ServerState {
+    sessions: AtomicUsize,
+    dropped: AtomicUsize,
+    /// Calls allowed in flight at once, for a fragile server
+    max_calls: Option<usize>,
+    in_flight: AtomicUsize,
+    peak_calls: AtomicUsize,
+}
+
+impl MockHttpMcpServer {
+    /// Start a server that drops connections idle for longer than `idle`
+    pub async fn start(idle: Duration) -> Self {
+        Self::spawn(idle, ServerState::default()).await
+    }
+
+    /// Start a server that fails calls made while `max_calls` are running
+    pub async fn start_fragile(max_calls: usize) -> Self {
+        let state = ServerState {
+            max_calls: Some(max_calls),
+            ..Default::default()
+        };
+        Self::spawn(Duration::MAX, state).await
+    }
+
+    async fn spawn(idle: Duration, state: ServerState) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/mcp", listener.local_addr().unwrap());
+        let state = Arc::new(state);
+
+        let shared = state.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve_connection(stream, idle, shared.clone()));
+            }
+        });
+
+        Self { url, state }
+    }
+
+    /// The `/mcp` endpoint URL
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Number of sessions clients have initialized
+    pub fn sessions(&self) -> usize {
+        self.state.sessions.load(Ordering::SeqCst)
+    }
+
+    /// Number of idle connections closed mid-request
+    pub fn dropped(&self) -> usize {
+        self.state.dropped.load(Ordering::SeqCst)
+    }
+
+    /// Most tool calls that were in flight at once
+    pub fn peak_calls(&self) -> usize {
+        self.state.peak_calls.load(Ordering::SeqCst)
+    }
+}
+
+async fn serve_connection(stream: TcpStream, idle: Duration, state: Arc<ServerState>) {
+    let mut reader = BufReader::new(stream);
+    let mut last_used: Option<Instant> = None;
+
+    loop {
+        let Some((method, body)) = read_request(&mut reader).await else {
+            return;
+        };
+        if last_used.is_some_and(|at| at.elapsed() > idle) {
+            state.dropped.fetch_add(1, Ordering::SeqCst);
+            return;
+        }
+
+        if state.max_calls.is_some() && method == "POST" && body.contains("\"tools/call\"") {
+            if fragile_call(&body, &state, reader.get_mut()).await.is_err() {
+                return;
+            }
+            last_used = Some(Instant::now());
+            continue;
+        }
+
+        let response = match method.as_str() {
+            "POST" => handle_message(&body, &state),
+            _ => http_response("405 Method Not Allowed", &[], ""),
+        };
+        if reader
+            .get_mut()
+            .write_all(response.as_bytes())
+            .await
+            .is_err()
+        {
+            return;
+        }
+        last_used = Some(Instant::now());
+    }
+}
+
+/// Read one request, returning its method and body
+async fn read_request(reader: &mut BufReader<TcpStream>) -> Option<(String, String)> {
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).await.ok()? == 0 {
+        return None;
+    }
+    let method = request_line.split_whitespace().next()?.to_string();
+
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).await.ok()?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().ok()?;
+            }
+        }
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).await.ok()?;
+    Some((method, String::from_utf8(body).ok()?))
+}
+
+/// Answer a JSON-RPC message posted to the endpoint
+fn handle_message(body: &str, state: &ServerState) -> String {
+    let message: serde_json::Value = serde_json::from_str(body).unwrap_or_default();
+    let Some(id) = message.get("id") else {
+        // Notifications and responses
+        return http_response("202 Accepted", &[], "");
+    };
+
+    let mut headers = Vec::new();
+    let result = match message["method"].as_str() {
+        Some("initialize") => {
+            let session = state.sessions.fetch_add(1, Ordering::SeqCst) + 1;
+            headers.push(format!("mcp-session-id: session-{}", session));
+            serde_json::json!({
+                "protocolVersion": "2025-03-26",
+                "capabilities": {"tools": {}},
+                "serverInfo": {"name": "mock-http-mcp-server", "version": "0.1.0"}
+            })
+        }
+        Some("tools/list") => serde_json::json!({
+            "tools": [{
+                "name": "echo",
+                "description": "Echo back the input",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {"message": {"type": "string"}}
+                }
+            }]
+        }),
+        Some("tools/call") => echo_result(&message),
+        _ => serde_json::json!({}),
+    };
+
+    headers.push("content-type: application/json".to_string());
+    let body = serde_json::json!({"jsonrpc": "2.0", "id": id, "result": result});
+    http_response("200 OK", &headers, &body.to_string())
+}
+
+fn echo_result(message: &serde_json::Value) -> serde_json::Value {
+    serde_json::json!({
+        "content": [{
+            "type": "text",
+            "text": message["params"]["arguments"]["message"]
+        }]
+    })
+}
+
+/// Answer a call over SSE after a delay, failing it if too many calls
+/// are already running
+///
+/// The headers go out first so the client sees a stream and can send
+/// further calls while this one is pending.
+async fn fragile_call(
+    body: &str,
+    state: &ServerState,
+    stream: &mut TcpStream,
+) -> std::io::Result<()> {
+    let message: serde_json::Value = serde_json::from_str(body).unwrap_or_default();
+    let in_flight = state.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+    state.peak_calls.fetch_max(in_flight, Ordering::SeqCst);
+
+    let reply = if state.max_calls.is_some_and(|max| in_flight > max) {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": message["id"],
+            "error": {"code": -32000, "message": "server busy"}
+        })
+    } else {
+        serde_json::json!({"jsonrpc": "2.0", "id": message["id"], "result": echo_result(&message)})
+    };
+    let event = format!("data: {}\n\n", reply);
+    let head = format!(
+        "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\ncontent-length: {}\r\n\r\n",
+        event.len()
+    );
+
+    let sent = async {
+        stream.write_all(head.as_bytes()).await?;
+        stream.flush().await?;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        Ok::<_, std::io::Error>(())
+    }
+    .await;
+    state.in_flight.fetch_sub(1, Ordering::SeqCst);
+    sent?;
+    stream.write_all(event.as_bytes()).await
+}
+
+fn http_response(status: &str, headers: &[String], body: &str) -> String {
+    let mut response = format!("HTTP/1.1 {}\r\ncontent-length: {}\r\n", status, body.len());
+    for header in headers {
+        response.push_str(header);
+        response.push_str("\r\n");
+    }
+    response.push_str("\r\n");
+    response.push_str(body);
+    response
+}
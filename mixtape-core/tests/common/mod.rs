@@ -47,6 +47,8 @@ impl GrantStore for AutoApproveGrantStore {
     }
 }
 
+#[cfg(feature = "mcp")]
+pub mod mock_http_mcp_server;
 #[cfg(feature = "mcp")]
 pub mod mock_mcp_server;
 
@@ -161,16 +163,27 @@ impl AgentHook for EventCollector {
             AgentEvent::RunStarted { .. } => "run_started",
             AgentEvent::RunCompleted { .. } => "run_completed",
             AgentEvent::RunFailed { .. } => "run_failed",
+            AgentEvent::BudgetWarning { .. } => "budget_warning",
+            AgentEvent::CacheInvalidated { .. } => "cache_invalidated",
             AgentEvent::ModelCallStarted { .. } => "model_call_started",
             AgentEvent::ModelCallStreaming { .. } => "model_streaming",
             AgentEvent::ModelCallCompleted { .. } => "model_call_completed",
+            AgentEvent::TurnCompleted { .. } => "turn_completed",
+            AgentEvent::ToolArgsDelta { .. } => "tool_args_delta",
+            AgentEvent::ToolInputRepaired { .. } => "tool_input_repaired",
             AgentEvent::ToolRequested { .. } => "tool_requested",
             AgentEvent::ToolExecuting { .. } => "tool_executing",
             AgentEvent::ToolCompleted { .. } => "tool_completed",
             AgentEvent::ToolFailed { .. } => "tool_failed",
+            AgentEvent::ToolSkippedDryRun { .. } => "tool_skipped_dry_run",
+            AgentEvent::ToolJsonRecovery { .. } => "tool_json_recovery",
             AgentEvent::PermissionRequired { .. } => "permission_required",
             AgentEvent::PermissionGranted { .. } => "permission_granted",
             AgentEvent::PermissionDenied { .. } => "permission_denied",
+            AgentEvent::ContentFiltered { .. } => "content_filtered",
+            AgentEvent::Refused { .. } => "refused",
+            AgentEvent::Progress { .. } => "progress",
+            AgentEvent::SubAgent { .. } => "sub_agent",
             #[cfg(feature = "session")]
             AgentEvent::SessionResumed { .. } => "session_resumed",
             #[cfg(feature = "session")]
@@ -210,6 +223,8 @@ pub struct MockProvider {
     name: &'static str,
     responses: Arc<Mutex<Vec<ModelResponse>>>,
     call_count: Arc<Mutex<usize>>,
+    received: Arc<Mutex<Vec<Vec<Message>>>>,
+    delay: Option<std::time::Duration>,
 }
 
 impl MockProvider {
@@ -219,9 +234,17 @@ impl MockProvider {
             name: "MockProvider",
             responses: Arc::new(Mutex::new(Vec::new())),
             call_count: Arc::new(Mutex::new(0)),
+            received: Arc::new(Mutex::new(Vec::new())),
+            delay: None,
         }
     }
 
+    /// Sleep for `delay` at the start of every call
+    pub fn with_delay(mut self, delay: std::time::Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
     /// Add a text response
     pub fn with_text(self, text: impl Into<String>) -> Self {
         let message = Message::assistant(text);
@@ -229,7 +252,40 @@ impl MockProvider {
         let response = ModelResponse {
             message,
             stop_reason: StopReason::EndTurn,
+            stop_sequence: None,
             usage: None,
+            service_tier: None,
+            model: None,
+        };
+
+        self.responses.lock().unwrap().push(response);
+        self
+    }
+
+    /// Add a response in which the model refuses to answer
+    pub fn with_refusal(self, text: impl Into<String>) -> Self {
+        let response = ModelResponse {
+            message: Message::assistant(text),
+            stop_reason: StopReason::Refusal,
+            stop_sequence: None,
+            usage: None,
+            service_tier: None,
+            model: None,
+        };
+
+        self.responses.lock().unwrap().push(response);
+        self
+    }
+
+    /// Add a final response with arbitrary content blocks
+    pub fn with_content(self, content: Vec<ContentBlock>) -> Self {
+        let response = ModelResponse {
+            message: Message::assistant_with_content(content),
+            stop_reason: StopReason::EndTurn,
+            stop_sequence: None,
+            usage: None,
+            service_tier: None,
+            model: None,
         };
 
         self.responses.lock().unwrap().push(response);
@@ -256,7 +312,62 @@ impl MockProvider {
         let response = ModelResponse {
             message,
             stop_reason: StopReason::ToolUse,
+            stop_sequence: None,
+            usage: None,
+            service_tier: None,
+            model: None,
+        };
+
+        self.responses.lock().unwrap().push(response);
+        self
+    }
+
+    /// Add a response that writes some text and then requests a tool
+    pub fn with_text_and_tool_use(
+        self,
+        text: impl Into<String>,
+        tool_name: impl Into<String>,
+        tool_input: serde_json::Value,
+    ) -> Self {
+        let tool_use = ToolUseBlock {
+            id: format!("tool_{}", uuid::Uuid::new_v4()),
+            name: tool_name.into(),
+            input: tool_input,
+        };
+
+        let response = ModelResponse {
+            message: Message {
+                role: Role::Assistant,
+                content: vec![
+                    ContentBlock::Text(text.into()),
+                    ContentBlock::ToolUse(tool_use),
+                ],
+            },
+            stop_reason: StopReason::ToolUse,
+            stop_sequence: None,
+            usage: None,
+            service_tier: None,
+            model: None,
+        };
+
+        self.responses.lock().unwrap().push(response);
+        self
+    }
+
+    /// Add a response requesting several tools at once
+    pub fn with_tool_uses(self, tool_uses: Vec<ToolUseBlock>) -> Self {
+        let message = Message {
+            role: Role::Assistant,
+            content: tool_uses.into_iter().map(ContentBlock::ToolUse).collect(),
+        };
+
+        let response = ModelResponse {
+            message,
+            stop_reason: StopReason::ToolUse,
+            stop_sequence: None,
             usage: None,
+            service_tier: None,
+            model: None,
         };
 
         self.responses.lock().unwrap().push(response);
@@ -267,6 +378,11 @@ impl MockProvider {
     pub fn call_count(&self) -> usize {
         *self.call_count.lock().unwrap()
     }
+
+    /// Messages sent with each call, in call order
+    pub fn received_messages(&self) -> Vec<Vec<Message>> {
+        self.received.lock().unwrap().clone()
+    }
 }
 
 #[async_trait::async_trait]
@@ -285,12 +401,17 @@ impl ModelProvider for MockProvider {
 
     async fn generate(
         &self,
-        _messages: Vec<Message>,
+        messages: Vec<Message>,
         _tools: Vec<ToolDefinition>,
         _system_prompt: Option<String>,
     ) -> Result<ModelResponse, ProviderError> {
+        if let Some(delay) = self.delay {
+            tokio::time::sleep(delay).await;
+        }
+
         let mut count = self.call_count.lock().unwrap();
         *count += 1;
+        self.received.lock().unwrap().push(messages);
 
         let mut responses = self.responses.lock().unwrap();
         if responses.is_empty() {
@@ -385,6 +506,7 @@ impl SessionStore for MockSessionStore {
                 message_count: s.messages.len(),
                 created_at: s.created_at,
                 updated_at: s.updated_at,
+                snippet: None,
             })
             .collect())
     }
@@ -4,8 +4,9 @@
 
 mod common;
 
+use common::mock_http_mcp_server::MockHttpMcpServer;
 use common::mock_mcp_server;
-use mixtape_core::mcp::{McpClient, McpServerConfig, McpTransport};
+use mixtape_core::mcp::{McpClient, McpError, McpServerConfig, McpTransport};
 
 /// Helper to create a client configured for the mock server
 fn mock_client(name: &str) -> McpClient {
@@ -168,12 +169,131 @@ async fn test_tool_with_namespace() {
     assert_eq!(tools.len(), 3);
 }
 
+#[tokio::test]
+async fn test_http_call_tool_is_not_retried_after_idle_drop() {
+    let server = MockHttpMcpServer::start(std::time::Duration::from_millis(100)).await;
+    let client = McpClient::new(McpServerConfig::new(
+        "http-server",
+        McpTransport::http(server.url()),
+    ))
+    .unwrap();
+
+    let result = client
+        .call_tool("echo".to_string(), serde_json::json!({"message": "first"}))
+        .await
+        .expect("Failed to call tool");
+    assert_eq!(result["content"][0]["text"], "first");
+    assert_eq!(server.sessions(), 1);
+
+    // Let the server close the pooled connection
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+
+    // The server read the call before closing, so it must not be sent again
+    let err = client
+        .call_tool("echo".to_string(), serde_json::json!({"message": "second"}))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, McpError::Transport(_)), "{:?}", err);
+    assert_eq!(server.dropped(), 1);
+    assert_eq!(server.sessions(), 1);
+
+    // The next call goes out on a fresh connection
+    let result = client
+        .call_tool("echo".to_string(), serde_json::json!({"message": "third"}))
+        .await
+        .expect("Dropped connection should be re-established");
+    assert_eq!(result["content"][0]["text"], "third");
+    assert_eq!(server.sessions(), 2);
+
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_http_list_tools_retries_after_idle_drop() {
+    let server = MockHttpMcpServer::start(std::time::Duration::from_millis(100)).await;
+    let client = McpClient::new(McpServerConfig::new(
+        "http-server",
+        McpTransport::http(server.url()),
+    ))
+    .unwrap();
+
+    assert_eq!(client.list_tools().await.unwrap().len(), 1);
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+
+    assert_eq!(client.list_tools().await.unwrap().len(), 1);
+    assert_eq!(server.dropped(), 1);
+    assert_eq!(server.sessions(), 2);
+
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_http_idle_timeout_avoids_dropped_connection() {
+    let server = MockHttpMcpServer::start(std::time::Duration::from_millis(200)).await;
+    let client = McpClient::new(McpServerConfig::new(
+        "http-server",
+        McpTransport::http(server.url()).idle_timeout(std::time::Duration::from_millis(50)),
+    ))
+    .unwrap();
+
+    client
+        .call_tool("echo".to_string(), serde_json::json!({"message": "first"}))
+        .await
+        .unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+
+    let result = client
+        .call_tool("echo".to_string(), serde_json::json!({"message": "second"}))
+        .await
+        .expect("The client should have closed the idle connection itself");
+    assert_eq!(result["content"][0]["text"], "second");
+    assert_eq!(server.dropped(), 0);
+
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_agent_mcp_concurrency_limit_serializes_calls() {
+    let server = MockHttpMcpServer::start_fragile(1).await;
+    let echo = |id: &str| ToolUseBlock {
+        id: id.to_string(),
+        name: "echo".to_string(),
+        input: serde_json::json!({"message": id}),
+    };
+    let provider = MockProvider::new()
+        .with_tool_uses(vec![echo("a"), echo("b"), echo("c")])
+        .with_text("Done");
+
+    let agent = Agent::builder()
+        .provider(provider)
+        .with_grant_store(AutoApproveGrantStore)
+        .with_mcp_server(
+            McpServerConfig::new("fragile", McpTransport::http(server.url()))
+                .without_namespace()
+                .concurrency(1),
+        )
+        .build()
+        .await
+        .unwrap();
+
+    let response = agent.run("Echo three times").await.unwrap();
+    assert_eq!(response.tool_calls.len(), 3);
+    assert!(
+        response.tool_calls.iter().all(|call| call.success),
+        "{:?}",
+        response.tool_calls
+    );
+    assert_eq!(server.peak_calls(), 1);
+
+    agent.shutdown().await;
+}
+
 // ============================================================================
 // Agent MCP Integration Tests
 // ============================================================================
 
 use common::{AutoApproveGrantStore, EventCollector, MockProvider};
-use mixtape_core::Agent;
+use mixtape_core::{Agent, ToolUseBlock};
 
 /// Helper to create an MCP server config for the mock server
 fn mock_mcp_config(name: &str) -> McpServerConfig {
@@ -210,6 +330,29 @@ async fn test_agent_add_mcp_server() {
     agent.shutdown().await;
 }
 
+#[tokio::test]
+async fn test_agent_add_mcp_server_rejected_tool_adds_none() {
+    let provider = MockProvider::new().with_text("Done");
+    let mut agent = Agent::builder().provider(provider).build().await.unwrap();
+
+    agent
+        .add_mcp_server(mock_mcp_config("srv").only_tools(["add"]))
+        .await
+        .unwrap();
+
+    // "srv_echo" would be fine, but "srv_add", the second tool, is taken
+    let err = agent
+        .add_mcp_server(mock_mcp_config("srv"))
+        .await
+        .unwrap_err();
+    assert!(matches!(&err, McpError::Config(message) if message.contains("srv_add")));
+
+    let tool_names: Vec<String> = agent.list_tools().into_iter().map(|t| t.name).collect();
+    assert_eq!(tool_names, ["srv_add"]);
+
+    agent.shutdown().await;
+}
+
 #[tokio::test]
 async fn test_agent_add_mcp_server_without_namespace() {
     let provider = MockProvider::new().with_text("Done");
@@ -437,3 +580,49 @@ async fn test_agent_shutdown_disconnects_mcp() {
     // Shutdown should disconnect all MCP clients without panicking
     agent.shutdown().await;
 }
+
+/// Config for a server whose command does not exist
+fn broken_mcp_config(name: &str) -> McpServerConfig {
+    McpServerConfig::new(
+        name,
+        McpTransport::stdio("/nonexistent/mixtape-mock-mcp-server"),
+    )
+}
+
+#[tokio::test]
+async fn test_builder_mcp_servers_fail_build_naming_failed_server() {
+    let result = Agent::builder()
+        .provider(MockProvider::new().with_text("Done"))
+        .with_mcp_servers([mock_mcp_config("good"), broken_mcp_config("broken")])
+        .build()
+        .await;
+
+    let Err(err) = result else {
+        panic!("Expected the build to fail");
+    };
+    let message = err.to_string();
+    assert!(message.contains("broken"), "{}", message);
+    assert!(!message.contains("good"), "{}", message);
+}
+
+#[tokio::test]
+async fn test_builder_mcp_best_effort_skips_failed_server() {
+    let agent = Agent::builder()
+        .provider(MockProvider::new().with_text("Done"))
+        .with_mcp_servers([mock_mcp_config("good"), broken_mcp_config("broken")])
+        .with_mcp_best_effort(true)
+        .build()
+        .await
+        .unwrap();
+
+    // Tools from the working server are namespaced and available
+    let mut tool_names: Vec<String> = agent.list_tools().into_iter().map(|t| t.name).collect();
+    tool_names.sort();
+    assert_eq!(tool_names, ["good_add", "good_echo", "good_fail"]);
+
+    let failures = agent.mcp_failures();
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].server, "broken");
+
+    agent.shutdown().await;
+}
@@ -3,7 +3,11 @@
 mod common;
 
 use common::{AutoApproveGrantStore, MockProvider, MockSessionStore};
-use mixtape_core::{Agent, SessionStore, Tool, ToolError, ToolResult};
+use mixtape_core::session::search_snippet;
+use mixtape_core::{
+    Agent, ContentBlock, MessageRole, Role, SessionMessage, SessionStore, Tool, ToolCall,
+    ToolError, ToolResult, ToolResultStatus, ToolUseRepair,
+};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -550,3 +554,315 @@ async fn test_get_session_history_without_session() {
     let history = agent.get_session_history(10).await.unwrap();
     assert_eq!(history.len(), 0);
 }
+
+// ===== Incremental Save Tests =====
+
+#[tokio::test]
+async fn test_incremental_save_survives_crash_after_tool_result() {
+    let store = MockSessionStore::new();
+
+    // The provider runs out of responses after the tool call, so the second
+    // model call fails mid-run - simulating a crash after the first tool result.
+    let provider =
+        MockProvider::new().with_tool_use("test_tool", serde_json::json!({"message": "hello"}));
+
+    let agent = Agent::builder()
+        .provider(provider)
+        .with_session_store(store.clone())
+        .with_incremental_session_save(true)
+        .add_tool(TestTool)
+        .with_grant_store(AutoApproveGrantStore)
+        .build()
+        .await
+        .unwrap();
+
+    assert!(agent.run("Use the tool").await.is_err());
+
+    // Reload from the store: the completed tool turn must be there
+    let session = store.get_or_create_session().await.unwrap();
+    assert_eq!(session.messages.len(), 2);
+    assert_eq!(session.messages[0].role, mixtape_core::MessageRole::User);
+    assert_eq!(session.messages[0].content, "Use the tool");
+
+    let turn = &session.messages[1];
+    assert_eq!(turn.role, mixtape_core::MessageRole::Assistant);
+    assert_eq!(turn.tool_calls.len(), 1);
+    assert_eq!(turn.tool_calls[0].name, "test_tool");
+    assert_eq!(turn.tool_results.len(), 1);
+    assert_eq!(turn.tool_results[0].tool_use_id, turn.tool_calls[0].id);
+    assert_eq!(turn.tool_results[0].content, "Processed: hello");
+
+    // A fresh agent can resume from the interrupted session
+    let agent2 = Agent::builder()
+        .provider(MockProvider::new().with_text("Recovered"))
+        .with_session_store(store.clone())
+        .add_tool(TestTool)
+        .with_grant_store(AutoApproveGrantStore)
+        .build()
+        .await
+        .unwrap();
+
+    let response = agent2.run("Continue").await.unwrap();
+    assert_eq!(response, "Recovered");
+
+    // The final save replaces nothing from the earlier turn
+    let session = store.get_or_create_session().await.unwrap();
+    assert_eq!(session.messages.len(), 4);
+    assert_eq!(session.messages[1].tool_calls.len(), 1);
+}
+
+#[tokio::test]
+async fn test_resume_repairs_dangling_tool_use() {
+    let store = MockSessionStore::new();
+
+    // A session saved after the model asked for a tool but before the
+    // result was recorded
+    let mut session = store.get_or_create_session().await.unwrap();
+    session.messages = vec![
+        SessionMessage {
+            role: MessageRole::User,
+            content: "Use the tool".to_string(),
+            tool_calls: vec![],
+            tool_results: vec![],
+            timestamp: chrono::Utc::now(),
+        },
+        SessionMessage {
+            role: MessageRole::Assistant,
+            content: String::new(),
+            tool_calls: vec![ToolCall {
+                id: "tool_1".to_string(),
+                name: "test_tool".to_string(),
+                input: r#"{"message": "hello"}"#.to_string(),
+            }],
+            tool_results: vec![],
+            timestamp: chrono::Utc::now(),
+        },
+    ];
+    store.save_session(&session).await.unwrap();
+
+    let provider = MockProvider::new().with_text("Recovered");
+    let agent = Agent::builder()
+        .provider(provider.clone())
+        .with_session_store(store.clone())
+        .add_tool(TestTool)
+        .with_grant_store(AutoApproveGrantStore)
+        .build()
+        .await
+        .unwrap();
+
+    let response = agent.run("Continue").await.unwrap();
+    assert_eq!(response, "Recovered");
+
+    // The model saw the tool use answered by an interrupted error result,
+    // with roles still alternating
+    let received = provider.received_messages();
+    let messages = &received[0];
+    let roles: Vec<Role> = messages.iter().map(|m| m.role).collect();
+    assert_eq!(
+        roles,
+        vec![
+            Role::User,
+            Role::Assistant,
+            Role::User,
+            Role::Assistant,
+            Role::User
+        ]
+    );
+    assert_eq!(messages[1].tool_uses()[0].id, "tool_1");
+    match &messages[2].content[0] {
+        ContentBlock::ToolResult(result) => {
+            assert_eq!(result.tool_use_id, "tool_1");
+            assert_eq!(result.status, ToolResultStatus::Error);
+        }
+        other => panic!("expected tool result, got {:?}", other),
+    }
+    assert_eq!(messages[4].text(), "Continue");
+}
+
+#[tokio::test]
+async fn test_resume_drops_dangling_tool_use() {
+    let store = MockSessionStore::new();
+    let mut session = store.get_or_create_session().await.unwrap();
+    session.messages = vec![
+        SessionMessage {
+            role: MessageRole::User,
+            content: "Use the tool".to_string(),
+            tool_calls: vec![],
+            tool_results: vec![],
+            timestamp: chrono::Utc::now(),
+        },
+        SessionMessage {
+            role: MessageRole::Assistant,
+            content: String::new(),
+            tool_calls: vec![ToolCall {
+                id: "tool_1".to_string(),
+                name: "test_tool".to_string(),
+                input: r#"{"message": "hello"}"#.to_string(),
+            }],
+            tool_results: vec![],
+            timestamp: chrono::Utc::now(),
+        },
+    ];
+    store.save_session(&session).await.unwrap();
+
+    let provider = MockProvider::new().with_text("Recovered");
+    let agent = Agent::builder()
+        .provider(provider.clone())
+        .with_session_store(store.clone())
+        .with_tool_use_repair(ToolUseRepair::DropToolUse)
+        .build()
+        .await
+        .unwrap();
+
+    agent.run("Continue").await.unwrap();
+
+    let received = provider.received_messages();
+    let messages = &received[0];
+    assert_eq!(messages.len(), 3);
+    assert!(messages.iter().all(|m| m.tool_uses().is_empty()));
+    assert_eq!(messages[1].role, Role::Assistant);
+    assert_eq!(messages[2].text(), "Continue");
+}
+
+#[tokio::test]
+async fn test_without_incremental_save_crash_loses_turn() {
+    let store = MockSessionStore::new();
+    let provider =
+        MockProvider::new().with_tool_use("test_tool", serde_json::json!({"message": "hello"}));
+
+    let agent = Agent::builder()
+        .provider(provider)
+        .with_session_store(store.clone())
+        .add_tool(TestTool)
+        .with_grant_store(AutoApproveGrantStore)
+        .build()
+        .await
+        .unwrap();
+
+    assert!(agent.run("Use the tool").await.is_err());
+
+    let session = store.get_or_create_session().await.unwrap();
+    assert!(session.messages.is_empty());
+}
+
+#[tokio::test]
+async fn test_incremental_save_final_save_replaces_checkpoint() {
+    let store = MockSessionStore::new();
+    let provider = MockProvider::new()
+        .with_tool_use("test_tool", serde_json::json!({"message": "one"}))
+        .with_tool_use("test_tool", serde_json::json!({"message": "two"}))
+        .with_text("Done");
+
+    let collector = SessionEventCollector::new();
+    let collector_clone = collector.clone();
+
+    let agent = Agent::builder()
+        .provider(provider)
+        .with_session_store(store.clone())
+        .with_incremental_session_save(true)
+        .add_tool(TestTool)
+        .with_grant_store(AutoApproveGrantStore)
+        .build()
+        .await
+        .unwrap();
+    agent.add_hook(collector);
+
+    agent.run("Use tools").await.unwrap();
+
+    // One save per tool turn plus the final save
+    let saves = collector_clone
+        .events()
+        .iter()
+        .filter(|e| matches!(e, AgentEvent::SessionSaved { .. }))
+        .count();
+    assert_eq!(saves, 3);
+
+    let session = store.get_or_create_session().await.unwrap();
+    assert_eq!(session.messages.len(), 2);
+    assert_eq!(session.messages[1].content, "Done");
+    assert_eq!(session.messages[1].tool_calls.len(), 2);
+    assert_eq!(session.messages[1].tool_results.len(), 2);
+}
+
+// ===== Session search =====
+
+async fn store_with_sessions(sessions: &[(&str, &[&str])]) -> MockSessionStore {
+    let store = MockSessionStore::new();
+    for (dir, messages) in sessions {
+        let dir_store = store.clone().with_directory(*dir);
+        let mut session = dir_store.get_or_create_session().await.unwrap();
+        for content in *messages {
+            session.messages.push(SessionMessage {
+                role: MessageRole::User,
+                content: content.to_string(),
+                tool_calls: vec![],
+                tool_results: vec![],
+                timestamp: chrono::Utc::now(),
+            });
+        }
+        dir_store.save_session(&session).await.unwrap();
+    }
+    store
+}
+
+#[tokio::test]
+async fn test_default_search_matches_message_content() {
+    let store = store_with_sessions(&[
+        ("/proj/api", &["Why does the Migration fail on startup?"]),
+        ("/proj/web", &["Center this div please"]),
+        ("/proj/db", &["List tables", "Rollback the last migration"]),
+    ])
+    .await;
+
+    let results = store.search("migration", 10).await.unwrap();
+    let mut dirs: Vec<&str> = results.iter().map(|s| s.directory.as_str()).collect();
+    dirs.sort();
+    assert_eq!(dirs, vec!["/proj/api", "/proj/db"]);
+
+    let db = results.iter().find(|s| s.directory == "/proj/db").unwrap();
+    assert_eq!(db.snippet.as_deref(), Some("Rollback the last migration"));
+    assert_eq!(db.message_count, 2);
+}
+
+#[tokio::test]
+async fn test_default_search_respects_limit_and_empty_query() {
+    let store =
+        store_with_sessions(&[("/a", &["deploy to prod"]), ("/b", &["deploy to staging"])]).await;
+
+    assert_eq!(store.search("deploy", 1).await.unwrap().len(), 1);
+    assert!(store.search("   ", 10).await.unwrap().is_empty());
+    assert!(store.search("kubernetes", 10).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_agent_search_sessions() {
+    let store = store_with_sessions(&[("/proj", &["debugging the flaky test"])]).await;
+    let agent = Agent::builder()
+        .provider(MockProvider::new())
+        .with_session_store(store)
+        .build()
+        .await
+        .unwrap();
+
+    let results = agent.search_sessions("flaky", 5).await.unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].directory, "/proj");
+
+    let agent = Agent::builder()
+        .provider(MockProvider::new())
+        .build()
+        .await
+        .unwrap();
+    assert!(agent.search_sessions("flaky", 5).await.unwrap().is_empty());
+}
+
+#[test]
+fn test_search_snippet_centers_on_match() {
+    let content = format!("{} needle {}", "a ".repeat(50), "b ".repeat(50));
+    let snippet = search_snippet(&content, "NEEDLE");
+    assert!(snippet.starts_with('…'));
+    assert!(snippet.ends_with('…'));
+    assert!(snippet.contains("needle"));
+
+    assert_eq!(search_snippet("short text", "missing"), "short text");
+}
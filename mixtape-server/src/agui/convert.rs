@@ -1,9 +1,23 @@
 //! Conversion from mixtape AgentEvent to AG-UI events.
 
+use std::collections::HashMap;
+use std::sync::Arc;
+
 use mixtape_core::events::AgentEvent;
+use mixtape_core::ToolResult;
+use serde_json::Value;
 
 use super::events::{AguiEvent, InterruptData, InterruptType, MessageRole};
 
+/// Hook that rewrites tool parameters before they leave the server.
+///
+/// Called with the tool name and its input; the returned value is sent in
+/// `TOOL_CALL_ARGS` and `INTERRUPT` events in place of the original.
+pub type RedactParams = Arc<dyn Fn(&str, &Value) -> Value + Send + Sync>;
+
+/// Marker appended to tool args and results cut to the payload limit.
+pub const TRUNCATION_MARKER: &str = "...[truncated]";
+
 /// Context for converting AgentEvent to AG-UI events.
 ///
 /// Maintains state across events to properly track message boundaries
@@ -15,6 +29,12 @@ pub struct ConversionContext {
     pub run_id: String,
     /// Current message ID being built (for streaming).
     current_message_id: Option<String>,
+    /// Output schemas of tools that declare one, keyed by tool name.
+    output_schemas: HashMap<String, Value>,
+    /// Hook applied to tool parameters before they are emitted.
+    redact_params: Option<RedactParams>,
+    /// Maximum size in bytes of emitted tool args and results.
+    max_payload_bytes: Option<usize>,
 }
 
 impl ConversionContext {
@@ -24,6 +44,50 @@ impl ConversionContext {
             thread_id,
             run_id,
             current_message_id: None,
+            output_schemas: HashMap::new(),
+            redact_params: None,
+            max_payload_bytes: None,
+        }
+    }
+
+    /// Attach tool output schemas to `TOOL_CALL_RESULT` events.
+    ///
+    /// Typically built from [`Agent::list_tools`](mixtape_core::Agent::list_tools).
+    pub fn with_output_schemas(mut self, output_schemas: HashMap<String, Value>) -> Self {
+        self.output_schemas = output_schemas;
+        self
+    }
+
+    /// Rewrite tool parameters before emitting them.
+    ///
+    /// Applies to `TOOL_CALL_ARGS` deltas and the params of tool approval
+    /// `INTERRUPT`s. The params hash is left unchanged so approvals still
+    /// match the original call.
+    pub fn with_redact_params(mut self, redact_params: Option<RedactParams>) -> Self {
+        self.redact_params = redact_params;
+        self
+    }
+
+    /// Truncate `TOOL_CALL_ARGS` deltas and `TOOL_CALL_RESULT` content
+    /// longer than `max_bytes`, ending them with [`TRUNCATION_MARKER`].
+    pub fn with_max_payload_bytes(mut self, max_bytes: Option<usize>) -> Self {
+        self.max_payload_bytes = max_bytes;
+        self
+    }
+
+    /// Apply the redaction hook, if any, to a tool's parameters.
+    fn redact(&self, tool_name: &str, params: &Value) -> Value {
+        match &self.redact_params {
+            Some(redact) => redact(tool_name, params),
+            None => params.clone(),
+        }
+    }
+
+    /// Cut a payload to the size limit, if any.
+    fn limit(&self, payload: String) -> String {
+        match self.max_payload_bytes {
+            Some(max_bytes) => truncate_payload(payload, max_bytes),
+            None => payload,
         }
     }
 
@@ -129,7 +193,8 @@ pub fn convert_event(event: &AgentEvent, ctx: &mut ConversionContext) -> Vec<Agu
             });
             events.push(AguiEvent::ToolCallArgs {
                 tool_call_id: tool_use_id.clone(),
-                delta: serde_json::to_string(input).unwrap_or_default(),
+                delta: ctx
+                    .limit(serde_json::to_string(&ctx.redact(name, input)).unwrap_or_default()),
             });
             events.push(AguiEvent::ToolCallEnd {
                 tool_call_id: tool_use_id.clone(),
@@ -145,14 +210,21 @@ pub fn convert_event(event: &AgentEvent, ctx: &mut ConversionContext) -> Vec<Agu
 
         AgentEvent::ToolCompleted {
             tool_use_id,
+            name,
             output,
             ..
         } => {
+            // The schema describes JSON output only
+            let output_schema = match output {
+                ToolResult::Json(_) => ctx.output_schemas.get(name).cloned(),
+                _ => None,
+            };
             vec![AguiEvent::ToolCallResult {
                 message_id: uuid::Uuid::new_v4().to_string(),
                 tool_call_id: tool_use_id.clone(),
-                content: output.as_text(),
+                content: ctx.limit(output.as_text()),
                 role: Some(MessageRole::Tool),
+                output_schema,
             }]
         }
 
@@ -162,8 +234,19 @@ pub fn convert_event(event: &AgentEvent, ctx: &mut ConversionContext) -> Vec<Agu
             vec![AguiEvent::ToolCallResult {
                 message_id: uuid::Uuid::new_v4().to_string(),
                 tool_call_id: tool_use_id.clone(),
-                content: format!("Error: {}", error),
+                content: ctx.limit(format!("Error: {}", error)),
+                role: Some(MessageRole::Tool),
+                output_schema: None,
+            }]
+        }
+
+        AgentEvent::ToolSkippedDryRun { tool_use_id, .. } => {
+            vec![AguiEvent::ToolCallResult {
+                message_id: uuid::Uuid::new_v4().to_string(),
+                tool_call_id: tool_use_id.clone(),
+                content: "Skipped (dry run)".to_string(),
                 role: Some(MessageRole::Tool),
+                output_schema: None,
             }]
         }
 
@@ -180,7 +263,7 @@ pub fn convert_event(event: &AgentEvent, ctx: &mut ConversionContext) -> Vec<Agu
                 data: InterruptData {
                     tool_use_id: proposal_id.clone(),
                     tool_name: tool_name.clone(),
-                    params: params.clone(),
+                    params: ctx.redact(tool_name, params),
                     params_hash: params_hash.clone(),
                 },
             }]
@@ -204,6 +287,22 @@ pub fn convert_event(event: &AgentEvent, ctx: &mut ConversionContext) -> Vec<Agu
     }
 }
 
+/// Cut `payload` to at most `max_bytes` bytes, including the marker.
+///
+/// Cuts on a char boundary, so the result may be a little shorter.
+fn truncate_payload(mut payload: String, max_bytes: usize) -> String {
+    if payload.len() <= max_bytes {
+        return payload;
+    }
+    let mut end = max_bytes.saturating_sub(TRUNCATION_MARKER.len());
+    while !payload.is_char_boundary(end) {
+        end -= 1;
+    }
+    payload.truncate(end);
+    payload.push_str(TRUNCATION_MARKER);
+    payload
+}
+
 #[cfg(test)]
 #[path = "convert_tests.rs"]
 mod tests;
@@ -102,6 +102,7 @@ fn test_run_completed_ends_current_message() {
 
     // RunCompleted should end the message
     let completed = AgentEvent::RunCompleted {
+        agent_name: None,
         output: "Done".to_string(),
         duration: Duration::from_secs(1),
     };
@@ -119,6 +120,7 @@ fn test_run_completed_without_current_message() {
 
     // RunCompleted with no active message
     let completed = AgentEvent::RunCompleted {
+        agent_name: None,
         output: "Done".to_string(),
         duration: Duration::from_secs(1),
     };
@@ -211,6 +213,7 @@ fn test_tool_completed_with_different_result_types() {
             tool_use_id: "tc-1".to_string(),
             name: "test_tool".to_string(),
             output: result,
+            model_output: None,
             duration: Duration::from_millis(100),
         };
 
@@ -225,6 +228,42 @@ fn test_tool_completed_with_different_result_types() {
     }
 }
 
+#[test]
+fn test_tool_call_result_includes_output_schema_for_json() {
+    let schema = serde_json::json!({"type": "object"});
+    let mut ctx = ConversionContext::new("thread-1".to_string(), "run-1".to_string())
+        .with_output_schemas(HashMap::from([("typed_tool".to_string(), schema.clone())]));
+
+    let completed = |name: &str, output: ToolResult| AgentEvent::ToolCompleted {
+        tool_use_id: "tc-1".to_string(),
+        name: name.to_string(),
+        output,
+        model_output: None,
+        duration: Duration::from_millis(1),
+    };
+
+    let events = convert_event(
+        &completed("typed_tool", ToolResult::Json(serde_json::json!({}))),
+        &mut ctx,
+    );
+    let AguiEvent::ToolCallResult { output_schema, .. } = &events[0] else {
+        panic!("Expected ToolCallResult");
+    };
+    assert_eq!(output_schema.as_ref(), Some(&schema));
+
+    // Text output and tools without a schema carry none
+    for event in [
+        completed("typed_tool", ToolResult::Text("plain".to_string())),
+        completed("other_tool", ToolResult::Json(serde_json::json!({}))),
+    ] {
+        let events = convert_event(&event, &mut ctx);
+        let AguiEvent::ToolCallResult { output_schema, .. } = &events[0] else {
+            panic!("Expected ToolCallResult");
+        };
+        assert!(output_schema.is_none());
+    }
+}
+
 #[test]
 fn test_tool_failed_error_formatting() {
     let mut ctx = ConversionContext::new("thread-1".to_string(), "run-1".to_string());
@@ -246,6 +285,32 @@ fn test_tool_failed_error_formatting() {
     }
 }
 
+#[test]
+fn test_tool_skipped_dry_run_result() {
+    let mut ctx = ConversionContext::new("thread-1".to_string(), "run-1".to_string());
+
+    let event = AgentEvent::ToolSkippedDryRun {
+        tool_use_id: "tc-1".to_string(),
+        name: "dangerous_tool".to_string(),
+        input: serde_json::json!({}),
+    };
+
+    let events = convert_event(&event, &mut ctx);
+    assert_eq!(events.len(), 1);
+
+    if let AguiEvent::ToolCallResult {
+        tool_call_id,
+        content,
+        ..
+    } = &events[0]
+    {
+        assert_eq!(tool_call_id, "tc-1");
+        assert_eq!(content, "Skipped (dry run)");
+    } else {
+        panic!("Expected ToolCallResult");
+    }
+}
+
 #[test]
 fn test_tool_call_args_with_complex_json() {
     let mut ctx = ConversionContext::new("thread-1".to_string(), "run-1".to_string());
@@ -360,6 +425,7 @@ fn test_multiple_tools_in_sequence() {
         tool_use_id: "tc-1".to_string(),
         name: "tool1".to_string(),
         output: ToolResult::Text("Result 1".to_string()),
+        model_output: None,
         duration: Duration::from_millis(100),
     };
     convert_event(&complete1, &mut ctx);
@@ -425,6 +491,7 @@ fn test_context_thread_and_run_ids() {
     let mut ctx = ConversionContext::new("custom-thread".to_string(), "custom-run".to_string());
 
     let event = AgentEvent::RunStarted {
+        agent_name: None,
         input: "test".to_string(),
         timestamp: Instant::now(),
     };
@@ -459,3 +526,94 @@ fn test_conversion_context_message_id_operations() {
     let id = ctx.take_current_message_id();
     assert!(id.is_none());
 }
+
+#[test]
+fn test_redact_params_hides_field_from_emitted_json() {
+    let redact: RedactParams = Arc::new(|_tool, params| {
+        let mut params = params.clone();
+        if let Some(key) = params.get_mut("api_key") {
+            *key = serde_json::json!("[REDACTED]");
+        }
+        params
+    });
+    let mut ctx = ConversionContext::new("thread-1".to_string(), "run-1".to_string())
+        .with_redact_params(Some(redact));
+    let params = serde_json::json!({"query": "weather", "api_key": "sk-secret-123"});
+
+    let requested = AgentEvent::ToolRequested {
+        tool_use_id: "tc-1".to_string(),
+        name: "search".to_string(),
+        input: params.clone(),
+    };
+    let permission = AgentEvent::PermissionRequired {
+        proposal_id: "tc-1".to_string(),
+        tool_name: "search".to_string(),
+        params,
+        params_hash: "hash-1".to_string(),
+    };
+
+    let mut events = convert_event(&requested, &mut ctx);
+    events.extend(convert_event(&permission, &mut ctx));
+    for event in &events {
+        let json = serde_json::to_string(event).unwrap();
+        assert!(!json.contains("sk-secret-123"), "secret leaked: {json}");
+    }
+
+    let args = events
+        .iter()
+        .find_map(|event| match event {
+            AguiEvent::ToolCallArgs { delta, .. } => Some(delta.clone()),
+            _ => None,
+        })
+        .expect("Expected ToolCallArgs");
+    assert!(args.contains("[REDACTED]"));
+    assert!(args.contains("weather"));
+}
+
+#[test]
+fn test_max_payload_bytes_truncates_args_and_results() {
+    let mut ctx = ConversionContext::new("thread-1".to_string(), "run-1".to_string())
+        .with_max_payload_bytes(Some(32));
+
+    let requested = AgentEvent::ToolRequested {
+        tool_use_id: "tc-1".to_string(),
+        name: "echo".to_string(),
+        input: serde_json::json!({"text": "é".repeat(100)}),
+    };
+    let args = convert_event(&requested, &mut ctx)
+        .into_iter()
+        .find_map(|event| match event {
+            AguiEvent::ToolCallArgs { delta, .. } => Some(delta),
+            _ => None,
+        })
+        .expect("Expected ToolCallArgs");
+    assert!(args.len() <= 32);
+    assert!(args.ends_with(TRUNCATION_MARKER));
+
+    let completed = AgentEvent::ToolCompleted {
+        tool_use_id: "tc-1".to_string(),
+        name: "echo".to_string(),
+        output: ToolResult::Text("x".repeat(100)),
+        model_output: None,
+        duration: Duration::from_millis(1),
+    };
+    let events = convert_event(&completed, &mut ctx);
+    let AguiEvent::ToolCallResult { content, .. } = &events[0] else {
+        panic!("Expected ToolCallResult");
+    };
+    assert_eq!(content.len(), 32);
+    assert!(content.ends_with(TRUNCATION_MARKER));
+
+    let short = AgentEvent::ToolCompleted {
+        tool_use_id: "tc-2".to_string(),
+        name: "echo".to_string(),
+        output: ToolResult::Text("ok".to_string()),
+        model_output: None,
+        duration: Duration::from_millis(1),
+    };
+    let events = convert_event(&short, &mut ctx);
+    let AguiEvent::ToolCallResult { content, .. } = &events[0] else {
+        panic!("Expected ToolCallResult");
+    };
+    assert_eq!(content, "ok");
+}
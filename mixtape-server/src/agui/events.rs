@@ -99,6 +99,9 @@ pub enum AguiEvent {
         /// Role (typically Tool).
         #[serde(skip_serializing_if = "Option::is_none")]
         role: Option<MessageRole>,
+        /// JSON schema of the content, when the tool declares one.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        output_schema: Option<Value>,
     },
 
     // ===== State Management Events =====
@@ -117,6 +117,7 @@ fn test_tool_call_events_complete_sequence() {
                 tool_call_id: "tc-1".to_string(),
                 content: "Success".to_string(),
                 role: Some(MessageRole::Tool),
+                output_schema: None,
             },
             "TOOL_CALL_RESULT",
         ),
@@ -126,6 +127,7 @@ fn test_tool_call_events_complete_sequence() {
                 tool_call_id: "tc-1".to_string(),
                 content: "Success".to_string(),
                 role: None,
+                output_schema: None,
             },
             "TOOL_CALL_RESULT",
         ),
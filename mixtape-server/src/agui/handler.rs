@@ -9,8 +9,9 @@ use axum::{
     Json,
 };
 use futures::stream::Stream;
-use mixtape_core::events::AgentEvent;
+use mixtape_core::events::{AgentEvent, HookId};
 use mixtape_core::permission::{AuthorizationResponse, Grant, Scope};
+use mixtape_core::Agent;
 use serde::Deserialize;
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::ReceiverStream;
@@ -71,14 +72,43 @@ pub struct InterruptRequest {
     pub response: InterruptResponse,
 }
 
+/// Aborts the agent run when the SSE stream is dropped.
+///
+/// Dropping the run future drops the provider stream with it, which closes
+/// the upstream model request instead of letting it run to completion after
+/// the client has gone.
+struct AbortOnDrop(tokio::task::AbortHandle);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Removes the event forwarding hook however the run ends, including abort.
+struct RemoveHookOnDrop {
+    agent: Arc<Agent>,
+    hook_id: HookId,
+}
+
+impl Drop for RemoveHookOnDrop {
+    fn drop(&mut self) {
+        self.agent.remove_hook(self.hook_id);
+    }
+}
+
 /// Handle AG-UI protocol requests.
 ///
 /// Accepts POST with AgentRequest body, returns SSE stream of AG-UI events.
+/// If the client disconnects, the agent run is cancelled.
 pub async fn agui_handler(
     State(state): State<AppState>,
     Json(request): Json<AgentRequest>,
 ) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
     let agent = state.agent.clone();
+    let keep_alive = KeepAlive::new()
+        .interval(state.sse_keepalive)
+        .text("keepalive");
     let thread_id = request
         .thread_id
         .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
@@ -94,15 +124,24 @@ pub async fn agui_handler(
     let tx_for_task = tx.clone();
     let thread_id_clone = thread_id.clone();
     let run_id_clone = run_id.clone();
+    let redact_params = state.redact_params.clone();
+    let max_payload_bytes = state.max_payload_bytes;
 
-    tokio::spawn(async move {
+    let run = tokio::spawn(async move {
         // Create conversion context with shared state
-        let ctx = Arc::new(parking_lot::Mutex::new(ConversionContext::new(
-            thread_id_clone,
-            run_id_clone,
-        )));
-
-        // Add hook to forward events (capture hook ID for cleanup)
+        let output_schemas = agent
+            .list_tools()
+            .into_iter()
+            .filter_map(|tool| Some((tool.name, tool.output_schema?)))
+            .collect();
+        let ctx = Arc::new(parking_lot::Mutex::new(
+            ConversionContext::new(thread_id_clone, run_id_clone)
+                .with_output_schemas(output_schemas)
+                .with_redact_params(redact_params)
+                .with_max_payload_bytes(max_payload_bytes),
+        ));
+
+        // Add hook to forward events, removed when the run ends
         let ctx_for_hook = ctx.clone();
         let tx_for_hook = tx_for_task.clone();
         let hook_id = agent.add_hook(move |event: &AgentEvent| {
@@ -113,6 +152,10 @@ pub async fn agui_handler(
                 let _ = tx_for_hook.try_send(agui_event);
             }
         });
+        let _hook = RemoveHookOnDrop {
+            agent: agent.clone(),
+            hook_id,
+        };
 
         // Run the agent
         match agent.run(&message).await {
@@ -126,13 +169,13 @@ pub async fn agui_handler(
                 });
             }
         }
-
-        // Clean up: remove the hook after the run completes
-        agent.remove_hook(hook_id);
     });
+    let abort_run = AbortOnDrop(run.abort_handle());
 
-    // Convert channel to SSE stream
-    let stream = ReceiverStream::new(rx).map(|event| {
+    // Convert channel to SSE stream. The stream owns the abort guard, so the
+    // run is cancelled when axum drops the stream on client disconnect.
+    let stream = ReceiverStream::new(rx).map(move |event| {
+        let _ = &abort_run;
         let json = serde_json::to_string(&event).unwrap_or_else(|e| {
             serde_json::json!({
                 "type": "RUN_ERROR",
@@ -143,7 +186,8 @@ pub async fn agui_handler(
         Ok::<_, Infallible>(Event::default().data(json))
     });
 
-    Sse::new(stream).keep_alive(KeepAlive::default())
+    // Keep-alive comments stop with the stream when the run ends
+    Sse::new(stream).keep_alive(keep_alive)
 }
 
 /// Handle interrupt responses (permission decisions).
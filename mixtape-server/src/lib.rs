@@ -39,6 +39,7 @@ pub(crate) mod agui;
 // Re-exports
 pub use error::{BuildError, ServerError, ServerResult};
 pub use router::MixtapeRouter;
+pub use state::DEFAULT_SSE_KEEPALIVE;
 
 // AG-UI protocol types (for consumers who need to reference the event types)
 #[cfg(feature = "agui")]
@@ -1,12 +1,15 @@
 //! Router builder for mixtape HTTP endpoints.
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use axum::Router;
 use mixtape_core::Agent;
 
+#[cfg(feature = "agui")]
+use crate::agui::convert::RedactParams;
 use crate::error::BuildError;
-use crate::state::AppState;
+use crate::state::{AppState, DEFAULT_SSE_KEEPALIVE};
 
 /// Builder for configuring mixtape HTTP endpoints.
 ///
@@ -27,10 +30,15 @@ use crate::state::AppState;
 /// ```
 pub struct MixtapeRouter {
     agent: Arc<Agent>,
+    sse_keepalive: Duration,
     #[cfg(feature = "agui")]
     agui_path: Option<String>,
     #[cfg(feature = "agui")]
     interrupt_path: Option<String>,
+    #[cfg(feature = "agui")]
+    redact_params: Option<RedactParams>,
+    #[cfg(feature = "agui")]
+    max_payload_bytes: Option<usize>,
 }
 
 impl MixtapeRouter {
@@ -40,10 +48,15 @@ impl MixtapeRouter {
     pub fn new(agent: Agent) -> Self {
         Self {
             agent: Arc::new(agent),
+            sse_keepalive: DEFAULT_SSE_KEEPALIVE,
             #[cfg(feature = "agui")]
             agui_path: None,
             #[cfg(feature = "agui")]
             interrupt_path: None,
+            #[cfg(feature = "agui")]
+            redact_params: None,
+            #[cfg(feature = "agui")]
+            max_payload_bytes: None,
         }
     }
 
@@ -53,10 +66,15 @@ impl MixtapeRouter {
     pub fn from_arc(agent: Arc<Agent>) -> Self {
         Self {
             agent,
+            sse_keepalive: DEFAULT_SSE_KEEPALIVE,
             #[cfg(feature = "agui")]
             agui_path: None,
             #[cfg(feature = "agui")]
             interrupt_path: None,
+            #[cfg(feature = "agui")]
+            redact_params: None,
+            #[cfg(feature = "agui")]
+            max_payload_bytes: None,
         }
     }
 
@@ -112,6 +130,81 @@ impl MixtapeRouter {
         self
     }
 
+    /// Rewrite tool parameters before they are sent to AG-UI clients.
+    ///
+    /// The hook receives the tool name and its input, and its result replaces
+    /// the input in `TOOL_CALL_ARGS` and tool approval `INTERRUPT` events.
+    /// Use it to mask secrets the frontend should not see. The agent still
+    /// runs the tool with the original input.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use mixtape_server::MixtapeRouter;
+    /// # use mixtape_core::Agent;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let agent: Agent = todo!();
+    /// let app = MixtapeRouter::new(agent)
+    ///     .with_agui("/api/copilotkit")
+    ///     .redact_params(|_tool, params| {
+    ///         let mut params = params.clone();
+    ///         if let Some(key) = params.get_mut("api_key") {
+    ///             *key = "[REDACTED]".into();
+    ///         }
+    ///         params
+    ///     })
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "agui")]
+    pub fn redact_params(
+        mut self,
+        redact: impl Fn(&str, &serde_json::Value) -> serde_json::Value + Send + Sync + 'static,
+    ) -> Self {
+        self.redact_params = Some(Arc::new(redact));
+        self
+    }
+
+    /// Cap the size of tool args and results in AG-UI events.
+    ///
+    /// `TOOL_CALL_ARGS` deltas and `TOOL_CALL_RESULT` content longer than
+    /// `max_bytes` are truncated and end with
+    /// [`TRUNCATION_MARKER`](crate::agui::convert::TRUNCATION_MARKER).
+    /// Unlimited by default.
+    #[cfg(feature = "agui")]
+    pub fn max_payload_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_payload_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Set how often idle SSE streams send a keep-alive comment.
+    ///
+    /// While the agent works without producing events, for example during a
+    /// long thinking phase, the stream sends `: keepalive` comment lines at
+    /// this interval so proxies and load balancers don't close it as idle.
+    /// Clients ignore comment lines. Defaults to 15 seconds.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use mixtape_server::MixtapeRouter;
+    /// # use mixtape_core::Agent;
+    /// # use std::time::Duration;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let agent: Agent = todo!();
+    /// let app = MixtapeRouter::new(agent)
+    ///     .with_agui("/api/copilotkit")
+    ///     .with_sse_keepalive(Duration::from_secs(5))
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_sse_keepalive(mut self, interval: Duration) -> Self {
+        self.sse_keepalive = interval;
+        self
+    }
+
     /// Build the router with all configured endpoints.
     ///
     /// Returns an axum `Router` that can be served directly or merged
@@ -132,7 +225,14 @@ impl MixtapeRouter {
             return Err(BuildError::NoEndpoints);
         }
 
-        let state = AppState::from_arc(self.agent);
+        let state = AppState {
+            sse_keepalive: self.sse_keepalive,
+            #[cfg(feature = "agui")]
+            redact_params: self.redact_params,
+            #[cfg(feature = "agui")]
+            max_payload_bytes: self.max_payload_bytes,
+            ..AppState::from_arc(self.agent)
+        };
         let mut router = Router::new();
 
         // Add AG-UI endpoints if enabled and configured
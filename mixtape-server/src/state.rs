@@ -1,9 +1,16 @@
 //! Application state for the mixtape server.
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use mixtape_core::Agent;
 
+#[cfg(feature = "agui")]
+use crate::agui::convert::RedactParams;
+
+/// Default interval between SSE keep-alive comments.
+pub const DEFAULT_SSE_KEEPALIVE: Duration = Duration::from_secs(15);
+
 /// Shared application state containing the agent.
 ///
 /// This state is cloned for each request handler and provides
@@ -12,11 +19,26 @@ use mixtape_core::Agent;
 pub struct AppState {
     /// The shared agent instance.
     pub agent: Arc<Agent>,
+    /// Interval between `: keepalive` comments on idle SSE streams.
+    pub sse_keepalive: Duration,
+    /// Hook applied to tool parameters in AG-UI events.
+    #[cfg(feature = "agui")]
+    pub redact_params: Option<RedactParams>,
+    /// Maximum size in bytes of tool args and results in AG-UI events.
+    #[cfg(feature = "agui")]
+    pub max_payload_bytes: Option<usize>,
 }
 
 impl AppState {
     /// Create new application state from an Arc<Agent>.
     pub fn from_arc(agent: Arc<Agent>) -> Self {
-        Self { agent }
+        Self {
+            agent,
+            sse_keepalive: DEFAULT_SSE_KEEPALIVE,
+            #[cfg(feature = "agui")]
+            redact_params: None,
+            #[cfg(feature = "agui")]
+            max_payload_bytes: None,
+        }
     }
 }
@@ -275,3 +275,143 @@ async fn test_invalid_request_body_returns_error() {
     let response = app.oneshot(request).await.unwrap();
     assert!(response.status().is_client_error());
 }
+
+// ============================================================================
+// Cancellation Tests
+// ============================================================================
+
+/// Tool that never finishes, reporting when it starts and when it is dropped
+struct HangingTool {
+    started: std::sync::Mutex<Option<tokio::sync::oneshot::Sender<()>>>,
+    dropped: std::sync::Mutex<Option<tokio::sync::oneshot::Sender<()>>>,
+}
+
+impl mixtape_core::Tool for HangingTool {
+    type Input = serde_json::Value;
+
+    fn name(&self) -> &str {
+        "hang"
+    }
+
+    fn description(&self) -> &str {
+        "Waits forever"
+    }
+
+    async fn execute(
+        &self,
+        _input: Self::Input,
+    ) -> Result<mixtape_core::ToolResult, mixtape_core::ToolError> {
+        // Dropped along with this future when the run is cancelled
+        let _dropped = self.dropped.lock().unwrap().take();
+        if let Some(started) = self.started.lock().unwrap().take() {
+            let _ = started.send(());
+        }
+        std::future::pending().await
+    }
+}
+
+#[tokio::test]
+async fn test_client_disconnect_cancels_run() {
+    let (started_tx, started_rx) = tokio::sync::oneshot::channel();
+    let (dropped_tx, dropped_rx) = tokio::sync::oneshot::channel::<()>();
+    let provider = MockProvider::new().with_tool_use("hang", serde_json::json!({}));
+    let agent = Agent::builder()
+        .provider(provider)
+        .add_trusted_tool(HangingTool {
+            started: std::sync::Mutex::new(Some(started_tx)),
+            dropped: std::sync::Mutex::new(Some(dropped_tx)),
+        })
+        .build()
+        .await
+        .unwrap();
+    let app = MixtapeRouter::new(agent)
+        .with_agui("/api/copilotkit")
+        .build()
+        .unwrap();
+
+    let response = app.oneshot(sse_request("Hi")).await.unwrap();
+    tokio::time::timeout(std::time::Duration::from_secs(5), started_rx)
+        .await
+        .expect("tool should start")
+        .unwrap();
+
+    // The client goes away mid-run
+    drop(response);
+
+    let dropped = tokio::time::timeout(std::time::Duration::from_secs(5), dropped_rx).await;
+    assert!(
+        matches!(dropped, Ok(Err(_))),
+        "run should be cancelled when the SSE stream is dropped"
+    );
+}
+
+// ============================================================================
+// Keep-alive Tests
+// ============================================================================
+
+/// Tool that takes a while, leaving the stream idle
+struct SlowTool;
+
+impl mixtape_core::Tool for SlowTool {
+    type Input = serde_json::Value;
+
+    fn name(&self) -> &str {
+        "slow"
+    }
+
+    fn description(&self) -> &str {
+        "Takes its time"
+    }
+
+    async fn execute(
+        &self,
+        _input: Self::Input,
+    ) -> Result<mixtape_core::ToolResult, mixtape_core::ToolError> {
+        tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+        Ok(mixtape_core::ToolResult::text("done"))
+    }
+}
+
+#[tokio::test]
+async fn test_sse_keepalive_during_slow_run() {
+    let provider = MockProvider::new()
+        .with_tool_use("slow", serde_json::json!({}))
+        .with_text("Finished");
+    let agent = Agent::builder()
+        .provider(provider)
+        .add_trusted_tool(SlowTool)
+        .build()
+        .await
+        .unwrap();
+    let app = MixtapeRouter::new(agent)
+        .with_agui("/api/copilotkit")
+        .with_sse_keepalive(std::time::Duration::from_millis(50))
+        .build()
+        .unwrap();
+
+    let response = app.oneshot(sse_request("Hi")).await.unwrap();
+    // The body ends with the run, so heartbeats don't outlive it
+    let bytes = tokio::time::timeout(
+        std::time::Duration::from_secs(5),
+        axum::body::to_bytes(response.into_body(), usize::MAX),
+    )
+    .await
+    .expect("stream should end when the run ends")
+    .unwrap();
+    let text = String::from_utf8_lossy(&bytes);
+
+    // Heartbeats are whole comment frames between events
+    let frames: Vec<&str> = text.split("\n\n").filter(|f| !f.is_empty()).collect();
+    let heartbeats = frames.iter().filter(|f| **f == ": keepalive").count();
+    assert!(heartbeats >= 2, "expected heartbeats, got: {}", text);
+
+    let events: Vec<String> = frames
+        .iter()
+        .filter(|f| **f != ": keepalive")
+        .map(|f| f.strip_prefix("data: ").expect("event frame").to_string())
+        .collect();
+    let event_types = extract_event_types(&events);
+    assert_eq!(event_types.len(), events.len(), "every event is valid JSON");
+    assert_eq!(event_types.first().unwrap(), "RUN_STARTED");
+    assert_eq!(event_types.last().unwrap(), "RUN_FINISHED");
+}
@@ -1,4 +1,4 @@
-use crate::filesystem::validate_path;
+use crate::filesystem::validate_write_path;
 use crate::prelude::*;
 use std::path::PathBuf;
 use strsim::normalized_levenshtein;
@@ -52,6 +52,7 @@ struct FuzzyMatch {
 /// Tool for surgical code editing with exact and fuzzy string replacement
 pub struct EditBlockTool {
     base_path: PathBuf,
+    write_base_path: Option<PathBuf>,
 }
 
 impl Default for EditBlockTool {
@@ -65,12 +66,25 @@ impl EditBlockTool {
     pub fn new() -> Self {
         Self {
             base_path: std::env::current_dir().expect("Failed to get current working directory"),
+            write_base_path: None,
         }
     }
 
     /// Create an EditBlockTool with a custom base directory
     pub fn with_base_path(base_path: PathBuf) -> Self {
-        Self { base_path }
+        Self {
+            base_path,
+            write_base_path: None,
+        }
+    }
+
+    /// Confines edits to a narrower directory than the base path.
+    ///
+    /// Relative paths still resolve against the base path, but the result
+    /// must lie within `write_base_path`. See [`validate_write_path`].
+    pub fn with_write_base_path(mut self, write_base_path: PathBuf) -> Self {
+        self.write_base_path = Some(write_base_path);
+        self
     }
 
     /// Find the best fuzzy match for a pattern in text
@@ -152,9 +166,16 @@ impl Tool for EditBlockTool {
         "Edit a file by replacing text. Supports exact matching with fallback to fuzzy matching. Preserves file line endings."
     }
 
+    fn is_destructive(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, input: Self::Input) -> std::result::Result<ToolResult, ToolError> {
-        let path = validate_path(&self.base_path, &input.file_path)
-            .map_err(|e| ToolError::from(e.to_string()))?;
+        let path = validate_write_path(
+            &self.base_path,
+            self.write_base_path.as_deref(),
+            &input.file_path,
+        )?;
 
         // Read the file
         let content = tokio::fs::read_to_string(&path)
@@ -399,6 +420,35 @@ mod tests {
         assert_eq!(content, "Hello, Rust!\nThis is a test.");
     }
 
+    #[tokio::test]
+    async fn test_edit_block_confined_to_write_base() {
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("output");
+        fs::create_dir(&output).unwrap();
+        fs::write(temp_dir.path().join("src.txt"), "Hello, World!").unwrap();
+        fs::write(output.join("out.txt"), "Hello, World!").unwrap();
+
+        let tool = EditBlockTool::with_base_path(temp_dir.path().to_path_buf())
+            .with_write_base_path(output.clone());
+        let edit = |file_path: &str| EditBlockInput {
+            file_path: PathBuf::from(file_path),
+            old_string: "World".to_string(),
+            new_string: "Rust".to_string(),
+            expected_replacements: 1,
+            enable_fuzzy: false,
+            fuzzy_threshold: 0.7,
+        };
+
+        // Readable under the base path, but outside the write base
+        assert!(tool.execute(edit("src.txt")).await.is_err());
+        let content = fs::read_to_string(temp_dir.path().join("src.txt")).unwrap();
+        assert_eq!(content, "Hello, World!");
+
+        tool.execute(edit("output/out.txt")).await.unwrap();
+        let content = fs::read_to_string(output.join("out.txt")).unwrap();
+        assert_eq!(content, "Hello, Rust!");
+    }
+
     #[tokio::test]
     async fn test_edit_block_fuzzy() {
         let temp_dir = TempDir::new().unwrap();
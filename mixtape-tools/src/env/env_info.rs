@@ -0,0 +1,146 @@
+use crate::prelude::*;
+use std::collections::BTreeMap;
+
+/// Input for reading environment info (no parameters needed)
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct EnvInfoInput {}
+
+/// Structured environment info returned by [`EnvInfoTool`]
+#[derive(Debug, Serialize)]
+struct EnvInfo {
+    os: &'static str,
+    family: &'static str,
+    arch: &'static str,
+    cwd: Option<String>,
+    /// Allowlisted variables; `null` when the variable is unset
+    env: BTreeMap<String, Option<String>>,
+}
+
+/// Tool for reporting OS, architecture, working directory, and allowlisted
+/// environment variables
+///
+/// Only variables named with [`allow_env`](Self::allow_env) are ever read,
+/// so the agent can learn things like locale or timezone without shelling
+/// out to `env` and seeing credentials.
+///
+/// ```
+/// use mixtape_tools::env::EnvInfoTool;
+///
+/// let tool = EnvInfoTool::new().allow_env(["LANG", "TZ"]);
+/// ```
+#[derive(Debug, Default)]
+pub struct EnvInfoTool {
+    allowed_env: Vec<String>,
+}
+
+impl EnvInfoTool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add environment variable names the tool may report
+    pub fn allow_env<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        for name in names {
+            let name = name.into();
+            if !self.allowed_env.contains(&name) {
+                self.allowed_env.push(name);
+            }
+        }
+        self
+    }
+
+    fn collect(&self) -> EnvInfo {
+        EnvInfo {
+            os: std::env::consts::OS,
+            family: std::env::consts::FAMILY,
+            arch: std::env::consts::ARCH,
+            cwd: std::env::current_dir()
+                .ok()
+                .map(|p| p.display().to_string()),
+            env: self
+                .allowed_env
+                .iter()
+                .map(|name| (name.clone(), std::env::var(name).ok()))
+                .collect(),
+        }
+    }
+}
+
+impl Tool for EnvInfoTool {
+    type Input = EnvInfoInput;
+
+    fn name(&self) -> &str {
+        "env_info"
+    }
+
+    fn description(&self) -> &str {
+        "Get the operating system, CPU architecture, current working directory, and the values of an allowlisted set of environment variables. Variables outside the allowlist are never returned."
+    }
+
+    async fn execute(&self, _input: Self::Input) -> std::result::Result<ToolResult, ToolError> {
+        Ok(ToolResult::Json(serde_json::to_value(self.collect())?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_returns_only_allowlisted_vars() {
+        std::env::set_var("MIXTAPE_ENV_INFO_ALLOWED", "visible");
+        std::env::set_var("MIXTAPE_ENV_INFO_SECRET", "hidden");
+
+        let tool = EnvInfoTool::new().allow_env(["MIXTAPE_ENV_INFO_ALLOWED"]);
+        let result = tool.execute(EnvInfoInput {}).await.unwrap();
+
+        let ToolResult::Json(json) = result else {
+            panic!("expected JSON result");
+        };
+        let env = json["env"].as_object().unwrap();
+        assert_eq!(env.len(), 1);
+        assert_eq!(env["MIXTAPE_ENV_INFO_ALLOWED"], "visible");
+        assert!(!env.contains_key("MIXTAPE_ENV_INFO_SECRET"));
+        assert!(!json.to_string().contains("hidden"));
+    }
+
+    #[tokio::test]
+    async fn test_reports_system_info_and_unset_vars() {
+        let tool = EnvInfoTool::new().allow_env(["MIXTAPE_ENV_INFO_UNSET"]);
+        let result = tool.execute(EnvInfoInput {}).await.unwrap();
+
+        let ToolResult::Json(json) = result else {
+            panic!("expected JSON result");
+        };
+        assert_eq!(json["os"], std::env::consts::OS);
+        assert_eq!(json["arch"], std::env::consts::ARCH);
+        assert_eq!(
+            json["cwd"],
+            std::env::current_dir().unwrap().display().to_string()
+        );
+        assert!(json["env"]["MIXTAPE_ENV_INFO_UNSET"].is_null());
+    }
+
+    #[test]
+    fn test_allow_env_accumulates_without_duplicates() {
+        let tool = EnvInfoTool::new()
+            .allow_env(["LANG", "TZ"])
+            .allow_env(vec!["TZ".to_string(), "HOME".to_string()]);
+
+        assert_eq!(tool.allowed_env, vec!["LANG", "TZ", "HOME"]);
+    }
+
+    #[tokio::test]
+    async fn test_no_allowlist_returns_empty_env() {
+        let result = EnvInfoTool::new().execute(EnvInfoInput {}).await.unwrap();
+
+        let ToolResult::Json(json) = result else {
+            panic!("expected JSON result");
+        };
+        assert!(json["env"].as_object().unwrap().is_empty());
+    }
+}
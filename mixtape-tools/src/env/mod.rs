@@ -0,0 +1,4 @@
+// Environment inspection tools
+mod env_info;
+
+pub use env_info::EnvInfoTool;
@@ -1,11 +1,30 @@
 use crate::prelude::*;
+use crate::utils::sanitize_error;
 use html2md::parse_html;
 use readability_rust::Readability;
 use reqwest::Client;
 use robotstxt::DefaultMatcher;
+use scraper::{ElementRef, Html, Node};
 use std::time::Duration;
 use url::Url;
 
+/// Upper bound on rendered text or Markdown, in bytes, before pagination
+const MAX_RENDERED_BYTES: usize = 512 * 1024;
+
+/// How the response body is rendered for the model
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExtractMode {
+    /// No conversion: the main-content HTML Readability picks out of a page
+    /// (the whole page if it finds none), or a JSON body as served
+    Raw,
+    /// HTML stripped to readable plain text; JSON compacted
+    Text,
+    /// HTML converted to Markdown; JSON pretty-printed
+    #[default]
+    Markdown,
+}
+
 /// Input for fetching web content
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct FetchInput {
@@ -20,10 +39,14 @@ pub struct FetchInput {
     #[serde(default)]
     pub start_index: Option<usize>,
 
-    /// Return raw HTML instead of Markdown (default: false)
+    /// Return raw HTML instead of Markdown (default: false). Same as `extract: "raw"`
     #[serde(default)]
     pub raw: bool,
 
+    /// Output rendering: "raw", "text", or "markdown" (default: "markdown")
+    #[serde(default)]
+    pub extract: Option<ExtractMode>,
+
     /// Force fetch even if robots.txt disallows (default: false, use with caution)
     #[serde(default)]
     pub force: bool,
@@ -126,6 +149,29 @@ impl FetchTool {
         parse_html(html)
     }
 
+    /// Convert HTML to plain text, dropping tags, scripts, and styles
+    fn html_to_text(&self, html: &str) -> String {
+        let document = Html::parse_document(html);
+        let mut text = String::new();
+        collect_text(document.root_element(), false, &mut text);
+        normalize_lines(&text)
+    }
+
+    /// Render a JSON body: compact for text, a pretty-printed code block for Markdown
+    fn render_json(&self, body: &str, mode: ExtractMode) -> String {
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(body) else {
+            return body.to_string();
+        };
+        match mode {
+            ExtractMode::Raw => body.to_string(),
+            ExtractMode::Text => value.to_string(),
+            ExtractMode::Markdown => format!(
+                "```json\n{}\n```",
+                serde_json::to_string_pretty(&value).unwrap_or_else(|_| body.to_string())
+            ),
+        }
+    }
+
     /// Paginate content
     fn paginate_content(
         &self,
@@ -140,8 +186,9 @@ impl FetchTool {
             return (String::new(), false, total_length);
         }
 
+        let start = floor_char_boundary(&content, start);
         if let Some(max_len) = max_length {
-            let end = (start + max_len).min(total_length);
+            let end = floor_char_boundary(&content, (start + max_len).min(total_length));
             let truncated_content = content[start..end].to_string();
             let is_truncated = end < total_length;
             (truncated_content, is_truncated, total_length)
@@ -166,7 +213,8 @@ impl Tool for FetchTool {
     }
 
     fn description(&self) -> &str {
-        "Fetch content from a URL with robots.txt compliance, content extraction, and Markdown conversion. \
+        "Fetch content from a URL with robots.txt compliance and content extraction. \
+         Renders HTML as Markdown (default), plain text, or raw; JSON is pretty-printed or compacted. \
          Supports pagination for large documents."
     }
 
@@ -186,7 +234,7 @@ impl Tool for FetchTool {
             let icon = match *key {
                 "URL" => "[>]",
                 "Title" => "[#]",
-                "Content Length" => "[=]",
+                "Rendered Length" => "[=]",
                 "Showing" => "[~]",
                 _ => "   ",
             };
@@ -214,7 +262,7 @@ impl Tool for FetchTool {
             let (icon, color) = match *key {
                 "URL" => ("\x1b[34m󰖟\x1b[0m", "\x1b[34m"),
                 "Title" => ("\x1b[33m󰉹\x1b[0m", "\x1b[1m"),
-                "Content Length" => ("\x1b[32m󰋊\x1b[0m", "\x1b[32m"),
+                "Rendered Length" => ("\x1b[32m󰋊\x1b[0m", "\x1b[32m"),
                 "Showing" => ("\x1b[36m󰦨\x1b[0m", "\x1b[36m"),
                 _ => ("  ", "\x1b[0m"),
             };
@@ -260,21 +308,23 @@ impl Tool for FetchTool {
 
     async fn execute(&self, input: Self::Input) -> std::result::Result<ToolResult, ToolError> {
         // Parse URL
-        let url =
-            Url::parse(&input.url).map_err(|e| ToolError::from(format!("Invalid URL: {}", e)))?;
+        let url = Url::parse(&input.url)
+            .map_err(|e| ToolError::from(sanitize_error(&format!("Invalid URL: {}", e))))?;
 
         // Check robots.txt compliance unless force is set
         if !input.force {
             let allowed = self
                 .check_robots_txt(&url, &input.user_agent)
                 .await
-                .map_err(|e| ToolError::from(format!("Robots.txt check failed: {}", e)))?;
+                .map_err(|e| {
+                    ToolError::from(sanitize_error(&format!("Robots.txt check failed: {}", e)))
+                })?;
 
             if !allowed {
-                return Err(format!(
+                return Err(sanitize_error(&format!(
                     "Access to {} is disallowed by robots.txt for user-agent '{}'",
                     input.url, input.user_agent
-                )
+                ))
                 .into());
             }
         }
@@ -289,7 +339,7 @@ impl Tool for FetchTool {
         )
         .await
         .map_err(|_| format!("Request timed out after {} seconds", input.timeout_seconds))?
-        .map_err(|e| ToolError::from(format!("Failed to fetch URL: {}", e)))?;
+        .map_err(|e| ToolError::from(sanitize_error(&format!("Failed to fetch URL: {}", e))))?;
 
         // Check response status
         if !response.status().is_success() {
@@ -301,26 +351,59 @@ impl Tool for FetchTool {
             .into());
         }
 
-        // Get the HTML content
-        let html = response
-            .text()
-            .await
-            .map_err(|e| ToolError::from(format!("Failed to read response body: {}", e)))?;
+        let is_json = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(is_json_content_type);
+
+        // Get the response body
+        let body = response.text().await.map_err(|e| {
+            ToolError::from(sanitize_error(&format!(
+                "Failed to read response body: {}",
+                e
+            )))
+        })?;
+
+        let mode = input.extract.unwrap_or(if input.raw {
+            ExtractMode::Raw
+        } else {
+            ExtractMode::Markdown
+        });
 
-        // Extract main content and title using Readability
-        let (title, content_html) = self.extract_content(&html, &input.url);
+        let (title, processed_content) = if is_json {
+            (None, self.render_json(&body, mode))
+        } else {
+            // Extract main content and title using Readability
+            let (title, content_html) = self.extract_content(&body, &input.url);
+            let rendered = match mode {
+                ExtractMode::Raw => content_html,
+                ExtractMode::Text => self.html_to_text(&content_html),
+                ExtractMode::Markdown => self.html_to_markdown(&content_html),
+            };
+            (title, rendered)
+        };
 
-        // Convert to markdown unless raw is requested
-        let processed_content = if input.raw {
-            content_html
+        let rendered_bytes = processed_content.len();
+        let processed_content = if mode == ExtractMode::Raw {
+            processed_content
         } else {
-            self.html_to_markdown(&content_html)
+            truncate_to_bytes(processed_content, MAX_RENDERED_BYTES)
         };
+        let kept_bytes = processed_content.len();
 
         // Apply pagination
-        let (final_content, is_truncated, total_length) =
+        let (mut final_content, is_truncated, total_length) =
             self.paginate_content(processed_content, input.start_index, input.max_length);
 
+        // Mark where the rendered content was cut, once the page reaches it
+        if kept_bytes < rendered_bytes && !is_truncated {
+            final_content.push_str(&format!(
+                "\n\n[truncated: {} of {} bytes]",
+                kept_bytes, rendered_bytes
+            ));
+        }
+
         // Format result
         let mut result = String::new();
         result.push_str(&format!("URL: {}\n", input.url));
@@ -329,7 +412,7 @@ impl Tool for FetchTool {
             result.push_str(&format!("Title: {}\n", page_title.trim()));
         }
 
-        result.push_str(&format!("Content Length: {} characters\n", total_length));
+        result.push_str(&format!("Rendered Length: {} characters\n", total_length));
 
         if is_truncated {
             let start = input.start_index.unwrap_or(0);
@@ -347,6 +430,126 @@ impl Tool for FetchTool {
     }
 }
 
+/// Whether a Content-Type header names a JSON body
+fn is_json_content_type(content_type: &str) -> bool {
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase();
+    mime == "application/json" || mime.ends_with("+json")
+}
+
+/// Elements whose content is never readable text
+const SKIPPED_ELEMENTS: &[&str] = &[
+    "head", "script", "style", "noscript", "template", "svg", "iframe",
+];
+
+/// Elements that start on a new line
+const BLOCK_ELEMENTS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "br",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "li",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "tr",
+    "ul",
+];
+
+/// Append the text of `element` to `out`, breaking lines around block elements
+fn collect_text(element: ElementRef, in_pre: bool, out: &mut String) {
+    let name = element.value().name();
+    if SKIPPED_ELEMENTS.contains(&name) {
+        return;
+    }
+    let is_block = BLOCK_ELEMENTS.contains(&name);
+    let in_pre = in_pre || name == "pre";
+
+    if is_block {
+        out.push('\n');
+    }
+    for child in element.children() {
+        match child.value() {
+            Node::Text(text) if in_pre => out.push_str(text),
+            Node::Text(text) => {
+                let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                if collapsed.is_empty() {
+                    if !text.is_empty() && !out.ends_with([' ', '\n']) {
+                        out.push(' ');
+                    }
+                    continue;
+                }
+                if text.starts_with(char::is_whitespace) && !out.ends_with([' ', '\n']) {
+                    out.push(' ');
+                }
+                out.push_str(&collapsed);
+                if text.ends_with(char::is_whitespace) {
+                    out.push(' ');
+                }
+            }
+            Node::Element(_) => {
+                if let Some(child) = ElementRef::wrap(child) {
+                    collect_text(child, in_pre, out);
+                }
+            }
+            _ => {}
+        }
+    }
+    if is_block {
+        out.push('\n');
+    }
+}
+
+/// Trim each line and drop blank ones
+fn normalize_lines(text: &str) -> String {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Largest char boundary in `s` at or below `index`
+fn floor_char_boundary(s: &str, index: usize) -> usize {
+    let mut index = index.min(s.len());
+    while !s.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+/// Cut `content` to at most `max_bytes`, on a char boundary
+fn truncate_to_bytes(mut content: String, max_bytes: usize) -> String {
+    let end = floor_char_boundary(&content, max_bytes);
+    content.truncate(end);
+    content
+}
+
 /// Parse fetch output header into metadata fields
 fn parse_fetch_header(output: &str) -> (Vec<(&str, &str)>, &str) {
     let mut metadata = Vec::new();
@@ -395,6 +598,7 @@ mod tests {
             user_agent: "test-agent".to_string(),
             timeout_seconds: 30,
             raw: false,
+            extract: None,
             force: false,
             start_index: None,
             max_length: None,
@@ -444,13 +648,13 @@ mod tests {
 
     #[test]
     fn test_parse_fetch_header_complete() {
-        let output = "URL: https://example.com\nTitle: Test Page\nContent Length: 1000 characters\nShowing: characters 0-500 (truncated)\n\n---\n\nThis is the content.";
+        let output = "URL: https://example.com\nTitle: Test Page\nRendered Length: 1000 characters\nShowing: characters 0-500 (truncated)\n\n---\n\nThis is the content.";
         let (metadata, content) = parse_fetch_header(output);
 
         assert_eq!(metadata.len(), 4);
         assert_eq!(metadata[0], ("URL", "https://example.com"));
         assert_eq!(metadata[1], ("Title", "Test Page"));
-        assert_eq!(metadata[2], ("Content Length", "1000 characters"));
+        assert_eq!(metadata[2], ("Rendered Length", "1000 characters"));
         assert_eq!(metadata[3], ("Showing", "characters 0-500 (truncated)"));
         assert!(content.contains("This is the content"));
     }
@@ -540,7 +744,7 @@ mod tests {
     #[test]
     fn test_format_output_plain_with_metadata() {
         let tool = FetchTool::new();
-        let result: ToolResult = "URL: https://example.com\nTitle: Test\nContent Length: 100 characters\n\n---\n\nContent here".into();
+        let result: ToolResult = "URL: https://example.com\nTitle: Test\nRendered Length: 100 characters\n\n---\n\nContent here".into();
 
         let formatted = tool.format_output_plain(&result);
 
@@ -557,14 +761,14 @@ mod tests {
     #[test]
     fn test_format_output_plain_icons() {
         let tool = FetchTool::new();
-        let result: ToolResult = "URL: https://example.com\nTitle: Test Title\nContent Length: 500 characters\nShowing: 0-100\n\n---\n\nBody".into();
+        let result: ToolResult = "URL: https://example.com\nTitle: Test Title\nRendered Length: 500 characters\nShowing: 0-100\n\n---\n\nBody".into();
 
         let formatted = tool.format_output_plain(&result);
 
         // Check icons for different metadata types
         assert!(formatted.contains("[>]")); // URL
         assert!(formatted.contains("[#]")); // Title
-        assert!(formatted.contains("[=]")); // Content Length
+        assert!(formatted.contains("[=]")); // Rendered Length
         assert!(formatted.contains("[~]")); // Showing
     }
 
@@ -595,7 +799,7 @@ mod tests {
     #[test]
     fn test_format_output_ansi_colors() {
         let tool = FetchTool::new();
-        let result: ToolResult = "URL: https://example.com\nTitle: Test\nContent Length: 100 characters\nShowing: 0-50\n\n---\n\nBody".into();
+        let result: ToolResult = "URL: https://example.com\nTitle: Test\nRendered Length: 100 characters\nShowing: 0-50\n\n---\n\nBody".into();
 
         let formatted = tool.format_output_ansi(&result);
 
@@ -603,7 +807,7 @@ mod tests {
         assert!(formatted.contains("\x1b[34m"));
         // Title should be bold
         assert!(formatted.contains("\x1b[1m"));
-        // Content Length should be green
+        // Rendered Length should be green
         assert!(formatted.contains("\x1b[32m"));
         // Showing should be cyan
         assert!(formatted.contains("\x1b[36m"));
@@ -636,14 +840,14 @@ mod tests {
     fn test_format_output_markdown_metadata_as_list() {
         let tool = FetchTool::new();
         let result: ToolResult =
-            "URL: https://example.com\nTitle: Test\nContent Length: 500 characters\n\n---\n\nBody"
+            "URL: https://example.com\nTitle: Test\nRendered Length: 500 characters\n\n---\n\nBody"
                 .into();
 
         let formatted = tool.format_output_markdown(&result);
 
         // Non-title metadata should be in list format with bold keys
         assert!(formatted.contains("- **URL**: https://example.com"));
-        assert!(formatted.contains("- **Content Length**: 500 characters"));
+        assert!(formatted.contains("- **Rendered Length**: 500 characters"));
         // Title should NOT be in the list (it's a heading)
         assert!(!formatted.contains("- **Title**"));
     }
@@ -851,6 +1055,126 @@ mod tests {
         assert!(output.contains("<h1>") || output.contains("Raw HTML"));
     }
 
+    #[test]
+    fn test_html_to_text_strips_tags() {
+        let tool = FetchTool::new();
+        let html = r#"
+            <html>
+                <head><title>Ignored</title><style>p { color: red; }</style></head>
+                <body>
+                    <h1>Heading</h1>
+                    <p>First <strong>bold</strong>   paragraph.</p>
+                    <script>alert("hi")</script>
+                    <ul><li>One</li><li>Two</li></ul>
+                </body>
+            </html>
+        "#;
+
+        let text = tool.html_to_text(html);
+
+        assert_eq!(text, "Heading\nFirst bold paragraph.\nOne\nTwo");
+    }
+
+    #[test]
+    fn test_render_json_modes() {
+        let tool = FetchTool::new();
+        let body = r#"{ "a": [1, 2],  "b": "x" }"#;
+
+        assert_eq!(tool.render_json(body, ExtractMode::Raw), body);
+        assert_eq!(
+            tool.render_json(body, ExtractMode::Text),
+            r#"{"a":[1,2],"b":"x"}"#
+        );
+        let markdown = tool.render_json(body, ExtractMode::Markdown);
+        assert!(markdown.starts_with("```json\n{\n  \"a\""));
+        assert!(markdown.ends_with("\n```"));
+        // Invalid JSON is passed through untouched
+        assert_eq!(tool.render_json("{oops", ExtractMode::Text), "{oops");
+    }
+
+    #[test]
+    fn test_is_json_content_type() {
+        assert!(is_json_content_type("application/json"));
+        assert!(is_json_content_type("application/json; charset=utf-8"));
+        assert!(is_json_content_type("application/problem+json"));
+        assert!(!is_json_content_type("text/html"));
+    }
+
+    #[test]
+    fn test_truncate_to_bytes_respects_char_boundaries() {
+        assert_eq!(truncate_to_bytes("héllo".to_string(), 2), "h");
+        assert_eq!(truncate_to_bytes("héllo".to_string(), 3), "hé");
+        assert_eq!(truncate_to_bytes("abc".to_string(), 10), "abc");
+    }
+
+    #[test]
+    fn test_paginate_content_multibyte() {
+        let tool = FetchTool::new();
+        let (result, truncated, _) = tool.paginate_content("a—b".to_string(), None, Some(2));
+        assert_eq!(result, "a");
+        assert!(truncated);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_text_mode_strips_tags() {
+        let mock_server = MockServer::start().await;
+
+        let html_body = r#"
+            <html>
+                <head><title>Doc</title></head>
+                <body>
+                    <article>
+                        <h1>Release Notes</h1>
+                        <p>This release adds <em>text</em> extraction to the fetch tool.</p>
+                        <p>It also <a href="/json">handles JSON</a> bodies.</p>
+                    </article>
+                </body>
+            </html>
+        "#;
+
+        Mock::given(method("GET"))
+            .and(path("/text"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(html_body))
+            .mount(&mock_server)
+            .await;
+
+        let tool = FetchTool::new();
+        let mut input = test_input(format!("{}/text", mock_server.uri()));
+        input.extract = Some(ExtractMode::Text);
+
+        let result = tool.execute(input).await.unwrap();
+        let output = result.as_text();
+        let (_, content) = parse_fetch_header(&output);
+
+        assert!(content.contains("adds text extraction to the fetch tool."));
+        assert!(content.contains("It also handles JSON bodies."));
+        assert!(!content.contains('<'));
+        assert!(!content.contains('*'));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_json_is_pretty_printed() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/data"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_raw(r#"{"items":[{"id":1}]}"#, "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let tool = FetchTool::new();
+        let input = test_input(format!("{}/data", mock_server.uri()));
+
+        let result = tool.execute(input).await.unwrap();
+        let output = result.as_text();
+
+        assert!(output.contains("```json"));
+        assert!(output.contains("\"items\": ["));
+    }
+
     #[tokio::test]
     async fn test_fetch_with_pagination() {
         let mock_server = MockServer::start().await;
@@ -882,6 +1206,36 @@ mod tests {
         assert!(output.contains("Showing:") || output.contains("truncated"));
     }
 
+    #[tokio::test]
+    async fn test_fetch_marks_rendered_truncation() {
+        let mock_server = MockServer::start().await;
+        let body = serde_json::json!({ "s": "a".repeat(600_000) }).to_string();
+
+        Mock::given(method("GET"))
+            .and(path("/big"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(body.clone(), "application/json"))
+            .mount(&mock_server)
+            .await;
+
+        let tool = FetchTool::new();
+        let mut input = test_input(format!("{}/big", mock_server.uri()));
+        input.extract = Some(ExtractMode::Text);
+        input.max_length = None;
+
+        let result = tool.execute(input).await.unwrap();
+        let output = result.as_text();
+
+        assert!(output.contains(&format!(
+            "Rendered Length: {} characters\n",
+            MAX_RENDERED_BYTES
+        )));
+        assert!(output.ends_with(&format!(
+            "[truncated: {} of {} bytes]",
+            MAX_RENDERED_BYTES,
+            body.len()
+        )));
+    }
+
     #[tokio::test]
     async fn test_fetch_invalid_url() {
         let tool = FetchTool::new();
@@ -1,4 +1,4 @@
 // Web fetching tools
 mod fetch_tool;
 
-pub use fetch_tool::FetchTool;
+pub use fetch_tool::{ExtractMode, FetchTool};
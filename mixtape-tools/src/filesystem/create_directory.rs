@@ -1,4 +1,4 @@
-use crate::filesystem::validate_path;
+use crate::filesystem::validate_write_path;
 use crate::prelude::*;
 use std::path::PathBuf;
 use tokio::fs;
@@ -13,6 +13,7 @@ pub struct CreateDirectoryInput {
 /// Tool for creating directories
 pub struct CreateDirectoryTool {
     base_path: PathBuf,
+    write_base_path: Option<PathBuf>,
 }
 
 impl Default for CreateDirectoryTool {
@@ -33,6 +34,7 @@ impl CreateDirectoryTool {
     pub fn new() -> Self {
         Self {
             base_path: std::env::current_dir().expect("Failed to get current working directory"),
+            write_base_path: None,
         }
     }
 
@@ -42,6 +44,7 @@ impl CreateDirectoryTool {
     pub fn try_new() -> std::io::Result<Self> {
         Ok(Self {
             base_path: std::env::current_dir()?,
+            write_base_path: None,
         })
     }
 
@@ -49,7 +52,19 @@ impl CreateDirectoryTool {
     ///
     /// All file operations will be constrained to this directory.
     pub fn with_base_path(base_path: PathBuf) -> Self {
-        Self { base_path }
+        Self {
+            base_path,
+            write_base_path: None,
+        }
+    }
+
+    /// Confines changes to a narrower directory than the base path.
+    ///
+    /// Relative paths still resolve against the base path, but the result
+    /// must lie within `write_base_path`. See [`validate_write_path`].
+    pub fn with_write_base_path(mut self, write_base_path: PathBuf) -> Self {
+        self.write_base_path = Some(write_base_path);
+        self
     }
 }
 
@@ -65,8 +80,12 @@ impl Tool for CreateDirectoryTool {
     }
 
     async fn execute(&self, input: Self::Input) -> std::result::Result<ToolResult, ToolError> {
-        // Validate path is within base directory before creation
-        let validated_path = validate_path(&self.base_path, &input.path)?;
+        // Validate path is within the writable directory before creation
+        let validated_path = validate_write_path(
+            &self.base_path,
+            self.write_base_path.as_deref(),
+            &input.path,
+        )?;
 
         // Create the directory (and any missing parents)
         fs::create_dir_all(&validated_path)
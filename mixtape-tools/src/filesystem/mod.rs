@@ -12,6 +12,8 @@
 //! - Paths are resolved relative to `base_path` (or used directly if absolute)
 //! - The resolved path is canonicalized to eliminate `..`, `.`, and symlinks
 //! - The canonical path must start with the canonical `base_path`
+//! - On Windows, verbatim (`\\?\`) prefixes and drive-letter case are normalized
+//!   before that comparison
 //! - For non-existent paths, the nearest existing ancestor is validated instead
 //!
 //! This means symlinks that point outside `base_path` are rejected, and crafted
@@ -31,11 +33,28 @@
 //!
 //! These tools are one layer in a security stack, not a standalone sandbox.
 //!
+//! # Narrower Write Access
+//!
+//! [`WriteFileTool`], [`CreateDirectoryTool`], [`MoveFileTool`], and
+//! [`EditBlockTool`](crate::edit::EditBlockTool) accept a separate write base via `with_write_base_path`. Paths still resolve against
+//! `base_path`, so the agent uses the same paths with every tool, but changes
+//! must land inside the write base. This lets an agent read anywhere under
+//! `/project` while writing only under `/project/output`:
+//!
+//! ```no_run
+//! use mixtape_tools::filesystem::{ReadFileTool, WriteFileTool};
+//! use std::path::PathBuf;
+//!
+//! let read = ReadFileTool::with_base_path(PathBuf::from("/project"));
+//! let write = WriteFileTool::with_base_path(PathBuf::from("/project"))
+//!     .with_write_base_path(PathBuf::from("/project/output"));
+//! ```
+//!
 //! # Available Tools
 //!
 //! | Tool | Description |
 //! |------|-------------|
-//! | [`ReadFileTool`] | Read file contents with optional offset/limit |
+//! | [`ReadFileTool`] | Read file contents with optional offset/limit, head/tail, or grep |
 //! | [`ReadMultipleFilesTool`] | Read multiple files concurrently |
 //! | [`WriteFileTool`] | Write or append to files |
 //! | [`CreateDirectoryTool`] | Create directories (including parents) |
@@ -132,22 +151,22 @@ pub fn validate_path(base_path: &Path, target_path: &Path) -> Result<PathBuf, To
 
     // Try to canonicalize if the file exists
     if full_path.exists() {
-        let canonical = full_path.canonicalize().map_err(|e| {
+        let canonical = normalize_path(full_path.canonicalize().map_err(|e| {
             ToolError::PathValidation(format!(
                 "Failed to canonicalize '{}': {}",
                 full_path.display(),
                 e
             ))
-        })?;
+        })?);
 
         // Canonicalize base path for comparison
-        let canonical_base = base_path.canonicalize().map_err(|e| {
+        let canonical_base = normalize_path(base_path.canonicalize().map_err(|e| {
             ToolError::PathValidation(format!(
                 "Failed to canonicalize base path '{}': {}",
                 base_path.display(),
                 e
             ))
-        })?;
+        })?);
 
         if !canonical.starts_with(&canonical_base) {
             return Err(ToolError::PathValidation(format!(
@@ -177,21 +196,21 @@ pub fn validate_path(base_path: &Path, target_path: &Path) -> Result<PathBuf, To
         }
 
         // Canonicalize the existing ancestor and verify it's within base
-        let canonical_ancestor = check_path.canonicalize().map_err(|e| {
+        let canonical_ancestor = normalize_path(check_path.canonicalize().map_err(|e| {
             ToolError::PathValidation(format!(
                 "Failed to canonicalize ancestor '{}': {}",
                 check_path.display(),
                 e
             ))
-        })?;
+        })?);
 
-        let canonical_base = base_path.canonicalize().map_err(|e| {
+        let canonical_base = normalize_path(base_path.canonicalize().map_err(|e| {
             ToolError::PathValidation(format!(
                 "Failed to canonicalize base path '{}': {}",
                 base_path.display(),
                 e
             ))
-        })?;
+        })?);
 
         if !canonical_ancestor.starts_with(&canonical_base) {
             return Err(ToolError::PathValidation(format!(
@@ -206,6 +225,74 @@ pub fn validate_path(base_path: &Path, target_path: &Path) -> Result<PathBuf, To
     }
 }
 
+/// Normalize a canonicalized path so prefix differences don't affect comparison
+///
+/// On Windows, `canonicalize` may or may not return a verbatim (`\\?\`) path
+/// depending on the input, and [`Path::starts_with`] treats `\\?\C:\` and
+/// `C:\` as different prefixes. This rewrites verbatim disk and UNC prefixes to
+/// their ordinary form and upper-cases the drive letter. Other paths, and all
+/// paths on Unix, are returned unchanged.
+#[cfg(windows)]
+fn normalize_path(path: PathBuf) -> PathBuf {
+    use std::ffi::OsString;
+    use std::path::{Component, Prefix};
+
+    let mut components = path.components();
+    let Some(Component::Prefix(prefix)) = components.next() else {
+        return path;
+    };
+
+    let mut normalized = match prefix.kind() {
+        Prefix::VerbatimDisk(drive) | Prefix::Disk(drive) => {
+            OsString::from(format!("{}:", drive.to_ascii_uppercase() as char))
+        }
+        Prefix::VerbatimUNC(server, share) | Prefix::UNC(server, share) => {
+            let mut unc = OsString::from(r"\\");
+            unc.push(server);
+            unc.push(r"\");
+            unc.push(share);
+            unc
+        }
+        _ => return path,
+    };
+    normalized.push(components.as_path());
+    PathBuf::from(normalized)
+}
+
+#[cfg(not(windows))]
+fn normalize_path(path: PathBuf) -> PathBuf {
+    path
+}
+
+/// Validates the target of a mutating operation.
+///
+/// Relative paths resolve against `base_path`, as in [`validate_path`]. When
+/// `write_base_path` is set, the resolved path must lie within it instead of
+/// `base_path`, so writes can be confined more narrowly than reads.
+///
+/// # Example
+///
+/// ```
+/// use mixtape_tools::filesystem::validate_write_path;
+/// use std::path::Path;
+///
+/// let base = Path::new("/project");
+/// let output = Path::new("/project/output");
+///
+/// // Inside the base path, but outside the write base
+/// assert!(validate_write_path(base, Some(output), Path::new("src/main.rs")).is_err());
+/// ```
+pub fn validate_write_path(
+    base_path: &Path,
+    write_base_path: Option<&Path>,
+    target_path: &Path,
+) -> Result<PathBuf, ToolError> {
+    match write_base_path {
+        Some(write_base_path) => validate_path(write_base_path, &base_path.join(target_path)),
+        None => validate_path(base_path, target_path),
+    }
+}
+
 /// Returns all read-only filesystem tools
 ///
 /// These tools can read and inspect files but cannot modify the filesystem.
@@ -294,6 +381,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_validate_write_path_confines_to_write_base() {
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("output");
+        fs::create_dir(&output).unwrap();
+
+        // Relative paths resolve against the base path
+        let result = validate_write_path(
+            temp_dir.path(),
+            Some(&output),
+            Path::new("output/report.txt"),
+        );
+        assert!(result.unwrap().ends_with("output/report.txt"));
+
+        // Inside the base path but outside the write base
+        let result = validate_write_path(temp_dir.path(), Some(&output), Path::new("notes.txt"));
+        assert!(result.is_err());
+
+        // Without a write base, the base path applies
+        let result = validate_write_path(temp_dir.path(), None, Path::new("notes.txt"));
+        assert!(result.is_ok());
+    }
+
     #[test]
     fn test_validate_path_rejects_absolute_path_outside_base() {
         let temp_dir = TempDir::new().unwrap();
@@ -482,4 +592,93 @@ mod tests {
             err_msg
         );
     }
+
+    #[cfg(windows)]
+    mod windows {
+        use super::*;
+
+        #[test]
+        fn test_normalize_path_strips_verbatim_disk_prefix() {
+            assert_eq!(
+                normalize_path(PathBuf::from(r"\\?\c:\Users\dev\project")),
+                PathBuf::from(r"C:\Users\dev\project")
+            );
+            assert_eq!(
+                normalize_path(PathBuf::from(r"c:\Users\dev")),
+                PathBuf::from(r"C:\Users\dev")
+            );
+        }
+
+        #[test]
+        fn test_normalize_path_strips_verbatim_unc_prefix() {
+            assert_eq!(
+                normalize_path(PathBuf::from(r"\\?\UNC\server\share\project\file.txt")),
+                PathBuf::from(r"\\server\share\project\file.txt")
+            );
+        }
+
+        #[test]
+        fn test_normalize_path_leaves_other_prefixes_unchanged() {
+            let device = PathBuf::from(r"\\.\COM1");
+            assert_eq!(normalize_path(device.clone()), device);
+        }
+
+        /// Swap the case of the drive letter in an absolute `X:\...` path
+        fn swap_drive_case(path: &Path) -> PathBuf {
+            let s = normalize_path(path.canonicalize().unwrap())
+                .to_string_lossy()
+                .into_owned();
+            let mut chars = s.chars();
+            let drive = chars.next().unwrap().to_ascii_lowercase();
+            PathBuf::from(format!("{}{}", drive, chars.as_str()))
+        }
+
+        #[test]
+        fn test_validate_path_accepts_different_drive_letter_case() {
+            let temp_dir = TempDir::new().unwrap();
+            fs::write(temp_dir.path().join("file.txt"), "content").unwrap();
+
+            let lower_base = swap_drive_case(temp_dir.path());
+            let result = validate_path(&lower_base, Path::new("file.txt"));
+            assert!(result.is_ok(), "{:?}", result);
+
+            let lower_target = lower_base.join("file.txt");
+            let result = validate_path(temp_dir.path(), &lower_target);
+            assert!(result.is_ok(), "{:?}", result);
+
+            let result = validate_path(temp_dir.path(), &lower_base.join("new_file.txt"));
+            assert!(result.is_ok(), "{:?}", result);
+        }
+
+        #[test]
+        fn test_validate_path_accepts_verbatim_target_in_plain_base() {
+            let temp_dir = TempDir::new().unwrap();
+            fs::write(temp_dir.path().join("file.txt"), "content").unwrap();
+
+            let plain_base = normalize_path(temp_dir.path().canonicalize().unwrap());
+            let verbatim_target =
+                PathBuf::from(format!(r"\\?\{}", plain_base.join("file.txt").display()));
+
+            let result = validate_path(&plain_base, &verbatim_target);
+            assert!(result.is_ok(), "{:?}", result);
+            assert!(!result.unwrap().to_string_lossy().starts_with(r"\\?\"));
+
+            let result = validate_path(verbatim_target.parent().unwrap(), Path::new("new.txt"));
+            assert!(result.is_ok(), "{:?}", result);
+        }
+
+        #[test]
+        fn test_validate_path_rejects_escape_with_verbatim_prefix() {
+            let temp_dir = TempDir::new().unwrap();
+            let other_dir = TempDir::new().unwrap();
+            fs::write(other_dir.path().join("secret.txt"), "secret").unwrap();
+
+            let outside = normalize_path(other_dir.path().canonicalize().unwrap());
+            let verbatim_target =
+                PathBuf::from(format!(r"\\?\{}", outside.join("secret.txt").display()));
+
+            let result = validate_path(temp_dir.path(), &verbatim_target);
+            assert!(result.unwrap_err().to_string().contains("escapes"));
+        }
+    }
 }
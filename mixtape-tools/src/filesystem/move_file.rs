@@ -1,4 +1,4 @@
-use crate::filesystem::validate_path;
+use crate::filesystem::validate_write_path;
 use crate::prelude::*;
 use std::path::PathBuf;
 use tokio::fs;
@@ -16,6 +16,7 @@ pub struct MoveFileInput {
 /// Tool for moving or renaming files and directories
 pub struct MoveFileTool {
     base_path: PathBuf,
+    write_base_path: Option<PathBuf>,
 }
 
 impl Default for MoveFileTool {
@@ -36,6 +37,7 @@ impl MoveFileTool {
     pub fn new() -> Self {
         Self {
             base_path: std::env::current_dir().expect("Failed to get current working directory"),
+            write_base_path: None,
         }
     }
 
@@ -45,6 +47,7 @@ impl MoveFileTool {
     pub fn try_new() -> std::io::Result<Self> {
         Ok(Self {
             base_path: std::env::current_dir()?,
+            write_base_path: None,
         })
     }
 
@@ -52,7 +55,19 @@ impl MoveFileTool {
     ///
     /// All file operations will be constrained to this directory.
     pub fn with_base_path(base_path: PathBuf) -> Self {
-        Self { base_path }
+        Self {
+            base_path,
+            write_base_path: None,
+        }
+    }
+
+    /// Confines changes to a narrower directory than the base path.
+    ///
+    /// Relative paths still resolve against the base path, but the result
+    /// must lie within `write_base_path`. See [`validate_write_path`].
+    pub fn with_write_base_path(mut self, write_base_path: PathBuf) -> Self {
+        self.write_base_path = Some(write_base_path);
+        self
     }
 }
 
@@ -67,10 +82,16 @@ impl Tool for MoveFileTool {
         "Move or rename a file or directory to a new location."
     }
 
+    fn is_destructive(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, input: Self::Input) -> std::result::Result<ToolResult, ToolError> {
-        // Validate both source and destination are within base directory
-        let source_path = validate_path(&self.base_path, &input.source)?;
-        let dest_path = validate_path(&self.base_path, &input.destination)?;
+        // Validate both source and destination are within the writable
+        // directory, since moving also removes the source
+        let write_base_path = self.write_base_path.as_deref();
+        let source_path = validate_write_path(&self.base_path, write_base_path, &input.source)?;
+        let dest_path = validate_write_path(&self.base_path, write_base_path, &input.destination)?;
 
         // Create parent directories for destination if they don't exist
         if let Some(parent) = dest_path.parent() {
@@ -167,6 +188,25 @@ mod tests {
         assert!(temp_dir.path().join("new_dir").exists());
     }
 
+    #[tokio::test]
+    async fn test_move_file_source_outside_write_base_rejected() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("source.txt");
+        fs::write(&source, "content").unwrap();
+        fs::create_dir(temp_dir.path().join("output")).unwrap();
+
+        // Moving a file out of the read-only area would delete it there
+        let tool = MoveFileTool::with_base_path(temp_dir.path().to_path_buf())
+            .with_write_base_path(temp_dir.path().join("output"));
+        let input = MoveFileInput {
+            source: PathBuf::from("source.txt"),
+            destination: PathBuf::from("output/dest.txt"),
+        };
+
+        assert!(tool.execute(input).await.is_err());
+        assert!(source.exists());
+    }
+
     // ===== Error Path Tests =====
 
     #[tokio::test]
@@ -1,9 +1,14 @@
 use crate::filesystem::validate_path;
 use crate::prelude::*;
-use std::path::PathBuf;
+use mixtape_core::{ImageFormat, MAX_IMAGE_BYTES};
+use regex::Regex;
+use std::path::{Path, PathBuf};
+
+/// Most lines returned by a head, tail, or grep read
+const MAX_SLICE_LINES: usize = 2000;
 
 /// Input for reading a file
-#[derive(Debug, Deserialize, JsonSchema)]
+#[derive(Debug, Default, Deserialize, JsonSchema)]
 pub struct ReadFileInput {
     /// Path to the file to read (relative to base path or absolute)
     pub path: PathBuf,
@@ -15,11 +20,29 @@ pub struct ReadFileInput {
     /// Maximum number of lines to read (optional)
     #[serde(default)]
     pub length: Option<usize>,
+
+    /// Read only the first N lines (optional)
+    #[serde(default)]
+    pub head: Option<usize>,
+
+    /// Read only the last N lines (optional)
+    #[serde(default)]
+    pub tail: Option<usize>,
+
+    /// Read only lines matching this regex, each prefixed with its line
+    /// number; combine with head or tail to keep the first or last matches
+    /// (optional)
+    #[serde(default)]
+    pub grep: Option<String>,
 }
 
 /// Tool for reading file contents from the filesystem
+///
+/// PNG, JPEG, GIF, and WebP files are returned as [`ToolResult::Image`] so
+/// vision-capable models can look at them; everything else is read as text.
 pub struct ReadFileTool {
     base_path: PathBuf,
+    max_image_bytes: usize,
 }
 
 impl Default for ReadFileTool {
@@ -40,6 +63,7 @@ impl ReadFileTool {
     pub fn new() -> Self {
         Self {
             base_path: std::env::current_dir().expect("Failed to get current working directory"),
+            max_image_bytes: MAX_IMAGE_BYTES,
         }
     }
 
@@ -49,6 +73,7 @@ impl ReadFileTool {
     pub fn try_new() -> std::io::Result<Self> {
         Ok(Self {
             base_path: std::env::current_dir()?,
+            max_image_bytes: MAX_IMAGE_BYTES,
         })
     }
 
@@ -56,7 +81,80 @@ impl ReadFileTool {
     ///
     /// All file operations will be constrained to this directory.
     pub fn with_base_path(base_path: PathBuf) -> Self {
-        Self { base_path }
+        Self {
+            base_path,
+            max_image_bytes: MAX_IMAGE_BYTES,
+        }
+    }
+
+    /// Sets the largest image file that will be returned, in bytes.
+    ///
+    /// Defaults to [`MAX_IMAGE_BYTES`]. Larger images fail to read rather
+    /// than being sent to the model.
+    pub fn with_max_image_bytes(mut self, max_image_bytes: usize) -> Self {
+        self.max_image_bytes = max_image_bytes;
+        self
+    }
+}
+
+/// The lines of `content` selected by the input's head, tail, and grep
+///
+/// Output is capped at [`MAX_SLICE_LINES`], with a note saying how many
+/// lines were left out.
+fn read_slice(content: &str, input: &ReadFileInput) -> std::result::Result<String, ToolError> {
+    if input.head.is_some() && input.tail.is_some() {
+        return Err(ToolError::from("Use either head or tail, not both"));
+    }
+
+    let mut lines: Vec<String> = match &input.grep {
+        Some(pattern) => {
+            let regex = Regex::new(pattern)
+                .map_err(|e| ToolError::from(format!("Invalid regex pattern: {}", e)))?;
+            content
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| regex.is_match(line))
+                .map(|(i, line)| format!("{}: {}", i + 1, line))
+                .collect()
+        }
+        None => content.lines().map(str::to_string).collect(),
+    };
+    if lines.is_empty() && input.grep.is_some() {
+        return Ok("(no matching lines)".to_string());
+    }
+
+    if let Some(head) = input.head {
+        lines.truncate(head);
+    }
+    if let Some(tail) = input.tail {
+        lines.drain(..lines.len().saturating_sub(tail));
+    }
+
+    let omitted = lines.len().saturating_sub(MAX_SLICE_LINES);
+    if input.tail.is_some() {
+        lines.drain(..omitted);
+    } else {
+        lines.truncate(MAX_SLICE_LINES);
+    }
+    let mut result = lines.join("\n");
+    if omitted > 0 {
+        result.push_str(&format!(
+            "\n... {} more lines not shown (limit {})",
+            omitted, MAX_SLICE_LINES
+        ));
+    }
+    Ok(result)
+}
+
+/// Image format named by a file's extension
+fn image_extension(path: &Path) -> Option<ImageFormat> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    match extension.as_str() {
+        "png" => Some(ImageFormat::Png),
+        "jpg" | "jpeg" => Some(ImageFormat::Jpeg),
+        "gif" => Some(ImageFormat::Gif),
+        "webp" => Some(ImageFormat::Webp),
+        _ => None,
     }
 }
 
@@ -68,10 +166,13 @@ impl Tool for ReadFileTool {
     }
 
     fn description(&self) -> &str {
-        "Read the contents of a file from the filesystem. Supports reading entire files or specific line ranges."
+        "Read the contents of a file from the filesystem. Supports reading entire files, specific line ranges, the first or last lines (head/tail), or only lines matching a regex (grep). PNG, JPEG, GIF, and WebP images are returned as images."
     }
 
     fn format_output_plain(&self, result: &ToolResult) -> String {
+        if matches!(result, ToolResult::Image { .. }) {
+            return result.as_text();
+        }
         let content = result.as_text();
         if content.is_empty() {
             return "(empty file)".to_string();
@@ -88,6 +189,9 @@ impl Tool for ReadFileTool {
     }
 
     fn format_output_ansi(&self, result: &ToolResult) -> String {
+        if matches!(result, ToolResult::Image { .. }) {
+            return format!("\x1b[2m{}\x1b[0m", result.as_text());
+        }
         let content = result.as_text();
         if content.is_empty() {
             return "\x1b[2m(empty file)\x1b[0m".to_string();
@@ -109,6 +213,9 @@ impl Tool for ReadFileTool {
     }
 
     fn format_output_markdown(&self, result: &ToolResult) -> String {
+        if matches!(result, ToolResult::Image { .. }) {
+            return format!("*{}*", result.as_text());
+        }
         let content = result.as_text();
         if content.is_empty() {
             return "*Empty file*".to_string();
@@ -120,11 +227,57 @@ impl Tool for ReadFileTool {
         let path = validate_path(&self.base_path, &input.path)
             .map_err(|e| ToolError::from(e.to_string()))?;
 
-        let content = tokio::fs::read_to_string(&path)
+        // Refuse an oversized image before loading it into memory
+        if image_extension(&path).is_some() {
+            let size = tokio::fs::metadata(&path)
+                .await
+                .map_err(|e| ToolError::from(format!("Failed to read file: {}", e)))?
+                .len();
+            if size > self.max_image_bytes as u64 {
+                return Err(ToolError::from(format!(
+                    "Image is {} bytes, larger than the {} byte limit",
+                    size, self.max_image_bytes
+                )));
+            }
+        }
+
+        let bytes = tokio::fs::read(&path)
             .await
             .map_err(|e| ToolError::from(format!("Failed to read file: {}", e)))?;
 
-        let result = if input.offset.is_some() || input.length.is_some() {
+        // Images are detected by their contents; an image extension on
+        // anything else is an error rather than a binary text read
+        match (ImageFormat::detect(&bytes), image_extension(&path)) {
+            (Some(format), _) => {
+                if bytes.len() > self.max_image_bytes {
+                    return Err(ToolError::from(format!(
+                        "Image is {} bytes, larger than the {} byte limit",
+                        bytes.len(),
+                        self.max_image_bytes
+                    )));
+                }
+                return Ok(ToolResult::image(format, bytes));
+            }
+            (None, Some(format)) => {
+                return Err(ToolError::from(format!(
+                    "File is not a valid {} image",
+                    format.media_type()
+                )));
+            }
+            (None, None) => {}
+        }
+
+        let content = String::from_utf8(bytes)
+            .map_err(|e| ToolError::from(format!("Failed to read file: {}", e.utf8_error())))?;
+
+        let result = if input.head.is_some() || input.tail.is_some() || input.grep.is_some() {
+            if input.offset.is_some() || input.length.is_some() {
+                return Err(ToolError::from(
+                    "head, tail, and grep can't be combined with offset and length",
+                ));
+            }
+            read_slice(&content, &input)?
+        } else if input.offset.is_some() || input.length.is_some() {
             let lines: Vec<&str> = content.lines().collect();
             let offset = input.offset.unwrap_or(0);
             let length = input.length.unwrap_or(lines.len().saturating_sub(offset));
@@ -190,6 +343,7 @@ mod tests {
             path: PathBuf::from("test.txt"),
             offset: None,
             length: None,
+            ..Default::default()
         };
 
         let result = tool.execute(input).await.unwrap();
@@ -207,6 +361,7 @@ mod tests {
             path: PathBuf::from("test.txt"),
             offset: Some(1),
             length: Some(2),
+            ..Default::default()
         };
 
         let result = tool.execute(input).await.unwrap();
@@ -222,6 +377,7 @@ mod tests {
             path: PathBuf::from("../../../etc/passwd"),
             offset: None,
             length: None,
+            ..Default::default()
         };
 
         let result = tool.execute(input).await;
@@ -246,6 +402,7 @@ mod tests {
             path: PathBuf::from("utf8.txt"),
             offset: None,
             length: None,
+            ..Default::default()
         };
 
         let result = tool.execute(input).await.unwrap();
@@ -262,6 +419,7 @@ mod tests {
             path: PathBuf::from("empty.txt"),
             offset: None,
             length: None,
+            ..Default::default()
         };
 
         let result = tool.execute(input).await.unwrap();
@@ -279,6 +437,7 @@ mod tests {
             path: PathBuf::from("crlf.txt"),
             offset: None,
             length: None,
+            ..Default::default()
         };
 
         let result = tool.execute(input).await.unwrap();
@@ -297,6 +456,7 @@ mod tests {
             path: PathBuf::from("nonexistent.txt"),
             offset: None,
             length: None,
+            ..Default::default()
         };
 
         let result = tool.execute(input).await;
@@ -304,4 +464,235 @@ mod tests {
         let err = result.unwrap_err().to_string();
         assert!(err.contains("Failed to read file") || err.contains("No such file"));
     }
+
+    // ===== Head, Tail, and Grep Tests =====
+
+    fn write_log(temp_dir: &TempDir) {
+        let log: Vec<String> = (1..=10)
+            .map(|i| {
+                if i % 4 == 0 {
+                    format!("ERROR event {}", i)
+                } else {
+                    format!("INFO event {}", i)
+                }
+            })
+            .collect();
+        fs::write(temp_dir.path().join("app.log"), log.join("\n")).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_read_file_head() {
+        let temp_dir = TempDir::new().unwrap();
+        write_log(&temp_dir);
+
+        let tool = ReadFileTool::with_base_path(temp_dir.path().to_path_buf());
+        let input = ReadFileInput {
+            path: PathBuf::from("app.log"),
+            head: Some(2),
+            ..Default::default()
+        };
+
+        let result = tool.execute(input).await.unwrap();
+        assert_eq!(result.as_text(), "INFO event 1\nINFO event 2");
+    }
+
+    #[tokio::test]
+    async fn test_read_file_tail() {
+        let temp_dir = TempDir::new().unwrap();
+        write_log(&temp_dir);
+
+        let tool = ReadFileTool::with_base_path(temp_dir.path().to_path_buf());
+        let input = ReadFileInput {
+            path: PathBuf::from("app.log"),
+            tail: Some(3),
+            ..Default::default()
+        };
+
+        let result = tool.execute(input).await.unwrap();
+        assert_eq!(
+            result.as_text(),
+            "ERROR event 8\nINFO event 9\nINFO event 10"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_file_grep() {
+        let temp_dir = TempDir::new().unwrap();
+        write_log(&temp_dir);
+
+        let tool = ReadFileTool::with_base_path(temp_dir.path().to_path_buf());
+        let input = ReadFileInput {
+            path: PathBuf::from("app.log"),
+            grep: Some("^ERROR".to_string()),
+            ..Default::default()
+        };
+        let result = tool.execute(input).await.unwrap();
+        assert_eq!(result.as_text(), "4: ERROR event 4\n8: ERROR event 8");
+
+        // The last match only
+        let input = ReadFileInput {
+            path: PathBuf::from("app.log"),
+            grep: Some("ERROR".to_string()),
+            tail: Some(1),
+            ..Default::default()
+        };
+        let result = tool.execute(input).await.unwrap();
+        assert_eq!(result.as_text(), "8: ERROR event 8");
+
+        let input = ReadFileInput {
+            path: PathBuf::from("app.log"),
+            grep: Some("WARN".to_string()),
+            ..Default::default()
+        };
+        let result = tool.execute(input).await.unwrap();
+        assert_eq!(result.as_text(), "(no matching lines)");
+    }
+
+    #[tokio::test]
+    async fn test_read_file_slice_capped() {
+        let temp_dir = TempDir::new().unwrap();
+        let lines: Vec<String> = (1..=MAX_SLICE_LINES + 5).map(|i| i.to_string()).collect();
+        fs::write(temp_dir.path().join("long.txt"), lines.join("\n")).unwrap();
+
+        let tool = ReadFileTool::with_base_path(temp_dir.path().to_path_buf());
+        let input = ReadFileInput {
+            path: PathBuf::from("long.txt"),
+            tail: Some(MAX_SLICE_LINES + 5),
+            ..Default::default()
+        };
+
+        let result = tool.execute(input).await.unwrap();
+        let text = result.as_text();
+        assert!(text.starts_with("6\n"));
+        assert!(text.ends_with("... 5 more lines not shown (limit 2000)"));
+    }
+
+    #[tokio::test]
+    async fn test_read_file_slice_rejects_conflicting_options() {
+        let temp_dir = TempDir::new().unwrap();
+        write_log(&temp_dir);
+        let tool = ReadFileTool::with_base_path(temp_dir.path().to_path_buf());
+
+        let input = ReadFileInput {
+            path: PathBuf::from("app.log"),
+            head: Some(1),
+            tail: Some(1),
+            ..Default::default()
+        };
+        assert!(tool.execute(input).await.is_err());
+
+        let input = ReadFileInput {
+            path: PathBuf::from("app.log"),
+            offset: Some(1),
+            grep: Some("INFO".to_string()),
+            ..Default::default()
+        };
+        assert!(tool.execute(input).await.is_err());
+
+        let input = ReadFileInput {
+            path: PathBuf::from("app.log"),
+            grep: Some("(".to_string()),
+            ..Default::default()
+        };
+        let err = tool.execute(input).await.unwrap_err().to_string();
+        assert!(err.contains("Invalid regex pattern"));
+    }
+
+    // ===== Image Tests =====
+
+    /// Header of a 1x1 PNG; enough for format detection
+    const PNG_BYTES: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\0\0\0\x01\0\0\0\x01\x08\x06\0\0\0";
+
+    #[tokio::test]
+    async fn test_read_file_png_returns_image() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("screenshot.png"), PNG_BYTES).unwrap();
+
+        let tool = ReadFileTool::with_base_path(temp_dir.path().to_path_buf());
+        let input = ReadFileInput {
+            path: PathBuf::from("screenshot.png"),
+            offset: None,
+            length: None,
+            ..Default::default()
+        };
+
+        let result = tool.execute(input).await.unwrap();
+        match result {
+            ToolResult::Image { format, data } => {
+                assert_eq!(format, ImageFormat::Png);
+                assert_eq!(data, PNG_BYTES);
+            }
+            other => panic!("expected image result, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_file_text_returns_text() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("notes.md"), "# Notes").unwrap();
+
+        let tool = ReadFileTool::with_base_path(temp_dir.path().to_path_buf());
+        let input = ReadFileInput {
+            path: PathBuf::from("notes.md"),
+            offset: None,
+            length: None,
+            ..Default::default()
+        };
+
+        let result = tool.execute(input).await.unwrap();
+        assert!(matches!(result, ToolResult::Text(_)));
+        assert_eq!(result.as_text(), "# Notes");
+    }
+
+    #[tokio::test]
+    async fn test_read_file_image_over_limit() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("big.png"), PNG_BYTES).unwrap();
+
+        let tool = ReadFileTool::with_base_path(temp_dir.path().to_path_buf())
+            .with_max_image_bytes(PNG_BYTES.len() - 1);
+        let input = ReadFileInput {
+            path: PathBuf::from("big.png"),
+            offset: None,
+            length: None,
+            ..Default::default()
+        };
+
+        let err = tool.execute(input).await.unwrap_err().to_string();
+        assert!(err.contains("byte limit"));
+    }
+
+    #[tokio::test]
+    async fn test_read_file_image_size_checked_before_reading() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = fs::File::create(temp_dir.path().join("huge.png")).unwrap();
+        file.set_len(64 * 1024 * 1024).unwrap();
+
+        let tool = ReadFileTool::with_base_path(temp_dir.path().to_path_buf());
+        let input = ReadFileInput {
+            path: PathBuf::from("huge.png"),
+            ..Default::default()
+        };
+
+        // Rejected by size, not by the contents it never read
+        let err = tool.execute(input).await.unwrap_err().to_string();
+        assert!(err.contains("byte limit"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_read_file_fake_image_extension() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("fake.jpg"), "not an image").unwrap();
+
+        let tool = ReadFileTool::with_base_path(temp_dir.path().to_path_buf());
+        let input = ReadFileInput {
+            path: PathBuf::from("fake.jpg"),
+            offset: None,
+            length: None,
+            ..Default::default()
+        };
+
+        let err = tool.execute(input).await.unwrap_err().to_string();
+        assert!(err.contains("image/jpeg"));
+    }
 }
@@ -1,4 +1,4 @@
-use crate::filesystem::validate_path;
+use crate::filesystem::validate_write_path;
 use crate::prelude::*;
 use std::path::PathBuf;
 use tokio::fs::OpenOptions;
@@ -32,6 +32,7 @@ pub struct WriteFileInput {
 /// Tool for writing content to files
 pub struct WriteFileTool {
     base_path: PathBuf,
+    write_base_path: Option<PathBuf>,
 }
 
 impl Default for WriteFileTool {
@@ -52,6 +53,7 @@ impl WriteFileTool {
     pub fn new() -> Self {
         Self {
             base_path: std::env::current_dir().expect("Failed to get current working directory"),
+            write_base_path: None,
         }
     }
 
@@ -61,6 +63,7 @@ impl WriteFileTool {
     pub fn try_new() -> std::io::Result<Self> {
         Ok(Self {
             base_path: std::env::current_dir()?,
+            write_base_path: None,
         })
     }
 
@@ -68,7 +71,19 @@ impl WriteFileTool {
     ///
     /// All file operations will be constrained to this directory.
     pub fn with_base_path(base_path: PathBuf) -> Self {
-        Self { base_path }
+        Self {
+            base_path,
+            write_base_path: None,
+        }
+    }
+
+    /// Confines changes to a narrower directory than the base path.
+    ///
+    /// Relative paths still resolve against the base path, but the result
+    /// must lie within `write_base_path`. See [`validate_write_path`].
+    pub fn with_write_base_path(mut self, write_base_path: PathBuf) -> Self {
+        self.write_base_path = Some(write_base_path);
+        self
     }
 }
 
@@ -83,9 +98,17 @@ impl Tool for WriteFileTool {
         "Write content to a file. Can either overwrite the file or append to it."
     }
 
+    fn is_destructive(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, input: Self::Input) -> std::result::Result<ToolResult, ToolError> {
-        // Validate path is within base directory
-        let validated_path = validate_path(&self.base_path, &input.path)?;
+        // Validate path is within the writable directory
+        let validated_path = validate_write_path(
+            &self.base_path,
+            self.write_base_path.as_deref(),
+            &input.path,
+        )?;
 
         // Create parent directories if they don't exist
         if let Some(parent) = validated_path.parent() {
@@ -191,6 +214,33 @@ mod tests {
         assert_eq!(content, "Hello, World!");
     }
 
+    #[tokio::test]
+    async fn test_write_file_confined_to_write_base() {
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("output");
+        fs::create_dir(&output).await.unwrap();
+        let tool = WriteFileTool::with_base_path(temp_dir.path().to_path_buf())
+            .with_write_base_path(output.clone());
+
+        // Inside the base path, outside the write base
+        let input = WriteFileInput {
+            path: PathBuf::from("notes.txt"),
+            content: "nope".to_string(),
+            mode: WriteMode::Rewrite,
+        };
+        assert!(tool.execute(input).await.is_err());
+        assert!(!temp_dir.path().join("notes.txt").exists());
+
+        let input = WriteFileInput {
+            path: PathBuf::from("output/report.txt"),
+            content: "done".to_string(),
+            mode: WriteMode::Rewrite,
+        };
+        tool.execute(input).await.unwrap();
+        let content = fs::read_to_string(output.join("report.txt")).await.unwrap();
+        assert_eq!(content, "done");
+    }
+
     #[tokio::test]
     async fn test_write_file_overwrite() {
         let temp_dir = TempDir::new().unwrap();
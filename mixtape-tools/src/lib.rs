@@ -1,7 +1,9 @@
 pub mod aws;
 pub mod edit;
+pub mod env;
 pub mod fetch;
 pub mod filesystem;
+pub mod openapi;
 pub mod process;
 pub mod search;
 #[cfg(feature = "sqlite")]
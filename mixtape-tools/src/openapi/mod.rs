@@ -0,0 +1,408 @@
+//! Tools generated from an OpenAPI 3 specification.
+//!
+//! [`tools_from_spec`] reads a JSON OpenAPI document and returns one tool per
+//! operation. Each tool is named after the operation's `operationId`, takes
+//! the operation's path, query, and header parameters as top-level inputs,
+//! and accepts a JSON request body under `body` (`request_body` if a
+//! parameter already has that name). Calling the tool sends the
+//! HTTP request to the first server listed in the spec and returns the
+//! response, as JSON when it parses as JSON.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use mixtape_tools::openapi::{tools_from_spec, OpenApiAuth};
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let token = std::env::var("PETSTORE_TOKEN")?;
+//!     let tools = tools_from_spec(
+//!         "https://petstore3.swagger.io/api/v3/openapi.json",
+//!         OpenApiAuth::bearer(token),
+//!     )
+//!     .await?;
+//!
+//!     for tool in &tools {
+//!         println!("{}: {}", tool.name(), tool.description());
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+mod operation_tool;
+mod spec;
+
+use mixtape_core::{box_tool, DynTool};
+use operation_tool::{ApiClient, OperationTool};
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use url::Url;
+
+/// Errors that can occur while loading an OpenAPI spec
+#[derive(Debug, Error)]
+pub enum OpenApiError {
+    /// The spec could not be downloaded
+    #[error("Failed to fetch spec: {0}")]
+    Fetch(String),
+
+    /// The spec file could not be read
+    #[error("Failed to read spec: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The spec is not valid JSON
+    #[error("Failed to parse spec as JSON: {0}")]
+    Parse(#[from] serde_json::Error),
+
+    /// The spec is valid JSON but not a usable OpenAPI 3 document
+    #[error("Invalid OpenAPI spec: {0}")]
+    InvalidSpec(String),
+}
+
+/// Where an API key is sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiKeyLocation {
+    /// As a request header
+    Header,
+    /// As a query string parameter
+    Query,
+}
+
+/// Credentials sent with every generated request
+#[derive(Clone, Default)]
+pub enum OpenApiAuth {
+    /// No authentication
+    #[default]
+    None,
+    /// `Authorization: Bearer <token>`
+    Bearer(String),
+    /// A static API key in a header or query parameter
+    ApiKey {
+        /// Header or query parameter name
+        name: String,
+        /// The key itself
+        value: String,
+        /// Where the key is sent
+        location: ApiKeyLocation,
+    },
+}
+
+impl OpenApiAuth {
+    /// Bearer token authentication
+    pub fn bearer(token: impl Into<String>) -> Self {
+        Self::Bearer(token.into())
+    }
+
+    /// API key sent in the named header
+    pub fn api_key_header(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self::ApiKey {
+            name: name.into(),
+            value: value.into(),
+            location: ApiKeyLocation::Header,
+        }
+    }
+
+    /// API key sent as the named query parameter
+    pub fn api_key_query(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self::ApiKey {
+            name: name.into(),
+            value: value.into(),
+            location: ApiKeyLocation::Query,
+        }
+    }
+}
+
+impl std::fmt::Debug for OpenApiAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "None"),
+            Self::Bearer(_) => f.debug_tuple("Bearer").field(&"[REDACTED]").finish(),
+            Self::ApiKey { name, location, .. } => f
+                .debug_struct("ApiKey")
+                .field("name", name)
+                .field("value", &"[REDACTED]")
+                .field("location", location)
+                .finish(),
+        }
+    }
+}
+
+/// Load an OpenAPI 3 spec and create one tool per operation
+///
+/// `source` is an `http://` or `https://` URL, or a path to a local file.
+/// Only JSON documents are supported; convert YAML specs to JSON first.
+///
+/// Requests go to the spec's first server. A relative server URL is
+/// resolved against the spec URL, so specs loaded from a file need an
+/// absolute one.
+pub async fn tools_from_spec(
+    source: &str,
+    auth: OpenApiAuth,
+) -> Result<Vec<Box<dyn DynTool>>, OpenApiError> {
+    let http = reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|e| OpenApiError::Fetch(e.to_string()))?;
+
+    let (spec, spec_url) = if source.starts_with("http://") || source.starts_with("https://") {
+        let url = Url::parse(source).map_err(|e| OpenApiError::Fetch(e.to_string()))?;
+        let response = http
+            .get(url.clone())
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| OpenApiError::Fetch(e.to_string()))?;
+        let text = response
+            .text()
+            .await
+            .map_err(|e| OpenApiError::Fetch(e.to_string()))?;
+        (serde_json::from_str::<Value>(&text)?, Some(url))
+    } else {
+        let text = tokio::fs::read_to_string(source).await?;
+        (serde_json::from_str::<Value>(&text)?, None)
+    };
+
+    let base_url = server_url(&spec, spec_url.as_ref())?;
+    let client = Arc::new(ApiClient {
+        http,
+        base_url,
+        auth,
+    });
+
+    Ok(spec::parse_operations(&spec)?
+        .into_iter()
+        .map(|operation| box_tool(OperationTool::new(operation, Arc::clone(&client))))
+        .collect())
+}
+
+/// The absolute base URL of the spec's first server
+fn server_url(spec: &Value, spec_url: Option<&Url>) -> Result<String, OpenApiError> {
+    let server = spec
+        .get("servers")
+        .and_then(Value::as_array)
+        .and_then(|servers| servers.first());
+
+    let mut url = server
+        .and_then(|s| s.get("url"))
+        .and_then(Value::as_str)
+        .unwrap_or("/")
+        .to_string();
+
+    // Substitute server variables with their defaults
+    if let Some(variables) = server
+        .and_then(|s| s.get("variables"))
+        .and_then(Value::as_object)
+    {
+        for (name, variable) in variables {
+            if let Some(default) = variable.get("default").and_then(Value::as_str) {
+                url = url.replace(&format!("{{{}}}", name), default);
+            }
+        }
+    }
+
+    if let Ok(absolute) = Url::parse(&url) {
+        return Ok(absolute.to_string());
+    }
+    let base = spec_url.ok_or_else(|| {
+        OpenApiError::InvalidSpec(format!(
+            "server URL '{}' is relative and the spec was not loaded from a URL",
+            url
+        ))
+    })?;
+    base.join(&url)
+        .map(|joined| joined.to_string())
+        .map_err(|e| OpenApiError::InvalidSpec(format!("invalid server URL '{}': {}", url, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wiremock::matchers::{body_json, header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn petstore_spec() -> Value {
+        json!({
+            "openapi": "3.0.3",
+            "info": {"title": "Petstore", "version": "1.0"},
+            "servers": [{"url": "/api"}],
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "operationId": "listPets",
+                        "summary": "List pets",
+                        "parameters": [
+                            {"name": "limit", "in": "query", "schema": {"type": "integer"}}
+                        ]
+                    },
+                    "post": {
+                        "operationId": "createPet",
+                        "summary": "Create a pet",
+                        "requestBody": {
+                            "required": true,
+                            "content": {"application/json": {"schema": {
+                                "type": "object",
+                                "properties": {"name": {"type": "string"}}
+                            }}}
+                        }
+                    }
+                },
+                "/pets/{petId}": {
+                    "get": {
+                        "operationId": "getPet",
+                        "summary": "Get a pet by ID",
+                        "parameters": [
+                            {"name": "petId", "in": "path", "required": true,
+                             "schema": {"type": "integer"}}
+                        ]
+                    }
+                }
+            }
+        })
+    }
+
+    async fn serve_spec(server: &MockServer) {
+        Mock::given(method("GET"))
+            .and(path("/openapi.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(petstore_spec()))
+            .mount(server)
+            .await;
+    }
+
+    fn find<'a>(tools: &'a [Box<dyn DynTool>], name: &str) -> &'a dyn DynTool {
+        tools
+            .iter()
+            .find(|t| t.name() == name)
+            .map(|t| t.as_ref())
+            .unwrap_or_else(|| panic!("no tool named {}", name))
+    }
+
+    #[tokio::test]
+    async fn test_tools_from_spec_calls_api() {
+        let server = MockServer::start().await;
+        serve_spec(&server).await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/pets/42"))
+            .and(header("authorization", "Bearer token-123"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({"id": 42, "name": "Rex"})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/pets"))
+            .and(query_param("limit", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{"id": 1}, {"id": 2}])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/pets"))
+            .and(body_json(json!({"name": "Tom"})))
+            .respond_with(ResponseTemplate::new(201).set_body_string("created"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let tools = tools_from_spec(
+            &format!("{}/openapi.json", server.uri()),
+            OpenApiAuth::bearer("token-123"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(tools.len(), 3);
+
+        let get_pet = find(&tools, "getPet");
+        assert_eq!(get_pet.description(), "Get a pet by ID");
+        assert_eq!(get_pet.input_schema()["required"], json!(["petId"]));
+        let result = get_pet.execute_raw(json!({"petId": 42})).await.unwrap();
+        assert_eq!(
+            result.as_text(),
+            json!({"id": 42, "name": "Rex"}).to_string()
+        );
+
+        let result = find(&tools, "listPets")
+            .execute_raw(json!({"limit": 2}))
+            .await
+            .unwrap();
+        assert!(
+            matches!(result, mixtape_core::ToolResult::Json(ref v) if v.as_array().unwrap().len() == 2)
+        );
+
+        let result = find(&tools, "createPet")
+            .execute_raw(json!({"body": {"name": "Tom"}}))
+            .await
+            .unwrap();
+        assert_eq!(result.as_text(), "created");
+    }
+
+    #[tokio::test]
+    async fn test_api_key_header_and_http_errors() {
+        let server = MockServer::start().await;
+        serve_spec(&server).await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/pets/1"))
+            .and(header("x-api-key", "k"))
+            .respond_with(ResponseTemplate::new(404).set_body_string("no such pet"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let tools = tools_from_spec(
+            &format!("{}/openapi.json", server.uri()),
+            OpenApiAuth::api_key_header("x-api-key", "k"),
+        )
+        .await
+        .unwrap();
+
+        let err = find(&tools, "getPet")
+            .execute_raw(json!({"petId": 1}))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("404"));
+        assert!(err.to_string().contains("no such pet"));
+    }
+
+    #[tokio::test]
+    async fn test_relative_server_requires_spec_url() {
+        let dir = tempfile::tempdir().unwrap();
+        let spec_path = dir.path().join("openapi.json");
+        std::fs::write(&spec_path, petstore_spec().to_string()).unwrap();
+
+        let err = tools_from_spec(spec_path.to_str().unwrap(), OpenApiAuth::None)
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(err, OpenApiError::InvalidSpec(_)));
+    }
+
+    #[test]
+    fn test_server_url_substitutes_variables() {
+        let spec = json!({
+            "servers": [{
+                "url": "https://{region}.example.com/v1",
+                "variables": {"region": {"default": "eu"}}
+            }]
+        });
+        assert_eq!(
+            server_url(&spec, None).unwrap(),
+            "https://eu.example.com/v1"
+        );
+    }
+
+    #[test]
+    fn test_auth_debug_redacts_secrets() {
+        let debug = format!(
+            "{:?} {:?}",
+            OpenApiAuth::bearer("tok"),
+            OpenApiAuth::api_key_query("key", "secret")
+        );
+        assert!(!debug.contains("tok\""));
+        assert!(!debug.contains("secret"));
+        assert!(debug.contains("[REDACTED]"));
+    }
+}
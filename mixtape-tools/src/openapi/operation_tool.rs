@@ -0,0 +1,372 @@
+use super::spec::{Operation, ParameterLocation};
+use super::{ApiKeyLocation, OpenApiAuth};
+use crate::prelude::*;
+use crate::utils::sanitize_error;
+use serde_json::Value;
+use std::sync::Arc;
+use url::Url;
+
+/// Connection details shared by every tool generated from one spec
+pub(crate) struct ApiClient {
+    pub http: reqwest::Client,
+    pub base_url: String,
+    pub auth: OpenApiAuth,
+}
+
+impl ApiClient {
+    /// Mask the configured credential in an error message, then anything
+    /// else [`sanitize_error`] recognizes
+    ///
+    /// A query API key can have any name, so the pattern-based masking
+    /// alone may miss it in a request URL.
+    fn sanitize(&self, message: &str) -> String {
+        let secret = match &self.auth {
+            OpenApiAuth::None => None,
+            OpenApiAuth::Bearer(token) => Some(token),
+            OpenApiAuth::ApiKey { value, .. } => Some(value),
+        };
+        let mut message = message.to_string();
+        if let Some(secret) = secret.filter(|secret| !secret.is_empty()) {
+            // As it appears in a query string
+            let encoded: String = url::form_urlencoded::byte_serialize(secret.as_bytes()).collect();
+            message = message
+                .replace(secret.as_str(), "[REDACTED]")
+                .replace(&encoded, "[REDACTED]");
+        }
+        sanitize_error(&message)
+    }
+}
+
+/// Tool that calls a single OpenAPI operation
+pub(crate) struct OperationTool {
+    operation: Operation,
+    client: Arc<ApiClient>,
+}
+
+impl OperationTool {
+    pub(crate) fn new(operation: Operation, client: Arc<ApiClient>) -> Self {
+        Self { operation, client }
+    }
+
+    /// Build the request URL from the path template and input parameters
+    fn build_url(&self, input: &Value) -> Result<Url, ToolError> {
+        let mut path = self.operation.path.clone();
+        for param in &self.operation.parameters {
+            if param.location != ParameterLocation::Path {
+                continue;
+            }
+            let value = input
+                .get(&param.name)
+                .and_then(param_value)
+                .ok_or_else(|| format!("Missing required path parameter '{}'", param.name))?;
+            path = path.replace(&format!("{{{}}}", param.name), &encode_path_segment(&value));
+        }
+
+        let mut url = Url::parse(&format!(
+            "{}{}",
+            self.client.base_url.trim_end_matches('/'),
+            path
+        ))
+        .map_err(|e| format!("Invalid request URL: {}", e))?;
+
+        {
+            let mut query = url.query_pairs_mut();
+            for param in &self.operation.parameters {
+                if param.location != ParameterLocation::Query {
+                    continue;
+                }
+                match input.get(&param.name) {
+                    Some(Value::Array(items)) => {
+                        for item in items.iter().filter_map(param_value) {
+                            query.append_pair(&param.name, &item);
+                        }
+                    }
+                    Some(value) => {
+                        if let Some(value) = param_value(value) {
+                            query.append_pair(&param.name, &value);
+                        }
+                    }
+                    None => {}
+                }
+            }
+            if let OpenApiAuth::ApiKey {
+                name,
+                value,
+                location: ApiKeyLocation::Query,
+            } = &self.client.auth
+            {
+                query.append_pair(name, value);
+            }
+        }
+        if url.query() == Some("") {
+            url.set_query(None);
+        }
+        Ok(url)
+    }
+}
+
+impl Tool for OperationTool {
+    // Inputs are described by the schema generated from the spec
+    type Input = Value;
+
+    fn name(&self) -> &str {
+        &self.operation.name
+    }
+
+    fn description(&self) -> &str {
+        &self.operation.description
+    }
+
+    fn input_schema(&self) -> Value {
+        self.operation.input_schema.clone()
+    }
+
+    async fn execute(&self, input: Self::Input) -> std::result::Result<ToolResult, ToolError> {
+        let url = self.build_url(&input)?;
+        let mut request = self.client.http.request(self.operation.method.clone(), url);
+
+        for param in &self.operation.parameters {
+            if param.location != ParameterLocation::Header {
+                continue;
+            }
+            if let Some(value) = input.get(&param.name).and_then(param_value) {
+                request = request.header(&param.name, value);
+            }
+        }
+
+        request = match &self.client.auth {
+            OpenApiAuth::None => request,
+            OpenApiAuth::Bearer(token) => request.bearer_auth(token),
+            OpenApiAuth::ApiKey {
+                name,
+                value,
+                location: ApiKeyLocation::Header,
+            } => request.header(name, value),
+            OpenApiAuth::ApiKey { .. } => request,
+        };
+
+        if let Some(body) = self.operation.body.as_ref().and_then(|key| input.get(key)) {
+            request = request.json(body);
+        }
+
+        let response = request.send().await.map_err(|e| {
+            ToolError::from(self.client.sanitize(&format!("Request failed: {}", e)))
+        })?;
+
+        let status = response.status();
+        let text = response.text().await.map_err(|e| {
+            ToolError::from(
+                self.client
+                    .sanitize(&format!("Failed to read response body: {}", e)),
+            )
+        })?;
+
+        if !status.is_success() {
+            return Err(self
+                .client
+                .sanitize(&format!(
+                    "HTTP error: {} {}: {}",
+                    status.as_u16(),
+                    status.canonical_reason().unwrap_or("Unknown"),
+                    text
+                ))
+                .into());
+        }
+
+        if text.trim().is_empty() {
+            return Ok(ToolResult::text(format!(
+                "HTTP {} {}",
+                status.as_u16(),
+                status.canonical_reason().unwrap_or("")
+            )));
+        }
+        Ok(match serde_json::from_str(&text) {
+            Ok(json) => ToolResult::Json(json),
+            Err(_) => ToolResult::Text(text),
+        })
+    }
+}
+
+/// Render a scalar input value as a parameter string
+fn param_value(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+/// Percent-encode everything except RFC 3986 unreserved characters
+fn encode_path_segment(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::spec::Parameter;
+    use super::*;
+    use reqwest::Method;
+    use serde_json::json;
+
+    fn tool(parameters: Vec<Parameter>, auth: OpenApiAuth) -> OperationTool {
+        OperationTool::new(
+            Operation {
+                name: "getThing".to_string(),
+                description: "Get a thing".to_string(),
+                method: Method::GET,
+                path: "/things/{id}".to_string(),
+                parameters,
+                body: None,
+                input_schema: json!({"type": "object"}),
+            },
+            Arc::new(ApiClient {
+                http: reqwest::Client::new(),
+                base_url: "https://api.example.com/v1/".to_string(),
+                auth,
+            }),
+        )
+    }
+
+    fn param(name: &str, location: ParameterLocation) -> Parameter {
+        Parameter {
+            name: name.to_string(),
+            location,
+        }
+    }
+
+    #[test]
+    fn test_build_url_encodes_path_and_query() {
+        let tool = tool(
+            vec![
+                param("id", ParameterLocation::Path),
+                param("tag", ParameterLocation::Query),
+                param("limit", ParameterLocation::Query),
+            ],
+            OpenApiAuth::None,
+        );
+
+        let url = tool
+            .build_url(&json!({"id": "a/b c", "tag": ["x", "y"], "limit": 5}))
+            .unwrap();
+        assert_eq!(
+            url.as_str(),
+            "https://api.example.com/v1/things/a%2Fb%20c?tag=x&tag=y&limit=5"
+        );
+    }
+
+    #[test]
+    fn test_build_url_requires_path_parameters() {
+        let tool = tool(
+            vec![param("id", ParameterLocation::Path)],
+            OpenApiAuth::None,
+        );
+
+        let err = tool.build_url(&json!({})).unwrap_err();
+        assert!(err.to_string().contains("'id'"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_sends_renamed_body() {
+        use wiremock::matchers::{body_json, method, path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/notes"))
+            .and(query_param("body", "markdown"))
+            .and(body_json(json!({"title": "Hi"})))
+            .respond_with(ResponseTemplate::new(201).set_body_string("created"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let tool = OperationTool::new(
+            Operation {
+                name: "createNote".to_string(),
+                description: "Create a note".to_string(),
+                method: Method::POST,
+                path: "/notes".to_string(),
+                parameters: vec![param("body", ParameterLocation::Query)],
+                body: Some("request_body".to_string()),
+                input_schema: json!({"type": "object"}),
+            },
+            Arc::new(ApiClient {
+                http: reqwest::Client::new(),
+                base_url: server.uri(),
+                auth: OpenApiAuth::None,
+            }),
+        );
+
+        let result = tool
+            .execute(json!({"body": "markdown", "request_body": {"title": "Hi"}}))
+            .await
+            .unwrap();
+        assert_eq!(result.as_text(), "created");
+    }
+
+    #[tokio::test]
+    async fn test_errors_redact_query_api_key() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let tool_at = |base_url: String| {
+            OperationTool::new(
+                Operation {
+                    name: "getThing".to_string(),
+                    description: "Get a thing".to_string(),
+                    method: Method::GET,
+                    path: "/things".to_string(),
+                    parameters: Vec::new(),
+                    body: None,
+                    input_schema: json!({"type": "object"}),
+                },
+                Arc::new(ApiClient {
+                    http: reqwest::Client::new(),
+                    base_url,
+                    auth: OpenApiAuth::api_key_query("key", "s3cret/key"),
+                }),
+            )
+        };
+
+        // Connection errors include the request URL
+        let err = tool_at("http://127.0.0.1:1".to_string())
+            .execute(json!({}))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("key=[REDACTED]"), "{}", err);
+        assert!(!err.contains("s3cret"), "{}", err);
+
+        // So can HTTP error bodies
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(401).set_body_string("bad key s3cret/key"))
+            .mount(&server)
+            .await;
+        let err = tool_at(server.uri())
+            .execute(json!({}))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("bad key [REDACTED]"), "{}", err);
+    }
+
+    #[test]
+    fn test_build_url_adds_query_api_key() {
+        let tool = tool(
+            vec![param("id", ParameterLocation::Path)],
+            OpenApiAuth::api_key_query("api_key", "secret"),
+        );
+
+        let url = tool.build_url(&json!({"id": 7})).unwrap();
+        assert_eq!(url.query(), Some("api_key=secret"));
+    }
+}
@@ -0,0 +1,470 @@
+//! Extracting callable operations from an OpenAPI 3 document
+
+use super::OpenApiError;
+use reqwest::Method;
+use serde_json::{json, Map, Value};
+
+/// HTTP methods that can appear in an OpenAPI path item
+const METHODS: [&str; 7] = ["get", "put", "post", "delete", "patch", "head", "options"];
+
+/// Longest tool name accepted by model providers
+const MAX_TOOL_NAME_LEN: usize = 64;
+
+/// Where an operation parameter is sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ParameterLocation {
+    Path,
+    Query,
+    Header,
+}
+
+/// A parameter the tool reads from its input and places in the request
+#[derive(Debug, Clone)]
+pub(crate) struct Parameter {
+    pub name: String,
+    pub location: ParameterLocation,
+}
+
+/// One API operation, ready to be exposed as a tool
+#[derive(Debug, Clone)]
+pub(crate) struct Operation {
+    pub name: String,
+    pub description: String,
+    pub method: Method,
+    pub path: String,
+    pub parameters: Vec<Parameter>,
+    /// Input property holding the JSON request body, if the operation takes one
+    pub body: Option<String>,
+    pub input_schema: Value,
+}
+
+/// Parse every supported operation in the spec
+///
+/// Parameters become top-level input properties; a JSON request body is
+/// accepted under `body`, or `request_body` when a parameter is already
+/// named `body`. Cookie parameters are ignored, and operations whose
+/// required body is not JSON are skipped.
+pub(crate) fn parse_operations(spec: &Value) -> Result<Vec<Operation>, OpenApiError> {
+    let version = spec.get("openapi").and_then(Value::as_str).unwrap_or("");
+    if !version.starts_with('3') {
+        return Err(OpenApiError::InvalidSpec(
+            "only OpenAPI 3.x documents are supported".to_string(),
+        ));
+    }
+
+    let paths = spec
+        .get("paths")
+        .and_then(Value::as_object)
+        .ok_or_else(|| OpenApiError::InvalidSpec("missing 'paths' object".to_string()))?;
+
+    let mut operations = Vec::new();
+    for (path, item) in paths {
+        let item = resolve(spec, item)?;
+        let shared_params = item
+            .get("parameters")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+
+        for method in METHODS {
+            let Some(op) = item.get(method) else {
+                continue;
+            };
+            if let Some(operation) = parse_operation(spec, path, method, op, &shared_params)? {
+                operations.push(operation);
+            }
+        }
+    }
+    Ok(operations)
+}
+
+fn parse_operation(
+    spec: &Value,
+    path: &str,
+    method: &str,
+    op: &Value,
+    shared_params: &[Value],
+) -> Result<Option<Operation>, OpenApiError> {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    let mut parameters: Vec<Parameter> = Vec::new();
+
+    // Operation-level parameters override path-level ones with the same name
+    let op_params = op
+        .get("parameters")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    for param in shared_params.iter().chain(op_params.iter()) {
+        let param = resolve(spec, param)?;
+        let Some(name) = param.get("name").and_then(Value::as_str) else {
+            continue;
+        };
+        let location = match param.get("in").and_then(Value::as_str) {
+            Some("path") => ParameterLocation::Path,
+            Some("query") => ParameterLocation::Query,
+            Some("header") => ParameterLocation::Header,
+            _ => continue,
+        };
+
+        let mut schema = inline_refs(spec, param.get("schema").unwrap_or(&json!({})))?;
+        if let (Some(description), Some(obj)) = (param.get("description"), schema.as_object_mut()) {
+            obj.entry("description").or_insert(description.clone());
+        }
+
+        parameters.retain(|p| p.name != name);
+        required.retain(|r| r != name);
+        parameters.push(Parameter {
+            name: name.to_string(),
+            location,
+        });
+        properties.insert(name.to_string(), schema);
+
+        let is_required = location == ParameterLocation::Path
+            || param.get("required").and_then(Value::as_bool) == Some(true);
+        if is_required {
+            required.push(name.to_string());
+        }
+    }
+
+    let mut body_property = None;
+    if let Some(body) = op.get("requestBody") {
+        let body = resolve(spec, body)?;
+        let body_required = body.get("required").and_then(Value::as_bool) == Some(true);
+        match json_body_schema(body) {
+            Some(schema) => {
+                let mut schema = inline_refs(spec, schema)?;
+                if let (Some(description), Some(obj)) =
+                    (body.get("description"), schema.as_object_mut())
+                {
+                    obj.entry("description").or_insert(description.clone());
+                }
+                // A parameter can't share the body's property
+                let mut key = "body".to_string();
+                if properties.contains_key(&key) {
+                    key = "request_body".to_string();
+                }
+                while properties.contains_key(&key) {
+                    key.insert(0, '_');
+                }
+                properties.insert(key.clone(), schema);
+                if body_required {
+                    required.push(key.clone());
+                }
+                body_property = Some(key);
+            }
+            None if body_required => return Ok(None),
+            None => {}
+        }
+    }
+
+    let mut input_schema = json!({
+        "type": "object",
+        "properties": properties,
+    });
+    if !required.is_empty() {
+        input_schema["required"] = json!(required);
+    }
+
+    let name = op
+        .get("operationId")
+        .and_then(Value::as_str)
+        .map(tool_name)
+        .unwrap_or_else(|| tool_name(&format!("{}_{}", method, path)));
+
+    let description = ["summary", "description"]
+        .iter()
+        .find_map(|key| op.get(*key).and_then(Value::as_str))
+        .filter(|text| !text.trim().is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("{} {}", method.to_uppercase(), path));
+
+    let method = Method::from_bytes(method.to_uppercase().as_bytes())
+        .map_err(|e| OpenApiError::InvalidSpec(e.to_string()))?;
+
+    Ok(Some(Operation {
+        name,
+        description,
+        method,
+        path: path.to_string(),
+        parameters,
+        body: body_property,
+        input_schema,
+    }))
+}
+
+/// The schema of a JSON request body, if the operation accepts one
+fn json_body_schema(body: &Value) -> Option<&Value> {
+    let content = body.get("content")?.as_object()?;
+    let media = content.get("application/json").or_else(|| {
+        content
+            .iter()
+            .find(|(media_type, _)| media_type.ends_with("+json"))
+            .map(|(_, media)| media)
+    })?;
+    Some(media.get("schema").unwrap_or(&Value::Bool(true)))
+}
+
+/// Follow a `$ref` to its target, or return the value unchanged
+fn resolve<'a>(spec: &'a Value, value: &'a Value) -> Result<&'a Value, OpenApiError> {
+    match value.get("$ref").and_then(Value::as_str) {
+        Some(reference) => lookup(spec, reference),
+        None => Ok(value),
+    }
+}
+
+fn lookup<'a>(spec: &'a Value, reference: &str) -> Result<&'a Value, OpenApiError> {
+    let pointer = reference.strip_prefix('#').ok_or_else(|| {
+        OpenApiError::InvalidSpec(format!(
+            "external reference '{}' is not supported",
+            reference
+        ))
+    })?;
+    spec.pointer(pointer)
+        .ok_or_else(|| OpenApiError::InvalidSpec(format!("unresolved reference '{}'", reference)))
+}
+
+/// Replace every `$ref` in a schema with its target
+///
+/// Recursive schemas are cut off where they refer back to themselves, which
+/// leaves an unconstrained `{}` in place of the cycle.
+fn inline_refs(spec: &Value, schema: &Value) -> Result<Value, OpenApiError> {
+    inline_refs_inner(spec, schema, &mut Vec::new())
+}
+
+fn inline_refs_inner(
+    spec: &Value,
+    value: &Value,
+    stack: &mut Vec<String>,
+) -> Result<Value, OpenApiError> {
+    match value {
+        Value::Object(obj) => {
+            if let Some(reference) = obj.get("$ref").and_then(Value::as_str) {
+                if stack.iter().any(|seen| seen == reference) {
+                    return Ok(json!({}));
+                }
+                let target = lookup(spec, reference)?;
+                stack.push(reference.to_string());
+                let inlined = inline_refs_inner(spec, target, stack);
+                stack.pop();
+                return inlined;
+            }
+            let mut out = Map::new();
+            for (key, child) in obj {
+                out.insert(key.clone(), inline_refs_inner(spec, child, stack)?);
+            }
+            Ok(Value::Object(out))
+        }
+        Value::Array(items) => items
+            .iter()
+            .map(|item| inline_refs_inner(spec, item, stack))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array),
+        other => Ok(other.clone()),
+    }
+}
+
+/// Turn an operationId or method/path pair into a valid tool name
+fn tool_name(raw: &str) -> String {
+    let mut name = String::with_capacity(raw.len());
+    for c in raw.chars() {
+        let c = if c.is_ascii_alphanumeric() || c == '-' {
+            c
+        } else {
+            '_'
+        };
+        if !(c == '_' && name.ends_with('_')) {
+            name.push(c);
+        }
+    }
+    let name = name.trim_matches('_');
+    name.chars().take(MAX_TOOL_NAME_LEN).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(paths: Value) -> Value {
+        json!({
+            "openapi": "3.0.3",
+            "info": {"title": "Test", "version": "1.0"},
+            "paths": paths,
+            "components": {
+                "schemas": {
+                    "Pet": {
+                        "type": "object",
+                        "properties": {
+                            "name": {"type": "string"},
+                            "parent": {"$ref": "#/components/schemas/Pet"}
+                        },
+                        "required": ["name"]
+                    }
+                },
+                "parameters": {
+                    "PetId": {"name": "petId", "in": "path", "schema": {"type": "integer"}}
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn test_rejects_swagger_2() {
+        let err = parse_operations(&json!({"swagger": "2.0", "paths": {}})).unwrap_err();
+        assert!(matches!(err, OpenApiError::InvalidSpec(_)));
+    }
+
+    #[test]
+    fn test_body_parameter_renames_request_body() {
+        let spec = spec(json!({
+            "/notes": {
+                "post": {
+                    "operationId": "createNote",
+                    "parameters": [
+                        {"name": "body", "in": "query", "schema": {"type": "string"}}
+                    ],
+                    "requestBody": {
+                        "required": true,
+                        "content": {"application/json": {"schema": {"type": "object"}}}
+                    }
+                }
+            }
+        }));
+
+        let ops = parse_operations(&spec).unwrap();
+        let op = &ops[0];
+        assert_eq!(op.body.as_deref(), Some("request_body"));
+        assert_eq!(op.parameters[0].name, "body");
+        assert_eq!(op.input_schema["properties"]["body"]["type"], "string");
+        assert_eq!(
+            op.input_schema["properties"]["request_body"]["type"],
+            "object"
+        );
+        assert_eq!(op.input_schema["required"], json!(["request_body"]));
+    }
+
+    #[test]
+    fn test_parameters_and_body_become_input_schema() {
+        let spec = spec(json!({
+            "/pets/{petId}": {
+                "parameters": [{"$ref": "#/components/parameters/PetId"}],
+                "put": {
+                    "operationId": "updatePet",
+                    "summary": "Update a pet",
+                    "parameters": [
+                        {"name": "dryRun", "in": "query", "description": "Validate only",
+                         "schema": {"type": "boolean"}},
+                        {"name": "session", "in": "cookie", "schema": {"type": "string"}}
+                    ],
+                    "requestBody": {
+                        "required": true,
+                        "content": {
+                            "application/json": {"schema": {"$ref": "#/components/schemas/Pet"}}
+                        }
+                    }
+                }
+            }
+        }));
+
+        let ops = parse_operations(&spec).unwrap();
+        assert_eq!(ops.len(), 1);
+        let op = &ops[0];
+        assert_eq!(op.name, "updatePet");
+        assert_eq!(op.description, "Update a pet");
+        assert_eq!(op.method, Method::PUT);
+        assert_eq!(op.body.as_deref(), Some("body"));
+
+        let locations: Vec<_> = op
+            .parameters
+            .iter()
+            .map(|p| (p.name.as_str(), p.location))
+            .collect();
+        assert_eq!(
+            locations,
+            vec![
+                ("petId", ParameterLocation::Path),
+                ("dryRun", ParameterLocation::Query)
+            ]
+        );
+
+        let schema = &op.input_schema;
+        assert_eq!(schema["properties"]["petId"]["type"], "integer");
+        assert_eq!(
+            schema["properties"]["dryRun"]["description"],
+            "Validate only"
+        );
+        assert_eq!(schema["properties"]["body"]["required"], json!(["name"]));
+        // The recursive reference is cut off rather than expanded forever
+        assert_eq!(
+            schema["properties"]["body"]["properties"]["parent"],
+            json!({})
+        );
+        assert_eq!(schema["required"], json!(["petId", "body"]));
+        assert!(schema["properties"].get("session").is_none());
+    }
+
+    #[test]
+    fn test_operation_parameter_overrides_path_parameter() {
+        let spec = spec(json!({
+            "/items": {
+                "parameters": [{"name": "limit", "in": "query", "schema": {"type": "string"}}],
+                "get": {
+                    "parameters": [{"name": "limit", "in": "query", "required": true,
+                                    "schema": {"type": "integer"}}]
+                }
+            }
+        }));
+
+        let ops = parse_operations(&spec).unwrap();
+        assert_eq!(ops[0].parameters.len(), 1);
+        assert_eq!(
+            ops[0].input_schema["properties"]["limit"]["type"],
+            "integer"
+        );
+        assert_eq!(ops[0].input_schema["required"], json!(["limit"]));
+    }
+
+    #[test]
+    fn test_name_and_description_fallbacks() {
+        let spec = spec(json!({
+            "/users/{id}/posts": {"get": {}}
+        }));
+
+        let ops = parse_operations(&spec).unwrap();
+        assert_eq!(ops[0].name, "get_users_id_posts");
+        assert_eq!(ops[0].description, "GET /users/{id}/posts");
+    }
+
+    #[test]
+    fn test_skips_operation_with_required_non_json_body() {
+        let spec = spec(json!({
+            "/upload": {
+                "post": {
+                    "operationId": "upload",
+                    "requestBody": {
+                        "required": true,
+                        "content": {"multipart/form-data": {"schema": {"type": "object"}}}
+                    }
+                }
+            }
+        }));
+
+        assert!(parse_operations(&spec).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_unresolved_reference_is_an_error() {
+        let spec = spec(json!({
+            "/pets": {"get": {"parameters": [{"$ref": "#/components/parameters/Missing"}]}}
+        }));
+
+        let err = parse_operations(&spec).unwrap_err();
+        assert!(err.to_string().contains("Missing"));
+    }
+
+    #[test]
+    fn test_tool_name_sanitizes_and_truncates() {
+        assert_eq!(tool_name("list.pets v2"), "list_pets_v2");
+        assert_eq!(tool_name("a".repeat(100).as_str()).len(), MAX_TOOL_NAME_LEN);
+    }
+}
@@ -30,6 +30,10 @@ impl Tool for ForceTerminateTool {
         "Forcefully terminate a process session. Can use either graceful SIGTERM or force SIGKILL."
     }
 
+    fn is_destructive(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, input: Self::Input) -> std::result::Result<ToolResult, ToolError> {
         let manager = SESSION_MANAGER.lock().await;
 
@@ -41,6 +41,10 @@ impl Tool for InteractWithProcessTool {
         "Send input to a running process and optionally wait for its response. Useful for interactive programs."
     }
 
+    fn is_destructive(&self) -> bool {
+        true
+    }
+
     fn format_output_plain(&self, result: &ToolResult) -> String {
         let text = result.as_text();
         let (pid, input_sent, status, response) = parse_interact_output(&text);
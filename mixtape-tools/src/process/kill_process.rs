@@ -22,6 +22,10 @@ impl Tool for KillProcessTool {
         "Terminate a running process by its PID. Use with caution as this forcefully kills the process."
     }
 
+    fn is_destructive(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, input: Self::Input) -> std::result::Result<ToolResult, ToolError> {
         let mut sys = System::new();
         let pid = Pid::from_u32(input.pid);
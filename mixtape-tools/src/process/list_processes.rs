@@ -1,14 +1,53 @@
 use crate::prelude::*;
+use crate::utils::{default_max_list_items, TruncatedList, DEFAULT_MAX_LIST_ITEMS_LIMIT};
 use schemars::JsonSchema;
 use serde::Deserialize;
 use sysinfo::{ProcessesToUpdate, System};
 
-/// Input for listing processes (no parameters needed)
+/// Input for listing processes
 #[derive(Debug, Deserialize, JsonSchema)]
-pub struct ListProcessesInput {}
+pub struct ListProcessesInput {
+    /// Maximum number of processes to return (default: 200)
+    #[serde(default = "default_max_list_items")]
+    pub max_items: usize,
+}
+
+/// One row of the process table
+struct ProcessRow {
+    pid: u32,
+    name: String,
+    cpu: f32,
+    memory: u64,
+}
 
 /// Tool for listing running processes
-pub struct ListProcessesTool;
+pub struct ListProcessesTool {
+    max_items_limit: usize,
+}
+
+impl Default for ListProcessesTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ListProcessesTool {
+    /// Creates a tool that returns at most [`DEFAULT_MAX_LIST_ITEMS_LIMIT`]
+    /// processes per call
+    pub fn new() -> Self {
+        Self {
+            max_items_limit: DEFAULT_MAX_LIST_ITEMS_LIMIT,
+        }
+    }
+
+    /// Sets the most processes one call can return
+    ///
+    /// A larger `max_items` from the model is lowered to this limit.
+    pub fn with_max_items_limit(mut self, limit: usize) -> Self {
+        self.max_items_limit = limit;
+        self
+    }
+}
 
 impl Tool for ListProcessesTool {
     type Input = ListProcessesInput;
@@ -18,53 +57,31 @@ impl Tool for ListProcessesTool {
     }
 
     fn description(&self) -> &str {
-        "List all running processes on the system with their PID, name, CPU and memory usage."
+        "List running processes on the system with their PID, name, CPU and memory usage. \
+         Returns at most max_items processes, ordered by PID."
     }
 
-    async fn execute(&self, _input: Self::Input) -> std::result::Result<ToolResult, ToolError> {
+    async fn execute(&self, input: Self::Input) -> std::result::Result<ToolResult, ToolError> {
         let mut sys = System::new();
         sys.refresh_processes(ProcessesToUpdate::All);
 
-        let mut processes: Vec<_> = sys.processes().iter().collect();
-        processes.sort_by_key(|(pid, _)| pid.as_u32());
-
-        let mut output = String::from("PID     | NAME                          | CPU%  | MEMORY\n");
-        output.push_str("--------|-------------------------------|-------|----------\n");
-
-        for (pid, process) in processes.iter().take(50) {
-            let name = process.name().to_string_lossy();
-            let cpu = process.cpu_usage();
-            let memory = process.memory();
-
-            let memory_str = if memory < 1024 * 1024 {
-                format!("{:.1} KB", memory as f64 / 1024.0)
-            } else if memory < 1024 * 1024 * 1024 {
-                format!("{:.1} MB", memory as f64 / (1024.0 * 1024.0))
-            } else {
-                format!("{:.1} GB", memory as f64 / (1024.0 * 1024.0 * 1024.0))
-            };
-
-            output.push_str(&format!(
-                "{:<7} | {:<29} | {:>5.1} | {:>8}\n",
-                pid.as_u32(),
-                if name.len() > 29 {
-                    format!("{}...", &name[..26])
-                } else {
-                    name.to_string()
-                },
-                cpu,
-                memory_str
-            ));
-        }
-
-        if processes.len() > 50 {
-            output.push_str(&format!(
-                "\n... and {} more processes\n",
-                processes.len() - 50
-            ));
-        }
-
-        Ok(output.into())
+        let mut processes: Vec<ProcessRow> = sys
+            .processes()
+            .iter()
+            .map(|(pid, process)| ProcessRow {
+                pid: pid.as_u32(),
+                name: process.name().to_string_lossy().into_owned(),
+                cpu: process.cpu_usage(),
+                memory: process.memory(),
+            })
+            .collect();
+        processes.sort_by_key(|p| p.pid);
+
+        Ok(render_processes(&TruncatedList::new(
+            processes,
+            input.max_items.min(self.max_items_limit),
+        ))
+        .into())
     }
 
     fn format_output_plain(&self, result: &ToolResult) -> String {
@@ -145,6 +162,41 @@ impl Tool for ListProcessesTool {
     }
 }
 
+/// Render processes as a fixed-width table, noting any left out
+fn render_processes(processes: &TruncatedList<ProcessRow>) -> String {
+    let mut output = String::from("PID     | NAME                          | CPU%  | MEMORY\n");
+    output.push_str("--------|-------------------------------|-------|----------\n");
+
+    for process in &processes.items {
+        let memory = process.memory;
+        let memory_str = if memory < 1024 * 1024 {
+            format!("{:.1} KB", memory as f64 / 1024.0)
+        } else if memory < 1024 * 1024 * 1024 {
+            format!("{:.1} MB", memory as f64 / (1024.0 * 1024.0))
+        } else {
+            format!("{:.1} GB", memory as f64 / (1024.0 * 1024.0 * 1024.0))
+        };
+
+        output.push_str(&format!(
+            "{:<7} | {:<29} | {:>5.1} | {:>8}\n",
+            process.pid,
+            if process.name.len() > 29 {
+                format!("{}...", &process.name[..26])
+            } else {
+                process.name.clone()
+            },
+            process.cpu,
+            memory_str
+        ));
+    }
+
+    if let Some(note) = processes.note("processes") {
+        output.push_str(&format!("\n{}\n", note));
+    }
+
+    output
+}
+
 /// Create a visual bar for resource usage
 fn resource_bar(percent: f32, width: usize) -> String {
     let filled = ((percent / 100.0) * width as f32).round() as usize;
@@ -168,12 +220,15 @@ fn resource_color(percent: f32) -> &'static str {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::DEFAULT_MAX_LIST_ITEMS;
     use mixtape_core::ToolResult;
 
     #[tokio::test]
     async fn test_list_processes_basic() {
-        let tool = ListProcessesTool;
-        let input = ListProcessesInput {};
+        let tool = ListProcessesTool::new();
+        let input = ListProcessesInput {
+            max_items: DEFAULT_MAX_LIST_ITEMS,
+        };
 
         let result = tool.execute(input).await;
         assert!(result.is_ok());
@@ -188,8 +243,10 @@ mod tests {
 
     #[tokio::test]
     async fn test_list_processes_contains_processes() {
-        let tool = ListProcessesTool;
-        let input = ListProcessesInput {};
+        let tool = ListProcessesTool::new();
+        let input = ListProcessesInput {
+            max_items: DEFAULT_MAX_LIST_ITEMS,
+        };
 
         let result = tool.execute(input).await;
         assert!(result.is_ok());
@@ -202,8 +259,10 @@ mod tests {
 
     #[tokio::test]
     async fn test_list_processes_memory_formatting() {
-        let tool = ListProcessesTool;
-        let input = ListProcessesInput {};
+        let tool = ListProcessesTool::new();
+        let input = ListProcessesInput {
+            max_items: DEFAULT_MAX_LIST_ITEMS,
+        };
 
         let result = tool.execute(input).await;
         assert!(result.is_ok());
@@ -216,6 +275,58 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_list_processes_truncates_to_max_items() {
+        let result = ListProcessesTool::new()
+            .execute(ListProcessesInput { max_items: 1 })
+            .await
+            .unwrap();
+
+        let output = result.as_text();
+        let rows = output.lines().skip(2).filter(|l| l.contains('|')).count();
+        assert_eq!(rows, 1);
+        // At least this test process and its parent are running
+        assert!(output.contains("more processes not shown (total_count: "));
+    }
+
+    #[tokio::test]
+    async fn test_list_processes_clamps_max_items_to_limit() {
+        let result = ListProcessesTool::new()
+            .with_max_items_limit(1)
+            .execute(ListProcessesInput {
+                max_items: usize::MAX,
+            })
+            .await
+            .unwrap();
+
+        let output = result.as_text();
+        let rows = output.lines().skip(2).filter(|l| l.contains('|')).count();
+        assert_eq!(rows, 1);
+    }
+
+    #[test]
+    fn test_render_processes_reports_total_count() {
+        let rows = (1..=5)
+            .map(|pid| ProcessRow {
+                pid,
+                name: format!("proc{}", pid),
+                cpu: 0.0,
+                memory: 2048,
+            })
+            .collect();
+
+        let output = render_processes(&TruncatedList::new(rows, 2));
+
+        assert!(output.contains("proc1"));
+        assert!(output.contains("proc2"));
+        assert!(!output.contains("proc3"));
+        assert!(output.contains("... and 3 more processes not shown (total_count: 5"));
+
+        // The note is styled like the old overflow line
+        let formatted = ListProcessesTool::new().format_output_ansi(&output.into());
+        assert!(formatted.contains("\x1b[2m... and 3 more"));
+    }
+
     // ==================== resource_bar tests ====================
 
     #[test]
@@ -303,7 +414,7 @@ mod tests {
 
     #[test]
     fn test_format_output_plain() {
-        let tool = ListProcessesTool;
+        let tool = ListProcessesTool::new();
         let result: ToolResult = "PID     | NAME                          | CPU%  | MEMORY\n--------|-------------------------------|-------|----------\n1       | init                          |   0.0 |   10.0 MB".into();
 
         let formatted = tool.format_output_plain(&result);
@@ -315,7 +426,7 @@ mod tests {
 
     #[test]
     fn test_format_output_ansi_basic() {
-        let tool = ListProcessesTool;
+        let tool = ListProcessesTool::new();
         let result: ToolResult = "PID     | NAME                          | CPU%  | MEMORY\n--------|-------------------------------|-------|----------\n1       | init                          |   0.0 |   10.0 MB".into();
 
         let formatted = tool.format_output_ansi(&result);
@@ -328,7 +439,7 @@ mod tests {
 
     #[test]
     fn test_format_output_ansi_cpu_colors() {
-        let tool = ListProcessesTool;
+        let tool = ListProcessesTool::new();
 
         // Low CPU (green)
         let low_cpu: ToolResult = "PID     | NAME                          | CPU%  | MEMORY\n--------|-------------------------------|-------|----------\n1       | proc                          |   5.0 |   10.0 MB".into();
@@ -343,7 +454,7 @@ mod tests {
 
     #[test]
     fn test_format_output_ansi_with_overflow_indicator() {
-        let tool = ListProcessesTool;
+        let tool = ListProcessesTool::new();
         let result: ToolResult = "PID     | NAME                          | CPU%  | MEMORY\n--------|-------------------------------|-------|----------\n1       | proc                          |   5.0 |   10.0 MB\n... and 100 more processes".into();
 
         let formatted = tool.format_output_ansi(&result);
@@ -355,7 +466,7 @@ mod tests {
 
     #[test]
     fn test_format_output_markdown_basic() {
-        let tool = ListProcessesTool;
+        let tool = ListProcessesTool::new();
         let result: ToolResult = "PID     | NAME                          | CPU%  | MEMORY\n--------|-------------------------------|-------|----------\n1       | init                          |   0.0 |   10.0 MB".into();
 
         let formatted = tool.format_output_markdown(&result);
@@ -368,7 +479,7 @@ mod tests {
 
     #[test]
     fn test_format_output_markdown_with_overflow() {
-        let tool = ListProcessesTool;
+        let tool = ListProcessesTool::new();
         let result: ToolResult = "PID     | NAME                          | CPU%  | MEMORY\n--------|-------------------------------|-------|----------\n1       | proc                          |   5.0 |   10.0 MB\n... and 50 more processes".into();
 
         let formatted = tool.format_output_markdown(&result);
@@ -379,7 +490,7 @@ mod tests {
 
     #[test]
     fn test_format_output_markdown_short_input() {
-        let tool = ListProcessesTool;
+        let tool = ListProcessesTool::new();
         let result: ToolResult = "short".into();
 
         let formatted = tool.format_output_markdown(&result);
@@ -392,13 +503,13 @@ mod tests {
 
     #[test]
     fn test_tool_name() {
-        let tool = ListProcessesTool;
+        let tool = ListProcessesTool::new();
         assert_eq!(tool.name(), "list_processes");
     }
 
     #[test]
     fn test_tool_description() {
-        let tool = ListProcessesTool;
+        let tool = ListProcessesTool::new();
         assert!(!tool.description().is_empty());
         assert!(tool.description().contains("process"));
     }
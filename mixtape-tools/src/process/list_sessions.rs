@@ -1,12 +1,43 @@
 use crate::prelude::*;
 use crate::process::start_process::SESSION_MANAGER;
+use crate::utils::{default_max_list_items, TruncatedList, DEFAULT_MAX_LIST_ITEMS_LIMIT};
 
-/// Input for listing sessions (no parameters needed)
+/// Input for listing sessions
 #[derive(Debug, Deserialize, JsonSchema)]
-pub struct ListSessionsInput {}
+pub struct ListSessionsInput {
+    /// Maximum number of sessions to return (default: 200)
+    #[serde(default = "default_max_list_items")]
+    pub max_items: usize,
+}
 
 /// Tool for listing active process sessions
-pub struct ListSessionsTool;
+pub struct ListSessionsTool {
+    max_items_limit: usize,
+}
+
+impl Default for ListSessionsTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ListSessionsTool {
+    /// Creates a tool that returns at most [`DEFAULT_MAX_LIST_ITEMS_LIMIT`]
+    /// sessions per call
+    pub fn new() -> Self {
+        Self {
+            max_items_limit: DEFAULT_MAX_LIST_ITEMS_LIMIT,
+        }
+    }
+
+    /// Sets the most sessions one call can return
+    ///
+    /// A larger `max_items` from the model is lowered to this limit.
+    pub fn with_max_items_limit(mut self, limit: usize) -> Self {
+        self.max_items_limit = limit;
+        self
+    }
+}
 
 impl Tool for ListSessionsTool {
     type Input = ListSessionsInput;
@@ -19,19 +50,22 @@ impl Tool for ListSessionsTool {
         "List all active process sessions with their PIDs, commands, status, and runtime."
     }
 
-    async fn execute(&self, _input: Self::Input) -> std::result::Result<ToolResult, ToolError> {
+    async fn execute(&self, input: Self::Input) -> std::result::Result<ToolResult, ToolError> {
         let manager = SESSION_MANAGER.lock().await;
-        let sessions = manager.list_sessions().await;
+        let mut sessions = manager.list_sessions().await;
 
         if sessions.is_empty() {
             return Ok("No active sessions".into());
         }
+        sessions.sort_by_key(|(pid, ..)| *pid);
+        let sessions = TruncatedList::new(sessions, input.max_items.min(self.max_items_limit));
 
         let mut content = String::from("Active Sessions:\n\n");
         content.push_str("PID    | STATUS              | RUNTIME | COMMAND\n");
         content.push_str("-------|---------------------|---------|------------------\n");
 
-        for (pid, command, status, elapsed_ms) in sessions {
+        let note = sessions.note("sessions");
+        for (pid, command, status, elapsed_ms) in sessions.items {
             let runtime = if elapsed_ms < 1000 {
                 format!("{}ms", elapsed_ms)
             } else if elapsed_ms < 60_000 {
@@ -53,6 +87,10 @@ impl Tool for ListSessionsTool {
             ));
         }
 
+        if let Some(note) = note {
+            content.push_str(&format!("\n{}\n", note));
+        }
+
         Ok(content.into())
     }
 
@@ -68,6 +106,10 @@ impl Tool for ListSessionsTool {
         out.push('\n');
 
         for line in lines.iter().skip(4) {
+            if line.starts_with("...") {
+                out.push_str(&format!("{}\n", line));
+                continue;
+            }
             let parts: Vec<&str> = line.split('|').collect();
             if parts.len() >= 4 {
                 let (pid, status, runtime, command) = (
@@ -107,6 +149,10 @@ impl Tool for ListSessionsTool {
         out.push_str(&format!("\x1b[2m{}\x1b[0m\n", "─".repeat(60)));
 
         for line in lines.iter().skip(4) {
+            if line.starts_with("...") {
+                out.push_str(&format!("\x1b[2m{}\x1b[0m\n", line));
+                continue;
+            }
             let parts: Vec<&str> = line.split('|').collect();
             if parts.len() >= 4 {
                 let (pid, status, runtime, command) = (
@@ -148,6 +194,10 @@ impl Tool for ListSessionsTool {
         let mut out = String::from("### Sessions\n\n| Status | PID | Command | Runtime |\n|--------|-----|---------|--------|\n");
 
         for line in lines.iter().skip(4) {
+            if line.starts_with("...") {
+                out.push_str(&format!("\n*{}*\n", line));
+                continue;
+            }
             let parts: Vec<&str> = line.split('|').collect();
             if parts.len() >= 4 {
                 let (pid, status, runtime, command) = (
@@ -212,12 +262,15 @@ fn format_runtime_nice(runtime_str: &str) -> String {
 mod tests {
     use super::*;
     use crate::process::start_process::{StartProcessInput, StartProcessTool};
+    use crate::utils::DEFAULT_MAX_LIST_ITEMS;
     use mixtape_core::ToolResult;
 
     #[tokio::test]
     async fn test_list_sessions_empty() {
-        let tool = ListSessionsTool;
-        let input = ListSessionsInput {};
+        let tool = ListSessionsTool::new();
+        let input = ListSessionsInput {
+            max_items: DEFAULT_MAX_LIST_ITEMS,
+        };
 
         let result = tool.execute(input).await;
         assert!(result.is_ok());
@@ -255,8 +308,10 @@ mod tests {
         let _ = start_tool.execute(input2).await;
 
         // Now list sessions
-        let list_tool = ListSessionsTool;
-        let list_input = ListSessionsInput {};
+        let list_tool = ListSessionsTool::new();
+        let list_input = ListSessionsInput {
+            max_items: DEFAULT_MAX_LIST_ITEMS,
+        };
 
         let result = list_tool.execute(list_input).await;
         assert!(result.is_ok());
@@ -286,8 +341,10 @@ mod tests {
         // Wait a moment for runtime to accumulate
         tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
 
-        let list_tool = ListSessionsTool;
-        let list_input = ListSessionsInput {};
+        let list_tool = ListSessionsTool::new();
+        let list_input = ListSessionsInput {
+            max_items: DEFAULT_MAX_LIST_ITEMS,
+        };
 
         let result = list_tool.execute(list_input).await;
         assert!(result.is_ok());
@@ -350,7 +407,7 @@ mod tests {
 
     #[test]
     fn test_format_output_plain_no_sessions() {
-        let tool = ListSessionsTool;
+        let tool = ListSessionsTool::new();
         let result: ToolResult = "No active sessions".into();
 
         let formatted = tool.format_output_plain(&result);
@@ -359,7 +416,7 @@ mod tests {
 
     #[test]
     fn test_format_output_plain_with_sessions() {
-        let tool = ListSessionsTool;
+        let tool = ListSessionsTool::new();
         let result: ToolResult = "Active Sessions:\n\nPID    | STATUS              | RUNTIME | COMMAND\n-------|---------------------|---------|------------------\n12345  | Running             | 500ms   | echo hello".into();
 
         let formatted = tool.format_output_plain(&result);
@@ -371,7 +428,7 @@ mod tests {
 
     #[test]
     fn test_format_output_ansi_no_sessions() {
-        let tool = ListSessionsTool;
+        let tool = ListSessionsTool::new();
         let result: ToolResult = "No active sessions".into();
 
         let formatted = tool.format_output_ansi(&result);
@@ -381,7 +438,7 @@ mod tests {
 
     #[test]
     fn test_format_output_ansi_with_sessions() {
-        let tool = ListSessionsTool;
+        let tool = ListSessionsTool::new();
         let result: ToolResult = "Active Sessions:\n\nPID    | STATUS              | RUNTIME | COMMAND\n-------|---------------------|---------|------------------\n12345  | Running             | 500ms   | sleep 10".into();
 
         let formatted = tool.format_output_ansi(&result);
@@ -393,7 +450,7 @@ mod tests {
 
     #[test]
     fn test_format_output_ansi_status_colors() {
-        let tool = ListSessionsTool;
+        let tool = ListSessionsTool::new();
 
         // Running = green
         let running: ToolResult = "Active Sessions:\n\nPID    | STATUS              | RUNTIME | COMMAND\n-------|---------------------|---------|------------------\n1      | Running             | 1ms     | cmd".into();
@@ -408,7 +465,7 @@ mod tests {
 
     #[test]
     fn test_format_output_markdown_no_sessions() {
-        let tool = ListSessionsTool;
+        let tool = ListSessionsTool::new();
         let result: ToolResult = "No active sessions".into();
 
         let formatted = tool.format_output_markdown(&result);
@@ -417,7 +474,7 @@ mod tests {
 
     #[test]
     fn test_format_output_markdown_with_sessions() {
-        let tool = ListSessionsTool;
+        let tool = ListSessionsTool::new();
         let result: ToolResult = "Active Sessions:\n\nPID    | STATUS              | RUNTIME | COMMAND\n-------|---------------------|---------|------------------\n12345  | Running             | 500ms   | echo hello".into();
 
         let formatted = tool.format_output_markdown(&result);
@@ -429,7 +486,7 @@ mod tests {
 
     #[test]
     fn test_format_output_markdown_status_emojis() {
-        let tool = ListSessionsTool;
+        let tool = ListSessionsTool::new();
 
         // Running = green circle
         let running: ToolResult = "Active Sessions:\n\nPID    | STATUS              | RUNTIME | COMMAND\n-------|---------------------|---------|------------------\n1      | Running             | 1ms     | cmd".into();
@@ -448,13 +505,13 @@ mod tests {
 
     #[test]
     fn test_tool_name() {
-        let tool = ListSessionsTool;
+        let tool = ListSessionsTool::new();
         assert_eq!(tool.name(), "list_sessions");
     }
 
     #[test]
     fn test_tool_description() {
-        let tool = ListSessionsTool;
+        let tool = ListSessionsTool::new();
         assert!(!tool.description().is_empty());
         assert!(tool.description().contains("session"));
     }
@@ -24,8 +24,8 @@ pub fn all_tools() -> Vec<Box<dyn DynTool>> {
         box_tool(StartProcessTool),
         box_tool(InteractWithProcessTool),
         box_tool(ReadProcessOutputTool),
-        box_tool(ListSessionsTool),
-        box_tool(ListProcessesTool),
+        box_tool(ListSessionsTool::new()),
+        box_tool(ListProcessesTool::new()),
         box_tool(KillProcessTool),
         box_tool(ForceTerminateTool),
     ]
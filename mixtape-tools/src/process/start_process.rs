@@ -36,6 +36,10 @@ impl Tool for StartProcessTool {
         "Start a new process session. Returns a PID that can be used to interact with the process, read its output, or terminate it."
     }
 
+    fn is_destructive(&self) -> bool {
+        true
+    }
+
     fn format_output_plain(&self, result: &ToolResult) -> String {
         let text = result.as_text();
         let (command, pid, status, output_lines) = parse_start_output(&text);
@@ -52,7 +52,7 @@ impl ConfiguredReadQueryTool {
     pub fn new(config: SqliteConfig) -> Self {
         Self {
             config: Arc::new(config),
-            inner: ReadQueryTool,
+            inner: ReadQueryTool::new(),
         }
     }
 
@@ -60,7 +60,7 @@ impl ConfiguredReadQueryTool {
     pub fn with_shared_config(config: Arc<SqliteConfig>) -> Self {
         Self {
             config,
-            inner: ReadQueryTool,
+            inner: ReadQueryTool::new(),
         }
     }
 }
@@ -76,6 +76,10 @@ impl Tool for ConfiguredReadQueryTool {
         self.inner.description()
     }
 
+    fn is_destructive(&self) -> bool {
+        self.inner.is_destructive()
+    }
+
     async fn execute(&self, mut input: Self::Input) -> Result<ToolResult, ToolError> {
         input.db_path = self.config.effective_db_path(input.db_path);
         validate_query(&self.config, &input.query)?;
@@ -118,6 +122,10 @@ impl Tool for ConfiguredWriteQueryTool {
         self.inner.description()
     }
 
+    fn is_destructive(&self) -> bool {
+        self.inner.is_destructive()
+    }
+
     async fn execute(&self, mut input: Self::Input) -> Result<ToolResult, ToolError> {
         input.db_path = self.config.effective_db_path(input.db_path);
         validate_query(&self.config, &input.query)?;
@@ -160,6 +168,10 @@ impl Tool for ConfiguredSchemaQueryTool {
         self.inner.description()
     }
 
+    fn is_destructive(&self) -> bool {
+        self.inner.is_destructive()
+    }
+
     async fn execute(&self, mut input: Self::Input) -> Result<ToolResult, ToolError> {
         input.db_path = self.config.effective_db_path(input.db_path);
         validate_query(&self.config, &input.query)?;
@@ -202,6 +214,10 @@ impl Tool for ConfiguredBulkInsertTool {
         self.inner.description()
     }
 
+    fn is_destructive(&self) -> bool {
+        self.inner.is_destructive()
+    }
+
     async fn execute(&self, mut input: Self::Input) -> Result<ToolResult, ToolError> {
         input.db_path = self.config.effective_db_path(input.db_path);
         if !self.config.can_write(&input.table) {
@@ -2,7 +2,7 @@
 
 use crate::prelude::*;
 use crate::sqlite::error::SqliteToolError;
-use crate::sqlite::manager::DATABASE_MANAGER;
+use crate::sqlite::manager::current_manager;
 
 /// Input for closing a database
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -33,23 +33,19 @@ impl Tool for CloseDatabaseTool {
     async fn execute(&self, input: Self::Input) -> Result<ToolResult, ToolError> {
         let db_name = input.db_path.clone();
 
+        let manager = current_manager();
         let result = tokio::task::spawn_blocking(move || {
             let name = match &db_name {
                 Some(n) => n.as_str(),
                 None => {
-                    DATABASE_MANAGER
-                        .get_default()
-                        .ok_or(SqliteToolError::NoDefaultDatabase)?
-                        .as_str()
-                        .to_string();
                     // Get the default and close it
-                    let default = DATABASE_MANAGER
+                    let default = manager
                         .get_default()
                         .ok_or(SqliteToolError::NoDefaultDatabase)?;
-                    return DATABASE_MANAGER.close(&default);
+                    return manager.close(&default);
                 }
             };
-            DATABASE_MANAGER.close(name)
+            manager.close(name)
         })
         .await
         .map_err(|e| ToolError::Custom(format!("Task join error: {}", e)))?;
@@ -73,6 +69,7 @@ impl Tool for CloseDatabaseTool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sqlite::manager::DATABASE_MANAGER;
     use crate::sqlite::test_utils::TestDatabase;
 
     #[tokio::test]
@@ -1,7 +1,8 @@
 //! List databases tool
 
 use crate::prelude::*;
-use crate::sqlite::manager::DATABASE_MANAGER;
+use crate::sqlite::manager::current_manager;
+use crate::utils::{default_max_list_items, TruncatedList, DEFAULT_MAX_LIST_ITEMS_LIMIT};
 use std::path::PathBuf;
 
 /// Input for listing database files
@@ -14,6 +15,10 @@ pub struct ListDatabasesInput {
     /// Whether to search recursively (default: false)
     #[serde(default)]
     pub recursive: bool,
+
+    /// Maximum number of databases to return (default: 200)
+    #[serde(default = "default_max_list_items")]
+    pub max_items: usize,
 }
 
 /// Database file information
@@ -28,7 +33,33 @@ struct DatabaseFile {
 ///
 /// Searches for files with common SQLite extensions (.db, .sqlite, .sqlite3)
 /// and returns information about each found database.
-pub struct ListDatabasesTool;
+pub struct ListDatabasesTool {
+    max_items_limit: usize,
+}
+
+impl Default for ListDatabasesTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ListDatabasesTool {
+    /// Creates a tool that returns at most [`DEFAULT_MAX_LIST_ITEMS_LIMIT`]
+    /// databases per call
+    pub fn new() -> Self {
+        Self {
+            max_items_limit: DEFAULT_MAX_LIST_ITEMS_LIMIT,
+        }
+    }
+
+    /// Sets the most databases one call can return
+    ///
+    /// A larger `max_items` from the model is lowered to this limit.
+    pub fn with_max_items_limit(mut self, limit: usize) -> Self {
+        self.max_items_limit = limit;
+        self
+    }
+}
 
 impl Tool for ListDatabasesTool {
     type Input = ListDatabasesInput;
@@ -38,7 +69,7 @@ impl Tool for ListDatabasesTool {
     }
 
     fn description(&self) -> &str {
-        "Discover SQLite database files in a directory. Searches for .db, .sqlite, and .sqlite3 files. Also shows currently open databases."
+        "Discover SQLite database files in a directory. Searches for .db, .sqlite, and .sqlite3 files. Also shows currently open databases. Returns at most max_items databases; total_count gives the number found."
     }
 
     async fn execute(&self, input: Self::Input) -> Result<ToolResult, ToolError> {
@@ -48,6 +79,7 @@ impl Tool for ListDatabasesTool {
 
         let recursive = input.recursive;
 
+        let manager = current_manager();
         let result = tokio::task::spawn_blocking(move || {
             let mut databases = Vec::new();
             let extensions = ["db", "sqlite", "sqlite3"];
@@ -64,7 +96,7 @@ impl Tool for ListDatabasesTool {
                     if let Ok(metadata) = std::fs::metadata(&path) {
                         let path_str = path.to_string_lossy().to_string();
                         databases.push(DatabaseFile {
-                            is_open: DATABASE_MANAGER.is_open(&path_str),
+                            is_open: manager.is_open(&path_str),
                             path: path_str,
                             size_bytes: metadata.len(),
                         });
@@ -73,7 +105,7 @@ impl Tool for ListDatabasesTool {
             }
 
             // Also include currently open databases that might not be in the searched directory
-            for open_db in DATABASE_MANAGER.list_open() {
+            for open_db in manager.list_open() {
                 if !databases.iter().any(|d| d.path == open_db) {
                     if let Ok(metadata) = std::fs::metadata(&open_db) {
                         databases.push(DatabaseFile {
@@ -90,11 +122,17 @@ impl Tool for ListDatabasesTool {
         .await
         .map_err(|e| ToolError::Custom(format!("Task join error: {}", e)))?;
 
-        let response = serde_json::json!({
-            "databases": result,
-            "count": result.len(),
-            "open_count": result.iter().filter(|d| d.is_open).count()
+        let open_count = result.iter().filter(|d| d.is_open).count();
+        let result = TruncatedList::new(result, input.max_items.min(self.max_items_limit));
+        let mut response = serde_json::json!({
+            "databases": result.items,
+            "count": result.items.len(),
+            "total_count": result.total_count,
+            "open_count": open_count
         });
+        if let Some(note) = result.note("databases") {
+            response["note"] = note.into();
+        }
 
         Ok(ToolResult::Json(response))
     }
@@ -154,6 +192,7 @@ fn search_recursive(dir: &PathBuf, extensions: &[&str]) -> std::io::Result<Vec<P
 mod tests {
     use super::*;
     use crate::sqlite::test_utils::TestDatabase;
+    use crate::utils::DEFAULT_MAX_LIST_ITEMS;
     use tempfile::TempDir;
 
     #[tokio::test]
@@ -166,10 +205,11 @@ mod tests {
         std::fs::write(temp_dir.path().join("test3.sqlite3"), "").unwrap();
         std::fs::write(temp_dir.path().join("not_a_db.txt"), "").unwrap();
 
-        let tool = ListDatabasesTool;
+        let tool = ListDatabasesTool::new();
         let input = ListDatabasesInput {
             directory: Some(temp_dir.path().to_path_buf()),
             recursive: false,
+            max_items: DEFAULT_MAX_LIST_ITEMS,
         };
 
         let result = tool.execute(input).await.unwrap();
@@ -212,10 +252,11 @@ mod tests {
         std::fs::write(subdir.join("sub.sqlite"), "").unwrap();
         std::fs::write(nested.join("deep.sqlite3"), "").unwrap();
 
-        let tool = ListDatabasesTool;
+        let tool = ListDatabasesTool::new();
         let input = ListDatabasesInput {
             directory: Some(temp_dir.path().to_path_buf()),
             recursive: true,
+            max_items: DEFAULT_MAX_LIST_ITEMS,
         };
 
         let result = tool.execute(input).await.unwrap();
@@ -255,10 +296,11 @@ mod tests {
         // Create normal file
         std::fs::write(temp_dir.path().join("visible.db"), "").unwrap();
 
-        let tool = ListDatabasesTool;
+        let tool = ListDatabasesTool::new();
         let input = ListDatabasesInput {
             directory: Some(temp_dir.path().to_path_buf()),
             recursive: true,
+            max_items: DEFAULT_MAX_LIST_ITEMS,
         };
 
         let result = tool.execute(input).await.unwrap();
@@ -300,10 +342,11 @@ mod tests {
         // Create another file in the temp dir that's not open
         std::fs::write(temp_dir.path().join("closed.db"), "").unwrap();
 
-        let tool = ListDatabasesTool;
+        let tool = ListDatabasesTool::new();
         let input = ListDatabasesInput {
             directory: Some(temp_dir.path().to_path_buf()),
             recursive: false,
+            max_items: DEFAULT_MAX_LIST_ITEMS,
         };
 
         let result = tool.execute(input).await.unwrap();
@@ -324,10 +367,11 @@ mod tests {
     async fn test_list_databases_empty_directory() {
         let temp_dir = TempDir::new().unwrap();
 
-        let tool = ListDatabasesTool;
+        let tool = ListDatabasesTool::new();
         let input = ListDatabasesInput {
             directory: Some(temp_dir.path().to_path_buf()),
             recursive: false,
+            max_items: DEFAULT_MAX_LIST_ITEMS,
         };
 
         let result = tool.execute(input).await.unwrap();
@@ -343,10 +387,11 @@ mod tests {
 
     #[tokio::test]
     async fn test_list_databases_default_directory() {
-        let tool = ListDatabasesTool;
+        let tool = ListDatabasesTool::new();
         let input = ListDatabasesInput {
             directory: None, // Use default (current directory)
             recursive: false,
+            max_items: DEFAULT_MAX_LIST_ITEMS,
         };
 
         // Should not panic, even if no databases in current directory
@@ -384,7 +429,7 @@ mod tests {
 
     #[test]
     fn test_tool_metadata() {
-        let tool = ListDatabasesTool;
+        let tool = ListDatabasesTool::new();
         assert_eq!(tool.name(), "sqlite_list_databases");
         assert!(!tool.description().is_empty());
     }
@@ -1,7 +1,7 @@
 //! Open database tool
 
 use crate::prelude::*;
-use crate::sqlite::manager::DATABASE_MANAGER;
+use crate::sqlite::manager::current_manager;
 use std::path::PathBuf;
 
 /// Input for opening a database
@@ -38,11 +38,11 @@ impl Tool for OpenDatabaseTool {
     }
 
     async fn execute(&self, input: Self::Input) -> Result<ToolResult, ToolError> {
-        let result = tokio::task::spawn_blocking(move || {
-            DATABASE_MANAGER.open(&input.db_path, input.create)
-        })
-        .await
-        .map_err(|e| ToolError::Custom(format!("Task join error: {}", e)))?;
+        let manager = current_manager();
+        let result =
+            tokio::task::spawn_blocking(move || manager.open(&input.db_path, input.create))
+                .await
+                .map_err(|e| ToolError::Custom(format!("Task join error: {}", e)))?;
 
         match result {
             Ok(db_name) => {
@@ -61,6 +61,7 @@ impl Tool for OpenDatabaseTool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sqlite::manager::DATABASE_MANAGER;
     use tempfile::TempDir;
 
     #[tokio::test]
@@ -51,6 +51,10 @@ pub enum SqliteToolError {
     #[error("Table not found: {0}")]
     TableNotFound(String),
 
+    /// Query would fully scan a table larger than the configured limit
+    #[error("Query would scan every row of table '{table}' (more than {max_rows} rows). Add a WHERE clause on an indexed column, or create an index for this query.")]
+    FullTableScan { table: String, max_rows: u64 },
+
     /// Migration not found
     #[error("Migration not found: {0}")]
     MigrationNotFound(String),
@@ -29,6 +29,10 @@ impl Tool for VacuumDatabaseTool {
         "Optimize database storage by rebuilding the database file. Reclaims unused space and defragments the database."
     }
 
+    fn is_destructive(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, input: Self::Input) -> Result<ToolResult, ToolError> {
         let (size_before, size_after) = with_connection(input.db_path, |conn| {
             // Get database path and size before vacuum
@@ -3,6 +3,10 @@
 //! Provides a singleton pattern for managing multiple database connections
 //! across tool invocations.
 //!
+//! Tools use the global `DATABASE_MANAGER` unless they were wrapped by a
+//! [`ScopedManager`](super::ScopedManager), in which case they use that
+//! manager's connections instead.
+//!
 //! # Test Isolation
 //!
 //! For test isolation, create local `DatabaseManager` instances instead of using
@@ -20,7 +24,23 @@ use std::sync::{Arc, Mutex, RwLock};
 
 lazy_static! {
     /// Global database manager instance
-    pub static ref DATABASE_MANAGER: DatabaseManager = DatabaseManager::new();
+    pub static ref DATABASE_MANAGER: Arc<DatabaseManager> = Arc::new(DatabaseManager::new());
+}
+
+tokio::task_local! {
+    /// Manager for the tool call currently running under a `ScopedManager`
+    pub(super) static SCOPED_MANAGER: Arc<DatabaseManager>;
+}
+
+/// The manager the current tool call should use
+///
+/// This is the [`ScopedManager`](super::ScopedManager) the tool was wrapped
+/// by, or the global `DATABASE_MANAGER`. Call it before spawning blocking
+/// work: the scope does not follow into `spawn_blocking`.
+pub fn current_manager() -> Arc<DatabaseManager> {
+    SCOPED_MANAGER
+        .try_with(Arc::clone)
+        .unwrap_or_else(|_| Arc::clone(&DATABASE_MANAGER))
 }
 
 /// Executes a closure with a database connection in a blocking task.
@@ -45,8 +65,9 @@ where
     T: Send + 'static,
     F: FnOnce(&Connection) -> Result<T, SqliteToolError> + Send + 'static,
 {
+    let manager = current_manager();
     tokio::task::spawn_blocking(move || {
-        let conn = DATABASE_MANAGER.get(db_path.as_deref())?;
+        let conn = manager.get(db_path.as_deref())?;
         let conn = conn.lock().unwrap();
         f(&conn)
     })
@@ -79,6 +79,10 @@ impl Tool for ImportMigrationsTool {
          sqlite_run_migrations."
     }
 
+    fn is_destructive(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, input: Self::Input) -> Result<ToolResult, ToolError> {
         let migrations = input.migrations;
         let on_conflict = input.on_conflict;
@@ -35,6 +35,10 @@ impl Tool for RemoveMigrationTool {
          can be removed. Use sqlite_list_migrations to see pending migrations."
     }
 
+    fn is_destructive(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, input: Self::Input) -> Result<ToolResult, ToolError> {
         let version = input.version;
 
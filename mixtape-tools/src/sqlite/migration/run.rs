@@ -2,7 +2,7 @@
 
 use crate::prelude::*;
 use crate::sqlite::error::SqliteToolError;
-use crate::sqlite::manager::DATABASE_MANAGER;
+use crate::sqlite::manager::current_manager;
 use chrono::Utc;
 
 use super::{compute_checksum, ensure_migrations_table, MIGRATIONS_TABLE};
@@ -33,9 +33,14 @@ impl Tool for RunMigrationsTool {
          transaction. If a migration fails, it is rolled back and no further migrations run."
     }
 
+    fn is_destructive(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, input: Self::Input) -> Result<ToolResult, ToolError> {
+        let manager = current_manager();
         let result = tokio::task::spawn_blocking(move || -> Result<_, SqliteToolError> {
-            let conn = DATABASE_MANAGER.get(input.db_path.as_deref())?;
+            let conn = manager.get(input.db_path.as_deref())?;
             let mut conn = conn.lock().unwrap();
 
             // Ensure migrations table exists
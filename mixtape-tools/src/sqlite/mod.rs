@@ -85,6 +85,34 @@
 //!     .await?;
 //! ```
 //!
+//! # Isolated Connections
+//!
+//! The tool groups above share one process-wide [`DATABASE_MANAGER`], so
+//! every agent in the process sees, and can close, every open database.
+//! For multi-tenant servers, give each agent its own manager:
+//!
+//! ```rust,ignore
+//! use mixtape_tools::sqlite;
+//!
+//! let (tools, db) = sqlite::scoped_tools();
+//! let agent = Agent::builder()
+//!     .add_tools(tools)
+//!     .build()
+//!     .await?;
+//! ```
+//!
+//! Migrating from the global manager:
+//!
+//! - Replace `add_tools(sqlite::all_tools())` with the tools from
+//!   [`scoped_tools()`], or wrap any group with [`ScopedManager::scope`].
+//! - Replace direct `DATABASE_MANAGER` calls in the host application
+//!   (pre-opening databases, `close_all()` on shutdown) with the same calls
+//!   on the returned [`ScopedManager`], which dereferences to
+//!   [`DatabaseManager`].
+//! - Custom tools that call [`with_connection`] need no changes: it uses the
+//!   manager of the scope the tool runs in. Code that calls
+//!   `DATABASE_MANAGER` directly should call [`current_manager()`] instead.
+//!
 //! # Fine-Grained Permissions
 //!
 //! For tighter control, use configured tools that restrict access to specific
@@ -181,6 +209,7 @@ pub mod maintenance;
 pub mod manager;
 pub mod migration;
 pub mod query;
+mod scoped;
 mod sql_parser;
 pub mod table;
 #[cfg(test)]
@@ -199,12 +228,13 @@ pub use configured::{
 pub use database::{CloseDatabaseTool, DatabaseInfoTool, ListDatabasesTool, OpenDatabaseTool};
 pub use error::SqliteToolError;
 pub use maintenance::{BackupDatabaseTool, ExportSchemaTool, VacuumDatabaseTool};
-pub use manager::{with_connection, DATABASE_MANAGER};
+pub use manager::{current_manager, with_connection, DatabaseManager, DATABASE_MANAGER};
 pub use migration::{
     AddMigrationTool, ExportMigrationsTool, GetMigrationTool, ImportMigrationsTool,
     ListMigrationsTool, RemoveMigrationTool, RunMigrationsTool,
 };
 pub use query::{BulkInsertTool, ReadQueryTool, SchemaQueryTool, WriteQueryTool};
+pub use scoped::ScopedManager;
 pub use table::{DescribeTableTool, ListTablesTool};
 pub use transaction::{BeginTransactionTool, CommitTransactionTool, RollbackTransactionTool};
 pub use types::*;
@@ -218,11 +248,11 @@ pub fn read_only_tools() -> Vec<Box<dyn DynTool>> {
     vec![
         box_tool(OpenDatabaseTool),
         box_tool(CloseDatabaseTool),
-        box_tool(ListDatabasesTool),
+        box_tool(ListDatabasesTool::new()),
         box_tool(DatabaseInfoTool),
-        box_tool(ListTablesTool),
+        box_tool(ListTablesTool::new()),
         box_tool(DescribeTableTool),
-        box_tool(ReadQueryTool),
+        box_tool(ReadQueryTool::new()),
         box_tool(ExportSchemaTool),
         box_tool(BackupDatabaseTool),
     ]
@@ -272,6 +302,18 @@ pub fn all_tools() -> Vec<Box<dyn DynTool>> {
     tools
 }
 
+/// Returns all SQLite tools bound to a new, private database manager
+///
+/// Unlike [`all_tools`], whose databases live in the process-wide
+/// [`DATABASE_MANAGER`], these tools only see databases opened through the
+/// returned [`ScopedManager`]. Give each agent its own set when agents
+/// serving different users share a process. To scope a smaller group, use
+/// [`ScopedManager::scope`], e.g. `ScopedManager::new().scope(read_only_tools())`.
+pub fn scoped_tools() -> (Vec<Box<dyn DynTool>>, ScopedManager) {
+    let manager = ScopedManager::new();
+    (manager.scope(all_tools()), manager)
+}
+
 // =============================================================================
 // Configured tool factory functions
 // =============================================================================
@@ -481,6 +523,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_destructive_tools() {
+        let mut destructive: Vec<_> = all_tools()
+            .iter()
+            .filter(|t| t.is_destructive())
+            .map(|t| t.name().to_string())
+            .collect();
+        destructive.sort();
+        assert_eq!(
+            destructive,
+            vec![
+                "sqlite_bulk_insert",
+                "sqlite_import_migrations",
+                "sqlite_remove_migration",
+                "sqlite_run_migrations",
+                "sqlite_schema_query",
+                "sqlite_vacuum",
+                "sqlite_write_query",
+            ]
+        );
+    }
+
     #[test]
     fn test_no_duplicate_tool_names() {
         let tools = all_tools();
@@ -52,6 +52,10 @@ impl Tool for BulkInsertTool {
         "Efficiently insert multiple records into a table using batched transactions. Each record is an object with column names as keys."
     }
 
+    fn is_destructive(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, input: Self::Input) -> Result<ToolResult, ToolError> {
         if input.data.is_empty() {
             return Ok(ToolResult::Json(serde_json::json!({
@@ -3,8 +3,11 @@
 use crate::prelude::*;
 use crate::sqlite::error::SqliteToolError;
 use crate::sqlite::manager::with_connection;
+use crate::sqlite::sql_parser::extract_table_aliases;
 use crate::sqlite::types::{json_to_sql, QueryResult};
+use mixtape_core::tool::schema_of;
 use rusqlite::types::ValueRef;
+use rusqlite::{Connection, OptionalExtension, ToSql};
 
 /// Input for read query execution
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -62,9 +65,33 @@ fn default_limit() -> usize {
 ///
 /// Executes SELECT, PRAGMA, and EXPLAIN queries.
 /// Other query types will be rejected for safety.
-pub struct ReadQueryTool;
+///
+/// With [`with_scan_guard`](Self::with_scan_guard), each query is checked with
+/// `EXPLAIN QUERY PLAN` first and rejected if it would scan every row of a
+/// table larger than the limit.
+#[derive(Debug, Default)]
+pub struct ReadQueryTool {
+    max_scan_rows: Option<u64>,
+}
 
 impl ReadQueryTool {
+    /// Creates a read query tool with no scan guard
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a read query tool that rejects full scans of large tables
+    ///
+    /// A query is rejected when its plan scans a table (rather than searching
+    /// it through an index or primary key) and that table holds more than
+    /// `max_scan_rows` rows. Scans of views, CTEs, and subqueries are not
+    /// checked; only scans of stored tables are.
+    pub fn with_scan_guard(max_scan_rows: u64) -> Self {
+        Self {
+            max_scan_rows: Some(max_scan_rows),
+        }
+    }
+
     /// Validates that a query is read-only
     fn is_read_only(sql: &str) -> bool {
         let normalized = sql.trim().to_uppercase();
@@ -84,6 +111,70 @@ impl ReadQueryTool {
     }
 }
 
+/// Reject the query if its plan fully scans a table with more than `max_rows` rows
+fn check_scan_guard(
+    conn: &Connection,
+    query: &str,
+    params: &[&dyn ToSql],
+    max_rows: u64,
+) -> Result<(), SqliteToolError> {
+    let normalized = query.trim_start().to_uppercase();
+    if !(normalized.starts_with("SELECT") || normalized.starts_with("WITH")) {
+        return Ok(());
+    }
+
+    let mut stmt = conn.prepare(&format!("EXPLAIN QUERY PLAN {}", query))?;
+    let details: Vec<String> = stmt
+        .query_map(params, |row| row.get::<_, String>(3))?
+        .collect::<Result<_, _>>()?;
+
+    let aliases = extract_table_aliases(query);
+    for detail in &details {
+        let Some(scanned) = scanned_name(detail) else {
+            continue;
+        };
+        let name = aliases.get(scanned).map(String::as_str).unwrap_or(scanned);
+        let Some(table) = stored_table(conn, name)? else {
+            continue;
+        };
+
+        // Count at most max_rows + 1 rows so the check stays cheap on huge tables
+        let rows: i64 = conn.query_row(
+            &format!(
+                "SELECT COUNT(*) FROM (SELECT 1 FROM \"{}\" LIMIT {})",
+                table.replace('"', "\"\""),
+                max_rows.saturating_add(1)
+            ),
+            [],
+            |row| row.get(0),
+        )?;
+        if rows as u64 > max_rows {
+            return Err(SqliteToolError::FullTableScan { table, max_rows });
+        }
+    }
+
+    Ok(())
+}
+
+/// Name scanned by an `EXPLAIN QUERY PLAN` step, e.g. `users` from
+/// `SCAN users USING COVERING INDEX idx` (or `SCAN TABLE users` on older SQLite)
+fn scanned_name(detail: &str) -> Option<&str> {
+    let rest = detail.strip_prefix("SCAN ")?;
+    let rest = rest.strip_prefix("TABLE ").unwrap_or(rest);
+    rest.split_whitespace().next()
+}
+
+/// Canonical name of a stored table, or `None` for views, CTEs, and subqueries
+fn stored_table(conn: &Connection, name: &str) -> Result<Option<String>, SqliteToolError> {
+    Ok(conn
+        .query_row(
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name = ?1 COLLATE NOCASE",
+            [name],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
 impl Tool for ReadQueryTool {
     type Input = ReadQueryInput;
 
@@ -95,6 +186,10 @@ impl Tool for ReadQueryTool {
         "Execute a read-only SQL query (SELECT, PRAGMA, EXPLAIN). Returns the query results with column names and row data."
     }
 
+    fn output_schema(&self) -> Option<serde_json::Value> {
+        Some(schema_of::<QueryResult>())
+    }
+
     async fn execute(&self, input: Self::Input) -> Result<ToolResult, ToolError> {
         // Validate query is read-only
         if !Self::is_read_only(&input.query) {
@@ -108,12 +203,9 @@ impl Tool for ReadQueryTool {
         let limit = input.limit;
         let offset = input.offset;
 
-        let result = with_connection(input.db_path, move |conn| {
-            let mut stmt = conn.prepare(&query)?;
-
-            // Get column names
-            let columns: Vec<String> = stmt.column_names().iter().map(|s| s.to_string()).collect();
+        let max_scan_rows = self.max_scan_rows;
 
+        let result = with_connection(input.db_path, move |conn| {
             // Convert params to rusqlite values
             let params_ref: Vec<Box<dyn rusqlite::ToSql>> =
                 params.iter().map(|v| json_to_sql(v)).collect();
@@ -121,6 +213,15 @@ impl Tool for ReadQueryTool {
             let params_slice: Vec<&dyn rusqlite::ToSql> =
                 params_ref.iter().map(|b| b.as_ref()).collect();
 
+            if let Some(max_rows) = max_scan_rows {
+                check_scan_guard(conn, &query, &params_slice, max_rows)?;
+            }
+
+            let mut stmt = conn.prepare(&query)?;
+
+            // Get column names
+            let columns: Vec<String> = stmt.column_names().iter().map(|s| s.to_string()).collect();
+
             // Execute query and collect rows
             let mut rows_result = stmt.query(params_slice.as_slice())?;
             let mut rows: Vec<Vec<serde_json::Value>> = Vec::new();
@@ -190,7 +291,7 @@ mod tests {
         )
         .await;
 
-        let result = ReadQueryTool
+        let result = ReadQueryTool::new()
             .execute(ReadQueryInput::new("SELECT * FROM users ORDER BY id").db_path(db.key()))
             .await
             .unwrap();
@@ -204,7 +305,7 @@ mod tests {
     async fn test_reject_write_query() {
         let db = TestDatabase::new().await;
 
-        let result = ReadQueryTool
+        let result = ReadQueryTool::new()
             .execute(ReadQueryInput::new("INSERT INTO users VALUES (1, 'test')").db_path(db.key()))
             .await;
         assert!(result.is_err());
@@ -228,7 +329,7 @@ mod tests {
 
     #[test]
     fn test_tool_metadata() {
-        let tool = ReadQueryTool;
+        let tool = ReadQueryTool::new();
         assert_eq!(tool.name(), "sqlite_read_query");
         assert!(!tool.description().is_empty());
     }
@@ -244,7 +345,7 @@ mod tests {
         .await;
 
         // Test with integer parameter
-        let result = ReadQueryTool
+        let result = ReadQueryTool::new()
             .execute(ReadQueryInput {
                 query: "SELECT * FROM data WHERE id = ?".to_string(),
                 params: vec![serde_json::json!(2)],
@@ -261,7 +362,7 @@ mod tests {
 
         // Test with string parameter
         let json = unwrap_json(
-            ReadQueryTool
+            ReadQueryTool::new()
                 .execute(ReadQueryInput {
                     query: "SELECT * FROM data WHERE name = ?".to_string(),
                     params: vec![serde_json::json!("Alice")],
@@ -276,7 +377,7 @@ mod tests {
 
         // Test with float parameter
         let json = unwrap_json(
-            ReadQueryTool
+            ReadQueryTool::new()
                 .execute(ReadQueryInput {
                     query: "SELECT * FROM data WHERE score > ?".to_string(),
                     params: vec![serde_json::json!(90.0)],
@@ -291,7 +392,7 @@ mod tests {
 
         // Test with boolean parameter (converts to 1/0)
         let json = unwrap_json(
-            ReadQueryTool
+            ReadQueryTool::new()
                 .execute(ReadQueryInput {
                     query: "SELECT * FROM data WHERE active = ?".to_string(),
                     params: vec![serde_json::json!(true)],
@@ -306,7 +407,7 @@ mod tests {
 
         // Test with multiple parameters
         let json = unwrap_json(
-            ReadQueryTool
+            ReadQueryTool::new()
                 .execute(ReadQueryInput {
                     query: "SELECT * FROM data WHERE id > ? AND score < ?".to_string(),
                     params: vec![serde_json::json!(1), serde_json::json!(80.0)],
@@ -330,7 +431,7 @@ mod tests {
         )
         .await;
 
-        let result = ReadQueryTool
+        let result = ReadQueryTool::new()
             .execute(ReadQueryInput {
                 query: "SELECT * FROM data WHERE name IS ?".to_string(),
                 params: vec![serde_json::Value::Null],
@@ -353,7 +454,7 @@ mod tests {
         db.execute("INSERT INTO files VALUES (1, X'48656C6C6F')"); // "Hello" in hex
         db.execute("INSERT INTO files VALUES (2, X'0001020304')");
 
-        let result = ReadQueryTool
+        let result = ReadQueryTool::new()
             .execute(ReadQueryInput {
                 query: "SELECT * FROM files ORDER BY id".to_string(),
                 params: vec![],
@@ -383,7 +484,7 @@ mod tests {
         )
         .await;
 
-        let result = ReadQueryTool
+        let result = ReadQueryTool::new()
             .execute(ReadQueryInput {
                 query: "SELECT * FROM numbers ORDER BY n".to_string(),
                 params: vec![],
@@ -408,7 +509,7 @@ mod tests {
         )
         .await;
 
-        let result = ReadQueryTool
+        let result = ReadQueryTool::new()
             .execute(ReadQueryInput {
                 query: "SELECT * FROM numbers ORDER BY n".to_string(),
                 params: vec![],
@@ -434,7 +535,7 @@ mod tests {
         )
         .await;
 
-        let result = ReadQueryTool
+        let result = ReadQueryTool::new()
             .execute(ReadQueryInput {
                 query: "SELECT * FROM numbers ORDER BY n".to_string(),
                 params: vec![],
@@ -461,7 +562,7 @@ mod tests {
         )
         .await;
 
-        let result = ReadQueryTool
+        let result = ReadQueryTool::new()
             .execute(ReadQueryInput {
                 query: "WITH managers AS (SELECT * FROM employees WHERE manager_id IS NOT NULL) SELECT * FROM managers".to_string(),
                 params: vec![],
@@ -482,7 +583,7 @@ mod tests {
             TestDatabase::with_schema("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT);")
                 .await;
 
-        let result = ReadQueryTool
+        let result = ReadQueryTool::new()
             .execute(ReadQueryInput {
                 query: "PRAGMA table_info(users)".to_string(),
                 params: vec![],
@@ -505,7 +606,7 @@ mod tests {
         )
         .await;
 
-        let result = ReadQueryTool
+        let result = ReadQueryTool::new()
             .execute(ReadQueryInput {
                 query: "SELECT * FROM data".to_string(),
                 params: vec![],
@@ -519,4 +620,121 @@ mod tests {
         let json = unwrap_json(result);
         assert!(json["rows"][0][1].is_null());
     }
+
+    /// 500-row table with an index on `user_id` but not on `kind`
+    async fn seeded_events_db() -> TestDatabase {
+        TestDatabase::with_schema(
+            "CREATE TABLE events (id INTEGER PRIMARY KEY, user_id TEXT, kind TEXT);
+             CREATE INDEX idx_events_user ON events(user_id);
+             WITH RECURSIVE seq(n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM seq WHERE n < 500)
+             INSERT INTO events SELECT n, 'user' || (n % 50), 'click' FROM seq;
+             CREATE TABLE kinds (name TEXT);
+             INSERT INTO kinds VALUES ('click'), ('view');",
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_scan_guard_rejects_unindexed_scan() {
+        let db = seeded_events_db().await;
+
+        let err = ReadQueryTool::with_scan_guard(100)
+            .execute(
+                ReadQueryInput::new("SELECT * FROM events WHERE kind = 'click'").db_path(db.key()),
+            )
+            .await
+            .unwrap_err();
+
+        let msg = err.to_string();
+        assert!(msg.contains("'events'"), "{}", msg);
+        assert!(msg.contains("WHERE"), "{}", msg);
+        assert!(msg.contains("index"), "{}", msg);
+    }
+
+    #[tokio::test]
+    async fn test_scan_guard_allows_indexed_lookup() {
+        let db = seeded_events_db().await;
+        let tool = ReadQueryTool::with_scan_guard(100);
+
+        let json = unwrap_json(
+            tool.execute(
+                ReadQueryInput::new("SELECT * FROM events WHERE user_id = ?")
+                    .params(vec![serde_json::json!("user7")])
+                    .db_path(db.key()),
+            )
+            .await
+            .unwrap(),
+        );
+        assert_eq!(json["row_count"], 10);
+
+        let json = unwrap_json(
+            tool.execute(
+                ReadQueryInput::new("SELECT * FROM events WHERE id = 42").db_path(db.key()),
+            )
+            .await
+            .unwrap(),
+        );
+        assert_eq!(json["row_count"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_scan_guard_allows_scan_of_small_table() {
+        let db = seeded_events_db().await;
+
+        let json = unwrap_json(
+            ReadQueryTool::with_scan_guard(100)
+                .execute(ReadQueryInput::new("SELECT * FROM kinds").db_path(db.key()))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(json["row_count"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_scan_guard_resolves_aliases() {
+        let db = seeded_events_db().await;
+
+        let result = ReadQueryTool::with_scan_guard(100)
+            .execute(
+                ReadQueryInput::new("SELECT e.id FROM events AS e WHERE e.kind = 'view'")
+                    .db_path(db.key()),
+            )
+            .await;
+        assert!(result.unwrap_err().to_string().contains("'events'"));
+    }
+
+    #[tokio::test]
+    async fn test_scan_guard_threshold_and_default() {
+        let db = seeded_events_db().await;
+        let query = "SELECT COUNT(*) FROM events WHERE kind = 'click'";
+
+        // Table size within the limit
+        let json = unwrap_json(
+            ReadQueryTool::with_scan_guard(500)
+                .execute(ReadQueryInput::new(query).db_path(db.key()))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(json["rows"][0][0], 500);
+
+        // No guard configured
+        assert!(ReadQueryTool::new()
+            .execute(ReadQueryInput::new(query).db_path(db.key()))
+            .await
+            .is_ok());
+    }
+
+    #[test]
+    fn test_scanned_name() {
+        assert_eq!(scanned_name("SCAN users"), Some("users"));
+        assert_eq!(scanned_name("SCAN TABLE users"), Some("users"));
+        assert_eq!(
+            scanned_name("SCAN users USING COVERING INDEX idx_email"),
+            Some("users")
+        );
+        assert_eq!(
+            scanned_name("SEARCH users USING INDEX idx_email (email=?)"),
+            None
+        );
+    }
 }
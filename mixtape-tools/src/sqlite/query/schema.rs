@@ -48,6 +48,10 @@ impl Tool for SchemaQueryTool {
         "Execute a DDL (Data Definition Language) SQL query (CREATE, ALTER, DROP). Use for schema modifications."
     }
 
+    fn is_destructive(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, input: Self::Input) -> Result<ToolResult, ToolError> {
         // Validate query is a DDL operation
         if !Self::is_schema_query(&input.query) {
@@ -56,6 +56,16 @@ impl Tool for WriteQueryTool {
         "Execute a data modification SQL query (INSERT, UPDATE, DELETE). Returns the number of rows affected."
     }
 
+    fn is_destructive(&self) -> bool {
+        true
+    }
+
+    /// SQLite allows one writer at a time; queue writes instead of
+    /// contending for the lock
+    fn max_concurrency(&self) -> Option<usize> {
+        Some(1)
+    }
+
     async fn execute(&self, input: Self::Input) -> Result<ToolResult, ToolError> {
         // Validate query is a write operation
         if !Self::is_write_query(&input.query) {
@@ -0,0 +1,216 @@
+//! Tools bound to their own database manager
+//!
+//! The tools in this module's parent normally share the process-wide
+//! [`DATABASE_MANAGER`](super::DATABASE_MANAGER): a database opened by one
+//! agent is visible, and closable, by every other agent in the process.
+//! A [`ScopedManager`] gives a set of tools private connections instead.
+
+use std::ops::Deref;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use mixtape_core::tool::DynTool;
+use mixtape_core::{ToolError, ToolResult};
+use serde_json::Value;
+
+use super::manager::{DatabaseManager, SCOPED_MANAGER};
+
+/// A database manager owned by a set of tools rather than the process
+///
+/// Tools wrapped with [`scope`](Self::scope) open, query, and close
+/// databases through this manager only. Connections are closed when the
+/// last clone of the manager and the last scoped tool are dropped.
+///
+/// Dereferences to [`DatabaseManager`], so the host application can
+/// inspect or pre-open databases:
+///
+/// ```rust,ignore
+/// let (tools, db) = sqlite::scoped_tools();
+/// db.open(Path::new("/data/tenant-42.db"), false)?;
+/// let agent = Agent::builder().add_tools(tools).build().await?;
+/// ```
+#[derive(Clone, Default)]
+pub struct ScopedManager {
+    manager: Arc<DatabaseManager>,
+}
+
+impl ScopedManager {
+    /// Create a manager with no open databases
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bind `tools` to this manager
+    ///
+    /// Works with any of the tool group functions and configured tools.
+    pub fn scope(&self, tools: Vec<Box<dyn DynTool>>) -> Vec<Box<dyn DynTool>> {
+        tools
+            .into_iter()
+            .map(|inner| {
+                Box::new(ScopedTool {
+                    inner,
+                    manager: Arc::clone(&self.manager),
+                }) as Box<dyn DynTool>
+            })
+            .collect()
+    }
+}
+
+impl Deref for ScopedManager {
+    type Target = DatabaseManager;
+
+    fn deref(&self) -> &DatabaseManager {
+        &self.manager
+    }
+}
+
+/// A tool that runs with a [`ScopedManager`]'s connections
+struct ScopedTool {
+    inner: Box<dyn DynTool>,
+    manager: Arc<DatabaseManager>,
+}
+
+impl DynTool for ScopedTool {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn description(&self) -> &str {
+        self.inner.description()
+    }
+
+    fn input_schema(&self) -> Value {
+        self.inner.input_schema()
+    }
+
+    fn output_schema(&self) -> Option<Value> {
+        self.inner.output_schema()
+    }
+
+    fn is_destructive(&self) -> bool {
+        self.inner.is_destructive()
+    }
+
+    fn max_concurrency(&self) -> Option<usize> {
+        self.inner.max_concurrency()
+    }
+
+    fn is_blocking(&self) -> bool {
+        self.inner.is_blocking()
+    }
+
+    fn validate_raw(&self, input: &Value) -> Result<(), ToolError> {
+        self.inner.validate_raw(input)
+    }
+
+    fn execute_raw(
+        &self,
+        input: Value,
+    ) -> Pin<Box<dyn std::future::Future<Output = Result<ToolResult, ToolError>> + Send + '_>> {
+        Box::pin(SCOPED_MANAGER.scope(Arc::clone(&self.manager), self.inner.execute_raw(input)))
+    }
+
+    fn format_input_plain(&self, params: &Value) -> String {
+        self.inner.format_input_plain(params)
+    }
+
+    fn format_input_ansi(&self, params: &Value) -> String {
+        self.inner.format_input_ansi(params)
+    }
+
+    fn format_input_markdown(&self, params: &Value) -> String {
+        self.inner.format_input_markdown(params)
+    }
+
+    fn format_output_plain(&self, result: &ToolResult) -> String {
+        self.inner.format_output_plain(result)
+    }
+
+    fn format_output_ansi(&self, result: &ToolResult) -> String {
+        self.inner.format_output_ansi(result)
+    }
+
+    fn format_output_markdown(&self, result: &ToolResult) -> String {
+        self.inner.format_output_markdown(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sqlite::{all_tools, scoped_tools, DATABASE_MANAGER};
+    use tempfile::TempDir;
+
+    /// Call the tool named `name` from `tools`
+    async fn call(tools: &[Box<dyn DynTool>], name: &str, input: Value) -> ToolResult {
+        let tool = tools.iter().find(|t| t.name() == name).unwrap();
+        tool.execute_raw(input).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_scoped_managers_are_isolated() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_a = temp_dir.path().join("a.db");
+        let db_b = temp_dir.path().join("b.db");
+
+        let (tools_a, manager_a) = scoped_tools();
+        let (tools_b, manager_b) = scoped_tools();
+
+        call(
+            &tools_a,
+            "sqlite_open_database",
+            serde_json::json!({"db_path": db_a}),
+        )
+        .await;
+        call(
+            &tools_b,
+            "sqlite_open_database",
+            serde_json::json!({"db_path": db_b}),
+        )
+        .await;
+
+        assert_eq!(manager_a.list_open().len(), 1);
+        assert!(manager_a.is_open("a.db"));
+        assert!(!manager_a.is_open("b.db"));
+        assert_eq!(manager_b.list_open().len(), 1);
+        assert!(manager_b.is_open("b.db"));
+
+        // Neither leaks into the global manager
+        assert!(!DATABASE_MANAGER.is_open(&db_a.to_string_lossy()));
+        assert!(!DATABASE_MANAGER.is_open(&db_b.to_string_lossy()));
+
+        // Queries without a db_path use the scope's own default database
+        call(
+            &tools_a,
+            "sqlite_schema_query",
+            serde_json::json!({"query": "CREATE TABLE only_in_a (id INTEGER)"}),
+        )
+        .await;
+        let tables = call(&tools_b, "sqlite_list_tables", serde_json::json!({})).await;
+        assert!(!tables.as_text().contains("only_in_a"));
+    }
+
+    #[test]
+    fn test_scope_keeps_tool_metadata() {
+        let scoped = ScopedManager::new().scope(all_tools());
+        let names: Vec<_> = scoped.iter().map(|t| t.name().to_string()).collect();
+        let expected: Vec<_> = all_tools().iter().map(|t| t.name().to_string()).collect();
+        assert_eq!(names, expected);
+        assert!(scoped
+            .iter()
+            .any(|t| t.name() == "sqlite_write_query" && t.is_destructive()));
+    }
+
+    #[test]
+    fn test_scope_keeps_input_validation() {
+        let scoped = ScopedManager::new().scope(all_tools());
+        let tool = scoped
+            .iter()
+            .find(|t| t.name() == "sqlite_write_query")
+            .unwrap();
+        assert!(tool.validate_raw(&serde_json::json!({})).is_err());
+        assert!(tool
+            .validate_raw(&serde_json::json!({"query": "DELETE FROM t"}))
+            .is_ok());
+    }
+}
@@ -9,7 +9,7 @@ use sqlparser::ast::{
 };
 use sqlparser::dialect::SQLiteDialect;
 use sqlparser::parser::Parser;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 /// Categorize tables by operation type
 #[derive(Debug, Default)]
@@ -208,6 +208,94 @@ fn extract_tables_from_expr(expr: &Expr, tables: &mut HashSet<String>) {
     }
 }
 
+/// Map table aliases to the tables they refer to
+///
+/// Covers aliases in FROM and JOIN clauses, including those inside CTEs,
+/// derived tables, set operations, and WHERE subqueries. Returns an empty
+/// map if the SQL cannot be parsed.
+pub fn extract_table_aliases(sql: &str) -> HashMap<String, String> {
+    let mut aliases = HashMap::new();
+    if let Ok(statements) = Parser::parse_sql(&SQLiteDialect {}, sql) {
+        for statement in statements {
+            if let Statement::Query(query) = statement {
+                aliases_from_query(&query, &mut aliases);
+            }
+        }
+    }
+    aliases
+}
+
+fn aliases_from_query(query: &Query, aliases: &mut HashMap<String, String>) {
+    if let Some(with) = &query.with {
+        for cte in &with.cte_tables {
+            aliases_from_query(&cte.query, aliases);
+        }
+    }
+    aliases_from_set_expr(&query.body, aliases);
+}
+
+fn aliases_from_set_expr(body: &SetExpr, aliases: &mut HashMap<String, String>) {
+    match body {
+        SetExpr::Select(select) => {
+            for twj in &select.from {
+                aliases_from_table_factor(&twj.relation, aliases);
+                for join in &twj.joins {
+                    aliases_from_table_factor(&join.relation, aliases);
+                }
+            }
+            if let Some(expr) = &select.selection {
+                aliases_from_expr(expr, aliases);
+            }
+        }
+        SetExpr::Query(query) => aliases_from_query(query, aliases),
+        SetExpr::SetOperation { left, right, .. } => {
+            aliases_from_set_expr(left, aliases);
+            aliases_from_set_expr(right, aliases);
+        }
+        _ => {}
+    }
+}
+
+fn aliases_from_table_factor(factor: &TableFactor, aliases: &mut HashMap<String, String>) {
+    match factor {
+        TableFactor::Table {
+            name,
+            alias: Some(alias),
+            ..
+        } => {
+            aliases.insert(alias.name.value.clone(), name.to_string());
+        }
+        TableFactor::Derived { subquery, .. } => aliases_from_query(subquery, aliases),
+        TableFactor::NestedJoin {
+            table_with_joins, ..
+        } => {
+            aliases_from_table_factor(&table_with_joins.relation, aliases);
+            for join in &table_with_joins.joins {
+                aliases_from_table_factor(&join.relation, aliases);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn aliases_from_expr(expr: &Expr, aliases: &mut HashMap<String, String>) {
+    match expr {
+        Expr::Subquery(query)
+        | Expr::InSubquery {
+            subquery: query, ..
+        }
+        | Expr::Exists {
+            subquery: query, ..
+        } => aliases_from_query(query, aliases),
+        Expr::BinaryOp { left, right, .. } => {
+            aliases_from_expr(left, aliases);
+            aliases_from_expr(right, aliases);
+        }
+        Expr::UnaryOp { expr, .. } | Expr::Nested(expr) => aliases_from_expr(expr, aliases),
+        _ => {}
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -303,4 +391,21 @@ mod tests {
         assert!(ops.read.contains("users"));
         assert!(ops.write.contains("logs"));
     }
+
+    #[test]
+    fn test_extract_table_aliases() {
+        let aliases = extract_table_aliases(
+            "SELECT * FROM users u JOIN orders AS o ON u.id = o.user_id \
+             WHERE u.id IN (SELECT s.user_id FROM sessions s)",
+        );
+        assert_eq!(aliases.get("u").map(String::as_str), Some("users"));
+        assert_eq!(aliases.get("o").map(String::as_str), Some("orders"));
+        assert_eq!(aliases.get("s").map(String::as_str), Some("sessions"));
+        assert_eq!(aliases.len(), 3);
+    }
+
+    #[test]
+    fn test_extract_table_aliases_unparseable() {
+        assert!(extract_table_aliases("NOT VALID SQL AT ALL").is_empty());
+    }
 }
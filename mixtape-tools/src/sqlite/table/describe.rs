@@ -4,6 +4,7 @@ use crate::prelude::*;
 use crate::sqlite::error::SqliteToolError;
 use crate::sqlite::manager::with_connection;
 use crate::sqlite::types::{ColumnDefinition, TableInfo, Verbosity};
+use mixtape_core::tool::schema_of;
 
 /// Input for describing a table
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -37,6 +38,10 @@ impl Tool for DescribeTableTool {
         "Get detailed schema information for a table including column definitions, types, and constraints."
     }
 
+    fn output_schema(&self) -> Option<serde_json::Value> {
+        Some(schema_of::<TableInfo>())
+    }
+
     async fn execute(&self, input: Self::Input) -> Result<ToolResult, ToolError> {
         let table_name = input.table.clone();
         let verbosity = input.verbosity;
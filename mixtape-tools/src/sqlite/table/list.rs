@@ -2,6 +2,8 @@
 
 use crate::prelude::*;
 use crate::sqlite::manager::with_connection;
+use crate::utils::{default_max_list_items, TruncatedList, DEFAULT_MAX_LIST_ITEMS_LIMIT};
+use mixtape_core::tool::schema_of;
 
 /// Input for listing tables
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -9,22 +11,68 @@ pub struct ListTablesInput {
     /// Database file path. If not specified, uses the default database.
     #[serde(default)]
     pub db_path: Option<String>,
+
+    /// Maximum number of tables and views to return (default: 200)
+    #[serde(default = "default_max_list_items")]
+    pub max_items: usize,
 }
 
 /// Table entry information
 #[derive(Debug, Serialize, JsonSchema)]
 struct TableEntry {
+    /// Table or view name
     name: String,
+    /// Either "table" or "view"
     #[serde(rename = "type")]
     table_type: String,
 }
 
+/// Output of the list tables tool
+#[derive(Debug, Serialize, JsonSchema)]
+struct ListTablesOutput {
+    /// Tables and views, tables first
+    tables: Vec<TableEntry>,
+    /// Number of entries in `tables`
+    count: usize,
+    /// Number of tables and views in the database
+    total_count: usize,
+    /// Present when `tables` was cut to `max_items`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    note: Option<String>,
+}
+
 /// Tool for listing all tables and views in a database
 ///
 /// Returns a list of all tables and views, excluding:
 /// - SQLite internal tables (`sqlite_*`)
 /// - System tables managed by tools (`_*`)
-pub struct ListTablesTool;
+pub struct ListTablesTool {
+    max_items_limit: usize,
+}
+
+impl Default for ListTablesTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ListTablesTool {
+    /// Creates a tool that returns at most [`DEFAULT_MAX_LIST_ITEMS_LIMIT`]
+    /// tables and views per call
+    pub fn new() -> Self {
+        Self {
+            max_items_limit: DEFAULT_MAX_LIST_ITEMS_LIMIT,
+        }
+    }
+
+    /// Sets the most tables and views one call can return
+    ///
+    /// A larger `max_items` from the model is lowered to this limit.
+    pub fn with_max_items_limit(mut self, limit: usize) -> Self {
+        self.max_items_limit = limit;
+        self
+    }
+}
 
 impl Tool for ListTablesTool {
     type Input = ListTablesInput;
@@ -35,7 +83,12 @@ impl Tool for ListTablesTool {
 
     fn description(&self) -> &str {
         "List all tables and views in a SQLite database. Excludes SQLite internal tables (sqlite_*) \
-         and system tables managed by tools (_*). Returns the name and type of each table/view."
+         and system tables managed by tools (_*). Returns the name and type of each table/view, \
+         at most max_items of them; total_count gives the number in the database."
+    }
+
+    fn output_schema(&self) -> Option<serde_json::Value> {
+        Some(schema_of::<ListTablesOutput>())
     }
 
     async fn execute(&self, input: Self::Input) -> Result<ToolResult, ToolError> {
@@ -62,11 +115,14 @@ impl Tool for ListTablesTool {
         })
         .await?;
 
-        let count = tables.len();
-        Ok(ToolResult::Json(serde_json::json!({
-            "tables": tables,
-            "count": count
-        })))
+        let tables = TruncatedList::new(tables, input.max_items.min(self.max_items_limit));
+        let note = tables.note("tables");
+        Ok(ToolResult::json(ListTablesOutput {
+            count: tables.items.len(),
+            total_count: tables.total_count,
+            tables: tables.items,
+            note,
+        })?)
     }
 }
 
@@ -74,6 +130,7 @@ impl Tool for ListTablesTool {
 mod tests {
     use super::*;
     use crate::sqlite::test_utils::{unwrap_json, TestDatabase};
+    use crate::utils::DEFAULT_MAX_LIST_ITEMS;
 
     #[tokio::test]
     async fn test_list_tables() {
@@ -84,9 +141,10 @@ mod tests {
         )
         .await;
 
-        let result = ListTablesTool
+        let result = ListTablesTool::new()
             .execute(ListTablesInput {
                 db_path: Some(db.key()),
+                max_items: DEFAULT_MAX_LIST_ITEMS,
             })
             .await
             .unwrap();
@@ -95,13 +153,68 @@ mod tests {
         assert_eq!(json["count"].as_i64().unwrap(), 3);
     }
 
+    #[tokio::test]
+    async fn test_list_tables_truncates_to_max_items() {
+        let db = TestDatabase::with_schema(
+            "CREATE TABLE a (id INTEGER);
+             CREATE TABLE b (id INTEGER);
+             CREATE TABLE c (id INTEGER);",
+        )
+        .await;
+
+        let result = ListTablesTool::new()
+            .execute(ListTablesInput {
+                db_path: Some(db.key()),
+                max_items: 2,
+            })
+            .await
+            .unwrap();
+
+        let json = unwrap_json(result);
+        assert_eq!(json["count"], 2);
+        assert_eq!(json["total_count"], 3);
+        assert_eq!(json["tables"].as_array().unwrap().len(), 2);
+        assert!(json["note"].as_str().unwrap().contains("1 more tables"));
+    }
+
+    #[tokio::test]
+    async fn test_list_tables_clamps_max_items_to_limit() {
+        let db = TestDatabase::with_schema(
+            "CREATE TABLE a (id INTEGER);
+             CREATE TABLE b (id INTEGER);
+             CREATE TABLE c (id INTEGER);",
+        )
+        .await;
+
+        let result = ListTablesTool::new()
+            .with_max_items_limit(1)
+            .execute(ListTablesInput {
+                db_path: Some(db.key()),
+                max_items: usize::MAX,
+            })
+            .await
+            .unwrap();
+
+        let json = unwrap_json(result);
+        assert_eq!(json["count"], 1);
+        assert_eq!(json["total_count"], 3);
+    }
+
     #[test]
     fn test_tool_metadata() {
-        let tool = ListTablesTool;
+        let tool = ListTablesTool::new();
         assert_eq!(tool.name(), "sqlite_list_tables");
         assert!(!tool.description().is_empty());
     }
 
+    #[test]
+    fn test_output_schema() {
+        let schema = ListTablesTool::new().output_schema().unwrap();
+        assert!(!schema.is_null());
+        assert!(schema["properties"]["tables"].is_object());
+        assert!(schema["properties"]["count"].is_object());
+    }
+
     #[tokio::test]
     async fn test_excludes_system_tables() {
         let db = TestDatabase::with_schema(
@@ -110,9 +223,10 @@ mod tests {
         )
         .await;
 
-        let result = ListTablesTool
+        let result = ListTablesTool::new()
             .execute(ListTablesInput {
                 db_path: Some(db.key()),
+                max_items: DEFAULT_MAX_LIST_ITEMS,
             })
             .await
             .unwrap();
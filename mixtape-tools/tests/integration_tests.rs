@@ -41,7 +41,7 @@ async fn test_filesystem_tools_integration() {
 /// Test that process tools work correctly
 #[tokio::test]
 async fn test_process_tools_basic() {
-    let list_tool = ListProcessesTool;
+    let list_tool = ListProcessesTool::new();
 
     // List processes
     let input = serde_json::json!({});
@@ -54,6 +54,26 @@ async fn test_process_tools_basic() {
     assert!(output.contains("NAME"));
 }
 
+/// Test that process tools able to change the system report it
+#[test]
+fn test_process_destructive_tools() {
+    let mut destructive: Vec<_> = mixtape_tools::process::all_tools()
+        .iter()
+        .filter(|t| t.is_destructive())
+        .map(|t| t.name().to_string())
+        .collect();
+    destructive.sort();
+    assert_eq!(
+        destructive,
+        vec![
+            "force_terminate",
+            "interact_with_process",
+            "kill_process",
+            "start_process",
+        ]
+    );
+}
+
 /// Test that search tools integrate with filesystem
 #[tokio::test]
 async fn test_search_integration() {